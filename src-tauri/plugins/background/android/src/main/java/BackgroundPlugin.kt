@@ -0,0 +1,43 @@
+package app.autosight.background
+
+import android.app.Activity
+import android.content.Intent
+import androidx.core.content.ContextCompat
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Channel
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+
+@InvokeArg
+class BeginArgs {
+    lateinit var title: String
+    lateinit var onExpired: Channel
+}
+
+@TauriPlugin
+class BackgroundPlugin(private val activity: Activity) : Plugin(activity) {
+    @Command
+    fun begin(invoke: Invoke) {
+        val args = invoke.parseArgs(BeginArgs::class.java)
+        DownloadService.onTimeout = { args.onExpired.send(JSObject()) }
+        try {
+            val intent = Intent(activity, DownloadService::class.java)
+                .putExtra(DownloadService.EXTRA_TITLE, args.title)
+            ContextCompat.startForegroundService(activity, intent)
+            invoke.resolve()
+        } catch (e: Exception) {
+            DownloadService.onTimeout = null
+            invoke.reject(e.message ?: "Failed to start foreground service")
+        }
+    }
+
+    @Command
+    fun end(invoke: Invoke) {
+        DownloadService.onTimeout = null
+        activity.stopService(Intent(activity, DownloadService::class.java))
+        invoke.resolve()
+    }
+}
@@ -0,0 +1,55 @@
+package app.autosight.background
+
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.app.Service
+import android.content.Intent
+import android.content.pm.ServiceInfo
+import android.os.Build
+import android.os.IBinder
+import androidx.core.app.NotificationCompat
+import androidx.core.app.ServiceCompat
+
+/** 一括ダウンロードの実行中に表示するフォアグラウンドサービス */
+class DownloadService : Service() {
+    companion object {
+        const val EXTRA_TITLE = "title"
+        private const val CHANNEL_ID = "autosight-downloads"
+        private const val NOTIFICATION_ID = 1
+
+        /** OSが実行時間の上限に達したときに呼ぶ */
+        @Volatile
+        var onTimeout: (() -> Unit)? = null
+    }
+
+    override fun onBind(intent: Intent?): IBinder? = null
+
+    override fun onStartCommand(intent: Intent?, flags: Int, startId: Int): Int {
+        val manager = getSystemService(NotificationManager::class.java)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            manager.createNotificationChannel(
+                NotificationChannel(CHANNEL_ID, "Downloads", NotificationManager.IMPORTANCE_LOW)
+            )
+        }
+        val notification = NotificationCompat.Builder(this, CHANNEL_ID)
+            .setContentTitle(intent?.getStringExtra(EXTRA_TITLE) ?: "AutoSight")
+            .setSmallIcon(android.R.drawable.stat_sys_download)
+            .setOngoing(true)
+            .setProgress(0, 0, true)
+            .build()
+        val type = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q) {
+            ServiceInfo.FOREGROUND_SERVICE_TYPE_DATA_SYNC
+        } else {
+            0
+        }
+        ServiceCompat.startForeground(this, NOTIFICATION_ID, notification, type)
+        // プロセスが終了された場合は再起動しない（次回の起動時に中断したバッチを再開する）
+        return START_NOT_STICKY
+    }
+
+    override fun onTimeout(startId: Int) {
+        onTimeout?.invoke()
+        onTimeout = null
+        stopSelf()
+    }
+}
@@ -0,0 +1,45 @@
+import SwiftRs
+import Tauri
+import UIKit
+import WebKit
+
+class BeginArgs: Decodable {
+  let title: String
+  let onExpired: Channel
+}
+
+class BackgroundPlugin: Plugin {
+  private var task: UIBackgroundTaskIdentifier = .invalid
+
+  @objc public func begin(_ invoke: Invoke) throws {
+    let args = try invoke.parseArgs(BeginArgs.self)
+    DispatchQueue.main.async {
+      self.finish()
+      self.task = UIApplication.shared.beginBackgroundTask(withName: args.title) {
+        // 上限に達した場合は中断を通知し、タスクを終了する（終了しないとアプリが強制終了される）
+        args.onExpired.send([:])
+        self.finish()
+      }
+      invoke.resolve()
+    }
+  }
+
+  @objc public func end(_ invoke: Invoke) throws {
+    DispatchQueue.main.async {
+      self.finish()
+      invoke.resolve()
+    }
+  }
+
+  private func finish() {
+    if task != .invalid {
+      UIApplication.shared.endBackgroundTask(task)
+      task = .invalid
+    }
+  }
+}
+
+@_cdecl("init_plugin_background")
+func initPlugin() -> Plugin {
+  return BackgroundPlugin()
+}
@@ -0,0 +1,115 @@
+//! バックグラウンドでの実行継続（Androidのフォアグラウンドサービス・iOSのバックグラウンドタスク）
+//!
+//! モバイルではアプリがバックグラウンドに移るとプロセスが停止・終了されるため、
+//! 一括ダウンロードの実行中はOSに実行の継続を要求する。
+//! iOSでは継続できる時間に上限があり、上限に達した場合は `on_expired` を呼ぶ
+//! （呼び出し側で処理を中断し、次回の起動時に再開できる状態にする）。
+//! デスクトップでは何もしない。
+
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Manager, Runtime,
+};
+
+#[cfg(target_os = "ios")]
+tauri::ios_plugin_binding!(init_plugin_background);
+
+#[cfg(mobile)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BeginArgs {
+    /// 実行中の通知に表示するタイトル（Androidのみ）
+    title: String,
+    /// 継続できる時間の上限に達したときに呼ばれるチャンネル
+    on_expired: tauri::ipc::Channel,
+}
+
+/// バックグラウンドでの実行継続のハンドル
+pub struct Background<R: Runtime> {
+    #[cfg(mobile)]
+    handle: tauri::plugin::PluginHandle<R>,
+    #[cfg(not(mobile))]
+    _marker: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R: Runtime> Background<R> {
+    /// 実行の継続を要求
+    ///
+    /// `title` はAndroidの実行中の通知に表示する。OSが継続を打ち切る場合は `on_expired` を呼ぶ。
+    pub fn begin(
+        &self,
+        title: &str,
+        on_expired: impl Fn() + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        #[cfg(mobile)]
+        {
+            let on_expired = tauri::ipc::Channel::new(move |_| {
+                on_expired();
+                Ok(())
+            });
+            self.handle
+                .run_mobile_plugin::<()>(
+                    "begin",
+                    BeginArgs {
+                        title: title.to_string(),
+                        on_expired,
+                    },
+                )
+                .map_err(|e| format!("Failed to begin background task: {}", e))
+        }
+        #[cfg(not(mobile))]
+        {
+            let _ = (title, on_expired);
+            Ok(())
+        }
+    }
+
+    /// 実行の継続を終了
+    pub fn end(&self) -> Result<(), String> {
+        #[cfg(mobile)]
+        {
+            self.handle
+                .run_mobile_plugin::<()>("end", ())
+                .map_err(|e| format!("Failed to end background task: {}", e))
+        }
+        #[cfg(not(mobile))]
+        {
+            Ok(())
+        }
+    }
+}
+
+/// `AppHandle` 等からハンドルを取得する
+pub trait BackgroundExt<R: Runtime> {
+    fn background(&self) -> &Background<R>;
+}
+
+impl<R: Runtime, T: Manager<R>> BackgroundExt<R> for T {
+    fn background(&self) -> &Background<R> {
+        self.state::<Background<R>>().inner()
+    }
+}
+
+/// プラグインを初期化
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("autosight-background")
+        .setup(|app, api| {
+            #[cfg(target_os = "android")]
+            let handle =
+                api.register_android_plugin("app.autosight.background", "BackgroundPlugin")?;
+            #[cfg(target_os = "ios")]
+            let handle = api.register_ios_plugin(init_plugin_background)?;
+            #[cfg(mobile)]
+            app.manage(Background { handle });
+
+            #[cfg(not(mobile))]
+            {
+                let _ = api;
+                app.manage(Background::<R> {
+                    _marker: std::marker::PhantomData,
+                });
+            }
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,48 @@
+package app.autosight.share
+
+import android.app.Activity
+import android.content.Intent
+import androidx.core.content.FileProvider
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.Plugin
+import java.io.File
+
+@InvokeArg
+class ShareFileArgs {
+    lateinit var path: String
+    lateinit var mimeType: String
+    var title: String? = null
+}
+
+@TauriPlugin
+class SharePlugin(private val activity: Activity) : Plugin(activity) {
+    @Command
+    fun shareFile(invoke: Invoke) {
+        val args = invoke.parseArgs(ShareFileArgs::class.java)
+        val file = File(args.path)
+        if (!file.isFile) {
+            invoke.reject("File not found: ${args.path}")
+            return
+        }
+
+        try {
+            val uri = FileProvider.getUriForFile(
+                activity,
+                "${activity.packageName}.autosight.share",
+                file
+            )
+            val intent = Intent(Intent.ACTION_SEND).apply {
+                type = args.mimeType
+                putExtra(Intent.EXTRA_STREAM, uri)
+                addFlags(Intent.FLAG_GRANT_READ_URI_PERMISSION)
+            }
+            activity.startActivity(Intent.createChooser(intent, args.title))
+            invoke.resolve()
+        } catch (e: Exception) {
+            invoke.reject(e.message ?: "Failed to open share sheet")
+        }
+    }
+}
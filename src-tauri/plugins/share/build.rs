@@ -0,0 +1,9 @@
+// フロントエンドから直接呼び出すコマンドはない（アプリのコマンドから呼び出す）
+const COMMANDS: &[&str] = &[];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS)
+        .android_path("android")
+        .ios_path("ios")
+        .build();
+}
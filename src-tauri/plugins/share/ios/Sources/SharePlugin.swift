@@ -0,0 +1,43 @@
+import SwiftRs
+import Tauri
+import UIKit
+import WebKit
+
+class ShareFileArgs: Decodable {
+  let path: String
+  let mimeType: String
+  let title: String?
+}
+
+class SharePlugin: Plugin {
+  @objc public func shareFile(_ invoke: Invoke) throws {
+    let args = try invoke.parseArgs(ShareFileArgs.self)
+    let url = URL(fileURLWithPath: args.path)
+    guard FileManager.default.fileExists(atPath: url.path) else {
+      invoke.reject("File not found: \(args.path)")
+      return
+    }
+
+    DispatchQueue.main.async {
+      guard let presenter = self.manager.viewController else {
+        invoke.reject("No view controller to present the share sheet")
+        return
+      }
+      let controller = UIActivityViewController(activityItems: [url], applicationActivities: nil)
+      // iPadではポップオーバーとして表示するため、表示位置の指定が必要
+      if let popover = controller.popoverPresentationController {
+        popover.sourceView = presenter.view
+        popover.sourceRect = CGRect(
+          x: presenter.view.bounds.midX, y: presenter.view.bounds.midY, width: 0, height: 0)
+        popover.permittedArrowDirections = []
+      }
+      presenter.present(controller, animated: true)
+      invoke.resolve()
+    }
+  }
+}
+
+@_cdecl("init_plugin_share")
+func initPlugin() -> Plugin {
+  return SharePlugin()
+}
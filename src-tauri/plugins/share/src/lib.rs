@@ -0,0 +1,85 @@
+//! 共有シート（Androidの共有・iOSの共有シート）
+//!
+//! モバイルでは任意の保存先フォルダを開けないため、ダウンロードしたファイルを
+//! OSの共有シートに渡してメール・クラウドストレージ等に送れるようにする。
+//! デスクトップでは使用できない（エラーを返す）。
+
+use serde::Serialize;
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Manager, Runtime,
+};
+
+#[cfg(target_os = "ios")]
+tauri::ios_plugin_binding!(init_plugin_share);
+
+/// 共有するファイル
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareFileRequest {
+    /// 共有するファイルのパス（アプリのデータ・ドキュメント・キャッシュディレクトリ配下）
+    pub path: String,
+    /// MIMEタイプ
+    pub mime_type: String,
+    /// 共有シートのタイトル（Androidのみ）
+    pub title: Option<String>,
+}
+
+/// 共有シートのハンドル
+pub struct Share<R: Runtime> {
+    #[cfg(mobile)]
+    handle: tauri::plugin::PluginHandle<R>,
+    #[cfg(not(mobile))]
+    _marker: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R: Runtime> Share<R> {
+    /// ファイルを共有シートに渡す（共有シートを表示した時点で戻る）
+    pub fn share_file(&self, request: ShareFileRequest) -> Result<(), String> {
+        #[cfg(mobile)]
+        {
+            self.handle
+                .run_mobile_plugin::<()>("shareFile", request)
+                .map_err(|e| format!("Failed to open share sheet: {}", e))
+        }
+        #[cfg(not(mobile))]
+        {
+            let _ = request;
+            Err("The share sheet is only available on mobile".to_string())
+        }
+    }
+}
+
+/// `AppHandle` 等から共有シートを取得する
+pub trait ShareExt<R: Runtime> {
+    fn share(&self) -> &Share<R>;
+}
+
+impl<R: Runtime, T: Manager<R>> ShareExt<R> for T {
+    fn share(&self) -> &Share<R> {
+        self.state::<Share<R>>().inner()
+    }
+}
+
+/// プラグインを初期化
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("autosight-share")
+        .setup(|app, api| {
+            #[cfg(target_os = "android")]
+            let handle = api.register_android_plugin("app.autosight.share", "SharePlugin")?;
+            #[cfg(target_os = "ios")]
+            let handle = api.register_ios_plugin(init_plugin_share)?;
+            #[cfg(mobile)]
+            app.manage(Share { handle });
+
+            #[cfg(not(mobile))]
+            {
+                let _ = api;
+                app.manage(Share::<R> {
+                    _marker: std::marker::PhantomData,
+                });
+            }
+            Ok(())
+        })
+        .build()
+}
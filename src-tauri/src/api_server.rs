@@ -0,0 +1,471 @@
+//! ローカルHTTP APIサーバー（オプトイン）
+//!
+//! 社内ツールやスクリプトからAutoSightを操作できるよう、Tauriコマンドと同じ操作を
+//! HTTPで公開する。`127.0.0.1` でのみ待ち受け、`Authorization: Bearer {トークン}` が
+//! 設定のトークンと一致するリクエストのみ受け付ける。
+//!
+//! | メソッド | パス | 内容 |
+//! |---|---|---|
+//! | `POST` | `/v1/import` | 器具リストを読み込む（`{ "path": ..., "profile": ... }`） |
+//! | `POST` | `/v1/downloads` | 一括ダウンロードを開始（`batch_download_ies_files` と同じリクエスト） |
+//! | `GET` | `/v1/status` | 実行中（または直前）の一括ダウンロードの状態 |
+//! | `GET` | `/v1/results` | APIから開始した直前の一括ダウンロードの結果 |
+//!
+//! 一括ダウンロードはバックグラウンドで実行し、`/v1/status` で進捗を確認する。
+//! 進捗・完了はアプリ上の一括ダウンロードと同じイベントでも通知する。
+
+use crate::batch::{BatchChannel, BatchState};
+use crate::commands::{self, BatchDownloadRequest, BatchDownloadResult};
+use crate::error::{CommandError, CommandResult, ErrorCode};
+use crate::excel::ImportProfile;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+
+/// 既定の待ち受けポート
+const DEFAULT_PORT: u16 = 47321;
+/// トークンの最小文字数
+const MIN_TOKEN_CHARS: usize = 16;
+/// リクエストヘッダーの上限（バイト）
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// リクエストボディの上限（バイト）
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+/// リクエストの受信のタイムアウト
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// 同時に処理する接続の上限（超えた接続は処理中の接続が終わるまで受け付けない）
+const MAX_CONNECTIONS: usize = 16;
+/// 接続の受け付けに失敗した場合（ファイルディスクリプタの枯渇等）に再試行するまでの待ち時間
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// ローカルHTTP APIサーバーの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ApiServerSettings {
+    /// サーバーを起動する（既定は無効）
+    pub enabled: bool,
+    /// 待ち受けポート（`127.0.0.1` のみ）
+    pub port: u16,
+    /// 認証トークン（有効時は必須）
+    pub token: Option<String>,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: None,
+        }
+    }
+}
+
+impl ApiServerSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err("apiServer.port must be 1024 or greater".to_string());
+        }
+        if self.enabled {
+            let token = self.token.as_deref().unwrap_or_default();
+            if token.chars().count() < MIN_TOKEN_CHARS {
+                return Err(format!(
+                    "apiServer.token must be at least {} characters",
+                    MIN_TOKEN_CHARS
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// サーバーの実行状態（Tauriの管理状態として保持する）
+#[derive(Default)]
+pub struct ApiServerState {
+    /// 待ち受け中のサーバー
+    server: Mutex<Option<JoinHandle<()>>>,
+    /// APIから開始した一括ダウンロードを実行中か
+    downloading: AtomicBool,
+    /// APIから開始した直前の一括ダウンロードの結果
+    last_result: Mutex<Option<CommandResult<BatchDownloadResult>>>,
+}
+
+/// 設定をサーバーに反映（起動中のサーバーは停止し、有効な場合は起動し直す）
+pub fn apply(app: &AppHandle, settings: &ApiServerSettings) {
+    let state = app.state::<ApiServerState>();
+    let mut server = state.server.lock().unwrap();
+    if let Some(handle) = server.take() {
+        handle.abort();
+        tracing::info!("api server stopped");
+    }
+    let Some(token) = settings.token.clone().filter(|_| settings.enabled) else {
+        return;
+    };
+
+    let app = app.clone();
+    let port = settings.port;
+    *server = Some(tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(error = %e, port, "failed to start api server");
+                return;
+            }
+        };
+        tracing::info!(port, "api server started");
+        let token = Arc::new(token);
+        let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            let Ok(permit) = connections.clone().acquire_owned().await else {
+                return;
+            };
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to accept api connection");
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let app = app.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                handle_connection(&app, &token, stream).await;
+                drop(permit);
+            });
+        }
+    }));
+}
+
+/// HTTPリクエスト
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    /// パス（クエリ文字列を除く）
+    path: String,
+    /// ヘッダー（名前は小文字）
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTPレスポンス（ボディはJSON）
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn json(status: u16, body: impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_value(body).unwrap_or(Value::Null),
+        }
+    }
+
+    fn error(status: u16, error: CommandError) -> Self {
+        Self::json(status, error)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            202 => "Accepted",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            _ => "Internal Server Error",
+        };
+        let body = self.body.to_string();
+        let mut bytes = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason,
+            body.len()
+        )
+        .into_bytes();
+        bytes.extend_from_slice(body.as_bytes());
+        bytes
+    }
+}
+
+/// リクエストライン・ヘッダーを解析（ボディは含まない）
+fn parse_head(head: &str) -> Result<Request, String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("Invalid request line: {}", request_line));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(format!("Unsupported HTTP version: {}", version));
+    }
+
+    let mut headers = Vec::new();
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid header: {}", line))?;
+        headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+    }
+    Ok(Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        headers,
+        body: Vec::new(),
+    })
+}
+
+/// リクエストを受信
+async fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let bad_request = |message: String| Response::error(400, CommandError::from(message));
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(bad_request("Request header too large".to_string()));
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) => return Err(bad_request("Connection closed".to_string())),
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            Err(e) => return Err(bad_request(format!("Failed to read request: {}", e))),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut request = parse_head(&head).map_err(bad_request)?;
+    let length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| bad_request(format!("Invalid Content-Length: {}", value)))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(
+            413,
+            CommandError::new(ErrorCode::InvalidInput, "Request body too large"),
+        ));
+    }
+
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < length {
+        match stream.read(&mut chunk).await {
+            Ok(0) => return Err(bad_request("Connection closed".to_string())),
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+            Err(e) => return Err(bad_request(format!("Failed to read request: {}", e))),
+        }
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(request)
+}
+
+/// `Authorization: Bearer {トークン}` を検証（比較時間がトークンの内容に依存しないようにする）
+fn authorized(request: &Request, token: &str) -> bool {
+    let Some(given) = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+async fn handle_connection(app: &AppHandle, token: &str, mut stream: TcpStream) {
+    let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Err(_) => Response::error(400, CommandError::from("Request timed out")),
+        Ok(Err(response)) => response,
+        Ok(Ok(request)) if !authorized(&request, token) => Response::error(
+            401,
+            CommandError::new(ErrorCode::InvalidInput, "Invalid or missing API token"),
+        ),
+        Ok(Ok(request)) => {
+            tracing::debug!(method = %request.method, path = %request.path, "api request");
+            route(app, request).await
+        }
+    };
+    let _ = stream.write_all(&response.to_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// `/v1/import` のリクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportRequest {
+    path: String,
+    #[serde(default)]
+    profile: Option<ImportProfile>,
+    /// 取り込み先のプロジェクトID（省略時は現在のプロジェクト）
+    #[serde(default)]
+    project_id: Option<String>,
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(request: &Request) -> Result<T, Response> {
+    serde_json::from_slice(&request.body).map_err(|e| {
+        Response::error(
+            400,
+            CommandError::new(ErrorCode::InvalidInput, format!("Invalid JSON body: {}", e)),
+        )
+    })
+}
+
+async fn route(app: &AppHandle, request: Request) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/v1/import") => import(app, &request).await,
+        ("POST", "/v1/downloads") => enqueue(app, &request),
+        ("GET", "/v1/status") => Ok(Response::json(200, app.state::<BatchState>().status(None))),
+        ("GET", "/v1/results") => results(app),
+        _ => Err(Response::error(
+            404,
+            CommandError::new(
+                ErrorCode::InvalidInput,
+                format!("Not found: {} {}", request.method, request.path),
+            ),
+        )),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+async fn import(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+    let body: ImportRequest = parse_body(request)?;
+    commands::import_excel(
+        app.clone(),
+        app.state(),
+        body.path,
+        body.profile,
+        body.project_id,
+    )
+    .await
+    .map(|result| Response::json(200, result))
+    .map_err(|e| Response::error(422, e))
+}
+
+/// 一括ダウンロードをバックグラウンドで開始
+fn enqueue(app: &AppHandle, request: &Request) -> Result<Response, Response> {
+    let body: BatchDownloadRequest = parse_body(request)?;
+    let state = app.state::<ApiServerState>();
+    let conflict = || {
+        Response::error(
+            409,
+            CommandError::new(
+                ErrorCode::InvalidInput,
+                "A batch download is already running",
+            ),
+        )
+    };
+    if app
+        .state::<BatchState>()
+        .status(body.batch_id.as_deref())
+        .running
+    {
+        return Err(conflict());
+    }
+    if state.downloading.swap(true, Ordering::SeqCst) {
+        return Err(conflict());
+    }
+
+    let total = body.items.len();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = commands::batch_download_ies_files(
+            app.clone(),
+            app.state(),
+            app.state(),
+            body,
+            BatchChannel::none(),
+        )
+        .await;
+        let state = app.state::<ApiServerState>();
+        *state.last_result.lock().unwrap() = Some(result);
+        state.downloading.store(false, Ordering::SeqCst);
+    });
+    Ok(Response::json(202, json!({ "accepted": total })))
+}
+
+fn results(app: &AppHandle) -> Result<Response, Response> {
+    match &*app.state::<ApiServerState>().last_result.lock().unwrap() {
+        Some(Ok(result)) => Ok(Response::json(200, result)),
+        Some(Err(e)) => Err(Response::error(422, e.clone())),
+        None => Err(Response::error(
+            404,
+            CommandError::new(ErrorCode::InvalidInput, "No batch download has been run"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "POST /v1/import?verbose=1 HTTP/1.1\r\nHost: 127.0.0.1\r\n\
+             Authorization: Bearer 0123456789abcdef\r\nContent-Length: 2",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/import");
+        assert_eq!(request.header("content-length"), Some("2"));
+
+        assert!(authorized(&request, "0123456789abcdef"));
+        assert!(!authorized(&request, "0123456789abcdeg"));
+        assert!(!authorized(&request, "0123456789"));
+
+        assert!(parse_head("GET /v1/status").is_err());
+        assert!(parse_head("GET /v1/status HTTP/2").is_err());
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        assert!(ApiServerSettings::default().validate().is_ok());
+
+        let mut settings = ApiServerSettings {
+            enabled: true,
+            ..ApiServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+        settings.token = Some("short".to_string());
+        assert!(settings.validate().is_err());
+        settings.token = Some("0123456789abcdef".to_string());
+        assert!(settings.validate().is_ok());
+        settings.port = 80;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_response_bytes() {
+        let response = Response::json(202, json!({ "accepted": 3 }));
+        let text = String::from_utf8(response.to_bytes()).unwrap();
+        assert!(text.starts_with("HTTP/1.1 202 Accepted\r\n"));
+        assert!(text.contains("Content-Length: 14\r\n"));
+        assert!(text.ends_with("\r\n\r\n{\"accepted\":3}"));
+    }
+}
@@ -0,0 +1,319 @@
+//! 一括ダウンロードの監査ログ（JSONL）
+//!
+//! 取得した配光データの出所を示す資料を求める顧客向けに、一括ダウンロードごとに
+//! 保存先フォルダへ `autosight-audit-YYYYMMDD-HHMMSS.jsonl` を書き出す。
+//! アイテムごとに送信したリクエストのURL・レスポンスのステータスと、
+//! 採用したファイル・取得元（URL・ステータス・Content-Type・Last-Modified）・保存先のパスを
+//! 1行1レコードで追記する（既存の行は変更しない）。
+//!
+//! 取得済みのアイテムを飛ばして再実行する場合（`skip_existing`）は、同じフォルダの
+//! 過去の監査ログを取得済みのファイルの一覧として読み込む（[`completed_files`]）。
+
+use crate::longpath;
+use crate::providers::{AssetType, DownloadResult, DownloadSource};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// 監査ログのファイル名の接頭辞
+const AUDIT_FILE_PREFIX: &str = "autosight-audit";
+
+/// 監査ログの1レコード
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AuditRecord {
+    /// 一括ダウンロードの開始
+    BatchStarted {
+        timestamp: DateTime<Utc>,
+        app_version: String,
+        items: usize,
+    },
+    /// 送信したリクエスト
+    Request {
+        timestamp: DateTime<Utc>,
+        spec_no: String,
+        method: String,
+        url: String,
+        /// レスポンスのステータス（通信エラーの場合は None）
+        status: Option<u16>,
+        error: Option<String>,
+    },
+    /// アセットの取得結果
+    Result {
+        timestamp: DateTime<Utc>,
+        spec_no: String,
+        asset_type: AssetType,
+        success: bool,
+        /// 採用したファイル（サーバー上のファイル名・ZIP内のエントリ名）
+        chosen_file: Option<String>,
+        /// 採用したファイルの取得元
+        source: Option<DownloadSource>,
+        /// 保存先のパス
+        final_path: Option<String>,
+        error: Option<String>,
+    },
+    /// キャンセルされたアイテム
+    Cancelled {
+        timestamp: DateTime<Utc>,
+        spec_no: String,
+    },
+}
+
+impl AuditRecord {
+    /// アセットの取得結果のレコード
+    pub fn result(spec_no: &str, asset_type: AssetType, result: &DownloadResult) -> Self {
+        AuditRecord::Result {
+            timestamp: Utc::now(),
+            spec_no: spec_no.to_string(),
+            asset_type,
+            success: result.success,
+            chosen_file: result.original_filename.clone(),
+            source: result.source.clone(),
+            final_path: result.file_path.clone(),
+            error: result.error.clone(),
+        }
+    }
+}
+
+/// 監査ログから読み込むアセットの取得結果（`result` 以外のレコードは読み飛ばす）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoggedResult {
+    kind: String,
+    spec_no: String,
+    asset_type: AssetType,
+    success: bool,
+    final_path: Option<String>,
+}
+
+/// 保存先フォルダの監査ログから、取得済みのファイル（Spec No.・アセット種別 → 保存先のパス）を読み込む
+///
+/// 監査ログを古い順に読み、取得に成功したもののうち、ファイルが残っているものを返す
+/// （同じアイテムを複数回取得した場合は最後のもの）。
+pub fn completed_files(dest_dir: &Path) -> HashMap<(String, AssetType), String> {
+    let Ok(entries) = std::fs::read_dir(dest_dir) else {
+        return HashMap::new();
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(AUDIT_FILE_PREFIX) && name.ends_with(".jsonl"))
+        })
+        .collect();
+    // ファイル名の日時の順
+    logs.sort();
+
+    let mut files = HashMap::new();
+    for log in logs {
+        let Ok(text) = std::fs::read_to_string(&log) else {
+            continue;
+        };
+        for record in text
+            .lines()
+            .filter_map(|line| serde_json::from_str::<LoggedResult>(line).ok())
+        {
+            if record.kind != "result" || !record.success {
+                continue;
+            }
+            if let Some(path) = record.final_path {
+                files.insert((record.spec_no, record.asset_type), path);
+            }
+        }
+    }
+    files.retain(|_, path| longpath::extended(path).exists());
+    files
+}
+
+/// 追記専用の監査ログファイル
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// 保存先フォルダに新しい監査ログを作成
+    pub fn create(dest_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dest_dir)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        let path = dest_dir.join(format!(
+            "{}-{}.jsonl",
+            AUDIT_FILE_PREFIX,
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create audit log: {}", e))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// レコードを1行追記
+    pub fn append(&self, record: &AuditRecord) {
+        let Ok(mut line) = serde_json::to_vec(record) else {
+            return;
+        };
+        line.push(b'\n');
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Err(e) = file.write_all(&line) {
+            tracing::warn!(error = %e, path = %self.path.display(), "failed to write audit log");
+        }
+    }
+}
+
+/// 実行中のアイテムの監査ログ
+#[derive(Clone)]
+pub struct AuditContext {
+    log: Arc<AuditLog>,
+    spec_no: String,
+}
+
+impl AuditContext {
+    pub fn new(log: Arc<AuditLog>, spec_no: &str) -> Self {
+        Self {
+            log,
+            spec_no: spec_no.to_string(),
+        }
+    }
+}
+
+tokio::task_local! {
+    /// 実行中のアイテムの監査ログ（プロバイダーのリクエストを記録するため）
+    static AUDIT_CONTEXT: AuditContext;
+}
+
+/// 監査ログを記録しながら処理を実行
+///
+/// 処理中に `send_request` で送信したリクエストは、このアイテムのレコードとして記録する。
+pub async fn scope<F: std::future::Future>(context: AuditContext, future: F) -> F::Output {
+    AUDIT_CONTEXT.scope(context, future).await
+}
+
+/// 送信したリクエストを記録（監査ログの記録中でなければ何もしない）
+pub fn record_request(method: &str, url: &str, status: Option<u16>, error: Option<String>) {
+    let _ = AUDIT_CONTEXT.try_with(|context| {
+        context.log.append(&AuditRecord::Request {
+            timestamp: Utc::now(),
+            spec_no: context.spec_no.clone(),
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            error,
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_audit_log() {
+        let temp = tempfile::tempdir().unwrap();
+        let log = Arc::new(AuditLog::create(temp.path()).unwrap());
+
+        // 記録中でないリクエストは無視される
+        record_request("GET", "https://example.com/ignored", Some(200), None);
+        block_on(scope(AuditContext::new(log.clone(), "A-1"), async {
+            record_request("GET", "https://example.com/search", Some(200), None);
+        }));
+        let result = DownloadResult::success(
+            "/out/A-1.ies".to_string(),
+            10,
+            Some("ABC123.ies".to_string()),
+        )
+        .with_source(DownloadSource {
+            url: "https://example.com/files/ABC123.ies".to_string(),
+            status: Some(200),
+            content_type: Some("application/octet-stream".to_string()),
+            last_modified: Some("Wed, 01 Oct 2025 00:00:00 GMT".to_string()),
+        });
+        log.append(&AuditRecord::result("A-1", AssetType::Ies, &result));
+
+        let contents = std::fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], "request");
+        assert_eq!(lines[0]["specNo"], "A-1");
+        assert_eq!(lines[0]["url"], "https://example.com/search");
+        assert_eq!(lines[1]["kind"], "result");
+        assert_eq!(lines[1]["chosenFile"], "ABC123.ies");
+        assert_eq!(lines[1]["finalPath"], "/out/A-1.ies");
+        assert_eq!(
+            lines[1]["source"]["url"],
+            "https://example.com/files/ABC123.ies"
+        );
+        assert_eq!(
+            lines[1]["source"]["lastModified"],
+            "Wed, 01 Oct 2025 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn test_completed_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let saved = temp.path().join("A-1_ABC123.ies");
+        std::fs::write(&saved, "IESNA:LM-63-2002").unwrap();
+        let saved = saved.to_string_lossy().into_owned();
+        let removed = temp
+            .path()
+            .join("A-2_DEF456.ies")
+            .to_string_lossy()
+            .into_owned();
+
+        let log = AuditLog::create(temp.path()).unwrap();
+        log.append(&AuditRecord::BatchStarted {
+            timestamp: Utc::now(),
+            app_version: "1.0.0".to_string(),
+            items: 3,
+        });
+        let success = |path: &str| DownloadResult::success(path.to_string(), 16, None);
+        log.append(&AuditRecord::result(
+            "A-1",
+            AssetType::Ies,
+            &success(&saved),
+        ));
+        // 削除されたファイルは取得済みとしない
+        log.append(&AuditRecord::result(
+            "A-2",
+            AssetType::Ies,
+            &success(&removed),
+        ));
+        log.append(&AuditRecord::result(
+            "A-3",
+            AssetType::Ies,
+            &DownloadResult::failure("request failed".to_string()),
+        ));
+
+        let files = completed_files(temp.path());
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files.get(&("A-1".to_string(), AssetType::Ies)),
+            Some(&saved)
+        );
+    }
+}
@@ -0,0 +1,1081 @@
+//! 一括ダウンロードの実行状態
+//!
+//! 処理中のアイテムを管理し、アイテム単位のキャンセル等の操作を提供する。
+//! バッチはIDごとに独立しており、複数のバッチを同時に実行できる。
+//! Tauriのmanaged stateとして保持する。
+
+use crate::error::ErrorCode;
+use crate::i18n::{Locale, Message};
+use crate::photometry::PhotometryFormat;
+use crate::providers::{CancelToken, DecisionRequest, DownloadPhase};
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::ipc::{Channel, CommandArg, CommandItem, InvokeError, JavaScriptChannelId};
+use tauri::Runtime;
+use tokio::sync::{oneshot, Notify};
+
+/// バッチIDを指定しない場合のバッチ
+///
+/// 既定のバッチの進捗は、バッチ固有のイベントに加えて従来のイベント名（`download-progress` 等）でも通知する。
+pub const DEFAULT_BATCH_ID: &str = "default";
+
+/// ダウンロード進捗イベントのペイロード（バッチ状態ではアイテムごとの状態を兼ねる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgressEvent {
+    /// Spec No.（アイテム識別用）
+    pub spec_no: String,
+    /// ステータス: "waiting" | "processing" | "success" | "error" | "cancelled" | "skipped"
+    ///
+    /// "skipped" は保存先に取得済みのファイルがあり、取得し直さなかったアイテム（`skip_existing`）。
+    pub status: String,
+    /// エラーメッセージ（エラー時のみ）
+    pub error: Option<String>,
+    /// エラーコード（エラー時のみ）
+    pub code: Option<ErrorCode>,
+    /// 再試行で解決する可能性がある失敗か
+    pub retryable: bool,
+    /// バッチ内の順番（0始まり）
+    #[serde(default)]
+    pub index: usize,
+    /// バッチ全体の件数
+    #[serde(default)]
+    pub total: usize,
+    /// 処理中の段階（処理中で、段階が分かる場合のみ）
+    #[serde(default)]
+    pub phase: Option<DownloadPhase>,
+    /// 受信したバイト数（受信中のみ）
+    #[serde(default)]
+    pub bytes: Option<u64>,
+    /// 受信するファイル全体のバイト数（受信中で、サーバーがサイズを返した場合のみ）
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
+    /// 利用者の判断を待っている内容（判断待ちの場合のみ）
+    #[serde(default)]
+    pub decision: Option<DecisionRequest>,
+    /// バッチID
+    #[serde(default)]
+    pub batch_id: String,
+    /// このイベント時点のバッチ全体の成功件数
+    #[serde(default)]
+    pub success_count: usize,
+    /// このイベント時点のバッチ全体の失敗件数
+    #[serde(default)]
+    pub failure_count: usize,
+    /// このイベント時点のバッチ全体のキャンセル件数
+    #[serde(default)]
+    pub cancelled_count: usize,
+    /// このイベント時点のバッチ全体の取得済みのため飛ばした件数
+    #[serde(default)]
+    pub skipped_count: usize,
+}
+
+impl DownloadProgressEvent {
+    pub fn new(spec_no: &str, status: &str, error: Option<String>) -> Self {
+        let code = error.as_deref().map(ErrorCode::classify);
+        Self {
+            spec_no: spec_no.to_string(),
+            status: status.to_string(),
+            error,
+            code,
+            retryable: status == "cancelled" || code.is_some_and(|c| c.is_retryable()),
+            index: 0,
+            total: 0,
+            phase: None,
+            bytes: None,
+            total_bytes: None,
+            decision: None,
+            batch_id: String::new(),
+            success_count: 0,
+            failure_count: 0,
+            cancelled_count: 0,
+            skipped_count: 0,
+        }
+    }
+
+    /// 処理中の段階・受信したバイト数を設定
+    pub fn with_phase(
+        mut self,
+        phase: DownloadPhase,
+        bytes: Option<u64>,
+        total_bytes: Option<u64>,
+    ) -> Self {
+        self.phase = Some(phase);
+        self.bytes = bytes;
+        self.total_bytes = total_bytes;
+        self
+    }
+}
+
+/// 呼び出し元のチャネルに送信するバッチのイベント
+///
+/// `{ "event": "progress", "data": {...} }` の形で送信する（`data` は対応するイベントのペイロード）。
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum BatchEvent {
+    /// アイテムの進捗（`download-progress`）
+    Progress(DownloadProgressEvent),
+    /// 一時停止・再開（`download-paused`。一時停止中か）
+    Paused(bool),
+    /// バッチの終了（`download-finished`）
+    Finished(BatchFinishedEvent),
+    /// アイテムの判断待ち（`download-decision-required`）
+    DecisionRequired(DecisionRequiredEvent),
+}
+
+/// バッチを開始したコマンドに渡されるイベントチャネル（省略可）
+///
+/// `Channel` は省略可能な引数（`Option<Channel<_>>`）として受け取れないため、
+/// チャネルIDの有無をこの型で受け取る。HTTP API等、チャネルを持たない呼び出し元は [`BatchChannel::none`] を渡す。
+pub struct BatchChannel(Option<Channel<BatchEvent>>);
+
+impl BatchChannel {
+    /// チャネルなし（イベントで通知する）
+    pub fn none() -> Self {
+        Self(None)
+    }
+}
+
+impl<'de, R: Runtime> CommandArg<'de, R> for BatchChannel {
+    fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+        let name = command.name;
+        let key = command.key;
+        let webview = command.message.webview();
+        let id: Option<JavaScriptChannelId> = Deserialize::deserialize(command)
+            .map_err(|e| InvokeError::from(tauri::Error::InvalidArgs(name, key, e)))?;
+        Ok(Self(id.map(|id| id.channel_on(webview))))
+    }
+}
+
+/// 判断待ちのアイテムのイベント（`download-decision-required`）のペイロード
+///
+/// `resolve_item_choice` で候補を選ぶまで、アイテムの処理は完了しない。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRequiredEvent {
+    /// バッチID
+    pub batch_id: String,
+    pub spec_no: String,
+    #[serde(flatten)]
+    pub decision: DecisionRequest,
+}
+
+/// 一括ダウンロードの完了イベント（`download-finished`）のペイロード
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFinishedEvent {
+    /// バッチID
+    pub batch_id: String,
+    pub success_count: usize,
+    pub failure_count: usize,
+    pub cancelled_count: usize,
+    /// 保存先ディレクトリ
+    pub dest_dir: String,
+    /// 保存先ディレクトリをFinder/エクスプローラーで開いたか
+    pub opened_dest_dir: bool,
+}
+
+impl BatchFinishedEvent {
+    /// 完了通知の本文（例: "182 succeeded, 3 failed"）
+    pub fn summary(&self, locale: Locale) -> String {
+        Message::BatchFinished {
+            success: self.success_count,
+            failure: self.failure_count,
+            cancelled: self.cancelled_count,
+        }
+        .text(locale)
+    }
+}
+
+/// 一括ダウンロードの現在の状態（`get_batch_status` の戻り値）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatus {
+    /// バッチID
+    pub batch_id: String,
+    /// バッチを実行中かどうか
+    pub running: bool,
+    /// 一時停止中か（処理中のアイテムは完了まで続け、次のアイテムから停止する）
+    pub paused: bool,
+    /// 全体の件数
+    pub total: usize,
+    /// 成功件数
+    pub success_count: usize,
+    /// 失敗件数
+    pub failure_count: usize,
+    /// キャンセル件数
+    pub cancelled_count: usize,
+    /// 取得済みのため飛ばした件数
+    pub skipped_count: usize,
+    /// 各アイテムの状態（リクエストの順序）
+    pub items: Vec<DownloadProgressEvent>,
+}
+
+/// 一括ダウンロードの実行状態（1バッチ分）
+///
+/// 複数のバッチ（例: 別々のプロジェクト）を同時に実行でき、状態・キャンセル指定はバッチごとに独立している。
+#[derive(Default)]
+pub struct Batch {
+    /// バッチID（進捗イベント名の名前空間）
+    id: String,
+    /// 処理中アイテムの中断ハンドル・中断指示（Spec No.をキーとする）
+    in_flight: Mutex<HashMap<String, (AbortHandle, CancelToken)>>,
+    /// 処理開始前にキャンセルされたアイテム
+    cancelled: Mutex<HashSet<String>>,
+    /// 実行中（または直前）のバッチの状態
+    status: Mutex<BatchStatus>,
+    /// バッチ全体が中断されたか（モバイルでバックグラウンドの実行時間が上限に達した場合等）
+    interrupted: AtomicBool,
+    /// 一時停止の解除・中断の通知
+    resumed: Notify,
+    /// バッチを開始した呼び出し元のチャネル（指定された場合、イベントはこのチャネルにのみ送信する）
+    channel: Mutex<Option<Channel<BatchEvent>>>,
+    /// 曖昧な一致を利用者に判断してもらうか（しない場合は自動で選ぶ）
+    interactive: AtomicBool,
+    /// 同時にダウンロードするアイテム数の上限（None は設定の同時実行数）
+    max_concurrency: Mutex<Option<usize>>,
+    /// IESファイルのダウンロードキャッシュを使うか
+    use_cache: AtomicBool,
+    /// 保存先に取得済みのファイルがあるアイテムを飛ばすか
+    skip_existing: AtomicBool,
+    /// 同じ内容のIESファイルの照合に保存先の既存のファイルも含めるか
+    dedupe_existing: AtomicBool,
+    /// 配光データの出力形式（LDTへの変換）
+    output_format: Mutex<PhotometryFormat>,
+    /// ファイル名テンプレート（None は設定のテンプレート）
+    filename_template: Mutex<Option<String>>,
+    /// 判断待ちのアイテムの選択結果の送信先（Spec No.をキーとする）
+    decisions: Mutex<HashMap<String, oneshot::Sender<Option<String>>>>,
+}
+
+impl Batch {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            status: Mutex::new(BatchStatus {
+                batch_id: id.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// バッチID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// バッチ固有のイベント名（例: `download-progress:{batch_id}`）
+    pub fn event_name(&self, event: &str) -> String {
+        format!("{}:{}", event, self.id)
+    }
+
+    /// 既定のバッチ（IDを指定しない従来の呼び出し）か
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_BATCH_ID
+    }
+
+    /// 新しいバッチの開始時に、前回のキャンセル指定・チャネル・判断待ちをクリア
+    pub fn reset(&self) {
+        self.cancelled.lock().unwrap().clear();
+        self.interrupted.store(false, Ordering::SeqCst);
+        *self.channel.lock().unwrap() = None;
+        self.interactive.store(false, Ordering::SeqCst);
+        *self.max_concurrency.lock().unwrap() = None;
+        self.use_cache.store(false, Ordering::SeqCst);
+        self.skip_existing.store(false, Ordering::SeqCst);
+        self.dedupe_existing.store(false, Ordering::SeqCst);
+        *self.output_format.lock().unwrap() = PhotometryFormat::default();
+        *self.filename_template.lock().unwrap() = None;
+        self.decisions.lock().unwrap().clear();
+    }
+
+    /// 曖昧な一致を利用者に判断してもらうかを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_interactive(&self, interactive: bool) {
+        self.interactive.store(interactive, Ordering::SeqCst);
+    }
+
+    /// 曖昧な一致を利用者に判断してもらうか
+    pub fn is_interactive(&self) -> bool {
+        self.interactive.load(Ordering::SeqCst)
+    }
+
+    /// 同時にダウンロードするアイテム数の上限を設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_max_concurrency(&self, max_concurrency: Option<usize>) {
+        *self.max_concurrency.lock().unwrap() = max_concurrency;
+    }
+
+    /// 同時にダウンロードするアイテム数の上限（None は設定の同時実行数）
+    pub fn max_concurrency(&self) -> Option<usize> {
+        *self.max_concurrency.lock().unwrap()
+    }
+
+    /// IESファイルのダウンロードキャッシュを使うかを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_use_cache(&self, use_cache: bool) {
+        self.use_cache.store(use_cache, Ordering::SeqCst);
+    }
+
+    /// IESファイルのダウンロードキャッシュを使うか
+    pub fn uses_cache(&self) -> bool {
+        self.use_cache.load(Ordering::SeqCst)
+    }
+
+    /// 保存先に取得済みのファイルがあるアイテムを飛ばすかを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_skip_existing(&self, skip_existing: bool) {
+        self.skip_existing.store(skip_existing, Ordering::SeqCst);
+    }
+
+    /// 保存先に取得済みのファイルがあるアイテムを飛ばすか
+    pub fn skips_existing(&self) -> bool {
+        self.skip_existing.load(Ordering::SeqCst)
+    }
+
+    /// 同じ内容のIESファイルの照合に保存先の既存のファイルも含めるかを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_dedupe_existing(&self, dedupe_existing: bool) {
+        self.dedupe_existing
+            .store(dedupe_existing, Ordering::SeqCst);
+    }
+
+    /// 同じ内容のIESファイルの照合に保存先の既存のファイルも含めるか
+    pub fn dedupes_existing(&self) -> bool {
+        self.dedupe_existing.load(Ordering::SeqCst)
+    }
+
+    /// 配光データの出力形式を設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_output_format(&self, format: PhotometryFormat) {
+        *self.output_format.lock().unwrap() = format;
+    }
+
+    /// 配光データの出力形式
+    pub fn output_format(&self) -> PhotometryFormat {
+        *self.output_format.lock().unwrap()
+    }
+
+    /// ファイル名テンプレートを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    pub fn set_filename_template(&self, template: Option<String>) {
+        *self.filename_template.lock().unwrap() = template;
+    }
+
+    /// ファイル名テンプレート（None は設定のテンプレート）
+    pub fn filename_template(&self) -> Option<String> {
+        self.filename_template.lock().unwrap().clone()
+    }
+
+    /// アイテムを判断待ちとして登録し、判断を待つ（判断せずに続行する指定の場合は `None`）
+    ///
+    /// 選択結果を取りこぼさないよう、候補の通知より前に呼んで登録しておく。
+    /// アイテムがキャンセルされた場合は、待機ごと破棄される。
+    pub fn wait_for_decision(&self, spec_no: &str) -> impl Future<Output = Option<String>> {
+        let (sender, receiver) = oneshot::channel();
+        self.decisions
+            .lock()
+            .unwrap()
+            .insert(spec_no.to_string(), sender);
+        async move { receiver.await.ok().flatten() }
+    }
+
+    /// 判断待ちのアイテムに選択結果を渡す（戻り値: 判断待ちだったか）
+    pub fn resolve_decision(&self, spec_no: &str, choice: Option<String>) -> bool {
+        let sender = self.decisions.lock().unwrap().remove(spec_no);
+        sender.is_some_and(|sender| sender.send(choice).is_ok())
+    }
+
+    /// イベントの送信先のチャネルを設定（バッチの開始後、最初のアイテムの処理前に呼ぶ）
+    ///
+    /// チャネルは呼び出し元のウィンドウにのみ届き、リスナーの登録前に発生したイベントも失われない。
+    pub fn set_channel(&self, channel: BatchChannel) {
+        *self.channel.lock().unwrap() = channel.0;
+    }
+
+    /// チャネルが設定されていればイベントを送信（戻り値: チャネルが設定されていたか）
+    pub fn send(&self, event: &BatchEvent) -> bool {
+        let Some(channel) = self.channel.lock().unwrap().clone() else {
+            return false;
+        };
+        if let Err(e) = channel.send(event.clone()) {
+            tracing::warn!(batch_id = %self.id, error = %e, "failed to send batch event");
+        }
+        true
+    }
+
+    /// 新しいバッチを開始し、全アイテムを待機中として記録
+    pub fn begin<'a>(&self, spec_nos: impl IntoIterator<Item = &'a str>) {
+        self.reset();
+        let spec_nos: Vec<_> = spec_nos.into_iter().collect();
+        let total = spec_nos.len();
+        let items = spec_nos
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec_no)| DownloadProgressEvent {
+                index,
+                total,
+                batch_id: self.id.clone(),
+                ..DownloadProgressEvent::new(spec_no, "waiting", None)
+            })
+            .collect();
+        *self.status.lock().unwrap() = BatchStatus {
+            batch_id: self.id.clone(),
+            running: true,
+            total,
+            items,
+            ..Default::default()
+        };
+    }
+
+    /// アイテムの進捗イベント（バッチID・バッチ内の順番・全体の件数を設定したもの）
+    ///
+    /// 成功・失敗等の件数は [`Batch::update`] で記録した時点の値に更新される。
+    pub fn progress_event(
+        &self,
+        spec_no: &str,
+        status: &str,
+        error: Option<String>,
+    ) -> DownloadProgressEvent {
+        let mut event = DownloadProgressEvent::new(spec_no, status, error);
+        event.batch_id = self.id.clone();
+        let status = self.status.lock().unwrap();
+        event.total = status.total;
+        if let Some(item) = status.items.iter().find(|item| item.spec_no == spec_no) {
+            event.index = item.index;
+        }
+        event
+    }
+
+    /// アイテムの状態を更新（イベントには更新後のバッチ全体の件数を設定する）
+    pub fn update(&self, event: &mut DownloadProgressEvent) {
+        let mut status = self.status.lock().unwrap();
+        match event.status.as_str() {
+            "success" => status.success_count += 1,
+            "error" => status.failure_count += 1,
+            "cancelled" => status.cancelled_count += 1,
+            "skipped" => status.skipped_count += 1,
+            _ => {}
+        }
+        event.success_count = status.success_count;
+        event.failure_count = status.failure_count;
+        event.cancelled_count = status.cancelled_count;
+        event.skipped_count = status.skipped_count;
+        if let Some(item) = status
+            .items
+            .iter_mut()
+            .find(|item| item.spec_no == event.spec_no)
+        {
+            *item = event.clone();
+        }
+    }
+
+    /// バッチの終了を記録（アイテムの状態は次のバッチ開始まで保持）
+    pub fn finish(&self) {
+        self.status.lock().unwrap().running = false;
+    }
+
+    /// 実行中かどうか
+    pub fn is_running(&self) -> bool {
+        self.status.lock().unwrap().running
+    }
+
+    /// 一時停止・再開（戻り値: 状態が変わった場合は true）
+    ///
+    /// 実行中でないバッチは一時停止できない。
+    pub fn set_paused(&self, paused: bool) -> bool {
+        let mut status = self.status.lock().unwrap();
+        if status.paused == paused || (paused && !status.running) {
+            return false;
+        }
+        status.paused = paused;
+        drop(status);
+        if !paused {
+            self.resumed.notify_waiters();
+        }
+        true
+    }
+
+    /// 一時停止中か
+    pub fn is_paused(&self) -> bool {
+        self.status.lock().unwrap().paused
+    }
+
+    /// 一時停止中であれば、再開または中断されるまで待つ
+    pub async fn wait_if_paused(&self) {
+        loop {
+            // 判定の前に待機を登録し、判定との間の再開を取りこぼさない
+            let resumed = self.resumed.notified();
+            if !self.is_paused() || self.is_interrupted() {
+                return;
+            }
+            resumed.await;
+        }
+    }
+
+    /// 直前のバッチで失敗・キャンセルしたアイテムのSpec No.
+    ///
+    /// `include_permanent` が false の場合は、再試行で解決する可能性があるものに限る。
+    pub fn failed_items(&self, include_permanent: bool) -> HashSet<String> {
+        self.status
+            .lock()
+            .unwrap()
+            .items
+            .iter()
+            .filter(|item| matches!(item.status.as_str(), "error" | "cancelled"))
+            .filter(|item| include_permanent || item.retryable)
+            .map(|item| item.spec_no.clone())
+            .collect()
+    }
+
+    /// 現在のバッチの状態を取得
+    pub fn status(&self) -> BatchStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// アイテムの処理をキャンセル可能な形で実行
+    ///
+    /// キャンセルされた場合（処理開始前を含む）は `None` を返す。
+    /// 中断時は実行中のHTTPリクエストごとfutureが破棄される。専用スレッドで実行中の処理
+    /// （ZIPの展開等）は破棄できないため、`start` に渡す中断指示で途中で止める。
+    pub async fn run_cancellable<F, Fut>(&self, spec_no: &str, start: F) -> Option<Fut::Output>
+    where
+        F: FnOnce(CancelToken) -> Fut,
+        Fut: Future,
+    {
+        if self.cancelled.lock().unwrap().remove(spec_no) || self.is_interrupted() {
+            return None;
+        }
+
+        let (handle, registration) = AbortHandle::new_pair();
+        let cancel = CancelToken::new();
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(spec_no.to_string(), (handle, cancel.clone()));
+
+        let result = Abortable::new(start(cancel), registration).await.ok();
+
+        self.in_flight.lock().unwrap().remove(spec_no);
+        result
+    }
+
+    /// アイテムをキャンセル
+    ///
+    /// 処理中であれば中断し、未着手であれば順番が来た時点でスキップする。
+    /// 戻り値: 処理中のアイテムを中断した場合は true
+    pub fn cancel(&self, spec_no: &str) -> bool {
+        match self.in_flight.lock().unwrap().get(spec_no) {
+            Some((handle, cancel)) => {
+                cancel.cancel();
+                handle.abort();
+                true
+            }
+            None => {
+                self.cancelled.lock().unwrap().insert(spec_no.to_string());
+                false
+            }
+        }
+    }
+
+    /// バッチ全体を中断
+    ///
+    /// 処理中のアイテムを中断し、残りのアイテムはすべてキャンセルとして扱う。
+    /// 中断したバッチは次回の起動時に再開できるよう、チェックポイントを残す。
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+        for (handle, cancel) in self.in_flight.lock().unwrap().values() {
+            cancel.cancel();
+            handle.abort();
+        }
+        self.resumed.notify_waiters();
+    }
+
+    /// バッチ全体が中断されたか
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+}
+
+/// 実行中・実行済みのバッチの一覧と、保存先ディレクトリの記録
+#[derive(Default)]
+pub struct BatchState {
+    /// バッチIDごとの実行状態
+    batches: Mutex<HashMap<String, Arc<Batch>>>,
+    /// これまでのバッチで使用した保存先ディレクトリ（正規化済み）
+    dest_dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl BatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// バッチを取得（未作成の場合は作成）
+    ///
+    /// `batch_id` を省略した場合は既定のバッチを返す。
+    pub fn get(&self, batch_id: Option<&str>) -> Arc<Batch> {
+        let id = batch_id.unwrap_or(DEFAULT_BATCH_ID);
+        self.batches
+            .lock()
+            .unwrap()
+            .entry(id.to_string())
+            .or_insert_with(|| Arc::new(Batch::new(id)))
+            .clone()
+    }
+
+    /// 新しいバッチを開始し、全アイテムを待機中として記録
+    ///
+    /// 同じIDのバッチが実行中の場合はエラー。別のIDのバッチとは同時に実行できる。
+    pub fn start<'a>(
+        &self,
+        batch_id: Option<&str>,
+        spec_nos: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Arc<Batch>, String> {
+        let id = batch_id.unwrap_or(DEFAULT_BATCH_ID);
+        if id.trim().is_empty() {
+            return Err("Batch ID must not be empty".to_string());
+        }
+        let mut batches = self.batches.lock().unwrap();
+        let batch = batches
+            .entry(id.to_string())
+            .or_insert_with(|| Arc::new(Batch::new(id)));
+        if batch.is_running() {
+            return Err(format!("Batch is already running: {}", id));
+        }
+        batch.begin(spec_nos);
+        Ok(batch.clone())
+    }
+
+    /// バッチの状態を取得（未作成の場合は空の状態）
+    pub fn status(&self, batch_id: Option<&str>) -> BatchStatus {
+        self.get(batch_id).status()
+    }
+
+    /// すべてのバッチの状態（バッチIDの順）
+    pub fn list(&self) -> Vec<BatchStatus> {
+        let mut statuses: Vec<_> = self
+            .batches
+            .lock()
+            .unwrap()
+            .values()
+            .map(|batch| batch.status())
+            .collect();
+        statuses.sort_by(|a, b| a.batch_id.cmp(&b.batch_id));
+        statuses
+    }
+
+    /// 実行中のバッチの数
+    pub fn running_count(&self) -> usize {
+        self.batches
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|batch| batch.is_running())
+            .count()
+    }
+
+    /// 実行中のバッチ
+    pub fn running(&self) -> Vec<Arc<Batch>> {
+        self.batches
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|batch| batch.is_running())
+            .cloned()
+            .collect()
+    }
+
+    /// 実行中のバッチをすべて中断
+    pub fn interrupt_all(&self) {
+        for batch in self.batches.lock().unwrap().values() {
+            if batch.is_running() {
+                batch.interrupt();
+            }
+        }
+    }
+
+    /// バッチの保存先ディレクトリを記録
+    ///
+    /// 記録したディレクトリ配下のファイルのみ、ダウンロード済みファイルとして開くことを許可する。
+    pub fn register_dest_dir(&self, dest_dir: &str) {
+        if let Ok(dir) = Path::new(dest_dir).canonicalize() {
+            self.dest_dirs.lock().unwrap().insert(dir);
+        }
+    }
+
+    /// ダウンロード済みファイルのパスを検証し、正規化したパスを返す
+    ///
+    /// 存在しないパスや、記録済みの保存先ディレクトリの外を指すパス
+    /// （`..` やシンボリックリンク経由を含む）はエラーとする。
+    pub fn resolve_downloaded_path(&self, path: &str) -> Result<PathBuf, String> {
+        let resolved = Path::new(path)
+            .canonicalize()
+            .map_err(|e| format!("File not found: {} ({})", path, e))?;
+
+        let dest_dirs = self.dest_dirs.lock().unwrap();
+        if dest_dirs.iter().any(|dir| resolved.starts_with(dir)) {
+            Ok(resolved)
+        } else {
+            Err(format!(
+                "Path is outside the destination directory: {}",
+                path
+            ))
+        }
+    }
+}
+
+/// アイテムの処理開始の間隔を空ける（バッチ内で同時に処理する全アイテムで共有する）
+///
+/// 前のアイテムの開始時刻を1か所で管理するため、同時実行数によらずアイテムの開始が間隔どおりに並ぶ。
+#[derive(Debug, Default)]
+pub struct StartPacer(tokio::sync::Mutex<Option<tokio::time::Instant>>);
+
+impl StartPacer {
+    /// 前のアイテムの開始から `delay` が経つまで待つ（`None` は待たずに開始する）
+    pub async fn wait(&self, delay: Option<Duration>) {
+        let mut last_started = self.0.lock().await;
+        if let (Some(last), Some(delay)) = (*last_started, delay) {
+            tokio::time::sleep_until(last + delay).await;
+        }
+        *last_started = Some(tokio::time::Instant::now());
+    }
+}
+
+/// `count` 件のアイテムを最大 `max_concurrency` 件ずつ同時に処理し、結果をアイテムと同じ順に返す
+pub async fn process_in_order<F, Fut>(
+    count: usize,
+    max_concurrency: usize,
+    process: F,
+) -> Vec<Fut::Output>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future,
+{
+    futures::stream::iter(0..count)
+        .map(process)
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_process_in_order() {
+        use std::sync::atomic::AtomicUsize;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        // 先のアイテムほど時間がかかる（完了順はアイテムの順と逆になる）
+        let results = runtime.block_on(process_in_order(4, 2, |i| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(40 - 10 * i as u64)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        }));
+        assert_eq!(results, vec![0, 1, 2, 3]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_start_pacer() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let pacer = StartPacer::default();
+        let delay = Duration::from_millis(20);
+        let started = std::time::Instant::now();
+        // 同時に開始しようとしても間隔を空けて1件ずつ開始する
+        runtime.block_on(process_in_order(3, 3, |_| pacer.wait(Some(delay))));
+        assert!(started.elapsed() >= delay * 2);
+
+        // 間隔の指定がなければ待たない
+        let started = std::time::Instant::now();
+        runtime.block_on(process_in_order(3, 3, |_| pacer.wait(None)));
+        assert!(started.elapsed() < delay);
+    }
+
+    #[test]
+    fn test_run_cancellable() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        assert_eq!(
+            block_on(state.run_cancellable("1001", |_| async { 42 })),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn test_batch_event_without_channel() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        // チャネル未設定時は送信せず、呼び出し元がイベントで通知する
+        assert!(!state.send(&BatchEvent::Paused(true)));
+        let json = serde_json::to_value(BatchEvent::Paused(true)).unwrap();
+        assert_eq!(json, serde_json::json!({ "event": "paused", "data": true }));
+    }
+
+    #[test]
+    fn test_resolve_decision() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        // 判断待ちでなければ選択結果を渡せない
+        assert!(!state.resolve_decision("1001", Some("a.ies".to_string())));
+
+        let waiting = state.wait_for_decision("1001");
+        assert!(state.resolve_decision("1001", Some("a.ies".to_string())));
+        assert_eq!(block_on(waiting), Some("a.ies".to_string()));
+        assert!(!state.resolve_decision("1001", None));
+    }
+
+    #[test]
+    fn test_cancel_signals_token() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        let mut token = None;
+        // 処理中のキャンセルは、専用スレッドの処理向けに中断指示も出す
+        let result = block_on(state.run_cancellable("1001", |cancel| {
+            token = Some(cancel);
+            async { state.cancel("1001") }
+        }));
+        assert_eq!(result, Some(true));
+        assert!(token.unwrap().is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_before_start() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+
+        // 未着手のアイテムは順番が来た時点でスキップされる
+        assert!(!state.cancel("1001"));
+        assert_eq!(
+            block_on(state.run_cancellable("1001", |_| async { 42 })),
+            None
+        );
+
+        // スキップは1回限り
+        assert_eq!(
+            block_on(state.run_cancellable("1001", |_| async { 42 })),
+            Some(42)
+        );
+
+        // 新しいバッチの開始時にクリアされる
+        state.cancel("1002");
+        state.reset();
+        assert_eq!(
+            block_on(state.run_cancellable("1002", |_| async { 42 })),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn test_interrupt() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        state.interrupt();
+        assert!(state.is_interrupted());
+        assert_eq!(
+            block_on(state.run_cancellable("1001", |_| async { 42 })),
+            None
+        );
+
+        // 新しいバッチの開始時に解除される
+        state.begin(["1001"]);
+        assert!(!state.is_interrupted());
+        assert_eq!(
+            block_on(state.run_cancellable("1001", |_| async { 42 })),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn test_finished_summary() {
+        let mut event = BatchFinishedEvent {
+            batch_id: DEFAULT_BATCH_ID.to_string(),
+            success_count: 182,
+            failure_count: 3,
+            cancelled_count: 0,
+            dest_dir: "/tmp/ies".to_string(),
+            opened_dest_dir: false,
+        };
+        assert_eq!(event.summary(Locale::En), "182 succeeded, 3 failed");
+        event.cancelled_count = 2;
+        assert_eq!(
+            event.summary(Locale::En),
+            "182 succeeded, 3 failed, 2 cancelled"
+        );
+        assert_eq!(
+            event.summary(Locale::Ja),
+            "成功 182件・失敗 3件・キャンセル 2件"
+        );
+    }
+
+    #[test]
+    fn test_batch_status() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        assert!(!state.status().running);
+
+        state.begin(["1001", "1002", "1003"]);
+        state.update(&mut DownloadProgressEvent::new("1001", "success", None));
+        state.update(&mut DownloadProgressEvent::new(
+            "1002",
+            "error",
+            Some("Not found".to_string()),
+        ));
+
+        let status = state.status();
+        assert!(status.running);
+        assert_eq!(status.total, 3);
+        assert_eq!(status.success_count, 1);
+        assert_eq!(status.failure_count, 1);
+        assert_eq!(status.items[1].error.as_deref(), Some("Not found"));
+        assert_eq!(status.items[2].status, "waiting");
+        assert_eq!((status.items[2].index, status.items[2].total), (2, 3));
+
+        // 処理中の段階・受信したバイト数も記録する
+        let mut event = state.progress_event("1003", "processing", None).with_phase(
+            DownloadPhase::Downloading,
+            Some(512),
+            Some(1024),
+        );
+        assert_eq!((event.index, event.total), (2, 3));
+        assert_eq!(event.batch_id, DEFAULT_BATCH_ID);
+        state.update(&mut event);
+        // イベントには記録した時点のバッチ全体の件数が入る
+        assert_eq!((event.success_count, event.failure_count), (1, 1));
+        let status = state.status();
+        let item = &status.items[2];
+        assert_eq!(item.phase, Some(DownloadPhase::Downloading));
+        assert_eq!((item.bytes, item.total_bytes), (Some(512), Some(1024)));
+
+        // 終了後も結果は参照できる
+        state.finish();
+        let status = state.status();
+        assert!(!status.running);
+        assert_eq!(status.items[0].status, "success");
+    }
+
+    #[test]
+    fn test_concurrent_batches() {
+        let state = BatchState::new();
+        let a = state.start(Some("p1"), ["1001", "1002"]).unwrap();
+        let b = state.start(Some("p2"), ["1001"]).unwrap();
+        assert_eq!(state.running_count(), 2);
+        assert_eq!(a.event_name("download-progress"), "download-progress:p1");
+
+        // 同じIDのバッチは二重に開始できない
+        assert!(state.start(Some("p1"), ["1003"]).is_err());
+        assert!(state.start(Some(""), ["1003"]).is_err());
+
+        // 状態・キャンセル指定はバッチごとに独立している
+        a.update(&mut DownloadProgressEvent::new("1001", "success", None));
+        b.cancel("1001");
+        assert_eq!(state.status(Some("p1")).success_count, 1);
+        assert_eq!(state.status(Some("p2")).success_count, 0);
+        assert_eq!(
+            block_on(a.run_cancellable("1001", |_| async { 42 })),
+            Some(42)
+        );
+        assert_eq!(block_on(b.run_cancellable("1001", |_| async { 42 })), None);
+
+        b.finish();
+        assert_eq!(state.running_count(), 1);
+        assert!(state.start(Some("p2"), ["1002"]).is_ok());
+        let ids: Vec<_> = state.list().into_iter().map(|s| s.batch_id).collect();
+        assert_eq!(ids, vec!["p1", "p2"]);
+
+        // IDを省略した場合は既定のバッチ
+        assert!(!state.status(None).running);
+        assert_eq!(state.status(None).batch_id, DEFAULT_BATCH_ID);
+    }
+
+    #[test]
+    fn test_pause() {
+        let batch = Batch::new(DEFAULT_BATCH_ID);
+        // 実行中でないバッチは一時停止できない
+        assert!(!batch.set_paused(true));
+
+        batch.begin(["1001"]);
+        assert!(batch.set_paused(true));
+        assert!(!batch.set_paused(true));
+        assert!(batch.status().paused);
+
+        // 再開すると待機中の処理が続行する
+        let waiting = batch.wait_if_paused();
+        assert!(batch.set_paused(false));
+        block_on(waiting);
+
+        // 中断した場合も待機を終える
+        batch.set_paused(true);
+        batch.interrupt();
+        block_on(batch.wait_if_paused());
+
+        // 新しいバッチの開始時に解除される
+        batch.begin(["1001"]);
+        assert!(!batch.is_paused());
+    }
+
+    #[test]
+    fn test_failed_items() {
+        let state = Batch::new(DEFAULT_BATCH_ID);
+        state.begin(["1001", "1002", "1003", "1004"]);
+        state.update(&mut DownloadProgressEvent::new("1001", "success", None));
+        state.update(&mut DownloadProgressEvent::new(
+            "1002",
+            "error",
+            Some("Download request failed: operation timed out".to_string()),
+        ));
+        state.update(&mut DownloadProgressEvent::new(
+            "1003",
+            "error",
+            Some("IES file not available for: AD1".to_string()),
+        ));
+        state.update(&mut DownloadProgressEvent::new("1004", "cancelled", None));
+
+        let retryable = state.failed_items(false);
+        assert_eq!(retryable.len(), 2);
+        assert!(retryable.contains("1002") && retryable.contains("1004"));
+        assert_eq!(state.failed_items(true).len(), 3);
+    }
+
+    #[test]
+    fn test_resolve_downloaded_path() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("dest");
+        std::fs::create_dir_all(dest.join("BIM")).unwrap();
+        std::fs::write(dest.join("BIM/1001.rfa"), b"").unwrap();
+        std::fs::write(root.path().join("secret.txt"), b"").unwrap();
+
+        let state = BatchState::new();
+        let file = dest.join("BIM/1001.rfa");
+
+        // 保存先が未記録の間は開けない
+        assert!(state
+            .resolve_downloaded_path(file.to_str().unwrap())
+            .is_err());
+
+        state.register_dest_dir(dest.to_str().unwrap());
+        assert_eq!(
+            state.resolve_downloaded_path(file.to_str().unwrap()),
+            Ok(file.canonicalize().unwrap())
+        );
+
+        // 保存先の外を指すパスは拒否する
+        let escaped = dest.join("../secret.txt");
+        assert!(state
+            .resolve_downloaded_path(escaped.to_str().unwrap())
+            .is_err());
+        assert!(state
+            .resolve_downloaded_path(dest.join("missing.ies").to_str().unwrap())
+            .is_err());
+    }
+}
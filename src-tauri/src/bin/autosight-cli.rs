@@ -0,0 +1,6 @@
+//! GUIを起動せずに一括ダウンロードを実行するコマンドラインツール（詳細は `autosight_lib::cli`）
+
+fn main() {
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(autosight_lib::cli::run(args));
+}
@@ -0,0 +1,120 @@
+//! ダウンロードバッファの上限
+//!
+//! レスポンス本文をメモリ上に読み込んでから保存するダウンロード（コイズミ照明の資料・
+//! URL指定ダウンロード）について、同時に保持するバッファの数と合計サイズを制限する。
+//! 上限に達した場合は、他のバッファが解放されるまで読み込みを待機する。
+//! 大きなZIPを含む1,000件規模の一括ダウンロードでもメモリを使い切らないようにするためのもの。
+
+use std::sync::LazyLock;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// 同時に保持するバッファ数の上限
+const MAX_BUFFERS: usize = 8;
+/// バッファの合計サイズの上限（バイト）
+const MAX_BUFFERED_BYTES: u64 = 256 * 1024 * 1024;
+/// サイズが不明なレスポンスに予約するサイズ（バイト）
+const UNKNOWN_SIZE_RESERVATION: u64 = 16 * 1024 * 1024;
+/// サイズを管理する単位（バイト。セマフォの許可数に収めるため KiB 単位で数える）
+const UNIT_BYTES: u64 = 1024;
+
+static BUDGET: LazyLock<BufferBudget> =
+    LazyLock::new(|| BufferBudget::new(MAX_BUFFERS, MAX_BUFFERED_BYTES));
+
+/// バッファの使用枠
+pub struct BufferBudget {
+    buffers: Semaphore,
+    units: Semaphore,
+    max_units: u32,
+}
+
+/// 確保したバッファの使用枠（破棄すると解放される）
+pub struct BufferPermit<'a> {
+    _buffer: SemaphorePermit<'a>,
+    _units: SemaphorePermit<'a>,
+}
+
+impl BufferBudget {
+    pub fn new(max_buffers: usize, max_bytes: u64) -> Self {
+        let max_units = (max_bytes / UNIT_BYTES).clamp(1, u32::MAX as u64) as u32;
+        Self {
+            buffers: Semaphore::new(max_buffers.max(1)),
+            units: Semaphore::new(max_units as usize),
+            max_units,
+        }
+    }
+
+    /// 使用枠を確保（空きがなければ待機する）
+    ///
+    /// `size` はレスポンスの Content-Length。上限を超えるサイズは上限として扱う
+    /// （単独では読み込めるが、他のバッファとは同時に保持しない）。
+    pub async fn acquire(&self, size: Option<u64>) -> BufferPermit<'_> {
+        let units = self.units_for(size);
+        if self.buffers.available_permits() == 0 || self.units.available_permits() < units as usize
+        {
+            tracing::debug!(size, "waiting for download buffer");
+        }
+        let buffer = self
+            .buffers
+            .acquire()
+            .await
+            .expect("Buffer semaphore closed");
+        let units = self
+            .units
+            .acquire_many(units)
+            .await
+            .expect("Buffer semaphore closed");
+        BufferPermit {
+            _buffer: buffer,
+            _units: units,
+        }
+    }
+
+    fn units_for(&self, size: Option<u64>) -> u32 {
+        let bytes = size.unwrap_or(UNKNOWN_SIZE_RESERVATION);
+        bytes.div_ceil(UNIT_BYTES).clamp(1, self.max_units as u64) as u32
+    }
+}
+
+/// レスポンス本文を読み込む前に、アプリ全体のバッファの使用枠を確保
+///
+/// 戻り値の使用枠は、読み込んだ内容をファイルに書き込み終えるまで保持すること。
+pub async fn acquire(size: Option<u64>) -> BufferPermit<'static> {
+    BUDGET.acquire(size).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::FutureExt;
+
+    #[test]
+    fn test_budget_backpressure() {
+        let budget = BufferBudget::new(2, 10 * UNIT_BYTES);
+
+        // 合計サイズの上限
+        let large = block_on(budget.acquire(Some(8 * UNIT_BYTES)));
+        assert!(budget
+            .acquire(Some(4 * UNIT_BYTES))
+            .now_or_never()
+            .is_none());
+        let small = block_on(budget.acquire(Some(2 * UNIT_BYTES)));
+
+        // バッファ数の上限
+        assert!(budget.acquire(Some(1)).now_or_never().is_none());
+
+        // 解放されると確保できる
+        drop(large);
+        assert!(budget
+            .acquire(Some(4 * UNIT_BYTES))
+            .now_or_never()
+            .is_some());
+        drop(small);
+
+        // 上限を超えるサイズは上限として扱う
+        assert!(budget
+            .acquire(Some(100 * UNIT_BYTES))
+            .now_or_never()
+            .is_some());
+    }
+}
@@ -0,0 +1,156 @@
+//! ディスクキャッシュの管理
+//!
+//! アプリのキャッシュディレクトリ配下を用途（スコープ）ごとのサブフォルダに分けて管理し、
+//! 使用量の集計と削除を行う。
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// キャッシュの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheScope {
+    /// HTTPレスポンス
+    Http,
+    /// 製品情報
+    ProductInfo,
+    /// TOKISTARのIES ZIP
+    TokistarZip,
+    /// 製品画像のサムネイル
+    Thumbnails,
+    /// ダウンロードしたIESファイル（`download_cache` モジュール）
+    IesDownload,
+}
+
+impl CacheScope {
+    /// すべての種別
+    pub const ALL: [CacheScope; 5] = [
+        CacheScope::Http,
+        CacheScope::ProductInfo,
+        CacheScope::TokistarZip,
+        CacheScope::Thumbnails,
+        CacheScope::IesDownload,
+    ];
+
+    /// キャッシュディレクトリ内のサブフォルダ名
+    pub fn subdir(&self) -> &'static str {
+        match self {
+            CacheScope::Http => "http",
+            CacheScope::ProductInfo => "product_info",
+            CacheScope::TokistarZip => "tokistar_zip",
+            CacheScope::Thumbnails => "thumbnails",
+            CacheScope::IesDownload => "ies_download",
+        }
+    }
+
+    /// この種別のキャッシュを保存するディレクトリ
+    pub fn dir(&self, cache_dir: &Path) -> PathBuf {
+        cache_dir.join(self.subdir())
+    }
+}
+
+/// キー（型番等）をファイル名に使える形式に変換（英数字と '-' 以外は '_'）
+pub fn file_stem(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 種別ごとのキャッシュ使用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub scope: CacheScope,
+    /// ファイル数
+    pub file_count: u64,
+    /// 合計サイズ（バイト）
+    pub total_bytes: u64,
+}
+
+/// ディレクトリ配下のファイル数と合計サイズを集計（存在しない場合は0）
+fn dir_usage(dir: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            let (count, bytes) = dir_usage(&entry.path());
+            file_count += count;
+            total_bytes += bytes;
+        } else {
+            file_count += 1;
+            total_bytes += metadata.len();
+        }
+    }
+    (file_count, total_bytes)
+}
+
+/// 全種別のキャッシュ使用量を取得
+pub fn stats(cache_dir: &Path) -> Vec<CacheStats> {
+    CacheScope::ALL
+        .iter()
+        .map(|&scope| {
+            let (file_count, total_bytes) = dir_usage(&scope.dir(cache_dir));
+            CacheStats {
+                scope,
+                file_count,
+                total_bytes,
+            }
+        })
+        .collect()
+}
+
+/// 指定した種別のキャッシュを削除し、削除した合計サイズ（バイト）を返す
+pub fn clear(cache_dir: &Path, scopes: &[CacheScope]) -> Result<u64, String> {
+    let mut freed = 0;
+    for scope in scopes {
+        let dir = scope.dir(cache_dir);
+        if !dir.exists() {
+            continue;
+        }
+        let (_, bytes) = dir_usage(&dir);
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to clear {} cache: {}", scope.subdir(), e))?;
+        freed += bytes;
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_and_clear() {
+        let temp = tempfile::tempdir().unwrap();
+        let thumbnails = CacheScope::Thumbnails.dir(temp.path()).join("koizumi");
+        std::fs::create_dir_all(&thumbnails).unwrap();
+        std::fs::write(thumbnails.join("a.png"), [0u8; 10]).unwrap();
+        std::fs::write(thumbnails.join("b.png"), [0u8; 5]).unwrap();
+
+        let stats = stats(temp.path());
+        assert_eq!(stats.len(), CacheScope::ALL.len());
+        let thumbnail_stats = stats
+            .iter()
+            .find(|s| s.scope == CacheScope::Thumbnails)
+            .unwrap();
+        assert_eq!(thumbnail_stats.file_count, 2);
+        assert_eq!(thumbnail_stats.total_bytes, 15);
+
+        assert_eq!(clear(temp.path(), &[CacheScope::Http]).unwrap(), 0);
+        assert_eq!(clear(temp.path(), &CacheScope::ALL).unwrap(), 15);
+        assert!(!CacheScope::Thumbnails.dir(temp.path()).exists());
+    }
+}
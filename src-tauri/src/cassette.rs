@@ -0,0 +1,226 @@
+//! 通信の記録・再生（カセット）
+//!
+//! 設定の `cassette.mode` により、メーカーサイトにアクセスせずに一括ダウンロードの処理全体を
+//! 動かす（デモ・通信できない場所での研修・結合テスト用）。
+//! - `mock`: すべてのメーカーの行をモックプロバイダー（`providers::mock`）で処理する
+//! - `record`: `send_request` で送信したリクエストと応答をカセットのディレクトリに記録する
+//! - `replay`: 記録した応答を返す。記録にないリクエストは送信せずにエラーにする
+//!
+//! 形式: {カセットのディレクトリ}/{メソッドとURLのSHA-256の先頭16桁}.json（本文はBase64）
+
+use crate::portable;
+use base64::Engine;
+use reqwest::ResponseBuilderExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use tauri::AppHandle;
+
+/// カセットのディレクトリの既定値（データディレクトリ内）
+const DEFAULT_DIR: &str = "cassettes";
+
+/// 通信の記録・再生のモード
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CassetteMode {
+    /// 通常どおり通信する
+    #[default]
+    Off,
+    /// すべてのメーカーの行をモックプロバイダーで処理する
+    Mock,
+    /// 通信した内容を記録する
+    Record,
+    /// 記録した内容を再生する（通信しない）
+    Replay,
+}
+
+/// 通信の記録・再生の設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CassetteSettings {
+    pub mode: CassetteMode,
+    /// カセットのディレクトリ（省略時はデータディレクトリの `cassettes`）
+    pub dir: Option<String>,
+}
+
+/// 実行中のモードとカセットのディレクトリ
+struct State {
+    mode: CassetteMode,
+    dir: PathBuf,
+}
+
+static STATE: LazyLock<RwLock<State>> = LazyLock::new(|| {
+    RwLock::new(State {
+        mode: CassetteMode::Off,
+        dir: PathBuf::new(),
+    })
+});
+
+/// 設定を反映（起動時・設定の保存時）
+pub fn apply(app: &AppHandle, settings: &CassetteSettings) {
+    let dir = match &settings.dir {
+        Some(dir) => PathBuf::from(dir),
+        None => portable::data_dir(app)
+            .map(|dir| dir.join(DEFAULT_DIR))
+            .unwrap_or_default(),
+    };
+    let mut state = STATE.write().unwrap();
+    if state.mode != settings.mode {
+        tracing::info!(mode = ?settings.mode, dir = %dir.display(), "cassette mode changed");
+    }
+    *state = State {
+        mode: settings.mode,
+        dir,
+    };
+}
+
+/// 実行中のモード
+pub fn mode() -> CassetteMode {
+    STATE.read().unwrap().mode
+}
+
+/// 指定したモードの場合、カセットのディレクトリを返す
+fn dir_for(mode: CassetteMode) -> Option<PathBuf> {
+    let state = STATE.read().unwrap();
+    (state.mode == mode).then(|| state.dir.clone())
+}
+
+/// 記録にないリクエストのエラーメッセージ
+pub fn not_recorded(url: &str) -> String {
+    format!("Not recorded in cassette: {}", url)
+}
+
+/// 記録した応答
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Recording {
+    method: String,
+    /// リクエストのURL
+    url: String,
+    /// 応答のURL（リダイレクトされた場合は最終的なURL）
+    response_url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// 本文（Base64。圧縮は展開済み）
+    body: String,
+}
+
+/// リクエストの記録のパス
+fn recording_path(dir: &Path, method: &str, url: &str) -> PathBuf {
+    let digest = format!(
+        "{:x}",
+        Sha256::digest(format!("{} {}", method, url).as_bytes())
+    );
+    dir.join(format!("{}.json", &digest[..16]))
+}
+
+/// 再生モードの場合、記録した応答を返す（再生モードでなければ `None`）
+pub fn replay(request: &reqwest::Request) -> Option<Result<reqwest::Response, String>> {
+    let dir = dir_for(CassetteMode::Replay)?;
+    Some(load(&dir, request.method().as_str(), request.url()))
+}
+
+/// 記録した応答を読み込む
+fn load(dir: &Path, method: &str, url: &reqwest::Url) -> Result<reqwest::Response, String> {
+    let path = recording_path(dir, method, url.as_str());
+    let text = std::fs::read_to_string(&path).map_err(|_| not_recorded(url.as_str()))?;
+    let invalid = |e: &dyn std::fmt::Display| format!("Invalid cassette {}: {}", path.display(), e);
+    let recording: Recording = serde_json::from_str(&text).map_err(|e| invalid(&e))?;
+    let body = base64::engine::general_purpose::STANDARD
+        .decode(&recording.body)
+        .map_err(|e| invalid(&e))?;
+    let response_url = reqwest::Url::parse(&recording.response_url).map_err(|e| invalid(&e))?;
+
+    let mut builder = http::Response::builder()
+        .status(recording.status)
+        .url(response_url);
+    for (name, value) in &recording.headers {
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(body)
+        .map(reqwest::Response::from)
+        .map_err(|e| invalid(&e))
+}
+
+/// 記録モードの場合、応答を記録して同じ内容の応答を返す
+///
+/// 記録のため本文を読み込んでから返す。記録に失敗しても応答はそのまま返す。
+pub async fn record(
+    method: &str,
+    url: &reqwest::Url,
+    response: reqwest::Response,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let Some(dir) = dir_for(CassetteMode::Record) else {
+        return Ok(response);
+    };
+    let (status, response_url, headers) = (
+        response.status(),
+        response.url().clone(),
+        response.headers().clone(),
+    );
+    let body = response.bytes().await?;
+
+    let recording = Recording {
+        method: method.to_string(),
+        url: url.to_string(),
+        response_url: response_url.to_string(),
+        status: status.as_u16(),
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+        body: base64::engine::general_purpose::STANDARD.encode(&body),
+    };
+    if let Err(e) = save(&dir, &recording) {
+        tracing::warn!(error = %e, url = %url, "failed to record response");
+    }
+
+    let mut builder = http::Response::builder().status(status).url(response_url);
+    if let Some(map) = builder.headers_mut() {
+        *map = headers;
+    }
+    Ok(reqwest::Response::from(
+        builder.body(body).expect("parts of a received response"),
+    ))
+}
+
+/// 応答の記録を保存
+fn save(dir: &Path, recording: &Recording) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let json = serde_json::to_string_pretty(recording).map_err(|e| e.to_string())?;
+    std::fs::write(recording_path(dir, &recording.method, &recording.url), json)
+        .map_err(|e| format!("Failed to write cassette: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_save_and_load() {
+        let temp = tempfile::tempdir().unwrap();
+        let url = reqwest::Url::parse("https://example.com/ies?id=1").unwrap();
+        let recording = Recording {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            response_url: "https://example.com/ies/1.ies".to_string(),
+            status: 200,
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            body: base64::engine::general_purpose::STANDARD.encode("IESNA:LM-63-2002"),
+        };
+        save(temp.path(), &recording).unwrap();
+
+        let response = load(temp.path(), "GET", &url).unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.url().as_str(), "https://example.com/ies/1.ies");
+        assert_eq!(response.headers()["content-type"], "text/plain");
+        assert_eq!(block_on(response.text()).unwrap(), "IESNA:LM-63-2002");
+
+        // 記録にないリクエスト（メソッド違いを含む）は通信せずにエラーにする
+        let error = load(temp.path(), "HEAD", &url).unwrap_err();
+        assert_eq!(error, not_recorded(url.as_str()));
+    }
+}
@@ -0,0 +1,185 @@
+//! 中断した一括ダウンロードの再開
+//!
+//! モバイルではバックグラウンドに移ったアプリがOSに終了されることがあり、デスクトップでも
+//! クラッシュ等で一括ダウンロードが途中で止まることがある。実行中のバッチのリクエストと
+//! 完了したアイテムをストアに記録しておき、次回の起動時に残りのアイテムだけを再実行できるようにする。
+//! 複数のバッチを同時に実行できるため、チェックポイントはバッチIDごとに記録する。
+//! 途中で止まったアイテムの一時ファイル（ZIPの展開途中等）は再実行時に上書きされる。
+
+use crate::commands::BatchDownloadItem;
+use crate::portable::{self, STORE_NAME};
+use crate::providers::AssetType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// チェックポイント（バッチIDをキーとするマップ）を保存するキー
+const CHECKPOINT_KEY: &str = "interruptedBatches";
+
+/// 読み込みから保存までの間に他のアイテム・バッチの記録を上書きしないためのロック
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// 実行中（または中断した）バッチのチェックポイント
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedBatch {
+    /// バッチID
+    pub batch_id: String,
+    pub items: Vec<BatchDownloadItem>,
+    /// アイテムごとの指定がない場合に取得するアセット種別
+    pub asset_types: Vec<AssetType>,
+    /// 保存先ディレクトリ（解決済みのパス）
+    pub dest_dir: String,
+    pub project_id: Option<String>,
+    /// 完了（成功・失敗）したアイテムのSpec No.
+    pub completed: Vec<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl InterruptedBatch {
+    pub fn new(
+        batch_id: &str,
+        items: &[BatchDownloadItem],
+        asset_types: &[AssetType],
+        dest_dir: &str,
+        project_id: Option<&str>,
+    ) -> Self {
+        Self {
+            batch_id: batch_id.to_string(),
+            items: items.to_vec(),
+            asset_types: asset_types.to_vec(),
+            dest_dir: dest_dir.to_string(),
+            project_id: project_id.map(str::to_string),
+            completed: Vec::new(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// 未完了のアイテム（リクエストの順序）
+    pub fn remaining_items(&self) -> Vec<BatchDownloadItem> {
+        self.items
+            .iter()
+            .filter(|item| !self.completed.contains(&item.spec_no))
+            .cloned()
+            .collect()
+    }
+}
+
+/// すべてのチェックポイントを読み込む（ない場合・読み込めない場合は空）
+fn load_all<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, InterruptedBatch> {
+    app.store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(CHECKPOINT_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// すべてのチェックポイントを保存
+fn save_all<R: Runtime>(
+    app: &AppHandle<R>,
+    checkpoints: &BTreeMap<String, InterruptedBatch>,
+) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        CHECKPOINT_KEY,
+        serde_json::to_value(checkpoints).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save checkpoint: {}", e))
+}
+
+/// 中断したバッチの一覧（開始日時の順）
+pub fn list<R: Runtime>(app: &AppHandle<R>) -> Vec<InterruptedBatch> {
+    let mut checkpoints: Vec<_> = load_all(app).into_values().collect();
+    checkpoints.sort_by_key(|checkpoint| checkpoint.started_at);
+    checkpoints
+}
+
+/// バッチのチェックポイントを読み込む（ない場合は None）
+pub fn load<R: Runtime>(app: &AppHandle<R>, batch_id: &str) -> Option<InterruptedBatch> {
+    load_all(app).remove(batch_id)
+}
+
+/// チェックポイントを保存（同じバッチIDのものは置き換える）
+pub fn save<R: Runtime>(app: &AppHandle<R>, checkpoint: &InterruptedBatch) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut checkpoints = load_all(app);
+    checkpoints.insert(checkpoint.batch_id.clone(), checkpoint.clone());
+    save_all(app, &checkpoints)
+}
+
+/// アイテムの完了を記録
+pub fn mark_completed<R: Runtime>(
+    app: &AppHandle<R>,
+    batch_id: &str,
+    spec_no: &str,
+) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut checkpoints = load_all(app);
+    let Some(checkpoint) = checkpoints.get_mut(batch_id) else {
+        return Ok(());
+    };
+    if !checkpoint.completed.iter().any(|s| s == spec_no) {
+        checkpoint.completed.push(spec_no.to_string());
+    }
+    save_all(app, &checkpoints)
+}
+
+/// チェックポイントを削除（バッチが最後まで実行された場合・再開しない場合）
+pub fn clear<R: Runtime>(app: &AppHandle<R>, batch_id: &str) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut checkpoints = load_all(app);
+    if checkpoints.remove(batch_id).is_some() {
+        save_all(app, &checkpoints)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(spec_no: &str) -> BatchDownloadItem {
+        BatchDownloadItem {
+            spec_no: spec_no.to_string(),
+            manufacturer: "KOIZUMI".to_string(),
+            model_number: format!("AD{}", spec_no),
+            psu: None,
+            asset_types: None,
+            quantity: None,
+            area: None,
+            wattage: None,
+            provider: None,
+            source_url: None,
+        }
+    }
+
+    #[test]
+    fn test_remaining_items() {
+        let mut checkpoint = InterruptedBatch::new(
+            "p1",
+            &[item("A01"), item("A02"), item("A03")],
+            &[AssetType::Ies],
+            "/tmp/ies",
+            Some("p1"),
+        );
+        checkpoint.completed.push("A02".to_string());
+        let remaining: Vec<_> = checkpoint
+            .remaining_items()
+            .into_iter()
+            .map(|item| item.spec_no)
+            .collect();
+        assert_eq!(remaining, vec!["A01", "A03"]);
+
+        let json = serde_json::to_value(&checkpoint).unwrap();
+        assert_eq!(json["batchId"], "p1");
+        assert_eq!(json["destDir"], "/tmp/ies");
+        assert_eq!(json["completed"][0], "A02");
+    }
+}
@@ -0,0 +1,316 @@
+//! コマンドラインでの一括ダウンロード（`autosight-cli`）
+//!
+//! GUIを起動せずに、器具リストのExcel・CSVからIESファイル等を一括ダウンロードする。
+//! ビルドサーバーでの定型作業等に使用する。プロバイダー・ファイル名テンプレート・
+//! 保存先ルールはアプリと共通の実装を使う（アプリの設定・履歴は読み書きしない）。
+//!
+//! 使い方:
+//! ```text
+//! autosight-cli download --excel schedule.xlsx --out ./ies
+//!     [--assets ies,specSheet] [--template "{spec_no}_{model}"] [--sheet "Fixture Base"]
+//!     [--no-overwrite] [--locale ja|en]
+//! autosight-cli job job.json
+//! ```
+//!
+//! `job` はジョブファイル（JSON）に定義された一括ダウンロードを実行する。
+//!
+//! 終了コード: 0 = すべて成功、1 = 失敗したアイテムがある、2 = 引数・入力ファイルの誤り
+
+use crate::commands::{self, AssetDownloadResult, BatchDownloadItem};
+use crate::excel::{self, ImportProfile};
+use crate::filename::{self, FilenameOptions};
+use crate::i18n::Locale;
+use crate::job;
+use crate::longpath;
+use crate::providers::{client_builder, AssetType, CancelToken, ProviderRegistry};
+use crate::settings::DestinationSettings;
+
+/// 使い方の表示
+const USAGE: &str = "Usage:
+  autosight-cli download --excel <schedule.xlsx|.csv> --out <dir> [options]
+  autosight-cli job <job.json>
+
+Options:
+  --assets <types>     Comma-separated asset types (default: ies)
+                       e.g. ies,photometricReport,specSheet,cad
+  --template <tmpl>    Filename template (default: provider naming rule)
+  --sheet <name>       Sheet name (default: Fixture Base)
+  --no-overwrite       Keep existing files with the same name
+  --locale <ja|en>     Language of import warnings (default: ja)";
+
+/// `download` サブコマンドの引数
+#[derive(Debug, PartialEq)]
+struct DownloadArgs {
+    excel: String,
+    out: String,
+    assets: Vec<AssetType>,
+    template: Option<String>,
+    sheet: Option<String>,
+    overwrite: bool,
+    locale: Locale,
+}
+
+fn parse_download_args(args: &[String]) -> Result<DownloadArgs, String> {
+    let mut excel = None;
+    let mut out = None;
+    let mut assets = vec![AssetType::Ies];
+    let mut template = None;
+    let mut sheet = None;
+    let mut overwrite = true;
+    let mut locale = Locale::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--excel" => excel = Some(value()?),
+            "--out" => out = Some(value()?),
+            "--assets" => {
+                assets = value()?
+                    .split(',')
+                    .map(|name| parse_enum::<AssetType>(name.trim(), "asset type"))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--template" => {
+                let value = value()?;
+                crate::filename::validate(&value)?;
+                template = Some(value);
+            }
+            "--sheet" => sheet = Some(value()?),
+            "--no-overwrite" => overwrite = false,
+            "--locale" => locale = parse_enum(&value()?, "locale")?,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    Ok(DownloadArgs {
+        excel: excel.ok_or("--excel is required")?,
+        out: out.ok_or("--out is required")?,
+        assets,
+        template,
+        sheet,
+        overwrite,
+        locale,
+    })
+}
+
+/// serde の名前（`ies`・`specSheet`・`en` 等）から列挙値を取得
+fn parse_enum<T: serde::de::DeserializeOwned>(name: &str, kind: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("Unknown {}: {}", kind, name))
+}
+
+/// コマンドラインを実行し、終了コードを返す
+pub fn run(args: Vec<String>) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("download") => match parse_download_args(&args[1..]) {
+            Ok(args) => tauri::async_runtime::block_on(download(args)),
+            Err(e) => {
+                eprintln!("error: {}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        Some("job") => match &args[1..] {
+            [path] => tauri::async_runtime::block_on(run_job(path)),
+            _ => {
+                eprintln!("error: job requires exactly one job file\n\n{}", USAGE);
+                2
+            }
+        },
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            0
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    }
+}
+
+/// 器具リストの全行をダウンロード
+async fn download(args: DownloadArgs) -> i32 {
+    let registry = ProviderRegistry::new();
+    let profile = ImportProfile {
+        sheet_name: args.sheet.clone(),
+        ..ImportProfile::default()
+    };
+    let import = match excel::import(&args.excel, &profile, args.locale, |manufacturer| {
+        registry.get_provider(manufacturer).is_some()
+    }) {
+        Ok(import) => import,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return 2;
+        }
+    };
+    for warning in &import.warnings {
+        eprintln!("warning: {}", warning.message);
+    }
+    if let Err(e) = std::fs::create_dir_all(longpath::extended(filename::dir_prefix(&args.out))) {
+        eprintln!("error: Failed to create directory: {}", e);
+        return 2;
+    }
+
+    let destination = DestinationSettings {
+        overwrite_existing: args.overwrite,
+        ..DestinationSettings::default()
+    };
+    let filename_options = FilenameOptions {
+        template: args.template.clone(),
+        halfwidth_alphanumerics: false,
+        reserved: None,
+    };
+    let client = match client_builder().build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("error: Failed to create HTTP client: {}", e);
+            return 2;
+        }
+    };
+
+    let mut success_count = 0;
+    let mut failure_count = 0;
+    registry.begin_batch();
+    for row in import.rows {
+        let item = BatchDownloadItem {
+            spec_no: row.spec_no,
+            manufacturer: row.manufacturer,
+            model_number: row.fixture,
+            psu: row.psu,
+            asset_types: None,
+            quantity: row.quantity,
+            area: None,
+            wattage: row.wattage,
+            provider: None,
+            source_url: None,
+        };
+        let providers = registry.get_providers_for(&item.manufacturer, None);
+        let assets = commands::download_item_assets(
+            &providers,
+            &item,
+            &args.assets,
+            &args.out,
+            &destination,
+            &filename_options,
+            &client,
+            CancelToken::new(),
+        )
+        .await;
+
+        print_assets(&item.spec_no, &assets);
+        if !assets.is_empty() && assets.iter().all(|asset| asset.result.success) {
+            success_count += 1;
+        } else {
+            failure_count += 1;
+        }
+    }
+    registry.end_batch();
+
+    eprintln!("{} succeeded, {} failed", success_count, failure_count);
+    if failure_count > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// ジョブファイルを実行
+async fn run_job(path: &str) -> i32 {
+    let job = match job::load(std::path::Path::new(path)) {
+        Ok(job) => job,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return 2;
+        }
+    };
+    let registry = ProviderRegistry::new();
+    let result = job::run(&job, &registry, |_, result| {
+        print_assets(&result.spec_no, &result.assets)
+    })
+    .await;
+    match result {
+        Ok(result) => {
+            eprintln!(
+                "{} succeeded, {} failed",
+                result.success_count, result.failure_count
+            );
+            if result.failure_count > 0 {
+                1
+            } else {
+                0
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            2
+        }
+    }
+}
+
+/// アセットごとの結果を1行ずつ出力（`OK`/`NG`・Spec No.・アセット種別・パスまたはエラー）
+fn print_assets(spec_no: &str, assets: &[AssetDownloadResult]) {
+    for asset in assets {
+        let result = &asset.result;
+        if result.success {
+            println!(
+                "OK\t{}\t{:?}\t{}",
+                spec_no,
+                asset.asset_type,
+                result.file_path.as_deref().unwrap_or_default()
+            );
+        } else {
+            println!(
+                "NG\t{}\t{:?}\t{}",
+                spec_no,
+                asset.asset_type,
+                result.error.as_deref().unwrap_or_default()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_download_args() {
+        let parsed = parse_download_args(&args(&[
+            "--excel",
+            "schedule.xlsx",
+            "--out",
+            "./ies",
+            "--assets",
+            "ies,specSheet",
+            "--no-overwrite",
+            "--locale",
+            "en",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.excel, "schedule.xlsx");
+        assert_eq!(parsed.out, "./ies");
+        assert_eq!(parsed.assets, vec![AssetType::Ies, AssetType::SpecSheet]);
+        assert!(!parsed.overwrite);
+        assert_eq!(parsed.locale, Locale::En);
+
+        // 既定値
+        let parsed = parse_download_args(&args(&["--excel", "a.csv", "--out", "out"])).unwrap();
+        assert_eq!(parsed.assets, vec![AssetType::Ies]);
+        assert!(parsed.overwrite);
+
+        assert!(parse_download_args(&args(&["--excel", "a.xlsx"])).is_err());
+        assert!(parse_download_args(&args(&["--excel"])).is_err());
+        assert!(parse_download_args(&args(&["--assets", "pdf"])).is_err());
+        assert!(parse_download_args(&args(&["--template", "{unknown}"])).is_err());
+        assert!(parse_download_args(&args(&["--verbose"])).is_err());
+    }
+}
@@ -0,0 +1,643 @@
+//! クラウドストレージ（Dropbox・Google Drive）へのアップロード（オプトイン）
+//!
+//! 成果物はクライアントとクラウドストレージで共有するため、一括ダウンロードの完了後に
+//! 保存先ディレクトリ（設定によりZIPにまとめたもの）を設定したフォルダにアップロードする。
+//! 認証は事前に取得したOAuthトークンを使用し、期限切れの場合はリフレッシュトークンで更新する。
+//! トークン（クライアントシークレットを含む）は設定とは別にOSのキーチェーンに保存する
+//! （[`crate::secrets`]）。ファイルはメモリに読み込まず、ストリームでアップロードする。
+//!
+//! - Dropbox: `{フォルダ}/{保存先ディレクトリ名}/...` に上書きでアップロードする
+//! - Google Drive: フォルダID配下に保存先ディレクトリと同じ構成のフォルダを作成してアップロードする
+//!   （同名のファイルがあっても上書きせず、別のファイルとして追加される）
+
+use crate::longpath;
+use crate::providers::{run_blocking, send_request, RequestError};
+use crate::secrets;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// トークンを保存するキー（キーチェーンのアカウント名。以前のバージョンはストアファイルのキー）
+const TOKENS_KEY: &str = "cloudTokens";
+
+const DROPBOX_TOKEN_URL: &str = "https://api.dropboxapi.com/oauth2/token";
+const DROPBOX_CONTENT_URL: &str = "https://content.dropboxapi.com/2/files";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const DRIVE_FILES_URL: &str = "https://www.googleapis.com/drive/v3/files";
+const DRIVE_UPLOAD_URL: &str =
+    "https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields=id";
+const DRIVE_FOLDER_MIME: &str = "application/vnd.google-apps.folder";
+
+/// Dropboxで1回のリクエストでアップロードできる上限（超える場合はセッションで分割する）
+const DROPBOX_SINGLE_UPLOAD_BYTES: u64 = 150 * 1024 * 1024;
+/// Dropboxのセッションで1回に送る大きさ
+const DROPBOX_CHUNK_BYTES: u64 = 64 * 1024 * 1024;
+/// 有効期限のこの秒数前からトークンを更新する
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// アップロード先のサービス
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloudProvider {
+    Dropbox,
+    GoogleDrive,
+}
+
+/// クラウドへのアップロードの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CloudUploadSettings {
+    /// 一括ダウンロードの完了後にアップロードする（既定は無効）
+    pub enabled: bool,
+    /// アップロード先のサービス
+    pub provider: CloudProvider,
+    /// アップロード先のフォルダ（Dropboxは `/` から始まるパス、Google DriveはフォルダID）
+    pub folder: String,
+    /// 保存先ディレクトリをZIPにまとめてアップロードする
+    pub upload_zip: bool,
+}
+
+impl Default for CloudUploadSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: CloudProvider::Dropbox,
+            folder: String::new(),
+            upload_zip: false,
+        }
+    }
+}
+
+impl CloudUploadSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let folder = self.folder.trim();
+        if folder.is_empty() {
+            return Err("cloudUpload.folder must not be empty".to_string());
+        }
+        if self.provider == CloudProvider::Dropbox && !folder.starts_with('/') {
+            return Err("cloudUpload.folder must start with '/' for Dropbox".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// OAuthトークン
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudToken {
+    pub access_token: String,
+    /// リフレッシュトークン（ある場合は期限切れ時に更新する）
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// 更新に使用するクライアントID・シークレット
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// アクセストークンの有効期限
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CloudToken {
+    fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        self.refresh_token.is_some()
+            && self
+                .expires_at
+                .is_some_and(|at| at - Duration::seconds(REFRESH_MARGIN_SECS) <= now)
+    }
+}
+
+/// アップロード結果（`cloud-upload-finished` イベントのペイロードを兼ねる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudUploadResult {
+    pub provider: CloudProvider,
+    /// アップロードしたディレクトリ
+    pub source: String,
+    pub uploaded_count: usize,
+    pub failure_count: usize,
+    /// 失敗したファイルとエラー
+    pub errors: Vec<String>,
+}
+
+fn load_tokens<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<BTreeMap<CloudProvider, CloudToken>, String> {
+    match secrets::get_or_migrate(app, TOKENS_KEY)? {
+        Some(value) => {
+            serde_json::from_str(&value).map_err(|e| format!("Failed to parse cloud tokens: {}", e))
+        }
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+fn save_tokens(tokens: &BTreeMap<CloudProvider, CloudToken>) -> Result<(), String> {
+    if tokens.is_empty() {
+        return secrets::delete(TOKENS_KEY);
+    }
+    secrets::set(
+        TOKENS_KEY,
+        &serde_json::to_string(tokens).map_err(|e| e.to_string())?,
+    )
+}
+
+/// トークンを保存（`None` の場合は削除）
+pub fn set_token<R: Runtime>(
+    app: &AppHandle<R>,
+    provider: CloudProvider,
+    token: Option<CloudToken>,
+) -> Result<(), String> {
+    let mut tokens = load_tokens(app)?;
+    match token {
+        Some(token) if token.access_token.trim().is_empty() => {
+            return Err("accessToken must not be empty".to_string())
+        }
+        Some(token) => tokens.insert(provider, token),
+        None => tokens.remove(&provider),
+    };
+    save_tokens(&tokens)
+}
+
+/// トークンを保存しているサービス
+pub fn connected_providers<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<CloudProvider>, String> {
+    Ok(load_tokens(app)?.into_keys().collect())
+}
+
+/// 有効なアクセストークンを取得（期限切れの場合は更新して保存）
+async fn access_token<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    provider: CloudProvider,
+) -> Result<String, String> {
+    let handle = app.clone();
+    let mut tokens = run_blocking(move || load_tokens(&handle)).await?;
+    let token = tokens
+        .get(&provider)
+        .cloned()
+        .ok_or_else(|| format!("No cloud token for {:?}", provider))?;
+    if !token.needs_refresh(Utc::now()) {
+        return Ok(token.access_token);
+    }
+
+    let url = match provider {
+        CloudProvider::Dropbox => DROPBOX_TOKEN_URL,
+        CloudProvider::GoogleDrive => GOOGLE_TOKEN_URL,
+    };
+    let mut form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        (
+            "refresh_token",
+            token.refresh_token.clone().unwrap_or_default(),
+        ),
+    ];
+    if let Some(id) = &token.client_id {
+        form.push(("client_id", id.clone()));
+    }
+    if let Some(secret) = &token.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    let response = send_request(client.post(url).form(&form))
+        .await
+        .map_err(|e| format!("Token refresh request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Token refresh returned status: {}",
+            response.status()
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read token response: {}", e))?;
+    let access_token = body["access_token"]
+        .as_str()
+        .ok_or("Token response has no access_token")?
+        .to_string();
+    let refreshed = CloudToken {
+        access_token: access_token.clone(),
+        expires_at: body["expires_in"]
+            .as_i64()
+            .map(|secs| Utc::now() + Duration::seconds(secs)),
+        ..token
+    };
+    tokens.insert(provider, refreshed);
+    run_blocking(move || save_tokens(&tokens)).await?;
+    Ok(access_token)
+}
+
+/// ディレクトリ配下のファイル（ディレクトリからの相対パス、`/` 区切り）
+fn collect_files(dir: &Path) -> Vec<(PathBuf, String)> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, String)>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                walk(root, &path, files);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                files.push((path, relative));
+            }
+        }
+    }
+    let mut files = Vec::new();
+    walk(dir, dir, &mut files);
+    files
+}
+
+/// ディレクトリをZIPにまとめる（ZIP内のパスはディレクトリ名から始める）
+pub(crate) fn zip_dir(dir: &Path, dest: &Path) -> Result<(), String> {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file = std::fs::File::create(dest).map_err(|e| format!("Failed to create ZIP: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    for (path, relative) in collect_files(dir) {
+        let contents = std::fs::read(longpath::extended(&path))
+            .map_err(|e| format!("Failed to read {}: {}", relative, e))?;
+        zip.start_file(format!("{}/{}", name, relative), options)
+            .map_err(|e| format!("Failed to add {} to ZIP: {}", relative, e))?;
+        zip.write_all(&contents)
+            .map_err(|e| format!("Failed to write {} to ZIP: {}", relative, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish ZIP: {}", e))?;
+    Ok(())
+}
+
+/// Dropbox-API-Arg ヘッダー用のJSON（ヘッダーに入れるため非ASCII文字は `\uXXXX` にする）
+fn dropbox_api_arg(value: &Value) -> String {
+    let mut out = String::new();
+    for c in value.to_string().chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                out.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    out
+}
+
+async fn check_status(response: reqwest::Response, action: &str) -> Result<Value, String> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("{} returned status: {} {}", action, status, body));
+    }
+    Ok(response.json().await.unwrap_or(Value::Null))
+}
+
+/// ファイルの `offset` から `len` バイトを送るリクエストボディ（ファイル全体をメモリに読み込まない）
+async fn file_body(file: &Path, offset: u64, len: u64) -> Result<reqwest::Body, String> {
+    let mut file = tokio::fs::File::open(longpath::extended(file))
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(reqwest::Body::wrap_stream(ReaderStream::new(
+        file.take(len),
+    )))
+}
+
+/// Dropboxにファイルをアップロード（上書き）
+async fn upload_dropbox(
+    client: &reqwest::Client,
+    token: &str,
+    path: &str,
+    file: &Path,
+    size: u64,
+) -> Result<(), String> {
+    let commit = json!({ "path": path, "mode": "overwrite", "autorename": false, "mute": true });
+    let post = |endpoint: &str, arg: Value, body: reqwest::Body, len: u64| {
+        client
+            .post(format!("{}/{}", DROPBOX_CONTENT_URL, endpoint))
+            .bearer_auth(token)
+            .header("Dropbox-API-Arg", dropbox_api_arg(&arg))
+            .header("Content-Type", "application/octet-stream")
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(body)
+    };
+    let request_failed = |e: RequestError| format!("Dropbox upload request failed: {}", e);
+
+    if size <= DROPBOX_SINGLE_UPLOAD_BYTES {
+        let body = file_body(file, 0, size).await?;
+        let response = send_request(post("upload", commit, body, size))
+            .await
+            .map_err(request_failed)?;
+        return check_status(response, "Dropbox upload").await.map(|_| ());
+    }
+
+    // 大きなファイルはセッションで分割して送る
+    let first = DROPBOX_CHUNK_BYTES.min(size);
+    let body = file_body(file, 0, first).await?;
+    let response = send_request(post("upload_session/start", json!({}), body, first))
+        .await
+        .map_err(request_failed)?;
+    let session_id = check_status(response, "Dropbox upload").await?["session_id"]
+        .as_str()
+        .ok_or("Dropbox upload session has no session_id")?
+        .to_string();
+    let mut offset = first;
+    while offset < size {
+        let len = DROPBOX_CHUNK_BYTES.min(size - offset);
+        let cursor = json!({ "cursor": { "session_id": session_id, "offset": offset } });
+        let body = file_body(file, offset, len).await?;
+        let response = send_request(post("upload_session/append_v2", cursor, body, len))
+            .await
+            .map_err(request_failed)?;
+        check_status(response, "Dropbox upload").await?;
+        offset += len;
+    }
+    let finish = json!({
+        "cursor": { "session_id": session_id, "offset": offset },
+        "commit": commit,
+    });
+    let response = send_request(post(
+        "upload_session/finish",
+        finish,
+        reqwest::Body::from(Vec::new()),
+        0,
+    ))
+    .await
+    .map_err(request_failed)?;
+    check_status(response, "Dropbox upload").await.map(|_| ())
+}
+
+/// Google Driveのフォルダを取得（ない場合は作成）し、IDを返す
+async fn drive_folder(
+    client: &reqwest::Client,
+    token: &str,
+    parent: &str,
+    name: &str,
+) -> Result<String, String> {
+    let escape = |value: &str| value.replace('\\', "\\\\").replace('\'', "\\'");
+    let query = format!(
+        "name = '{}' and '{}' in parents and mimeType = '{}' and trashed = false",
+        escape(name),
+        escape(parent),
+        DRIVE_FOLDER_MIME
+    );
+    let response = send_request(
+        client
+            .get(DRIVE_FILES_URL)
+            .bearer_auth(token)
+            .query(&[("q", query.as_str()), ("fields", "files(id)")]),
+    )
+    .await
+    .map_err(|e| format!("Google Drive request failed: {}", e))?;
+    let found = check_status(response, "Google Drive folder lookup").await?;
+    if let Some(id) = found["files"][0]["id"].as_str() {
+        return Ok(id.to_string());
+    }
+
+    let metadata = json!({ "name": name, "mimeType": DRIVE_FOLDER_MIME, "parents": [parent] });
+    let response = send_request(
+        client
+            .post(format!("{}?fields=id", DRIVE_FILES_URL))
+            .bearer_auth(token)
+            .json(&metadata),
+    )
+    .await
+    .map_err(|e| format!("Google Drive request failed: {}", e))?;
+    check_status(response, "Google Drive folder creation").await?["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Google Drive folder creation returned no id".to_string())
+}
+
+/// Google Driveにファイルをアップロード（再開可能なアップロードで1回で送る）
+async fn upload_drive(
+    client: &reqwest::Client,
+    token: &str,
+    parent: &str,
+    name: &str,
+    file: &Path,
+    size: u64,
+) -> Result<(), String> {
+    let metadata = json!({ "name": name, "parents": [parent] });
+    let response = send_request(
+        client
+            .post(DRIVE_UPLOAD_URL)
+            .bearer_auth(token)
+            .json(&metadata),
+    )
+    .await
+    .map_err(|e| format!("Google Drive upload request failed: {}", e))?;
+    let session = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    check_status(response, "Google Drive upload").await?;
+    let session = session.ok_or("Google Drive upload returned no session URL")?;
+
+    let body = file_body(file, 0, size).await?;
+    let response = send_request(
+        client
+            .put(session)
+            .bearer_auth(token)
+            .header(reqwest::header::CONTENT_LENGTH, size)
+            .body(body),
+    )
+    .await
+    .map_err(|e| format!("Google Drive upload request failed: {}", e))?;
+    check_status(response, "Google Drive upload")
+        .await
+        .map(|_| ())
+}
+
+/// アップロード先のパス・フォルダ
+enum Destination {
+    /// Dropboxのパス
+    Dropbox(String),
+    /// Google DriveのフォルダID（作成済みのサブフォルダのIDをキャッシュする）
+    Drive {
+        root: String,
+        folders: HashMap<String, String>,
+    },
+}
+
+impl Destination {
+    async fn upload(
+        &mut self,
+        client: &reqwest::Client,
+        token: &str,
+        relative: &str,
+        file: &Path,
+    ) -> Result<(), String> {
+        let size = tokio::fs::metadata(longpath::extended(file))
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?
+            .len();
+        match self {
+            Destination::Dropbox(folder) => {
+                let path = format!("{}/{}", folder.trim_end_matches('/'), relative);
+                upload_dropbox(client, token, &path, file, size).await
+            }
+            Destination::Drive { root, folders } => {
+                let (dir, name) = relative.rsplit_once('/').unwrap_or(("", relative));
+                // 親フォルダを上から順に作成
+                let mut parent = root.clone();
+                let mut current = String::new();
+                for segment in dir.split('/').filter(|s| !s.is_empty()) {
+                    if !current.is_empty() {
+                        current.push('/');
+                    }
+                    current.push_str(segment);
+                    parent = match folders.get(&current) {
+                        Some(id) => id.clone(),
+                        None => {
+                            let id = drive_folder(client, token, &parent, segment).await?;
+                            folders.insert(current.clone(), id.clone());
+                            id
+                        }
+                    };
+                }
+                upload_drive(client, token, &parent, name, file, size).await
+            }
+        }
+    }
+}
+
+/// ディレクトリをアップロード
+///
+/// アップロード先には保存先ディレクトリ名のフォルダ（ZIPの場合は `{ディレクトリ名}.zip`）を作成する。
+/// ファイル単位の失敗は結果に記録し、残りのファイルのアップロードを続ける。
+pub async fn upload_dir<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    settings: &CloudUploadSettings,
+    dir: &Path,
+) -> Result<CloudUploadResult, String> {
+    settings.validate()?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+    let token = access_token(app, client, settings.provider).await?;
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "autosight".to_string());
+
+    // ZIPは一時ディレクトリに作成（アップロード後に削除される）
+    let temp = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let files = if settings.upload_zip {
+        let zip_path = temp.path().join(format!("{}.zip", name));
+        let (source, dest) = (dir.to_path_buf(), zip_path.clone());
+        run_blocking(move || zip_dir(&source, &dest)).await?;
+        vec![(zip_path, format!("{}.zip", name))]
+    } else {
+        collect_files(dir)
+            .into_iter()
+            .map(|(path, relative)| (path, format!("{}/{}", name, relative)))
+            .collect()
+    };
+
+    let folder = settings.folder.trim().to_string();
+    let mut destination = match settings.provider {
+        CloudProvider::Dropbox => Destination::Dropbox(folder),
+        CloudProvider::GoogleDrive => Destination::Drive {
+            root: folder,
+            folders: HashMap::new(),
+        },
+    };
+    let mut result = CloudUploadResult {
+        provider: settings.provider,
+        source: dir.to_string_lossy().into_owned(),
+        uploaded_count: 0,
+        failure_count: 0,
+        errors: Vec::new(),
+    };
+    for (path, relative) in files {
+        let uploaded = destination.upload(client, &token, &relative, &path).await;
+        match uploaded {
+            Ok(()) => result.uploaded_count += 1,
+            Err(e) => {
+                tracing::warn!(file = %relative, error = %e, "cloud upload failed");
+                result.failure_count += 1;
+                result.errors.push(format!("{}: {}", relative, e));
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropbox_api_arg() {
+        let arg = json!({ "path": "/案件/A01.ies" });
+        assert_eq!(dropbox_api_arg(&arg), r#"{"path":"/\u6848\u4ef6/A01.ies"}"#);
+        // BMP外の文字はサロゲートペア
+        assert_eq!(dropbox_api_arg(&json!("😀")), r#""\ud83d\ude00""#);
+    }
+
+    #[test]
+    fn test_collect_and_zip() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("ies");
+        std::fs::create_dir_all(dir.join("BIM")).unwrap();
+        std::fs::write(dir.join("A01.ies"), "TILT=NONE").unwrap();
+        std::fs::write(dir.join("BIM/A01.rfa"), "rfa").unwrap();
+
+        let files: Vec<_> = collect_files(&dir).into_iter().map(|(_, r)| r).collect();
+        assert_eq!(files, ["A01.ies", "BIM/A01.rfa"]);
+
+        let zip_path = temp.path().join("ies.zip");
+        zip_dir(&dir, &zip_path).unwrap();
+        let archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        let mut names: Vec<_> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, ["ies/A01.ies", "ies/BIM/A01.rfa"]);
+    }
+
+    #[test]
+    fn test_settings_and_token() {
+        let mut settings = CloudUploadSettings {
+            enabled: true,
+            folder: "Projects".to_string(),
+            ..CloudUploadSettings::default()
+        };
+        assert!(settings.validate().is_err());
+        settings.folder = "/Projects".to_string();
+        assert!(settings.validate().is_ok());
+        settings.provider = CloudProvider::GoogleDrive;
+        settings.folder = "1AbCdEf".to_string();
+        assert!(settings.validate().is_ok());
+
+        let now = Utc::now();
+        let mut token = CloudToken {
+            access_token: "a".to_string(),
+            refresh_token: Some("r".to_string()),
+            client_id: None,
+            client_secret: None,
+            expires_at: Some(now + Duration::seconds(30)),
+        };
+        assert!(token.needs_refresh(now));
+        token.expires_at = Some(now + Duration::hours(1));
+        assert!(!token.needs_refresh(now));
+        token.refresh_token = None;
+        token.expires_at = Some(now);
+        assert!(!token.needs_refresh(now));
+    }
+}
@@ -2,22 +2,260 @@
 //!
 //! フロントエンド（React）から呼び出すためのコマンドを定義する。
 
-use crate::providers::{DownloadResult, ProductInfo, ProviderRegistry};
+use crate::api_server;
+use crate::audit::{self, AuditContext, AuditLog, AuditRecord};
+use crate::batch::{
+    process_in_order, Batch, BatchChannel, BatchEvent, BatchFinishedEvent, BatchState, BatchStatus,
+    DecisionRequiredEvent, StartPacer, DEFAULT_BATCH_ID,
+};
+use crate::cache::{self, CacheScope, CacheStats};
+use crate::cassette;
+use crate::checkpoint::{self, InterruptedBatch};
+use crate::cloud::{self, CloudProvider, CloudToken, CloudUploadResult};
+use crate::config_transfer;
+use crate::crash::{self, CrashReport};
+use crate::dedupe::DuplicateIndex;
+use crate::deeplink::{self, DeepLinkItem, DeepLinkState};
+use crate::diagnostics;
+use crate::direct::{self, UrlDownloadRequest, UrlDownloadResult};
+use crate::download_cache;
+use crate::error::{CommandError, CommandResult, ErrorCode, ProviderError, ProviderResult};
+use crate::error_reporting;
+use crate::excel::{self, ClipboardImportResult, ImportProfile, ImportResult, ImportedRow};
+use crate::filename::{self, FilenameContext, FilenameOptions};
+use crate::history::{self, HistoryEntry, HistoryPage, HistoryQuery};
+use crate::i18n::Message;
+use crate::ignore_list::{self, IgnoreKind, IgnoreList};
+use crate::job::{self, JobResult};
+use crate::library::{self, LibraryEntry};
+use crate::lighting_export::{self, ExportTarget, ProjectExportResult};
+use crate::logging::{LogLevel, LoggingState};
+use crate::longpath;
+use crate::manual_source::{self, ManualSources};
+use crate::manufacturer_alias::{self, ManufacturerAlias};
+use crate::network_share::{self, NetworkShareSettings};
+use crate::offline;
+use crate::photometry::{self, ConeDiagram, IesAnalysis, IesSummary, PhotometryFormat};
+use crate::portable::{self, PortableInfo};
+use crate::power::{self, PowerStatus};
+use crate::prefetch::{self, PrefetchState, PrefetchSummary};
+use crate::providers::{
+    apply_cassette_mode, apply_custom_providers, apply_domain_concurrency,
+    apply_domain_request_interval, apply_network_settings, apply_provider_config,
+    apply_request_timeout, apply_retry_settings, cancelled, client_builder,
+    generic::{GenericProvider, ProviderDefinition},
+    not_provided, report_phase, run_blocking, send_request, with_decision_resolver,
+    with_phase_notifier, with_zip_member, zip_member, AccessoryKind, AssetType, CancelToken,
+    CustomProvidersResult, DecisionResolver, Diagnosis, DiagnosisStatus, DownloadPhase,
+    DownloadResult, DownloadTiming, DownloadWarning, DownloadWarningKind, ManufacturerProvider,
+    PhaseNotifier, Price, ProductCandidate, ProductInfo, ProviderConfig, ProviderInfo,
+    ProviderRegistry, ResolvedIesUrl, SharedRegistry, ZipCandidate,
+};
+use crate::report::{self, ReportFormat};
+use crate::rules_update::{self, RulesStatus};
+use crate::schedule_report::{self, ConnectedLoad, CostItem, CostRollup, LoadItem, WattageCheck};
+use crate::session::{self, Session};
+use crate::settings::{self, DestinationSettings, ProxySettings, Settings, TlsSettings};
+use crate::storage::{self, StorageExportResult};
+use crate::succession;
+use crate::suggest::{self, SuggestField, Suggestion};
+use crate::telemetry::{self, ProviderCounts, UsageReport};
+use crate::thumbnail;
+#[cfg(desktop)]
+use crate::tray;
+use crate::writeback::{self, WritebackResult};
+use futures::{FutureExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use tokio::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_autosight_background::BackgroundExt;
+use tauri_plugin_autosight_share::{ShareExt, ShareFileRequest};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
+use tracing::Instrument;
 
-/// ダウンロード進捗イベントのペイロード
-#[derive(Debug, Clone, Serialize)]
+/// 製品情報一括取得の1行分の結果（イベントのペイロードを兼ねる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductInfoResult {
+    /// Spec No.（アイテム識別用）
+    pub spec_no: String,
+    /// 製品情報（取得成功時のみ）
+    pub info: Option<ProductInfo>,
+    /// エラーメッセージ（エラー時のみ）
+    pub error: Option<String>,
+    /// エラーコード（エラー時のみ）
+    pub code: Option<ErrorCode>,
+    /// 再試行で解決する可能性がある失敗か
+    pub retryable: bool,
+    /// キャッシュから返した結果か
+    pub cached: bool,
+}
+
+impl ProductInfoResult {
+    fn new(spec_no: String, fetched: ProviderResult<ProductInfo>, cached: bool) -> Self {
+        match fetched {
+            Ok(info) => Self {
+                spec_no,
+                info: Some(info),
+                error: None,
+                code: None,
+                retryable: false,
+                cached,
+            },
+            Err(e) => Self {
+                spec_no,
+                info: None,
+                error: Some(e.message),
+                code: Some(e.code),
+                retryable: e.code.is_retryable(),
+                cached,
+            },
+        }
+    }
+}
+
+/// 定価取得の1行分の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceResult {
+    /// 定価（掲載されていない場合は None）
+    pub price: Option<Price>,
+    /// エラーメッセージ（エラー時・定価取得に対応していないメーカーの場合）
+    pub error: Option<String>,
+    /// エラーコード（エラー時のみ）
+    pub code: Option<ErrorCode>,
+    /// 再試行で解決する可能性がある失敗か
+    pub retryable: bool,
+}
+
+/// 保存先ファイル名の衝突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameCollision {
+    /// 保存先ファイルパス
+    pub file_path: String,
+    /// このパスに保存されるアイテムのSpec No.
+    pub spec_nos: Vec<String>,
+    /// 同名のファイルが既に存在するか
+    pub exists: bool,
+}
+
+/// 一括ダウンロードの事前見積もり
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEstimate {
+    /// 全体の件数
+    pub total: usize,
+    /// 対応メーカーのアイテム数
+    pub supported_count: usize,
+    /// IESファイルのURLを解決できたアイテム数
+    pub resolved_count: usize,
+    /// 対応メーカーがないアイテムのSpec No.
+    pub unsupported: Vec<String>,
+    /// IESファイルのURLを解決できなかったアイテムのSpec No.
+    pub unresolved: Vec<String>,
+    /// 保存先ファイル名の衝突（アイテム同士、または既存ファイルとの衝突）
+    pub collisions: Vec<FilenameCollision>,
+    /// IESファイルの合計サイズの目安（バイト。サイズを取得できたファイルのみ）
+    pub total_size: u64,
+    /// サイズを取得できたファイル数
+    pub sized_count: usize,
+}
+
+/// 事前確認（[`check_availability`]）の1行分の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AvailabilityStatus {
+    /// IESファイルが掲載されている
+    Found,
+    /// 製品、またはIESファイルが見つからない
+    NotFound,
+    /// 対応するメーカーがない
+    Unsupported,
+    /// IESファイルは掲載されているが、電源別売の製品でPSU型番の指定がない
+    NeedsPsu,
+    /// 通信エラー等で確認できなかった（再試行で解決する可能性がある）
+    Failed,
+}
+
+/// 事前確認の1行分の結果（イベントのペイロードを兼ねる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DownloadProgressEvent {
+pub struct AvailabilityResult {
     /// Spec No.（アイテム識別用）
     pub spec_no: String,
-    /// ステータス: "processing" | "success" | "error"
-    pub status: String,
+    pub status: AvailabilityStatus,
+    /// 確認に使用したプロバイダーID（対応するメーカーがない場合は None）
+    pub provider: Option<String>,
+    /// 製品情報（取得できた場合のみ）
+    pub info: Option<ProductInfo>,
+    /// エラーメッセージ（取得に失敗した場合のみ）
+    pub error: Option<String>,
+    /// エラーコード（取得に失敗した場合のみ）
+    pub code: Option<ErrorCode>,
+}
+
+/// 事前確認の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityReport {
+    /// 全体の件数
+    pub total: usize,
+    /// IESファイルが掲載されているアイテム数（PSU型番の指定が必要なものを除く）
+    pub found_count: usize,
+    /// 各アイテムの結果（リクエストの順）
+    pub results: Vec<AvailabilityResult>,
+}
+
+/// ダウンロード済みファイルの再リネームのリクエスト
+///
+/// 対象はダウンロード履歴に記録された成功ファイルのうち、条件に一致するもの。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenameRequest {
+    /// 対象のプロジェクトID
+    pub project_id: Option<String>,
+    /// 対象のフォルダ（配下のファイルのみ）
+    pub dir: Option<String>,
+    /// true の場合はリネームせず、変更内容のみ返す
+    pub dry_run: bool,
+    /// true の場合は設定のテンプレートではなく、IESファイルの内容（色温度・ビーム角）から
+    /// 命名するテンプレート（例: `1001_XD93319_3000K_30deg.ies`）を使用する
+    pub content_aware: bool,
+}
+
+/// 1ファイル分の再リネーム結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    pub spec_no: String,
+    /// 変更前のパス
+    pub old_path: String,
+    /// 変更後のパス
+    pub new_path: String,
+    /// リネームしたかどうか（dry run では false）
+    pub renamed: bool,
     /// エラーメッセージ（エラー時のみ）
     pub error: Option<String>,
+    /// エラーコード（エラー時のみ）
+    pub code: Option<ErrorCode>,
+}
+
+/// プロバイダーの疎通確認結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiagnosis {
+    /// プロバイダーID
+    pub provider_id: String,
+    /// 診断結果
+    #[serde(flatten)]
+    pub diagnosis: Diagnosis,
+    /// 所要時間（ミリ秒）
+    pub elapsed_ms: u64,
 }
 
 /// 一括ダウンロードの進捗情報
@@ -42,8 +280,90 @@ pub struct BatchProgress {
 pub struct BatchDownloadRequest {
     /// ダウンロード対象のリスト（メーカー名、型番のペア）
     pub items: Vec<BatchDownloadItem>,
-    /// 保存先ディレクトリ
-    pub dest_dir: String,
+    /// 保存先ディレクトリ（省略時は設定の既定の保存先ディレクトリ）
+    ///
+    /// `{project}` `{date}` `{manufacturer}` 等のプレースホルダーを使用できる（[`filename::render_dir`]）。
+    #[serde(default)]
+    pub dest_dir: Option<String>,
+    /// 取得するアセット種別（アイテム側で指定がない行に適用。省略時はIESのみ）
+    #[serde(default = "default_asset_types")]
+    pub asset_types: Vec<AssetType>,
+    /// ダウンロード履歴に記録するプロジェクトID
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// バッチID（省略時は既定のバッチ）。IDの異なるバッチは同時に実行できる
+    #[serde(default)]
+    pub batch_id: Option<String>,
+    /// 読み込んだシート名（保存先ディレクトリの `{sheet}` に使用）
+    #[serde(default)]
+    pub sheet_name: Option<String>,
+    /// 曖昧な一致（同程度に一致するIESファイル・近い型番の製品）を利用者に判断してもらうか
+    ///
+    /// 指定した場合、該当するアイテムは `resolve_item_choice` で選ぶまで完了しない。
+    /// 省略時は従来どおり自動で選ぶ（選べない場合は失敗にする）。
+    #[serde(default)]
+    pub interactive: bool,
+    /// 同時にダウンロードするアイテム数の上限（省略時は設定の同時実行数）
+    ///
+    /// バッテリー駆動時は設定に従って下げる。
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// IESファイルのダウンロードキャッシュを使うか（メーカー・型番・PSUが同じファイルを再ダウンロードしない）
+    ///
+    /// 指定した場合、キャッシュにないファイルはダウンロード後にキャッシュに保存する。
+    #[serde(default)]
+    pub use_cache: bool,
+    /// 保存先に取得済みのファイルがあるアイテムを飛ばすか（途中で止まったバッチの再実行用）
+    ///
+    /// 保存先フォルダの監査ログに取得の成功が記録され、ファイルが残っているアセットを取得済みとする。
+    /// 要求したアセットがすべて取得済みのアイテムは取得し直さず、"skipped" として通知する。
+    #[serde(default)]
+    pub skip_existing: bool,
+    /// 同じ内容のIESファイルの照合に保存先の既存のファイルも含めるか
+    ///
+    /// バッチ内で同じ内容のファイルを取得したアイテムは常に重複として報告する
+    /// （`SingleDownloadResult::duplicate_of`）。指定した場合、保存先フォルダに以前から
+    /// あるIESファイルと同じ内容のファイルも重複として報告する。
+    #[serde(default)]
+    pub dedupe_existing: bool,
+    /// 配光データの出力形式（省略時はIESのみ）
+    ///
+    /// LDTを含む場合、取得したIESファイルをEULUMDAT形式に変換し、同じ名前（拡張子のみ異なる）で保存する。
+    #[serde(default)]
+    pub output_format: PhotometryFormat,
+    /// ファイル名テンプレート（省略時は設定のテンプレート。設定もない場合はプロバイダーの命名規則）
+    ///
+    /// 使用できるプレースホルダーは設定のテンプレートと同じ（[`filename`] を参照）。
+    /// バッチ内で別のアイテムと同じファイル名になった場合は連番を付ける。
+    #[serde(default)]
+    pub filename_template: Option<String>,
+}
+
+fn default_asset_types() -> Vec<AssetType> {
+    vec![AssetType::Ies]
+}
+
+/// アセット一括ダウンロードのリクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchAssetDownloadRequest {
+    /// ダウンロード対象のリスト（IES一括ダウンロードと同じ行）
+    pub items: Vec<BatchDownloadItem>,
+    /// 保存先ディレクトリ（プロジェクトフォルダ。アセット種別ごとのサブフォルダに保存）
+    /// 省略時は設定の既定の保存先ディレクトリ
+    #[serde(default)]
+    pub dest_dir: Option<String>,
+    /// アセット種別
+    pub asset_type: AssetType,
+    /// ダウンロード履歴に記録するプロジェクトID
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// 読み込んだシート名（保存先ディレクトリの `{sheet}` に使用）
+    #[serde(default)]
+    pub sheet_name: Option<String>,
+    /// バッチID（省略時は既定のバッチ）。IDの異なるバッチは同時に実行できる
+    #[serde(default)]
+    pub batch_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +377,23 @@ pub struct BatchDownloadItem {
     pub model_number: String,
     /// PSU型番（オプション）
     pub psu: Option<String>,
+    /// この行で取得するアセット種別（省略時はリクエストの指定に従う）
+    pub asset_types: Option<Vec<AssetType>>,
+    /// 台数（接続負荷・定価の集計に使用。省略時は1台）
+    #[serde(default)]
+    pub quantity: Option<u32>,
+    /// 設置エリア（接続負荷の集計単位。省略可）
+    #[serde(default)]
+    pub area: Option<String>,
+    /// 器具リストの消費電力（W。IESファイルの入力電力との照合に使用。省略可）
+    #[serde(default)]
+    pub wattage: Option<f64>,
+    /// プロバイダーID（指定するとメーカー名にかかわらずこのプロバイダーで処理する。省略可）
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// IESファイルの取得元のURL（手動指定。指定するとプロバイダーの照合の代わりにこのURLから取得する）
+    #[serde(default)]
+    pub source_url: Option<String>,
 }
 
 /// 一括ダウンロードの結果
@@ -67,8 +404,20 @@ pub struct BatchDownloadResult {
     pub success_count: usize,
     /// 失敗件数
     pub failure_count: usize,
+    /// キャンセル件数
+    pub cancelled_count: usize,
+    /// 取得済みのため飛ばした件数（`skip_existing`）
+    #[serde(default)]
+    pub skipped_count: usize,
     /// 各ファイルの結果
     pub results: Vec<SingleDownloadResult>,
+    /// 監査ログのパス（作成できなかった場合は None）
+    pub audit_log_path: Option<String>,
+    /// 接続負荷（ダウンロードしたIESファイルの入力電力 × 台数）のメーカー・設置エリアごとの集計
+    pub connected_load: ConnectedLoad,
+    /// 定価（取得済みの製品情報）× 台数のアイテム・メーカーごとの集計
+    #[serde(default)]
+    pub cost_rollup: CostRollup,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,152 +425,4976 @@ pub struct BatchDownloadResult {
 pub struct SingleDownloadResult {
     pub spec_no: String,
     pub model_number: String,
+    /// 主アセットの結果（IESを要求した場合はIES、それ以外は先頭のアセット）
+    pub result: DownloadResult,
+    /// 要求したアセット種別ごとの結果
+    pub assets: Vec<AssetDownloadResult>,
+    /// 所要時間・転送量（全アセットの合計。`total_ms` はキャンセル待ちを含むアイテム全体の時間）
+    pub timing: DownloadTiming,
+    /// 器具リストの消費電力とIESファイルの入力電力の照合結果（消費電力の指定がある場合のみ）
+    #[serde(default)]
+    pub wattage_check: Option<WattageCheck>,
+    /// 取得済みのため取得し直さなかったか（結果は取得済みのファイル）
+    #[serde(default)]
+    pub skipped: bool,
+    /// 取得したIESファイルが先に保存したファイルと同じ内容か（SHA-256で照合）
+    #[serde(default)]
+    pub deduplicated: bool,
+    /// 同じ内容の先に保存したファイルのパス（`deduplicated` の場合のみ）
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
+}
+
+/// アセット種別ごとのダウンロード結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDownloadResult {
+    pub asset_type: AssetType,
     pub result: DownloadResult,
+    /// 取得元のプロバイダーID（メーカー欄に複数のメーカーがある場合に、どれから取得したか。失敗時は None）
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+/// 1アイテムの解決（`resolve_item`）のオプション
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResolveItemOptions {
+    /// アセットをダウンロードするか（false の場合はIESファイルのURLの解決まで）
+    pub download: bool,
+    /// 取得するアセット種別（省略時はアイテムの指定、それもなければIESのみ）
+    pub asset_types: Option<Vec<AssetType>>,
+    /// 保存先ディレクトリ（省略時は設定の既定の保存先）
+    pub dest_dir: Option<String>,
+}
+
+/// 1アイテムの解決の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemResolution {
+    pub spec_no: String,
+    /// 処理したプロバイダーID（メーカー欄に複数のメーカーがある場合は先頭のもの）
+    pub provider: String,
+    /// 製品情報の取得結果
+    pub product_info: ProductInfoResult,
+    /// IESファイルのURL（ダウンロードしない場合のみ）
+    pub resolved_ies_url: Option<ResolvedIesUrl>,
+    /// IESファイルのURLを解決できなかった場合のエラー
+    pub resolve_error: Option<String>,
+    /// アセット種別ごとのダウンロード結果（ダウンロードした場合のみ）
+    pub assets: Vec<AssetDownloadResult>,
+    /// ダウンロードしたIESファイルの配光データ
+    pub analysis: Option<IesAnalysis>,
+    /// ダウンロードしたIESファイルを配光データとして読み込めなかった場合のエラー
+    pub validation_error: Option<String>,
+    /// 器具リストの消費電力とIESファイルの入力電力の照合結果（消費電力の指定がある場合のみ）
+    pub wattage_check: Option<WattageCheck>,
+}
+
+/// 1アイテム分の全アセットのダウンロード結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleItemResult {
+    pub spec_no: String,
+    pub model_number: String,
+    /// 保存先フォルダ（{保存先ディレクトリ}/{Spec No.}）
+    pub dest_dir: String,
+    /// アセット種別ごとの結果
+    pub assets: Vec<AssetDownloadResult>,
+}
+
+/// 全アセット一括ダウンロードの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBundleResult {
+    /// 1つ以上のアセットを取得できたアイテム数
+    pub success_count: usize,
+    /// 1つもアセットを取得できなかったアイテム数
+    pub failure_count: usize,
+    /// キャンセルされたアイテム数
+    pub cancelled_count: usize,
+    /// 各アイテムの結果
+    pub results: Vec<BundleItemResult>,
 }
 
 /// 対応メーカー一覧を取得
 #[tauri::command]
 pub async fn get_supported_manufacturers(
-    registry: State<'_, Arc<Mutex<ProviderRegistry>>>,
-) -> Result<Vec<String>, String> {
-    let registry = registry.lock().await;
+    registry: State<'_, SharedRegistry>,
+) -> CommandResult<Vec<String>> {
+    let registry = registry.load();
     Ok(registry.get_supported_manufacturers())
 }
 
-/// 製品情報を取得
+/// 登録済みプロバイダーのメタデータ一覧を取得
+///
+/// 表示名・判定に使う別名・対応アセット・検索対応・有効状態・ベースURLを返す。
 #[tauri::command]
-pub async fn fetch_product_info(
-    registry: State<'_, Arc<Mutex<ProviderRegistry>>>,
-    manufacturer: String,
-    model_number: String,
-) -> Result<ProductInfo, String> {
-    let registry = registry.lock().await;
-    let provider = registry
-        .get_provider(&manufacturer)
-        .ok_or_else(|| format!("No provider for manufacturer: {}", manufacturer))?;
+pub async fn list_providers(
+    registry: State<'_, SharedRegistry>,
+) -> CommandResult<Vec<ProviderInfo>> {
+    let registry = registry.load();
+    Ok(registry.list_providers())
+}
 
-    provider.fetch_product_info(&model_number).await
+/// プロバイダーの有効・無効を切り替え
+///
+/// 無効にしたプロバイダーはメーカー名の判定に使用せず、未対応メーカーとして扱う。
+#[tauri::command]
+pub async fn set_provider_enabled(
+    registry: State<'_, SharedRegistry>,
+    id: String,
+    enabled: bool,
+) -> CommandResult<()> {
+    Ok(registry.update(|registry| registry.set_enabled(&id, enabled))?)
 }
 
-/// IESファイルを単体ダウンロード
+/// 利用者が登録したメーカー名の別名一覧を取得
 #[tauri::command]
-pub async fn download_ies_file(
-    registry: State<'_, Arc<Mutex<ProviderRegistry>>>,
-    manufacturer: String,
-    model_number: String,
-    psu: Option<String>,
-    dest_path: String,
-) -> Result<DownloadResult, String> {
-    let registry = registry.lock().await;
-    let provider = registry
-        .get_provider(&manufacturer)
-        .ok_or_else(|| format!("No provider for manufacturer: {}", manufacturer))?;
+pub async fn list_manufacturer_aliases(app: AppHandle) -> CommandResult<Vec<ManufacturerAlias>> {
+    Ok(manufacturer_alias::load(&app))
+}
 
-    provider
-        .download_ies_file(&model_number, psu.as_deref(), &dest_path)
-        .await
+/// メーカー名の別名を登録
+///
+/// 組み込みの別名で判定できないメーカー表記（略称・旧社名等）を `provider_id` のプロバイダーに
+/// 対応付ける。表記は正規化（全角・半角、大文字・小文字、法人格の違いを無視）して照合し、
+/// 同じ表記の別名がある場合は置き換える。登録後の別名一覧を返す。
+#[tauri::command]
+pub async fn add_manufacturer_alias(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    alias: String,
+    provider_id: String,
+) -> CommandResult<Vec<ManufacturerAlias>> {
+    Ok(manufacturer_alias::add(
+        &app,
+        &registry,
+        &alias,
+        &provider_id,
+    )?)
 }
 
-/// IESファイルを一括ダウンロード
+/// プロバイダーごとの接続設定（ベースURL・User-Agent・タイムアウト・試行回数）を取得
+///
+/// 設定していないプロバイダーは含まない（既定値を使う）。
 #[tauri::command]
-pub async fn batch_download_ies_files(
+pub async fn get_provider_settings(
     app: AppHandle,
-    registry: State<'_, Arc<Mutex<ProviderRegistry>>>,
-    request: BatchDownloadRequest,
-) -> Result<BatchDownloadResult, String> {
-    let registry = registry.lock().await;
-    let mut results = Vec::new();
-    let mut success_count = 0;
-    let mut failure_count = 0;
+) -> CommandResult<BTreeMap<String, ProviderConfig>> {
+    Ok(settings::load(&app)?.providers)
+}
 
-    for item in &request.items {
-        // 処理開始イベントを発火
-        let _ = app.emit(
-            "download-progress",
-            DownloadProgressEvent {
-                spec_no: item.spec_no.clone(),
-                status: "processing".to_string(),
-                error: None,
+/// プロバイダーごとの接続設定を検証して保存し、プロバイダーを作り直す
+///
+/// 実行中の一括ダウンロードは開始時の設定のまま続け、次のダウンロードから反映する。
+#[tauri::command]
+pub async fn update_provider_settings(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    providers: BTreeMap<String, ProviderConfig>,
+) -> CommandResult<BTreeMap<String, ProviderConfig>> {
+    let settings = Settings {
+        providers,
+        ..settings::load(&app)?
+    };
+    settings::save(&app, &settings)?;
+    apply_provider_config(&registry, &settings.providers)?;
+    tracing::info!(
+        count = settings.providers.len(),
+        "provider settings updated"
+    );
+    Ok(settings.providers)
+}
+
+/// プロバイダーの疎通確認と製品検索の動作確認を行う
+///
+/// Webサイトへの接続と、既知の型番での製品情報取得を順に試し、
+/// 失敗した場合は原因（DNS・プロキシ認証・ページ構成の変更等）を分類して返す。
+#[tauri::command]
+pub async fn test_provider_connection(
+    registry: State<'_, SharedRegistry>,
+    id: String,
+) -> CommandResult<ProviderDiagnosis> {
+    let provider = {
+        let registry = registry.load();
+        registry
+            .get_provider_by_id(&id)
+            .ok_or_else(|| format!("Unknown provider: {}", id))?
+    };
+    Ok(diagnose_provider(provider.as_ref()).await)
+}
+
+/// 定義ファイルのプロバイダー（カスタムプロバイダー）を読み込み直す
+///
+/// アプリのデータディレクトリの `providers/` にある定義ファイル（JSON・TOML）と使用中の
+/// プロバイダールールのバンドルからプロバイダーを作り直して登録する。読み込めなかった定義は
+/// 結果の `errors` で返す。実行中の一括ダウンロードは開始時のプロバイダーのまま続け、
+/// 次のダウンロードから反映する。
+#[tauri::command]
+pub async fn reload_custom_providers(app: AppHandle) -> CommandResult<CustomProvidersResult> {
+    let result = run_blocking(move || reload_providers(&app)).await?;
+    tracing::info!(
+        loaded = result.loaded.len(),
+        errors = result.errors.len(),
+        "custom providers reloaded"
+    );
+    Ok(result)
+}
+
+/// プロバイダーの定義（JSON・TOML）を検証
+///
+/// 定義ファイルとして保存する前の確認用。問題がなければ解析した定義を返す
+/// （組み込みのプロバイダー・他の定義ファイルのプロバイダーと同じIDはエラー）。
+#[tauri::command]
+pub async fn validate_provider_definition(
+    registry: State<'_, SharedRegistry>,
+    contents: String,
+) -> CommandResult<ProviderDefinition> {
+    let definition = ProviderDefinition::parse(&contents)?;
+    let providers = registry.load().list_providers();
+    let taken: Vec<&str> = providers
+        .iter()
+        .filter(|p| !p.custom || p.id != definition.id)
+        .map(|p| p.id.as_str())
+        .collect();
+    Ok(GenericProvider::new(definition, &taken)?
+        .definition()
+        .clone())
+}
+
+/// 接続確認と既知の型番での製品情報取得を順に試し、結果を分類
+async fn diagnose_provider(provider: &dyn ManufacturerProvider) -> ProviderDiagnosis {
+    let started = Instant::now();
+
+    let diagnosis = match provider.check_connection().await {
+        Err(diagnosis) => diagnosis,
+        Ok(()) => match provider.sample_model_number() {
+            None => Diagnosis::new(DiagnosisStatus::Ok, "Connected (no lookup test available)"),
+            Some(model_number) => match provider.fetch_product_info(model_number).await {
+                Ok(info) if info.ies_file_url.is_some() => Diagnosis::new(
+                    DiagnosisStatus::Ok,
+                    format!("Found IES file for {}", model_number),
+                ),
+                Ok(_) => Diagnosis::new(
+                    DiagnosisStatus::HtmlChanged,
+                    format!("IES link not found on the page for {}", model_number),
+                ),
+                Err(e) => Diagnosis::new(DiagnosisStatus::LookupFailed, e),
             },
-        );
-        // 一時ファイル名でダウンロード（後で元ファイル名を使ってリネーム）
-        let temp_path = format!(
-            "{}/temp_{}.ies",
-            request.dest_dir,
-            item.spec_no
-        );
+        },
+    };
 
-        let result = if let Some(provider) = registry.get_provider(&item.manufacturer) {
-            match provider
-                .download_ies_file(&item.model_number, item.psu.as_deref(), &temp_path)
-                .await
-            {
-                Ok(mut r) => {
-                    if r.success {
-                        // プロバイダーの命名規則でファイル名を生成
-                        let filename = provider.generate_filename(
-                            &item.spec_no,
-                            &item.model_number,
-                            item.psu.as_deref(),
-                            r.original_filename.as_deref(),
-                        );
-                        let final_path = format!("{}/{}", request.dest_dir, filename);
-
-                        // ファイルをリネーム
-                        if let Err(e) = std::fs::rename(&temp_path, &final_path) {
-                            r = DownloadResult::failure(format!("Failed to rename file: {}", e));
-                        } else {
-                            r.file_path = Some(final_path);
-                        }
-                    }
-                    r
-                }
-                Err(e) => DownloadResult::failure(e),
-            }
-        } else {
-            DownloadResult::failure(format!("No provider for: {}", item.manufacturer))
+    ProviderDiagnosis {
+        provider_id: provider.id().to_string(),
+        diagnosis,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// 診断情報バンドルに含める失敗履歴の件数
+const DIAGNOSTICS_FAILURE_LIMIT: usize = 100;
+
+/// 整形済みJSONのバイト列に変換
+fn pretty_json<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// 診断情報バンドルのファイル（アプリ情報・設定・直前の一括ダウンロードの結果・ログ・クラッシュレポート）
+fn diagnostics_files(app: &AppHandle) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let package = app.package_info();
+    let app_info = serde_json::json!({
+        "name": package.name,
+        "version": package.version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "createdAt": chrono::Utc::now(),
+    });
+    let settings = settings::load(app).unwrap_or_default();
+
+    let mut files = vec![
+        ("app.json".to_string(), pretty_json(&app_info)?),
+        (
+            "settings.json".to_string(),
+            pretty_json(&diagnostics::redact_settings(&settings))?,
+        ),
+    ];
+    if let Some(last_batch) = portable::data_dir(app)
+        .ok()
+        .and_then(|dir| diagnostics::load_last_batch(&dir))
+    {
+        files.push(("last_batch.json".to_string(), last_batch));
+    }
+    if let Ok(log_dir) = portable::log_dir(app) {
+        files.extend(diagnostics::collect_logs(&log_dir));
+    }
+    if let Ok(crash_dir) = crash_dir(app) {
+        for report in crash::list_reports(&crash_dir) {
+            files.push((format!("crashes/{}.json", report.id), pretty_json(&report)?));
+        }
+    }
+    Ok(files)
+}
+
+/// 不具合報告用の診断情報バンドル（ZIP）を作成
+///
+/// アプリ情報・設定（プロキシの認証情報は伏せ字）・有効なプロバイダーの疎通確認結果・
+/// 最近の失敗履歴・直前の一括ダウンロードの結果・ログファイル・クラッシュレポートをまとめ、保存先パスを返す。
+/// `dest_path` 省略時はデータディレクトリの `diagnostics` フォルダに日時入りの名前で保存する。
+/// `skip_provider_checks` を指定した場合はメーカーサイトへの疎通確認を行わない（オフライン時等）。
+#[tauri::command]
+pub async fn create_diagnostics_bundle(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    dest_path: Option<String>,
+    skip_provider_checks: Option<bool>,
+) -> CommandResult<String> {
+    let dest_path = match dest_path {
+        Some(path) => path,
+        None => portable::data_dir(&app)?
+            .join("diagnostics")
+            .join(format!(
+                "autosight-diagnostics-{}.zip",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ))
+            .to_string_lossy()
+            .into_owned(),
+    };
+    let diagnoses = if skip_provider_checks.unwrap_or(false) {
+        None
+    } else {
+        let providers: Vec<_> = {
+            let registry = registry.load();
+            registry
+                .list_providers()
+                .into_iter()
+                .filter(|p| p.enabled)
+                .filter_map(|p| registry.get_provider_by_id(&p.id))
+                .collect()
         };
+        Some(
+            futures::future::join_all(providers.iter().map(|p| diagnose_provider(p.as_ref())))
+                .await,
+        )
+    };
 
-        if result.success {
-            success_count += 1;
-        } else {
-            failure_count += 1;
+    Ok(run_blocking(move || -> Result<_, String> {
+        let failures: Vec<_> = history::load(&app)
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .filter(|e| !e.success)
+            .take(DIAGNOSTICS_FAILURE_LIMIT)
+            .collect();
+
+        let mut files = diagnostics_files(&app)?;
+        if let Some(diagnoses) = diagnoses {
+            files.push(("providers.json".to_string(), pretty_json(&diagnoses)?));
         }
+        files.push(("recent_failures.json".to_string(), pretty_json(&failures)?));
 
-        // 完了イベントを発火
-        let _ = app.emit(
-            "download-progress",
-            DownloadProgressEvent {
-                spec_no: item.spec_no.clone(),
-                status: if result.success {
-                    "success".to_string()
-                } else {
-                    "error".to_string()
-                },
-                error: result.error.clone(),
-            },
-        );
+        diagnostics::write_bundle(&longpath::extended(&dest_path), &files)?;
+        Ok(dest_path)
+    })
+    .await?)
+}
 
-        results.push(SingleDownloadResult {
-            spec_no: item.spec_no.clone(),
-            model_number: item.model_number.clone(),
-            result,
-        });
+/// `get_recent_logs` で返す既定の行数
+const RECENT_LOG_LINES: usize = 500;
+
+/// 最近のログ（ログファイルの末尾 `limit` 行（省略時は500行）、古い順）を取得
+///
+/// 不具合報告の前に、メーカーサイトへのリクエストや失敗の内容をアプリ内で確認するために使用する。
+#[tauri::command]
+pub async fn get_recent_logs(app: AppHandle, limit: Option<usize>) -> CommandResult<Vec<String>> {
+    let log_dir = portable::log_dir(&app)?;
+    let limit = limit.unwrap_or(RECENT_LOG_LINES);
+    Ok(
+        run_blocking(move || Ok::<_, String>(diagnostics::recent_log_lines(&log_dir, limit)))
+            .await?,
+    )
+}
+
+/// 定義ファイルのプロバイダー（カスタムプロバイダー）の定義ファイルのディレクトリ
+pub fn custom_providers_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    portable::data_dir(app).map(|dir| dir.join("providers"))
+}
+
+/// 定義ファイルと使用中のプロバイダールールのバンドルからカスタムプロバイダーを登録し直す
+pub fn reload_providers(app: &AppHandle) -> Result<CustomProvidersResult, String> {
+    let dir = custom_providers_dir(app)?;
+    let rules = rules_update::active_bundle(app)?;
+    apply_custom_providers(&app.state::<SharedRegistry>(), &dir, rules.as_deref())
+}
+
+/// クラッシュレポートの保存先ディレクトリ
+pub fn crash_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    portable::data_dir(app).map(|dir| dir.join("crashes"))
+}
+
+/// 未確認のクラッシュレポート一覧を取得（新しい順）
+///
+/// 起動時に呼び出し、前回の実行中に発生したパニックをユーザーに知らせるために使用する。
+#[tauri::command]
+pub async fn get_crash_reports(app: AppHandle) -> CommandResult<Vec<CrashReport>> {
+    Ok(crash::list_reports(&crash_dir(&app)?))
+}
+
+/// クラッシュレポートを指定パスに書き出す
+#[tauri::command]
+pub async fn export_crash_report(
+    app: AppHandle,
+    id: String,
+    dest_path: String,
+) -> CommandResult<String> {
+    crash::export_report(&crash_dir(&app)?, &id, Path::new(&dest_path))?;
+    Ok(dest_path)
+}
+
+/// クラッシュレポートを確認済みにする（一覧から削除）
+#[tauri::command]
+pub async fn dismiss_crash_report(app: AppHandle, id: String) -> CommandResult<()> {
+    Ok(crash::dismiss_report(&crash_dir(&app)?, &id)?)
+}
+
+/// Excel器具リストを読み込む
+///
+/// Fixture Base シートの各行を型付きの行データに変換し、未対応メーカー・型番未入力・
+/// Spec No.重複等の検証警告とあわせて返す。`profile` 省略時は標準の列構成で読み込む。
+/// 列名の表記の違いは無視し、設定の行に必須列がなければヘッダー行を探す。
+#[tauri::command]
+pub async fn import_excel(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    path: String,
+    profile: Option<ImportProfile>,
+    project_id: Option<String>,
+) -> CommandResult<ImportResult> {
+    let registry = registry.load();
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    Ok(run_blocking(move || -> Result<_, String> {
+        let mut result = excel::import(
+            &path,
+            &profile.unwrap_or_default(),
+            locale,
+            |manufacturer| registry.get_provider(manufacturer).is_some(),
+        )?;
+        // プロジェクトの無視リストに含まれる行に印を付ける（取得を試みないようにする）
+        let ignore_list = ignore_list::load(&app, project_id.as_deref());
+        for row in &mut result.rows {
+            row.ignored = ignore_list.matches(&row.spec_no, &row.fixture);
+        }
+        // 再起動後に読み込みをやり直さずに済むよう、作業状態として記録
+        if let Err(e) = session::record_import(&app, &path, &result) {
+            tracing::warn!(error = %e, "failed to save session");
+        }
+        Ok(result)
+    })
+    .await?)
+}
+
+/// Excelからコピーした行（タブ区切り）をダウンロードアイテムに変換
+///
+/// ファイルを保存・読み込みせずに、貼り付けた行をそのまま一括ダウンロードできるようにする。
+/// ヘッダー行がない場合は「メーカー・型番・Spec No.・PSU」の順の列とみなす。
+#[tauri::command]
+pub async fn parse_clipboard_items(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    text: String,
+    profile: Option<ImportProfile>,
+) -> CommandResult<ClipboardImportResult> {
+    let registry = registry.load();
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    Ok(excel::parse_clipboard(
+        &text,
+        &profile.unwrap_or_default(),
+        locale,
+        |manufacturer| registry.get_provider(manufacturer).is_some(),
+    ))
+}
+
+/// IESライブラリの保存先ディレクトリ
+pub(crate) fn library_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    portable::data_dir(app).map(|dir| dir.join("library"))
+}
+
+/// ドロップされたファイルの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DroppedFileKind {
+    /// 器具リスト（Excel・CSV）
+    Schedule,
+    /// IESファイル
+    Ies,
+    /// 未対応の形式
+    Unsupported,
+}
+
+/// ドロップされたファイル1件の処理結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedFileResult {
+    pub path: String,
+    pub kind: DroppedFileKind,
+    /// 器具リストの読み込み結果（器具リストの場合のみ）
+    pub import: Option<ImportResult>,
+    /// IESライブラリの登録内容（IESファイルの場合のみ）
+    pub library_entry: Option<LibraryEntry>,
+    /// IESライブラリに新しく登録したか（登録済みの内容と同じファイルの場合は false）
+    pub added: bool,
+    /// エラーメッセージ（失敗時・未対応の形式の場合）
+    pub error: Option<String>,
+}
+
+/// ドロップされたファイルの種別を判定
+///
+/// 拡張子で判定し、拡張子から判定できない場合はIESファイルのヘッダーの有無を確認する。
+fn dropped_file_kind(path: &Path) -> DroppedFileKind {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" | "csv" => DroppedFileKind::Schedule,
+        "ies" => DroppedFileKind::Ies,
+        _ => {
+            let mut head = [0u8; 4096];
+            let len = std::fs::File::open(path)
+                .and_then(|mut file| std::io::Read::read(&mut file, &mut head))
+                .unwrap_or(0);
+            let head = String::from_utf8_lossy(&head[..len]);
+            if head.starts_with("IESNA") || head.contains("TILT=") {
+                DroppedFileKind::Ies
+            } else {
+                DroppedFileKind::Unsupported
+            }
+        }
     }
+}
 
-    Ok(BatchDownloadResult {
-        success_count,
-        failure_count,
-        results,
+/// ドロップされたファイルを取り込む
+///
+/// 種別を判定し、器具リスト（Excel・CSV）は読み込み結果を返し、IESファイルはIESライブラリに登録する。
+/// フロントエンドのドロップ領域はパスを渡すだけで、解析はすべてバックエンドで行う。
+/// 1件の失敗で他のファイルの取り込みは中断しない。
+#[tauri::command]
+pub async fn ingest_dropped_files(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    paths: Vec<String>,
+    profile: Option<ImportProfile>,
+) -> CommandResult<Vec<DroppedFileResult>> {
+    let registry = registry.load();
+    let profile = profile.unwrap_or_default();
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    let library_dir = library_dir(&app)?;
+
+    Ok(run_blocking(move || {
+        let mut results = Vec::new();
+        for path in paths {
+            let kind = dropped_file_kind(Path::new(&path));
+            let mut result = DroppedFileResult {
+                path: path.clone(),
+                kind,
+                import: None,
+                library_entry: None,
+                added: false,
+                error: None,
+            };
+            match kind {
+                DroppedFileKind::Schedule => {
+                    match excel::import(&path, &profile, locale, |manufacturer| {
+                        registry.get_provider(manufacturer).is_some()
+                    }) {
+                        Ok(import) => {
+                            if let Err(e) = session::record_import(&app, &path, &import) {
+                                tracing::warn!(error = %e, "failed to save session");
+                            }
+                            result.import = Some(import);
+                        }
+                        Err(e) => result.error = Some(e),
+                    }
+                }
+                DroppedFileKind::Ies => match library::ingest(&library_dir, Path::new(&path)) {
+                    Ok((entry, added)) => {
+                        result.library_entry = Some(entry);
+                        result.added = added;
+                    }
+                    Err(e) => result.error = Some(e),
+                },
+                DroppedFileKind::Unsupported => {
+                    result.error = Some(format!("Unsupported file type: {}", path));
+                }
+            }
+            results.push(result);
+        }
+        Ok::<_, String>(results)
     })
+    .await?)
 }
 
-/// メーカーが対応しているか確認
+/// IESライブラリの登録内容を取得
 #[tauri::command]
-pub async fn is_manufacturer_supported(
-    registry: State<'_, Arc<Mutex<ProviderRegistry>>>,
+pub async fn get_library_entries(app: AppHandle) -> CommandResult<Vec<LibraryEntry>> {
+    Ok(library::load(&library_dir(&app)?)?)
+}
+
+/// IESライブラリを検索
+///
+/// 「3000K 15度 Koizumi」のように空白で区切ったすべての語が、ヘッダーのキーワード
+/// （`[LAMPCAT]` `[MANUFAC]` 等）または元ファイル名に含まれる登録内容を新しい順に返す。
+#[tauri::command]
+pub async fn search_library(app: AppHandle, query: String) -> CommandResult<Vec<LibraryEntry>> {
+    let entries = library::load(&library_dir(&app)?)?;
+    Ok(library::search(&entries, &query)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// メーカー名・型番の入力の候補を取得
+///
+/// ダウンロード履歴（成功したもの）とIESライブラリから、入力中の文字列に前方一致するものを
+/// 使った回数の多い順に返す。`manufacturer` を指定した場合、型番の候補をそのメーカーのものに絞る。
+#[tauri::command]
+pub async fn suggest(
+    app: AppHandle,
+    field: SuggestField,
+    prefix: String,
+    manufacturer: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<Suggestion>> {
+    let history = history::load(&app)?;
+    let library = library::load(&library_dir(&app)?)?;
+    Ok(suggest::suggest(
+        &history,
+        &library,
+        field,
+        &prefix,
+        manufacturer.as_deref(),
+        limit,
+    ))
+}
+
+/// 製品情報を取得
+///
+/// オフラインモードでは製品情報のキャッシュから返す。
+#[tauri::command]
+pub async fn fetch_product_info(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
     manufacturer: String,
-) -> Result<bool, String> {
-    let registry = registry.lock().await;
-    Ok(registry.get_provider(&manufacturer).is_some())
+    model_number: String,
+) -> CommandResult<ProductInfo> {
+    let registry = registry.load();
+    let provider = registry.get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+
+    if offline::is_enabled() {
+        let (fetched, _) = lookup_model(&app, provider.as_ref(), &model_number).await;
+        return Ok(fetched?);
+    }
+    Ok(provider.fetch_product_info(&model_number).await?)
+}
+
+/// 同時実行数（バッテリー駆動時は設定に従って下げる）
+fn concurrency(settings: &Settings) -> usize {
+    settings
+        .battery
+        .concurrency(settings.concurrency, power::on_battery() == Some(true))
+}
+
+/// リクエストで指定された同時実行数（省略時は設定の同時実行数。バッテリー駆動時の上限も適用する）
+fn requested_concurrency(settings: &Settings, requested: Option<usize>) -> Result<usize, String> {
+    match requested {
+        Some(n) => Ok(settings.battery.concurrency(
+            settings::clamp_concurrency(n)?,
+            power::on_battery() == Some(true),
+        )),
+        None => Ok(concurrency(settings)),
+    }
+}
+
+/// 製品情報一括取得の1行分を処理し、結果をイベントで通知
+async fn fetch_product_info_row(
+    app: AppHandle,
+    item: BatchDownloadItem,
+    provider: Option<Arc<dyn ManufacturerProvider>>,
+) -> ProductInfoResult {
+    let (fetched, cached) = lookup_product_info(&app, &item, provider.as_deref()).await;
+    let result = ProductInfoResult::new(item.spec_no, fetched, cached);
+    let _ = app.emit("product-info-progress", result.clone());
+    result
+}
+
+/// 製品情報を取得（キャッシュが有効な場合はメーカーサイトにアクセスしない）
+///
+/// 廃番で後継品が掲載されており、設定の `follow_successor` が有効な場合は後継品の製品情報を返す
+/// （後継品の取得に失敗した場合は元の製品情報を返す）。
+/// 型番が見つからず、後継品の対応表に記録がある場合は後継品の製品情報を返す（`replaces` に元の型番）。
+///
+/// 戻り値: (取得結果, キャッシュから返したか)
+async fn lookup_product_info(
+    app: &AppHandle,
+    item: &BatchDownloadItem,
+    provider: Option<&dyn ManufacturerProvider>,
+) -> (ProviderResult<ProductInfo>, bool) {
+    let Some(provider) = provider else {
+        return (Err(no_provider(&item.manufacturer)), false);
+    };
+    let (fetched, cached) = lookup_model(app, provider, &item.model_number).await;
+    let successor = match &fetched {
+        Ok(info) => successor_to_follow(app, info),
+        Err(e) if succession::is_not_found(e.code, &e.message) => {
+            succession::successor(provider.id(), &item.model_number)
+        }
+        Err(_) => None,
+    };
+    let Some(successor) = successor else {
+        return (fetched, cached);
+    };
+
+    match lookup_model(app, provider, &successor).await {
+        (Ok(mut info), cached) => {
+            info.replaces = Some(item.model_number.clone());
+            (Ok(info), cached)
+        }
+        (Err(e), _) => {
+            tracing::warn!(error = %e, successor = %successor, "failed to fetch successor product info");
+            (fetched, cached)
+        }
+    }
+}
+
+/// 取得するアセット種別が指定されていない行の結果
+fn no_asset_types() -> DownloadResult {
+    DownloadResult::failure_with_code(
+        ErrorCode::InvalidInput,
+        "No asset types requested".to_string(),
+    )
+}
+
+/// メーカー名に対応するプロバイダーがない行のエラー
+fn no_provider(manufacturer: &str) -> ProviderError {
+    ProviderError::new(
+        ErrorCode::ProviderNotFound,
+        format!("No provider for: {}", manufacturer),
+    )
+}
+
+/// 切り替える後継品の型番（廃番で後継品が掲載されており、設定で有効な場合のみ）
+fn successor_to_follow(app: &AppHandle, info: &ProductInfo) -> Option<String> {
+    let successor = info.discontinued.as_ref()?.successor.clone()?;
+    settings::load(app)
+        .is_ok_and(|s| s.follow_successor)
+        .then_some(successor)
+}
+
+/// 1型番分の製品情報を取得（キャッシュが有効な場合はメーカーサイトにアクセスしない）
+///
+/// オフラインモードでは有効期間を過ぎたキャッシュも使用し、キャッシュにない場合はエラーにする。
+///
+/// 戻り値: (取得結果, キャッシュから返したか)
+async fn lookup_model(
+    app: &AppHandle,
+    provider: &dyn ManufacturerProvider,
+    model_number: &str,
+) -> (ProviderResult<ProductInfo>, bool) {
+    let cache_path = cache_dir(app)
+        .ok()
+        .map(|dir| prefetch::cache_path(&dir, provider.id(), model_number));
+    if let Some(info) = cache_path
+        .as_deref()
+        .and_then(|path| prefetch::load_cached(path, chrono::Utc::now()))
+    {
+        return (Ok(info), true);
+    }
+    if offline::is_enabled() {
+        let info = cache_path.as_deref().and_then(prefetch::load_stale);
+        let cached = info.is_some();
+        let fetched =
+            info.ok_or_else(|| offline::not_cached(&format!("product info for {}", model_number)));
+        return (fetched, cached);
+    }
+
+    let fetched = provider.fetch_product_info(model_number).await;
+    if let (Ok(info), Some(path)) = (&fetched, &cache_path) {
+        prefetch::store_cached(path, info);
+    }
+    // 旧型番のページが削除された後も後継品で取得できるよう、後継品を記録しておく
+    if let Some(successor) = fetched
+        .as_ref()
+        .ok()
+        .and_then(|info| info.discontinued.as_ref()?.successor.as_deref())
+    {
+        if let Err(e) = succession::record(app, provider.id(), model_number, successor) {
+            tracing::warn!(error = %e, "failed to save successor");
+        }
+    }
+    (fetched, false)
+}
+
+/// 製品情報を一括取得
+///
+/// 同時実行数を制限しつつ並列に取得し、1行ごとに `product-info-progress` イベントで
+/// 結果を通知する。同時実行数の省略時は設定値を使用する。戻り値の順序は完了順。
+#[tauri::command]
+pub async fn fetch_product_info_batch(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    items: Vec<BatchDownloadItem>,
+    max_concurrency: Option<usize>,
+) -> CommandResult<Vec<ProductInfoResult>> {
+    // プロバイダーを先に解決（処理中に有効・無効が切り替わっても開始時点の状態で処理する）
+    let jobs: Vec<_> = {
+        let registry = registry.load();
+        items
+            .into_iter()
+            .map(|item| {
+                let provider =
+                    registry.get_provider_for(&item.manufacturer, item.provider.as_deref());
+                (item, provider)
+            })
+            .collect()
+    };
+    let concurrency = requested_concurrency(&settings::load(&app)?, max_concurrency)?;
+
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|(item, provider)| fetch_product_info_row(app.clone(), item, provider))
+        .collect();
+    let results = futures::stream::iter(tasks)
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+    if let Err(e) = session::record_product_info(&app, &results) {
+        tracing::warn!(error = %e, "failed to save session");
+    }
+
+    Ok(results)
+}
+
+/// 器具リスト取り込み後の製品情報の先読みを開始
+///
+/// 全行の製品情報をバックグラウンドで並列に取得し、1行ごとに `product-info-progress`
+/// イベントで通知する（メーカー・型番が同じ行は1回だけ取得する）。取得結果は
+/// キャッシュされ、以降の取得ではメーカーサイトにアクセスしない。全行の通知後に
+/// `product-info-prefetch-finished` イベントを送る。実行中の先読みは中止して置き換える。
+///
+/// 戻り値: 取得する製品数（重複を除いた件数）
+#[tauri::command]
+pub async fn prefetch_product_info(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    prefetch: State<'_, PrefetchState>,
+    items: Vec<BatchDownloadItem>,
+    max_concurrency: Option<usize>,
+) -> CommandResult<usize> {
+    // メーカー・プロバイダー指定・型番ごとにまとめる（プロバイダーは開始時点の状態で解決）
+    let mut groups: BTreeMap<(String, Option<String>, String), Vec<BatchDownloadItem>> =
+        BTreeMap::new();
+    for item in items {
+        let key = (
+            item.manufacturer.clone(),
+            item.provider.clone(),
+            item.model_number.clone(),
+        );
+        groups.entry(key).or_default().push(item);
+    }
+    let jobs: Vec<_> = {
+        let registry = registry.load();
+        groups
+            .into_values()
+            .map(|rows| {
+                let provider =
+                    registry.get_provider_for(&rows[0].manufacturer, rows[0].provider.as_deref());
+                (rows, provider)
+            })
+            .collect()
+    };
+    let unique = jobs.len();
+    let concurrency = requested_concurrency(&settings::load(&app)?, max_concurrency)?;
+
+    let task_app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let app = task_app;
+        let tasks: Vec<_> = jobs
+            .into_iter()
+            .map(|(rows, provider)| {
+                let app = app.clone();
+                async move {
+                    let (fetched, cached) =
+                        lookup_product_info(&app, &rows[0], provider.as_deref()).await;
+                    let mut summary = PrefetchSummary::default();
+                    let mut results = Vec::new();
+                    for row in rows {
+                        let result = ProductInfoResult::new(row.spec_no, fetched.clone(), cached);
+                        summary.total += 1;
+                        summary.cached += usize::from(result.cached);
+                        summary.failed += usize::from(result.error.is_some());
+                        let _ = app.emit("product-info-progress", result.clone());
+                        results.push(result);
+                    }
+                    if let Err(e) = session::record_product_info(&app, &results) {
+                        tracing::warn!(error = %e, "failed to save session");
+                    }
+                    summary
+                }
+            })
+            .collect();
+        let summary = futures::stream::iter(tasks)
+            .buffer_unordered(concurrency)
+            .fold(PrefetchSummary::default(), |mut acc, row| async move {
+                acc.total += row.total;
+                acc.cached += row.cached;
+                acc.failed += row.failed;
+                acc
+            })
+            .await;
+        tracing::info!(
+            total = summary.total,
+            cached = summary.cached,
+            failed = summary.failed,
+            "Product info prefetch finished"
+        );
+        let _ = app.emit("product-info-prefetch-finished", summary);
+    });
+    prefetch.start(task);
+
+    Ok(unique)
+}
+
+/// 実行中の製品情報の先読みを中止
+#[tauri::command]
+pub async fn cancel_prefetch(prefetch: State<'_, PrefetchState>) -> CommandResult<()> {
+    prefetch.cancel();
+    Ok(())
+}
+
+/// オフラインの準備（現場での確認に備え、シリーズの製品情報とIESファイルを取得しておく）
+///
+/// メーカーの製品検索で `series_prefix` から始まる型番の製品（`max_items` 件まで。省略時は
+/// 100件）を探し、製品情報をキャッシュし、IESファイルをIESライブラリに登録する。
+/// 1製品ごとに `offline-prepare-progress` イベントで通知する。キャッシュ・IESライブラリに
+/// すでにあるものは取得しない。オフラインモードの間は実行できない。
+#[tauri::command]
+pub async fn prepare_offline(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    series_prefix: String,
+    max_items: Option<usize>,
+) -> CommandResult<offline::PrepareSummary> {
+    if offline::is_enabled() {
+        return Err("Cannot prepare offline data while offline mode is enabled".into());
+    }
+    if series_prefix.trim().is_empty() {
+        return Err("Series prefix must not be empty".into());
+    }
+    let provider = registry.load().get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+    if !provider.supports_search() {
+        return Err(format!(
+            "{} does not support product search",
+            provider.display_name()
+        )
+        .into());
+    }
+
+    let candidates = provider.search_products(series_prefix.trim()).await?;
+    let models = offline::series_models(
+        &candidates,
+        &series_prefix,
+        max_items.unwrap_or(offline::DEFAULT_PREPARE_LIMIT),
+    );
+    let library_dir = library_dir(&app)?;
+    let concurrency = concurrency(&settings::load(&app)?).max(1);
+
+    let tasks: Vec<_> = models
+        .into_iter()
+        .map(|model_number| {
+            let app = app.clone();
+            let provider = provider.clone();
+            let library_dir = library_dir.clone();
+            async move {
+                let progress =
+                    prepare_offline_model(&app, provider.as_ref(), &library_dir, model_number)
+                        .await;
+                let _ = app.emit("offline-prepare-progress", progress.clone());
+                progress
+            }
+        })
+        .collect();
+    let summary = futures::stream::iter(tasks)
+        .buffer_unordered(concurrency)
+        .fold(
+            offline::PrepareSummary::default(),
+            |mut acc, progress| async move {
+                acc.add(&progress);
+                acc
+            },
+        )
+        .await;
+    tracing::info!(
+        manufacturer = %manufacturer,
+        series_prefix = %series_prefix,
+        total = summary.total,
+        failed = summary.failed,
+        "offline data prepared"
+    );
+    Ok(summary)
+}
+
+/// オフラインの準備の1製品分（製品情報をキャッシュし、IESファイルをIESライブラリに登録）
+async fn prepare_offline_model(
+    app: &AppHandle,
+    provider: &dyn ManufacturerProvider,
+    library_dir: &Path,
+    model_number: String,
+) -> offline::PrepareProgress {
+    let mut errors = Vec::new();
+    let (fetched, _) = lookup_model(app, provider, &model_number).await;
+    if let Err(e) = &fetched {
+        errors.push(e.message.clone());
+    }
+
+    let registered = library::load(library_dir)
+        .is_ok_and(|entries| library::find(&entries, &model_number).is_some());
+    let ies = registered || {
+        match download_to_library(provider, library_dir, &model_number).await {
+            Ok(()) => true,
+            Err(e) => {
+                errors.push(e);
+                false
+            }
+        }
+    };
+
+    offline::PrepareProgress {
+        model_number,
+        product_info: fetched.is_ok(),
+        ies,
+        error: (!errors.is_empty()).then(|| errors.join("; ")),
+    }
+}
+
+/// IESファイルを一時ディレクトリにダウンロードし、IESライブラリに登録
+async fn download_to_library(
+    provider: &dyn ManufacturerProvider,
+    library_dir: &Path,
+    model_number: &str,
+) -> Result<(), String> {
+    let temp_dir =
+        tempfile::tempdir().map_err(|e| format!("Failed to create temporary directory: {}", e))?;
+    // 型番をファイル名にし、IESライブラリで型番から探せるようにする
+    let temp_path = temp_dir
+        .path()
+        .join(format!("{}.ies", filename::sanitize_filename(model_number)));
+    let result = provider
+        .download_ies_file(
+            model_number,
+            None,
+            &temp_path.to_string_lossy(),
+            &CancelToken::new(),
+        )
+        .await?;
+    if !result.success {
+        return Err(result
+            .error
+            .unwrap_or_else(|| format!("IES file not downloaded: {}", model_number)));
+    }
+    library::ingest(library_dir, &temp_path)?;
+    Ok(())
+}
+
+/// 1行分の定価を取得
+async fn fetch_price_row(
+    item: BatchDownloadItem,
+    provider: Option<Arc<dyn ManufacturerProvider>>,
+) -> (String, PriceResult) {
+    let fetched = match provider {
+        Some(provider) if provider.supports_pricing() => {
+            provider.fetch_price(&item.model_number).await
+        }
+        Some(provider) => Err(ProviderError::new(
+            ErrorCode::AssetNotAvailable,
+            format!("{} does not provide prices", provider.display_name()),
+        )),
+        None => Err(no_provider(&item.manufacturer)),
+    };
+    let result = match fetched {
+        Ok(price) => PriceResult {
+            price,
+            error: None,
+            code: None,
+            retryable: false,
+        },
+        Err(e) => PriceResult {
+            price: None,
+            error: Some(e.message),
+            code: Some(e.code),
+            retryable: e.code.is_retryable(),
+        },
+    };
+    (item.spec_no, result)
+}
+
+/// 定価を一括取得
+///
+/// 定価の取得に対応しているメーカーについて、設定の同時実行数で並列に取得し、
+/// Spec No.をキーとして返す。
+#[tauri::command]
+pub async fn fetch_prices(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    items: Vec<BatchDownloadItem>,
+) -> CommandResult<BTreeMap<String, PriceResult>> {
+    // プロバイダーを先に解決（処理中に有効・無効が切り替わっても開始時点の状態で処理する）
+    let jobs: Vec<_> = {
+        let registry = registry.load();
+        items
+            .into_iter()
+            .map(|item| {
+                let provider =
+                    registry.get_provider_for(&item.manufacturer, item.provider.as_deref());
+                (item, provider)
+            })
+            .collect()
+    };
+    let concurrency = concurrency(&settings::load(&app)?);
+
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|(item, provider)| fetch_price_row(item, provider))
+        .collect();
+    let results = futures::stream::iter(tasks)
+        .buffer_unordered(concurrency)
+        .collect::<BTreeMap<_, _>>()
+        .await;
+
+    Ok(results)
+}
+
+/// キーワードで製品を検索
+///
+/// 型番が不確かな場合に、メーカーの検索ページから候補一覧を取得する。
+#[tauri::command]
+pub async fn search_products(
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    keyword: String,
+) -> CommandResult<Vec<ProductCandidate>> {
+    let registry = registry.load();
+    let provider = registry.get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+
+    Ok(provider.search_products(keyword.trim()).await?)
+}
+
+/// IESファイルのダウンロードURLを解決（ダウンロードはしない）
+///
+/// TOKISTARのようにZIPで配布される場合は、ZIP内のIESファイル候補と
+/// ダウンロード時に選択されるファイルも返す。
+#[tauri::command]
+pub async fn resolve_ies_url(
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    model_number: String,
+    psu: Option<String>,
+) -> CommandResult<ResolvedIesUrl> {
+    let registry = registry.load();
+    let provider = registry.get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+
+    Ok(provider
+        .resolve_ies_url(&model_number, psu.as_deref())
+        .await?)
+}
+
+/// ZIPで配布されるIESファイルの候補を一致度の高い順に取得（ダウンロードはしない）
+///
+/// ダウンロード時の選択が曖昧な場合に、候補を確認して手動で選び直すためのもの。
+/// ZIPで配布しないメーカーでは空を返す。
+#[tauri::command]
+pub async fn list_zip_candidates(
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    model_number: String,
+) -> CommandResult<Vec<ZipCandidate>> {
+    let registry = registry.load();
+    let provider = registry.get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+
+    Ok(provider.list_zip_candidates(&model_number).await?)
+}
+
+/// IESファイルを単体ダウンロード
+///
+/// `dest_path` を省略した場合は、設定の既定の保存先ディレクトリにファイル名テンプレートで
+/// 命名して保存する（`spec_no` はファイル名にのみ使用）。
+/// `member` にZIP内のファイル名（[`list_zip_candidates`] の候補）を指定した場合は、
+/// 一致度によらずそのファイルを保存する。
+/// オフラインモードではIESライブラリの型番が一致するファイルをコピーする。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn download_ies_file(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    model_number: String,
+    psu: Option<String>,
+    dest_path: Option<String>,
+    spec_no: Option<String>,
+    member: Option<String>,
+) -> CommandResult<DownloadResult> {
+    let registry = registry.load();
+    let provider = registry.get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+
+    if let Some(dest_path) = dest_path {
+        if offline::is_enabled() {
+            return Ok(offline::copy_ies(&model_number, &dest_path));
+        }
+        let cancel = CancelToken::new();
+        let downloaded =
+            provider.download_ies_file(&model_number, psu.as_deref(), &dest_path, &cancel);
+        return Ok(with_zip_member(member, downloaded).await?);
+    }
+
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(&app, &settings, None, None, None)?;
+    tokio::fs::create_dir_all(longpath::extended(filename::dir_prefix(&dest_dir)))
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let item = BatchDownloadItem {
+        spec_no: spec_no.unwrap_or_default(),
+        manufacturer,
+        model_number,
+        psu,
+        asset_types: None,
+        quantity: None,
+        area: None,
+        wattage: None,
+        provider: None,
+        source_url: None,
+    };
+    let filename_options = settings.filename_options();
+    let client = http_client(&settings)?;
+    let cancel = CancelToken::new();
+    let downloaded = download_item_asset(
+        provider.as_ref(),
+        &item,
+        AssetType::Ies,
+        &dest_dir,
+        &settings.destination,
+        &filename_options,
+        &client,
+        &cancel,
+    );
+    Ok(with_zip_member(member, downloaded).await)
+}
+
+/// 1アイテムを解決（製品情報・IESファイルのURLまたはアセットのダウンロード・IESファイルの検証）
+///
+/// 1行ごとに製品情報の取得・URLの解決・ダウンロード・解析を個別に呼び出す代わりに、
+/// まとめて1回で行う。製品情報・URLの解決・検証の失敗は結果の各項目に記録し、コマンドは
+/// 対応するプロバイダーがない場合と保存先を決定できない場合のみエラーにする。
+/// ダウンロードした結果は履歴に記録する。
+#[tauri::command]
+pub async fn resolve_item(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    item: BatchDownloadItem,
+    options: Option<ResolveItemOptions>,
+) -> CommandResult<ItemResolution> {
+    let options = options.unwrap_or_default();
+    let providers = registry
+        .load()
+        .get_providers_for(&item.manufacturer, item.provider.as_deref());
+    let Some(provider) = providers.first().cloned() else {
+        return Err(CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", item.manufacturer),
+        ));
+    };
+
+    let (fetched, cached) = lookup_product_info(&app, &item, Some(provider.as_ref())).await;
+    let mut resolution = ItemResolution {
+        spec_no: item.spec_no.clone(),
+        provider: provider.id().to_string(),
+        product_info: ProductInfoResult::new(item.spec_no.clone(), fetched, cached),
+        resolved_ies_url: None,
+        resolve_error: None,
+        assets: Vec::new(),
+        analysis: None,
+        validation_error: None,
+        wattage_check: None,
+    };
+
+    if !options.download {
+        let resolved = if offline::is_enabled() {
+            Err(offline::not_cached(&format!(
+                "IES URL for {}",
+                item.model_number
+            )))
+        } else {
+            provider
+                .resolve_ies_url(&item.model_number, item.psu.as_deref())
+                .await
+        };
+        match resolved {
+            Ok(resolved) => resolution.resolved_ies_url = Some(resolved),
+            Err(e) => resolution.resolve_error = Some(e.message),
+        }
+        return Ok(resolution);
+    }
+
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(&app, &settings, options.dest_dir.as_deref(), None, None)?;
+    tokio::fs::create_dir_all(longpath::extended(filename::dir_prefix(&dest_dir)))
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let asset_types = options
+        .asset_types
+        .clone()
+        .or_else(|| item.asset_types.clone())
+        .unwrap_or_else(default_asset_types);
+    resolution.assets = download_item_assets(
+        &providers,
+        &item,
+        &asset_types,
+        &dest_dir,
+        &settings.destination,
+        &settings.filename_options(),
+        &http_client(&settings)?,
+        CancelToken::new(),
+    )
+    .await;
+
+    // ダウンロードしたIESファイルを配光データとして読み込めるか確認する
+    if let Some(path) = resolution
+        .assets
+        .iter()
+        .find(|a| a.asset_type == AssetType::Ies && a.result.success)
+        .and_then(|a| a.result.file_path.clone())
+    {
+        let ies = tokio::fs::read(longpath::extended(&path))
+            .await
+            .map_err(|e| format!("Failed to read IES file: {}", e))
+            .and_then(|bytes| photometry::parse_ies(&String::from_utf8_lossy(&bytes)));
+        match ies {
+            Ok(ies) => {
+                resolution.wattage_check = item
+                    .wattage
+                    .filter(|_| ies.input_watts > 0.0)
+                    .and_then(|watts| schedule_report::check_wattage(watts, ies.input_watts));
+                resolution.analysis = Some(ies.analyze());
+            }
+            Err(e) => resolution.validation_error = Some(e),
+        }
+    }
+
+    if let Err(e) = history::append(&app, history_entries(None, &item, &resolution.assets)) {
+        tracing::error!(error = %e, "failed to save download history");
+    }
+    Ok(resolution)
+}
+
+/// 保存先ディレクトリを決定（指定がない場合は設定の既定の保存先ディレクトリ）
+///
+/// バッチ単位のプレースホルダー（`{project}` `{project_id}` `{date}` `{sheet}`）を展開する。
+/// `{manufacturer}` はアイテムごとに展開するため残す（[`item_dest_dir`]）。
+/// 設定したネットワーク共有配下のディレクトリの場合は共有に接続し、書き込みに使うパスを返す。
+/// モバイルではアプリのドキュメントディレクトリ配下のパスに置き換える。
+fn resolve_dest_dir(
+    app: &AppHandle,
+    settings: &Settings,
+    dest_dir: Option<&str>,
+    project_id: Option<&str>,
+    sheet_name: Option<&str>,
+) -> Result<String, String> {
+    let project_name = project_id.and_then(|id| history::project_name(app, id));
+    let today = chrono::Local::now().date_naive();
+    let dir =
+        settings
+            .destination
+            .resolve_dir(dest_dir, project_id, project_name.as_deref(), today)?;
+    filename::validate_dir(&dir)?;
+    let date = today.format("%Y-%m-%d").to_string();
+    let dir = filename::render_dir(
+        &dir,
+        &[
+            ("project", project_name.as_deref().or(project_id)),
+            ("project_id", project_id),
+            ("date", Some(&date)),
+            ("sheet", sheet_name),
+        ],
+    );
+    let dir = network_share::resolve(app, &settings.network_share, &dir)?;
+    storage::writable_dir(app, &dir)
+}
+
+/// アイテムの保存先ディレクトリ（`{manufacturer}` をアイテムのメーカー名に置き換える）
+fn item_dest_dir(dest_dir: &str, item: &BatchDownloadItem) -> String {
+    filename::render_dir(dest_dir, &[("manufacturer", Some(&item.manufacturer))])
+}
+
+/// プロバイダーを介さない取得（URL指定ダウンロード・サムネイル）用のHTTPクライアント
+fn http_client(settings: &Settings) -> Result<reqwest::Client, String> {
+    client_builder()
+        .timeout(std::time::Duration::from_secs(
+            settings.request_timeout_secs,
+        ))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// 一括ダウンロードで使用するHTTPクライアント（作成できない場合は設定を反映しないクライアント）
+///
+/// 手動指定の取得元（URL）からの取得に使用する。一括ダウンロードはエラーにせずに続ける。
+fn batch_http_client(settings: &Settings) -> reqwest::Client {
+    http_client(settings).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "failed to create HTTP client");
+        reqwest::Client::new()
+    })
+}
+
+/// 製品画像のサムネイルを取得（data URL）
+///
+/// 製品画像を縮小してPNGの data URL で返す。縮小した画像はキャッシュディレクトリに保存し、
+/// 次回以降はメーカーサイトにアクセスしない。`size` は長辺のピクセル数（省略時は96）。
+/// オフラインモードではキャッシュにない場合はエラーにする。
+#[tauri::command]
+pub async fn fetch_thumbnail(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+    model_number: String,
+    size: Option<u32>,
+) -> CommandResult<String> {
+    let provider = registry.load().get_provider(&manufacturer).ok_or_else(|| {
+        CommandError::new(
+            ErrorCode::ProviderNotFound,
+            format!("No provider for manufacturer: {}", manufacturer),
+        )
+    })?;
+    let size = thumbnail::clamp_size(size);
+
+    let cache_path = thumbnail::cache_path(&cache_dir(&app)?, provider.id(), &model_number, size);
+    if let Some(png) = thumbnail::load_cached(&cache_path) {
+        return Ok(thumbnail::data_url(&png));
+    }
+    if offline::is_enabled() {
+        return Err(offline::not_cached(&format!("thumbnail for {}", model_number)).into());
+    }
+
+    let image_url = provider
+        .fetch_product_info(&model_number)
+        .await?
+        .image_url
+        .ok_or_else(|| format!("No product image for: {}", model_number))?;
+
+    let client = http_client(&settings::load(&app)?)?;
+    let response = send_request(client.get(&image_url))
+        .await
+        .map_err(|e| format!("Image request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Image download failed with status: {}", response.status()).into());
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+
+    let png = thumbnail::downscale(&bytes, size)?;
+    thumbnail::store_cached(&cache_path, &png);
+    Ok(thumbnail::data_url(&png))
+}
+
+/// アプリのキャッシュディレクトリ
+fn cache_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    portable::cache_dir(app)
+}
+
+/// キャッシュの使用量を種別ごとに取得
+#[tauri::command]
+pub async fn get_cache_stats(app: AppHandle) -> CommandResult<Vec<CacheStats>> {
+    Ok(cache::stats(&cache_dir(&app)?))
+}
+
+/// キャッシュを削除し、削除した合計サイズ（バイト）を返す
+///
+/// `scope` を省略した場合はすべての種別を削除する。
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle, scope: Option<CacheScope>) -> CommandResult<u64> {
+    let scopes = match scope {
+        Some(scope) => vec![scope],
+        None => CacheScope::ALL.to_vec(),
+    };
+    Ok(cache::clear(&cache_dir(&app)?, &scopes)?)
+}
+
+/// IESファイルのダウンロードキャッシュを削除し、削除した合計サイズ（バイト）を返す
+///
+/// 使用量は [`get_cache_stats`] の `iesDownload` で確認できる。
+#[tauri::command]
+pub async fn clear_download_cache(app: AppHandle) -> CommandResult<u64> {
+    Ok(cache::clear(&cache_dir(&app)?, &[CacheScope::IesDownload])?)
+}
+
+/// ポータブルモードの状態を取得
+#[tauri::command]
+pub async fn get_portable_info() -> CommandResult<PortableInfo> {
+    Ok(portable::info())
+}
+
+/// 次回の起動からポータブルモードを有効・無効にする
+///
+/// `dir` は保存先フォルダ（実行ファイルのフォルダからの相対パスも可。省略時は `AutoSight Data`）。
+/// 反映にはアプリの再起動が必要。
+#[tauri::command]
+pub async fn set_portable_mode(enabled: bool, dir: Option<String>) -> CommandResult<PortableInfo> {
+    Ok(portable::set_enabled(enabled, dir.as_deref())?)
+}
+
+/// フロントエンドが tauri-plugin-store で開くストアファイルのパス
+///
+/// ポータブルモードではバックエンドと同じ保存先フォルダのストアを開くために使用する。
+#[tauri::command]
+pub async fn get_store_path() -> CommandResult<String> {
+    Ok(portable::store_path(portable::STORE_NAME)
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// 任意のURLからファイルをダウンロード
+///
+/// プロバイダーのないメーカーについて、ユーザーが見つけたURLから取得する。
+/// 保存先ルール・ファイル名テンプレートは一括ダウンロードと同じで、結果はダウンロード履歴に記録する。
+/// メーカーのプロバイダーがある場合は、その既定テンプレート（命名規則）を使用する。
+#[tauri::command]
+pub async fn download_from_url(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batch: State<'_, BatchState>,
+    request: UrlDownloadRequest,
+) -> CommandResult<UrlDownloadResult> {
+    let provider = registry.load().get_provider(&request.manufacturer);
+    let settings = settings::load(&app)?;
+    let client = http_client(&settings)?;
+
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        Some(&request.dest_dir),
+        request.project_id.as_deref(),
+        None,
+    )?;
+    let dest_dir =
+        filename::render_dir(&dest_dir, &[("manufacturer", Some(&request.manufacturer))]);
+    batch.register_dest_dir(&dest_dir);
+
+    let asset_dir = asset_dest_dir(&dest_dir, request.asset_type, &settings.destination);
+    let filename_options = settings.filename_options();
+    let downloaded = direct::download(
+        &client,
+        &request,
+        &asset_dir,
+        settings.destination.overwrite_existing,
+        |original_filename, bytes| {
+            // IESファイルは内容（色温度・ビーム角・器具光束）もファイル名に使用できる
+            let photometry = (request.asset_type == AssetType::Ies)
+                .then(|| photometry::parse_ies(&String::from_utf8_lossy(bytes)).ok())
+                .flatten()
+                .map(|ies| ies.filename_values());
+            let context = FilenameContext {
+                spec_no: &request.spec_no,
+                manufacturer: &request.manufacturer,
+                model_number: &request.model_number,
+                psu: None,
+                original_filename,
+                photometry,
+            };
+            let filename = asset_filename(
+                provider.as_deref(),
+                &filename_options,
+                request.asset_type,
+                &context,
+            );
+            match &filename_options.reserved {
+                Some(reserved) => reserved.reserve(&asset_dir, &filename),
+                None => filename,
+            }
+        },
+    )
+    .await;
+
+    let _ = history::append(
+        &app,
+        vec![HistoryEntry {
+            project_id: request.project_id.clone(),
+            spec_no: request.spec_no.clone(),
+            manufacturer: request.manufacturer.clone(),
+            model_number: request.model_number.clone(),
+            psu: None,
+            asset_type: request.asset_type,
+            success: downloaded.result.success,
+            file_path: downloaded.result.file_path.clone(),
+            original_filename: downloaded.result.original_filename.clone(),
+            sha256: downloaded.sha256.clone(),
+            source: downloaded.result.source.clone(),
+            provider: None,
+            error: downloaded.result.error.clone(),
+            downloaded_at: chrono::Utc::now(),
+        }],
+    );
+
+    Ok(downloaded)
+}
+
+/// IESファイルを手動指定のURLからダウンロード
+///
+/// 自動の照合で誤った配光のファイルが選ばれる場合や、代理店のページにのみ掲載されている場合に、
+/// 利用者が指定したURL（IESファイルまたはZIP）から取得する。ZIPの場合は型番に最も一致する
+/// IESファイルを取り出す。ファイル名は一括ダウンロードと同じくプロバイダーの命名規則で生成し、
+/// 結果は履歴に記録する。取得できたURLは手動指定の取得元として登録し、以降の一括ダウンロードでも
+/// プロバイダーの照合の代わりに使用する。
+#[tauri::command]
+pub async fn download_ies_from_url(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batch: State<'_, BatchState>,
+    item: BatchDownloadItem,
+    url: String,
+    dest_dir: Option<String>,
+    project_id: Option<String>,
+) -> CommandResult<DownloadResult> {
+    direct::validate_url(&url)?;
+    let provider = registry
+        .load()
+        .get_provider_for(&item.manufacturer, item.provider.as_deref())
+        .ok_or_else(|| {
+            CommandError::new(
+                ErrorCode::ProviderNotFound,
+                format!("No provider for manufacturer: {}", item.manufacturer),
+            )
+        })?;
+
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        dest_dir.as_deref(),
+        project_id.as_deref(),
+        None,
+    )?;
+    let dest_dir = item_dest_dir(&dest_dir, &item);
+    tokio::fs::create_dir_all(longpath::extended(&dest_dir))
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    batch.register_dest_dir(&dest_dir);
+
+    let item = BatchDownloadItem {
+        source_url: Some(url.trim().to_string()),
+        ..item
+    };
+    let result = download_item_asset(
+        provider.as_ref(),
+        &item,
+        AssetType::Ies,
+        &dest_dir,
+        &settings.destination,
+        &settings.filename_options(),
+        &http_client(&settings)?,
+        &CancelToken::new(),
+    )
+    .await;
+
+    if result.success {
+        manual_source::set(
+            &app,
+            project_id.as_deref(),
+            &item.spec_no,
+            &item.model_number,
+            &url,
+        )?;
+    }
+    let assets = [AssetDownloadResult {
+        asset_type: AssetType::Ies,
+        result: result.clone(),
+        provider: result.success.then(|| provider.id().to_string()),
+    }];
+    if let Err(e) = history::append(&app, history_entries(project_id.as_deref(), &item, &assets)) {
+        tracing::error!(error = %e, "failed to save download history");
+    }
+    Ok(result)
+}
+
+/// プロジェクトの手動指定の取得元を取得（`project_id` 省略時はプロジェクト未指定の取得元）
+#[tauri::command]
+pub async fn get_manual_sources(
+    app: AppHandle,
+    project_id: Option<String>,
+) -> CommandResult<ManualSources> {
+    Ok(manual_source::load(&app, project_id.as_deref()))
+}
+
+/// 手動指定の取得元の登録を削除（以降の一括ダウンロードはプロバイダーの照合で取得する）
+///
+/// 戻り値: 更新後の取得元
+#[tauri::command]
+pub async fn remove_manual_source(
+    app: AppHandle,
+    project_id: Option<String>,
+    spec_no: String,
+) -> CommandResult<ManualSources> {
+    Ok(manual_source::remove(
+        &app,
+        project_id.as_deref(),
+        &spec_no,
+    )?)
+}
+
+/// ファイル名テンプレートで保存先ファイル名を生成
+///
+/// 設定のテンプレートが未指定の場合は、プロバイダーの既定テンプレート（命名規則）を使用する
+/// （プロバイダーがない場合は [`filename::DEFAULT_TEMPLATE`]）。
+fn asset_filename(
+    provider: Option<&dyn ManufacturerProvider>,
+    options: &FilenameOptions,
+    asset_type: AssetType,
+    context: &FilenameContext,
+) -> String {
+    let template = options.template.as_deref().unwrap_or_else(|| {
+        provider.map_or(filename::DEFAULT_TEMPLATE, |p| {
+            p.default_filename_template(asset_type)
+        })
+    });
+    let filename = filename::render(template, asset_type, context);
+    if options.halfwidth_alphanumerics {
+        filename::to_halfwidth_alphanumerics(&filename)
+    } else {
+        filename
+    }
+}
+
+/// 1アイテム分のアセットをダウンロードし、ファイル名テンプレートでリネーム
+///
+/// 一時ファイル名でダウンロードした後、サーバーから取得した元ファイル名を使って
+/// 最終的なファイル名にリネームする。ネットワーク共有への保存では一時ファイルをローカルに作成し、
+/// 完成したファイルを共有にコピーする（切断された場合は再接続して再試行する）。
+/// オフラインモードでは、IESファイルはIESライブラリの型番が一致するファイルを使用する。
+///
+/// 型番が見つからず、後継品の対応表に記録がある場合は後継品の型番で取得し直す
+/// （結果に `SuccessorSubstituted` の警告を付け、利用者が確認できるようにする）。
+/// `client` は手動指定の取得元（URL）からの取得に使用する（[`http_client`]）。
+#[allow(clippy::too_many_arguments)]
+async fn download_item_asset(
+    provider: &dyn ManufacturerProvider,
+    item: &BatchDownloadItem,
+    asset_type: AssetType,
+    dest_dir: &str,
+    destination: &DestinationSettings,
+    filename_options: &FilenameOptions,
+    client: &reqwest::Client,
+    cancel: &CancelToken,
+) -> DownloadResult {
+    let result = download_item_asset_as(
+        provider,
+        item,
+        asset_type,
+        dest_dir,
+        destination,
+        filename_options,
+        client,
+        cancel,
+    )
+    .await;
+    // 手動指定の取得元は後継品で取得し直さない
+    if result.success
+        || cancel.is_cancelled()
+        || item.source_url.is_some()
+        || !result
+            .error
+            .as_deref()
+            .is_some_and(|e| succession::is_not_found(result.code.unwrap_or(ErrorCode::Unknown), e))
+    {
+        return result;
+    }
+    let Some(successor) = succession::successor(provider.id(), &item.model_number) else {
+        return result;
+    };
+
+    tracing::info!(model_number = %item.model_number, %successor, "retrying with successor");
+    // 元の型番のPSUは後継品に合うとは限らないため、型番のみで取得する
+    let successor_item = BatchDownloadItem {
+        model_number: successor.clone(),
+        psu: None,
+        ..item.clone()
+    };
+    let retried = download_item_asset_as(
+        provider,
+        &successor_item,
+        asset_type,
+        dest_dir,
+        destination,
+        filename_options,
+        client,
+        cancel,
+    )
+    .await;
+    if !retried.success {
+        return result;
+    }
+    retried.with_warning(DownloadWarning::new(
+        DownloadWarningKind::SuccessorSubstituted,
+        format!(
+            "{} not found, used successor {} instead",
+            item.model_number, successor
+        ),
+    ))
+}
+
+/// 1アイテム分のアセットを、アイテムの型番のままダウンロードしてリネーム
+#[allow(clippy::too_many_arguments)]
+async fn download_item_asset_as(
+    provider: &dyn ManufacturerProvider,
+    item: &BatchDownloadItem,
+    asset_type: AssetType,
+    dest_dir: &str,
+    destination: &DestinationSettings,
+    filename_options: &FilenameOptions,
+    client: &reqwest::Client,
+    cancel: &CancelToken,
+) -> DownloadResult {
+    if !provider.supported_assets().contains(&asset_type) {
+        return DownloadResult::failure(not_provided(provider.display_name(), asset_type));
+    }
+
+    let spec_no = filename::sanitize_filename(&item.spec_no);
+    let on_share = network_share::is_share_path(Path::new(dest_dir));
+    let temp_dir = if on_share {
+        match network_share::staging_dir() {
+            Ok(dir) => dir.to_string_lossy().into_owned(),
+            Err(e) => return DownloadResult::failure(e),
+        }
+    } else {
+        dest_dir.to_string()
+    };
+    // 保存先に移すまで保持する（移さなかった一時ファイルは破棄時に削除される）
+    let temp_file = match create_temp_file(temp_dir, spec_no, asset_type).await {
+        Ok(temp_file) => temp_file,
+        Err(e) => return DownloadResult::failure_with_code(ErrorCode::FileSystem, e),
+    };
+    let temp_path = temp_file.to_string_lossy().into_owned();
+
+    report_phase(DownloadPhase::Lookup, None, None);
+    let downloaded = match asset_type {
+        AssetType::Ies if item.source_url.is_some() => {
+            let url = item.source_url.as_deref().unwrap_or_default();
+            cancel
+                .run(direct::download_ies_to(
+                    client,
+                    url,
+                    &item.model_number,
+                    &temp_path,
+                ))
+                .await
+        }
+        AssetType::Ies if offline::is_enabled() => {
+            let (model_number, dest) = (item.model_number.clone(), temp_path.clone());
+            run_blocking(move || Ok(offline::copy_ies(&model_number, &dest))).await
+        }
+        // ZIP内のファイルを指定した場合は、自動で選んだファイルのキャッシュを使わない
+        AssetType::Ies if zip_member().is_some() => {
+            provider
+                .download_ies_file(&item.model_number, item.psu.as_deref(), &temp_path, cancel)
+                .await
+        }
+        AssetType::Ies => match restore_cached(provider.id(), item, &temp_path).await {
+            Some(cached) => Ok(cached),
+            None => {
+                provider
+                    .download_ies_file(&item.model_number, item.psu.as_deref(), &temp_path, cancel)
+                    .await
+            }
+        },
+        _ => {
+            provider
+                .download_asset(
+                    &item.model_number,
+                    item.psu.as_deref(),
+                    asset_type,
+                    &temp_path,
+                    cancel,
+                )
+                .await
+        }
+    };
+
+    match downloaded {
+        Ok(mut r) => {
+            let converted_from = r.converted_from.take();
+            // エラーページや途中で切れたファイルを保存先に残さない
+            if r.success && asset_type == AssetType::Ies {
+                let temp = longpath::extended(&temp_path);
+                let validated = match tokio::fs::read(&temp).await {
+                    Ok(bytes) => run_blocking(move || photometry::validate_ies(&bytes)).await,
+                    Err(e) => Err(format!("Failed to read downloaded file: {}", e)),
+                };
+                match validated {
+                    Ok(metadata) => {
+                        r.ies_metadata = Some(metadata);
+                        if !r.from_cache
+                            && !offline::is_enabled()
+                            && item.source_url.is_none()
+                            && zip_member().is_none()
+                        {
+                            store_cached(provider.id(), item, temp, r.clone()).await;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tokio::fs::remove_file(&temp).await;
+                        let source = r.source.take();
+                        let timing = r.timing;
+                        r = DownloadResult::failure(e);
+                        r.source = source;
+                        r.timing = timing;
+                    }
+                }
+            }
+            if r.success {
+                report_phase(DownloadPhase::Renaming, None, None);
+                // IESファイルは内容（色温度・ビーム角・器具光束）もファイル名に使用できる
+                let photometry = match asset_type {
+                    AssetType::Ies => {
+                        let temp = longpath::extended(&temp_path);
+                        run_blocking(move || {
+                            Ok::<_, String>(photometry::read_filename_values(&temp))
+                        })
+                        .await
+                        .ok()
+                        .flatten()
+                    }
+                    _ => None,
+                };
+                let context = FilenameContext {
+                    spec_no: &item.spec_no,
+                    manufacturer: provider.display_name(),
+                    model_number: &item.model_number,
+                    psu: item.psu.as_deref(),
+                    original_filename: r.original_filename.as_deref(),
+                    photometry,
+                };
+                let filename =
+                    asset_filename(Some(provider), filename_options, asset_type, &context);
+                // 同じバッチの別のアイテムが保存したファイルは上書きしない
+                let filename = match &filename_options.reserved {
+                    Some(reserved) => reserved.reserve(dest_dir, &filename),
+                    None => filename,
+                };
+                let final_path = format!("{}/{}", dest_dir, filename);
+
+                // ファイルをリネーム（上書きしない設定の場合は既存ファイルを残す）
+                let temp = longpath::extended(&temp_path);
+                let dest = longpath::extended(&final_path);
+                if !destination.overwrite_existing
+                    && tokio::fs::try_exists(&dest).await.unwrap_or(false)
+                {
+                    let _ = tokio::fs::remove_file(&temp).await;
+                    r = DownloadResult::failure_with_code(
+                        ErrorCode::FileExists,
+                        format!("File already exists: {}", final_path),
+                    );
+                } else if on_share {
+                    let copied = run_blocking(move || {
+                        network_share::copy_to_share(&temp, &dest).map_err(|e| e.to_string())
+                    })
+                    .await;
+                    match copied {
+                        Ok(()) => r.file_path = Some(final_path),
+                        Err(e) => {
+                            r = DownloadResult::failure_with_code(
+                                ErrorCode::FileSystem,
+                                format!("Failed to copy file to network share: {}", e),
+                            )
+                        }
+                    }
+                } else if let Err(e) = tokio::fs::rename(&temp, &dest).await {
+                    r = DownloadResult::failure_with_code(
+                        ErrorCode::FileSystem,
+                        format!("Failed to rename file: {}", e),
+                    );
+                } else {
+                    r.file_path = Some(final_path);
+                }
+            }
+            if let Some(source) = converted_from {
+                r.converted_from =
+                    move_converted_source(&source, r.file_path.as_deref(), on_share).await;
+            }
+            r
+        }
+        Err(e) => DownloadResult::failure(e),
+    }
+}
+
+/// ダウンロード中の一時ファイルを作成
+///
+/// 同時に実行する他のアイテム・他のバッチと重ならないよう、試行ごとに別の名前で作成する。
+/// 戻り値を破棄すると、保存先に移さなかった一時ファイルは削除される。
+async fn create_temp_file(
+    dir: String,
+    spec_no: String,
+    asset_type: AssetType,
+) -> Result<tempfile::TempPath, String> {
+    run_blocking(move || {
+        let dir = longpath::extended(&dir);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+        let suffix = match asset_type {
+            AssetType::Ies => ".ies",
+            _ => ".download",
+        };
+        tempfile::Builder::new()
+            .prefix(&format!("temp_{}_", spec_no))
+            .suffix(suffix)
+            .tempfile_in(&dir)
+            .map(tempfile::NamedTempFile::into_temp_path)
+            .map_err(|e| format!("Failed to create temporary file: {}", e))
+    })
+    .await
+}
+
+/// ダウンロードキャッシュからIESファイルを `dest_path` に復元（ファイルのコピーは専用スレッドで行う）
+async fn restore_cached(
+    provider_id: &str,
+    item: &BatchDownloadItem,
+    dest_path: &str,
+) -> Option<DownloadResult> {
+    let (provider_id, model_number, psu, dest_path) = (
+        provider_id.to_string(),
+        item.model_number.clone(),
+        item.psu.clone(),
+        dest_path.to_string(),
+    );
+    run_blocking(move || {
+        Ok::<_, String>(download_cache::restore(
+            &provider_id,
+            &model_number,
+            psu.as_deref(),
+            &dest_path,
+        ))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// 検証済みのIESファイルをダウンロードキャッシュに保存（ファイルのコピーは専用スレッドで行う）
+async fn store_cached(
+    provider_id: &str,
+    item: &BatchDownloadItem,
+    path: PathBuf,
+    result: DownloadResult,
+) {
+    let (provider_id, model_number, psu) = (
+        provider_id.to_string(),
+        item.model_number.clone(),
+        item.psu.clone(),
+    );
+    let _ = run_blocking(move || {
+        download_cache::store(&provider_id, &model_number, psu.as_deref(), &path, &result);
+        Ok::<_, String>(())
+    })
+    .await;
+}
+
+/// 変換元のファイル（LDT）を、変換後のファイルと同じ名前（拡張子のみ異なる）で保存先に移す
+///
+/// 戻り値は移動後のパス。変換後のファイルを保存できなかった場合は変換元も削除し、None を返す。
+async fn move_converted_source(
+    source: &str,
+    converted_path: Option<&str>,
+    on_share: bool,
+) -> Option<String> {
+    let temp = longpath::extended(source);
+    let Some(converted_path) = converted_path else {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return None;
+    };
+    let extension = Path::new(source)
+        .extension()?
+        .to_string_lossy()
+        .into_owned();
+    let final_path = Path::new(converted_path)
+        .with_extension(extension)
+        .to_string_lossy()
+        .into_owned();
+    let dest = longpath::extended(&final_path);
+    let moved = if on_share {
+        run_blocking(move || {
+            let copied = network_share::copy_to_share(&temp, &dest).map_err(|e| e.to_string());
+            let _ = std::fs::remove_file(&temp);
+            copied
+        })
+        .await
+    } else {
+        tokio::fs::rename(&temp, &dest)
+            .await
+            .map_err(|e| e.to_string())
+    };
+    match moved {
+        Ok(()) => Some(final_path),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to save converted source file");
+            None
+        }
+    }
+}
+
+/// アセット種別ごとの保存先ディレクトリ
+///
+/// サブフォルダを使わない設定の場合は、すべて保存先ディレクトリ直下に保存する。
+fn asset_dest_dir(
+    dest_dir: &str,
+    asset_type: AssetType,
+    destination: &DestinationSettings,
+) -> String {
+    match asset_type.subdir().filter(|_| destination.asset_subdirs) {
+        Some(subdir) => format!("{}/{}", dest_dir, subdir),
+        None => dest_dir.to_string(),
+    }
+}
+
+/// 指定アセットに、プロバイダーが一緒に取得するアセットを加える（重複は除く）
+fn with_companion_assets(
+    provider: Option<&dyn ManufacturerProvider>,
+    asset_types: &[AssetType],
+) -> Vec<AssetType> {
+    let mut result: Vec<AssetType> = Vec::new();
+    for &asset_type in asset_types {
+        let companions = provider
+            .map(|p| p.companion_assets(asset_type))
+            .unwrap_or_default();
+        for asset_type in std::iter::once(asset_type).chain(companions) {
+            if !result.contains(&asset_type) {
+                result.push(asset_type);
+            }
+        }
+    }
+    result
+}
+
+/// 1アイテム分の指定アセットを順にダウンロード
+///
+/// プロバイダーが一緒に取得するアセット（コイズミ照明の仕様書等）も同じ処理で取得する。
+/// メーカー欄に複数のメーカーがある場合（[`ProviderRegistry::get_providers_for`]）は、
+/// アセットごとに取得できるまで `providers` の順に試す（すべて失敗した場合は最初のエラー）。
+/// `cancel` で中断が指示された場合、実行中のアセットは中断する（結果は `CANCELLED` のエラー）。
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn download_item_assets(
+    providers: &[Arc<dyn ManufacturerProvider>],
+    item: &BatchDownloadItem,
+    asset_types: &[AssetType],
+    dest_dir: &str,
+    destination: &DestinationSettings,
+    filename_options: &FilenameOptions,
+    client: &reqwest::Client,
+    cancel: CancelToken,
+) -> Vec<AssetDownloadResult> {
+    let asset_types = with_companion_assets(providers.first().map(|p| p.as_ref()), asset_types);
+    let dest_dir = item_dest_dir(dest_dir, item);
+    let mut assets = Vec::new();
+    for asset_type in asset_types {
+        let dir = asset_dest_dir(&dest_dir, asset_type, destination);
+        let started = Instant::now();
+        let mut first_failure = None;
+        let mut supplied = None;
+        for provider in providers {
+            let result = download_item_asset(
+                provider.as_ref(),
+                item,
+                asset_type,
+                &dir,
+                destination,
+                filename_options,
+                client,
+                &cancel,
+            )
+            .await;
+            if result.success {
+                supplied = Some((provider.id().to_string(), result));
+                break;
+            }
+            first_failure.get_or_insert(result);
+            if cancel.is_cancelled() {
+                break;
+            }
+        }
+        let (provider, mut result) = match (supplied, first_failure) {
+            (Some((id, result)), _) => (Some(id), result),
+            (None, Some(result)) => (None, result),
+            (None, None) => (
+                None,
+                DownloadResult::failure(no_provider(&item.manufacturer)),
+            ),
+        };
+        result.timing.total_ms = started.elapsed().as_millis() as u64;
+        assets.push(AssetDownloadResult {
+            asset_type,
+            result,
+            provider,
+        });
+    }
+    assets
+}
+
+/// 取得したIESファイルを出力形式にあわせてEULUMDAT（LDT）に変換
+///
+/// LDTはIESファイルと同じ名前（拡張子のみ異なる）で保存し、LDTのみの場合はIESファイルを削除して
+/// 結果の保存先をLDTにする。変換できない場合はIESファイルを残し、警告を付ける。
+fn convert_photometry_assets(
+    assets: &mut [AssetDownloadResult],
+    format: PhotometryFormat,
+    model_number: &str,
+) {
+    if !format.writes_ldt() {
+        return;
+    }
+    for asset in assets
+        .iter_mut()
+        .filter(|a| a.asset_type == AssetType::Ies && a.result.success)
+    {
+        let Some(path) = asset.result.file_path.clone() else {
+            continue;
+        };
+        let ldt_path = photometry::converted_path(Path::new(&path))
+            .to_string_lossy()
+            .into_owned();
+        let converted = photometry::convert_file(
+            &longpath::extended(&path),
+            &longpath::extended(&ldt_path),
+            model_number,
+        );
+        match converted {
+            Ok(()) if format.keeps_ies() => {}
+            Ok(()) => {
+                let _ = std::fs::remove_file(longpath::extended(&path));
+                asset.result.file_path = Some(ldt_path);
+            }
+            Err(e) => asset.result.warnings.push(DownloadWarning::new(
+                DownloadWarningKind::LdtConversionFailed,
+                format!("Failed to convert to LDT: {}", e),
+            )),
+        }
+    }
+}
+
+/// バッチの開始時に待機中として記録するSpec No.
+fn spec_nos(items: &[BatchDownloadItem]) -> impl Iterator<Item = &str> {
+    items.iter().map(|item| item.spec_no.as_str())
+}
+
+/// バッチのイベントを通知
+///
+/// バッチの開始時に呼び出し元がチャネルを指定した場合はチャネルにのみ送信し、
+/// それ以外はバッチ固有のイベント（`{event}:{batch_id}`）を発火する。
+fn emit_batch_event(app: &AppHandle, batch: &Batch, event: BatchEvent) {
+    if batch.send(&event) {
+        return;
+    }
+    match event {
+        BatchEvent::Progress(payload) => emit_named(app, batch, "download-progress", payload),
+        BatchEvent::Paused(paused) => emit_named(app, batch, "download-paused", paused),
+        BatchEvent::Finished(payload) => emit_named(app, batch, "download-finished", payload),
+        BatchEvent::DecisionRequired(payload) => {
+            emit_named(app, batch, "download-decision-required", payload)
+        }
+    }
+}
+
+/// バッチ固有のイベント（`{event}:{batch_id}`）を発火
+///
+/// 既定のバッチでは従来のイベント名でも発火する。
+fn emit_named<S: Serialize + Clone>(app: &AppHandle, batch: &Batch, event: &str, payload: S) {
+    if batch.is_default() {
+        let _ = app.emit(event, payload.clone());
+    }
+    let _ = app.emit(&batch.event_name(event), payload);
+}
+
+/// ダウンロード進捗をバッチ状態に記録し、`download-progress:{batch_id}` イベントで通知
+fn notify_progress(
+    app: &AppHandle,
+    batch: &Batch,
+    spec_no: &str,
+    status: &str,
+    error: Option<String>,
+) {
+    let mut event = batch.progress_event(spec_no, status, error);
+    batch.update(&mut event);
+    // ストアファイルの保存は専用スレッドで行い、同時に処理中の他のアイテムを止めない
+    let (handle, recorded) = (app.clone(), event.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = session::record_status(&handle, &recorded) {
+            tracing::warn!(error = %e, "failed to save session");
+        }
+    });
+    emit_batch_event(app, batch, BatchEvent::Progress(event));
+    #[cfg(desktop)]
+    tray::update(app);
+}
+
+/// 受信中の進捗イベントの最短間隔
+const PHASE_EVENT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// 処理中のアイテムの段階・受信したバイト数を `download-progress:{batch_id}` イベントで通知する
+/// 通知先
+///
+/// 受信中の通知は `PHASE_EVENT_INTERVAL` ごとに間引く（段階が変わった場合はすぐに通知する）。
+/// 処理中の状態はセッションに保存しないため、バッチ状態の更新とイベントの発火のみ行う。
+fn phase_notifier(app: &AppHandle, batch: &Arc<Batch>, spec_no: &str) -> PhaseNotifier {
+    let (app, batch, spec_no) = (app.clone(), batch.clone(), spec_no.to_string());
+    let last = std::sync::Mutex::new(None::<(DownloadPhase, Instant)>);
+    Arc::new(move |phase, bytes, total_bytes| {
+        {
+            let mut last = last.lock().unwrap();
+            if last.is_some_and(|(last_phase, at)| {
+                last_phase == phase && at.elapsed() < PHASE_EVENT_INTERVAL
+            }) {
+                return;
+            }
+            *last = Some((phase, Instant::now()));
+        }
+        let mut event = batch
+            .progress_event(&spec_no, "processing", None)
+            .with_phase(phase, bytes, total_bytes);
+        batch.update(&mut event);
+        emit_batch_event(&app, &batch, BatchEvent::Progress(event));
+    })
+}
+
+/// 処理中のアイテムの判断の依頼先
+///
+/// アイテムを判断待ちとして記録し、`download-decision-required:{batch_id}` イベントで候補を通知して、
+/// `resolve_item_choice` で選ばれるまで待つ。
+fn decision_resolver(app: &AppHandle, batch: &Arc<Batch>, spec_no: &str) -> DecisionResolver {
+    let (app, batch, spec_no) = (app.clone(), batch.clone(), spec_no.to_string());
+    Arc::new(move |decision| {
+        let waiting = batch.wait_for_decision(&spec_no);
+        let mut event = batch
+            .progress_event(&spec_no, "processing", None)
+            .with_phase(DownloadPhase::AwaitingDecision, None, None);
+        event.decision = Some(decision.clone());
+        batch.update(&mut event);
+        emit_batch_event(&app, &batch, BatchEvent::Progress(event));
+        emit_batch_event(
+            &app,
+            &batch,
+            BatchEvent::DecisionRequired(DecisionRequiredEvent {
+                batch_id: batch.id().to_string(),
+                spec_no: spec_no.clone(),
+                decision,
+            }),
+        );
+        tracing::info!(batch_id = batch.id(), spec_no, "waiting for decision");
+        waiting.boxed()
+    })
+}
+
+/// バッチを一時停止・再開し、`download-paused:{batch_id}` イベント（一時停止中か）で通知
+///
+/// 戻り値: 状態が変わった場合は true
+pub(crate) fn set_batch_paused(app: &AppHandle, batch: &Batch, paused: bool) -> bool {
+    if !batch.set_paused(paused) {
+        return false;
+    }
+    tracing::info!(batch_id = batch.id(), paused, "batch pause toggled");
+    emit_batch_event(app, batch, BatchEvent::Paused(paused));
+    #[cfg(desktop)]
+    tray::update(app);
+    true
+}
+
+/// バッチの終了を記録し、`download-finished:{batch_id}` イベントで通知
+///
+/// 設定で有効な場合は、1件以上保存できていれば保存先ディレクトリをFinder/エクスプローラーで開く。
+/// 開くのはバッチの保存先として記録したディレクトリに限る。
+/// また、他の作業中でも完了が分かるようにデスクトップ通知を表示する。
+/// クラウドへのアップロードが有効な場合は、バックグラウンドで保存先ディレクトリをアップロードする。
+fn notify_finished(
+    app: &AppHandle,
+    batch: &Batch,
+    settings: &Settings,
+    mut event: BatchFinishedEvent,
+) {
+    batch.finish();
+    #[cfg(desktop)]
+    tray::update(app);
+    if settings.destination.open_when_finished && event.success_count > 0 {
+        let opened = app
+            .state::<BatchState>()
+            .resolve_downloaded_path(&event.dest_dir)
+            .and_then(|dir| {
+                if !dir.is_dir() {
+                    return Err(format!("Not a directory: {}", event.dest_dir));
+                }
+                app.opener()
+                    .open_path(dir.to_string_lossy(), None::<&str>)
+                    .map_err(|e| format!("Failed to open folder: {}", e))
+            });
+        match opened {
+            Ok(()) => event.opened_dest_dir = true,
+            Err(e) => tracing::warn!(error = %e, "failed to open destination directory"),
+        }
+    }
+    if settings.notify_when_finished {
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(&app.package_info().name)
+            .body(event.summary(settings.locale))
+            .show()
+        {
+            tracing::warn!(error = %e, "failed to show notification");
+        }
+    }
+    if settings.cloud_upload.enabled && event.success_count > 0 {
+        upload_in_background(app, settings, &event.dest_dir);
+    }
+    emit_batch_event(app, batch, BatchEvent::Finished(event));
+}
+
+/// 保存先ディレクトリをクラウドにアップロードし、`cloud-upload-finished` イベントで通知
+///
+/// トークンがない等でアップロードを開始できなかった場合は `cloud-upload-failed` イベント
+/// （エラーメッセージ）で通知する。
+fn upload_in_background(app: &AppHandle, settings: &Settings, dest_dir: &str) {
+    let app = app.clone();
+    let settings = settings.clone();
+    let dir = std::path::PathBuf::from(dest_dir);
+    tauri::async_runtime::spawn(async move {
+        let uploaded = match http_client(&settings) {
+            Ok(client) => cloud::upload_dir(&app, &client, &settings.cloud_upload, &dir).await,
+            Err(e) => Err(e),
+        };
+        match uploaded {
+            Ok(result) => {
+                tracing::info!(
+                    uploaded = result.uploaded_count,
+                    failed = result.failure_count,
+                    "cloud upload finished"
+                );
+                let _ = app.emit("cloud-upload-finished", result);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "cloud upload failed");
+                let _ = app.emit("cloud-upload-failed", e);
+            }
+        }
+    });
+}
+
+/// 1アイテム分のアセットの結果を履歴エントリに変換
+fn history_entries(
+    project_id: Option<&str>,
+    item: &BatchDownloadItem,
+    assets: &[AssetDownloadResult],
+) -> Vec<HistoryEntry> {
+    let downloaded_at = chrono::Utc::now();
+    assets
+        .iter()
+        .map(|a| HistoryEntry {
+            project_id: project_id.map(str::to_string),
+            spec_no: item.spec_no.clone(),
+            manufacturer: item.manufacturer.clone(),
+            model_number: item.model_number.clone(),
+            psu: item.psu.clone(),
+            asset_type: a.asset_type,
+            success: a.result.success,
+            file_path: a.result.file_path.clone(),
+            original_filename: a.result.original_filename.clone(),
+            sha256: a.result.file_path.as_deref().and_then(file_sha256),
+            source: a.result.source.clone(),
+            provider: a.provider.clone(),
+            error: a.result.error.clone(),
+            downloaded_at,
+        })
+        .collect()
+}
+
+/// 手動指定の取得元の登録がある行に、取得元のURLを設定（リクエストで指定済みの場合はそのまま）
+fn with_manual_source(sources: &ManualSources, item: &BatchDownloadItem) -> BatchDownloadItem {
+    let mut item = item.clone();
+    if item.source_url.is_none() {
+        item.source_url = sources
+            .url_for(&item.spec_no, &item.model_number)
+            .map(str::to_string);
+    }
+    item
+}
+
+/// 保存したファイルのSHA-256（読み込めない場合は None）
+fn file_sha256(path: &str) -> Option<String> {
+    std::fs::read(longpath::extended(Path::new(path)))
+        .ok()
+        .map(|bytes| direct::sha256_hex(&bytes))
+}
+
+/// 同じ内容のIESファイルを取得したアイテムに重複の印を付ける（先に保存したファイルが元。結果はアイテムと同じ順）
+fn flag_duplicates(results: &mut [SingleDownloadResult], scan_existing: bool) {
+    let ies_path = |result: &SingleDownloadResult| {
+        result
+            .assets
+            .iter()
+            .find(|a| a.asset_type == AssetType::Ies && a.result.success)
+            .and_then(|a| a.result.file_path.clone())
+    };
+    let downloaded: Vec<Option<String>> = results
+        .iter()
+        .map(|result| ies_path(result).filter(|_| !result.skipped))
+        .collect();
+    let mut index = DuplicateIndex::new(
+        downloaded.iter().flatten().map(String::as_str),
+        scan_existing,
+    );
+    for (result, path) in results.iter_mut().zip(&downloaded) {
+        let Some(path) = path else {
+            continue;
+        };
+        let Some(sha256) = file_sha256(path) else {
+            continue;
+        };
+        if let Some(original) = index.check(path, &sha256) {
+            tracing::info!(spec_no = %result.spec_no, path, original, "duplicate IES file");
+            result.deduplicated = true;
+            result.duplicate_of = Some(original);
+        }
+    }
+}
+
+/// 一括ダウンロードの1アイテムの結果
+enum ItemStatus {
+    Success,
+    Failure,
+    Cancelled,
+    Skipped,
+}
+
+/// 取得済みのファイル（[`audit::completed_files`]）から、要求したアセットの結果を作る
+///
+/// 1つでも取得済みでないアセットがある場合は None（アイテムを取得し直す）。
+async fn existing_assets(
+    existing_files: &HashMap<(String, AssetType), String>,
+    spec_no: &str,
+    asset_types: &[AssetType],
+) -> Option<Vec<AssetDownloadResult>> {
+    if existing_files.is_empty() || asset_types.is_empty() {
+        return None;
+    }
+    let mut assets = Vec::new();
+    for &asset_type in asset_types {
+        let path = existing_files.get(&(spec_no.to_string(), asset_type))?;
+        let size = tokio::fs::metadata(longpath::extended(path))
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        let mut result = DownloadResult::success(path.clone(), size, None);
+        result.timing = DownloadTiming::default();
+        assets.push(AssetDownloadResult {
+            asset_type,
+            result,
+            provider: None,
+        });
+    }
+    Some(assets)
+}
+
+/// アイテムの完了をチェックポイントに記録（ストアファイルの保存は専用スレッドで行う）
+async fn mark_completed(app: &AppHandle, batch: &Batch, spec_no: &str) {
+    let (app, batch_id, spec_no) = (app.clone(), batch.id().to_string(), spec_no.to_string());
+    if let Err(e) =
+        run_blocking(move || checkpoint::mark_completed(&app, &batch_id, &spec_no)).await
+    {
+        tracing::warn!(error = %e, "failed to update batch checkpoint");
+    }
+}
+
+/// 器具リストの消費電力と異なる出力のIESファイルを取得していないか照合する
+fn wattage_check(item: &BatchDownloadItem, assets: &[AssetDownloadResult]) -> Option<WattageCheck> {
+    let schedule_watts = item.wattage?;
+    let ies_watts = assets
+        .iter()
+        .find(|a| a.asset_type == AssetType::Ies && a.result.success)
+        .and_then(|a| a.result.file_path.as_deref())
+        .and_then(|path| schedule_report::ies_watts(&longpath::extended(path)))?;
+    schedule_report::check_wattage(schedule_watts, ies_watts)
+}
+
+/// 一括ダウンロードの1アイテムの処理結果（集計用）
+struct BatchItemOutcome {
+    status: ItemStatus,
+    /// 集計するプロバイダー（キャンセルされたアイテムは集計しない）
+    provider_id: Option<String>,
+    history: Vec<HistoryEntry>,
+    unit_price: Option<Price>,
+    result: SingleDownloadResult,
+}
+
+/// 一括ダウンロードのループ本体
+///
+/// 各アイテムについて、行ごとに指定されたアセット種別（指定がなければ `default_assets`）を
+/// 取得する。要求したアセットがすべて取得できたアイテムを成功として集計する。
+/// アイテムは最大 [`Batch::max_concurrency`] 件（省略時は設定の同時実行数）を同時に処理する。
+/// [`Batch::uses_cache`] の場合、IESファイルはダウンロードキャッシュにあればそれを使う。
+/// 進捗イベントはアイテムごとに完了した時点で発火する。
+/// キャンセルされたアイテム以外の結果はダウンロード履歴に記録する。
+/// 送信したリクエストと取得結果は保存先フォルダの監査ログに記録する。
+/// `batch` は `BatchState::start` で開始したもの。
+async fn run_batch(
+    app: &AppHandle,
+    registry: &ProviderRegistry,
+    batch: &Arc<Batch>,
+    items: &[BatchDownloadItem],
+    default_assets: &[AssetType],
+    dest_dir: &str,
+    project_id: Option<&str>,
+) -> BatchDownloadResult {
+    let mut results = Vec::new();
+    let mut history_log = Vec::new();
+    let mut success_count = 0;
+    let mut failure_count = 0;
+    let mut cancelled_count = 0;
+    let mut skipped_count = 0;
+    let mut provider_counts: BTreeMap<String, ProviderCounts> = BTreeMap::new();
+    let mut unit_prices = Vec::new();
+    let settings = settings::load(app).unwrap_or_default();
+    let destination = settings.destination.clone();
+    let mut filename_options = settings.filename_options();
+    if let Some(template) = batch.filename_template() {
+        filename_options.template = Some(template);
+    }
+    filename_options.reserved = Some(Arc::default());
+    let client = batch_http_client(&settings);
+    let download_cache_dir = batch
+        .uses_cache()
+        .then(|| cache_dir(app).ok())
+        .flatten()
+        .map(|dir| CacheScope::IesDownload.dir(&dir));
+    // バッチの同時実行数はバッチの開始時に検証・上限に丸め済み
+    let max_concurrency = match batch.max_concurrency() {
+        Some(n) => settings
+            .battery
+            .concurrency(n, power::on_battery() == Some(true)),
+        None => concurrency(&settings),
+    };
+
+    registry.begin_batch();
+    tracing::info!(
+        batch_id = batch.id(),
+        items = items.len(),
+        dest_dir,
+        "batch download started"
+    );
+
+    // 中断した場合に再開できるよう、リクエストを記録しておく
+    let checkpoint = InterruptedBatch::new(batch.id(), items, default_assets, dest_dir, project_id);
+    if let Err(e) = checkpoint::save(app, &checkpoint) {
+        tracing::warn!(error = %e, "failed to save batch checkpoint");
+    }
+    // モバイルではバックグラウンドに移っても実行を続ける（打ち切られた場合は実行中のバッチをすべて中断する）
+    let handle = app.clone();
+    let title = Message::BackgroundDownload { total: items.len() }.text(settings.locale);
+    if let Err(e) = app.background().begin(&title, move || {
+        tracing::warn!("background execution expired, interrupting batch");
+        handle.state::<BatchState>().interrupt_all();
+    }) {
+        tracing::warn!(error = %e, "failed to begin background execution");
+    }
+
+    // 監査ログを作成できなくてもダウンロードは続行する
+    // （保存先がメーカーごとに分かれる場合は、分かれる前のディレクトリに作成する）
+    let root_dir = filename::dir_prefix(dest_dir);
+    // 取得済みのファイルは過去の監査ログから読み込む（今回の監査ログを作成する前に）
+    let existing_files = if batch.skips_existing() {
+        audit::completed_files(Path::new(root_dir))
+    } else {
+        HashMap::new()
+    };
+    let audit_log = match AuditLog::create(Path::new(root_dir)) {
+        Ok(log) => {
+            log.append(&AuditRecord::BatchStarted {
+                timestamp: chrono::Utc::now(),
+                app_version: app.package_info().version.to_string(),
+                items: items.len(),
+            });
+            Some(Arc::new(log))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to create audit log");
+            None
+        }
+    };
+    // 保存先ディレクトリは監査ログの作成時に作成される
+    app.state::<BatchState>().register_dest_dir(root_dir);
+
+    // アイテムは同時に処理し、結果はアイテムと同じ順に集計する
+    // （メーカーサイトごとの同時リクエスト数は設定の `domain_concurrency` と既定の上限で制限される）
+    let outcomes: Vec<BatchItemOutcome> = {
+        let (settings, destination, filename_options, client, audit_log, download_cache_dir) = (
+            &settings,
+            &destination,
+            &filename_options,
+            &client,
+            &audit_log,
+            &download_cache_dir,
+        );
+        let existing_files = &existing_files;
+        let manual_sources = &manual_source::load(app, project_id);
+        let pacer = &StartPacer::default();
+        process_in_order(items.len(), max_concurrency, |i| async move {
+            let item = &items[i];
+            // 手動指定の取得元の登録がある行は、プロバイダーの照合の代わりにそのURLから取得する
+            let item = &with_manual_source(manual_sources, item);
+            let asset_types = item.asset_types.as_deref().unwrap_or(default_assets);
+            let providers =
+                registry.get_providers_for(&item.manufacturer, item.provider.as_deref());
+
+            // 要求したアセットがすべて取得済みのアイテムは取得し直さない
+            if let Some(assets) = existing_assets(existing_files, &item.spec_no, asset_types).await
+            {
+                notify_progress(app, batch, &item.spec_no, "skipped", None);
+                mark_completed(app, batch, &item.spec_no).await;
+                let result = assets
+                    .iter()
+                    .find(|a| a.asset_type == AssetType::Ies)
+                    .or_else(|| assets.first())
+                    .map(|a| a.result.clone())
+                    .unwrap_or_else(no_asset_types);
+                return BatchItemOutcome {
+                    status: ItemStatus::Skipped,
+                    provider_id: None,
+                    history: Vec::new(),
+                    unit_price: cached_price(app, &providers, &[], &item.model_number).await,
+                    result: SingleDownloadResult {
+                        spec_no: item.spec_no.clone(),
+                        model_number: item.model_number.clone(),
+                        result,
+                        assets,
+                        timing: DownloadTiming::default(),
+                        wattage_check: None,
+                        skipped: true,
+                        deduplicated: false,
+                        duplicate_of: None,
+                    },
+                };
+            }
+
+            // バッテリー駆動時はアイテムの開始の間隔を空けて通信量を抑える
+            // （同時に処理するアイテムで共有するため、同時実行数によらず間隔が空く）
+            pacer
+                .wait(
+                    settings
+                        .battery
+                        .item_delay(power::on_battery() == Some(true)),
+                )
+                .await;
+            // 一時停止中は次のアイテムに進まない
+            batch.wait_if_paused().await;
+            // 処理開始イベントを発火
+            notify_progress(app, batch, &item.spec_no, "processing", None);
+
+            let span = tracing::info_span!(
+                "download_item",
+                spec_no = %item.spec_no,
+                manufacturer = %item.manufacturer,
+                model_number = %item.model_number
+            );
+            let started = Instant::now();
+            let notifier = phase_notifier(app, batch, &item.spec_no);
+            let resolver = batch
+                .is_interactive()
+                .then(|| decision_resolver(app, batch, &item.spec_no));
+            let download = |cancel| {
+                let download = download_item_assets(
+                    &providers,
+                    item,
+                    asset_types,
+                    dest_dir,
+                    destination,
+                    filename_options,
+                    client,
+                    cancel,
+                );
+                let download =
+                    with_phase_notifier(notifier, with_decision_resolver(resolver, download));
+                download_cache::scope(download_cache_dir.clone(), download).instrument(span.clone())
+            };
+            let downloaded = match &audit_log {
+                Some(log) => {
+                    let context = AuditContext::new(log.clone(), &item.spec_no);
+                    batch
+                        .run_cancellable(&item.spec_no, |cancel| {
+                            audit::scope(context, download(cancel))
+                        })
+                        .await
+                }
+                None => batch.run_cancellable(&item.spec_no, download).await,
+            };
+
+            // キャンセルされたアイテムは成功・失敗とは別に集計
+            let Some(mut assets) = downloaded else {
+                span.in_scope(|| tracing::info!("cancelled"));
+                if let Some(log) = &audit_log {
+                    log.append(&AuditRecord::Cancelled {
+                        timestamp: chrono::Utc::now(),
+                        spec_no: item.spec_no.clone(),
+                    });
+                }
+                notify_progress(app, batch, &item.spec_no, "cancelled", None);
+                return BatchItemOutcome {
+                    status: ItemStatus::Cancelled,
+                    provider_id: None,
+                    history: Vec::new(),
+                    unit_price: cached_price(app, &providers, &[], &item.model_number).await,
+                    result: SingleDownloadResult {
+                        spec_no: item.spec_no.clone(),
+                        model_number: item.model_number.clone(),
+                        result: DownloadResult::failure(cancelled()),
+                        assets: vec![],
+                        timing: DownloadTiming {
+                            total_ms: started.elapsed().as_millis() as u64,
+                            ..Default::default()
+                        },
+                        wattage_check: None,
+                        skipped: false,
+                        deduplicated: false,
+                        duplicate_of: None,
+                    },
+                };
+            };
+            let (format, model_number) = (batch.output_format(), item.model_number.clone());
+            let assets = run_blocking(move || {
+                convert_photometry_assets(&mut assets, format, &model_number);
+                Ok::<_, String>(assets)
+            })
+            .await
+            .unwrap_or_default();
+
+            let result = assets
+                .iter()
+                .find(|a| a.asset_type == AssetType::Ies)
+                .or_else(|| assets.first())
+                .map(|a| a.result.clone())
+                .unwrap_or_else(no_asset_types);
+            let success = !assets.is_empty() && assets.iter().all(|a| a.result.success);
+            let error = assets.iter().find_map(|a| a.result.error.clone());
+            let mut timing = DownloadTiming::default();
+            for asset in &assets {
+                timing.add(&asset.result.timing);
+            }
+            timing.total_ms = started.elapsed().as_millis() as u64;
+
+            // 複数のメーカーを試した場合は取得できたプロバイダーで集計する
+            let provider_id = assets
+                .iter()
+                .find_map(|a| a.provider.clone())
+                .or_else(|| providers.first().map(|p| p.id().to_string()))
+                .unwrap_or_else(|| "unsupported".to_string());
+            if success {
+                span.in_scope(|| {
+                    tracing::info!(
+                        lookup_ms = timing.lookup_ms,
+                        download_ms = timing.download_ms,
+                        bytes = timing.bytes_transferred,
+                        "downloaded"
+                    )
+                });
+            } else {
+                span.in_scope(|| tracing::warn!(error = error.as_deref(), "download failed"));
+            }
+            if let Some(log) = &audit_log {
+                for asset in &assets {
+                    log.append(&AuditRecord::result(
+                        &item.spec_no,
+                        asset.asset_type,
+                        &asset.result,
+                    ));
+                }
+            }
+
+            // 完了イベントを発火
+            let status = if success { "success" } else { "error" };
+            notify_progress(app, batch, &item.spec_no, status, error);
+            mark_completed(app, batch, &item.spec_no).await;
+
+            // 器具リストの消費電力との照合・履歴のハッシュはファイルを読むため専用スレッドで行う
+            let (history, wattage_check) = {
+                let (item, assets) = (item.clone(), assets.clone());
+                let project_id = project_id.map(str::to_string);
+                run_blocking(move || {
+                    Ok::<_, String>((
+                        history_entries(project_id.as_deref(), &item, &assets),
+                        wattage_check(&item, &assets),
+                    ))
+                })
+                .await
+                .unwrap_or_default()
+            };
+            if wattage_check.as_ref().is_some_and(|check| check.mismatch) {
+                span.in_scope(|| tracing::warn!(?wattage_check, "wattage mismatch"));
+            }
+
+            BatchItemOutcome {
+                status: if success {
+                    ItemStatus::Success
+                } else {
+                    ItemStatus::Failure
+                },
+                provider_id: Some(provider_id),
+                history,
+                unit_price: cached_price(app, &providers, &assets, &item.model_number).await,
+                result: SingleDownloadResult {
+                    spec_no: item.spec_no.clone(),
+                    model_number: item.model_number.clone(),
+                    result,
+                    assets,
+                    timing,
+                    wattage_check,
+                    skipped: false,
+                    deduplicated: false,
+                    duplicate_of: None,
+                },
+            }
+        })
+        .await
+    };
+
+    for outcome in outcomes {
+        let counts = outcome
+            .provider_id
+            .map(|provider_id| provider_counts.entry(provider_id).or_default());
+        match outcome.status {
+            ItemStatus::Success => {
+                success_count += 1;
+                if let Some(counts) = counts {
+                    counts.success += 1;
+                }
+            }
+            ItemStatus::Failure => {
+                failure_count += 1;
+                if let Some(counts) = counts {
+                    counts.failure += 1;
+                }
+            }
+            ItemStatus::Cancelled => cancelled_count += 1,
+            ItemStatus::Skipped => skipped_count += 1,
+        }
+        history_log.extend(outcome.history);
+        unit_prices.push(outcome.unit_price);
+        results.push(outcome.result);
+    }
+    flag_duplicates(&mut results, batch.dedupes_existing());
+
+    // 履歴の保存に失敗してもダウンロード結果は返す
+    if let Err(e) = history::append(app, history_log) {
+        tracing::error!(error = %e, "failed to save download history");
+    }
+    registry.end_batch();
+    tracing::info!(
+        success_count,
+        failure_count,
+        cancelled_count,
+        skipped_count,
+        "batch download finished"
+    );
+    // 他のバッチが実行中の場合はバックグラウンドでの実行を続ける
+    if app.state::<BatchState>().running_count() <= 1 {
+        if let Err(e) = app.background().end() {
+            tracing::warn!(error = %e, "failed to end background execution");
+        }
+    }
+    // 中断した場合は次回の起動時に再開できるようチェックポイントを残す
+    if !batch.is_interrupted() {
+        if let Err(e) = checkpoint::clear(app, batch.id()) {
+            tracing::warn!(error = %e, "failed to clear batch checkpoint");
+        }
+    }
+    notify_finished(
+        app,
+        batch,
+        &settings,
+        BatchFinishedEvent {
+            batch_id: batch.id().to_string(),
+            success_count,
+            failure_count,
+            cancelled_count,
+            dest_dir: root_dir.to_string(),
+            opened_dest_dir: false,
+        },
+    );
+    if settings.telemetry.enabled {
+        record_usage(app, &settings, &provider_counts);
+    }
+
+    // 接続負荷はダウンロードできたIESファイルの入力電力から集計する（結果はアイテムと同じ順）
+    let connected_load =
+        schedule_report::connected_load(items.iter().zip(&results).map(|(item, result)| {
+            LoadItem {
+                manufacturer: &item.manufacturer,
+                area: item.area.as_deref(),
+                quantity: item.quantity.unwrap_or(1),
+                watts: result
+                    .assets
+                    .iter()
+                    .find(|a| a.asset_type == AssetType::Ies && a.result.success)
+                    .and_then(|a| a.result.file_path.as_deref())
+                    .and_then(|path| schedule_report::ies_watts(&longpath::extended(path))),
+            }
+        }));
+
+    // 定価は取得済みの製品情報から集計する（概算見積もり用。結果はアイテムと同じ順）
+    let cost_rollup =
+        schedule_report::cost_rollup(items.iter().zip(unit_prices).map(|(item, unit_price)| {
+            CostItem {
+                spec_no: &item.spec_no,
+                manufacturer: &item.manufacturer,
+                quantity: item.quantity.unwrap_or(1),
+                unit_price,
+            }
+        }));
+
+    let result = BatchDownloadResult {
+        success_count,
+        failure_count,
+        cancelled_count,
+        skipped_count,
+        results,
+        audit_log_path: audit_log.map(|log| log.path().to_string_lossy().to_string()),
+        connected_load,
+        cost_rollup,
+    };
+    // 不具合報告の診断情報バンドルに含めるため、直前の結果として残す
+    if let Err(e) =
+        portable::data_dir(app).and_then(|dir| diagnostics::save_last_batch(&dir, &result))
+    {
+        tracing::warn!(error = %e, "failed to save last batch result");
+    }
+    result
+}
+
+/// 取得済みの製品情報（キャッシュ）から定価を取得（メーカーサイトにはアクセスしない）
+///
+/// メーカー欄に複数のメーカーがある場合は、ファイルを取得できたプロバイダーの製品情報を優先する。
+async fn cached_price(
+    app: &AppHandle,
+    providers: &[Arc<dyn ManufacturerProvider>],
+    assets: &[AssetDownloadResult],
+    model_number: &str,
+) -> Option<Price> {
+    let dir = cache_dir(app).ok()?;
+    let supplier = assets.iter().find_map(|a| a.provider.as_deref());
+    let paths: Vec<PathBuf> = providers
+        .iter()
+        .filter(|p| supplier.is_none_or(|id| p.id() == id))
+        .map(|p| prefetch::cache_path(&dir, p.id(), model_number))
+        .collect();
+    run_blocking(move || {
+        Ok::<_, String>(
+            paths
+                .iter()
+                .find_map(|path| prefetch::load_stale(path))
+                .and_then(|info| info.price),
+        )
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// プロバイダールールの更新を確認する間隔（実際に取得するかは設定の確認間隔で判断する）
+const RULES_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// プロバイダールールの更新を定期的に確認
+///
+/// 設定は確認のたびに読み込む。使用するバージョンが変わった場合はプロバイダーを登録し直し、
+/// `provider-rules-updated` イベントを発火する。オフラインモードの間は確認しない。
+pub fn spawn_rules_updater(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = settings::load(&app).unwrap_or_default();
+            if settings.rules_update.enabled && !settings.offline {
+                let before = rules_update::status(&app).active;
+                let checked = match http_client(&settings) {
+                    Ok(client) => {
+                        rules_update::check_if_due(&app, &client, &settings.rules_update).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match checked {
+                    Ok(status) if status.active != before => {
+                        apply_rules(&app).await;
+                        let _ = app.emit("provider-rules-updated", &status);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(error = %e, "failed to check provider rules update"),
+                }
+            }
+            tokio::time::sleep(RULES_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// 切り替えたプロバイダールールをレジストリに反映
+async fn apply_rules(app: &AppHandle) {
+    let handle = app.clone();
+    match run_blocking(move || reload_providers(&handle)).await {
+        Ok(result) => {
+            for error in &result.errors {
+                tracing::warn!(file = %error.file, error = %error.error, "failed to load provider rule");
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "failed to apply provider rules"),
+    }
+}
+
+/// プロバイダールールの更新状態を取得
+#[tauri::command]
+pub async fn get_rules_status(app: AppHandle) -> CommandResult<RulesStatus> {
+    Ok(rules_update::status(&app))
+}
+
+/// プロバイダールールの更新を今すぐ確認（確認間隔・有効/無効の設定によらず実行する）
+#[tauri::command]
+pub async fn check_rules_update(app: AppHandle) -> CommandResult<RulesStatus> {
+    let settings = settings::load(&app)?;
+    let client = http_client(&settings)?;
+    let before = rules_update::status(&app).active;
+    let status = rules_update::check(&app, &client, &settings.rules_update).await?;
+    if status.active != before {
+        apply_rules(&app).await;
+    }
+    let _ = app.emit("provider-rules-updated", &status);
+    Ok(status)
+}
+
+/// プロバイダールールを直前のバージョンに戻す
+#[tauri::command]
+pub async fn rollback_rules(app: AppHandle) -> CommandResult<RulesStatus> {
+    let handle = app.clone();
+    let status = run_blocking(move || rules_update::rollback(&handle)).await?;
+    apply_rules(&app).await;
+    let _ = app.emit("provider-rules-updated", &status);
+    Ok(status)
+}
+
+/// 利用状況を記録し、送信間隔が経過していればバックグラウンドで送信
+fn record_usage(app: &AppHandle, settings: &Settings, counts: &BTreeMap<String, ProviderCounts>) {
+    if let Err(e) = telemetry::record_batch(app, counts) {
+        tracing::warn!(error = %e, "failed to record usage counts");
+        return;
+    }
+    let Some(endpoint) = settings.telemetry.endpoint.clone() else {
+        return;
+    };
+    let Ok(client) = http_client(settings) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = telemetry::send_if_due(&app, &client, &endpoint).await {
+            tracing::warn!(error = %e, "failed to send usage report");
+        }
+    });
+}
+
+/// 1アイテム分の見積もり結果
+struct ItemEstimate {
+    spec_no: String,
+    supported: bool,
+    resolved: bool,
+    size: Option<u64>,
+    /// 予定している保存先ファイルパス
+    file_paths: Vec<String>,
+}
+
+/// 1アイテム分の保存先ファイル名・IESファイルのURL・サイズを見積もる
+async fn estimate_item(
+    item: BatchDownloadItem,
+    provider: Option<Arc<dyn ManufacturerProvider>>,
+    asset_types: Vec<AssetType>,
+    dest_dir: String,
+    destination: DestinationSettings,
+    filename_options: FilenameOptions,
+) -> ItemEstimate {
+    let Some(provider) = provider else {
+        return ItemEstimate {
+            spec_no: item.spec_no,
+            supported: false,
+            resolved: false,
+            size: None,
+            file_paths: vec![],
+        };
+    };
+
+    // 元ファイル名はダウンロードするまで分からないため、フォールバックの命名規則で予測する
+    let file_paths = asset_types
+        .iter()
+        .filter(|asset_type| provider.supported_assets().contains(asset_type))
+        .map(|&asset_type| {
+            let context = FilenameContext {
+                spec_no: &item.spec_no,
+                manufacturer: provider.display_name(),
+                model_number: &item.model_number,
+                psu: item.psu.as_deref(),
+                original_filename: None,
+                photometry: None,
+            };
+            let filename = asset_filename(
+                Some(provider.as_ref()),
+                &filename_options,
+                asset_type,
+                &context,
+            );
+            format!(
+                "{}/{}",
+                asset_dest_dir(&item_dest_dir(&dest_dir, &item), asset_type, &destination),
+                filename
+            )
+        })
+        .collect();
+
+    let ies_url = provider
+        .fetch_product_info(&item.model_number)
+        .await
+        .ok()
+        .and_then(|info| info.ies_file_url);
+    let size = match &ies_url {
+        Some(url) => provider.fetch_file_size(url).await,
+        None => None,
+    };
+
+    ItemEstimate {
+        spec_no: item.spec_no,
+        supported: true,
+        resolved: ies_url.is_some(),
+        size,
+        file_paths,
+    }
+}
+
+/// 保存先ファイルパスの衝突を検出
+///
+/// 複数のアイテムが同じパスに保存される場合と、既にファイルが存在する場合を衝突とする。
+fn find_collisions<'a>(
+    planned: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<FilenameCollision> {
+    let mut by_path: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (spec_no, file_path) in planned {
+        by_path
+            .entry(file_path)
+            .or_default()
+            .push(spec_no.to_string());
+    }
+
+    by_path
+        .into_iter()
+        .filter_map(|(file_path, spec_nos)| {
+            let exists = Path::new(file_path).exists();
+            if spec_nos.len() > 1 || exists {
+                Some(FilenameCollision {
+                    file_path: file_path.to_string(),
+                    spec_nos,
+                    exists,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 一括ダウンロードの事前見積もり
+///
+/// ダウンロードを開始する前に、対応メーカーの有無・IESファイルのURLの解決可否・
+/// 保存先ファイル名の衝突・合計サイズの目安を確認する。ファイルは保存しない。
+#[tauri::command]
+pub async fn estimate_batch(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    request: BatchDownloadRequest,
+) -> CommandResult<BatchEstimate> {
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        request.dest_dir.as_deref(),
+        request.project_id.as_deref(),
+        request.sheet_name.as_deref(),
+    )?;
+    let mut filename_options = settings.filename_options();
+    if let Some(template) = request.filename_template {
+        filename::validate(&template)?;
+        filename_options.template = Some(template);
+    }
+
+    // プロバイダーを先に解決（処理中に有効・無効が切り替わっても開始時点の状態で処理する）
+    let jobs: Vec<_> = {
+        let registry = registry.load();
+        request
+            .items
+            .into_iter()
+            .map(|item| {
+                let provider =
+                    registry.get_provider_for(&item.manufacturer, item.provider.as_deref());
+                (item, provider)
+            })
+            .collect()
+    };
+
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|(item, provider)| {
+            let asset_types = item
+                .asset_types
+                .clone()
+                .unwrap_or_else(|| request.asset_types.clone());
+            estimate_item(
+                item,
+                provider,
+                asset_types,
+                dest_dir.clone(),
+                settings.destination.clone(),
+                filename_options.clone(),
+            )
+        })
+        .collect();
+    // リクエストの順序を保つ
+    let estimates = futures::stream::iter(tasks)
+        .buffered(concurrency(&settings))
+        .collect::<Vec<_>>()
+        .await;
+
+    let collisions = find_collisions(estimates.iter().flat_map(|e| {
+        e.file_paths
+            .iter()
+            .map(|path| (e.spec_no.as_str(), path.as_str()))
+    }));
+    let sizes: Vec<u64> = estimates.iter().filter_map(|e| e.size).collect();
+
+    Ok(BatchEstimate {
+        total: estimates.len(),
+        supported_count: estimates.iter().filter(|e| e.supported).count(),
+        resolved_count: estimates.iter().filter(|e| e.resolved).count(),
+        unsupported: estimates
+            .iter()
+            .filter(|e| !e.supported)
+            .map(|e| e.spec_no.clone())
+            .collect(),
+        unresolved: estimates
+            .iter()
+            .filter(|e| e.supported && !e.resolved)
+            .map(|e| e.spec_no.clone())
+            .collect(),
+        collisions,
+        total_size: sizes.iter().sum(),
+        sized_count: sizes.len(),
+    })
+}
+
+/// 製品情報の取得結果から事前確認の状態を判定
+fn availability_status(
+    item: &BatchDownloadItem,
+    fetched: &ProviderResult<ProductInfo>,
+) -> AvailabilityStatus {
+    match fetched {
+        Ok(info) if info.ies_file_url.is_none() => AvailabilityStatus::NotFound,
+        Ok(info)
+            if item.psu.is_none()
+                && info
+                    .accessories
+                    .iter()
+                    .any(|a| a.kind == AccessoryKind::PowerSupply) =>
+        {
+            AvailabilityStatus::NeedsPsu
+        }
+        Ok(_) => AvailabilityStatus::Found,
+        // 製品ページの 404 等、再試行しても変わらないもの
+        Err(e) if succession::is_not_found(e.code, &e.message) => AvailabilityStatus::NotFound,
+        Err(e) => match e.code {
+            ErrorCode::ProviderNotFound => AvailabilityStatus::Unsupported,
+            code if code.is_retryable() => AvailabilityStatus::Failed,
+            _ => AvailabilityStatus::NotFound,
+        },
+    }
+}
+
+/// 器具リスト全体の事前確認（ダウンロードせずに、各行のIESファイルが取得できるかを確認）
+///
+/// 一括ダウンロードと同じリクエストを受け取り、各行の製品情報のみを並列に取得する
+/// （キャッシュが有効な場合はメーカーサイトにアクセスしない。ファイルは保存しない）。
+/// 1行ごとに `availability-progress` イベントで結果を通知し、全行の結果をリクエストの順で返す。
+/// 同時に確認する件数はリクエストの `max_concurrency`（省略時は設定の同時実行数）。
+#[tauri::command]
+pub async fn check_availability(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    request: BatchDownloadRequest,
+) -> CommandResult<AvailabilityReport> {
+    // プロバイダーは開始時点の状態で解決
+    let jobs: Vec<_> = {
+        let registry = registry.load();
+        request
+            .items
+            .into_iter()
+            .map(|item| {
+                let provider =
+                    registry.get_provider_for(&item.manufacturer, item.provider.as_deref());
+                (item, provider)
+            })
+            .collect()
+    };
+    let concurrency = requested_concurrency(&settings::load(&app)?, request.max_concurrency)?;
+
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|(item, provider)| {
+            let app = app.clone();
+            async move {
+                let (fetched, _) = lookup_product_info(&app, &item, provider.as_deref()).await;
+                let status = availability_status(&item, &fetched);
+                let (info, code, error) = match fetched {
+                    Ok(info) => (Some(info), None, None),
+                    Err(e) => (None, Some(e.code), Some(e.message)),
+                };
+                let result = AvailabilityResult {
+                    spec_no: item.spec_no,
+                    status,
+                    provider: provider.map(|p| p.id().to_string()),
+                    info,
+                    code,
+                    error,
+                };
+                let _ = app.emit("availability-progress", result.clone());
+                result
+            }
+        })
+        .collect();
+    // リクエストの順序を保つ
+    let results = futures::stream::iter(tasks)
+        .buffered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(AvailabilityReport {
+        total: results.len(),
+        found_count: results
+            .iter()
+            .filter(|r| r.status == AvailabilityStatus::Found)
+            .count(),
+        results,
+    })
+}
+
+/// IESファイルを一括ダウンロード
+///
+/// `asset_types` の指定により、IES以外のアセットも同じバッチで取得できる。
+/// `on_event` を指定した場合、進捗・一時停止・完了はイベントではなくこのチャネルにのみ送信する。
+/// 実行中のバッチは同じ `batch_id`（省略時は既定のバッチ）を指定した [`cancel_batch`] で中止できる
+/// （処理中のアイテムの通信も中断する）。
+#[tauri::command]
+pub async fn batch_download_ies_files(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    request: BatchDownloadRequest,
+    on_event: BatchChannel,
+) -> CommandResult<BatchDownloadResult> {
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        request.dest_dir.as_deref(),
+        request.project_id.as_deref(),
+        request.sheet_name.as_deref(),
+    )?;
+    if let Some(template) = &request.filename_template {
+        filename::validate(template)?;
+    }
+    let max_concurrency = request
+        .max_concurrency
+        .map(settings::clamp_concurrency)
+        .transpose()?;
+    let batch = batches.start(request.batch_id.as_deref(), spec_nos(&request.items))?;
+    batch.set_channel(on_event);
+    batch.set_interactive(request.interactive);
+    batch.set_max_concurrency(max_concurrency);
+    batch.set_use_cache(request.use_cache);
+    batch.set_skip_existing(request.skip_existing);
+    batch.set_dedupe_existing(request.dedupe_existing);
+    batch.set_output_format(request.output_format);
+    batch.set_filename_template(request.filename_template);
+    let registry = registry.load();
+    Ok(run_batch(
+        &app,
+        &registry,
+        &batch,
+        &request.items,
+        &request.asset_types,
+        &dest_dir,
+        request.project_id.as_deref(),
+    )
+    .await)
+}
+
+/// 直前のバッチで失敗したアイテムを再ダウンロード
+///
+/// `request` には直前のバッチと同じリクエストを渡す。そのうち失敗・キャンセルしたアイテムのみ
+/// 再実行する。既定では再試行で解決する可能性があるもの（通信エラー等）に限り、
+/// `include_permanent` を指定した場合は掲載なし等の恒久的なエラーも再実行する。
+/// プロジェクトの無視リストに含まれるアイテムは再実行しない。再実行は同じバッチIDで行う。
+/// `on_event` を指定した場合、進捗・一時停止・完了はイベントではなくこのチャネルにのみ送信する。
+#[tauri::command]
+pub async fn retry_failed_items(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    request: BatchDownloadRequest,
+    include_permanent: Option<bool>,
+    on_event: BatchChannel,
+) -> CommandResult<BatchDownloadResult> {
+    let failed = batches
+        .get(request.batch_id.as_deref())
+        .failed_items(include_permanent.unwrap_or(false));
+    let ignore_list = ignore_list::load(&app, request.project_id.as_deref());
+    let items: Vec<_> = request
+        .items
+        .into_iter()
+        .filter(|item| failed.contains(&item.spec_no))
+        .filter(|item| !ignore_list.matches(&item.spec_no, &item.model_number))
+        .collect();
+
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        request.dest_dir.as_deref(),
+        request.project_id.as_deref(),
+        request.sheet_name.as_deref(),
+    )?;
+    if let Some(template) = &request.filename_template {
+        filename::validate(template)?;
+    }
+    let max_concurrency = request
+        .max_concurrency
+        .map(settings::clamp_concurrency)
+        .transpose()?;
+    let batch = batches.start(request.batch_id.as_deref(), spec_nos(&items))?;
+    batch.set_channel(on_event);
+    batch.set_interactive(request.interactive);
+    batch.set_max_concurrency(max_concurrency);
+    batch.set_use_cache(request.use_cache);
+    batch.set_skip_existing(request.skip_existing);
+    batch.set_dedupe_existing(request.dedupe_existing);
+    batch.set_output_format(request.output_format);
+    batch.set_filename_template(request.filename_template);
+    let registry = registry.load();
+    Ok(run_batch(
+        &app,
+        &registry,
+        &batch,
+        &items,
+        &request.asset_types,
+        &dest_dir,
+        request.project_id.as_deref(),
+    )
+    .await)
+}
+
+/// 中断した一括ダウンロードの一覧を取得
+///
+/// アプリが終了された・バックグラウンドの実行時間が上限に達した等で最後まで実行されなかったバッチ。
+#[tauri::command]
+pub async fn get_interrupted_batches(app: AppHandle) -> CommandResult<Vec<InterruptedBatch>> {
+    Ok(checkpoint::list(&app))
+}
+
+/// 中断した一括ダウンロードの未完了のアイテムを再実行
+///
+/// `batch_id` 省略時は既定のバッチ。再実行は中断したバッチと同じバッチIDで行う。
+/// `on_event` を指定した場合、進捗・一時停止・完了はイベントではなくこのチャネルにのみ送信する。
+#[tauri::command]
+pub async fn resume_interrupted_batch(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    batch_id: Option<String>,
+    on_event: BatchChannel,
+) -> CommandResult<BatchDownloadResult> {
+    let batch_id = batch_id.as_deref().unwrap_or(DEFAULT_BATCH_ID);
+    let interrupted = checkpoint::load(&app, batch_id).ok_or("No interrupted batch to resume")?;
+    let items = interrupted.remaining_items();
+    let batch = batches.start(Some(batch_id), spec_nos(&items))?;
+    batch.set_channel(on_event);
+    let registry = registry.load();
+    Ok(run_batch(
+        &app,
+        &registry,
+        &batch,
+        &items,
+        &interrupted.asset_types,
+        &interrupted.dest_dir,
+        interrupted.project_id.as_deref(),
+    )
+    .await)
+}
+
+/// 中断した一括ダウンロードを再開せずに破棄（`batch_id` 省略時は既定のバッチ）
+#[tauri::command]
+pub async fn discard_interrupted_batch(
+    app: AppHandle,
+    batch_id: Option<String>,
+) -> CommandResult<()> {
+    Ok(checkpoint::clear(
+        &app,
+        batch_id.as_deref().unwrap_or(DEFAULT_BATCH_ID),
+    )?)
+}
+
+/// プロジェクトの無視リストを取得（`project_id` 省略時はプロジェクト未指定のリスト）
+#[tauri::command]
+pub async fn get_ignore_list(
+    app: AppHandle,
+    project_id: Option<String>,
+) -> CommandResult<IgnoreList> {
+    Ok(ignore_list::load(&app, project_id.as_deref()))
+}
+
+/// プロジェクトの無視リストにSpec No. または型番を追加
+///
+/// 追加した器具は失敗したアイテムの再実行の対象から外れ、器具リストの読み込み時に
+/// `ignored` の印が付く。同じ対象を追加した場合は理由を更新する。
+///
+/// 戻り値: 更新後の無視リスト
+#[tauri::command]
+pub async fn add_to_ignore_list(
+    app: AppHandle,
+    project_id: Option<String>,
+    kind: IgnoreKind,
+    value: String,
+    reason: Option<String>,
+) -> CommandResult<IgnoreList> {
+    Ok(ignore_list::add(
+        &app,
+        project_id.as_deref(),
+        kind,
+        &value,
+        reason,
+    )?)
+}
+
+/// プロジェクトの無視リストから削除
+///
+/// 戻り値: 更新後の無視リスト
+#[tauri::command]
+pub async fn remove_from_ignore_list(
+    app: AppHandle,
+    project_id: Option<String>,
+    kind: IgnoreKind,
+    value: String,
+) -> CommandResult<IgnoreList> {
+    Ok(ignore_list::remove(
+        &app,
+        project_id.as_deref(),
+        kind,
+        &value,
+    )?)
+}
+
+/// IES以外のアセット（BIM・取扱説明書等）を一括ダウンロード
+///
+/// 保存先ディレクトリ直下のアセット種別ごとのサブフォルダ（例: `BIM/`）に保存する。
+/// `on_event` を指定した場合、進捗・一時停止・完了はイベントではなくこのチャネルにのみ送信する。
+#[tauri::command]
+pub async fn batch_download_assets(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    request: BatchAssetDownloadRequest,
+    on_event: BatchChannel,
+) -> CommandResult<BatchDownloadResult> {
+    let settings = settings::load(&app)?;
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        request.dest_dir.as_deref(),
+        request.project_id.as_deref(),
+        request.sheet_name.as_deref(),
+    )?;
+    let batch = batches.start(request.batch_id.as_deref(), spec_nos(&request.items))?;
+    batch.set_channel(on_event);
+    let registry = registry.load();
+    Ok(run_batch(
+        &app,
+        &registry,
+        &batch,
+        &request.items,
+        &[request.asset_type],
+        &dest_dir,
+        request.project_id.as_deref(),
+    )
+    .await)
+}
+
+/// 各アイテムの全アセット（IES・仕様書・画像・CAD・3D・取説等）を一括ダウンロード
+///
+/// プロバイダーが対応しているアセット種別をすべて取得し、
+/// Spec No.ごとのフォルダ（`{保存先ディレクトリ}/{Spec No.}/`）にまとめて保存する。
+/// `on_event` を指定した場合、進捗・一時停止・完了はイベントではなくこのチャネルにのみ送信する。
+#[tauri::command]
+pub async fn batch_download_asset_bundle(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    request: BatchDownloadRequest,
+    on_event: BatchChannel,
+) -> CommandResult<BatchBundleResult> {
+    let registry = registry.load();
+    let mut results = Vec::new();
+    let mut history_log = Vec::new();
+    let mut success_count = 0;
+    let mut failure_count = 0;
+    let mut cancelled_count = 0;
+    let settings = settings::load(&app).unwrap_or_default();
+    let destination = settings.destination.clone();
+    let filename_options = settings.filename_options();
+    let client = batch_http_client(&settings);
+    let dest_dir = resolve_dest_dir(
+        &app,
+        &settings,
+        request.dest_dir.as_deref(),
+        request.project_id.as_deref(),
+        request.sheet_name.as_deref(),
+    )?;
+
+    let batch = batches.start(request.batch_id.as_deref(), spec_nos(&request.items))?;
+    batch.set_channel(on_event);
+    batch.set_interactive(request.interactive);
+    let root_dir = filename::dir_prefix(&dest_dir).to_string();
+    if let Err(e) = std::fs::create_dir_all(longpath::extended(&root_dir)) {
+        tracing::warn!(error = %e, "failed to create destination directory");
+    }
+    batches.register_dest_dir(&root_dir);
+
+    for item in &request.items {
+        // 一時停止中は次のアイテムに進まない
+        batch.wait_if_paused().await;
+        // 処理開始イベントを発火
+        notify_progress(&app, &batch, &item.spec_no, "processing", None);
+
+        let item_dir = format!(
+            "{}/{}",
+            item_dest_dir(&dest_dir, item),
+            filename::sanitize_filename(&item.spec_no)
+        );
+        let mut assets = Vec::new();
+        let mut error = None;
+
+        match registry.get_provider_for(&item.manufacturer, item.provider.as_deref()) {
+            Some(provider) => {
+                if let Err(e) = std::fs::create_dir_all(longpath::extended(&item_dir)) {
+                    error = Some(format!("Failed to create directory: {}", e));
+                } else {
+                    let notifier = phase_notifier(&app, &batch, &item.spec_no);
+                    let resolver = batch
+                        .is_interactive()
+                        .then(|| decision_resolver(&app, &batch, &item.spec_no));
+                    let downloaded = batch
+                        .run_cancellable(&item.spec_no, |cancel| {
+                            let (provider, item_dir) = (&provider, &item_dir);
+                            let (destination, filename_options, client) =
+                                (&destination, &filename_options, &client);
+                            let download = async move {
+                                let mut assets = Vec::new();
+                                for asset_type in provider.supported_assets() {
+                                    let result = download_item_asset(
+                                        provider.as_ref(),
+                                        item,
+                                        asset_type,
+                                        item_dir,
+                                        destination,
+                                        filename_options,
+                                        client,
+                                        &cancel,
+                                    )
+                                    .await;
+                                    let supplied =
+                                        result.success.then(|| provider.id().to_string());
+                                    assets.push(AssetDownloadResult {
+                                        asset_type,
+                                        result,
+                                        provider: supplied,
+                                    });
+                                }
+                                assets
+                            };
+                            with_phase_notifier(
+                                notifier,
+                                with_decision_resolver(resolver, download),
+                            )
+                        })
+                        .await;
+
+                    match downloaded {
+                        Some(downloaded) => assets = downloaded,
+                        None => {
+                            cancelled_count += 1;
+                            notify_progress(&app, &batch, &item.spec_no, "cancelled", None);
+                            results.push(BundleItemResult {
+                                spec_no: item.spec_no.clone(),
+                                model_number: item.model_number.clone(),
+                                dest_dir: item_dir,
+                                assets,
+                            });
+                            continue;
+                        }
+                    }
+                }
+            }
+            None => error = Some(no_provider(&item.manufacturer).message),
+        }
+
+        let success = assets.iter().any(|a| a.result.success);
+        if success {
+            success_count += 1;
+        } else {
+            failure_count += 1;
+            if error.is_none() {
+                error = assets.iter().find_map(|a| a.result.error.clone());
+            }
+        }
+        history_log.extend(history_entries(
+            request.project_id.as_deref(),
+            item,
+            &assets,
+        ));
+
+        // 完了イベントを発火
+        let status = if success { "success" } else { "error" };
+        notify_progress(&app, &batch, &item.spec_no, status, error);
+
+        results.push(BundleItemResult {
+            spec_no: item.spec_no.clone(),
+            model_number: item.model_number.clone(),
+            dest_dir: item_dir,
+            assets,
+        });
+    }
+
+    // 履歴の保存に失敗してもダウンロード結果は返す
+    if let Err(e) = history::append(&app, history_log) {
+        tracing::error!(error = %e, "failed to save download history");
+    }
+    notify_finished(
+        &app,
+        &batch,
+        &settings,
+        BatchFinishedEvent {
+            batch_id: batch.id().to_string(),
+            success_count,
+            failure_count,
+            cancelled_count,
+            dest_dir: root_dir.clone(),
+            opened_dest_dir: false,
+        },
+    );
+
+    Ok(BatchBundleResult {
+        success_count,
+        failure_count,
+        cancelled_count,
+        results,
+    })
+}
+
+/// ジョブファイル（JSON）に定義された一括ダウンロードを実行
+///
+/// 保存先・アセット種別・ファイル名テンプレート・同時実行数はジョブファイルの指定に従い、
+/// アプリの設定は完了時の通知とバッテリー駆動時の同時実行数の制限のみに使用する。
+/// 進捗・完了は通常の一括ダウンロードと同じイベント（`on_event` 指定時はチャネル）で通知する。
+/// `batch_id` 省略時は既定のバッチとして実行する。
+#[tauri::command]
+pub async fn run_job_file(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    batches: State<'_, BatchState>,
+    path: String,
+    batch_id: Option<String>,
+    on_event: BatchChannel,
+) -> CommandResult<JobResult> {
+    let mut job = job::load(Path::new(&path))?;
+    let settings = settings::load(&app).unwrap_or_default();
+    let registry = registry.load();
+    // バッテリー駆動時はジョブファイルの同時実行数も下げる
+    job.concurrency = settings
+        .battery
+        .concurrency(job.concurrency, power::on_battery() == Some(true));
+
+    let batch = batches.start(batch_id.as_deref(), spec_nos(&job.items))?;
+    batch.set_channel(on_event);
+    tracing::info!(items = job.items.len(), path, "job file started");
+    let mut history_log = Vec::new();
+    let result = job::run(&job, &registry, |item, result| {
+        let error = result.assets.iter().find_map(|a| a.result.error.clone());
+        let status = if result.success { "success" } else { "error" };
+        notify_progress(&app, &batch, &result.spec_no, status, error);
+        history_log.extend(history_entries(None, item, &result.assets));
+    })
+    .await;
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            batch.finish();
+            return Err(e.into());
+        }
+    };
+
+    if let Err(e) = history::append(&app, history_log) {
+        tracing::error!(error = %e, "failed to save download history");
+    }
+    batches.register_dest_dir(&result.dest_dir);
+    notify_finished(
+        &app,
+        &batch,
+        &settings,
+        BatchFinishedEvent {
+            batch_id: batch.id().to_string(),
+            success_count: result.success_count,
+            failure_count: result.failure_count,
+            cancelled_count: 0,
+            dest_dir: result.dest_dir.clone(),
+            opened_dest_dir: false,
+        },
+    );
+    Ok(result)
+}
+
+/// 電源の状態と、それに応じた同時実行数を取得
+#[tauri::command]
+pub async fn get_power_status(app: AppHandle) -> CommandResult<PowerStatus> {
+    let settings = settings::load(&app)?;
+    let on_battery = power::on_battery();
+    Ok(PowerStatus {
+        on_battery,
+        reduced: settings.battery.enabled && on_battery == Some(true),
+        concurrency: concurrency(&settings),
+    })
+}
+
+/// 一括ダウンロード中のアイテムを個別にキャンセル
+///
+/// 処理中であれば実行中のリクエストを中断し、未着手であれば順番が来た時点でスキップする。
+/// バッチ全体は継続する。`batch_id` 省略時は既定のバッチ。
+/// 戻り値: 処理中のアイテムを中断した場合は true
+#[tauri::command]
+pub async fn cancel_item(
+    batches: State<'_, BatchState>,
+    spec_no: String,
+    batch_id: Option<String>,
+) -> CommandResult<bool> {
+    Ok(batches.get(batch_id.as_deref()).cancel(&spec_no))
+}
+
+/// 判断待ちのアイテムの候補を選ぶ
+///
+/// `choice` には `download-decision-required` イベントの候補の値を指定する。省略した場合は判断せずに続行する
+/// （自動で選ぶ候補があればそれを使い、なければ失敗にする）。`batch_id` 省略時は既定のバッチ。
+/// 戻り値: 判断待ちのアイテムだった場合は true
+#[tauri::command]
+pub async fn resolve_item_choice(
+    batches: State<'_, BatchState>,
+    spec_no: String,
+    choice: Option<String>,
+    batch_id: Option<String>,
+) -> CommandResult<bool> {
+    Ok(batches
+        .get(batch_id.as_deref())
+        .resolve_decision(&spec_no, choice))
+}
+
+/// 実行中の一括ダウンロードを一時停止
+///
+/// 処理中のアイテムは完了まで続け、次のアイテムから停止する。`batch_id` 省略時は既定のバッチ。
+/// 戻り値: 一時停止した場合は true（実行中でない・一時停止中の場合は false）
+#[tauri::command]
+pub async fn pause_batch(
+    app: AppHandle,
+    batches: State<'_, BatchState>,
+    batch_id: Option<String>,
+) -> CommandResult<bool> {
+    let batch = batches.get(batch_id.as_deref());
+    Ok(set_batch_paused(&app, &batch, true))
+}
+
+/// 一時停止した一括ダウンロードを再開（戻り値: 再開した場合は true）
+#[tauri::command]
+pub async fn resume_batch(
+    app: AppHandle,
+    batches: State<'_, BatchState>,
+    batch_id: Option<String>,
+) -> CommandResult<bool> {
+    let batch = batches.get(batch_id.as_deref());
+    Ok(set_batch_paused(&app, &batch, false))
+}
+
+/// 実行中の一括ダウンロードをバッチ単位でキャンセル
+///
+/// 処理中のアイテムを中断し、残りのアイテムはすべてキャンセルとして扱う。他のバッチは継続する。
+/// 再開できるよう、チェックポイントは残す。戻り値: バッチが実行中だった場合は true
+#[tauri::command]
+pub async fn cancel_batch(
+    batches: State<'_, BatchState>,
+    batch_id: Option<String>,
+) -> CommandResult<bool> {
+    let batch = batches.get(batch_id.as_deref());
+    if !batch.is_running() {
+        return Ok(false);
+    }
+    batch.interrupt();
+    Ok(true)
+}
+
+/// ダウンロード結果のレポートを出力
+///
+/// プロジェクトのダウンロード履歴（`project_id` 省略時は全履歴）を古い順に並べ、
+/// CSV・JSON・HTMLのいずれかの形式で文字列として返す。保存はフロントエンドで行う。
+#[tauri::command]
+pub async fn export_report(
+    app: AppHandle,
+    project_id: Option<String>,
+    format: ReportFormat,
+) -> CommandResult<String> {
+    let mut entries = history::load(&app)?;
+    if let Some(id) = &project_id {
+        entries.retain(|e| e.project_id.as_ref() == Some(id));
+    }
+
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    let project = project_id.and_then(|id| history::project_name(&app, &id));
+    let title = Message::ReportTitle {
+        project: project.as_deref(),
+    }
+    .text(locale);
+    Ok(report::render(&entries, format, &title, locale)?)
+}
+
+/// 一括ダウンロードの結果を器具リストExcelに書き戻す
+///
+/// 元の器具リスト（`source_path`）のシートを新しいブックに写し、Spec No. ごとに結果・ファイル名・
+/// ファイルサイズ・エラーの列を追加して `dest_path`（省略時は `{元のファイル名}_results.xlsx`）に保存する。
+/// 元のファイルは変更しない。`profile` 省略時は標準の列構成で読み込む。
+#[tauri::command]
+pub async fn export_batch_report(
+    app: AppHandle,
+    source_path: String,
+    result: BatchDownloadResult,
+    dest_path: Option<String>,
+    profile: Option<ImportProfile>,
+) -> CommandResult<WritebackResult> {
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    let dest = match dest_path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => writeback::default_dest_path(Path::new(&source_path)),
+    };
+    let profile = profile.unwrap_or_default();
+    Ok(run_blocking(move || {
+        writeback::write(&source_path, &dest, &profile, &result.results, locale)
+    })
+    .await?)
+}
+
+/// 器具リスト（見積もり用の一覧表）を出力
+///
+/// 器具リストの各行に、プロジェクトのダウンロード履歴（`project_id` 省略時は全履歴）の
+/// IESファイルの配光データ（器具光束・消費電力・ビーム角・BUG評価）と、取得済みの製品情報
+/// （品名・定価。キャッシュから読み込み、メーカーサイトにはアクセスしない）を結合する。
+/// CSV・JSON・HTMLのいずれかの形式で文字列として返す。保存はフロントエンドで行う。
+#[tauri::command]
+pub async fn export_schedule_summary(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    rows: Vec<ImportedRow>,
+    project_id: Option<String>,
+    format: ReportFormat,
+) -> CommandResult<String> {
+    let mut entries = history::load(&app)?;
+    if let Some(id) = &project_id {
+        entries.retain(|e| e.project_id.as_ref() == Some(id));
+    }
+
+    let registry = registry.load();
+    let cache_dir = cache_dir(&app).ok();
+    let summary = schedule_report::build_rows(&rows, &entries, |row| {
+        let provider = registry.get_provider(&row.manufacturer)?;
+        let path = prefetch::cache_path(cache_dir.as_deref()?, provider.id(), &row.fixture);
+        prefetch::load_stale(&path)
+    });
+
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    let project = project_id.and_then(|id| history::project_name(&app, &id));
+    let title = Message::ScheduleTitle {
+        project: project.as_deref(),
+    }
+    .text(locale);
+    Ok(schedule_report::render(&summary, format, &title, locale)?)
+}
+
+/// IESファイルの配光データを解析
+///
+/// 器具光束・入力電力・ビーム角に加え、屋外器具の光害対策の確認用にゾーナル光束と
+/// BUG評価（IES TM-15-11）を返す。
+#[tauri::command]
+pub async fn analyze_ies(path: String) -> CommandResult<IesAnalysis> {
+    let bytes = tokio::fs::read(longpath::extended(&path))
+        .await
+        .map_err(|e| format!("Failed to read IES file: {}", e))?;
+    let ies = photometry::parse_ies(&String::from_utf8_lossy(&bytes))?;
+    Ok(ies.analyze())
+}
+
+/// 配光曲線の鉛直角の既定の間隔（度）
+const POLAR_CURVE_STEP: f64 = 5.0;
+
+/// ダウンロードしたIESファイルの配光データの概要を取得
+///
+/// 解析結果（器具光束・ビーム角等）に加え、最大光度・1/10ビーム角・対称性と、
+/// Spec No.ごとの配光曲線のプレビュー用に鉛直角を `step` 度（省略時は5度）ごとに間引いた光度を返す。
+#[tauri::command]
+pub async fn analyze_ies_file(path: String, step: Option<f64>) -> CommandResult<IesSummary> {
+    let bytes = tokio::fs::read(longpath::extended(&path))
+        .await
+        .map_err(|e| format!("Failed to read IES file: {}", e))?;
+    let ies = photometry::parse_ies(&String::from_utf8_lossy(&bytes))?;
+    Ok(ies.summary(step.unwrap_or(POLAR_CURVE_STEP)))
+}
+
+/// 配光データをIES・EULUMDAT（LDT）の間で変換
+///
+/// IESファイルはLDTに、LDTはIESファイルに変換して `dest_path`（省略時は同じフォルダの
+/// 拡張子のみ異なる名前）に保存する。保存先のパスを返す。LDTの器具名には元のファイル名を使用する。
+#[tauri::command]
+pub async fn convert_photometry(path: String, dest_path: Option<String>) -> CommandResult<String> {
+    let dest = dest_path.unwrap_or_else(|| {
+        photometry::converted_path(Path::new(&path))
+            .to_string_lossy()
+            .into_owned()
+    });
+    let name = Path::new(&path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(run_blocking(move || -> Result<_, String> {
+        photometry::convert_file(
+            &longpath::extended(&path),
+            &longpath::extended(&dest),
+            &name,
+        )?;
+        Ok(dest)
+    })
+    .await?)
+}
+
+/// 照射円錐図（コーンダイアグラム）のデータを計算
+///
+/// 取付高さ `heights`（m）ごとに、光軸上の照度（lx）とビーム径（m）を返す。
+/// ダウンロードした器具の照準の目安として表示する。
+#[tauri::command]
+pub async fn cone_diagram(path: String, heights: Vec<f64>) -> CommandResult<ConeDiagram> {
+    let bytes = tokio::fs::read(longpath::extended(&path))
+        .await
+        .map_err(|e| format!("Failed to read IES file: {}", e))?;
+    let ies = photometry::parse_ies(&String::from_utf8_lossy(&bytes))?;
+    Ok(ies.cone_diagram(&heights)?)
+}
+
+/// ダウンロード済みの配光データを照明計算ソフト（DIALux evo・Relux・AGi32）向けに書き出す
+///
+/// プロジェクトのダウンロード履歴（`project_id` 省略時は全履歴）のIESファイルを
+/// メーカーごとのフォルダにまとめ、一覧（index.csv）を作成する。Relux向けはLDTに変換する。
+/// AGi32向けはファイル名の制約にあわせたラベルで平置きし、器具表（agi32_schedule.csv）を作成する。
+/// 設定の `scrub_ies_headers` が有効な場合は、IESファイルのヘッダーから内部情報を取り除く。
+/// 書き出し先は、ダウンロード済みファイルとして開けるよう保存先ディレクトリとして記録する。
+#[tauri::command]
+pub async fn export_lighting_project(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    project_id: Option<String>,
+    dest_dir: String,
+    target: ExportTarget,
+) -> CommandResult<ProjectExportResult> {
+    let mut entries = history::load(&app)?;
+    if let Some(id) = &project_id {
+        entries.retain(|e| e.project_id.as_ref() == Some(id));
+    }
+    let settings = settings::load(&app).unwrap_or_default();
+    let result = lighting_export::export(
+        &entries,
+        Path::new(&dest_dir),
+        target,
+        settings.locale,
+        settings.scrub_ies_headers,
+    )?;
+    batch.register_dest_dir(&dest_dir);
+    Ok(result)
+}
+
+/// 保存先ディレクトリをクラウド（Dropbox・Google Drive）にアップロード
+///
+/// アップロード先・ZIPにまとめるかは設定に従う（自動アップロードが無効でも実行できる）。
+/// アップロードできるのは一括ダウンロードの保存先として記録したディレクトリに限る。
+#[tauri::command]
+pub async fn upload_to_cloud(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    dir: String,
+) -> CommandResult<CloudUploadResult> {
+    let dir = batch.resolve_downloaded_path(&dir)?;
+    let settings = settings::load(&app)?;
+    let upload = cloud::CloudUploadSettings {
+        enabled: true,
+        ..settings.cloud_upload.clone()
+    };
+    let client = http_client(&settings)?;
+    Ok(cloud::upload_dir(&app, &client, &upload, &dir).await?)
+}
+
+/// クラウドのOAuthトークンを保存（`token` 省略時は削除して連携を解除）
+#[tauri::command]
+pub async fn set_cloud_token(
+    app: AppHandle,
+    provider: CloudProvider,
+    token: Option<CloudToken>,
+) -> CommandResult<()> {
+    Ok(run_blocking(move || cloud::set_token(&app, provider, token)).await?)
+}
+
+/// トークンを保存しているクラウドのサービス
+#[tauri::command]
+pub async fn get_cloud_connections(app: AppHandle) -> CommandResult<Vec<CloudProvider>> {
+    Ok(run_blocking(move || cloud::connected_providers(&app)).await?)
+}
+
+/// ダウンロード済みファイルをユーザーが選んだ場所に書き出す
+///
+/// モバイルでは保存先がアプリ内のフォルダになるため、保存ダイアログで作成したファイルのURI
+/// （AndroidのSAF・iOSの「ファイル」アプリ）を `target` に渡して書き出す。
+#[tauri::command]
+pub async fn export_downloaded_file(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    path: String,
+    target: String,
+) -> CommandResult<StorageExportResult> {
+    let source = batch.resolve_downloaded_path(&path)?;
+    Ok(run_blocking(move || storage::export_file(&app, &source, &target)).await?)
+}
+
+/// ダウンロード済みファイルをOSの共有シートに渡す（モバイル）
+///
+/// フォルダ（Spec No.ごとのフォルダ・保存先ディレクトリ）を指定した場合はZIPにまとめて共有する。
+/// ZIPはキャッシュディレクトリに作成し、共有先のアプリが読み込めるよう次回の共有まで残す。
+#[tauri::command]
+pub async fn share_downloaded_file(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    path: String,
+) -> CommandResult<()> {
+    let resolved = batch.resolve_downloaded_path(&path)?;
+    let share_dir = cache_dir(&app)?.join("share");
+    Ok(run_blocking(move || {
+        let (file, mime_type) = if resolved.is_dir() {
+            let _ = std::fs::remove_dir_all(&share_dir);
+            std::fs::create_dir_all(&share_dir)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            let name = resolved
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let zip_path = share_dir.join(format!("{}.zip", filename::sanitize_filename(&name)));
+            cloud::zip_dir(&resolved, &zip_path)?;
+            (zip_path, "application/zip")
+        } else {
+            let mime_type = share_mime_type(&resolved);
+            (resolved, mime_type)
+        };
+        app.share().share_file(ShareFileRequest {
+            path: file.to_string_lossy().into_owned(),
+            mime_type: mime_type.to_string(),
+            title: None,
+        })
+    })
+    .await?)
+}
+
+/// 共有シートに渡すMIMEタイプ（IES等の登録されていない形式は application/octet-stream）
+fn share_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "csv" => "text/csv",
+        _ => "application/octet-stream",
+    }
+}
+
+/// ネットワーク共有のパスワードを保存（`password` 省略時は削除）
+#[tauri::command]
+pub async fn set_network_share_password(
+    app: AppHandle,
+    password: Option<String>,
+) -> CommandResult<()> {
+    Ok(run_blocking(move || network_share::set_password(&app, password)).await?)
+}
+
+/// ネットワーク共有に接続できるか確認し、書き込みに使うパスを返す
+///
+/// 保存前の設定を確認できるよう、現在の設定ではなく `settings` を使用する。
+#[tauri::command]
+pub async fn test_network_share(
+    app: AppHandle,
+    settings: NetworkShareSettings,
+) -> CommandResult<String> {
+    Ok(run_blocking(move || network_share::test(&app, &settings)).await?)
+}
+
+/// ダウンロード済みファイルに現在の命名規則を再適用
+///
+/// ダウンロード履歴のメタデータ（Spec No.・型番・PSU・元ファイル名）とIESファイルの内容
+/// （色温度・ビーム角・器具光束）からファイル名を再生成し、
+/// 名前が変わるファイルをリネームする。同名のファイルが既にある場合は上書きせずエラーとする。
+/// リネーム後のパスは履歴にも反映する。
+#[tauri::command]
+pub async fn rename_downloaded_files(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    request: RenameRequest,
+) -> CommandResult<Vec<RenameResult>> {
+    let registry = registry.load();
+    let settings = settings::load(&app)?;
+    let mut filename_options = settings.filename_options();
+    if request.content_aware {
+        filename_options.template = Some(filename::CONTENT_TEMPLATE.to_string());
+    }
+    let mut entries = history::load(&app)?;
+    let dir = request.dir.as_deref().map(Path::new);
+
+    // 同じファイルの履歴が複数ある場合は最新のものを使う
+    let mut latest: BTreeMap<String, usize> = BTreeMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(path) = entry.file_path.as_ref().filter(|_| entry.success) else {
+            continue;
+        };
+        let in_project = request
+            .project_id
+            .as_ref()
+            .is_none_or(|id| entry.project_id.as_ref() == Some(id));
+        let in_dir = dir.is_none_or(|dir| Path::new(path).starts_with(dir));
+        if in_project && in_dir && Path::new(path).exists() {
+            latest.insert(path.clone(), i);
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut renamed_paths = Vec::new();
+    for (old_path, i) in latest {
+        let entry = &entries[i];
+        let Some(provider) = registry.get_provider(&entry.manufacturer) else {
+            continue;
+        };
+        let photometry = (entry.asset_type == AssetType::Ies)
+            .then(|| photometry::read_filename_values(&longpath::extended(&old_path)))
+            .flatten();
+        let context = FilenameContext {
+            spec_no: &entry.spec_no,
+            manufacturer: provider.display_name(),
+            model_number: &entry.model_number,
+            psu: entry.psu.as_deref(),
+            original_filename: entry.original_filename.as_deref(),
+            photometry,
+        };
+        let new_path = Path::new(&old_path)
+            .with_file_name(asset_filename(
+                Some(provider.as_ref()),
+                &filename_options,
+                entry.asset_type,
+                &context,
+            ))
+            .to_string_lossy()
+            .into_owned();
+        if new_path == old_path {
+            continue;
+        }
+
+        let error = if longpath::extended(&new_path).exists() {
+            Some(format!("File already exists: {}", new_path))
+        } else if request.dry_run {
+            None
+        } else {
+            std::fs::rename(longpath::extended(&old_path), longpath::extended(&new_path))
+                .err()
+                .map(|e| format!("Failed to rename file: {}", e))
+        };
+        let renamed = !request.dry_run && error.is_none();
+        if renamed {
+            renamed_paths.push((old_path.clone(), new_path.clone()));
+        }
+
+        results.push(RenameResult {
+            spec_no: entry.spec_no.clone(),
+            old_path,
+            new_path,
+            renamed,
+            code: error.as_deref().map(ErrorCode::classify),
+            error,
+        });
+    }
+
+    if !renamed_paths.is_empty() {
+        for entry in entries.iter_mut() {
+            if let Some((_, new_path)) = renamed_paths
+                .iter()
+                .find(|(old_path, _)| entry.file_path.as_ref() == Some(old_path))
+            {
+                entry.file_path = Some(new_path.clone());
+            }
+        }
+        history::save(&app, entries)?;
+    }
+
+    Ok(results)
+}
+
+/// 通信の設定（プロキシ・TLS）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// プロキシ設定（未指定時は直接接続）
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    #[serde(default)]
+    pub tls: TlsSettings,
+}
+
+/// 通信の設定（プロキシ・TLS）を取得
+#[tauri::command]
+pub async fn get_network_settings(app: AppHandle) -> CommandResult<NetworkSettings> {
+    let settings = settings::load(&app)?;
+    Ok(NetworkSettings {
+        proxy: settings.proxy,
+        tls: settings.tls,
+    })
+}
+
+/// 通信の設定（プロキシ・TLS）を検証して保存し、プロバイダーを作り直す
+///
+/// ルート証明書を読み込めない場合はエラーとし、保存しない。
+/// 実行中の一括ダウンロードは開始時の設定のまま続け、次のダウンロードから反映する。
+#[tauri::command]
+pub async fn update_network_settings(
+    app: AppHandle,
+    registry: State<'_, SharedRegistry>,
+    network: NetworkSettings,
+) -> CommandResult<NetworkSettings> {
+    let settings = Settings {
+        proxy: network.proxy,
+        tls: network.tls,
+        ..settings::load(&app)?
+    };
+    settings.validate()?;
+    apply_network_settings(settings.proxy.as_ref(), &settings.tls)?;
+    settings::save(&app, &settings)?;
+    apply_provider_config(&registry, &settings.providers)?;
+    tracing::info!(
+        proxy = settings.proxy.is_some(),
+        root_certificate = settings.tls.root_certificate_path.is_some(),
+        accept_invalid_certs = settings.tls.accept_invalid_certs,
+        "network settings updated"
+    );
+    Ok(NetworkSettings {
+        proxy: settings.proxy,
+        tls: settings.tls,
+    })
+}
+
+/// アプリ設定を取得
+///
+/// 古いバージョンで保存された設定は現在のバージョンに変換して返す。
+#[tauri::command]
+pub async fn get_settings(app: AppHandle) -> CommandResult<Settings> {
+    Ok(settings::load(&app)?)
+}
+
+/// アプリ設定を検証して保存
+///
+/// 不明なキーや範囲外の値を含む場合はエラーとし、保存しない。
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, settings: Settings) -> CommandResult<Settings> {
+    let settings = Settings {
+        version: settings::SETTINGS_VERSION,
+        ..settings
+    };
+    let previous = settings::load(&app).unwrap_or_default();
+    // ルート証明書を読み込めない設定は保存しない
+    let network_changed = settings.proxy != previous.proxy || settings.tls != previous.tls;
+    if network_changed {
+        settings.validate()?;
+        apply_network_settings(settings.proxy.as_ref(), &settings.tls)?;
+    }
+    settings::save(&app, &settings)?;
+    apply_log_level(&app, settings.log_level)?;
+    apply_error_reporting(&app, &settings);
+    api_server::apply(&app, &settings.api_server);
+    offline::apply(&app, settings.offline);
+    apply_domain_concurrency(&settings.domain_concurrency);
+    apply_domain_request_interval(&settings.domain_request_interval_ms);
+    apply_retry_settings(&settings.retry);
+    apply_request_timeout(settings.request_timeout_secs);
+    // プロバイダーの作り直しで接続を捨てないよう、接続設定が変わった場合だけ反映する
+    if network_changed
+        || settings.providers != previous.providers
+        || settings.request_timeout_secs != previous.request_timeout_secs
+    {
+        apply_provider_config(&app.state::<SharedRegistry>(), &settings.providers)?;
+    }
+    cassette::apply(&app, &settings.cassette);
+    apply_cassette_mode(&app.state::<SharedRegistry>())?;
+    Ok(settings)
+}
+
+/// 設定の読み込みの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportResult {
+    /// 反映した設定
+    pub settings: Settings,
+    /// 無視リストに追加した項目の件数
+    pub ignore_entries_added: usize,
+}
+
+/// アプリ設定と無視リストを1つのファイルに書き出す（別のPCで読み込むため）
+///
+/// プロキシのパスワード等の認証情報は書き出さない。
+#[tauri::command]
+pub async fn export_config(app: AppHandle, dest_path: String) -> CommandResult<String> {
+    let bundle = config_transfer::export(
+        &settings::load(&app)?,
+        ignore_list::load_all(&app),
+        &app.package_info().version.to_string(),
+    )?;
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    tokio::fs::write(&dest_path, json)
+        .await
+        .map_err(|e| format!("Failed to write configuration file: {}", e))?;
+    Ok(dest_path)
+}
+
+/// 書き出した設定ファイルを読み込んで反映
+///
+/// 設定は保存時と同じ検証を行い、不正な場合は何も変更しない。無視リストは既存の項目に追加する。
+#[tauri::command]
+pub async fn import_config(app: AppHandle, path: String) -> CommandResult<ConfigImportResult> {
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read configuration file: {}", e))?;
+    let imported = config_transfer::import(&contents, &settings::load(&app)?)?;
+    let settings = update_settings(app.clone(), imported.settings).await?;
+    let ignore_entries_added = ignore_list::merge_all(&app, imported.ignore_lists)?;
+    tracing::info!(%path, ignore_entries_added, "configuration imported");
+    Ok(ConfigImportResult {
+        settings,
+        ignore_entries_added,
+    })
+}
+
+/// エラー報告の設定を反映
+pub fn apply_error_reporting(app: &AppHandle, settings: &Settings) {
+    let client = match http_client(settings) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to configure error reporting");
+            return;
+        }
+    };
+    error_reporting::configure(
+        &settings.error_reporting,
+        client,
+        &app.package_info().version.to_string(),
+    );
+}
+
+/// 実行中のログ出力にログレベルを反映（ログ出力を初期化できなかった場合は何もしない）
+fn apply_log_level(app: &AppHandle, level: LogLevel) -> Result<(), String> {
+    match app.try_state::<LoggingState>() {
+        Some(logging) => logging.set_level(level),
+        None => Ok(()),
+    }
+}
+
+/// ログレベルを変更して設定に保存
+#[tauri::command]
+pub async fn set_log_level(app: AppHandle, level: LogLevel) -> CommandResult<()> {
+    let settings = Settings {
+        log_level: level,
+        ..settings::load(&app)?
+    };
+    settings::save(&app, &settings)?;
+    apply_log_level(&app, level)?;
+    tracing::info!(?level, "log level changed");
+    Ok(())
+}
+
+/// 送信される利用状況（前回の送信以降の集計値）を取得
+///
+/// 利用状況の送信を有効にする前に、送信内容をユーザーが確認できるようにするためのもの。
+#[tauri::command]
+pub async fn get_usage_report(app: AppHandle) -> CommandResult<UsageReport> {
+    let counts = telemetry::load(&app)?;
+    Ok(UsageReport::new(
+        app.package_info().version.to_string(),
+        counts,
+    ))
+}
+
+/// 実行中（または直前）の一括ダウンロードの状態を取得
+///
+/// フロントエンドの再読み込み後など、進捗イベントを受け取れなかった場合に
+/// 実行中のバッチへ再接続するために使用する。`batch_id` 省略時は既定のバッチ。
+#[tauri::command]
+pub async fn get_batch_status(
+    batches: State<'_, BatchState>,
+    batch_id: Option<String>,
+) -> CommandResult<BatchStatus> {
+    Ok(batches.status(batch_id.as_deref()))
+}
+
+/// すべてのバッチ（実行中・直前に実行したもの）の状態を取得
+#[tauri::command]
+pub async fn list_batches(batches: State<'_, BatchState>) -> CommandResult<Vec<BatchStatus>> {
+    Ok(batches.list())
+}
+
+/// 直前の作業状態を取得（ない場合は None）
+///
+/// 最後に読み込んだ器具リスト・取得した製品情報・各行のダウンロード結果。
+/// クラッシュや再起動の後、器具リストの読み込み・製品情報の取得をやり直さずに作業を再開するために使用する。
+#[tauri::command]
+pub async fn restore_last_session(app: AppHandle) -> CommandResult<Option<Session>> {
+    Ok(session::load(&app))
+}
+
+/// 直前の作業状態を破棄
+#[tauri::command]
+pub async fn clear_last_session(app: AppHandle) -> CommandResult<()> {
+    Ok(session::clear(&app)?)
+}
+
+/// ディープリンク（`autosight://`）で開かれたURLを処理
+///
+/// 対応メーカーのアイテムをダウンロード待ちとして保持し、`deep-link-download` イベントで通知する。
+/// 不正なURL・未対応のメーカーは無視する。
+pub fn handle_deep_links<'a>(app: &AppHandle, urls: impl IntoIterator<Item = &'a str>) {
+    let registry = app.state::<SharedRegistry>().load();
+    let state = app.state::<DeepLinkState>();
+    for url in urls {
+        let item = match deeplink::parse(url) {
+            Ok(item) => item,
+            Err(e) => {
+                tracing::warn!(error = %e, "ignored deep link");
+                continue;
+            }
+        };
+        if registry.get_provider(&item.manufacturer).is_none() {
+            tracing::warn!(manufacturer = %item.manufacturer, "deep link for unsupported manufacturer");
+            continue;
+        }
+        tracing::info!(spec_no = %item.spec_no, model_number = %item.model_number, "deep link received");
+        state.push(item.clone());
+        let _ = app.emit("deep-link-download", item);
+    }
+}
+
+/// ディープリンクで追加されたアイテムを取り出す
+///
+/// フロントエンドの起動時に呼び出し、起動前に開かれたリンクのアイテムを受け取る。
+/// 取り出したアイテムは保持しない。
+#[tauri::command]
+pub async fn take_deep_link_items(
+    state: State<'_, DeepLinkState>,
+) -> CommandResult<Vec<DeepLinkItem>> {
+    Ok(state.take())
+}
+
+/// ダウンロード済みファイルを既定のアプリで開く
+///
+/// 一括ダウンロードの保存先ディレクトリ配下のファイルのみ開くことができる。
+#[tauri::command]
+pub async fn open_downloaded_file(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    path: String,
+) -> CommandResult<()> {
+    let path = batch.resolve_downloaded_path(&path)?;
+    app.opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| CommandError::from(format!("Failed to open file: {}", e)))
+}
+
+/// ダウンロード済みファイルをFinder/エクスプローラーで表示
+///
+/// 一括ダウンロードの保存先ディレクトリ配下のファイルのみ表示できる。
+#[tauri::command]
+pub async fn reveal_in_folder(
+    app: AppHandle,
+    batch: State<'_, BatchState>,
+    path: String,
+) -> CommandResult<()> {
+    let path = batch.resolve_downloaded_path(&path)?;
+    app.opener()
+        .reveal_item_in_dir(path)
+        .map_err(|e| CommandError::from(format!("Failed to reveal file: {}", e)))
+}
+
+/// ダウンロード履歴を検索
+///
+/// プロジェクト・メーカー・期間・成否で絞り込み、新しい順にページ単位で返す。
+#[tauri::command]
+pub async fn get_download_history(
+    app: AppHandle,
+    query: HistoryQuery,
+) -> CommandResult<HistoryPage> {
+    let entries = history::load(&app)?;
+    Ok(history::query(&entries, &query))
+}
+
+/// ダウンロード履歴を文字列で検索
+///
+/// 型番・Spec No.・メーカー名・保存先パス・取得元URLのいずれかに `text` を含む履歴を、
+/// `query` の条件（省略時は条件なし）で絞り込んで新しい順にページ単位で返す。
+#[tauri::command]
+pub async fn search_history(
+    app: AppHandle,
+    text: String,
+    query: Option<HistoryQuery>,
+) -> CommandResult<HistoryPage> {
+    let query = HistoryQuery {
+        text: Some(text),
+        ..query.unwrap_or_default()
+    };
+    let entries = history::load(&app)?;
+    Ok(history::query(&entries, &query))
+}
+
+/// ダウンロード履歴を監査用のCSVに書き出す
+///
+/// `query` の条件に一致する履歴すべて（ページ分割しない）を、取得元URL・SHA-256を含めて
+/// `dest_path` に保存する。書き出した件数を返す。
+#[tauri::command]
+pub async fn export_history_csv(
+    app: AppHandle,
+    dest_path: String,
+    query: Option<HistoryQuery>,
+) -> CommandResult<usize> {
+    let entries = history::filter(&history::load(&app)?, &query.unwrap_or_default());
+    let locale = settings::load(&app).unwrap_or_default().locale;
+    let csv = report::render_audit_csv(&entries, locale);
+    tokio::fs::write(longpath::extended(Path::new(&dest_path)), csv)
+        .await
+        .map_err(|e| format!("Failed to write history CSV: {}", e))?;
+    Ok(entries.len())
+}
+
+/// メーカーが対応しているか確認
+#[tauri::command]
+pub async fn is_manufacturer_supported(
+    registry: State<'_, SharedRegistry>,
+    manufacturer: String,
+) -> CommandResult<bool> {
+    let registry = registry.load();
+    Ok(registry.get_provider(&manufacturer).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::Accessory;
+
+    #[test]
+    fn test_dropped_file_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            dropped_file_kind(&dir.path().join("照明器具リスト.XLSX")),
+            DroppedFileKind::Schedule
+        );
+        assert_eq!(
+            dropped_file_kind(&dir.path().join("fixtures.csv")),
+            DroppedFileKind::Schedule
+        );
+        assert_eq!(
+            dropped_file_kind(&dir.path().join("AD12345.IES")),
+            DroppedFileKind::Ies
+        );
+
+        // 拡張子が異なるIESファイルはヘッダーで判定する
+        let renamed = dir.path().join("AD12345.txt");
+        std::fs::write(&renamed, "IESNA:LM-63-2002\r\nTILT=NONE\r\n").unwrap();
+        assert_eq!(dropped_file_kind(&renamed), DroppedFileKind::Ies);
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "memo").unwrap();
+        assert_eq!(dropped_file_kind(&notes), DroppedFileKind::Unsupported);
+    }
+
+    #[test]
+    fn test_availability_status() {
+        let item = |psu: Option<&str>| BatchDownloadItem {
+            spec_no: "1001".to_string(),
+            manufacturer: "コイズミ".to_string(),
+            model_number: "AD12345".to_string(),
+            psu: psu.map(str::to_string),
+            asset_types: None,
+            quantity: None,
+            area: None,
+            wattage: None,
+            provider: None,
+            source_url: None,
+        };
+        let info = |ies: bool, accessories: Vec<Accessory>| ProductInfo {
+            model_number: "AD12345".to_string(),
+            product_name: None,
+            price: None,
+            ies_file_url: ies.then(|| "https://example.com/AD12345.ies".to_string()),
+            image_url: None,
+            product_page_url: None,
+            accessories,
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
+        };
+        let psu = Accessory {
+            model_number: "XE12345".to_string(),
+            name: Some("調光電源".to_string()),
+            kind: AccessoryKind::PowerSupply,
+            product_page_url: None,
+        };
+
+        let status = |item: &BatchDownloadItem, fetched: ProviderResult<ProductInfo>| {
+            availability_status(item, &fetched)
+        };
+        assert_eq!(
+            status(&item(None), Ok(info(true, vec![]))),
+            AvailabilityStatus::Found
+        );
+        assert_eq!(
+            status(&item(None), Ok(info(false, vec![]))),
+            AvailabilityStatus::NotFound
+        );
+        // 電源別売の製品はPSU型番の指定が必要
+        assert_eq!(
+            status(&item(None), Ok(info(true, vec![psu.clone()]))),
+            AvailabilityStatus::NeedsPsu
+        );
+        assert_eq!(
+            status(&item(Some("XE12345")), Ok(info(true, vec![psu]))),
+            AvailabilityStatus::Found
+        );
+        assert_eq!(
+            status(&item(None), Err(no_provider("山田照明"))),
+            AvailabilityStatus::Unsupported
+        );
+        assert_eq!(
+            status(
+                &item(None),
+                Err(ProviderError::new(
+                    ErrorCode::NetworkTimeout,
+                    "Request timed out"
+                ))
+            ),
+            AvailabilityStatus::Failed
+        );
+        assert_eq!(
+            status(
+                &item(None),
+                Err(ProviderError::new(
+                    ErrorCode::IesNotAvailable,
+                    "IES file not found for: AD12345"
+                ))
+            ),
+            AvailabilityStatus::NotFound
+        );
+        assert_eq!(
+            status(
+                &item(None),
+                Err(ProviderError::new(
+                    ErrorCode::HttpStatus,
+                    "Download failed with status: 404 Not Found"
+                ))
+            ),
+            AvailabilityStatus::NotFound
+        );
+    }
+
+    #[test]
+    fn test_find_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().to_str().unwrap();
+        std::fs::write(dir.path().join("1003.ies"), b"").unwrap();
+
+        let planned = [
+            ("1001", format!("{}/1001.ies", dest)),
+            ("1002", format!("{}/1001.ies", dest)),
+            ("1003", format!("{}/1003.ies", dest)),
+            ("1004", format!("{}/1004.ies", dest)),
+        ];
+        let collisions = find_collisions(
+            planned
+                .iter()
+                .map(|(spec_no, path)| (*spec_no, path.as_str())),
+        );
+
+        assert_eq!(collisions.len(), 2);
+        assert_eq!(collisions[0].spec_nos, vec!["1001", "1002"]);
+        assert!(!collisions[0].exists);
+        // 既存ファイルとの衝突
+        assert_eq!(collisions[1].spec_nos, vec!["1003"]);
+        assert!(collisions[1].exists);
+    }
 }
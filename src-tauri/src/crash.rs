@@ -0,0 +1,233 @@
+//! クラッシュレポート
+//!
+//! パニック時にバックトレース・直前の操作・アプリのバージョンをファイルに書き出す。
+//! 非同期タスク内のパニックは画面に現れずに消えてしまうため、次回起動時に
+//! フロントエンドから未確認のレポートを取得して表示・書き出しできるようにする。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 記録しておく直前の操作の件数
+const MAX_RECENT_OPERATIONS: usize = 50;
+
+/// 直前の操作（ログイベント）の記録
+static RECENT_OPERATIONS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// 直前の操作を記録（古いものから破棄）
+pub fn record_operation(operation: String) {
+    let Ok(mut recent) = RECENT_OPERATIONS.lock() else {
+        return;
+    };
+    if recent.len() >= MAX_RECENT_OPERATIONS {
+        recent.pop_front();
+    }
+    recent.push_back(operation);
+}
+
+/// 記録されている直前の操作（古い順）
+fn recent_operations() -> Vec<String> {
+    RECENT_OPERATIONS
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// ログイベントを直前の操作として記録するレイヤー
+pub struct RecentOperationsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentOperationsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldsVisitor::default();
+        event.record(&mut visitor);
+        record_operation(format!(
+            "{} {} {}",
+            Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            event.metadata().level(),
+            visitor.0
+        ));
+    }
+}
+
+/// イベントのフィールドを `message key=value ...` の形式で連結する
+#[derive(Default)]
+struct FieldsVisitor(String);
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, "{}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// クラッシュレポート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// レポートID（ファイル名から拡張子を除いたもの）
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    pub os: String,
+    /// パニックのメッセージ
+    pub message: String,
+    /// パニックが発生したソースの位置
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    /// 直前の操作（古い順）
+    pub recent_operations: Vec<String>,
+    /// エラー報告の送信先に送信済み
+    #[serde(default)]
+    pub reported: bool,
+}
+
+/// パニック時にクラッシュレポートを書き出すフックを登録
+///
+/// 既定のフック（標準エラー出力への表示）も引き続き呼び出す。
+pub fn install_panic_hook(crash_dir: PathBuf, app_version: String) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let created_at = Utc::now();
+        let report = CrashReport {
+            id: format!("crash-{}", created_at.format("%Y%m%d-%H%M%S%.3f")),
+            created_at,
+            app_version: app_version.clone(),
+            os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            recent_operations: recent_operations(),
+            reported: false,
+        };
+        if let Err(e) = write_report(&crash_dir, &report) {
+            eprintln!("Failed to write crash report: {}", e);
+        }
+        default_hook(info);
+    }));
+}
+
+/// クラッシュレポートをファイルに書き出す
+pub fn write_report(crash_dir: &Path, report: &CrashReport) -> Result<(), String> {
+    std::fs::create_dir_all(crash_dir)
+        .map_err(|e| format!("Failed to create crash directory: {}", e))?;
+    let json = serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(report_path(crash_dir, &report.id)?, json)
+        .map_err(|e| format!("Failed to write crash report: {}", e))
+}
+
+/// レポートIDからファイルパスを取得（ディレクトリ外を指すIDは拒否）
+pub fn report_path(crash_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !id.starts_with('.');
+    if !valid {
+        return Err(format!("Invalid crash report id: {}", id));
+    }
+    Ok(crash_dir.join(format!("{}.json", id)))
+}
+
+/// 未確認のクラッシュレポート一覧（新しい順）
+pub fn list_reports(crash_dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(crash_dir) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| {
+            let contents = std::fs::read(e.path()).ok()?;
+            serde_json::from_slice(&contents).ok()
+        })
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+    reports
+}
+
+/// クラッシュレポートを確認済みとして削除
+pub fn dismiss_report(crash_dir: &Path, id: &str) -> Result<(), String> {
+    let path = report_path(crash_dir, id)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete crash report: {}", e))
+}
+
+/// クラッシュレポートを指定パスに書き出す
+pub fn export_report(crash_dir: &Path, id: &str, dest_path: &Path) -> Result<(), String> {
+    let path = report_path(crash_dir, id)?;
+    if !path.exists() {
+        return Err(format!("Crash report file not found: {}", id));
+    }
+    std::fs::copy(&path, dest_path)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to export crash report: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_path() {
+        let dir = Path::new("/crashes");
+        assert_eq!(
+            report_path(dir, "crash-20261015-120000.123").unwrap(),
+            Path::new("/crashes/crash-20261015-120000.123.json")
+        );
+        assert!(report_path(dir, "../settings").is_err());
+        assert!(report_path(dir, "").is_err());
+    }
+
+    #[test]
+    fn test_list_and_dismiss() {
+        let temp = tempfile::tempdir().unwrap();
+        let report = CrashReport {
+            id: "crash-20261015-120000.000".to_string(),
+            created_at: Utc::now(),
+            app_version: "0.2.0".to_string(),
+            os: "linux x86_64".to_string(),
+            message: "boom".to_string(),
+            location: Some("src/commands.rs:1:1".to_string()),
+            thread: None,
+            backtrace: String::new(),
+            recent_operations: vec!["batch download started".to_string()],
+            reported: false,
+        };
+        write_report(temp.path(), &report).unwrap();
+
+        let reports = list_reports(temp.path());
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].message, "boom");
+
+        let export_dir = tempfile::tempdir().unwrap();
+        let dest = export_dir.path().join("crash.json");
+        export_report(temp.path(), &report.id, &dest).unwrap();
+        assert!(dest.exists());
+
+        dismiss_report(temp.path(), &report.id).unwrap();
+        assert!(list_reports(temp.path()).is_empty());
+        assert!(dismiss_report(temp.path(), &report.id).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! 重複ファイルの検出
+//!
+//! 器具リストでは同じ器具が複数のSpec No. で記載されることが多く、同じ内容のIESファイルを
+//! 何度も取得する。一括ダウンロードで保存したファイルをSHA-256で照合し、先に保存したファイルと
+//! 同じ内容のファイルを重複として報告する（保存先の既存のファイルとの照合は任意）。
+
+use crate::direct;
+use crate::longpath;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 照合済みのファイルの索引（SHA-256 → 最初に見つかったファイルのパス）
+#[derive(Debug, Default)]
+pub struct DuplicateIndex {
+    by_hash: HashMap<String, String>,
+    /// 今回のバッチで保存したファイル（既存のファイルとして索引に加えない）
+    written: HashSet<PathBuf>,
+    /// 保存先の既存のファイルとも照合するか
+    scan_existing: bool,
+    /// 既存のファイルを索引に加えたディレクトリ
+    scanned_dirs: HashSet<PathBuf>,
+}
+
+impl DuplicateIndex {
+    /// 索引を作成
+    ///
+    /// # Arguments
+    /// * `written` - 今回のバッチで保存したファイルのパス
+    /// * `scan_existing` - 保存先ディレクトリの既存のIESファイルとも照合するか
+    pub fn new<'a>(written: impl IntoIterator<Item = &'a str>, scan_existing: bool) -> Self {
+        Self {
+            written: written.into_iter().map(PathBuf::from).collect(),
+            scan_existing,
+            ..Default::default()
+        }
+    }
+
+    /// 同じ内容のファイルが先にあればそのパスを返す（なければこのファイルを索引に加えて None）
+    pub fn check(&mut self, path: &str, sha256: &str) -> Option<String> {
+        if self.scan_existing {
+            if let Some(dir) = Path::new(path).parent() {
+                self.scan_dir(dir);
+            }
+        }
+        match self.by_hash.get(sha256) {
+            Some(original) if original != path => Some(original.clone()),
+            Some(_) => None,
+            None => {
+                self.by_hash.insert(sha256.to_string(), path.to_string());
+                None
+            }
+        }
+    }
+
+    /// ディレクトリの既存のIESファイルを索引に加える（1ディレクトリにつき1回）
+    fn scan_dir(&mut self, dir: &Path) {
+        if !self.scanned_dirs.insert(dir.to_path_buf()) {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(longpath::extended(dir)) else {
+            return;
+        };
+        let mut existing: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| dir.join(entry.file_name()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("ies"))
+                    && !self.written.contains(path)
+            })
+            .collect();
+        // 同じ内容のファイルが複数ある場合に結果が変わらないよう、名前順に索引に加える
+        existing.sort();
+        for path in existing {
+            let Ok(bytes) = std::fs::read(longpath::extended(&path)) else {
+                continue;
+            };
+            self.by_hash
+                .entry(direct::sha256_hex(&bytes))
+                .or_insert_with(|| path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let mut index = DuplicateIndex::new(["/out/1001_A.ies", "/out/1002_A.ies"], false);
+        assert_eq!(index.check("/out/1001_A.ies", "aaa"), None);
+        assert_eq!(
+            index.check("/out/1002_A.ies", "aaa").as_deref(),
+            Some("/out/1001_A.ies")
+        );
+        assert_eq!(index.check("/out/1003_B.ies", "bbb"), None);
+        // 同じファイルは重複としない
+        assert_eq!(index.check("/out/1001_A.ies", "aaa"), None);
+    }
+
+    #[test]
+    fn test_check_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_string_lossy().into_owned();
+        std::fs::write(dir.path().join("old_A.ies"), b"A").unwrap();
+        std::fs::write(dir.path().join("1001_A.ies"), b"A").unwrap();
+        std::fs::write(dir.path().join("1002_B.ies"), b"B").unwrap();
+        let (sha_a, sha_b) = (direct::sha256_hex(b"A"), direct::sha256_hex(b"B"));
+
+        let written = [path("1001_A.ies"), path("1002_B.ies")];
+        let mut index = DuplicateIndex::new(written.iter().map(String::as_str), true);
+        assert_eq!(index.check(&written[0], &sha_a), Some(path("old_A.ies")));
+        assert_eq!(index.check(&written[1], &sha_b), None);
+
+        // 既存のファイルと照合しない場合
+        let mut index = DuplicateIndex::new(written.iter().map(String::as_str), false);
+        assert_eq!(index.check(&written[0], &sha_a), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! ディープリンク（`autosight://`）
+//!
+//! 社内Wikiや器具リストのExcelに埋め込んだリンクからアプリを開き、アイテムをダウンロード待ちに追加する。
+//!
+//! 形式: `autosight://download?manufacturer=KOIZUMI&model=XD93319&spec=1001`
+//! - `manufacturer`: メーカー名（必須）
+//! - `model`: 型番（必須）
+//! - `spec`: Spec No.（省略時は型番）
+//! - `psu`: PSU型番（省略可）
+//!
+//! フロントエンドの起動前に開かれたリンクも失わないよう、受け取ったアイテムは
+//! 取り出されるまで保持し、あわせて `deep-link-download` イベントで通知する。
+
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// ディープリンクのスキーム
+pub const SCHEME: &str = "autosight";
+
+/// パラメータの長さの上限（文字数）
+const MAX_PARAM_CHARS: usize = 200;
+
+/// ディープリンクで追加するアイテム（`BatchDownloadItem` と同じ形式）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkItem {
+    pub spec_no: String,
+    pub manufacturer: String,
+    pub model_number: String,
+    pub psu: Option<String>,
+}
+
+/// ディープリンクのURLを解析
+pub fn parse(url: &str) -> Result<DeepLinkItem, String> {
+    let parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid deep link: {}", e))?;
+    if parsed.scheme() != SCHEME {
+        return Err(format!("Unsupported deep link scheme: {}", parsed.scheme()));
+    }
+    let action = parsed.host_str().unwrap_or_default();
+    if action != "download" {
+        return Err(format!("Unsupported deep link action: {}", action));
+    }
+
+    let mut manufacturer = None;
+    let mut model_number = None;
+    let mut spec_no = None;
+    let mut psu = None;
+    for (key, value) in parsed.query_pairs() {
+        let value = value.trim();
+        if value.chars().count() > MAX_PARAM_CHARS {
+            return Err(format!("Deep link parameter too long: {}", key));
+        }
+        if value.is_empty() {
+            continue;
+        }
+        match key.as_ref() {
+            "manufacturer" => manufacturer = Some(value.to_string()),
+            "model" => model_number = Some(value.to_string()),
+            "spec" => spec_no = Some(value.to_string()),
+            "psu" => psu = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let manufacturer = manufacturer.ok_or("Deep link is missing manufacturer")?;
+    let model_number = model_number.ok_or("Deep link is missing model")?;
+    Ok(DeepLinkItem {
+        spec_no: spec_no.unwrap_or_else(|| model_number.clone()),
+        manufacturer,
+        model_number,
+        psu,
+    })
+}
+
+/// 取り出されていないディープリンクのアイテム（Tauriの管理状態として保持する）
+#[derive(Default)]
+pub struct DeepLinkState {
+    pending: Mutex<Vec<DeepLinkItem>>,
+}
+
+impl DeepLinkState {
+    /// アイテムを追加
+    pub fn push(&self, item: DeepLinkItem) {
+        self.pending.lock().unwrap().push(item);
+    }
+
+    /// 保持しているアイテムをすべて取り出す
+    pub fn take(&self) -> Vec<DeepLinkItem> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let item =
+            parse("autosight://download?manufacturer=KOIZUMI&model=XD93319&spec=1001").unwrap();
+        assert_eq!(
+            item,
+            DeepLinkItem {
+                spec_no: "1001".to_string(),
+                manufacturer: "KOIZUMI".to_string(),
+                model_number: "XD93319".to_string(),
+                psu: None,
+            }
+        );
+
+        // Spec No.を省略した場合は型番。パーセントエンコードされた値も扱う
+        let item = parse(
+            "autosight://download?manufacturer=%E3%82%B3%E3%82%A4%E3%82%BA%E3%83%9F&model=AD12345&psu=XE92701",
+        )
+        .unwrap();
+        assert_eq!(item.manufacturer, "コイズミ");
+        assert_eq!(item.spec_no, "AD12345");
+        assert_eq!(item.psu.as_deref(), Some("XE92701"));
+
+        assert!(parse("https://download?manufacturer=KOIZUMI&model=XD93319").is_err());
+        assert!(parse("autosight://delete?manufacturer=KOIZUMI&model=XD93319").is_err());
+        assert!(parse("autosight://download?manufacturer=KOIZUMI").is_err());
+        assert!(parse(&format!(
+            "autosight://download?manufacturer=KOIZUMI&model={}",
+            "X".repeat(MAX_PARAM_CHARS + 1)
+        ))
+        .is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! URL指定ダウンロード
+//!
+//! プロバイダーのないメーカーについて、ユーザーが見つけたURLから直接ファイルを取得する。
+//! 保存先・ファイル名・履歴の扱いは一括ダウンロードと揃える。
+
+use crate::buffer::{self, BufferPermit};
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use crate::providers::{
+    ambiguous_matches, filename_from_content_disposition, filename_from_url, retry_incomplete,
+    run_blocking, select_best_file, send_request, status_error, verify_length, verify_written,
+    AssetType, DownloadResult, DownloadSource, DownloadWarning, DownloadWarningKind,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// URL指定ダウンロードのリクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlDownloadRequest {
+    /// ダウンロードするURL（http / https）
+    pub url: String,
+    /// Spec No.（ファイル名に使用）
+    pub spec_no: String,
+    /// メーカー名（履歴に記録）
+    #[serde(default)]
+    pub manufacturer: String,
+    /// 型番（元ファイル名が取得できない場合のファイル名に使用）
+    #[serde(default)]
+    pub model_number: String,
+    /// アセット種別（省略時はIES）
+    #[serde(default = "default_asset_type")]
+    pub asset_type: AssetType,
+    /// 保存先ディレクトリ（空の場合は設定の既定の保存先ディレクトリ）
+    pub dest_dir: String,
+    /// ダウンロード履歴に記録するプロジェクトID
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+fn default_asset_type() -> AssetType {
+    AssetType::Ies
+}
+
+/// URL指定ダウンロードの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlDownloadResult {
+    #[serde(flatten)]
+    pub result: DownloadResult,
+    /// 保存したファイルのSHA-256（16進数、成功時のみ）
+    pub sha256: Option<String>,
+}
+
+/// URLを検証（http / https のみ許可）
+pub fn validate_url(url: &str) -> Result<reqwest::Url, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+    }
+    Ok(parsed)
+}
+
+/// 取得した内容がアセットとして妥当か検証
+///
+/// ログインページ・エラーページ等のHTMLを保存しないようにする。
+fn validate_content(asset_type: AssetType, bytes: &[u8]) -> ProviderResult<()> {
+    if bytes.is_empty() {
+        return Err(ProviderError::new(
+            ErrorCode::InvalidContent,
+            "Downloaded file is empty",
+        ));
+    }
+
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        return Err(ProviderError::new(
+            ErrorCode::InvalidContent,
+            "URL returned an HTML page instead of a file",
+        ));
+    }
+
+    // IESファイル（LM-63）は TILT= 行を必ず含む
+    if asset_type == AssetType::Ies && !String::from_utf8_lossy(bytes).contains("TILT=") {
+        return Err(ProviderError::new(
+            ErrorCode::InvalidContent,
+            "Downloaded file is not a valid IES file (TILT= line not found)",
+        ));
+    }
+
+    Ok(())
+}
+
+/// SHA-256を16進数文字列で取得
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// URLからファイルを取得し、`dest_dir` に保存
+///
+/// # Arguments
+/// * `client` - HTTPクライアント
+/// * `request` - リクエスト
+/// * `dest_dir` - 保存先ディレクトリ（アセット種別のサブフォルダを含む）
+/// * `overwrite_existing` - 同名のファイルがある場合に上書きするか
+/// * `filename` - 元ファイル名と取得した内容から保存先ファイル名を生成する関数
+pub async fn download<F>(
+    client: &reqwest::Client,
+    request: &UrlDownloadRequest,
+    dest_dir: &str,
+    overwrite_existing: bool,
+    filename: F,
+) -> UrlDownloadResult
+where
+    F: Fn(Option<&str>, &[u8]) -> String,
+{
+    // 受信が不完全な場合は1回だけやり直す
+    let fetched = retry_incomplete(|| {
+        fetch_and_save(client, request, dest_dir, overwrite_existing, &filename)
+    })
+    .await;
+    match fetched {
+        Ok((result, sha256)) => UrlDownloadResult {
+            result,
+            sha256: Some(sha256),
+        },
+        Err(e) => UrlDownloadResult {
+            result: DownloadResult::failure(e),
+            sha256: None,
+        },
+    }
+}
+
+/// URLから取得した内容
+struct Fetched {
+    bytes: Vec<u8>,
+    /// 元ファイル名（Content-Disposition、なければURLの末尾）
+    original_filename: Option<String>,
+    source: DownloadSource,
+    /// 保存し終えるまで保持するバッファの使用枠
+    _permit: BufferPermit<'static>,
+}
+
+/// URLから内容を取得（保存はしない）
+async fn fetch(client: &reqwest::Client, url: &str) -> ProviderResult<Fetched> {
+    let url = validate_url(url).map_err(|e| ProviderError::new(ErrorCode::InvalidInput, e))?;
+
+    let response = send_request(client.get(url.clone()))
+        .await
+        .map_err(|e| e.context("Download request failed"))?;
+
+    if !response.status().is_success() {
+        return Err(status_error(
+            response.status(),
+            format!("Download failed with status: {}", response.status()),
+        ));
+    }
+
+    // 元ファイル名はContent-Disposition、なければURLの末尾から取得
+    let original_filename = response
+        .headers()
+        .get("content-disposition")
+        .and_then(|h| h.to_str().ok())
+        .and_then(filename_from_content_disposition)
+        .or_else(|| filename_from_url(response.url()))
+        .or_else(|| filename_from_url(&url));
+    let source = DownloadSource::from_response(&response);
+
+    let total_bytes = response.content_length();
+    let permit = buffer::acquire(total_bytes).await;
+    let bytes = response.bytes().await.map_err(ProviderError::wrap(
+        ErrorCode::NetworkError,
+        "Failed to read file content",
+    ))?;
+    verify_length(total_bytes, bytes.len() as u64)?;
+
+    Ok(Fetched {
+        bytes: bytes.to_vec(),
+        original_filename,
+        source,
+        _permit: permit,
+    })
+}
+
+async fn fetch_and_save(
+    client: &reqwest::Client,
+    request: &UrlDownloadRequest,
+    dest_dir: &str,
+    overwrite_existing: bool,
+    filename: &impl Fn(Option<&str>, &[u8]) -> String,
+) -> ProviderResult<(DownloadResult, String)> {
+    let fetched = fetch(client, &request.url).await?;
+    let bytes = &fetched.bytes;
+    validate_content(request.asset_type, bytes)?;
+
+    let filename = filename(fetched.original_filename.as_deref(), bytes);
+    let dest_path = Path::new(dest_dir).join(&filename);
+    if !overwrite_existing && longpath::extended(&dest_path).exists() {
+        return Err(ProviderError::new(
+            ErrorCode::FileExists,
+            format!("File already exists: {}", dest_path.display()),
+        ));
+    }
+
+    tokio::fs::create_dir_all(longpath::extended(dest_dir))
+        .await
+        .map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to create directory",
+        ))?;
+    tokio::fs::write(longpath::extended(&dest_path), bytes)
+        .await
+        .map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+    verify_written(&longpath::extended(&dest_path), bytes.len() as u64).await?;
+
+    Ok((
+        DownloadResult::success(
+            dest_path.to_string_lossy().to_string(),
+            bytes.len() as u64,
+            fetched.original_filename,
+        )
+        .with_source(fetched.source),
+        sha256_hex(bytes),
+    ))
+}
+
+/// ZIPか（ローカルファイルヘッダーのシグネチャで判定）
+fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+/// ZIPから型番に最も一致するIESファイルを取り出す
+///
+/// 戻り値は (ZIP内のファイル名, 内容, 同程度に一致した他のファイル)。
+/// IESファイルが1つだけの場合は型番が一致しなくてもそのファイルを使用する。
+fn extract_ies(bytes: &[u8], model_number: &str) -> ProviderResult<(String, Vec<u8>, Vec<String>)> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(ProviderError::wrap(
+        ErrorCode::ZipInvalid,
+        "Failed to open ZIP",
+    ))?;
+    let files: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.to_lowercase().ends_with(".ies"))
+        .map(str::to_string)
+        .collect();
+    if files.is_empty() {
+        return Err(ProviderError::new(
+            ErrorCode::ZipNoMatch,
+            "No .ies files found in ZIP",
+        ));
+    }
+    let best_file = select_best_file(model_number, &files)
+        .or_else(|| (files.len() == 1).then(|| files[0].clone()))
+        .ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::ZipNoMatch,
+                format!("No matching .ies file found for: {}", model_number),
+            )
+        })?;
+
+    let mut contents = Vec::new();
+    archive
+        .by_name(&best_file)
+        .and_then(|mut entry| Ok(entry.read_to_end(&mut contents)?))
+        .map_err(|e| {
+            ProviderError::new(
+                ErrorCode::ZipInvalid,
+                format!("Failed to read {} from ZIP: {}", best_file, e),
+            )
+        })?;
+    let others = ambiguous_matches(model_number, &files, &best_file);
+    Ok((best_file, contents, others))
+}
+
+/// 手動で指定したURLからIESファイルを取得し、`dest_path` に保存
+///
+/// 自動の照合で誤った配光のファイルが選ばれる場合や、代理店のページにのみ掲載されている
+/// 場合に、一括ダウンロードの取得元を置き換える。ZIPの場合は型番に最も一致するIESファイルを
+/// 取り出す（同程度に一致するファイルがある場合は警告を付ける）。
+/// 定義ファイルのプロバイダーも、製品ページで見つけたリンクからの取得に使用する。
+///
+/// `client` は呼び出し元のHTTPクライアント（プロキシ・タイムアウト等の設定を反映したもの）。
+pub async fn download_ies_to(
+    client: &reqwest::Client,
+    url: &str,
+    model_number: &str,
+    dest_path: &str,
+) -> ProviderResult<DownloadResult> {
+    // 受信が不完全な場合は1回だけやり直す
+    let fetched = retry_incomplete(|| fetch(client, url)).await?;
+
+    let (contents, original_filename, others) = if is_zip(&fetched.bytes) {
+        let bytes = fetched.bytes;
+        let model_number = model_number.to_string();
+        let (name, contents, others) =
+            run_blocking(move || extract_ies(&bytes, &model_number)).await?;
+        let original_filename = name.rsplit(['/', '\\']).next().map(str::to_string);
+        (contents, original_filename, others)
+    } else {
+        (fetched.bytes, fetched.original_filename, Vec::new())
+    };
+    validate_content(AssetType::Ies, &contents)?;
+
+    let dest = longpath::extended(dest_path);
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
+    }
+    tokio::fs::write(&dest, &contents)
+        .await
+        .map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+    verify_written(&dest, contents.len() as u64).await?;
+
+    let mut result = DownloadResult::success(
+        dest_path.to_string(),
+        contents.len() as u64,
+        original_filename,
+    )
+    .with_source(fetched.source);
+    if !others.is_empty() {
+        let message = format!(
+            "Selected {} for {}, but {} also matched",
+            result.original_filename.as_deref().unwrap_or_default(),
+            model_number,
+            others.join(", ")
+        );
+        result = result.with_warning(DownloadWarning::new(
+            DownloadWarningKind::AmbiguousZipMatch,
+            message,
+        ));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://example.com/files/a.ies").is_ok());
+        assert!(validate_url("file:///etc/passwd").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_filename_from_url() {
+        let url = validate_url("https://example.com/dl/ABC-123%20v2.ies?x=1").unwrap();
+        assert_eq!(filename_from_url(&url).as_deref(), Some("ABC-123%20v2.ies"));
+        assert_eq!(
+            filename_from_url(&validate_url("https://example.com/").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_content() {
+        let ies = b"IESNA:LM-63-2002\r\n[TEST] x\r\nTILT=NONE\r\n";
+        assert!(validate_content(AssetType::Ies, ies).is_ok());
+        assert!(validate_content(AssetType::Ies, b"").is_err());
+        assert!(validate_content(AssetType::Ies, b"hello").is_err());
+        assert!(validate_content(AssetType::SpecSheet, b"<!DOCTYPE html><html></html>").is_err());
+        assert!(validate_content(AssetType::SpecSheet, b"%PDF-1.7").is_ok());
+    }
+
+    #[test]
+    fn test_extract_ies() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["IES/AD12345_30.ies", "IES/AD12346_30.ies", "readme.txt"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(name.as_bytes()).unwrap();
+        }
+        let bytes = writer.finish().unwrap().into_inner();
+
+        assert!(is_zip(&bytes));
+        assert!(!is_zip(b"IESNA:LM-63-2002"));
+
+        let (name, contents, others) = extract_ies(&bytes, "AD12346").unwrap();
+        assert_eq!(name, "IES/AD12346_30.ies");
+        assert_eq!(contents, b"IES/AD12346_30.ies");
+        assert!(others.is_empty());
+        assert!(extract_ies(&bytes, "XX999").is_err());
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! IESファイルのダウンロードキャッシュ
+//!
+//! 一括ダウンロードで取得したIESファイルを、メーカー（プロバイダーID）・型番・PSUごとに
+//! アプリのキャッシュディレクトリに保存しておき、同じプロジェクトを再実行した場合等に
+//! メーカーサイトからダウンロードし直さずにキャッシュから取り出す。
+//! 検証（[`crate::photometry::validate_ies`]）に成功したファイルだけを保存する。
+//!
+//! キャッシュを使うかはバッチごとに指定する（`use_cache`）。指定したバッチの処理中だけ
+//! [`scope`] で保存先を設定し、それ以外（単品のダウンロード等）では読み書きしない。
+//!
+//! 形式: {キャッシュディレクトリ}/ies_download/{プロバイダーID}/{型番}[__{PSU}].ies と、
+//! 同じ名前の .json（元ファイル名・取得元・保存日時）
+
+use crate::cache;
+use crate::providers::{DownloadResult, DownloadSource};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+tokio::task_local! {
+    /// 実行中のバッチのキャッシュの保存先（キャッシュを使わない場合は None）
+    static CACHE_DIR: Option<PathBuf>;
+}
+
+/// キャッシュの保存先を設定して処理を実行（`dir` が None の場合はキャッシュを使わない）
+pub async fn scope<F: Future>(dir: Option<PathBuf>, future: F) -> F::Output {
+    CACHE_DIR.scope(dir, future).await
+}
+
+/// 実行中の処理のキャッシュの保存先
+fn current_dir() -> Option<PathBuf> {
+    CACHE_DIR.try_with(|dir| dir.clone()).ok().flatten()
+}
+
+/// キャッシュしたファイルの情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedEntry {
+    /// サーバーから取得した元のファイル名
+    original_filename: Option<String>,
+    /// 取得元
+    source: Option<DownloadSource>,
+    /// キャッシュに保存した日時
+    cached_at: DateTime<Utc>,
+}
+
+/// キャッシュのファイルのパス（拡張子なし）
+///
+/// 型番・PSUは大文字小文字と前後の空白を区別しない。
+fn entry_path(dir: &Path, provider_id: &str, model_number: &str, psu: Option<&str>) -> PathBuf {
+    let mut stem = cache::file_stem(&model_number.trim().to_uppercase());
+    if let Some(psu) = psu.map(str::trim).filter(|psu| !psu.is_empty()) {
+        stem.push_str("__");
+        stem.push_str(&cache::file_stem(&psu.to_uppercase()));
+    }
+    dir.join(cache::file_stem(provider_id)).join(stem)
+}
+
+/// キャッシュにあるIESファイルを `dest_path` にコピーする（キャッシュを使わない場合・ない場合は None）
+pub fn restore(
+    provider_id: &str,
+    model_number: &str,
+    psu: Option<&str>,
+    dest_path: &str,
+) -> Option<DownloadResult> {
+    let dir = current_dir()?;
+    restore_from(&dir, provider_id, model_number, psu, dest_path)
+}
+
+fn restore_from(
+    dir: &Path,
+    provider_id: &str,
+    model_number: &str,
+    psu: Option<&str>,
+    dest_path: &str,
+) -> Option<DownloadResult> {
+    let path = entry_path(dir, provider_id, model_number, psu);
+    let entry: CachedEntry = std::fs::read_to_string(path.with_extension("json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())?;
+    let size = match std::fs::copy(path.with_extension("ies"), dest_path) {
+        Ok(size) => size,
+        Err(e) => {
+            tracing::warn!(error = %e, model_number, "failed to restore cached IES file");
+            return None;
+        }
+    };
+    tracing::debug!(model_number, cached_at = %entry.cached_at, "using cached IES file");
+    let mut result = DownloadResult::success(dest_path.to_string(), size, entry.original_filename);
+    result.source = entry.source;
+    result.timing = Default::default();
+    result.from_cache = true;
+    Some(result)
+}
+
+/// ダウンロードしたIESファイル（`path`）をキャッシュに保存（キャッシュを使わない場合は何もしない）
+///
+/// 保存できなくてもダウンロードの結果には影響しないため、失敗はログに記録するだけにする。
+pub fn store(
+    provider_id: &str,
+    model_number: &str,
+    psu: Option<&str>,
+    path: &Path,
+    result: &DownloadResult,
+) {
+    let Some(dir) = current_dir() else {
+        return;
+    };
+    if let Err(e) = store_in(&dir, provider_id, model_number, psu, path, result) {
+        tracing::warn!(error = %e, model_number, "failed to cache IES file");
+    }
+}
+
+fn store_in(
+    dir: &Path,
+    provider_id: &str,
+    model_number: &str,
+    psu: Option<&str>,
+    path: &Path,
+    result: &DownloadResult,
+) -> Result<(), String> {
+    let dest = entry_path(dir, provider_id, model_number, psu);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::copy(path, dest.with_extension("ies")).map_err(|e| e.to_string())?;
+    let entry = CachedEntry {
+        original_filename: result.original_filename.clone(),
+        source: result.source.clone(),
+        cached_at: Utc::now(),
+    };
+    let json = serde_json::to_string_pretty(&entry).map_err(|e| e.to_string())?;
+    std::fs::write(dest.with_extension("json"), json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_restore() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("ies_download");
+        let downloaded = temp.path().join("temp_1001.ies");
+        std::fs::write(&downloaded, "IESNA:LM-63-2002\n").unwrap();
+        let mut result = DownloadResult::success(
+            downloaded.to_string_lossy().into_owned(),
+            17,
+            Some("AD12345.ies".to_string()),
+        );
+        result.source = Some(DownloadSource {
+            url: "https://example.com/AD12345.ies".to_string(),
+            ..Default::default()
+        });
+
+        store_in(
+            &dir,
+            "koizumi",
+            "AD12345",
+            Some("SE 1"),
+            &downloaded,
+            &result,
+        )
+        .unwrap();
+
+        let dest = temp.path().join("restored.ies");
+        let dest = dest.to_string_lossy();
+        // PSUが異なる場合は別のファイル
+        assert!(restore_from(&dir, "koizumi", "AD12345", None, &dest).is_none());
+        assert!(restore_from(&dir, "tokistar", "AD12345", Some("SE 1"), &dest).is_none());
+
+        let restored = restore_from(&dir, "koizumi", " ad12345 ", Some("se 1"), &dest).unwrap();
+        assert!(restored.success);
+        assert!(restored.from_cache);
+        assert_eq!(restored.file_size, Some(17));
+        assert_eq!(restored.original_filename.as_deref(), Some("AD12345.ies"));
+        assert_eq!(restored.timing.bytes_transferred, 0);
+        assert_eq!(
+            restored.source.unwrap().url,
+            "https://example.com/AD12345.ies"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.as_ref()).unwrap(),
+            "IESNA:LM-63-2002\n"
+        );
+    }
+
+    #[test]
+    fn test_without_scope() {
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("restored.ies");
+        assert!(restore("koizumi", "AD12345", None, &dest.to_string_lossy()).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+//! エラーコード
+//!
+//! フロントエンドがエラーメッセージの文字列に依存せずに表示文言の切り替えや
+//! 再試行の判断をできるよう、失敗の種別を固定のコードで返す。
+//! コードの値（`PROVIDER_NOT_FOUND` 等）はフロントエンドとの契約のため変更しないこと。
+//! 各コードは再試行で解決する可能性があるか（retryable）どうかにも分類する。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// エラーの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// メーカー名に対応するプロバイダーがない
+    ProviderNotFound,
+    /// IESファイルが掲載されていない
+    IesNotAvailable,
+    /// IES以外のアセットが掲載されていない、またはメーカーが提供していない
+    AssetNotAvailable,
+    /// 廃番（後継品の型番はメッセージに含める）
+    Discontinued,
+    /// 配光データが複数あり、型番・PSUから1つに絞り込めない（候補はメッセージに含める）
+    IesAmbiguous,
+    /// ZIP内に型番と一致するファイルがない
+    ZipNoMatch,
+    /// ZIPを展開できない
+    ZipInvalid,
+    /// タイムアウト
+    NetworkTimeout,
+    /// 接続・通信エラー
+    NetworkError,
+    /// 受信・保存が途中で切れた（受信・保存したサイズが応答のサイズと一致しない）
+    IncompleteDownload,
+    /// 再試行してもレート制限（429 Too Many Requests）が解除されなかった
+    RateLimited,
+    /// サーバーがエラーステータスを返した
+    HttpStatus,
+    /// 取得した内容が不正（HTMLページ・空ファイル等）
+    InvalidContent,
+    /// 同名のファイルが既に存在する
+    FileExists,
+    /// ファイルが見つからない
+    FileNotFound,
+    /// ファイルの読み書きに失敗
+    FileSystem,
+    /// 入力値・設定値が不正
+    InvalidInput,
+    /// キャンセルされた
+    Cancelled,
+    /// オフラインモードで、キャッシュ・IESライブラリにない（通信の再生モードで記録にない場合を含む）
+    OfflineNotCached,
+    /// 取得したページからリンク・仕様を何も抽出できない（サイトの構成の変更。プロバイダーの更新が必要）
+    ProviderOutdated,
+    /// 分類できないエラー
+    Unknown,
+}
+
+/// メッセージの先頭の定型文と種別（先に一致したものを使う）
+///
+/// 型番・URL・ライブラリのエラー等の埋め込まれた値で誤判定しないよう、先頭の定型文のみで判定する。
+const MESSAGE_PREFIXES: &[(&str, ErrorCode)] = &[
+    ("provider outdated:", ErrorCode::ProviderOutdated),
+    ("cancelled", ErrorCode::Cancelled),
+    ("offline, not cached", ErrorCode::OfflineNotCached),
+    ("not recorded in cassette", ErrorCode::OfflineNotCached),
+    ("discontinued:", ErrorCode::Discontinued),
+    ("no provider for", ErrorCode::ProviderNotFound),
+    ("unknown provider", ErrorCode::ProviderNotFound),
+    ("multiple ies files", ErrorCode::IesAmbiguous),
+    ("no matching", ErrorCode::ZipNoMatch),
+    ("no .ies files found in zip", ErrorCode::ZipNoMatch),
+    ("failed to open zip", ErrorCode::ZipInvalid),
+    ("ies file not", ErrorCode::IesNotAvailable),
+    ("ies archive not found", ErrorCode::IesNotAvailable),
+    ("ies link not found", ErrorCode::IesNotAvailable),
+    ("no product image", ErrorCode::AssetNotAvailable),
+    ("download failed with status: 429", ErrorCode::RateLimited),
+    (
+        "image download failed with status: 429",
+        ErrorCode::RateLimited,
+    ),
+    ("download failed with status", ErrorCode::HttpStatus),
+    ("image download failed with status", ErrorCode::HttpStatus),
+    ("rules feed returned status", ErrorCode::HttpStatus),
+    ("zip download failed", ErrorCode::NetworkError),
+    ("rules download failed", ErrorCode::NetworkError),
+    ("failed to read response", ErrorCode::NetworkError),
+    ("failed to read file content", ErrorCode::NetworkError),
+    ("failed to read zip content", ErrorCode::NetworkError),
+    ("failed to read image", ErrorCode::NetworkError),
+    ("incomplete download", ErrorCode::IncompleteDownload),
+    ("file already exists", ErrorCode::FileExists),
+    ("file not found", ErrorCode::FileNotFound),
+    ("downloaded file is empty", ErrorCode::InvalidContent),
+    ("downloaded file is not a valid", ErrorCode::InvalidContent),
+    ("not a valid", ErrorCode::InvalidContent),
+    ("url returned an html page", ErrorCode::InvalidContent),
+    ("image url returned an html page", ErrorCode::InvalidContent),
+    ("failed to decode", ErrorCode::InvalidContent),
+    ("failed to create", ErrorCode::FileSystem),
+    ("failed to write", ErrorCode::FileSystem),
+    ("failed to rename", ErrorCode::FileSystem),
+    ("failed to read", ErrorCode::FileSystem),
+    ("failed to save", ErrorCode::FileSystem),
+    ("failed to open", ErrorCode::FileSystem),
+    ("invalid", ErrorCode::InvalidInput),
+    ("unsupported", ErrorCode::InvalidInput),
+];
+
+impl ErrorCode {
+    /// エラーメッセージから種別を判定
+    ///
+    /// 種別を判定済みのエラー（[`ProviderError`]）を返せない箇所（ライブラリ・アプリの他の処理の
+    /// 文字列のエラー）のみに使う。メッセージはバックエンドで生成した英語の定型文であることを前提とし、
+    /// 先頭の定型文（[`MESSAGE_PREFIXES`]）、`"<処理> request failed: ..."` の形の通信エラー、
+    /// `"<設定項目> must ..."` の形の入力値のエラーのみで判定する。
+    pub fn classify(message: &str) -> Self {
+        let lower = message.trim_start().to_lowercase();
+        if let Some((_, code)) = MESSAGE_PREFIXES
+            .iter()
+            .find(|(prefix, _)| lower.starts_with(prefix))
+        {
+            return *code;
+        }
+        let label = lower.split(": ").next().unwrap_or_default();
+        if label.ends_with(" request failed") {
+            return ErrorCode::NetworkError;
+        }
+        let mut words = lower.split_whitespace();
+        if words.nth(1) == Some("must") {
+            return ErrorCode::InvalidInput;
+        }
+        ErrorCode::Unknown
+    }
+
+    /// 再試行で解決する可能性があるか（通信エラー・レート制限・サーバーエラー・キャンセル）
+    ///
+    /// 掲載がない・入力が不正等の恒久的なエラーは、再試行しても結果が変わらないため false。
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::NetworkTimeout
+                | ErrorCode::NetworkError
+                | ErrorCode::IncompleteDownload
+                | ErrorCode::RateLimited
+                | ErrorCode::HttpStatus
+                | ErrorCode::ZipInvalid
+                | ErrorCode::Cancelled
+        )
+    }
+}
+
+/// コマンドのエラー（フロントエンドには `{ code, message }` として渡る）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub code: ErrorCode,
+    /// 詳細メッセージ（ログ・デバッグ用）
+    pub message: String,
+    /// 再試行で解決する可能性があるか
+    pub retryable: bool,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: code.is_retryable(),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::classify(&message), message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// コマンドの戻り値
+pub type CommandResult<T> = Result<T, CommandError>;
+
+/// 種別を判定済みのエラー（プロバイダー・ダウンロード処理の戻り値）
+///
+/// 失敗した箇所で種別を決めて返し、コマンドのエラー・ダウンロード結果まで種別を引き継ぐ。
+/// 文字列のエラー（ライブラリ・アプリの他の処理のメッセージ）から変換した場合のみ、
+/// [`ErrorCode::classify`] でメッセージから種別を判定する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ProviderError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// 処理の説明を付けて変換する `map_err` 用の関数（例: `"Failed to write file: ..."`）
+    pub fn wrap<E: fmt::Display>(code: ErrorCode, what: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |e| Self::new(code, format!("{}: {}", what, e))
+    }
+}
+
+impl From<String> for ProviderError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::classify(&message), message)
+    }
+}
+
+impl From<&str> for ProviderError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl From<ProviderError> for CommandError {
+    fn from(error: ProviderError) -> Self {
+        Self::new(error.code, error.message)
+    }
+}
+
+/// 文字列のエラーを返す処理に渡す場合（種別はメッセージから判定し直す）
+impl From<ProviderError> for String {
+    fn from(error: ProviderError) -> Self {
+        error.message
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// プロバイダー・ダウンロード処理の戻り値
+pub type ProviderResult<T> = Result<T, ProviderError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                "No provider for manufacturer: 大光電機",
+                ErrorCode::ProviderNotFound,
+            ),
+            (
+                "IES file not available for: AD12345",
+                ErrorCode::IesNotAvailable,
+            ),
+            (
+                "IES file not found for: AD12345 nor AD1",
+                ErrorCode::IesNotAvailable,
+            ),
+            (
+                "Download request failed: error sending request: operation timed out",
+                ErrorCode::NetworkError,
+            ),
+            ("Search request failed: dns error", ErrorCode::NetworkError),
+            (
+                "Incomplete download: received 512 of 2048 bytes",
+                ErrorCode::IncompleteDownload,
+            ),
+            (
+                "Offline, not cached: product info for AD12345",
+                ErrorCode::OfflineNotCached,
+            ),
+            (
+                "Discontinued: AD12345, successor: AD12346",
+                ErrorCode::Discontinued,
+            ),
+            (
+                "Multiple IES files found for AD12345: 配光データ (https://example.com/id/1)",
+                ErrorCode::IesAmbiguous,
+            ),
+            (
+                "No matching .ies file found for: OSP01",
+                ErrorCode::ZipNoMatch,
+            ),
+            ("No .ies files found in ZIP", ErrorCode::ZipNoMatch),
+            (
+                "Download failed with status: 404 Not Found",
+                ErrorCode::HttpStatus,
+            ),
+            (
+                "Download failed with status: 429 Too Many Requests",
+                ErrorCode::RateLimited,
+            ),
+            (
+                "Image URL returned an HTML page instead of an image",
+                ErrorCode::InvalidContent,
+            ),
+            (
+                "File already exists: /tmp/1001_A.ies",
+                ErrorCode::FileExists,
+            ),
+            (
+                "Failed to write file: permission denied",
+                ErrorCode::FileSystem,
+            ),
+            (
+                "concurrency must be between 1 and 16",
+                ErrorCode::InvalidInput,
+            ),
+            (
+                "Provider outdated: no download link recognized on Koizumi page for AD12345 \
+                 (evidence: title \"商品詳細\", text \"AD12345 timed out\")",
+                ErrorCode::ProviderOutdated,
+            ),
+            ("Cancelled", ErrorCode::Cancelled),
+            ("something unexpected", ErrorCode::Unknown),
+            // 埋め込まれた値（型番・URL・ライブラリのエラー）では判定しない
+            (
+                "Failed to parse IES library: invalid type at line 1",
+                ErrorCode::Unknown,
+            ),
+            (
+                "Spec No. 1001 must be unique (row 3 timed out)",
+                ErrorCode::Unknown,
+            ),
+            (
+                "Token response has no access_token: file already exists",
+                ErrorCode::Unknown,
+            ),
+        ];
+        for (message, code) in cases {
+            assert_eq!(ErrorCode::classify(message), code, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let error = CommandError::from("No provider for manufacturer: X");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "PROVIDER_NOT_FOUND",
+                "message": "No provider for manufacturer: X",
+                "retryable": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_provider_error() {
+        // 種別を判定済みのエラーはメッセージによらず種別を引き継ぐ
+        let error = ProviderError::new(
+            ErrorCode::IesNotAvailable,
+            "Detail request failed: Cancelled",
+        );
+        let command = CommandError::from(error.clone());
+        assert_eq!(command.code, ErrorCode::IesNotAvailable);
+        assert_eq!(command.message, error.message);
+        assert!(!command.retryable);
+
+        // 文字列のエラーはメッセージから判定する
+        let error = ProviderError::from("Failed to write file: permission denied");
+        assert_eq!(error.code, ErrorCode::FileSystem);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(ErrorCode::NetworkTimeout.is_retryable());
+        assert!(ErrorCode::Cancelled.is_retryable());
+        assert!(!ErrorCode::IesNotAvailable.is_retryable());
+        assert!(!ErrorCode::ZipNoMatch.is_retryable());
+        assert!(!ErrorCode::Unknown.is_retryable());
+    }
+}
@@ -0,0 +1,301 @@
+//! エラー報告（Sentry互換）
+//!
+//! 社内ツールを一元的に監視している事業所向けに、設定で有効にした場合のみ
+//! バックエンドのエラー（ERRORレベルのログ）とパニックを、指定したSentry互換のDSNに送信する。
+//! パニックはプロセスが不安定な状態で送信せず、次回起動時にクラッシュレポートから送信する。
+
+use crate::crash::{self, CrashReport};
+use crate::providers::send_request;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// エラー報告の設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ErrorReportingSettings {
+    /// 送信を行う（既定は無効）
+    pub enabled: bool,
+    /// Sentry互換のDSN（例: `https://<key>@sentry.example.com/<project>`）
+    pub dsn: Option<String>,
+    /// 環境名（例: "production"。送信先での絞り込み用）
+    pub environment: Option<String>,
+}
+
+impl ErrorReportingSettings {
+    /// DSNを検証
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(dsn) = &self.dsn {
+            Dsn::parse(dsn)?;
+        }
+        if self.enabled && self.dsn.is_none() {
+            return Err("errorReporting.dsn must be set when enabled".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 解析済みのDSN
+#[derive(Debug, Clone, PartialEq)]
+struct Dsn {
+    /// イベントの送信先（`{scheme}://{host}/api/{project}/store/`）
+    store_url: String,
+    public_key: String,
+}
+
+impl Dsn {
+    fn parse(dsn: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(dsn).map_err(|e| format!("Invalid DSN: {}", e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported DSN scheme: {}", url.scheme()));
+        }
+        if url.username().is_empty() {
+            return Err("Invalid DSN: public key is missing".to_string());
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| "Invalid DSN: host is missing".to_string())?;
+        let path = url.path().trim_matches('/');
+        let (prefix, project) = match path.rsplit_once('/') {
+            Some((prefix, project)) => (format!("/{}", prefix), project),
+            None => (String::new(), path),
+        };
+        if project.is_empty() {
+            return Err("Invalid DSN: project id is missing".to_string());
+        }
+
+        let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+        Ok(Self {
+            store_url: format!(
+                "{}://{}{}{}/api/{}/store/",
+                url.scheme(),
+                host,
+                port,
+                prefix,
+                project
+            ),
+            public_key: url.username().to_string(),
+        })
+    }
+
+    fn auth_header(&self, release: &str) -> String {
+        format!(
+            "Sentry sentry_version=7, sentry_key={}, sentry_client={}",
+            self.public_key, release
+        )
+    }
+}
+
+/// 送信先と送信に使う情報
+struct Reporter {
+    dsn: Dsn,
+    client: reqwest::Client,
+    release: String,
+    environment: Option<String>,
+}
+
+/// 現在の送信先（無効の場合は None）
+static REPORTER: RwLock<Option<Reporter>> = RwLock::new(None);
+
+/// 設定を反映（起動時・設定変更時に呼び出す）
+pub fn configure(settings: &ErrorReportingSettings, client: reqwest::Client, app_version: &str) {
+    let reporter = settings
+        .dsn
+        .as_deref()
+        .filter(|_| settings.enabled)
+        .and_then(|dsn| Dsn::parse(dsn).ok())
+        .map(|dsn| Reporter {
+            dsn,
+            client,
+            release: format!("autosight@{}", app_version),
+            environment: settings.environment.clone(),
+        });
+    if let Ok(mut current) = REPORTER.write() {
+        *current = reporter;
+    }
+}
+
+/// イベントID（32桁の16進数）を生成
+fn event_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{:016x}{:016x}",
+        nanos,
+        count ^ u64::from(std::process::id())
+    )
+}
+
+/// Sentryのイベントを組み立てる
+fn build_event(
+    reporter: &Reporter,
+    logger: &str,
+    message: &str,
+    extra: Map<String, Value>,
+) -> Value {
+    json!({
+        "event_id": event_id(),
+        "timestamp": Utc::now().to_rfc3339(),
+        "level": "error",
+        "platform": "other",
+        "logger": logger,
+        "release": reporter.release,
+        "environment": reporter.environment,
+        "message": { "formatted": message },
+        "tags": {
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+        },
+        "extra": extra,
+    })
+}
+
+/// イベントを送信
+async fn send(dsn: Dsn, client: reqwest::Client, release: String, event: Value) {
+    let request = client
+        .post(&dsn.store_url)
+        .header("X-Sentry-Auth", dsn.auth_header(&release))
+        .json(&event);
+    // 送信の失敗はERRORレベルで記録しない（送信の失敗を再度送信しないため）
+    match send_request(request).await {
+        Ok(response) if !response.status().is_success() => {
+            tracing::debug!(status = %response.status(), "error report rejected")
+        }
+        Err(e) => tracing::debug!(error = %e, "failed to send error report"),
+        _ => {}
+    }
+}
+
+/// 送信が有効な場合にイベントを組み立ててバックグラウンドで送信
+fn report(logger: &str, message: &str, extra: Map<String, Value>) {
+    let Ok(reporter) = REPORTER.read() else {
+        return;
+    };
+    let Some(reporter) = reporter.as_ref() else {
+        return;
+    };
+    let event = build_event(reporter, logger, message, extra);
+    let dsn = reporter.dsn.clone();
+    let client = reporter.client.clone();
+    let release = reporter.release.clone();
+    tauri::async_runtime::spawn(send(dsn, client, release, event));
+}
+
+/// クラッシュレポートを送信
+fn report_crash(report: &CrashReport) {
+    let mut extra = Map::new();
+    extra.insert("crashId".to_string(), Value::from(report.id.clone()));
+    extra.insert(
+        "appVersion".to_string(),
+        Value::from(report.app_version.clone()),
+    );
+    extra.insert("location".to_string(), json!(report.location));
+    extra.insert(
+        "backtrace".to_string(),
+        Value::from(report.backtrace.clone()),
+    );
+    extra.insert(
+        "recentOperations".to_string(),
+        json!(report.recent_operations),
+    );
+    self::report("panic", &report.message, extra);
+}
+
+/// 未送信のクラッシュレポートを送信し、送信済みとして記録する
+///
+/// エラー報告が無効な場合は何もしない（有効にした後の起動時に、それ以前のレポートも送信する）。
+pub fn report_pending_crashes(crash_dir: &Path) {
+    if !REPORTER.read().is_ok_and(|reporter| reporter.is_some()) {
+        return;
+    }
+    for mut crash in crash::list_reports(crash_dir) {
+        if crash.reported {
+            continue;
+        }
+        report_crash(&crash);
+        crash.reported = true;
+        if let Err(e) = crash::write_report(crash_dir, &crash) {
+            tracing::warn!(error = %e, "failed to mark crash report as reported");
+        }
+    }
+}
+
+/// ERRORレベルのログイベントを送信するレイヤー
+pub struct ErrorReportingLayer;
+
+impl<S: Subscriber> Layer<S> for ErrorReportingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        let message = visitor
+            .message
+            .unwrap_or_else(|| event.metadata().name().to_string());
+        report(event.metadata().target(), &message, visitor.fields);
+    }
+}
+
+/// イベントのフィールドをJSONに変換する
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.fields
+                .insert(field.name().to_string(), Value::from(value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dsn() {
+        let dsn = Dsn::parse("https://abc123@sentry.example.com/42").unwrap();
+        assert_eq!(dsn.store_url, "https://sentry.example.com/api/42/store/");
+        assert_eq!(dsn.public_key, "abc123");
+
+        // パス付き・ポート指定のセルフホスト
+        let dsn = Dsn::parse("http://key@monitor.local:9000/sentry/7").unwrap();
+        assert_eq!(
+            dsn.store_url,
+            "http://monitor.local:9000/sentry/api/7/store/"
+        );
+
+        assert!(Dsn::parse("https://sentry.example.com/42").is_err());
+        assert!(Dsn::parse("https://key@sentry.example.com/").is_err());
+        assert!(Dsn::parse("ftp://key@sentry.example.com/1").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ErrorReportingSettings::default().validate().is_ok());
+        let settings = ErrorReportingSettings {
+            enabled: true,
+            dsn: None,
+            environment: None,
+        };
+        assert!(settings.validate().is_err());
+    }
+}
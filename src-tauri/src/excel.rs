@@ -0,0 +1,812 @@
+//! Excel器具リストの読み込み
+//!
+//! IES照明器具リストExcel（`schema/ies-fixture-list.schema.json`）の Fixture Base シートを読み込み、
+//! 型付きの行データと検証警告（未対応メーカー・型番未入力・Spec No.重複等）に変換する。
+//! Fixture Base シートをCSVで書き出したファイルも同じ列構成で読み込める。
+//! Excelからコピーした行（タブ区切り）も、ファイルを保存せずにダウンロードアイテムに変換できる。
+//!
+//! 列名は全角・半角、大文字・小文字、空白と `.` の有無を区別せずに照合する（`SPEC NO` → `Spec No.`）。
+//! 設定のヘッダー行に必須列がない場合は、先頭の行からヘッダー行を探す（表題行がある場合等）。
+
+use crate::commands::BatchDownloadItem;
+use crate::i18n::{Locale, Message};
+use calamine::{open_workbook_auto, Data, DataType, Range, Reader};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+
+/// 読み込み設定（シート名・ヘッダー行・列名の対応）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImportProfile {
+    /// シート名（省略時は "Fixture Base" または名前に "fixture" を含むシート）
+    pub sheet_name: Option<String>,
+    /// ヘッダー行（1始まり）
+    pub header_row: u32,
+    /// データ開始行（1始まり。ヘッダー行との間の行は注釈行としてスキップ）
+    pub data_start_row: u32,
+    /// Spec No. の列名
+    pub spec_no_column: String,
+    /// メーカーの列名
+    pub manufacturer_column: String,
+    /// 型番の列名
+    pub fixture_column: String,
+    /// PSUの列名
+    pub psu_column: String,
+}
+
+impl Default for ImportProfile {
+    fn default() -> Self {
+        Self {
+            sheet_name: None,
+            header_row: 1,
+            data_start_row: 3,
+            spec_no_column: "Spec No.".to_string(),
+            manufacturer_column: "メーカー".to_string(),
+            fixture_column: "FIXTURE".to_string(),
+            psu_column: "PSU".to_string(),
+        }
+    }
+}
+
+/// 読み込んだ1行分の器具データ（フロントエンドの `Fixture` に対応）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedRow {
+    /// Excel上の行番号（1始まり。フロントエンドから受け取る場合は省略可）
+    #[serde(default)]
+    pub row_number: u32,
+    pub spec_no: String,
+    /// 更新日（YYYY-MM-DD）
+    pub date: Option<String>,
+    pub revision: Option<String>,
+    pub omitted: Option<f64>,
+    /// 器具タイプ名称（未入力の場合は "不明"）
+    pub luminaire_type: String,
+    pub light_source_type: Option<String>,
+    pub color_temp: Option<String>,
+    pub beam_angle: Option<String>,
+    pub lumen: Option<f64>,
+    pub wattage: Option<f64>,
+    pub va: Option<f64>,
+    pub unit: Option<String>,
+    pub manufacturer: String,
+    /// 型番
+    pub fixture: String,
+    pub model_note: Option<String>,
+    pub control: Option<String>,
+    pub psu: Option<String>,
+    pub accessories: Option<String>,
+    pub notes: Option<String>,
+    pub product_link: Option<String>,
+    pub ies_file_check: Option<String>,
+    pub cost_fixture: Option<f64>,
+    pub cost_driver: Option<f64>,
+    pub cost_others: Option<f64>,
+    /// 台数（「台数」列。定価 × 台数・接続負荷の集計に使用）
+    #[serde(default)]
+    pub quantity: Option<u32>,
+    /// プロジェクトの無視リストに含まれる行（取得を試みない）
+    #[serde(default)]
+    pub ignored: bool,
+}
+
+/// 検証警告の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportWarningKind {
+    /// 必須列が見つからない
+    MissingColumn,
+    /// Spec No. が未入力（行は読み込まない）
+    EmptySpecNo,
+    /// メーカーが未入力（行は読み込まない）
+    EmptyManufacturer,
+    /// 型番が未入力（行は読み込まない）
+    EmptyModel,
+    /// 対応プロバイダーのないメーカー（行は読み込む）
+    UnknownManufacturer,
+    /// Spec No. が重複（2件目以降も読み込む）
+    DuplicateSpecNo,
+}
+
+/// 検証警告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWarning {
+    pub kind: ImportWarningKind,
+    /// Excel上の行番号（列に関する警告では None）
+    pub row_number: Option<u32>,
+    pub spec_no: Option<String>,
+    pub message: String,
+}
+
+/// 読み込み結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    /// 読み込んだシート名
+    pub sheet_name: String,
+    /// 全シート名
+    pub sheet_names: Vec<String>,
+    pub rows: Vec<ImportedRow>,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// 読み込んだシート
+pub(crate) struct Sheet {
+    /// シート名（CSVの場合はファイル名（拡張子なし））
+    pub name: String,
+    /// 全シート名
+    pub names: Vec<String>,
+    pub range: Range<Data>,
+}
+
+/// Excelファイル（.csv の場合はCSV）から器具リストのシートを読み込む
+pub(crate) fn read_sheet(
+    path: &str,
+    profile: &ImportProfile,
+    locale: Locale,
+) -> Result<Sheet, String> {
+    let is_csv = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    if is_csv {
+        return read_csv_sheet(path, locale);
+    }
+
+    let mut workbook =
+        open_workbook_auto(path).map_err(|e| format!("Failed to open Excel file: {}", e))?;
+
+    let names = workbook.sheet_names();
+    let name = match &profile.sheet_name {
+        Some(name) => names.iter().find(|n| *n == name).cloned(),
+        None => names
+            .iter()
+            .find(|n| *n == "Fixture Base" || n.to_lowercase().contains("fixture"))
+            .cloned(),
+    }
+    .ok_or_else(|| Message::SheetNotFound.text(locale))?;
+
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|e| format!("Failed to read sheet {}: {}", name, e))?;
+    Ok(Sheet { name, names, range })
+}
+
+/// CSVファイルを1シートとして読み込む（UTF-8（BOM付き可）のみ対応）
+fn read_csv_sheet(path: &str, locale: Locale) -> Result<Sheet, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to open CSV file: {}", e))?;
+    let text = String::from_utf8(bytes).map_err(|_| Message::CsvNotUtf8.text(locale))?;
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(Sheet {
+        names: vec![name.clone()],
+        name,
+        range: csv_range(parse_csv(text.trim_start_matches('\u{feff}'))),
+    })
+}
+
+/// Excelファイルを読み込む
+///
+/// 拡張子が .csv のファイルはCSVとして読み込む（UTF-8のみ。シート名はファイル名）。
+///
+/// # Arguments
+/// * `path` - .xlsx / .xls / .csv ファイルのパス
+/// * `profile` - 読み込み設定
+/// * `locale` - 検証警告の表示言語
+/// * `is_supported` - メーカー名に対応するプロバイダーがあるか判定する関数
+pub fn import(
+    path: &str,
+    profile: &ImportProfile,
+    locale: Locale,
+    is_supported: impl Fn(&str) -> bool,
+) -> Result<ImportResult, String> {
+    let sheet = read_sheet(path, profile, locale)?;
+    let (rows, warnings) = parse_sheet(&sheet.range, profile, locale, is_supported);
+
+    Ok(ImportResult {
+        sheet_name: sheet.name,
+        sheet_names: sheet.names,
+        rows,
+        warnings,
+    })
+}
+
+/// CSVを行・フィールドに分割（ダブルクォートで囲んだフィールド内のカンマ・改行に対応）
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    parse_delimited(text, ',')
+}
+
+/// 区切り文字で行・フィールドに分割（ダブルクォートで囲んだフィールド内の区切り文字・改行に対応）
+fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter && !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// CSVの行をシートの範囲（A1から始まる）に変換
+fn csv_range(rows: Vec<Vec<String>>) -> Range<Data> {
+    let height = rows.len() as u32;
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    if height == 0 || width == 0 {
+        return Range::empty();
+    }
+    let mut range = Range::new((0, 0), (height - 1, width - 1));
+    for (r, row) in rows.into_iter().enumerate() {
+        for (c, value) in row.into_iter().enumerate() {
+            if !value.is_empty() {
+                range.set_value((r as u32, c as u32), Data::String(value));
+            }
+        }
+    }
+    range
+}
+
+/// クリップボードの貼り付け結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardImportResult {
+    pub items: Vec<BatchDownloadItem>,
+    /// 検証警告（行番号は貼り付けたテキストの行番号）
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Excelからコピーした行（タブ区切り）をダウンロードアイテムに変換
+///
+/// 1行目に読み込み設定の列名（メーカー・型番）が含まれる場合はヘッダー行として列を対応付ける。
+/// ヘッダー行がない場合は「メーカー・型番・Spec No.・PSU」の順の列とみなす。
+/// ヘッダー行に「台数」列がある場合は台数も読み込む。
+/// Spec No. がない行は行番号を Spec No. とする。
+pub fn parse_clipboard(
+    text: &str,
+    profile: &ImportProfile,
+    locale: Locale,
+    is_supported: impl Fn(&str) -> bool,
+) -> ClipboardImportResult {
+    let rows = parse_delimited(text.trim_start_matches('\u{feff}'), '\t');
+    let position = |name: &str| {
+        let name = header_key(name);
+        rows.first()
+            .and_then(|header| header.iter().position(|cell| header_key(cell) == name))
+    };
+    let (manufacturer_col, fixture_col, spec_no_col, psu_col, skip) = match (
+        position(&profile.manufacturer_column),
+        position(&profile.fixture_column),
+    ) {
+        (Some(manufacturer), Some(fixture)) => (
+            manufacturer,
+            fixture,
+            position(&profile.spec_no_column),
+            position(&profile.psu_column),
+            1,
+        ),
+        _ => (0, 1, Some(2), Some(3), 0),
+    };
+    // 台数はヘッダー行に「台数」列がある場合のみ読み込む
+    let quantity_col = if skip == 1 {
+        position(QUANTITY_COLUMN)
+    } else {
+        None
+    };
+
+    let mut items = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen_spec_nos = HashSet::new();
+    for (index, row) in rows.iter().enumerate().skip(skip) {
+        let row_number = index as u32 + 1;
+        let cell = |col: Option<usize>| {
+            col.and_then(|col| row.get(col))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let manufacturer = cell(Some(manufacturer_col));
+        let model_number = cell(Some(fixture_col));
+        if manufacturer.is_none() && model_number.is_none() {
+            continue;
+        }
+        let spec_no = cell(spec_no_col).unwrap_or_else(|| row_number.to_string());
+
+        let Some(manufacturer) = manufacturer else {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::EmptyManufacturer,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::EmptyManufacturer { spec_no: &spec_no }.text(locale),
+            });
+            continue;
+        };
+        let Some(model_number) = model_number else {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::EmptyModel,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::EmptyModel { spec_no: &spec_no }.text(locale),
+            });
+            continue;
+        };
+
+        if !is_supported(&manufacturer) {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::UnknownManufacturer,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::UnknownManufacturer {
+                    spec_no: &spec_no,
+                    manufacturer: &manufacturer,
+                }
+                .text(locale),
+            });
+        }
+        if !seen_spec_nos.insert(spec_no.clone()) {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::DuplicateSpecNo,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::DuplicateSpecNo { spec_no: &spec_no }.text(locale),
+            });
+        }
+
+        items.push(BatchDownloadItem {
+            spec_no,
+            manufacturer,
+            model_number,
+            psu: cell(psu_col),
+            asset_types: None,
+            quantity: cell(quantity_col)
+                .and_then(|value| value.parse().ok())
+                .and_then(parse_quantity),
+            area: None,
+            wattage: None,
+            provider: None,
+            source_url: None,
+        });
+    }
+
+    ClipboardImportResult { items, warnings }
+}
+
+/// 範囲外のセル
+static EMPTY_CELL: Data = Data::Empty;
+
+/// Excel上の行番号（1始まり）・列番号（0始まり）でセルを取得
+///
+/// Range は使用範囲の左上から始まるため、位置を補正する。
+pub(crate) fn cell_at(range: &Range<Data>, row: u32, col: u32) -> &Data {
+    let (first_row, first_col) = range.start().unwrap_or((0, 0));
+    row.checked_sub(first_row + 1)
+        .zip(col.checked_sub(first_col))
+        .and_then(|(r, c)| range.get((r as usize, c as usize)))
+        .unwrap_or(&EMPTY_CELL)
+}
+
+/// セルの値を文字列に変換（空セルは None）
+pub(crate) fn cell_string(cell: &Data) -> Option<String> {
+    let value = match cell {
+        Data::Empty | Data::Error(_) => return None,
+        Data::Float(f) if f.fract() == 0.0 => format!("{}", *f as i64),
+        other => other.to_string(),
+    };
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// セルの値を数値に変換
+fn cell_number(cell: &Data) -> Option<f64> {
+    match cell {
+        Data::Float(f) => Some(*f),
+        Data::Int(i) => Some(*i as f64),
+        other => cell_string(other)?.parse().ok(),
+    }
+}
+
+/// 台数の列名
+const QUANTITY_COLUMN: &str = "台数";
+
+/// ヘッダー行を探す範囲（先頭からの行数）
+const HEADER_SCAN_ROWS: u32 = 20;
+
+/// 列名の照合用の形式（全角・半角、大文字・小文字、空白と `.` の違いを無視する）
+pub(crate) fn header_key(name: &str) -> String {
+    name.nfkc()
+        .filter(|c| !c.is_whitespace() && *c != '.')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// ヘッダー行の列名（照合用の形式）→ 列番号の対応
+pub(crate) fn header_columns(range: &Range<Data>, header_row: u32) -> HashMap<String, u32> {
+    let mut columns = HashMap::new();
+    let (_, first_col) = range.start().unwrap_or((0, 0));
+    let (_, last_col) = range.end().unwrap_or((0, 0));
+    for col in first_col..=last_col {
+        if let Some(header) = cell_string(cell_at(range, header_row, col)) {
+            columns.entry(header_key(&header)).or_insert(col);
+        }
+    }
+    columns
+}
+
+/// 必須列（Spec No.・メーカー・型番）の列名
+fn required_columns(profile: &ImportProfile) -> [&String; 3] {
+    [
+        &profile.spec_no_column,
+        &profile.manufacturer_column,
+        &profile.fixture_column,
+    ]
+}
+
+/// ヘッダー行を決める（設定の行に必須列がそろっていなければ、そろっている最初の行）
+pub(crate) fn detect_header_row(range: &Range<Data>, profile: &ImportProfile) -> u32 {
+    let has_required = |row: u32| {
+        let columns = header_columns(range, row);
+        required_columns(profile)
+            .iter()
+            .all(|name| columns.contains_key(&header_key(name)))
+    };
+    if has_required(profile.header_row) {
+        return profile.header_row;
+    }
+    let (first_row, _) = range.start().unwrap_or((0, 0));
+    let (last_row, _) = range.end().unwrap_or((0, 0));
+    (first_row + 1..=(last_row + 1).min(first_row + HEADER_SCAN_ROWS))
+        .find(|&row| has_required(row))
+        .unwrap_or(profile.header_row)
+}
+
+/// 台数（1以上の整数。小数は四捨五入し、0以下・範囲外は None）
+fn parse_quantity(value: f64) -> Option<u32> {
+    let rounded = value.round();
+    (1.0..=f64::from(u32::MAX))
+        .contains(&rounded)
+        .then_some(rounded as u32)
+}
+
+/// シートの内容を行データと検証警告に変換
+fn parse_sheet(
+    range: &Range<Data>,
+    profile: &ImportProfile,
+    locale: Locale,
+    is_supported: impl Fn(&str) -> bool,
+) -> (Vec<ImportedRow>, Vec<ImportWarning>) {
+    let mut rows = Vec::new();
+    let mut warnings = Vec::new();
+
+    // ヘッダー行から列名 → 列番号の対応を作成
+    // （ヘッダー行が設定と異なる場合、データ開始行もヘッダー行からの位置を保ってずらす）
+    let header_row = detect_header_row(range, profile);
+    let data_start_row = (profile.data_start_row + header_row).saturating_sub(profile.header_row);
+    if header_row != profile.header_row {
+        tracing::debug!(header_row, data_start_row, "detected header row");
+    }
+    let columns = header_columns(range, header_row);
+    let (last_row, _) = range.end().unwrap_or((0, 0));
+
+    for required in required_columns(profile) {
+        if !columns.contains_key(&header_key(required)) {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::MissingColumn,
+                row_number: None,
+                spec_no: None,
+                message: Message::MissingColumn { column: required }.text(locale),
+            });
+        }
+    }
+
+    let mut seen_spec_nos = HashSet::new();
+
+    for row_number in data_start_row..=last_row + 1 {
+        let text = |name: &str| {
+            columns
+                .get(&header_key(name))
+                .and_then(|&col| cell_string(cell_at(range, row_number, col)))
+        };
+        let number = |name: &str| {
+            columns
+                .get(&header_key(name))
+                .and_then(|&col| cell_number(cell_at(range, row_number, col)))
+        };
+
+        let spec_no = text(&profile.spec_no_column);
+        let manufacturer = text(&profile.manufacturer_column);
+        let fixture = text(&profile.fixture_column);
+
+        // 空行はスキップ
+        if spec_no.is_none() && manufacturer.is_none() && fixture.is_none() {
+            continue;
+        }
+
+        let Some(spec_no) = spec_no else {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::EmptySpecNo,
+                row_number: Some(row_number),
+                spec_no: None,
+                message: Message::EmptySpecNo { row_number }.text(locale),
+            });
+            continue;
+        };
+        let Some(manufacturer) = manufacturer else {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::EmptyManufacturer,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::EmptyManufacturer { spec_no: &spec_no }.text(locale),
+            });
+            continue;
+        };
+        let Some(fixture) = fixture else {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::EmptyModel,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::EmptyModel { spec_no: &spec_no }.text(locale),
+            });
+            continue;
+        };
+
+        if !is_supported(&manufacturer) {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::UnknownManufacturer,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::UnknownManufacturer {
+                    spec_no: &spec_no,
+                    manufacturer: &manufacturer,
+                }
+                .text(locale),
+            });
+        }
+        if !seen_spec_nos.insert(spec_no.clone()) {
+            warnings.push(ImportWarning {
+                kind: ImportWarningKind::DuplicateSpecNo,
+                row_number: Some(row_number),
+                spec_no: Some(spec_no.clone()),
+                message: Message::DuplicateSpecNo { spec_no: &spec_no }.text(locale),
+            });
+        }
+
+        let date = columns.get(&header_key("Date")).and_then(|&col| {
+            let value = cell_at(range, row_number, col);
+            match value.as_date() {
+                Some(date) => Some(date.to_string()),
+                None => cell_string(value),
+            }
+        });
+
+        rows.push(ImportedRow {
+            row_number,
+            spec_no,
+            date,
+            revision: text("Rev."),
+            omitted: number("omitted"),
+            luminaire_type: text("Luminaire_Type").unwrap_or_else(|| "不明".to_string()),
+            light_source_type: text("Light source type"),
+            color_temp: text("色温度"),
+            beam_angle: text("配光角"),
+            lumen: number("Lumen"),
+            wattage: number("消費電力"),
+            va: number("VA"),
+            unit: text("Unit"),
+            manufacturer,
+            fixture,
+            model_note: text("型番備考"),
+            control: text("制御"),
+            psu: text(&profile.psu_column),
+            accessories: text("ACCESSORIES"),
+            notes: text("注記"),
+            product_link: text("製品リンク"),
+            ies_file_check: text("IES File Check"),
+            cost_fixture: number("Cost of Fixture"),
+            cost_driver: number("Cost of Driver"),
+            cost_others: number("Cost of Others"),
+            quantity: number(QUANTITY_COLUMN).and_then(parse_quantity),
+            ignored: false,
+        });
+    }
+
+    (rows, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(value: &str) -> Data {
+        Data::String(value.to_string())
+    }
+
+    fn sheet(rows: Vec<Vec<Data>>) -> Range<Data> {
+        let height = rows.len() as u32;
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        let mut range = Range::new((0, 0), (height - 1, width - 1));
+        for (r, row) in rows.into_iter().enumerate() {
+            for (c, value) in row.into_iter().enumerate() {
+                range.set_value((r as u32, c as u32), value);
+            }
+        }
+        range
+    }
+
+    fn header() -> Vec<Data> {
+        vec![
+            s("Spec No."),
+            s("メーカー"),
+            s("FIXTURE"),
+            s("PSU"),
+            s("消費電力"),
+        ]
+    }
+
+    #[test]
+    fn test_parse_sheet() {
+        let range = sheet(vec![
+            header(),
+            vec![s("*Insert new Rows above this line")],
+            vec![
+                s("A01"),
+                s("コイズミ照明"),
+                s("AD12345"),
+                s("XE92701"),
+                Data::Float(8.5),
+            ],
+            vec![Data::Empty, Data::Empty, Data::Empty],
+            vec![
+                s("A02"),
+                s("TOKISTAR"),
+                s("SDL-1"),
+                Data::Empty,
+                Data::Int(12),
+            ],
+        ]);
+        let (rows, warnings) = parse_sheet(&range, &ImportProfile::default(), Locale::Ja, |_| true);
+
+        assert!(warnings.is_empty());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row_number, 3);
+        assert_eq!(rows[0].spec_no, "A01");
+        assert_eq!(rows[0].psu.as_deref(), Some("XE92701"));
+        assert_eq!(rows[0].wattage, Some(8.5));
+        assert_eq!(rows[0].luminaire_type, "不明");
+        assert_eq!(rows[1].row_number, 5);
+        assert_eq!(rows[1].wattage, Some(12.0));
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("Spec No.,メーカー,FIXTURE\r\nA01,\"Koizumi, Inc.\",\"AD\"\"1\"\r\n\r\nA02,TOKISTAR,SDL-1");
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], vec!["A01", "Koizumi, Inc.", "AD\"1"]);
+        assert_eq!(rows[2], vec![""]);
+
+        let range = csv_range(rows);
+        let profile = ImportProfile {
+            data_start_row: 2,
+            ..ImportProfile::default()
+        };
+        let (rows, warnings) = parse_sheet(&range, &profile, Locale::Ja, |_| true);
+        assert!(warnings.is_empty());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].row_number, 4);
+        assert_eq!(rows[1].fixture, "SDL-1");
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        // ヘッダー行なし: メーカー・型番・Spec No.・PSU の順
+        let text = "コイズミ照明\tAD12345\tA01\tXE92701\r\nTOKISTAR\tSDL-1\r\n\r\nDAIKO\t\tA03\r\n";
+        let result = parse_clipboard(text, &ImportProfile::default(), Locale::En, |m| {
+            m != "DAIKO"
+        });
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].spec_no, "A01");
+        assert_eq!(result.items[0].psu.as_deref(), Some("XE92701"));
+        assert_eq!(result.items[1].spec_no, "2");
+        assert_eq!(result.items[1].model_number, "SDL-1");
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].kind, ImportWarningKind::EmptyModel);
+        assert_eq!(result.warnings[0].row_number, Some(4));
+
+        // ヘッダー行あり: 列の順序は問わない（セル内の改行はダブルクォートで囲まれる）
+        let text = "FIXTURE\tSpec No.\tメーカー\t台数\n\"AD12345\nW\"\tA01\tコイズミ照明\t4\n";
+        let result = parse_clipboard(text, &ImportProfile::default(), Locale::En, |_| true);
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].manufacturer, "コイズミ照明");
+        assert_eq!(result.items[0].model_number, "AD12345\nW");
+        assert_eq!(result.items[0].psu, None);
+        assert_eq!(result.items[0].quantity, Some(4));
+        assert_eq!(parse_quantity(2.4), Some(2));
+        assert_eq!(parse_quantity(0.0), None);
+    }
+
+    #[test]
+    fn test_parse_sheet_warnings() {
+        let range = sheet(vec![
+            header(),
+            vec![],
+            vec![s("A01"), s("コイズミ照明"), s("AD12345")],
+            vec![s("A01"), s("コイズミ照明"), s("AD67890")],
+            vec![s("A02"), s("コイズミ照明"), Data::Empty],
+            vec![s("A03"), s("Unknown Maker"), s("X-1")],
+        ]);
+        let (rows, warnings) = parse_sheet(&range, &ImportProfile::default(), Locale::Ja, |m| {
+            m == "コイズミ照明"
+        });
+
+        let kinds: Vec<_> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ImportWarningKind::DuplicateSpecNo,
+                ImportWarningKind::EmptyModel,
+                ImportWarningKind::UnknownManufacturer,
+            ]
+        );
+        assert_eq!(warnings[1].row_number, Some(5));
+        // 未対応メーカー・重複の行は読み込む
+        assert_eq!(
+            rows.iter().map(|r| r.spec_no.as_str()).collect::<Vec<_>>(),
+            vec!["A01", "A01", "A03"]
+        );
+    }
+
+    #[test]
+    fn test_parse_sheet_detect_header() {
+        // 表題行の下にヘッダー行があり、列名の表記が異なる
+        let range = sheet(vec![
+            vec![s("照明器具リスト")],
+            vec![],
+            vec![s("SPEC NO"), s("メーカー"), s("ＦＩＸＴＵＲＥ"), s(" psu ")],
+            vec![s("*Insert new Rows above this line")],
+            vec![s("A01"), s("コイズミ照明"), s("AD12345"), s("XE92701")],
+        ]);
+        let (rows, warnings) = parse_sheet(&range, &ImportProfile::default(), Locale::Ja, |_| true);
+
+        assert!(warnings.is_empty());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].row_number, 5);
+        assert_eq!(rows[0].fixture, "AD12345");
+        assert_eq!(rows[0].psu.as_deref(), Some("XE92701"));
+        assert_eq!(header_key("Spec No."), header_key("ｓｐｅｃ　ｎｏ"));
+    }
+
+    #[test]
+    fn test_parse_sheet_missing_column() {
+        let range = sheet(vec![vec![s("Spec No."), s("メーカー")], vec![]]);
+        let (_, warnings) = parse_sheet(&range, &ImportProfile::default(), Locale::Ja, |_| true);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, ImportWarningKind::MissingColumn);
+    }
+}
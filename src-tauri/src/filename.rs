@@ -0,0 +1,631 @@
+//! ファイル名テンプレート
+//!
+//! ダウンロードしたファイルの保存名を `{spec_no}_{original}` のようなテンプレートから生成する。
+//! 事務所ごとに異なる命名規則に合わせられるよう、設定でテンプレートを指定できる。
+//! 指定がない場合は各プロバイダーの既定テンプレートを使用する。
+//!
+//! プレースホルダー:
+//! - `{spec_no}`: Spec No.
+//! - `{model}`: 型番
+//! - `{psu}`: PSU型番
+//! - `{item}`: 型番とPSU型番を `+` で連結したもの（PSUがない場合は型番）
+//! - `{original}`: サーバーから取得した元ファイル名（拡張子なし）
+//! - `{manufacturer}`: メーカー名
+//! - `{cct}`: 色温度（型番・元ファイル名中の `30K` 等から。例: `3000K`）
+//! - `{ies_cct}`: IESファイルのヘッダーに記載された色温度（例: `3000K`）
+//! - `{beam}`: IESファイルの配光データから求めたビーム角（例: `30deg`）
+//! - `{lumens}`: IESファイルの配光データから求めた器具光束（例: `850lm`）
+//!
+//! `{ies_cct}` `{beam}` `{lumens}` はIESファイルの内容から取得するため、IESファイルにのみ使用でき、
+//! ダウンロード前の見積もりでは値がない。型番の表記ではなく実際の配光データで命名したい場合は
+//! `CONTENT_TEMPLATE`（例: `1001_XD93319_3000K_30deg.ies`）で再リネームする。
+//!
+//! `{original|model}` のように `|` で区切ると、値がある最初のものを使用する。
+//! 値のないプレースホルダーの直後の区切り文字（`_` `-` `+` 空白）は省略する。
+//! 拡張子はテンプレートに含めず、IESファイルは `.ies`、その他は元ファイル名の拡張子を付ける。
+//!
+//! テンプレートは一括ダウンロードごとにも指定できる（設定のテンプレートより優先する）。
+//! 一括ダウンロード中に別のアイテムと同じファイル名になった場合は、上書きせずに拡張子の前に
+//! 連番（`_2` `_3` …）を付ける（[`ReservedPaths`]）。
+//!
+//! 生成したファイル名はUnicode正規化（NFC）する。メーカーのサーバーから取得した元ファイル名は
+//! 濁点が分解された形（NFD）の場合があり、そのままではmacOS・Windows・Excelへの書き戻しで
+//! 並び順や照合が一致しないため。設定により全角英数字を半角に変換することもできる。
+//!
+//! 保存先ディレクトリにもプレースホルダー（[`DIR_PLACEHOLDERS`]）を使用でき、
+//! `.../{project}/{date}/IES/{manufacturer}` のようなフォルダ構成で保存できる。
+//! バッチの開始時に `{project}` `{project_id}` `{date}` `{sheet}` を、
+//! アイテムごとに `{manufacturer}` を展開する（[`render_dir`]）。
+
+use crate::providers::AssetType;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+use unicode_normalization::UnicodeNormalization;
+
+/// 既定のテンプレート（元ファイル名がない場合は型番を使用）
+pub const DEFAULT_TEMPLATE: &str = "{spec_no}_{original|model}";
+
+/// IESファイルの内容から命名するテンプレート（ヘッダーに色温度がない場合は型番から）
+pub const CONTENT_TEMPLATE: &str = "{spec_no}_{model}_{ies_cct|cct}_{beam}";
+
+/// 使用できるプレースホルダー
+const PLACEHOLDERS: [&str; 10] = [
+    "spec_no",
+    "model",
+    "psu",
+    "item",
+    "original",
+    "manufacturer",
+    "cct",
+    "ies_cct",
+    "beam",
+    "lumens",
+];
+
+/// 保存先ディレクトリに使用できるプレースホルダー
+pub const DIR_PLACEHOLDERS: [&str; 5] = ["project", "project_id", "date", "sheet", "manufacturer"];
+
+/// 保存先ディレクトリ中のプレースホルダー（英小文字と `_` のみ。それ以外の `{}` はフォルダ名とみなす）
+static DIR_PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").unwrap());
+
+/// 値のないプレースホルダーの前後で省略する区切り文字
+const SEPARATORS: [char; 4] = ['_', '-', '+', ' '];
+
+/// ファイル名に使用できない文字（Windowsの禁止文字を含む）
+const INVALID_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Windowsの予約デバイス名（拡張子の有無・大文字小文字に関わらず使用できない）
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// ファイル名の長さの上限（UTF-8のバイト数）
+/// 多くのファイルシステムの上限（255バイト）に、同名回避の連番等を付ける余裕を残す。
+const MAX_FILENAME_BYTES: usize = 240;
+
+/// 型番・ファイル名中の色温度（"OSP01-30K-30D" の "30K"、"3000K"）
+static CCT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:^|[-_ ])(\d{4}|\d{2})K(?:$|[-_ .])").unwrap());
+
+/// ファイル名の生成に使用する値
+pub struct FilenameContext<'a> {
+    pub spec_no: &'a str,
+    pub manufacturer: &'a str,
+    pub model_number: &'a str,
+    pub psu: Option<&'a str>,
+    /// サーバーから取得した元ファイル名（ダウンロード前の見積もりでは None）
+    pub original_filename: Option<&'a str>,
+    /// IESファイルの内容から取得した値（IESファイル以外・ダウンロード前は None）
+    pub photometry: Option<PhotometricValues>,
+}
+
+/// IESファイルの内容から取得した、ファイル名に使用する値
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhotometricValues {
+    /// ヘッダーに記載された色温度（K）
+    pub cct: Option<u32>,
+    /// ビーム角（度）
+    pub beam_angle: Option<f64>,
+    /// 器具光束（lm）
+    pub lumens: Option<f64>,
+}
+
+/// ファイル名の生成設定
+#[derive(Debug, Clone, Default)]
+pub struct FilenameOptions {
+    /// ファイル名テンプレート（未指定時はプロバイダーの既定テンプレート）
+    pub template: Option<String>,
+    /// 全角英数字を半角に変換する
+    pub halfwidth_alphanumerics: bool,
+    /// 一括ダウンロード中に保存したファイルのパス（指定した場合、同じ名前には連番を付ける）
+    pub reserved: Option<Arc<ReservedPaths>>,
+}
+
+/// 一括ダウンロード中に保存したファイルのパス（同じバッチの別のアイテムによる上書きを防ぐ）
+#[derive(Debug, Default)]
+pub struct ReservedPaths(Mutex<HashSet<String>>);
+
+impl ReservedPaths {
+    /// `dir` に保存するファイル名を予約
+    ///
+    /// 同じバッチで予約済みの場合は、拡張子の前に連番を付けた名前（`_2` `_3` …）を予約して返す。
+    /// 大文字・小文字の違いは同じ名前とみなす（Windows・macOSの既定のファイルシステムにあわせる）。
+    pub fn reserve(&self, dir: &str, filename: &str) -> String {
+        let (stem, extension) = match filename.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+            _ => (filename, None),
+        };
+        let mut reserved = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let mut name = filename.to_string();
+        let mut number = 1;
+        while !reserved.insert(format!("{}/{}", dir, name).to_lowercase()) {
+            number += 1;
+            name = match extension {
+                Some(extension) => format!("{}_{}.{}", stem, number, extension),
+                None => format!("{}_{}", stem, number),
+            };
+        }
+        name
+    }
+}
+
+/// テンプレートの構成要素
+enum Segment<'a> {
+    Literal(&'a str),
+    /// `|` で区切った候補
+    Placeholder(Vec<&'a str>),
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(&rest[..start]));
+        }
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .ok_or_else(|| format!("Unclosed placeholder in filename template: {}", template))?;
+        let names: Vec<&str> = rest[start + 1..end].split('|').map(str::trim).collect();
+        if let Some(name) = names.iter().find(|name| !PLACEHOLDERS.contains(name)) {
+            return Err(format!(
+                "Unknown placeholder in filename template: {{{}}}",
+                name
+            ));
+        }
+        segments.push(Segment::Placeholder(names));
+        rest = &rest[end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(format!(
+            "Unopened placeholder in filename template: {}",
+            template
+        ));
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    Ok(segments)
+}
+
+/// テンプレートの形式を検証
+pub fn validate(template: &str) -> Result<(), String> {
+    let segments = parse(template)?;
+    if !segments
+        .iter()
+        .any(|segment| matches!(segment, Segment::Placeholder(_)))
+    {
+        return Err("filenameTemplate must contain at least one placeholder".to_string());
+    }
+    Ok(())
+}
+
+/// テンプレートからファイル名（拡張子付き）を生成
+///
+/// 不正なテンプレートの場合は既定のテンプレートを使用する（設定の保存時に検証済みのため通常は起きない）。
+pub fn render(template: &str, asset_type: AssetType, context: &FilenameContext) -> String {
+    let (stem, extension) = split_original(asset_type, context.original_filename);
+    let segments = parse(template).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "invalid filename template, using default");
+        parse(DEFAULT_TEMPLATE).expect("Invalid default filename template")
+    });
+
+    let mut name = String::new();
+    let mut skip_separator = false;
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => {
+                let text = if skip_separator {
+                    text.strip_prefix(&SEPARATORS[..]).unwrap_or(text)
+                } else {
+                    text
+                };
+                name.push_str(text);
+                skip_separator = false;
+            }
+            Segment::Placeholder(names) => {
+                let value = names
+                    .iter()
+                    .map(|placeholder| placeholder_value(placeholder, context, stem.as_deref()))
+                    .find(|value| !value.is_empty())
+                    .unwrap_or_default();
+                if value.is_empty() {
+                    skip_separator = name.is_empty() || name.ends_with(&SEPARATORS[..]);
+                } else {
+                    name.push_str(&sanitize(&value));
+                    skip_separator = false;
+                }
+            }
+        }
+    }
+
+    let name = match name.trim_matches(&SEPARATORS[..]) {
+        "" => sanitize(context.spec_no),
+        name => name.to_string(),
+    };
+    match extension {
+        Some(extension) => sanitize_filename(&format!("{}.{}", name, extension)),
+        None => sanitize_filename(&name),
+    }
+}
+
+/// どのOSでも保存できるファイル名に変換
+///
+/// ファイルを書き込む前に、保存するファイル名・フォルダ名に必ず適用する。
+/// - Unicode正規化（NFC）
+/// - 使用できない文字（`/ \ : * ? " < > |`・制御文字）を '_' に置換
+/// - 末尾のピリオド・空白を除去（Windowsでは自動的に削除され、別名になるため）
+/// - Windowsの予約デバイス名（CON・PRN・COM1等）の先頭に '_' を付加
+/// - 長すぎる名前は拡張子を残して切り詰める
+pub fn sanitize_filename(name: &str) -> String {
+    let name = sanitize(&name.nfc().collect::<String>());
+    let mut name = name
+        .trim_start_matches(' ')
+        .trim_end_matches(['.', ' '])
+        .to_string();
+    if name.is_empty() {
+        return "_".to_string();
+    }
+
+    let device = name.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| device.eq_ignore_ascii_case(reserved))
+    {
+        name.insert(0, '_');
+    }
+
+    if name.len() > MAX_FILENAME_BYTES {
+        // 拡張子（短いもののみ）を残して名前の部分を切り詰める
+        let extension = match name.rfind('.') {
+            Some(i) if i > 0 && name.len() - i <= 16 => name[i..].to_string(),
+            _ => String::new(),
+        };
+        let mut end = MAX_FILENAME_BYTES - extension.len();
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        let stem = name[..end].trim_end_matches(['.', ' ']);
+        name = format!("{}{}", stem, extension);
+    }
+    name
+}
+
+/// 全角英数字（`Ａ`〜`Ｚ`・`ａ`〜`ｚ`・`０`〜`９`）を半角に変換
+pub fn to_halfwidth_alphanumerics(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'Ａ'..='Ｚ' | 'ａ'..='ｚ' | '０'..='９' => {
+                char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+            }
+            c => c,
+        })
+        .collect()
+}
+
+/// 保存先ディレクトリのプレースホルダーを検証
+pub fn validate_dir(dir: &str) -> Result<(), String> {
+    for caps in DIR_PLACEHOLDER_RE.captures_iter(dir) {
+        if !DIR_PLACEHOLDERS.contains(&&caps[1]) {
+            return Err(format!(
+                "Unknown placeholder in destination directory: {}",
+                &caps[0]
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 保存先ディレクトリのプレースホルダーを展開
+///
+/// `values` にないプレースホルダーはそのまま残す（バッチの開始時とアイテムごとに分けて展開するため）。
+/// 値はフォルダ名として使えるよう無害化する。値が空のプレースホルダーだけのフォルダは省略する
+/// （例: プロジェクト未指定の `{project}/{date}` は `{date}` のみ）。
+pub fn render_dir(dir: &str, values: &[(&str, Option<&str>)]) -> String {
+    let mut rendered = String::new();
+    for part in dir.split_inclusive(['/', '\\']) {
+        let (segment, separator) = match part.strip_suffix(['/', '\\']) {
+            Some(segment) => (segment, &part[segment.len()..]),
+            None => (part, ""),
+        };
+        let mut replaced = false;
+        let segment =
+            DIR_PLACEHOLDER_RE.replace_all(segment, |caps: &regex::Captures| {
+                match values.iter().find(|(name, _)| *name == &caps[1]) {
+                    Some((_, value)) => {
+                        replaced = true;
+                        value
+                            .filter(|value| !value.trim().is_empty())
+                            .map(sanitize_filename)
+                            .unwrap_or_default()
+                    }
+                    None => caps[0].to_string(),
+                }
+            });
+        if replaced && segment.is_empty() {
+            continue;
+        }
+        rendered.push_str(&segment);
+        rendered.push_str(separator);
+    }
+    if !dir.ends_with(['/', '\\']) && rendered.len() > 1 {
+        rendered.truncate(rendered.trim_end_matches(['/', '\\']).len());
+    }
+    rendered
+}
+
+/// 保存先ディレクトリのうち、展開していないプレースホルダーを含むフォルダより前の部分
+///
+/// 監査ログの作成・完了後に開くフォルダ等、アイテムによらないディレクトリとして使用する。
+pub fn dir_prefix(dir: &str) -> &str {
+    let Some(m) = DIR_PLACEHOLDER_RE.find(dir) else {
+        return dir;
+    };
+    match dir[..m.start()].rfind(['/', '\\']) {
+        Some(0) => &dir[..1],
+        Some(end) => &dir[..end],
+        None => "",
+    }
+}
+
+/// プレースホルダーの値（値がない場合は空文字列）
+fn placeholder_value(
+    placeholder: &str,
+    context: &FilenameContext,
+    original_stem: Option<&str>,
+) -> String {
+    let psu = context.psu.filter(|psu| !psu.is_empty());
+    let photometry = context.photometry.as_ref();
+    match placeholder {
+        "spec_no" => context.spec_no.to_string(),
+        "model" => context.model_number.to_string(),
+        "psu" => psu.unwrap_or_default().to_string(),
+        "item" => match psu {
+            Some(psu) => format!("{}+{}", context.model_number, psu),
+            None => context.model_number.to_string(),
+        },
+        "original" => original_stem.unwrap_or_default().to_string(),
+        "manufacturer" => context.manufacturer.to_string(),
+        "cct" => [Some(context.model_number), original_stem]
+            .into_iter()
+            .flatten()
+            .find_map(color_temperature)
+            .unwrap_or_default(),
+        "ies_cct" => photometry
+            .and_then(|p| p.cct)
+            .map(|kelvin| format!("{}K", kelvin))
+            .unwrap_or_default(),
+        "beam" => photometry
+            .and_then(|p| p.beam_angle)
+            .map(|angle| format!("{:.0}deg", angle))
+            .unwrap_or_default(),
+        "lumens" => photometry
+            .and_then(|p| p.lumens)
+            .map(|lumens| format!("{:.0}lm", lumens))
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// 元ファイル名を拡張子なしの名前と拡張子に分割
+///
+/// IESファイルは元ファイル名に関わらず拡張子を `.ies` とする。
+fn split_original(
+    asset_type: AssetType,
+    original_filename: Option<&str>,
+) -> (Option<String>, Option<String>) {
+    let Some(original) = original_filename.filter(|name| !name.is_empty()) else {
+        let extension = (asset_type == AssetType::Ies).then(|| "ies".to_string());
+        return (None, extension);
+    };
+    if asset_type == AssetType::Ies {
+        let stem = if original.to_ascii_lowercase().ends_with(".ies") {
+            &original[..original.len() - 4]
+        } else {
+            original
+        };
+        return (Some(stem.to_string()), Some("ies".to_string()));
+    }
+    let path = Path::new(original);
+    match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => (
+            Some(stem.to_string_lossy().into_owned()),
+            Some(extension.to_string_lossy().into_owned()),
+        ),
+        _ => (Some(original.to_string()), None),
+    }
+}
+
+/// 型番・ファイル名から色温度を抽出（2桁は100倍する。例: "30K" → "3000K"）
+fn color_temperature(text: &str) -> Option<String> {
+    let digits = CCT_RE.captures(text)?.get(1)?.as_str();
+    let kelvin: u32 = digits.parse().ok()?;
+    let kelvin = if digits.len() == 2 {
+        kelvin * 100
+    } else {
+        kelvin
+    };
+    Some(format!("{}K", kelvin))
+}
+
+/// ファイル名に使用できない文字を '_' に置換
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if INVALID_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(psu: Option<&'a str>, original: Option<&'a str>) -> FilenameContext<'a> {
+        FilenameContext {
+            spec_no: "1001",
+            manufacturer: "TOKISTAR",
+            model_number: "OSP01-30K-30D",
+            psu,
+            original_filename: original,
+            photometry: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let ctx = context(None, Some("OSP01_30K_30D.IES"));
+        assert_eq!(
+            render(DEFAULT_TEMPLATE, AssetType::Ies, &ctx),
+            "1001_OSP01_30K_30D.ies"
+        );
+        assert_eq!(
+            render("{manufacturer} {model} {cct}", AssetType::Ies, &ctx),
+            "TOKISTAR OSP01-30K-30D 3000K.ies"
+        );
+
+        // 値のないプレースホルダーと直後の区切り文字は省略する
+        let ctx = context(None, None);
+        assert_eq!(
+            render("{spec_no}_{psu}_{model}", AssetType::Ies, &ctx),
+            "1001_OSP01-30K-30D.ies"
+        );
+        assert_eq!(
+            render("{spec_no}_{original|model}", AssetType::Cad, &ctx),
+            "1001_OSP01-30K-30D"
+        );
+
+        // PSUは型番と連結し、ファイル名に使用できない文字は置換する
+        let ctx = context(Some("DALI:XE92701"), Some("manual.pdf"));
+        assert_eq!(
+            render("{spec_no}_{item}", AssetType::Ies, &ctx),
+            "1001_OSP01-30K-30D+DALI_XE92701.ies"
+        );
+        assert_eq!(
+            render(DEFAULT_TEMPLATE, AssetType::Manual, &ctx),
+            "1001_manual.pdf"
+        );
+    }
+
+    #[test]
+    fn test_render_photometric_values() {
+        let mut ctx = context(None, Some("OSP01_30K_30D.IES"));
+        ctx.model_number = "XD93319";
+        // ダウンロード前（IESファイルの内容がない）は型番の色温度を使用し、ビーム角は省略する
+        assert_eq!(
+            render(CONTENT_TEMPLATE, AssetType::Ies, &ctx),
+            "1001_XD93319_3000K.ies"
+        );
+
+        ctx.photometry = Some(PhotometricValues {
+            cct: Some(2700),
+            beam_angle: Some(29.6),
+            lumens: Some(851.4),
+        });
+        assert_eq!(
+            render(CONTENT_TEMPLATE, AssetType::Ies, &ctx),
+            "1001_XD93319_2700K_30deg.ies"
+        );
+        assert_eq!(
+            render("{spec_no}_{lumens}", AssetType::Ies, &ctx),
+            "1001_851lm.ies"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("spec:1?.pdf"), "spec_1_.pdf");
+        assert_eq!(sanitize_filename(" AD12345. . "), "AD12345");
+        assert_eq!(sanitize_filename("con.ies"), "_con.ies");
+        assert_eq!(sanitize_filename("COM1"), "_COM1");
+        assert_eq!(sanitize_filename("CONSOLE.ies"), "CONSOLE.ies");
+        assert_eq!(sanitize_filename("..."), "_");
+
+        // 分解された濁点（NFD）は合成済みの文字（NFC）にする
+        assert_eq!(
+            sanitize_filename("\u{30BF}\u{3099}ウンライト.ies"),
+            "ダウンライト.ies"
+        );
+
+        // 拡張子を残して切り詰める（マルチバイト文字の途中で切らない）
+        let long = format!("{}.ies", "照明".repeat(100));
+        let sanitized = sanitize_filename(&long);
+        assert!(sanitized.len() <= MAX_FILENAME_BYTES);
+        assert!(sanitized.ends_with("明.ies") || sanitized.ends_with("照.ies"));
+    }
+
+    #[test]
+    fn test_to_halfwidth_alphanumerics() {
+        assert_eq!(
+            to_halfwidth_alphanumerics("１００１_ＡＤ１２３４５ｂ_照明－Ａ"),
+            "1001_AD12345b_照明－A"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(DEFAULT_TEMPLATE).is_ok());
+        assert!(validate("{spec_no}-{model}-{cct}").is_ok());
+        assert!(validate(CONTENT_TEMPLATE).is_ok());
+        assert!(validate("{specNo}").is_err());
+        assert!(validate("{spec_no").is_err());
+        assert!(validate("spec_no}").is_err());
+        assert!(validate("fixture").is_err());
+    }
+
+    #[test]
+    fn test_reserved_paths() {
+        let reserved = ReservedPaths::default();
+        assert_eq!(reserved.reserve("/work", "AD12345.ies"), "AD12345.ies");
+        assert_eq!(reserved.reserve("/work", "ad12345.IES"), "ad12345_2.IES");
+        assert_eq!(reserved.reserve("/work", "AD12345.ies"), "AD12345_3.ies");
+        // 保存先ディレクトリが異なれば衝突しない
+        assert_eq!(reserved.reserve("/work/B", "AD12345.ies"), "AD12345.ies");
+        assert_eq!(reserved.reserve("/work", "README"), "README");
+        assert_eq!(reserved.reserve("/work", "README"), "README_2");
+    }
+
+    #[test]
+    fn test_render_dir() {
+        let dir = "/work/{project}/{date}/IES/{manufacturer}";
+        let dir = render_dir(
+            dir,
+            &[("project", Some("渋谷/A棟")), ("date", Some("2026-10-16"))],
+        );
+        assert_eq!(dir, "/work/渋谷_A棟/2026-10-16/IES/{manufacturer}");
+        assert_eq!(dir_prefix(&dir), "/work/渋谷_A棟/2026-10-16/IES");
+        assert_eq!(
+            render_dir(&dir, &[("manufacturer", Some("コイズミ照明"))]),
+            "/work/渋谷_A棟/2026-10-16/IES/コイズミ照明"
+        );
+
+        // 値が空のプレースホルダーだけのフォルダは省略する
+        assert_eq!(
+            render_dir(
+                "C:\\IES\\{project}\\{sheet}_{date}",
+                &[
+                    ("project", None),
+                    ("sheet", None),
+                    ("date", Some("2026-10-16"))
+                ]
+            ),
+            "C:\\IES\\_2026-10-16"
+        );
+        assert_eq!(render_dir("/work/{project}", &[("project", None)]), "/work");
+        assert_eq!(dir_prefix("/work/IES"), "/work/IES");
+
+        assert!(validate_dir("/work/{project}/{manufacturer}").is_ok());
+        assert!(validate_dir("/work/{Project Files}").is_ok());
+        assert!(validate_dir("/work/{projects}").is_err());
+    }
+}
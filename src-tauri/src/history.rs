@@ -0,0 +1,304 @@
+//! ダウンロード履歴
+//!
+//! 一括ダウンロードの結果をアセット単位で記録し、条件を指定して検索する。
+//! tauri-plugin-store のストアファイル（`autosight.store.json`）に永続化する。
+
+use crate::portable::{self, STORE_NAME};
+use crate::providers::{AssetType, DownloadSource};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 履歴を保存するキー
+const HISTORY_KEY: &str = "download_history";
+/// 保持する履歴の最大件数（超えた分は古いものから削除）
+const MAX_HISTORY_ENTRIES: usize = 10_000;
+/// 1ページあたりのデフォルト件数
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// 履歴の1件（1アイテム・1アセット分）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    /// プロジェクトID（プロジェクト外のダウンロードでは None）
+    pub project_id: Option<String>,
+    /// Spec No.
+    pub spec_no: String,
+    /// メーカー名
+    pub manufacturer: String,
+    /// 型番
+    pub model_number: String,
+    /// PSU型番
+    #[serde(default)]
+    pub psu: Option<String>,
+    /// アセット種別
+    pub asset_type: AssetType,
+    /// 成功したかどうか
+    pub success: bool,
+    /// 保存先パス（成功時のみ）
+    pub file_path: Option<String>,
+    /// サーバーから取得した元ファイル名（再リネーム時に使用）
+    #[serde(default)]
+    pub original_filename: Option<String>,
+    /// 保存したファイルのSHA-256（成功時のみ）
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// 取得元のURL・HTTP応答の情報
+    #[serde(default)]
+    pub source: Option<DownloadSource>,
+    /// 取得元のプロバイダーID（メーカー欄に複数のメーカーがある場合に、どれから取得したか）
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// エラーメッセージ（失敗時のみ）
+    pub error: Option<String>,
+    /// ダウンロード日時
+    pub downloaded_at: DateTime<Utc>,
+}
+
+/// 履歴の検索条件（指定した条件はすべて満たす必要がある）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryQuery {
+    /// プロジェクトID
+    pub project_id: Option<String>,
+    /// メーカー名（大文字・小文字を区別しない）
+    pub manufacturer: Option<String>,
+    /// この日時以降
+    pub from: Option<DateTime<Utc>>,
+    /// この日時以前
+    pub to: Option<DateTime<Utc>>,
+    /// 成功（true）/失敗（false）のみ
+    pub success: Option<bool>,
+    /// 型番・Spec No.・メーカー名・保存先パス・取得元URLのいずれかに含まれる文字列（大文字・小文字を区別しない）
+    pub text: Option<String>,
+    /// 読み飛ばす件数
+    pub offset: usize,
+    /// 取得件数（省略時は50件）
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.project_id
+            .as_ref()
+            .is_none_or(|id| entry.project_id.as_ref() == Some(id))
+            && self
+                .manufacturer
+                .as_ref()
+                .is_none_or(|m| entry.manufacturer.eq_ignore_ascii_case(m))
+            && self.from.is_none_or(|from| entry.downloaded_at >= from)
+            && self.to.is_none_or(|to| entry.downloaded_at <= to)
+            && self.success.is_none_or(|s| entry.success == s)
+            && self.text.as_ref().is_none_or(|t| contains_text(entry, t))
+    }
+}
+
+/// 履歴の文字列項目のいずれかに `text` が含まれるか
+fn contains_text(entry: &HistoryEntry, text: &str) -> bool {
+    let text = text.trim().to_lowercase();
+    [
+        Some(entry.model_number.as_str()),
+        Some(entry.spec_no.as_str()),
+        Some(entry.manufacturer.as_str()),
+        entry.file_path.as_deref(),
+        entry.source.as_ref().map(|s| s.url.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|value| value.to_lowercase().contains(&text))
+}
+
+/// 条件に一致する履歴を新しい順にすべて取得（ページ分割しない）
+pub fn filter(entries: &[HistoryEntry], query: &HistoryQuery) -> Vec<HistoryEntry> {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| query.matches(e))
+        .cloned()
+        .collect()
+}
+
+/// 履歴の検索結果（1ページ分）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    /// 条件に一致した全件数
+    pub total: usize,
+    /// このページの履歴（新しい順）
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// 条件に一致する履歴を新しい順に1ページ分取得
+pub fn query(entries: &[HistoryEntry], query: &HistoryQuery) -> HistoryPage {
+    let matched: Vec<_> = entries.iter().rev().filter(|e| query.matches(e)).collect();
+    let total = matched.len();
+    let entries = matched
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .cloned()
+        .collect();
+
+    HistoryPage { total, entries }
+}
+
+/// 保存済みの履歴を古い順に読み込む
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<HistoryEntry>, String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    match store.get(HISTORY_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse download history: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// プロジェクト名を取得（プロジェクトはフロントエンドが `projects` キーに保存している）
+pub fn project_name<R: Runtime>(app: &AppHandle<R>, project_id: &str) -> Option<String> {
+    let projects = app
+        .store(portable::store_path(STORE_NAME))
+        .ok()?
+        .get("projects")?;
+    projects
+        .as_array()?
+        .iter()
+        .find(|p| p.get("id").and_then(|id| id.as_str()) == Some(project_id))?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// 履歴を追記して保存
+pub fn append<R: Runtime>(
+    app: &AppHandle<R>,
+    new_entries: Vec<HistoryEntry>,
+) -> Result<(), String> {
+    if new_entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut entries = load(app)?;
+    entries.extend(new_entries);
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+    }
+    save(app, entries)
+}
+
+/// 履歴全体を上書き保存
+pub fn save<R: Runtime>(app: &AppHandle<R>, entries: Vec<HistoryEntry>) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        HISTORY_KEY,
+        serde_json::to_value(entries).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save download history: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(project_id: &str, manufacturer: &str, success: bool, at: &str) -> HistoryEntry {
+        HistoryEntry {
+            project_id: Some(project_id.to_string()),
+            spec_no: "1001".to_string(),
+            manufacturer: manufacturer.to_string(),
+            model_number: "AD12345".to_string(),
+            psu: None,
+            asset_type: AssetType::Ies,
+            success,
+            file_path: None,
+            original_filename: None,
+            sha256: None,
+            source: None,
+            provider: None,
+            error: None,
+            downloaded_at: at.parse().unwrap(),
+        }
+    }
+
+    fn history() -> Vec<HistoryEntry> {
+        vec![
+            entry("p1", "Koizumi", true, "2026-01-01T00:00:00Z"),
+            entry("p1", "Tokistar", false, "2026-02-01T00:00:00Z"),
+            entry("p2", "Koizumi", true, "2026-03-01T00:00:00Z"),
+            entry("p1", "Koizumi", false, "2026-04-01T00:00:00Z"),
+        ]
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let entries = history();
+
+        let page = query(
+            &entries,
+            &HistoryQuery {
+                project_id: Some("p1".to_string()),
+                manufacturer: Some("koizumi".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total, 2);
+        // 新しい順
+        assert_eq!(page.entries[0].downloaded_at, entries[3].downloaded_at);
+
+        let page = query(
+            &entries,
+            &HistoryQuery {
+                from: Some("2026-02-01T00:00:00Z".parse().unwrap()),
+                to: Some("2026-03-31T00:00:00Z".parse().unwrap()),
+                success: Some(true),
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].project_id.as_deref(), Some("p2"));
+    }
+
+    #[test]
+    fn test_query_pagination() {
+        let entries = history();
+        let page = query(
+            &entries,
+            &HistoryQuery {
+                offset: 1,
+                limit: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total, 4);
+        assert_eq!(page.entries.len(), 2);
+        assert_eq!(page.entries[0].downloaded_at, entries[2].downloaded_at);
+        assert_eq!(page.entries[1].downloaded_at, entries[1].downloaded_at);
+    }
+
+    #[test]
+    fn test_query_text() {
+        let mut entries = history();
+        entries[1].model_number = "LZD-93195XW".to_string();
+        entries[2].source = Some(DownloadSource {
+            url: "https://www.example.com/ies/ad12345.ies".to_string(),
+            ..Default::default()
+        });
+
+        let text = |t: &str| HistoryQuery {
+            text: Some(t.to_string()),
+            ..Default::default()
+        };
+        let page = query(&entries, &text("lzd-93195"));
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].manufacturer, "Tokistar");
+
+        // 取得元URLも対象
+        assert_eq!(filter(&entries, &text("example.com")).len(), 1);
+        assert!(filter(&entries, &text("XYZ")).is_empty());
+    }
+}
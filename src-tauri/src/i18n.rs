@@ -0,0 +1,464 @@
+//! バックエンドが生成する表示用文言の多言語化
+//!
+//! Excel読み込みの警告・レポート・デスクトップ通知など、ユーザーに表示する文言を
+//! メッセージキー（[`Message`] のバリアント）と表示言語（[`Locale`]）から生成する。
+//! 海外拠点でも使えるよう、表示言語は設定で切り替える。
+//!
+//! ログやエラーコードの判定に使うエラーメッセージ（英語の定型文）は対象外。
+
+use crate::providers::{Currency, Price};
+use serde::{Deserialize, Serialize};
+
+/// 表示言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// 言語コード（HTMLの `lang` 属性等に使用）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ja => "ja",
+            Locale::En => "en",
+        }
+    }
+}
+
+/// 表示用文言のメッセージキー
+pub enum Message<'a> {
+    /// Fixture Base シートが見つからない
+    SheetNotFound,
+    /// CSVファイルがUTF-8でない
+    CsvNotUtf8,
+    /// 必須列が見つからない
+    MissingColumn { column: &'a str },
+    /// Spec No. が未入力
+    EmptySpecNo { row_number: u32 },
+    /// メーカーが未入力
+    EmptyManufacturer { spec_no: &'a str },
+    /// 型番が未入力
+    EmptyModel { spec_no: &'a str },
+    /// 対応プロバイダーのないメーカー
+    UnknownManufacturer {
+        spec_no: &'a str,
+        manufacturer: &'a str,
+    },
+    /// Spec No. が重複
+    DuplicateSpecNo { spec_no: &'a str },
+    /// レポートの見出し（プロジェクト名があれば付ける）
+    ReportTitle { project: Option<&'a str> },
+    /// レポートの集計
+    ReportSummary {
+        success: usize,
+        failure: usize,
+        total: usize,
+    },
+    /// レポートの列見出し: メーカー
+    ColumnManufacturer,
+    /// レポートの列見出し: 型番
+    ColumnModel,
+    /// レポートの列見出し: アセット
+    ColumnAsset,
+    /// レポートの列見出し: 結果
+    ColumnResult,
+    /// レポートの列見出し: ファイル
+    ColumnFile,
+    /// レポートの列見出し: ファイルサイズ
+    ColumnFileSize,
+    /// レポートの列見出し: エラー
+    ColumnError,
+    /// レポートの列見出し: 日時
+    ColumnDate,
+    /// 器具リストの見出し（プロジェクト名があれば付ける）
+    ScheduleTitle { project: Option<&'a str> },
+    /// 器具リストの集計（定価 × 台数の合計。通貨・税込／税抜ごと）
+    ScheduleSummary {
+        rows: usize,
+        totals: &'a [Price],
+        unpriced: usize,
+    },
+    /// 価格（例: "12800円（税抜）"、"USD 12.50 (excl. tax)"）
+    PriceAmount { price: &'a Price },
+    /// 器具リストの列見出し: 器具タイプ
+    ColumnLuminaireType,
+    /// 器具リストの列見出し: PSU
+    ColumnPsu,
+    /// 器具リストの列見出し: 品名
+    ColumnProductName,
+    /// 器具リストの列見出し: 器具光束
+    ColumnLumens,
+    /// 器具リストの列見出し: 消費電力
+    ColumnWatts,
+    /// 器具リストの列見出し: ビーム角
+    ColumnBeamAngle,
+    /// 器具リストの列見出し: BUG評価
+    ColumnBugRating,
+    /// 器具リストの列見出し: 定価
+    ColumnPrice,
+    /// 器具リストの列見出し: 台数
+    ColumnQuantity,
+    /// 器具リストの列見出し: 定価 × 台数
+    ColumnExtendedPrice,
+    /// 器具リストの列見出し: 通貨
+    ColumnCurrency,
+    /// 器具リストの列見出し: 税込／税抜
+    ColumnTax,
+    /// 税込
+    TaxIncluded,
+    /// 税抜
+    TaxExcluded,
+    /// 一括ダウンロードの完了通知
+    BatchFinished {
+        success: usize,
+        failure: usize,
+        cancelled: usize,
+    },
+    /// バックグラウンドで実行中の通知（Android）
+    BackgroundDownload { total: usize },
+    /// トレイメニュー: 実行中の一括ダウンロードの進捗
+    TrayProgress {
+        done: usize,
+        total: usize,
+        paused: bool,
+    },
+    /// トレイメニュー: 一括ダウンロードを実行していない
+    TrayIdle,
+    /// トレイメニュー: ウィンドウを表示
+    TrayShow,
+    /// トレイメニュー: 一時停止
+    TrayPause,
+    /// トレイメニュー: 再開
+    TrayResume,
+    /// トレイメニュー: キャンセル
+    TrayCancel,
+    /// トレイメニュー: 終了
+    TrayQuit,
+}
+
+impl Message<'_> {
+    /// 指定した言語の文言
+    pub fn text(&self, locale: Locale) -> String {
+        use Locale::{En, Ja};
+        match (self, locale) {
+            (Message::SheetNotFound, Ja) => "Fixture Base シートが見つかりません".to_string(),
+            (Message::SheetNotFound, En) => "Fixture Base sheet not found".to_string(),
+            (Message::CsvNotUtf8, Ja) => "CSVファイルはUTF-8で保存してください".to_string(),
+            (Message::CsvNotUtf8, En) => "CSV files must be saved as UTF-8".to_string(),
+            (Message::MissingColumn { column }, Ja) => {
+                format!("必須カラム「{}」が見つかりません", column)
+            }
+            (Message::MissingColumn { column }, En) => {
+                format!("Required column \"{}\" not found", column)
+            }
+            (Message::EmptySpecNo { row_number }, Ja) => {
+                format!("{}行目: Spec No. が入力されていません", row_number)
+            }
+            (Message::EmptySpecNo { row_number }, En) => {
+                format!("Row {}: Spec No. is empty", row_number)
+            }
+            (Message::EmptyManufacturer { spec_no }, Ja) => {
+                format!("{}: メーカーが入力されていません", spec_no)
+            }
+            (Message::EmptyManufacturer { spec_no }, En) => {
+                format!("{}: manufacturer is empty", spec_no)
+            }
+            (Message::EmptyModel { spec_no }, Ja) => {
+                format!("{}: 型番が入力されていません", spec_no)
+            }
+            (Message::EmptyModel { spec_no }, En) => {
+                format!("{}: model number is empty", spec_no)
+            }
+            (
+                Message::UnknownManufacturer {
+                    spec_no,
+                    manufacturer,
+                },
+                Ja,
+            ) => format!("{}: 未対応のメーカーです（{}）", spec_no, manufacturer),
+            (
+                Message::UnknownManufacturer {
+                    spec_no,
+                    manufacturer,
+                },
+                En,
+            ) => format!("{}: unsupported manufacturer ({})", spec_no, manufacturer),
+            (Message::DuplicateSpecNo { spec_no }, Ja) => {
+                format!("{}: Spec No. が重複しています", spec_no)
+            }
+            (Message::DuplicateSpecNo { spec_no }, En) => {
+                format!("{}: duplicate Spec No.", spec_no)
+            }
+            (Message::ReportTitle { project }, Ja) => match project {
+                Some(project) => format!("{} ダウンロードレポート", project),
+                None => "ダウンロードレポート".to_string(),
+            },
+            (Message::ReportTitle { project }, En) => match project {
+                Some(project) => format!("{} Download Report", project),
+                None => "Download Report".to_string(),
+            },
+            (
+                Message::ReportSummary {
+                    success,
+                    failure,
+                    total,
+                },
+                Ja,
+            ) => format!("成功: {} / 失敗: {} / 合計: {}", success, failure, total),
+            (
+                Message::ReportSummary {
+                    success,
+                    failure,
+                    total,
+                },
+                En,
+            ) => format!(
+                "Succeeded: {} / Failed: {} / Total: {}",
+                success, failure, total
+            ),
+            (Message::ColumnManufacturer, Ja) => "メーカー".to_string(),
+            (Message::ColumnManufacturer, En) => "Manufacturer".to_string(),
+            (Message::ColumnModel, Ja) => "型番".to_string(),
+            (Message::ColumnModel, En) => "Model".to_string(),
+            (Message::ColumnAsset, Ja) => "アセット".to_string(),
+            (Message::ColumnAsset, En) => "Asset".to_string(),
+            (Message::ColumnResult, Ja) => "結果".to_string(),
+            (Message::ColumnResult, En) => "Result".to_string(),
+            (Message::ColumnFile, Ja) => "ファイル".to_string(),
+            (Message::ColumnFile, En) => "File".to_string(),
+            (Message::ColumnFileSize, Ja) => "ファイルサイズ".to_string(),
+            (Message::ColumnFileSize, En) => "File Size".to_string(),
+            (Message::ColumnError, Ja) => "エラー".to_string(),
+            (Message::ColumnError, En) => "Error".to_string(),
+            (Message::ColumnDate, Ja) => "日時".to_string(),
+            (Message::ColumnDate, En) => "Date".to_string(),
+            (Message::ScheduleTitle { project }, Ja) => match project {
+                Some(project) => format!("{} 器具リスト", project),
+                None => "器具リスト".to_string(),
+            },
+            (Message::ScheduleTitle { project }, En) => match project {
+                Some(project) => format!("{} Lighting Schedule", project),
+                None => "Lighting Schedule".to_string(),
+            },
+            (
+                Message::ScheduleSummary {
+                    rows,
+                    totals,
+                    unpriced,
+                },
+                Ja,
+            ) => {
+                let total = match totals {
+                    [] => "0円".to_string(),
+                    totals => totals
+                        .iter()
+                        .map(|price| Message::PriceAmount { price }.text(locale))
+                        .collect::<Vec<_>>()
+                        .join("＋"),
+                };
+                let mut text = format!("器具 {}件・定価合計 {}", rows, total);
+                if *unpriced > 0 {
+                    text.push_str(&format!("（定価不明 {}件を除く）", unpriced));
+                }
+                text
+            }
+            (
+                Message::ScheduleSummary {
+                    rows,
+                    totals,
+                    unpriced,
+                },
+                En,
+            ) => {
+                let total = match totals {
+                    [] => "JPY 0".to_string(),
+                    totals => totals
+                        .iter()
+                        .map(|price| Message::PriceAmount { price }.text(locale))
+                        .collect::<Vec<_>>()
+                        .join(" + "),
+                };
+                let mut text = format!("{} fixtures, list price total {}", rows, total);
+                if *unpriced > 0 {
+                    text.push_str(&format!(" (excluding {} without a price)", unpriced));
+                }
+                text
+            }
+            (Message::ColumnLuminaireType, Ja) => "器具タイプ".to_string(),
+            (Message::ColumnLuminaireType, En) => "Type".to_string(),
+            (Message::ColumnPsu, _) => "PSU".to_string(),
+            (Message::ColumnProductName, Ja) => "品名".to_string(),
+            (Message::ColumnProductName, En) => "Product".to_string(),
+            (Message::ColumnLumens, Ja) => "器具光束 (lm)".to_string(),
+            (Message::ColumnLumens, En) => "Lumens (lm)".to_string(),
+            (Message::ColumnWatts, Ja) => "消費電力 (W)".to_string(),
+            (Message::ColumnWatts, En) => "Power (W)".to_string(),
+            (Message::ColumnBeamAngle, Ja) => "ビーム角 (°)".to_string(),
+            (Message::ColumnBeamAngle, En) => "Beam (°)".to_string(),
+            (Message::ColumnBugRating, Ja) => "BUG評価".to_string(),
+            (Message::ColumnBugRating, En) => "BUG rating".to_string(),
+            (Message::PriceAmount { price }, Ja) => {
+                let mut text = match price.currency {
+                    Currency::Jpy => format!("{}円", price.amount()),
+                    currency => format!("{} {}", price.amount(), currency.code()),
+                };
+                match price.tax_included {
+                    Some(true) => text.push_str("（税込）"),
+                    Some(false) => text.push_str("（税抜）"),
+                    None => {}
+                }
+                text
+            }
+            (Message::PriceAmount { price }, En) => {
+                let mut text = format!("{} {}", price.currency.code(), price.amount());
+                match price.tax_included {
+                    Some(true) => text.push_str(" (incl. tax)"),
+                    Some(false) => text.push_str(" (excl. tax)"),
+                    None => {}
+                }
+                text
+            }
+            (Message::ColumnPrice, Ja) => "定価".to_string(),
+            (Message::ColumnPrice, En) => "List price".to_string(),
+            (Message::ColumnQuantity, Ja) => "台数".to_string(),
+            (Message::ColumnQuantity, En) => "Qty".to_string(),
+            (Message::ColumnExtendedPrice, Ja) => "定価 × 台数".to_string(),
+            (Message::ColumnExtendedPrice, En) => "Extended price".to_string(),
+            (Message::ColumnCurrency, Ja) => "通貨".to_string(),
+            (Message::ColumnCurrency, En) => "Currency".to_string(),
+            (Message::ColumnTax, Ja) => "税込／税抜".to_string(),
+            (Message::ColumnTax, En) => "Tax".to_string(),
+            (Message::TaxIncluded, Ja) => "税込".to_string(),
+            (Message::TaxIncluded, En) => "Incl.".to_string(),
+            (Message::TaxExcluded, Ja) => "税抜".to_string(),
+            (Message::TaxExcluded, En) => "Excl.".to_string(),
+            (
+                Message::BatchFinished {
+                    success,
+                    failure,
+                    cancelled,
+                },
+                Ja,
+            ) => {
+                let mut text = format!("成功 {}件・失敗 {}件", success, failure);
+                if *cancelled > 0 {
+                    text.push_str(&format!("・キャンセル {}件", cancelled));
+                }
+                text
+            }
+            (
+                Message::BatchFinished {
+                    success,
+                    failure,
+                    cancelled,
+                },
+                En,
+            ) => {
+                let mut text = format!("{} succeeded, {} failed", success, failure);
+                if *cancelled > 0 {
+                    text.push_str(&format!(", {} cancelled", cancelled));
+                }
+                text
+            }
+            (Message::BackgroundDownload { total }, Ja) => {
+                format!("{}件をダウンロード中", total)
+            }
+            (Message::BackgroundDownload { total }, En) => {
+                format!("Downloading {} items", total)
+            }
+            (
+                Message::TrayProgress {
+                    done,
+                    total,
+                    paused,
+                },
+                Ja,
+            ) => {
+                let state = if *paused {
+                    "一時停止中"
+                } else {
+                    "ダウンロード中"
+                };
+                format!("{} {}/{}件", state, done, total)
+            }
+            (
+                Message::TrayProgress {
+                    done,
+                    total,
+                    paused,
+                },
+                En,
+            ) => {
+                let state = if *paused { "Paused" } else { "Downloading" };
+                format!("{} {}/{}", state, done, total)
+            }
+            (Message::TrayIdle, Ja) => "待機中".to_string(),
+            (Message::TrayIdle, En) => "Idle".to_string(),
+            (Message::TrayShow, Ja) => "ウィンドウを表示".to_string(),
+            (Message::TrayShow, En) => "Show Window".to_string(),
+            (Message::TrayPause, Ja) => "一時停止".to_string(),
+            (Message::TrayPause, En) => "Pause".to_string(),
+            (Message::TrayResume, Ja) => "再開".to_string(),
+            (Message::TrayResume, En) => "Resume".to_string(),
+            (Message::TrayCancel, Ja) => "キャンセル".to_string(),
+            (Message::TrayCancel, En) => "Cancel".to_string(),
+            (Message::TrayQuit, Ja) => "終了".to_string(),
+            (Message::TrayQuit, En) => "Quit".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text() {
+        let message = Message::UnknownManufacturer {
+            spec_no: "A01",
+            manufacturer: "大光電機",
+        };
+        assert_eq!(
+            message.text(Locale::Ja),
+            "A01: 未対応のメーカーです（大光電機）"
+        );
+        assert_eq!(
+            message.text(Locale::En),
+            "A01: unsupported manufacturer (大光電機)"
+        );
+
+        let message = Message::ReportTitle {
+            project: Some("A棟"),
+        };
+        assert_eq!(message.text(Locale::En), "A棟 Download Report");
+
+        let totals = [
+            Price::jpy(12800).assume_tax_included(false),
+            Price {
+                minor_units: 1250,
+                currency: Currency::Usd,
+                tax_included: None,
+            },
+        ];
+        let message = Message::ScheduleSummary {
+            rows: 3,
+            totals: &totals,
+            unpriced: 1,
+        };
+        assert_eq!(
+            message.text(Locale::Ja),
+            "器具 3件・定価合計 12800円（税抜）＋12.50 USD（定価不明 1件を除く）"
+        );
+        assert_eq!(
+            message.text(Locale::En),
+            "3 fixtures, list price total JPY 12800 (excl. tax) + USD 12.50 (excluding 1 without a price)"
+        );
+        assert_eq!(
+            serde_json::from_str::<Locale>("\"en\"").unwrap(),
+            Locale::En
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! 無視リスト
+//!
+//! 特注品・造作照明等、メーカーサイトに配光データが掲載されていないとわかっている器具を
+//! Spec No. または型番で登録しておき、失敗したアイテムの再実行や器具リストの読み込み直しで
+//! 何度も取得を試みないようにする。無視リストはプロジェクトごとにストアに記録する。
+
+use crate::portable::{self, STORE_NAME};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 無視リスト（プロジェクトIDをキーとするマップ）を保存するキー
+const IGNORE_LIST_KEY: &str = "ignoreLists";
+/// プロジェクト未指定の無視リストのキー
+const NO_PROJECT_KEY: &str = "";
+
+/// 無視する対象の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IgnoreKind {
+    /// Spec No.（完全一致）
+    SpecNo,
+    /// 型番（大文字・小文字と空白を無視して比較）
+    Model,
+}
+
+/// 無視リストの項目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IgnoreEntry {
+    pub kind: IgnoreKind,
+    /// Spec No. または型番
+    pub value: String,
+    /// 無視する理由（例: "特注品のため配光データなし"）
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+impl IgnoreEntry {
+    /// 同じ対象か
+    fn same_target(&self, kind: IgnoreKind, value: &str) -> bool {
+        self.kind == kind && normalize(kind, &self.value) == normalize(kind, value)
+    }
+}
+
+/// 比較用に正規化（型番は大文字・小文字と空白を無視する）
+fn normalize(kind: IgnoreKind, value: &str) -> String {
+    match kind {
+        IgnoreKind::SpecNo => value.trim().to_string(),
+        IgnoreKind::Model => value
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect(),
+    }
+}
+
+/// プロジェクトの無視リスト
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IgnoreList(pub Vec<IgnoreEntry>);
+
+impl IgnoreList {
+    /// 行が無視リストに含まれるか
+    pub fn matches(&self, spec_no: &str, model_number: &str) -> bool {
+        self.0.iter().any(|entry| match entry.kind {
+            IgnoreKind::SpecNo => entry.same_target(IgnoreKind::SpecNo, spec_no),
+            IgnoreKind::Model => entry.same_target(IgnoreKind::Model, model_number),
+        })
+    }
+
+    /// 項目を追加（同じ対象がある場合は理由を更新する）
+    fn add(&mut self, kind: IgnoreKind, value: &str, reason: Option<String>) {
+        match self.0.iter_mut().find(|e| e.same_target(kind, value)) {
+            Some(entry) => entry.reason = reason,
+            None => self.0.push(IgnoreEntry {
+                kind,
+                value: value.trim().to_string(),
+                reason,
+                added_at: Utc::now(),
+            }),
+        }
+    }
+
+    /// 項目を削除（削除した場合は true）
+    fn remove(&mut self, kind: IgnoreKind, value: &str) -> bool {
+        let before = self.0.len();
+        self.0.retain(|e| !e.same_target(kind, value));
+        self.0.len() != before
+    }
+}
+
+/// すべてのプロジェクトの無視リストを読み込む（ない場合・読み込めない場合は空）
+pub fn load_all<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, IgnoreList> {
+    app.store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(IGNORE_LIST_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// すべてのプロジェクトの無視リストを保存
+fn save_all<R: Runtime>(
+    app: &AppHandle<R>,
+    lists: &BTreeMap<String, IgnoreList>,
+) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        IGNORE_LIST_KEY,
+        serde_json::to_value(lists).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save ignore list: {}", e))
+}
+
+/// プロジェクトの無視リストを読み込む（`project_id` 省略時はプロジェクト未指定のリスト）
+pub fn load<R: Runtime>(app: &AppHandle<R>, project_id: Option<&str>) -> IgnoreList {
+    load_all(app)
+        .remove(project_id.unwrap_or(NO_PROJECT_KEY))
+        .unwrap_or_default()
+}
+
+/// プロジェクトの無視リストに追加
+pub fn add<R: Runtime>(
+    app: &AppHandle<R>,
+    project_id: Option<&str>,
+    kind: IgnoreKind,
+    value: &str,
+    reason: Option<String>,
+) -> Result<IgnoreList, String> {
+    if value.trim().is_empty() {
+        return Err("Ignore list value is empty".to_string());
+    }
+    let mut lists = load_all(app);
+    let list = lists
+        .entry(project_id.unwrap_or(NO_PROJECT_KEY).to_string())
+        .or_default();
+    list.add(kind, value, reason);
+    let list = list.clone();
+    save_all(app, &lists)?;
+    Ok(list)
+}
+
+/// 別のPCから読み込んだ無視リストを追加（同じ対象がある場合は理由を更新する）
+///
+/// 戻り値は追加した項目の件数。
+pub fn merge_all<R: Runtime>(
+    app: &AppHandle<R>,
+    imported: BTreeMap<String, IgnoreList>,
+) -> Result<usize, String> {
+    if imported.values().all(|list| list.0.is_empty()) {
+        return Ok(0);
+    }
+    let mut lists = load_all(app);
+    let mut added = 0;
+    for (project_id, imported) in imported {
+        let list = lists.entry(project_id).or_default();
+        for entry in imported.0 {
+            let before = list.0.len();
+            list.add(entry.kind, &entry.value, entry.reason);
+            added += list.0.len() - before;
+        }
+    }
+    lists.retain(|_, list| !list.0.is_empty());
+    save_all(app, &lists)?;
+    Ok(added)
+}
+
+/// プロジェクトの無視リストから削除
+pub fn remove<R: Runtime>(
+    app: &AppHandle<R>,
+    project_id: Option<&str>,
+    kind: IgnoreKind,
+    value: &str,
+) -> Result<IgnoreList, String> {
+    let mut lists = load_all(app);
+    let key = project_id.unwrap_or(NO_PROJECT_KEY);
+    let Some(list) = lists.get_mut(key) else {
+        return Ok(IgnoreList::default());
+    };
+    let removed = list.remove(kind, value);
+    let list = list.clone();
+    if list.0.is_empty() {
+        lists.remove(key);
+    }
+    if removed {
+        save_all(app, &lists)?;
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let mut list = IgnoreList::default();
+        list.add(IgnoreKind::SpecNo, "A01", None);
+        list.add(
+            IgnoreKind::Model,
+            "ad 12345",
+            Some("特注品のため配光データなし".to_string()),
+        );
+
+        assert!(list.matches("A01", "XX-1"));
+        assert!(!list.matches("a01", "XX-1"));
+        // 型番は大文字・小文字と空白を無視する
+        assert!(list.matches("B01", "AD12345"));
+        assert!(!list.matches("B01", "AD12346"));
+
+        // 同じ対象は重複して登録しない
+        list.add(IgnoreKind::Model, "AD12345", None);
+        assert_eq!(list.0.len(), 2);
+        assert_eq!(list.0[1].reason, None);
+
+        assert!(list.remove(IgnoreKind::SpecNo, "A01"));
+        assert!(!list.remove(IgnoreKind::SpecNo, "A01"));
+        assert!(!list.matches("A01", "XX-1"));
+    }
+}
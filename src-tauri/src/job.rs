@@ -0,0 +1,268 @@
+//! ジョブファイル（JSON）による一括ダウンロード
+//!
+//! ダウンロードするアイテム・保存先・アセット種別・ファイル名テンプレート・同時実行数を
+//! JSONファイルで宣言し、アプリのコマンドまたは `autosight-cli job` で実行する。
+//! プロジェクトフォルダに置いてレビュー・再実行できるようにするためのもの。
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "destDir": "./ies",
+//!   "assetTypes": ["ies", "specSheet"],
+//!   "filenameTemplate": "{spec_no}_{model}",
+//!   "concurrency": 2,
+//!   "items": [
+//!     { "specNo": "1001", "manufacturer": "KOIZUMI", "modelNumber": "AD12345" }
+//!   ]
+//! }
+//! ```
+//!
+//! `destDir` が相対パスの場合はジョブファイルのあるディレクトリからの相対パスとする。
+
+use crate::commands::{self, AssetDownloadResult, BatchDownloadItem};
+use crate::filename::{self, FilenameOptions};
+use crate::providers::{client_builder, AssetType, CancelToken, ProviderRegistry};
+use crate::settings::DestinationSettings;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 対応しているジョブファイルの形式のバージョン
+const JOB_VERSION: u32 = 1;
+/// 同時実行数の上限
+const MAX_CONCURRENCY: usize = 16;
+
+/// ジョブファイルの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct JobFile {
+    /// 形式のバージョン
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// ダウンロードするアイテム
+    pub items: Vec<BatchDownloadItem>,
+    /// 保存先ディレクトリ
+    pub dest_dir: String,
+    /// 取得するアセット種別（アイテムごとの指定が優先。省略時はIESのみ）
+    #[serde(default = "default_asset_types")]
+    pub asset_types: Vec<AssetType>,
+    /// ファイル名テンプレート（省略時はプロバイダーの命名規則）
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    /// 同時にダウンロードするアイテム数
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// IES以外のアセットを種別ごとのサブフォルダに保存する
+    #[serde(default = "default_true")]
+    pub asset_subdirs: bool,
+    /// 同名のファイルがある場合に上書きする
+    #[serde(default = "default_true")]
+    pub overwrite_existing: bool,
+}
+
+fn default_version() -> u32 {
+    JOB_VERSION
+}
+
+fn default_asset_types() -> Vec<AssetType> {
+    vec![AssetType::Ies]
+}
+
+fn default_concurrency() -> usize {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// ジョブの1アイテム分の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobItemResult {
+    pub spec_no: String,
+    pub model_number: String,
+    /// 要求したアセットがすべて取得できたか
+    pub success: bool,
+    pub assets: Vec<AssetDownloadResult>,
+}
+
+/// ジョブの実行結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobResult {
+    /// 保存先ディレクトリ（ジョブファイルを基準に解決したパス）
+    pub dest_dir: String,
+    pub success_count: usize,
+    pub failure_count: usize,
+    /// アイテムごとの結果（ジョブファイルの順序）
+    pub results: Vec<JobItemResult>,
+}
+
+impl JobFile {
+    /// 内容を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != JOB_VERSION {
+            return Err(format!("Unsupported job file version: {}", self.version));
+        }
+        if self.items.is_empty() {
+            return Err("Job file must contain at least one item".to_string());
+        }
+        if self.dest_dir.trim().is_empty() {
+            return Err("destDir must not be empty".to_string());
+        }
+        if self.asset_types.is_empty() {
+            return Err("assetTypes must not be empty".to_string());
+        }
+        if !(1..=MAX_CONCURRENCY).contains(&self.concurrency) {
+            return Err(format!(
+                "concurrency must be between 1 and {}",
+                MAX_CONCURRENCY
+            ));
+        }
+        if let Some(template) = &self.filename_template {
+            if template.trim().is_empty() {
+                return Err("filenameTemplate must not be empty".to_string());
+            }
+            if template.contains(['/', '\\']) {
+                return Err("filenameTemplate must not contain path separators".to_string());
+            }
+            filename::validate(template)?;
+        }
+        Ok(())
+    }
+}
+
+/// ジョブファイルを読み込む
+///
+/// 検証し、相対パスの保存先ディレクトリはジョブファイルのディレクトリを基準に解決する。
+pub fn load(path: &Path) -> Result<JobFile, String> {
+    let contents = std::fs::read(path).map_err(|e| format!("Failed to read job file: {}", e))?;
+    let mut job: JobFile =
+        serde_json::from_slice(&contents).map_err(|e| format!("Invalid job file: {}", e))?;
+    job.validate()?;
+
+    if Path::new(&job.dest_dir).is_relative() {
+        let base = path.parent().unwrap_or(Path::new("."));
+        job.dest_dir = base.join(&job.dest_dir).to_string_lossy().into_owned();
+    }
+    Ok(job)
+}
+
+/// ジョブを実行
+///
+/// `concurrency` 件ずつ並行してダウンロードし、アイテムが完了するたびに `on_item` を呼ぶ
+/// （呼び出し順はジョブファイルの順序）。
+pub async fn run(
+    job: &JobFile,
+    registry: &ProviderRegistry,
+    mut on_item: impl FnMut(&BatchDownloadItem, &JobItemResult),
+) -> Result<JobResult, String> {
+    std::fs::create_dir_all(crate::longpath::extended(filename::dir_prefix(
+        &job.dest_dir,
+    )))
+    .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let destination = DestinationSettings {
+        asset_subdirs: job.asset_subdirs,
+        overwrite_existing: job.overwrite_existing,
+        ..DestinationSettings::default()
+    };
+    let filename_options = FilenameOptions {
+        template: job.filename_template.clone(),
+        halfwidth_alphanumerics: false,
+        reserved: None,
+    };
+    let client = client_builder()
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    registry.begin_batch();
+    let mut downloads = futures::stream::iter(0..job.items.len())
+        .map(|i| {
+            let item = &job.items[i];
+            let destination = &destination;
+            let filename_options = &filename_options;
+            let client = &client;
+            async move {
+                let providers =
+                    registry.get_providers_for(&item.manufacturer, item.provider.as_deref());
+                let asset_types = item.asset_types.as_deref().unwrap_or(&job.asset_types);
+                let assets = commands::download_item_assets(
+                    &providers,
+                    item,
+                    asset_types,
+                    &job.dest_dir,
+                    destination,
+                    filename_options,
+                    client,
+                    CancelToken::new(),
+                )
+                .await;
+                let result = JobItemResult {
+                    spec_no: item.spec_no.clone(),
+                    model_number: item.model_number.clone(),
+                    success: !assets.is_empty() && assets.iter().all(|a| a.result.success),
+                    assets,
+                };
+                (item, result)
+            }
+        })
+        .buffered(job.concurrency);
+
+    let mut results = Vec::new();
+    while let Some((item, result)) = downloads.next().await {
+        on_item(item, &result);
+        results.push(result);
+    }
+    registry.end_batch();
+
+    let success_count = results.iter().filter(|r| r.success).count();
+    Ok(JobResult {
+        dest_dir: job.dest_dir.clone(),
+        success_count,
+        failure_count: results.len() - success_count,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("job.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "destDir": "ies",
+                "assetTypes": ["ies", "specSheet"],
+                "concurrency": 2,
+                "items": [
+                    { "specNo": "1001", "manufacturer": "KOIZUMI", "modelNumber": "AD12345" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let job = load(&path).unwrap();
+        assert_eq!(job.version, JOB_VERSION);
+        assert_eq!(Path::new(&job.dest_dir), dir.path().join("ies"));
+        assert_eq!(job.asset_types, vec![AssetType::Ies, AssetType::SpecSheet]);
+        assert_eq!(job.items[0].model_number, "AD12345");
+        assert!(job.overwrite_existing);
+
+        // 不明なキー・範囲外の値はエラー
+        std::fs::write(&path, r#"{ "destDir": "ies", "items": [], "unknown": 1 }"#).unwrap();
+        assert!(load(&path).is_err());
+        std::fs::write(
+            &path,
+            r#"{ "destDir": "ies", "concurrency": 0,
+                 "items": [{ "specNo": "1", "manufacturer": "K", "modelNumber": "M" }] }"#,
+        )
+        .unwrap();
+        assert!(load(&path).unwrap_err().contains("concurrency"));
+    }
+}
@@ -1,27 +1,338 @@
+mod api_server;
+mod audit;
+mod batch;
+mod buffer;
+mod cache;
+mod cassette;
+mod checkpoint;
+pub mod cli;
+mod cloud;
 mod commands;
+mod config_transfer;
+mod crash;
+mod dedupe;
+mod deeplink;
+mod diagnostics;
+mod direct;
+mod download_cache;
+mod error;
+mod error_reporting;
+mod excel;
+mod filename;
+mod history;
+mod i18n;
+mod ignore_list;
+mod job;
+mod library;
+mod lighting_export;
+mod logging;
+mod longpath;
+mod manual_source;
+mod manufacturer_alias;
+mod network_share;
+mod offline;
+mod photometry;
+mod portable;
+mod power;
+mod prefetch;
 mod providers;
+mod report;
+mod rules_update;
+mod schedule_report;
+mod scrub;
+mod secrets;
+mod session;
+mod settings;
+mod storage;
+mod succession;
+mod suggest;
+mod telemetry;
+mod thumbnail;
+#[cfg(desktop)]
+mod tray;
+mod writeback;
 
-use providers::ProviderRegistry;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use providers::{ProviderRegistry, SharedRegistry};
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // プロバイダーレジストリを初期化
-    let registry = Arc::new(Mutex::new(ProviderRegistry::new()));
+    let registry = SharedRegistry::new(ProviderRegistry::new());
 
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+    // 2つ目の起動（ディープリンクから開いた場合を含む）は既存のウィンドウで処理する
+    // ディープリンクのURLは既存のプロセスの `on_open_url` に渡される
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            tray::show_window(app);
+        }))
+        // トレイに常駐する設定では、ウィンドウを閉じても一括ダウンロードを続ける
+        .on_window_event(tray::on_window_event);
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_autosight_background::init())
+        .plugin(tauri_plugin_autosight_share::init())
+        .setup(|app| {
+            // パニック時にクラッシュレポートを書き出す
+            if let Ok(crash_dir) = commands::crash_dir(app.handle()) {
+                crash::install_panic_hook(crash_dir, app.package_info().version.to_string());
+            }
+
+            // オフラインモードの設定を反映
+            offline::apply(
+                app.handle(),
+                settings::load(app.handle()).is_ok_and(|s| s.offline),
+            );
+
+            // ドメインごとの同時リクエスト数の上限を反映
+            providers::apply_domain_concurrency(
+                &settings::load(app.handle())
+                    .unwrap_or_default()
+                    .domain_concurrency,
+            );
+            providers::apply_domain_request_interval(
+                &settings::load(app.handle())
+                    .unwrap_or_default()
+                    .domain_request_interval_ms,
+            );
+
+            // プロキシ・TLSの設定を反映（プロバイダーを作り直す前に）
+            let network = settings::load(app.handle()).unwrap_or_default();
+            if let Err(e) = providers::apply_network_settings(network.proxy.as_ref(), &network.tls)
+            {
+                tracing::warn!(error = %e, "failed to apply network settings");
+            }
+
+            // リクエストのタイムアウトの既定値を反映（プロバイダーを作り直す前に）
+            providers::apply_request_timeout(
+                settings::load(app.handle())
+                    .unwrap_or_default()
+                    .request_timeout_secs,
+            );
+
+            // プロバイダーの接続設定（ベースURL・User-Agent等）を反映
+            if let Err(e) = providers::apply_provider_config(
+                &app.state::<SharedRegistry>(),
+                &settings::load(app.handle()).unwrap_or_default().providers,
+            ) {
+                tracing::warn!(error = %e, "failed to apply provider settings");
+            }
+
+            // 定義ファイル・プロバイダールールのプロバイダー（カスタムプロバイダー）を登録
+            match commands::reload_providers(app.handle()) {
+                Ok(result) => {
+                    for error in &result.errors {
+                        tracing::warn!(
+                            file = %error.file,
+                            error = %error.error,
+                            "failed to load custom provider"
+                        );
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "failed to load custom providers"),
+            }
+
+            // 再試行の設定を反映
+            providers::apply_retry_settings(
+                &settings::load(app.handle()).unwrap_or_default().retry,
+            );
+
+            // 後継品の対応表を読み込む
+            succession::load(app.handle());
+
+            // メーカー名の別名表を反映
+            if let Err(e) = manufacturer_alias::apply(
+                &app.state::<SharedRegistry>(),
+                &manufacturer_alias::load(app.handle()),
+            ) {
+                tracing::warn!(error = %e, "failed to apply manufacturer aliases");
+            }
+
+            // 通信の記録・再生の設定を反映
+            cassette::apply(
+                app.handle(),
+                &settings::load(app.handle()).unwrap_or_default().cassette,
+            );
+            if let Err(e) = providers::apply_cassette_mode(&app.state::<SharedRegistry>()) {
+                tracing::warn!(error = %e, "failed to apply cassette mode");
+            }
+
+            // TOKISTARのIES ZIPをキャッシュディレクトリに保存して再利用する
+            if let Ok(cache_dir) = portable::cache_dir(app.handle()) {
+                providers::tokistar::set_zip_cache_dir(&cache_dir);
+            }
+
+            // レート制限等による待機を `download-backoff` イベントで通知
+            let handle = app.handle().clone();
+            providers::set_backoff_notifier(move |event| {
+                let _ = handle.emit("download-backoff", event);
+            });
+
+            // ログ出力を初期化（失敗してもアプリは起動する）
+            let level = settings::load(app.handle())
+                .map(|s| s.log_level)
+                .unwrap_or_default();
+            match portable::log_dir(app.handle()).and_then(|dir| logging::init(&dir, level)) {
+                Ok(logging) => {
+                    app.manage(logging);
+                    tracing::info!(version = %app.package_info().version, "started");
+                }
+                Err(e) => eprintln!("Failed to initialize logging: {}", e),
+            }
+
+            // エラー報告が有効なら、前回の実行中に発生したパニックを送信
+            commands::apply_error_reporting(
+                app.handle(),
+                &settings::load(app.handle()).unwrap_or_default(),
+            );
+            if let Ok(crash_dir) = commands::crash_dir(app.handle()) {
+                error_reporting::report_pending_crashes(&crash_dir);
+            }
+
+            // システムトレイ（作成できなくてもアプリは起動する）
+            #[cfg(desktop)]
+            if let Err(e) = tray::init(app.handle()) {
+                tracing::warn!(error = %e, "failed to create tray icon");
+            }
+
+            // プロバイダールールの更新を定期的に確認
+            commands::spawn_rules_updater(app.handle().clone());
+
+            // ローカルHTTP APIサーバーが有効なら起動
+            api_server::apply(
+                app.handle(),
+                &settings::load(app.handle()).unwrap_or_default().api_server,
+            );
+
+            // ディープリンク（autosight://）で開かれたアイテムをダウンロード待ちに追加
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            if let Err(e) = app.deep_link().register_all() {
+                tracing::warn!(error = %e, "failed to register deep link scheme");
+            }
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                commands::handle_deep_links(app.handle(), urls.iter().map(|url| url.as_str()));
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                commands::handle_deep_links(&handle, event.urls().iter().map(|url| url.as_str()));
+            });
+            Ok(())
+        })
         .manage(registry)
+        .manage(batch::BatchState::new())
+        .manage(prefetch::PrefetchState::default())
+        .manage(deeplink::DeepLinkState::default())
+        .manage(api_server::ApiServerState::default())
         .invoke_handler(tauri::generate_handler![
             commands::get_supported_manufacturers,
+            commands::list_providers,
+            commands::set_provider_enabled,
+            commands::list_manufacturer_aliases,
+            commands::add_manufacturer_alias,
+            commands::get_provider_settings,
+            commands::update_provider_settings,
+            commands::get_network_settings,
+            commands::update_network_settings,
+            commands::test_provider_connection,
+            commands::reload_custom_providers,
+            commands::validate_provider_definition,
+            commands::create_diagnostics_bundle,
+            commands::get_recent_logs,
+            commands::get_crash_reports,
+            commands::export_crash_report,
+            commands::dismiss_crash_report,
+            commands::import_excel,
+            commands::parse_clipboard_items,
+            commands::ingest_dropped_files,
+            commands::get_library_entries,
+            commands::search_library,
+            commands::suggest,
             commands::fetch_product_info,
+            commands::fetch_product_info_batch,
+            commands::prefetch_product_info,
+            commands::cancel_prefetch,
+            commands::prepare_offline,
+            commands::fetch_prices,
+            commands::search_products,
+            commands::fetch_thumbnail,
+            commands::resolve_ies_url,
+            commands::list_zip_candidates,
             commands::download_ies_file,
+            commands::resolve_item,
+            commands::download_from_url,
+            commands::download_ies_from_url,
+            commands::get_manual_sources,
+            commands::remove_manual_source,
+            commands::estimate_batch,
+            commands::check_availability,
             commands::batch_download_ies_files,
+            commands::retry_failed_items,
+            commands::get_interrupted_batches,
+            commands::resume_interrupted_batch,
+            commands::discard_interrupted_batch,
+            commands::get_ignore_list,
+            commands::add_to_ignore_list,
+            commands::remove_from_ignore_list,
+            commands::batch_download_assets,
+            commands::batch_download_asset_bundle,
+            commands::run_job_file,
+            commands::cancel_item,
+            commands::resolve_item_choice,
+            commands::pause_batch,
+            commands::resume_batch,
+            commands::cancel_batch,
+            commands::get_batch_status,
+            commands::list_batches,
+            commands::restore_last_session,
+            commands::clear_last_session,
+            commands::take_deep_link_items,
+            commands::open_downloaded_file,
+            commands::reveal_in_folder,
+            commands::get_download_history,
+            commands::search_history,
+            commands::export_history_csv,
+            commands::export_report,
+            commands::export_batch_report,
+            commands::export_schedule_summary,
+            commands::analyze_ies,
+            commands::analyze_ies_file,
+            commands::convert_photometry,
+            commands::cone_diagram,
+            commands::export_lighting_project,
+            commands::upload_to_cloud,
+            commands::set_cloud_token,
+            commands::get_cloud_connections,
+            commands::set_network_share_password,
+            commands::test_network_share,
+            commands::export_downloaded_file,
+            commands::share_downloaded_file,
+            commands::rename_downloaded_files,
+            commands::get_settings,
+            commands::update_settings,
+            commands::export_config,
+            commands::import_config,
+            commands::set_log_level,
+            commands::get_rules_status,
+            commands::check_rules_update,
+            commands::rollback_rules,
+            commands::get_usage_report,
+            commands::get_cache_stats,
+            commands::clear_cache,
+            commands::clear_download_cache,
+            commands::get_power_status,
+            commands::get_portable_info,
+            commands::set_portable_mode,
+            commands::get_store_path,
             commands::is_manufacturer_supported,
         ])
         .run(tauri::generate_context!())
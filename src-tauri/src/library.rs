@@ -0,0 +1,305 @@
+//! IESライブラリ
+//!
+//! ドラッグ＆ドロップ等で取り込んだIESファイルをアプリのデータディレクトリに保管し、
+//! ヘッダーのキーワード（`[MANUFAC]` `[LUMCAT]` 等）とあわせて一覧できるようにする。
+//! 同じ内容のファイル（SHA-256が一致）は重複して登録しない。
+//!
+//! 登録時にキーワード（`[LAMPCAT]` `[MANUFAC]` 等）と元ファイル名から検索語を作成し、
+//! 「3000K 15度 Koizumi」のような空白区切りの検索ができるようにする。
+//! 色温度（`3000K` `3000 K`）・角度（`15度` `15°` `15deg`）は表記を揃えて照合する。
+//!
+//! 形式: {ライブラリディレクトリ}/index.json（登録内容）・{SHA-256の先頭12桁}_{元ファイル名}
+
+use crate::filename::sanitize_filename;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::LazyLock;
+use unicode_normalization::UnicodeNormalization;
+
+/// 登録内容のファイル名
+const INDEX_FILE: &str = "index.json";
+
+/// 色温度（`3000K` `3000 K`）
+static CCT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d{4,5})\s*k\b").unwrap());
+/// 角度（`15度` `15°` `15 deg`）
+static ANGLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{1,3}(?:\.\d+)?)\s*(?:度|°|deg(?:rees?)?\b)").unwrap());
+
+/// ライブラリに登録したIESファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    /// ファイルのSHA-256（16進数。登録の識別子を兼ねる）
+    pub sha256: String,
+    /// 取り込んだときの元ファイル名
+    pub file_name: String,
+    /// ライブラリ内の保存先パス
+    pub path: String,
+    /// ファイルサイズ（バイト）
+    pub size: u64,
+    /// ヘッダーのキーワード（`[MANUFAC]` → メーカー名 等。キーは大文字）
+    pub keywords: BTreeMap<String, String>,
+    /// 検索語（キーワードの値・元ファイル名から作成。表記を揃えた小文字）
+    #[serde(default)]
+    pub search_terms: Vec<String>,
+    /// 登録日時
+    pub added_at: DateTime<Utc>,
+}
+
+/// IESファイルのヘッダーからキーワードを取得
+///
+/// `TILT=` 行より前の `[KEYWORD] 値` の行を対象とする。
+/// 同じキーワードが複数行ある場合（`[MORE]` 等）は空白で連結する。
+pub fn parse_keywords(text: &str) -> BTreeMap<String, String> {
+    let mut keywords: BTreeMap<String, String> = BTreeMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("TILT=") {
+            break;
+        }
+        let Some((key, value)) = line.strip_prefix('[').and_then(|rest| rest.split_once(']'))
+        else {
+            continue;
+        };
+        let value = value.trim();
+        if key.is_empty() || value.is_empty() {
+            continue;
+        }
+        keywords
+            .entry(key.trim().to_uppercase())
+            .and_modify(|existing| {
+                existing.push(' ');
+                existing.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    keywords
+}
+
+/// 検索語・検索条件の表記を揃える
+///
+/// 全角英数字を半角・小文字にし、色温度は `3000k`、角度は `15deg` の形にする。
+fn normalize(text: &str) -> String {
+    let text = text.nfkc().collect::<String>().to_lowercase();
+    let text = CCT_RE.replace_all(&text, " ${1}k ");
+    ANGLE_RE.replace_all(&text, " ${1}deg ").into_owned()
+}
+
+/// 空白・記号で区切った語
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | '(' | ')' | '[' | ']'))
+        .filter(|token| !token.is_empty())
+}
+
+/// キーワードの値・元ファイル名から検索語を作成
+pub fn index_terms(file_name: &str, keywords: &BTreeMap<String, String>) -> Vec<String> {
+    let stem = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem);
+    let mut terms = BTreeSet::new();
+    for text in keywords.values().map(String::as_str).chain([stem]) {
+        let text = normalize(text);
+        for token in tokens(&text) {
+            terms.insert(token.to_string());
+            // 型番・ファイル名の区切り（`AD12345-3000K` 等）でも一致させる
+            terms.extend(
+                token
+                    .split(['-', '_'])
+                    .filter(|part| !part.is_empty() && *part != token)
+                    .map(str::to_string),
+            );
+        }
+    }
+    terms.into_iter().collect()
+}
+
+impl LibraryEntry {
+    /// 検索語（検索語を持たない以前の登録内容はキーワードから作成）
+    fn terms(&self) -> Vec<String> {
+        if self.search_terms.is_empty() {
+            index_terms(&self.file_name, &self.keywords)
+        } else {
+            self.search_terms.clone()
+        }
+    }
+}
+
+/// 検索条件に一致する登録内容を探す（後から登録したものを先に返す）
+///
+/// 検索条件を空白で区切ったすべての語が、いずれかの検索語に含まれるものを返す
+/// （大文字・小文字、全角・半角は区別しない）。空の検索条件はすべてに一致する。
+pub fn search<'a>(entries: &'a [LibraryEntry], query: &str) -> Vec<&'a LibraryEntry> {
+    let query = normalize(query);
+    let words: Vec<&str> = tokens(&query).collect();
+    entries
+        .iter()
+        .rev()
+        .filter(|entry| {
+            let terms = entry.terms();
+            words
+                .iter()
+                .all(|word| terms.iter().any(|term| term.contains(word)))
+        })
+        .collect()
+}
+
+/// 登録内容を読み込む（ライブラリが未作成の場合は空）
+pub fn load(library_dir: &Path) -> Result<Vec<LibraryEntry>, String> {
+    let path = library_dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        std::fs::read(&path).map_err(|e| format!("Failed to read IES library: {}", e))?;
+    serde_json::from_slice(&contents).map_err(|e| format!("Failed to parse IES library: {}", e))
+}
+
+fn save(library_dir: &Path, entries: &[LibraryEntry]) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(entries)
+        .map_err(|e| format!("Failed to serialize IES library: {}", e))?;
+    std::fs::write(library_dir.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to save IES library: {}", e))
+}
+
+/// IESファイルをライブラリに登録
+///
+/// 戻り値: (登録内容, 新しく登録したか)。登録済みの内容と同じファイルは登録済みのものを返す。
+pub fn ingest(library_dir: &Path, source: &Path) -> Result<(LibraryEntry, bool), String> {
+    let bytes = std::fs::read(source).map_err(|e| format!("Failed to read IES file: {}", e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    if !text.contains("TILT=") {
+        return Err(format!(
+            "Not a valid IES file (TILT= line not found): {}",
+            source.display()
+        ));
+    }
+
+    let sha256 = format!("{:x}", Sha256::digest(&bytes));
+    let mut entries = load(library_dir)?;
+    if let Some(entry) = entries.iter().find(|entry| entry.sha256 == sha256) {
+        return Ok((entry.clone(), false));
+    }
+
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "fixture.ies".to_string());
+    std::fs::create_dir_all(library_dir)
+        .map_err(|e| format!("Failed to create IES library: {}", e))?;
+    let dest = library_dir.join(sanitize_filename(&format!(
+        "{}_{}",
+        &sha256[..12],
+        file_name
+    )));
+    std::fs::write(&dest, &bytes).map_err(|e| format!("Failed to save IES file: {}", e))?;
+
+    let keywords = parse_keywords(&text);
+    let entry = LibraryEntry {
+        sha256,
+        search_terms: index_terms(&file_name, &keywords),
+        file_name,
+        path: dest.to_string_lossy().into_owned(),
+        size: bytes.len() as u64,
+        keywords,
+        added_at: Utc::now(),
+    };
+    entries.push(entry.clone());
+    save(library_dir, &entries)?;
+    Ok((entry, true))
+}
+
+/// 型番に一致するIESファイルを探す
+///
+/// ヘッダーの `[LUMCAT]`（カタログ番号）が型番と一致するもの、なければ元ファイル名が
+/// 型番で始まるものを返す（大文字・小文字は区別しない）。後から登録したものを優先する。
+pub fn find<'a>(entries: &'a [LibraryEntry], model_number: &str) -> Option<&'a LibraryEntry> {
+    let model_number = model_number.trim().to_uppercase();
+    if model_number.is_empty() {
+        return None;
+    }
+    entries
+        .iter()
+        .rev()
+        .find(|entry| {
+            entry
+                .keywords
+                .get("LUMCAT")
+                .is_some_and(|lumcat| lumcat.trim().to_uppercase() == model_number)
+        })
+        .or_else(|| {
+            entries
+                .iter()
+                .rev()
+                .find(|entry| entry.file_name.to_uppercase().starts_with(&model_number))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IES: &str = "IESNA:LM-63-2002\r\n[TEST] 12345\r\n[MANUFAC] KOIZUMI\r\n\
+        [LUMCAT] AD12345\r\n[LUMINAIRE] ダウンライト\r\n[MORE] 3000K\r\n[MORE] 15度\r\n\
+        TILT=NONE\r\n[AFTER] ignored\r\n";
+
+    #[test]
+    fn test_parse_keywords() {
+        let keywords = parse_keywords(IES);
+        assert_eq!(keywords["MANUFAC"], "KOIZUMI");
+        assert_eq!(keywords["LUMCAT"], "AD12345");
+        assert_eq!(keywords["MORE"], "3000K 15度");
+        assert!(!keywords.contains_key("AFTER"));
+    }
+
+    #[test]
+    fn test_search() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("AD12345-W.ies");
+        std::fs::write(&source, IES).unwrap();
+        let library_dir = temp.path().join("library");
+        let (entry, _) = ingest(&library_dir, &source).unwrap();
+        assert!(entry.search_terms.contains(&"3000k".to_string()));
+        assert!(entry.search_terms.contains(&"15deg".to_string()));
+
+        let entries = load(&library_dir).unwrap();
+        assert_eq!(search(&entries, "3000K 15度 Koizumi").len(), 1);
+        assert_eq!(search(&entries, "３０００ｋ 15°").len(), 1);
+        assert_eq!(search(&entries, "ad12345").len(), 1);
+        assert_eq!(search(&entries, "4000K koizumi").len(), 0);
+        assert_eq!(search(&entries, "").len(), 1);
+    }
+
+    #[test]
+    fn test_ingest() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("AD12345.ies");
+        std::fs::write(&source, IES).unwrap();
+        let library_dir = temp.path().join("library");
+
+        let (entry, added) = ingest(&library_dir, &source).unwrap();
+        assert!(added);
+        assert_eq!(entry.file_name, "AD12345.ies");
+        assert!(Path::new(&entry.path).exists());
+
+        // 同じ内容のファイルは重複して登録しない
+        let (again, added) = ingest(&library_dir, &source).unwrap();
+        assert!(!added);
+        assert_eq!(again.path, entry.path);
+        assert_eq!(load(&library_dir).unwrap().len(), 1);
+
+        let entries = load(&library_dir).unwrap();
+        assert_eq!(
+            find(&entries, "ad12345").map(|e| &e.sha256),
+            Some(&entry.sha256)
+        );
+        assert!(find(&entries, "AD99999").is_none());
+
+        let invalid = temp.path().join("notes.ies");
+        std::fs::write(&invalid, "not an ies file").unwrap();
+        assert!(ingest(&library_dir, &invalid).is_err());
+    }
+}
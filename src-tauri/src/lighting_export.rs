@@ -0,0 +1,406 @@
+//! 照明計算ソフト（DIALux evo・Relux・AGi32）向けのプロジェクト書き出し
+//!
+//! ダウンロード済みの配光データを、照明計算ソフトで一括インポートしやすいフォルダ構成に
+//! まとめ直し、一覧（index.csv）を作成する。ダウンロード後に手作業で並べ替える手間をなくすためのもの。
+//!
+//! 形式: {書き出し先}/{メーカー}/{Spec No.}_{型番}.ies（DIALux evo）・.ldt（Relux）
+//! - DIALux evo: IESファイルをそのままコピーする
+//! - Relux: EULUMDAT（LDT）に変換する。変換できない配光データはIESのままコピーし、一覧に理由を記録する
+//! - AGi32: {書き出し先}/{ラベル}.ies に平置きし、器具表（agi32_schedule.csv）を作成する。
+//!   ラベル（ファイル名）はAGi32の制約にあわせ、半角英数字・`-`・`_` のみ、32文字までとする
+//!
+//! 設定の `scrub_ies_headers` が有効な場合は、IESファイルのヘッダーから社内のパス・測定者名等を
+//! 取り除いてから書き出す（施主等への納品用。配光データは変更しない）。
+
+use crate::filename::sanitize_filename;
+use crate::history::HistoryEntry;
+use crate::i18n::{Locale, Message};
+use crate::longpath;
+use crate::photometry;
+use crate::providers::AssetType;
+use crate::report::csv_field;
+use crate::scrub;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// 一覧のファイル名
+const INDEX_FILE: &str = "index.csv";
+/// AGi32の器具表のファイル名
+const AGI32_SCHEDULE_FILE: &str = "agi32_schedule.csv";
+/// AGi32のラベル（ファイル名の拡張子を除いた部分）の最大文字数
+const AGI32_MAX_LABEL_CHARS: usize = 32;
+
+/// 書き出し先の照明計算ソフト
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportTarget {
+    DialuxEvo,
+    Relux,
+    Agi32,
+}
+
+/// 書き出したファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFile {
+    pub spec_no: String,
+    pub manufacturer: String,
+    pub model_number: String,
+    /// AGi32のラベル（AGi32向けのみ）
+    pub label: Option<String>,
+    /// 書き出したファイルのパス（失敗時は None）
+    pub path: Option<String>,
+    /// LDTへの変換に失敗した・ファイルがない等の理由
+    pub note: Option<String>,
+}
+
+/// 書き出し結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectExportResult {
+    /// 一覧（index.csv）のパス
+    pub index_path: String,
+    /// AGi32の器具表のパス（AGi32向けのみ）
+    pub schedule_path: Option<String>,
+    pub files: Vec<ExportedFile>,
+}
+
+/// ダウンロード済みのIESファイルを書き出す
+///
+/// Spec No.ごとに最新の成功したIESの履歴を対象とする。
+/// `scrub_headers` が true の場合はIESファイルのヘッダーから内部情報を取り除く。
+pub fn export(
+    entries: &[HistoryEntry],
+    dest_dir: &Path,
+    target: ExportTarget,
+    locale: Locale,
+    scrub_headers: bool,
+) -> Result<ProjectExportResult, String> {
+    let mut latest: BTreeMap<&str, &HistoryEntry> = BTreeMap::new();
+    for entry in entries {
+        if entry.success && entry.asset_type == AssetType::Ies && entry.file_path.is_some() {
+            latest.insert(&entry.spec_no, entry);
+        }
+    }
+    std::fs::create_dir_all(longpath::extended(dest_dir))
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let mut labels = HashSet::new();
+    let files: Vec<_> = latest
+        .values()
+        .map(|entry| {
+            let label = (target == ExportTarget::Agi32)
+                .then(|| unique_label(&agi32_label(&entry.spec_no), &mut labels));
+            export_entry(entry, dest_dir, target, label, scrub_headers)
+        })
+        .collect();
+
+    let index_path = dest_dir.join(INDEX_FILE);
+    std::fs::write(
+        longpath::extended(&index_path),
+        render_index(&files, dest_dir, locale),
+    )
+    .map_err(|e| format!("Failed to write export index: {}", e))?;
+
+    let schedule_path = if target == ExportTarget::Agi32 {
+        let path = dest_dir.join(AGI32_SCHEDULE_FILE);
+        std::fs::write(longpath::extended(&path), render_agi32_schedule(&files))
+            .map_err(|e| format!("Failed to write AGi32 schedule: {}", e))?;
+        Some(path.to_string_lossy().into_owned())
+    } else {
+        None
+    };
+    Ok(ProjectExportResult {
+        index_path: index_path.to_string_lossy().into_owned(),
+        schedule_path,
+        files,
+    })
+}
+
+fn export_entry(
+    entry: &HistoryEntry,
+    dest_dir: &Path,
+    target: ExportTarget,
+    label: Option<String>,
+    scrub_headers: bool,
+) -> ExportedFile {
+    let mut file = ExportedFile {
+        spec_no: entry.spec_no.clone(),
+        manufacturer: entry.manufacturer.clone(),
+        model_number: entry.model_number.clone(),
+        label,
+        path: None,
+        note: None,
+    };
+    let source = entry.file_path.as_deref().unwrap_or_default();
+    let bytes = match std::fs::read(longpath::extended(source)) {
+        Ok(bytes) if scrub_headers => scrub::scrub_ies_header(&bytes),
+        Ok(bytes) => bytes,
+        Err(e) => {
+            file.note = Some(format!("Failed to read IES file: {}", e));
+            return file;
+        }
+    };
+
+    let (dir, stem) = match &file.label {
+        Some(label) => (dest_dir.to_path_buf(), label.clone()),
+        None => (
+            dest_dir.join(sanitize_filename(&entry.manufacturer)),
+            format!("{}_{}", entry.spec_no, entry.model_number),
+        ),
+    };
+    let (extension, contents) = match target {
+        ExportTarget::DialuxEvo | ExportTarget::Agi32 => ("ies", bytes),
+        ExportTarget::Relux => {
+            let name = sanitize_filename(&format!("{}.ldt", stem));
+            let parsed = photometry::parse_ies(&String::from_utf8_lossy(&bytes));
+            match parsed {
+                Ok(ies) => (
+                    "ldt",
+                    ies.to_eulumdat(&entry.model_number, &name).into_bytes(),
+                ),
+                Err(e) => {
+                    file.note = Some(format!("LDT conversion failed: {}", e));
+                    ("ies", bytes)
+                }
+            }
+        }
+    };
+
+    let dest = dir.join(sanitize_filename(&format!("{}.{}", stem, extension)));
+    let written = std::fs::create_dir_all(longpath::extended(&dir))
+        .and_then(|_| std::fs::write(longpath::extended(&dest), contents));
+    match written {
+        Ok(()) => file.path = Some(dest.to_string_lossy().into_owned()),
+        Err(e) => file.note = Some(format!("Failed to write file: {}", e)),
+    }
+    file
+}
+
+/// Spec No.からAGi32のラベルを生成（使用できない文字は `_` に置き換え、32文字までに切り詰める）
+fn agi32_label(spec_no: &str) -> String {
+    let mut label = String::new();
+    for c in spec_no.trim().chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            c
+        } else {
+            '_'
+        };
+        // `_` は連続させない
+        if !(c == '_' && label.ends_with('_')) {
+            label.push(c);
+        }
+    }
+    let label: String = label
+        .trim_matches('_')
+        .chars()
+        .take(AGI32_MAX_LABEL_CHARS)
+        .collect();
+    if label.is_empty() {
+        "LUM".to_string()
+    } else {
+        label
+    }
+}
+
+/// 他と重複しないラベル（重複する場合は末尾に連番を付ける）
+fn unique_label(label: &str, used: &mut HashSet<String>) -> String {
+    let mut candidate = label.to_string();
+    let mut n = 2;
+    while !used.insert(candidate.to_ascii_uppercase()) {
+        let suffix = format!("_{}", n);
+        let base: String = label
+            .chars()
+            .take(AGI32_MAX_LABEL_CHARS - suffix.len())
+            .collect();
+        candidate = format!("{}{}", base, suffix);
+        n += 1;
+    }
+    candidate
+}
+
+/// AGi32の器具表（Label・Filename・Manufacturer・Catalog・Description・Lumens・Watts・LLF）
+///
+/// AGi32の器具表インポートに合わせ、BOMなしのASCII・CRLF改行で出力する。
+/// 光束・電力は配光データから求める（読み込めない場合は空欄）。
+fn render_agi32_schedule(files: &[ExportedFile]) -> String {
+    let mut out =
+        String::from("Label,Filename,Manufacturer,Catalog,Description,Lumens,Watts,LLF\r\n");
+    for file in files {
+        let (Some(label), Some(path)) = (&file.label, &file.path) else {
+            continue;
+        };
+        let photometry = std::fs::read(longpath::extended(path))
+            .ok()
+            .and_then(|bytes| photometry::parse_ies(&String::from_utf8_lossy(&bytes)).ok());
+        let (lumens, watts) = photometry
+            .map(|ies| {
+                (
+                    format!("{:.0}", ies.luminaire_lumens()),
+                    format!("{}", ies.input_watts),
+                )
+            })
+            .unwrap_or_default();
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let fields = [
+            label.as_str(),
+            file_name.as_str(),
+            file.manufacturer.as_str(),
+            file.model_number.as_str(),
+            file.spec_no.as_str(),
+            lumens.as_str(),
+            watts.as_str(),
+            "1",
+        ];
+        let fields: Vec<_> = fields.iter().map(|v| csv_field(v)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// 一覧（BOM付きUTF-8のCSV、パスは書き出し先からの相対パス）
+fn render_index(files: &[ExportedFile], dest_dir: &Path, locale: Locale) -> String {
+    let mut out = String::from("\u{feff}");
+    let columns = [
+        "Spec No.".to_string(),
+        Message::ColumnManufacturer.text(locale),
+        Message::ColumnModel.text(locale),
+        Message::ColumnFile.text(locale),
+        Message::ColumnError.text(locale),
+    ];
+    out.push_str(&columns.join(","));
+    out.push_str("\r\n");
+    for file in files {
+        let path = file
+            .path
+            .as_deref()
+            .map(|path| {
+                Path::new(path)
+                    .strip_prefix(dest_dir)
+                    .unwrap_or(Path::new(path))
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .unwrap_or_default();
+        let fields = [
+            file.spec_no.as_str(),
+            file.manufacturer.as_str(),
+            file.model_number.as_str(),
+            path.as_str(),
+            file.note.as_deref().unwrap_or_default(),
+        ];
+        let fields: Vec<_> = fields.iter().map(|v| csv_field(v)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IES: &str = "IESNA:LM-63-2002\r\n[MANUFAC] KOIZUMI\r\nTILT=NONE\r\n\
+        1 1000 1 3 1 1 2 0.1 0.1 0\r\n1 1 10\r\n0 45 90\r\n0\r\n300 200 0\r\n";
+
+    fn entry(spec_no: &str, path: &Path) -> HistoryEntry {
+        HistoryEntry {
+            project_id: None,
+            spec_no: spec_no.to_string(),
+            manufacturer: "KOIZUMI".to_string(),
+            model_number: "AD12345".to_string(),
+            psu: None,
+            asset_type: AssetType::Ies,
+            success: true,
+            file_path: Some(path.to_string_lossy().into_owned()),
+            original_filename: None,
+            sha256: None,
+            source: None,
+            provider: None,
+            error: None,
+            downloaded_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_export() {
+        let temp = tempfile::tempdir().unwrap();
+        let valid = temp.path().join("A01.ies");
+        let invalid = temp.path().join("A02.ies");
+        std::fs::write(&valid, IES).unwrap();
+        std::fs::write(&invalid, "IESNA:LM-63-2002\r\nTILT=NONE\r\n1 1000").unwrap();
+        let entries = [entry("A01", &valid), entry("A02", &invalid)];
+
+        let dest = temp.path().join("relux");
+        let result = export(&entries, &dest, ExportTarget::Relux, Locale::En, false).unwrap();
+        assert_eq!(result.files.len(), 2);
+        assert!(dest.join("KOIZUMI/A01_AD12345.ldt").exists());
+        // 変換できない配光データはIESのまま
+        assert!(dest.join("KOIZUMI/A02_AD12345.ies").exists());
+        assert!(result.files[1]
+            .note
+            .as_deref()
+            .unwrap()
+            .contains("LDT conversion failed"));
+
+        let index = std::fs::read_to_string(&result.index_path).unwrap();
+        let lines: Vec<_> = index.trim_start_matches('\u{feff}').split("\r\n").collect();
+        assert_eq!(lines[0], "Spec No.,Manufacturer,Model,File,Error");
+        assert_eq!(lines[1], "A01,KOIZUMI,AD12345,KOIZUMI/A01_AD12345.ldt,");
+
+        let dest = temp.path().join("dialux");
+        export(&entries, &dest, ExportTarget::DialuxEvo, Locale::En, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dest.join("KOIZUMI/A01_AD12345.ies")).unwrap(),
+            IES
+        );
+
+        // ヘッダーの内部情報を取り除いて書き出す
+        std::fs::write(&valid, IES.replace("TILT=", "[TESTER] Taro\r\nTILT=")).unwrap();
+        let dest = temp.path().join("scrubbed");
+        export(&entries, &dest, ExportTarget::DialuxEvo, Locale::En, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dest.join("KOIZUMI/A01_AD12345.ies")).unwrap(),
+            IES
+        );
+    }
+
+    #[test]
+    fn test_export_agi32() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("source.ies");
+        std::fs::write(&source, IES).unwrap();
+        let entries = [entry("L-1 (A)", &source), entry("L-1 [A]", &source)];
+
+        let dest = temp.path().join("agi32");
+        let result = export(&entries, &dest, ExportTarget::Agi32, Locale::En, false).unwrap();
+        let labels: Vec<_> = result
+            .files
+            .iter()
+            .map(|f| f.label.clone().unwrap())
+            .collect();
+        assert_eq!(labels, ["L-1_A", "L-1_A_2"]);
+        assert!(dest.join("L-1_A_2.ies").exists());
+
+        let schedule = std::fs::read_to_string(result.schedule_path.unwrap()).unwrap();
+        let lines: Vec<_> = schedule.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "Label,Filename,Manufacturer,Catalog,Description,Lumens,Watts,LLF"
+        );
+        assert!(lines[1].starts_with("L-1_A,L-1_A.ies,KOIZUMI,AD12345,L-1 (A),"));
+        assert!(lines[1].ends_with(",10,1"));
+    }
+
+    #[test]
+    fn test_agi32_label() {
+        assert_eq!(agi32_label("ダウンライト A-01"), "A-01");
+        assert_eq!(agi32_label("***"), "LUM");
+        assert_eq!(agi32_label(&"X".repeat(40)).len(), AGI32_MAX_LABEL_CHARS);
+    }
+}
@@ -0,0 +1,122 @@
+//! ログ出力
+//!
+//! `tracing` のイベントをアプリのログディレクトリに日付ごとのファイル
+//! （`autosight.YYYY-MM-DD.log`）として書き出す。古いファイルは一定数を超えると削除する。
+//! ログレベルは実行中に `set_log_level` コマンドで変更できる。
+//! 出力したイベントはクラッシュレポート用に直前の操作としても記録し、
+//! ERRORレベルのイベントはエラー報告が有効な場合に送信する。
+
+use crate::crash::RecentOperationsLayer;
+use crate::error_reporting::ErrorReportingLayer;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// ログファイル名の接頭辞
+const LOG_FILE_PREFIX: &str = "autosight";
+/// 保持するログファイル数（日数）
+const MAX_LOG_FILES: usize = 7;
+
+/// ログレベル
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    /// ログの絞り込み条件
+    ///
+    /// このアプリのログは指定レベル、依存クレートのログは warn 以上のみ出力する。
+    pub fn filter(&self) -> EnvFilter {
+        EnvFilter::new(format!("warn,autosight_lib={}", self.as_str()))
+    }
+}
+
+/// ログ出力の状態（Tauriの管理状態として保持する）
+pub struct LoggingState {
+    filter: reload::Handle<EnvFilter, Registry>,
+    /// ドロップするとファイルへの書き出しが止まるため保持する
+    _guard: WorkerGuard,
+}
+
+impl LoggingState {
+    /// ログレベルを変更
+    pub fn set_level(&self, level: LogLevel) -> Result<(), String> {
+        self.filter
+            .reload(level.filter())
+            .map_err(|e| format!("Failed to change log level: {}", e))
+    }
+}
+
+/// ログ出力を初期化
+pub fn init(log_dir: &Path, level: LogLevel) -> Result<LoggingState, String> {
+    std::fs::create_dir_all(log_dir)
+        .map_err(|e| format!("Failed to create log directory: {}", e))?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| format!("Failed to create log file: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let (filter, handle) = reload::Layer::new(level.filter());
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(RecentOperationsLayer)
+        .with(ErrorReportingLayer)
+        .try_init()
+        .map_err(|e| format!("Failed to initialize logging: {}", e))?;
+
+    Ok(LoggingState {
+        filter: handle,
+        _guard: guard,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level() {
+        assert_eq!(LogLevel::default(), LogLevel::Info);
+        assert_eq!(
+            serde_json::from_str::<LogLevel>("\"debug\"").unwrap(),
+            LogLevel::Debug
+        );
+        // ディレクティブの表示順は tracing-subscriber のバージョンで異なるため順序を問わない
+        let filter = LogLevel::Warn.filter().to_string();
+        let mut directives: Vec<_> = filter.split(',').collect();
+        directives.sort_unstable();
+        assert_eq!(directives, ["autosight_lib=warn", "warn"]);
+    }
+}
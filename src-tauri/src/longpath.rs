@@ -0,0 +1,113 @@
+//! Windowsの長いパスへの対応
+//!
+//! 深い階層のプロジェクトフォルダに保存すると、保存先のパスが260文字（MAX_PATH）を超えて
+//! 書き込みに失敗する。Windowsでは絶対パスを拡張長パス（`\\?\` 形式）に変換してから
+//! ファイル操作を行う。拡張長パスではOSによる正規化が行われないため、区切り文字の統一と
+//! `.` / `..` の解決は変換時に行う。
+//!
+//! 変換するのはファイル操作に渡すパスのみで、画面・履歴に表示するパスは元の形式のままとする。
+
+use std::path::{Path, PathBuf};
+
+/// 拡張長パスの接頭辞
+const VERBATIM_PREFIX: &str = r"\\?\";
+/// UNCパスの拡張長パスの接頭辞
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// ファイル操作に使うパスを取得
+///
+/// Windowsでは絶対パスを拡張長パスに変換する。それ以外の環境ではそのまま返す。
+pub fn extended(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if cfg!(windows) {
+        if let Some(s) = path.to_str() {
+            return PathBuf::from(to_verbatim(s));
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Windows形式のパス文字列を拡張長パスに変換
+///
+/// 変換済みのパス・デバイスパス（`\\.\`）・相対パスはそのまま返す。
+fn to_verbatim(path: &str) -> String {
+    if path.starts_with(VERBATIM_PREFIX) || path.starts_with(r"\\.\") {
+        return path.to_string();
+    }
+
+    let normalized = path.replace('/', "\\");
+    if let Some(rest) = normalized.strip_prefix(r"\\") {
+        // UNCパス: \\server\share\... → \\?\UNC\server\share\...
+        let mut parts = rest.splitn(3, '\\');
+        let (Some(server), Some(share)) = (parts.next(), parts.next()) else {
+            return path.to_string();
+        };
+        if server.is_empty() || share.is_empty() {
+            return path.to_string();
+        }
+        let root = format!("{}{}\\{}", VERBATIM_UNC_PREFIX, server, share);
+        return join_components(&root, parts.next().unwrap_or(""));
+    }
+
+    let bytes = normalized.as_bytes();
+    let is_drive_absolute =
+        bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+    if !is_drive_absolute {
+        return path.to_string();
+    }
+    let root = format!("{}{}", VERBATIM_PREFIX, &normalized[..2]);
+    join_components(&root, &normalized[3..])
+}
+
+/// ルートにパスの各要素を連結（空要素・`.` を除き、`..` は1つ上に戻る）
+fn join_components(root: &str, rest: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('\\') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            c => components.push(c),
+        }
+    }
+
+    let mut result = root.to_string();
+    if components.is_empty() {
+        result.push('\\');
+    }
+    for component in components {
+        result.push('\\');
+        result.push_str(component);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_verbatim() {
+        assert_eq!(
+            to_verbatim(r"C:\Projects\A\照明\1003.ies"),
+            r"\\?\C:\Projects\A\照明\1003.ies"
+        );
+        // 区切り文字の統一と . / .. の解決
+        assert_eq!(
+            to_verbatim(r"C:\Projects/A\.\B\..\temp_1003.ies"),
+            r"\\?\C:\Projects\A\temp_1003.ies"
+        );
+        assert_eq!(to_verbatim(r"D:\"), r"\\?\D:\");
+        // UNCパス
+        assert_eq!(
+            to_verbatim(r"\\nas\share\案件\1003.ies"),
+            r"\\?\UNC\nas\share\案件\1003.ies"
+        );
+        // 変換済み・デバイスパス・相対パスはそのまま
+        assert_eq!(to_verbatim(r"\\?\C:\a\b"), r"\\?\C:\a\b");
+        assert_eq!(to_verbatim(r"\\.\pipe\x"), r"\\.\pipe\x");
+        assert_eq!(to_verbatim(r"downloads\1003.ies"), r"downloads\1003.ies");
+        assert_eq!(to_verbatim("/home/user/1003.ies"), "/home/user/1003.ies");
+    }
+}
@@ -0,0 +1,187 @@
+//! 手動指定の取得元
+//!
+//! 自動の照合で誤った配光（ビーム角違い等）のファイルが選ばれる場合や、IESファイルが代理店の
+//! ページにのみ掲載されている場合に、利用者が見つけたURLをSpec No.ごとに登録しておき、
+//! 一括ダウンロードの再実行でもプロバイダーの代わりにそのURLから取得する。
+//! 取得元はプロジェクトごとにストアに記録する。
+
+use crate::portable::{self, STORE_NAME};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 手動指定の取得元（プロジェクトIDをキーとするマップ）を保存するキー
+const MANUAL_SOURCES_KEY: &str = "manualSources";
+/// プロジェクト未指定の取得元のキー
+const NO_PROJECT_KEY: &str = "";
+
+/// 手動指定の取得元
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualSource {
+    pub spec_no: String,
+    /// 登録時の型番（器具リストの型番が変わった場合は適用しない）
+    pub model_number: String,
+    /// IESファイルまたはZIPのURL
+    pub url: String,
+    pub added_at: DateTime<Utc>,
+}
+
+impl ManualSource {
+    /// 行に適用するか（Spec No. が一致し、型番が登録時から変わっていない）
+    fn applies_to(&self, spec_no: &str, model_number: &str) -> bool {
+        self.spec_no == spec_no.trim()
+            && normalize_model(&self.model_number) == normalize_model(model_number)
+    }
+}
+
+/// 比較用に型番を正規化（大文字・小文字と空白を無視する）
+fn normalize_model(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// プロジェクトの手動指定の取得元
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ManualSources(pub Vec<ManualSource>);
+
+impl ManualSources {
+    /// 行の取得元のURL（登録がない場合は None）
+    pub fn url_for(&self, spec_no: &str, model_number: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|source| source.applies_to(spec_no, model_number))
+            .map(|source| source.url.as_str())
+    }
+
+    /// 取得元を登録（同じSpec No. の登録は置き換える）
+    fn set(&mut self, spec_no: &str, model_number: &str, url: &str) {
+        self.0.retain(|source| source.spec_no != spec_no.trim());
+        self.0.push(ManualSource {
+            spec_no: spec_no.trim().to_string(),
+            model_number: model_number.trim().to_string(),
+            url: url.trim().to_string(),
+            added_at: Utc::now(),
+        });
+    }
+
+    /// 取得元の登録を削除（削除した場合は true）
+    fn remove(&mut self, spec_no: &str) -> bool {
+        let before = self.0.len();
+        self.0.retain(|source| source.spec_no != spec_no.trim());
+        self.0.len() != before
+    }
+}
+
+/// すべてのプロジェクトの取得元を読み込む（ない場合・読み込めない場合は空）
+fn load_all<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, ManualSources> {
+    app.store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(MANUAL_SOURCES_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// すべてのプロジェクトの取得元を保存
+fn save_all<R: Runtime>(
+    app: &AppHandle<R>,
+    sources: &BTreeMap<String, ManualSources>,
+) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        MANUAL_SOURCES_KEY,
+        serde_json::to_value(sources).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save manual sources: {}", e))
+}
+
+/// プロジェクトの取得元を読み込む（`project_id` 省略時はプロジェクト未指定の取得元）
+pub fn load<R: Runtime>(app: &AppHandle<R>, project_id: Option<&str>) -> ManualSources {
+    load_all(app)
+        .remove(project_id.unwrap_or(NO_PROJECT_KEY))
+        .unwrap_or_default()
+}
+
+/// プロジェクトの取得元を登録
+pub fn set<R: Runtime>(
+    app: &AppHandle<R>,
+    project_id: Option<&str>,
+    spec_no: &str,
+    model_number: &str,
+    url: &str,
+) -> Result<ManualSources, String> {
+    if spec_no.trim().is_empty() {
+        return Err("Spec No. is empty".to_string());
+    }
+    let mut all = load_all(app);
+    let sources = all
+        .entry(project_id.unwrap_or(NO_PROJECT_KEY).to_string())
+        .or_default();
+    sources.set(spec_no, model_number, url);
+    let sources = sources.clone();
+    save_all(app, &all)?;
+    Ok(sources)
+}
+
+/// プロジェクトの取得元の登録を削除
+pub fn remove<R: Runtime>(
+    app: &AppHandle<R>,
+    project_id: Option<&str>,
+    spec_no: &str,
+) -> Result<ManualSources, String> {
+    let mut all = load_all(app);
+    let key = project_id.unwrap_or(NO_PROJECT_KEY);
+    let Some(sources) = all.get_mut(key) else {
+        return Ok(ManualSources::default());
+    };
+    let removed = sources.remove(spec_no);
+    let sources = sources.clone();
+    if sources.0.is_empty() {
+        all.remove(key);
+    }
+    if removed {
+        save_all(app, &all)?;
+    }
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_for() {
+        let mut sources = ManualSources::default();
+        sources.set("A01", "ad 12345", "https://example.com/a.ies");
+
+        assert_eq!(
+            sources.url_for("A01", "AD12345"),
+            Some("https://example.com/a.ies")
+        );
+        assert_eq!(sources.url_for("A02", "AD12345"), None);
+        // 型番が変わった行には適用しない
+        assert_eq!(sources.url_for("A01", "AD12346"), None);
+
+        // 同じSpec No. の登録は置き換える
+        sources.set("A01", "AD12345", "https://example.com/a.zip");
+        assert_eq!(sources.0.len(), 1);
+        assert_eq!(
+            sources.url_for("A01", "AD12345"),
+            Some("https://example.com/a.zip")
+        );
+
+        assert!(sources.remove("A01"));
+        assert!(!sources.remove("A01"));
+        assert_eq!(sources.url_for("A01", "AD12345"), None);
+    }
+}
@@ -0,0 +1,119 @@
+//! メーカー名の別名表
+//!
+//! 組み込みの別名（[`crate::providers::ManufacturerProvider::aliases`]）では判定できないメーカー表記
+//! （略称・旧社名・英語社名等）を、利用者がプロバイダーに対応付けて登録する。
+//! 別名表はストアに保存し、起動時と登録時にプロバイダーレジストリへ反映する。
+
+use crate::portable::{self, STORE_NAME};
+use crate::providers::{normalize_manufacturer, SharedRegistry};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 別名表を保存するキー
+const ALIAS_KEY: &str = "manufacturer_aliases";
+
+/// メーカー名の別名（1件）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManufacturerAlias {
+    /// メーカー欄の表記（登録した表記のまま保存し、照合時に正規化する）
+    pub alias: String,
+    /// 対応付けるプロバイダーID
+    pub provider_id: String,
+}
+
+/// 保存済みの別名表を読み込む
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Vec<ManufacturerAlias> {
+    app.store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(ALIAS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// 別名表をレジストリに反映（起動時・登録時）
+pub fn apply(registry: &SharedRegistry, aliases: &[ManufacturerAlias]) -> Result<(), String> {
+    registry.update(|registry| {
+        registry.set_aliases(
+            aliases
+                .iter()
+                .map(|a| (a.alias.as_str(), a.provider_id.as_str())),
+        );
+        Ok(())
+    })
+}
+
+/// 別名を追加した別名表（正規化すると同じ表記の別名は置き換える）
+fn with_alias(
+    mut aliases: Vec<ManufacturerAlias>,
+    alias: &str,
+    provider_id: &str,
+) -> Result<Vec<ManufacturerAlias>, String> {
+    let key = normalize_manufacturer(alias);
+    if key.is_empty() {
+        return Err("Manufacturer alias is empty".to_string());
+    }
+    aliases.retain(|a| normalize_manufacturer(&a.alias) != key);
+    aliases.push(ManufacturerAlias {
+        alias: alias.trim().to_string(),
+        provider_id: provider_id.to_string(),
+    });
+    Ok(aliases)
+}
+
+/// 別名を登録して保存し、レジストリに反映する（戻り値: 登録後の別名表）
+pub fn add<R: Runtime>(
+    app: &AppHandle<R>,
+    registry: &SharedRegistry,
+    alias: &str,
+    provider_id: &str,
+) -> Result<Vec<ManufacturerAlias>, String> {
+    if registry.load().get_provider_by_id(provider_id).is_none() {
+        return Err(format!("Unknown provider: {}", provider_id));
+    }
+    let aliases = with_alias(load(app), alias, provider_id)?;
+
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        ALIAS_KEY,
+        serde_json::to_value(&aliases).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save manufacturer aliases: {}", e))?;
+    apply(registry, &aliases)?;
+    Ok(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ProviderRegistry;
+
+    #[test]
+    fn test_with_alias() {
+        let aliases = with_alias(Vec::new(), " KL照明 ", "koizumi").unwrap();
+        assert_eq!(aliases[0].alias, "KL照明");
+
+        // 正規化して同じ表記は置き換える
+        let aliases = with_alias(aliases, "ＫＬ照明株式会社", "tokistar").unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].provider_id, "tokistar");
+
+        assert!(with_alias(aliases, "株式会社 ", "koizumi").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let registry = SharedRegistry::new(ProviderRegistry::new());
+        let aliases = with_alias(Vec::new(), "KL照明", "tokistar").unwrap();
+        apply(&registry, &aliases).unwrap();
+        assert!(registry
+            .load()
+            .get_provider("kl照明")
+            .is_some_and(|p| p.id() == "tokistar"));
+    }
+}
@@ -0,0 +1,541 @@
+//! SMB（ネットワーク共有）への保存
+//!
+//! 事務所ではNASのプロジェクトフォルダに保存することが多いが、ネットワークドライブの割り当ては
+//! サンドボックス化されたアプリから見えない・再起動後に切れている等、動作が安定しない。
+//! 設定した共有（`\\server\share` / `smb://server/share`）配下を保存先に指定した場合は、
+//! 保存した資格情報で共有に接続してから書き込む。
+//!
+//! - Windows: 資格情報を指定して共有に接続し（ドライブ文字は割り当てない）、UNCパスに書き込む
+//! - macOS: `mount_smbfs` でアプリのデータディレクトリ配下にマウントし、マウント先に書き込む
+//!
+//! ダウンロード中の一時ファイルはローカルに作成し、完成したファイルを共有にコピーする。
+//! コピー中に共有が切断された場合（一時的なネットワークエラー）は再接続して再試行する。
+//! パスワードは設定とは別にOSのキーチェーンに保存する（[`crate::secrets`]）。macOSでは
+//! `mount_smbfs` のコマンドライン引数に含めず（`ps` で見えるため）、キーチェーンのSMBの
+//! パスワードとして登録してから `-N` でマウントする。
+
+use crate::longpath;
+use crate::portable;
+use crate::secrets;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+/// パスワードを保存するキー（キーチェーンのアカウント名。以前のバージョンはストアファイルのキー）
+const PASSWORD_KEY: &str = "networkSharePassword";
+/// 再試行回数の上限
+const MAX_RETRIES: u32 = 10;
+/// 再試行の待機時間（試行回数に比例して延ばす）
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+/// ダウンロード中の一時ファイルを置くディレクトリ名（OSの一時ディレクトリ配下）
+const STAGING_DIR: &str = "autosight-share";
+
+/// 接続中の共有（書き込みの再試行時に再接続するため保持する）
+static CONNECTIONS: Mutex<Vec<Connection>> = Mutex::new(Vec::new());
+
+/// ネットワーク共有の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct NetworkShareSettings {
+    /// 共有への保存時に資格情報で接続する（既定は無効）
+    pub enabled: bool,
+    /// 共有（`\\server\share` または `smb://server/share`）
+    pub share: String,
+    /// ユーザー名
+    pub username: String,
+    /// ドメイン（ワークグループ）
+    pub domain: Option<String>,
+    /// 共有が切断された場合の再試行回数
+    pub max_retries: u32,
+}
+
+impl Default for NetworkShareSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            share: String::new(),
+            username: String::new(),
+            domain: None,
+            max_retries: 3,
+        }
+    }
+}
+
+impl NetworkShareSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_retries > MAX_RETRIES {
+            return Err(format!(
+                "networkShare.maxRetries must be at most {}",
+                MAX_RETRIES
+            ));
+        }
+        if !self.enabled {
+            return Ok(());
+        }
+        match ShareRoot::parse(&self.share) {
+            Some(root) if root.path.is_empty() => {}
+            _ => return Err(
+                "networkShare.share must be in the form \\\\server\\share or smb://server/share"
+                    .to_string(),
+            ),
+        }
+        if self.username.trim().is_empty() {
+            return Err("networkShare.username must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 共有のルートと、その配下のパス
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ShareRoot {
+    server: String,
+    share: String,
+    /// 共有配下のパス（`/` 区切り、先頭・末尾の区切りなし）
+    path: String,
+}
+
+impl ShareRoot {
+    /// `\\server\share\...` `//server/share/...` `smb://server/share/...` を解析
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let rest = match s.get(..6) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("smb://") => &s[6..],
+            _ => s.strip_prefix(r"\\").or_else(|| s.strip_prefix("//"))?,
+        };
+        let mut parts = rest
+            .split(['\\', '/'])
+            .filter(|part| !part.is_empty() && *part != ".");
+        let server = parts.next()?;
+        let share = parts.next()?;
+        let path: Vec<&str> = parts.collect();
+        if path.contains(&"..") {
+            return None;
+        }
+        Some(Self {
+            server: server.to_string(),
+            share: share.to_string(),
+            path: path.join("/"),
+        })
+    }
+
+    /// 同じ共有か（サーバー名・共有名は大文字小文字を区別しない）
+    fn same_share(&self, other: &ShareRoot) -> bool {
+        self.server.eq_ignore_ascii_case(&other.server)
+            && self.share.eq_ignore_ascii_case(&other.share)
+    }
+
+    fn unc(&self) -> String {
+        format!(r"\\{}\{}", self.server, self.share)
+    }
+}
+
+/// ネットワーク共有のパス（`\\server\share\...` / `smb://server/share/...`）か
+pub fn is_share_spec(s: &str) -> bool {
+    ShareRoot::parse(s).is_some()
+}
+
+/// 接続に必要な情報（パスワードは接続時にキーチェーンから読み込む）
+#[derive(Debug, Clone)]
+struct Connection {
+    root: ShareRoot,
+    #[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+    username: String,
+    #[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+    domain: Option<String>,
+    /// 書き込みに使うローカルのパス（WindowsはUNCパス、macOSはマウント先）
+    local_root: PathBuf,
+    max_retries: u32,
+}
+
+/// パスワードを保存（`None` の場合は削除）
+pub fn set_password<R: Runtime>(
+    app: &AppHandle<R>,
+    password: Option<String>,
+) -> Result<(), String> {
+    match password {
+        Some(password) => secrets::set(PASSWORD_KEY, &password)?,
+        None => secrets::delete(PASSWORD_KEY)?,
+    }
+    secrets::remove_from_store(app, PASSWORD_KEY)
+}
+
+#[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+fn load_password() -> Result<String, String> {
+    Ok(secrets::get(PASSWORD_KEY)?.unwrap_or_default())
+}
+
+/// 保存先ディレクトリを書き込みに使うパスに変換
+///
+/// 設定した共有配下のディレクトリであれば共有に接続し、ローカルから書き込めるパスを返す。
+/// それ以外のディレクトリはそのまま返す。
+pub fn resolve<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &NetworkShareSettings,
+    dir: &str,
+) -> Result<String, String> {
+    if !settings.enabled {
+        return Ok(dir.to_string());
+    }
+    let (Some(configured), Some(target)) =
+        (ShareRoot::parse(&settings.share), ShareRoot::parse(dir))
+    else {
+        return Ok(dir.to_string());
+    };
+    if !configured.same_share(&target) {
+        return Ok(dir.to_string());
+    }
+
+    // 以前のバージョンがストアファイルに保存したパスワードをキーチェーンに移す
+    secrets::get_or_migrate(app, PASSWORD_KEY)?;
+    let mount_dir = portable::data_dir(app)?.join("shares");
+    let connection = Connection {
+        local_root: local_root(&configured, &mount_dir),
+        root: configured,
+        username: settings.username.trim().to_string(),
+        domain: settings.domain.clone().filter(|d| !d.trim().is_empty()),
+        max_retries: settings.max_retries,
+    };
+    connect(&connection)?;
+
+    let local = target
+        .path
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .fold(connection.local_root.clone(), |dir, part| dir.join(part));
+    register(connection);
+    Ok(local.to_string_lossy().into_owned())
+}
+
+/// 接続を確認（設定画面の「接続テスト」用）
+///
+/// 共有に接続し、書き込みに使うローカルのパスを返す。
+pub fn test<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &NetworkShareSettings,
+) -> Result<String, String> {
+    settings.validate()?;
+    if !settings.enabled {
+        return Err("Network share is not enabled".to_string());
+    }
+    let local = resolve(app, settings, &settings.share)?;
+    std::fs::read_dir(longpath::extended(&local))
+        .map_err(|e| format!("Failed to access network share: {}", e))?;
+    Ok(local)
+}
+
+/// 書き込みに使うローカルのパス
+fn local_root(root: &ShareRoot, mount_dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(root.unc())
+    } else {
+        mount_dir.join(crate::filename::sanitize_filename(&format!(
+            "{}_{}",
+            root.server, root.share
+        )))
+    }
+}
+
+fn register(connection: Connection) {
+    let mut connections = CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    connections.retain(|c| !c.root.same_share(&connection.root));
+    connections.push(connection);
+}
+
+/// パスを含む接続中の共有
+fn connection_for(path: &Path) -> Option<Connection> {
+    CONNECTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|c| path.starts_with(&c.local_root))
+        .cloned()
+}
+
+/// 接続中の共有配下のパスか
+pub fn is_share_path(path: &Path) -> bool {
+    connection_for(path).is_some()
+}
+
+/// ダウンロード中の一時ファイルを置くローカルのディレクトリ
+pub fn staging_dir() -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(STAGING_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+    Ok(dir)
+}
+
+/// 一時ファイルを共有にコピーし、一時ファイルを削除
+///
+/// 途中で共有が切断された場合は再接続してコピーし直す（ブロッキングするため
+/// `run_blocking` から呼ぶ）。
+pub fn copy_to_share(temp: &Path, dest: &Path) -> io::Result<()> {
+    let result = retry_io(dest, || {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(temp, dest).map(|_| ())
+    });
+    let _ = std::fs::remove_file(temp);
+    result
+}
+
+/// ファイル操作を実行し、共有の切断によるエラーの場合は再接続して再試行
+///
+/// 接続中の共有配下でないパスはそのまま1回だけ実行する。
+pub fn retry_io<T>(path: &Path, mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        let error = match op() {
+            Err(e) if is_transient(&e) => e,
+            result => return result,
+        };
+        let Some(connection) = connection_for(path) else {
+            return Err(error);
+        };
+        if attempt >= connection.max_retries {
+            return Err(error);
+        }
+        attempt += 1;
+        tracing::warn!(
+            error = %error,
+            attempt,
+            share = %connection.root.unc(),
+            "network share disconnected, reconnecting"
+        );
+        std::thread::sleep(RETRY_DELAY * attempt);
+        if let Err(e) = reconnect(&connection) {
+            tracing::warn!(error = %e, "failed to reconnect network share");
+        }
+    }
+}
+
+/// 共有の切断・一時的なネットワークエラーか
+fn is_transient(e: &io::Error) -> bool {
+    if matches!(
+        e.kind(),
+        io::ErrorKind::NotConnected
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::TimedOut
+    ) {
+        return true;
+    }
+    // ErrorKind に対応がないOS固有のエラーコード
+    let codes: &[i32] = if cfg!(windows) {
+        // ERROR_BAD_NETPATH, ERROR_UNEXP_NET_ERR, ERROR_NETNAME_DELETED, ERROR_SEM_TIMEOUT,
+        // ERROR_NETWORK_UNREACHABLE
+        &[53, 59, 64, 121, 1231]
+    } else if cfg!(target_os = "macos") {
+        // EHOSTDOWN, EHOSTUNREACH, ESTALE
+        &[64, 65, 70]
+    } else {
+        // EHOSTDOWN, EHOSTUNREACH, ESTALE
+        &[112, 113, 116]
+    };
+    e.raw_os_error().is_some_and(|code| codes.contains(&code))
+}
+
+/// 共有に接続（接続済みの場合は何もしない）
+fn connect(connection: &Connection) -> Result<(), String> {
+    if std::fs::metadata(longpath::extended(&connection.local_root)).is_ok()
+        && (cfg!(windows) || is_mounted(&connection.local_root))
+    {
+        return Ok(());
+    }
+    mount(connection)
+}
+
+/// 共有に接続し直す
+fn reconnect(connection: &Connection) -> Result<(), String> {
+    if !cfg!(windows) {
+        // 切断されたマウントが残っていると再マウントできないため、先に解除する
+        let _ = std::process::Command::new("umount")
+            .arg("-f")
+            .arg(&connection.local_root)
+            .output();
+    }
+    mount(connection)
+}
+
+#[cfg(windows)]
+fn mount(connection: &Connection) -> Result<(), String> {
+    use windows_sys::Win32::NetworkManagement::WNet::{
+        WNetAddConnection2W, NETRESOURCEW, RESOURCETYPE_DISK,
+    };
+
+    /// ERROR_SESSION_CREDENTIAL_CONFLICT（別の資格情報で接続済み）
+    const SESSION_CREDENTIAL_CONFLICT: u32 = 1219;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    let mut remote = wide(&connection.root.unc());
+    let username = match &connection.domain {
+        Some(domain) => format!(r"{}\{}", domain, connection.username),
+        None => connection.username.clone(),
+    };
+    let username = wide(&username);
+    let password = wide(&load_password()?);
+    let resource = NETRESOURCEW {
+        dwScope: 0,
+        dwType: RESOURCETYPE_DISK,
+        dwDisplayType: 0,
+        dwUsage: 0,
+        lpLocalName: std::ptr::null_mut(),
+        lpRemoteName: remote.as_mut_ptr(),
+        lpComment: std::ptr::null_mut(),
+        lpProvider: std::ptr::null_mut(),
+    };
+    // SAFETY: 文字列はすべてNUL終端で、呼び出しの間は有効
+    let code = unsafe { WNetAddConnection2W(&resource, password.as_ptr(), username.as_ptr(), 0) };
+    match code {
+        // 既存の接続（エクスプローラーで接続済み等）をそのまま使う
+        0 | SESSION_CREDENTIAL_CONFLICT => Ok(()),
+        code => Err(format!(
+            "Failed to connect to {}: {}",
+            connection.root.unc(),
+            io::Error::from_raw_os_error(code as i32)
+        )),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn mount(connection: &Connection) -> Result<(), String> {
+    use security_framework::os::macos::passwords::{SecAuthenticationType, SecProtocolType};
+    use security_framework::passwords::set_internet_password;
+
+    std::fs::create_dir_all(&connection.local_root)
+        .map_err(|e| format!("Failed to create mount point: {}", e))?;
+    // mount_smbfs はキーチェーンに登録したSMBのパスワードを使う
+    set_internet_password(
+        &connection.root.server,
+        connection.domain.as_deref(),
+        &connection.username,
+        "",
+        None,
+        SecProtocolType::SMB,
+        SecAuthenticationType::Default,
+        load_password()?.as_bytes(),
+    )
+    .map_err(|e| format!("Failed to save network share password to keychain: {}", e))?;
+    let user = match &connection.domain {
+        Some(domain) => format!(
+            "{};{}",
+            crate::providers::url_encode(domain),
+            crate::providers::url_encode(&connection.username)
+        ),
+        None => crate::providers::url_encode(&connection.username),
+    };
+    let url = format!(
+        "//{}@{}/{}",
+        user,
+        connection.root.server,
+        crate::providers::url_encode(&connection.root.share)
+    );
+    let output = std::process::Command::new("mount_smbfs")
+        .arg("-N")
+        .arg(&url)
+        .arg(&connection.local_root)
+        .output()
+        .map_err(|e| format!("Failed to run mount_smbfs: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to mount {}: {}",
+            connection.root.unc(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn mount(connection: &Connection) -> Result<(), String> {
+    Err(format!(
+        "Connecting to {} is not supported on this platform; mount the share and use its local path",
+        connection.root.unc()
+    ))
+}
+
+/// マウント先に共有がマウントされているか（マウント先と親ディレクトリのデバイスが異なるか）
+#[cfg(unix)]
+fn is_mounted(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let (Ok(dir), Some(Ok(parent))) = (
+        std::fs::metadata(path),
+        path.parent().map(std::fs::metadata),
+    ) else {
+        return false;
+    };
+    dir.dev() != parent.dev()
+}
+
+#[cfg(not(unix))]
+fn is_mounted(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_share() {
+        let root = ShareRoot::parse(r"\\nas01\Projects\2024\案件A").unwrap();
+        assert_eq!(root.server, "nas01");
+        assert_eq!(root.share, "Projects");
+        assert_eq!(root.path, "2024/案件A");
+        assert_eq!(root.unc(), r"\\nas01\Projects");
+
+        let url = ShareRoot::parse("smb://NAS01/projects/").unwrap();
+        assert!(url.same_share(&root));
+        assert_eq!(url.path, "");
+        assert_eq!(
+            ShareRoot::parse("//nas01/projects/a/./b").unwrap().path,
+            "a/b"
+        );
+
+        assert_eq!(ShareRoot::parse(r"C:\Projects"), None);
+        assert_eq!(ShareRoot::parse(r"\\nas01"), None);
+        assert_eq!(ShareRoot::parse(r"\\nas01\share\..\other"), None);
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        let mut settings = NetworkShareSettings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.enabled = true;
+        settings.share = r"\\nas01\Projects".to_string();
+        assert!(settings.validate().unwrap_err().contains("username"));
+        settings.username = "lighting".to_string();
+        assert!(settings.validate().is_ok());
+
+        // 共有配下のフォルダではなく共有そのものを指定する
+        settings.share = r"\\nas01\Projects\2024".to_string();
+        assert!(settings.validate().is_err());
+        settings.share = "smb://nas01/Projects".to_string();
+        settings.max_retries = MAX_RETRIES + 1;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_retry_io() {
+        // 接続中の共有配下でなければ再試行しない
+        let mut calls = 0;
+        let result: io::Result<()> = retry_io(Path::new("/tmp/not-a-share"), || {
+            calls += 1;
+            Err(io::Error::from(io::ErrorKind::NotConnected))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        assert!(is_transient(&io::Error::from(io::ErrorKind::TimedOut)));
+        assert!(!is_transient(&io::Error::from(io::ErrorKind::NotFound)));
+    }
+}
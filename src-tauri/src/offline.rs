@@ -0,0 +1,158 @@
+//! オフラインモード
+//!
+//! 設定の `offline` が有効な間はメーカーサイト等への通信を行わず、製品情報のキャッシュ・
+//! サムネイルのキャッシュ・IESライブラリだけから結果を返す。キャッシュにないものは
+//! 接続のタイムアウトを待たずに `Offline, not cached: ...` のエラーにする。
+//! 通信できない現場での確認用。製品情報のキャッシュは有効期間を過ぎていても使用する。
+//!
+//! 現場に出る前に、メーカーとシリーズ（型番の接頭辞）を指定して製品を検索し、製品情報の
+//! キャッシュとIESライブラリへの登録をまとめて済ませておける（オフラインの準備）。
+
+use crate::commands;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::library::{self, LibraryEntry};
+use crate::providers::{DownloadResult, ProductCandidate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// IESライブラリのディレクトリ（起動時に決定）
+static LIBRARY_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 設定を反映（起動時・設定の保存時）
+pub fn apply(app: &AppHandle, enabled: bool) {
+    if let Ok(dir) = commands::library_dir(app) {
+        let _ = LIBRARY_DIR.set(dir);
+    }
+    if ENABLED.swap(enabled, Ordering::Relaxed) != enabled {
+        tracing::info!(enabled, "offline mode changed");
+    }
+}
+
+/// オフラインモードか
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// キャッシュにない場合のエラー
+pub fn not_cached(what: &str) -> ProviderError {
+    ProviderError::new(
+        ErrorCode::OfflineNotCached,
+        format!("Offline, not cached: {}", what),
+    )
+}
+
+/// IESライブラリから型番に一致するIESファイルを探す
+fn find_ies(model_number: &str) -> ProviderResult<LibraryEntry> {
+    let entries = match LIBRARY_DIR.get() {
+        Some(dir) => library::load(dir)?,
+        None => Vec::new(),
+    };
+    library::find(&entries, model_number)
+        .cloned()
+        .ok_or_else(|| not_cached(&format!("IES file for {}", model_number)))
+}
+
+/// IESライブラリのファイルを `dest_path` にコピー
+pub fn copy_ies(model_number: &str, dest_path: &str) -> DownloadResult {
+    let entry = match find_ies(model_number) {
+        Ok(entry) => entry,
+        Err(e) => return DownloadResult::failure(e),
+    };
+    match std::fs::copy(Path::new(&entry.path), dest_path) {
+        Ok(size) => DownloadResult::success(dest_path.to_string(), size, Some(entry.file_name)),
+        Err(e) => DownloadResult::failure(ProviderError::new(
+            ErrorCode::FileSystem,
+            format!("Failed to copy IES file from library: {}", e),
+        )),
+    }
+}
+
+/// オフラインの準備で取得する製品数の上限のデフォルト
+pub const DEFAULT_PREPARE_LIMIT: usize = 100;
+
+/// オフラインの準備の1製品分の結果（`offline-prepare-progress` イベントのペイロード）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepareProgress {
+    pub model_number: String,
+    /// 製品情報をキャッシュしたか（すでにキャッシュにあった場合を含む）
+    pub product_info: bool,
+    /// IESファイルをIESライブラリに登録したか（すでに登録されていた場合を含む）
+    pub ies: bool,
+    /// エラーメッセージ（製品情報・IESファイルのどちらかを取得できなかった場合）
+    pub error: Option<String>,
+}
+
+/// オフラインの準備の結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepareSummary {
+    /// 対象の製品数
+    pub total: usize,
+    /// 製品情報をキャッシュした製品数
+    pub product_info: usize,
+    /// IESファイルをIESライブラリに登録した製品数
+    pub ies: usize,
+    /// どちらかを取得できなかった製品数
+    pub failed: usize,
+}
+
+impl PrepareSummary {
+    pub fn add(&mut self, progress: &PrepareProgress) {
+        self.total += 1;
+        self.product_info += usize::from(progress.product_info);
+        self.ies += usize::from(progress.ies);
+        self.failed += usize::from(progress.error.is_some());
+    }
+}
+
+/// 検索結果のうち、型番がシリーズの接頭辞で始まるものを重複なく返す（大文字・小文字と空白は無視）
+pub fn series_models(candidates: &[ProductCandidate], prefix: &str, limit: usize) -> Vec<String> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect()
+    };
+    let prefix = normalize(prefix);
+    let mut seen = HashSet::new();
+    candidates
+        .iter()
+        .filter(|c| normalize(&c.model_number).starts_with(&prefix))
+        .filter(|c| seen.insert(normalize(&c.model_number)))
+        .map(|c| c.model_number.trim().to_string())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_models() {
+        let candidate = |model_number: &str| ProductCandidate {
+            model_number: model_number.to_string(),
+            product_name: None,
+            price: None,
+            product_page_url: None,
+        };
+        let candidates = vec![
+            candidate("AD12345"),
+            candidate("XD12345"),
+            candidate("ad 12346"),
+            candidate("AD12345"),
+            candidate("AD12347"),
+        ];
+        assert_eq!(
+            series_models(&candidates, "ad123", 10),
+            vec!["AD12345", "ad 12346", "AD12347"]
+        );
+        assert_eq!(series_models(&candidates, "AD", 1), vec!["AD12345"]);
+    }
+}
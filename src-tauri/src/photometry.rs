@@ -0,0 +1,1479 @@
+//! 配光データ（IES LM-63）の読み込みとEULUMDAT（LDT）への変換
+//!
+//! Relux等、LDT形式を前提とする照明計算ソフトに取り込むため、IESファイルの配光データを
+//! EULUMDAT形式に変換する。対応するのはタイプC配光（IESの photometric type 1）のみ。
+//! 水平角の対称性（軸対称・4象限対称・左右対称）はLDT側では展開して対称性なし（Isym 0）とし、
+//! 軸対称のみ Isym 1 のまま出力する。
+//!
+//! メーカーがEULUMDATしか公開していない器具のために、LDTを読み込んでIES形式で出力する
+//! 逆方向の変換も行う（`parse_ldt` → `IesPhotometry::to_ies`）。
+//!
+//! あわせて、屋外器具の光害（ダークスカイ）対策の確認用に、区分ごとの光束（ゾーナル光束）と
+//! BUG評価（IES TM-15-11）を計算する。
+//!
+//! ダウンロードしたファイルがIESファイルとして読み込めるか（エラーページのHTMLや途中で切れた
+//! ファイルでないか）の検証と、一覧に表示する概要（光束・消費電力等）の取得も行う（`validate_ies`）。
+
+use crate::filename::PhotometricValues;
+use crate::library::parse_keywords;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// ヘッダーのキーワード中の色温度（"3000K" "2700 K"）
+static CCT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:^|[^0-9])(\d{4,5})\s*K(?:$|[^A-Za-z0-9])").unwrap());
+
+/// ゾーナル光束の鉛直角の区分の境界（度）
+const ZONE_BOUNDARIES: [f64; 5] = [30.0, 60.0, 80.0, 90.0, 100.0];
+
+/// BUG評価の各区分の上限光束（lm。評価0〜4の上限、超える場合は5。IES TM-15-11 Addendum A）
+const BACKLIGHT_HIGH: [f64; 5] = [110.0, 500.0, 1000.0, 2500.0, 5000.0];
+const BACKLIGHT_MID: [f64; 5] = [220.0, 1000.0, 2500.0, 5000.0, 8500.0];
+const BACKLIGHT_LOW: [f64; 5] = [110.0, 500.0, 1000.0, 2500.0, 5000.0];
+const UPLIGHT: [f64; 5] = [0.0, 10.0, 50.0, 500.0, 1000.0];
+const GLARE_VERY_HIGH: [f64; 5] = [10.0, 100.0, 225.0, 500.0, 750.0];
+const GLARE_FRONT_HIGH: [f64; 5] = [660.0, 1800.0, 5000.0, 7500.0, 12000.0];
+const GLARE_BACK_HIGH: [f64; 5] = [110.0, 500.0, 1000.0, 2500.0, 5000.0];
+
+/// IESファイルの配光データ
+#[derive(Debug, Clone, PartialEq)]
+pub struct IesPhotometry {
+    /// ヘッダーのキーワード（キーは大文字）
+    pub keywords: BTreeMap<String, String>,
+    /// ランプ数（0の場合は1灯とみなす）
+    pub lamp_count: u32,
+    /// ランプ1灯あたりの光束（lm。絶対測光の場合は負の値（-1））
+    pub lumens_per_lamp: f64,
+    /// 光度の倍率（candela multiplier × ballast factor）
+    pub multiplier: f64,
+    /// 器具の幅・長さ・高さ（m。負の値は円形）
+    pub width_m: f64,
+    pub length_m: f64,
+    pub height_m: f64,
+    /// 入力電力（W）
+    pub input_watts: f64,
+    /// 鉛直角（γ、度）
+    pub vertical_angles: Vec<f64>,
+    /// 水平角（C、度）
+    pub horizontal_angles: Vec<f64>,
+    /// 光度（cd）。`candela[水平角][鉛直角]`
+    pub candela: Vec<Vec<f64>>,
+}
+
+/// 一括ダウンロードで保存する配光データの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PhotometryFormat {
+    /// IESファイルのみ（変換しない）
+    #[default]
+    Ies,
+    /// EULUMDAT（LDT）のみ（変換後にIESファイルを削除する）
+    Ldt,
+    /// IESファイルとLDTの両方
+    Both,
+}
+
+impl PhotometryFormat {
+    /// IESファイルを残すか
+    pub fn keeps_ies(self) -> bool {
+        self != Self::Ldt
+    }
+
+    /// LDTに変換するか
+    pub fn writes_ldt(self) -> bool {
+        self != Self::Ies
+    }
+}
+
+/// ダウンロードしたIESファイルの概要（一覧での表示用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IesMetadata {
+    /// 規格（1行目の "IESNA:LM-63-2002" 等。記載がない場合は None）
+    pub format: Option<String>,
+    /// 配光のタイプ（"C" / "B" / "A"）
+    pub photometric_type: String,
+    /// 器具光束（lm。タイプCは光度分布から求め、それ以外はランプ光束の合計。求められない場合は None）
+    pub lumens: Option<f64>,
+    /// 入力電力（W。記載がない場合は None）
+    pub input_watts: Option<f64>,
+    /// 鉛直角の数
+    pub vertical_angle_count: usize,
+    /// 水平角の数
+    pub horizontal_angle_count: usize,
+}
+
+/// TILT= 行より後の数値（TILT=INCLUDE の場合はランプの傾きのデータを読み飛ばした後から）
+fn photometric_values(
+    text: &str,
+) -> Result<impl Iterator<Item = Result<f64, String>> + '_, String> {
+    let (_, rest) = text
+        .split_once("TILT=")
+        .ok_or("Not a valid IES file (TILT= line not found)")?;
+    let mut lines = rest.lines();
+    let tilt = lines.next().unwrap_or_default().trim();
+    let mut numbers = lines
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number in IES file: {}", token))
+        });
+
+    if tilt == "INCLUDE" {
+        let mut next = || {
+            numbers
+                .next()
+                .unwrap_or(Err("IES file ended unexpectedly".to_string()))
+        };
+        next()?;
+        let count = next()? as usize;
+        for _ in 0..count * 2 {
+            next()?;
+        }
+    } else if tilt != "NONE" {
+        return Err(format!("Unsupported TILT in IES file: {}", tilt));
+    }
+    Ok(numbers)
+}
+
+/// ダウンロードした内容がIESファイルとして妥当か検証し、概要を返す
+///
+/// エラーページ等のHTML、TILT= 行のないファイル、ヘッダーに記載された角度の数に対して
+/// 光度の値が足りない（途中で切れた）ファイルはエラーにする。
+/// タイプB・タイプAの配光も妥当なIESファイルとして扱う（光束はランプ光束の合計）。
+pub fn validate_ies(bytes: &[u8]) -> Result<IesMetadata, String> {
+    let invalid = |reason: String| format!("Downloaded file is not a valid IES file: {}", reason);
+    if bytes.is_empty() {
+        return Err(invalid("file is empty".to_string()));
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let head: String = text
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .chars()
+        .take(64)
+        .collect::<String>()
+        .to_lowercase();
+    if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        return Err(invalid("received an HTML page".to_string()));
+    }
+
+    let mut values = photometric_values(&text).map_err(invalid)?;
+    let header = values
+        .by_ref()
+        .take(13)
+        .collect::<Result<Vec<f64>, String>>()
+        .map_err(invalid)?;
+    if header.len() < 13 {
+        return Err(invalid("header ended unexpectedly".to_string()));
+    }
+    let (lamp_count, lumens_per_lamp, input_watts) = (header[0], header[1], header[12]);
+    let (vertical_count, horizontal_count) = (header[3] as usize, header[4] as usize);
+    let photometric_type = match header[5] as u32 {
+        1 => "C",
+        2 => "B",
+        3 => "A",
+        other => return Err(invalid(format!("unknown photometric type {}", other))),
+    };
+    if vertical_count == 0 || horizontal_count == 0 {
+        return Err(invalid("no candela values".to_string()));
+    }
+    let expected = vertical_count + horizontal_count + vertical_count * horizontal_count;
+    let found = values
+        .take(expected)
+        .collect::<Result<Vec<f64>, String>>()
+        .map_err(invalid)?
+        .len();
+    if found < expected {
+        return Err(invalid(format!(
+            "expected {} angle and candela values, found {}",
+            expected, found
+        )));
+    }
+
+    let lumens = if photometric_type == "C" {
+        parse_ies(&text).ok().map(|ies| ies.luminaire_lumens())
+    } else {
+        Some(lumens_per_lamp * lamp_count.max(1.0))
+    };
+    Ok(IesMetadata {
+        format: text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .next()
+            .map(str::trim)
+            .filter(|line| line.to_uppercase().starts_with("IESNA"))
+            .map(str::to_string),
+        photometric_type: photometric_type.to_string(),
+        lumens: lumens.filter(|lm| *lm > 0.0),
+        input_watts: Some(input_watts).filter(|w| *w > 0.0),
+        vertical_angle_count: vertical_count,
+        horizontal_angle_count: horizontal_count,
+    })
+}
+
+/// IESファイルを解析
+pub fn parse_ies(text: &str) -> Result<IesPhotometry, String> {
+    let keywords = parse_keywords(text);
+    let mut numbers = photometric_values(text)?;
+    let mut next = || {
+        numbers
+            .next()
+            .unwrap_or(Err("IES file ended unexpectedly".to_string()))
+    };
+
+    let lamp_count = next()? as u32;
+    let lumens_per_lamp = next()?;
+    let candela_multiplier = next()?;
+    let vertical_count = next()? as usize;
+    let horizontal_count = next()? as usize;
+    let photometric_type = next()? as u32;
+    let units = next()? as u32;
+    let unit_scale = if units == 1 { 0.3048 } else { 1.0 };
+    let width_m = next()? * unit_scale;
+    let length_m = next()? * unit_scale;
+    let height_m = next()? * unit_scale;
+    let ballast_factor = next()?;
+    let _future_use = next()?;
+    let input_watts = next()?;
+
+    if photometric_type != 1 {
+        return Err(format!(
+            "Unsupported photometric type in IES file: {}",
+            photometric_type
+        ));
+    }
+    if vertical_count == 0 || horizontal_count == 0 {
+        return Err("IES file has no candela values".to_string());
+    }
+
+    let vertical_angles = (0..vertical_count)
+        .map(|_| next())
+        .collect::<Result<Vec<_>, _>>()?;
+    let horizontal_angles = (0..horizontal_count)
+        .map(|_| next())
+        .collect::<Result<Vec<_>, _>>()?;
+    let candela = (0..horizontal_count)
+        .map(|_| (0..vertical_count).map(|_| next()).collect())
+        .collect::<Result<Vec<Vec<_>>, _>>()?;
+
+    Ok(IesPhotometry {
+        keywords,
+        lamp_count,
+        lumens_per_lamp,
+        multiplier: candela_multiplier * ballast_factor,
+        width_m,
+        length_m,
+        height_m,
+        input_watts,
+        vertical_angles,
+        horizontal_angles,
+        candela,
+    })
+}
+
+/// EULUMDAT（LDT）ファイルを解析
+///
+/// 光度（cd/klm）はランプ光束の合計と換算係数からcdに戻す。ランプ数が負の値のもの
+/// （DIALux・Reluxの慣例による絶対測光）は、IESと同じくランプ光束を -1 とする。
+/// 小数点にカンマを使ったファイルにも対応する。
+pub fn parse_ldt(text: &str) -> Result<IesPhotometry, String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let line = |i: usize| lines.get(i).copied().unwrap_or_default();
+    let number = |token: &str| {
+        token
+            .replace(',', ".")
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid number in LDT file: {}", token))
+    };
+    let field = |i: usize| match lines.get(i) {
+        Some(value) => number(value),
+        None => Err("LDT file ended unexpectedly".to_string()),
+    };
+
+    let isym = field(2)? as u32;
+    let mc = field(3)? as usize;
+    let ng = field(5)? as usize;
+    let conversion = Some(field(23)?).filter(|f| *f > 0.0).unwrap_or(1.0);
+    let sets = field(25)? as usize;
+    if mc == 0 || ng == 0 {
+        return Err("LDT file has no intensity values".to_string());
+    }
+
+    let mut lamp_count = 0;
+    let mut absolute = false;
+    let mut lamp_flux = 0.0;
+    let mut input_watts = 0.0;
+    for set in 0..sets {
+        let base = 26 + set * 6;
+        let lamps = field(base)?;
+        absolute |= lamps < 0.0;
+        lamp_count += lamps.abs() as u32;
+        lamp_flux += field(base + 2)?;
+        input_watts += field(base + 5)?;
+    }
+    if lamp_flux <= 0.0 {
+        return Err("LDT file has no lamp flux".to_string());
+    }
+
+    // 直接比（10行）の後は C角・γ角・光度（1行に1つ。複数ある場合も読めるよう空白で区切る）
+    let mut numbers = lines
+        .iter()
+        .skip(26 + sets * 6 + 10)
+        .flat_map(|line| line.split_whitespace())
+        .map(number);
+    let mut next = || {
+        numbers
+            .next()
+            .unwrap_or(Err("LDT file ended unexpectedly".to_string()))
+    };
+    let c_angles = (0..mc).map(|_| next()).collect::<Result<Vec<_>, _>>()?;
+    let vertical_angles = (0..ng).map(|_| next()).collect::<Result<Vec<_>, _>>()?;
+    // 対称性に応じて光度データのあるC面（開始位置・面数）
+    let (start, planes) = match isym {
+        0 => (0, mc),
+        1 => (0, 1),
+        2 => (0, mc / 2 + 1),
+        3 => (mc / 4, mc / 2 + 1),
+        4 => (0, mc / 4 + 1),
+        _ => return Err(format!("Unsupported symmetry in LDT file: {}", isym)),
+    };
+    let horizontal_angles = if isym == 1 {
+        vec![0.0]
+    } else {
+        c_angles
+            .get(start..start + planes)
+            .ok_or("LDT file has too few C planes")?
+            .to_vec()
+    };
+    let to_cd = lamp_flux / 1000.0 * conversion;
+    let candela = (0..planes)
+        .map(|_| (0..ng).map(|_| next().map(|v| v * to_cd)).collect())
+        .collect::<Result<Vec<Vec<_>>, _>>()?;
+
+    let mut keywords = BTreeMap::new();
+    for (key, value) in [
+        ("TEST", line(7)),
+        ("MANUFAC", line(0)),
+        ("LUMINAIRE", line(8)),
+        ("LUMCAT", line(9)),
+        ("ISSUEDATE", line(11)),
+        ("LAMP", line(27)),
+    ] {
+        if !value.is_empty() {
+            keywords.insert(key.to_string(), value.to_string());
+        }
+    }
+    keywords.insert("OTHER".to_string(), "Converted from EULUMDAT".to_string());
+
+    // 幅0は円形（長さが直径）
+    let (length_mm, width_mm) = (field(12)?, field(13)?);
+    let (width_m, length_m) = if width_mm == 0.0 {
+        (-length_mm / 1000.0, -length_mm / 1000.0)
+    } else {
+        (width_mm / 1000.0, length_mm / 1000.0)
+    };
+    let lamp_count = lamp_count.max(1);
+    Ok(IesPhotometry {
+        keywords,
+        lamp_count,
+        lumens_per_lamp: if absolute {
+            -1.0
+        } else {
+            lamp_flux / lamp_count as f64
+        },
+        multiplier: 1.0,
+        width_m,
+        length_m,
+        height_m: field(14)? / 1000.0,
+        input_watts,
+        vertical_angles,
+        horizontal_angles,
+        candela,
+    })
+}
+
+/// 区分ごとの光束（lm。IES TM-15-11 の区分）
+///
+/// 鉛直角は真下を0度とし、前方はC0〜180度（C90が道路側）、後方はC180〜360度とする。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZonalLumens {
+    /// 前方 0〜30度
+    pub front_low: f64,
+    /// 前方 30〜60度
+    pub front_mid: f64,
+    /// 前方 60〜80度
+    pub front_high: f64,
+    /// 前方 80〜90度
+    pub front_very_high: f64,
+    /// 後方 0〜30度
+    pub back_low: f64,
+    /// 後方 30〜60度
+    pub back_mid: f64,
+    /// 後方 60〜80度
+    pub back_high: f64,
+    /// 後方 80〜90度
+    pub back_very_high: f64,
+    /// 上方 90〜100度
+    pub uplight_low: f64,
+    /// 上方 100〜180度
+    pub uplight_high: f64,
+}
+
+impl ZonalLumens {
+    /// 鉛直角 `gamma` の区分に前方・後方の光束を加える
+    fn add(&mut self, gamma: f64, front: f64, back: f64) {
+        let (front_zone, back_zone) = match gamma {
+            g if g < 30.0 => (&mut self.front_low, &mut self.back_low),
+            g if g < 60.0 => (&mut self.front_mid, &mut self.back_mid),
+            g if g < 80.0 => (&mut self.front_high, &mut self.back_high),
+            g if g < 90.0 => (&mut self.front_very_high, &mut self.back_very_high),
+            g if g < 100.0 => {
+                self.uplight_low += front + back;
+                return;
+            }
+            _ => {
+                self.uplight_high += front + back;
+                return;
+            }
+        };
+        *front_zone += front;
+        *back_zone += back;
+    }
+
+    /// BUG評価（IES TM-15-11）
+    pub fn bug_rating(&self) -> BugRating {
+        let rating = |lumens: f64, limits: &[f64; 5]| {
+            limits
+                .iter()
+                .position(|&limit| lumens <= limit)
+                .unwrap_or(5) as u8
+        };
+        BugRating {
+            backlight: rating(self.back_high, &BACKLIGHT_HIGH)
+                .max(rating(self.back_mid, &BACKLIGHT_MID))
+                .max(rating(self.back_low, &BACKLIGHT_LOW)),
+            uplight: rating(self.uplight_high, &UPLIGHT).max(rating(self.uplight_low, &UPLIGHT)),
+            glare: rating(self.front_very_high, &GLARE_VERY_HIGH)
+                .max(rating(self.back_very_high, &GLARE_VERY_HIGH))
+                .max(rating(self.front_high, &GLARE_FRONT_HIGH))
+                .max(rating(self.back_high, &GLARE_BACK_HIGH)),
+        }
+    }
+}
+
+/// BUG評価（Backlight・Uplight・Glare。それぞれ0〜5で、小さいほど光害が少ない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugRating {
+    pub backlight: u8,
+    pub uplight: u8,
+    pub glare: u8,
+}
+
+impl std::fmt::Display for BugRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "B{}-U{}-G{}", self.backlight, self.uplight, self.glare)
+    }
+}
+
+/// IESファイルの配光データの解析結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IesAnalysis {
+    /// 器具光束（lm）
+    pub lumens: f64,
+    /// 入力電力（W。記載がない場合は None）
+    pub input_watts: Option<f64>,
+    /// 絶対測光（LED器具等、ランプ光束のない測光）か
+    pub absolute_photometry: bool,
+    /// ランプ数
+    pub lamp_count: u32,
+    /// ランプ光束の合計（lm。絶対測光の場合は None）
+    pub lamp_lumens: Option<f64>,
+    /// 器具効率（%。器具光束 / ランプ光束。絶対測光の場合は None）
+    pub light_output_ratio: Option<f64>,
+    /// 固有エネルギー消費効率（lm/W。器具光束 / 入力電力）
+    pub efficacy: Option<f64>,
+    /// ビーム角（度）
+    pub beam_angle: Option<f64>,
+    /// 区分ごとの光束（lm）
+    pub zonal_lumens: ZonalLumens,
+    /// BUG評価（屋外器具向け）
+    pub bug_rating: BugRating,
+}
+
+/// 照射円錐図（コーンダイアグラム）の1行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConePoint {
+    /// 器具からの距離（m。取付高さ）
+    pub distance: f64,
+    /// 光軸上の照度（lx）
+    pub center_illuminance: f64,
+    /// ビーム径（m。ビーム角が求められない場合は None）
+    pub beam_diameter: Option<f64>,
+}
+
+/// 照射円錐図（コーンダイアグラム）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConeDiagram {
+    /// 光軸（真下）方向の光度（cd）
+    pub center_intensity: f64,
+    /// ビーム角（度）
+    pub beam_angle: Option<f64>,
+    /// 距離ごとの照度・ビーム径（指定した距離の順）
+    pub points: Vec<ConePoint>,
+}
+
+/// 水平角の対称性（IESの水平角の範囲で表す）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Symmetry {
+    /// 軸対称（水平角が0度のみ）
+    Axial,
+    /// 4象限対称（0〜90度）
+    Quadrant,
+    /// C0-C180面で左右対称（0〜180度）
+    #[serde(rename = "c0c180")]
+    C0C180,
+    /// C90-C270面で左右対称（90〜270度）
+    #[serde(rename = "c90c270")]
+    C90C270,
+    /// 対称性なし（0〜360度）
+    None,
+}
+
+/// 配光曲線（極座標グラフ）の1面
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolarPlane {
+    /// 水平角（C、度）
+    pub horizontal_angle: f64,
+    /// 光度（cd。`PolarCurve::vertical_angles` の順）
+    pub candela: Vec<f64>,
+}
+
+/// 配光曲線（極座標グラフ）の表示用データ
+///
+/// 鉛直角を一定の間隔に間引き、C0・C90・C180・C270面の光度を対称性に従って展開したもの。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolarCurve {
+    /// 鉛直角（γ、度）
+    pub vertical_angles: Vec<f64>,
+    pub planes: Vec<PolarPlane>,
+}
+
+/// IESファイルの配光データの概要（解析結果に最大光度・1/10ビーム角・配光曲線を加えたもの）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IesSummary {
+    #[serde(flatten)]
+    pub analysis: IesAnalysis,
+    /// 最大光度（cd）
+    pub max_candela: f64,
+    /// 最大光度の鉛直角（度）
+    pub max_candela_vertical_angle: f64,
+    /// 最大光度の水平角（度）
+    pub max_candela_horizontal_angle: f64,
+    /// 1/10ビーム角（フィールド角。光度が最大光度の10%まで下がる鉛直角の2倍）
+    pub field_angle: Option<f64>,
+    /// 水平角の対称性
+    pub symmetry: Symmetry,
+    /// 鉛直角の数
+    pub vertical_angle_count: usize,
+    /// 水平角の数
+    pub horizontal_angle_count: usize,
+    /// 配光曲線の表示用データ
+    pub polar_curve: PolarCurve,
+}
+
+/// 配光曲線で表示する水平角
+const POLAR_PLANES: [f64; 4] = [0.0, 90.0, 180.0, 270.0];
+
+impl IesPhotometry {
+    fn symmetry(&self) -> Symmetry {
+        let first = self.horizontal_angles[0];
+        let last = *self.horizontal_angles.last().unwrap();
+        if self.horizontal_angles.len() == 1 {
+            Symmetry::Axial
+        } else if first == 0.0 && last == 90.0 {
+            Symmetry::Quadrant
+        } else if first == 0.0 && last == 180.0 {
+            Symmetry::C0C180
+        } else if first == 90.0 && last == 270.0 {
+            Symmetry::C90C270
+        } else {
+            Symmetry::None
+        }
+    }
+
+    /// 水平角 `c`（0〜360度）の光度データの添字（IESの対称性に従って折り返す）
+    fn plane_index(&self, c: f64) -> usize {
+        let folded = match self.symmetry() {
+            Symmetry::Axial => self.horizontal_angles[0],
+            Symmetry::Quadrant => match c {
+                c if c <= 90.0 => c,
+                c if c <= 180.0 => 180.0 - c,
+                c if c <= 270.0 => c - 180.0,
+                c => 360.0 - c,
+            },
+            Symmetry::C0C180 if c > 180.0 => 360.0 - c,
+            Symmetry::C90C270 if !(90.0..=270.0).contains(&c) => (540.0 - c) % 360.0,
+            _ => c,
+        };
+        self.horizontal_angles
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (*a - folded)
+                    .abs()
+                    .partial_cmp(&(*b - folded).abs())
+                    .unwrap()
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// 0〜360度（360度は含まない）に展開した水平角
+    fn full_c_angles(&self) -> Vec<f64> {
+        let symmetry = self.symmetry();
+        let mirrored = |h: f64| match symmetry {
+            Symmetry::Quadrant => vec![h, 180.0 - h, 180.0 + h, 360.0 - h],
+            Symmetry::C0C180 => vec![h, 360.0 - h],
+            Symmetry::C90C270 => vec![h, 540.0 - h],
+            Symmetry::Axial | Symmetry::None => vec![h],
+        };
+        let mut angles: Vec<f64> = self
+            .horizontal_angles
+            .iter()
+            .flat_map(|&h| mirrored(h))
+            .map(|c| c.rem_euclid(360.0))
+            .collect();
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        angles.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+        angles
+    }
+
+    /// LDTに出力する水平角（軸対称の場合は0度のみ、それ以外は0〜360度に展開）
+    fn c_angles(&self) -> Vec<f64> {
+        if self.symmetry() == Symmetry::Axial {
+            vec![0.0]
+        } else {
+            self.full_c_angles()
+        }
+    }
+
+    /// 絶対測光（ランプ光束が負）か
+    ///
+    /// LED器具等のランプと器具を分けて測れない測光では、ランプ光束を -1 とし、
+    /// 光度は器具としての実測値になる。
+    pub fn is_absolute(&self) -> bool {
+        self.lumens_per_lamp <= 0.0
+    }
+
+    /// ランプ数（0と記載されたファイルは1灯とみなす）
+    pub fn lamps(&self) -> u32 {
+        self.lamp_count.max(1)
+    }
+
+    /// ランプ光束の合計（lm。ランプ1灯あたりの光束 × ランプ数。絶対測光の場合は None）
+    pub fn lamp_lumens(&self) -> Option<f64> {
+        (!self.is_absolute()).then(|| self.lumens_per_lamp * self.lamps() as f64)
+    }
+
+    /// 器具効率（%。器具光束 / ランプ光束の合計。絶対測光の場合は None）
+    pub fn light_output_ratio(&self) -> Option<f64> {
+        let lamp_lumens = self.lamp_lumens().filter(|lm| *lm > 0.0)?;
+        Some(self.luminaire_lumens() / lamp_lumens * 100.0)
+    }
+
+    /// 固有エネルギー消費効率（lm/W。器具光束 / 入力電力。入力電力の記載がない場合は None）
+    ///
+    /// 相対測光・絶対測光のどちらも、光度分布を積分した器具光束を使用する
+    /// （ランプ光束 / 入力電力 ではない）。
+    pub fn efficacy(&self) -> Option<f64> {
+        if self.input_watts <= 0.0 {
+            return None;
+        }
+        let lumens = self.luminaire_lumens();
+        (lumens > 0.0).then(|| lumens / self.input_watts)
+    }
+
+    /// 光度分布を積分した器具光束（lm）
+    pub fn luminaire_lumens(&self) -> f64 {
+        self.luminaire_flux(&self.c_angles()).0
+    }
+
+    /// ビーム角（度。光度が最大光度の50%まで下がる鉛直角の2倍。水平角ごとの値の平均）
+    ///
+    /// 最大光度が真下方向付近にある器具（ダウンライト・スポットライト等）を想定する。
+    /// どの水平角でも光度が50%まで下がらない場合は None。
+    pub fn beam_angle(&self) -> Option<f64> {
+        self.spread_angle(0.5)
+    }
+
+    /// 1/10ビーム角（フィールド角。度。光度が最大光度の10%まで下がる鉛直角の2倍）
+    pub fn field_angle(&self) -> Option<f64> {
+        self.spread_angle(0.1)
+    }
+
+    /// 光度が最大光度の `ratio` 倍まで下がる鉛直角の2倍（度。水平角ごとの値の平均）
+    fn spread_angle(&self, ratio: f64) -> Option<f64> {
+        let angles: Vec<f64> = self
+            .candela
+            .iter()
+            .filter_map(|values| falloff_angle(&self.vertical_angles, values, ratio))
+            .collect();
+        if angles.is_empty() {
+            return None;
+        }
+        Some(2.0 * angles.iter().sum::<f64>() / angles.len() as f64)
+    }
+
+    /// 最大光度（cd）とその鉛直角・水平角（度）
+    pub fn max_intensity(&self) -> (f64, f64, f64) {
+        let mut max = (0.0, 0.0, 0.0);
+        for (values, &h) in self.candela.iter().zip(&self.horizontal_angles) {
+            for (&value, &v) in values.iter().zip(&self.vertical_angles) {
+                if value * self.multiplier > max.0 {
+                    max = (value * self.multiplier, v, h);
+                }
+            }
+        }
+        max
+    }
+
+    /// 配光曲線の表示用データ（鉛直角を `step` 度ごとに間引く。前後の角度の間は線形補間）
+    pub fn polar_curve(&self, step: f64) -> PolarCurve {
+        let (Some(&first), Some(&last)) =
+            (self.vertical_angles.first(), self.vertical_angles.last())
+        else {
+            return PolarCurve {
+                vertical_angles: Vec::new(),
+                planes: Vec::new(),
+            };
+        };
+        let step = if step > 0.0 { step } else { last - first };
+        let mut vertical_angles = Vec::new();
+        let mut angle = first;
+        while angle < last - 1e-6 {
+            vertical_angles.push(angle);
+            angle += step;
+        }
+        vertical_angles.push(last);
+        let planes = POLAR_PLANES
+            .iter()
+            .map(|&c| {
+                let values = &self.candela[self.plane_index(c)];
+                PolarPlane {
+                    horizontal_angle: c,
+                    candela: vertical_angles
+                        .iter()
+                        .map(|&g| interpolate(&self.vertical_angles, values, g) * self.multiplier)
+                        .collect(),
+                }
+            })
+            .collect();
+        PolarCurve {
+            vertical_angles,
+            planes,
+        }
+    }
+
+    /// 配光データの概要（配光曲線は鉛直角を `step` 度ごとに間引く）
+    pub fn summary(&self, step: f64) -> IesSummary {
+        let (max_candela, max_candela_vertical_angle, max_candela_horizontal_angle) =
+            self.max_intensity();
+        IesSummary {
+            analysis: self.analyze(),
+            max_candela,
+            max_candela_vertical_angle,
+            max_candela_horizontal_angle,
+            field_angle: self.field_angle(),
+            symmetry: self.symmetry(),
+            vertical_angle_count: self.vertical_angles.len(),
+            horizontal_angle_count: self.horizontal_angles.len(),
+            polar_curve: self.polar_curve(step),
+        }
+    }
+
+    /// 配光データの解析結果
+    pub fn analyze(&self) -> IesAnalysis {
+        let zonal_lumens = self.zonal_lumens();
+        IesAnalysis {
+            lumens: self.luminaire_lumens(),
+            input_watts: Some(self.input_watts).filter(|w| *w > 0.0),
+            absolute_photometry: self.is_absolute(),
+            lamp_count: self.lamps(),
+            lamp_lumens: self.lamp_lumens(),
+            light_output_ratio: self.light_output_ratio(),
+            efficacy: self.efficacy(),
+            beam_angle: self.beam_angle(),
+            zonal_lumens,
+            bug_rating: zonal_lumens.bug_rating(),
+        }
+    }
+
+    /// ヘッダーのキーワード（`[LAMPCAT]` `[LAMP]` `[MORE]` 等）に記載された色温度（K）
+    pub fn color_temperature(&self) -> Option<u32> {
+        let preferred = ["LAMPCAT", "LAMP", "LUMCAT", "LUMINAIRE", "MORE"];
+        preferred
+            .iter()
+            .filter_map(|key| self.keywords.get(*key))
+            .chain(self.keywords.values())
+            .filter_map(|value| CCT_RE.captures(value)?.get(1)?.as_str().parse().ok())
+            .find(|kelvin| (1000..=10000).contains(kelvin))
+    }
+
+    /// ファイル名テンプレートに使用する値（色温度・ビーム角・器具光束）
+    pub fn filename_values(&self) -> PhotometricValues {
+        PhotometricValues {
+            cct: self.color_temperature(),
+            beam_angle: self.beam_angle(),
+            lumens: Some(self.luminaire_lumens()).filter(|lm| *lm > 0.0),
+        }
+    }
+
+    /// 光軸（真下）方向の光度（cd。水平角ごとの値の平均。鉛直角0度のデータがない場合は None）
+    pub fn center_intensity(&self) -> Option<f64> {
+        if self.vertical_angles.first() != Some(&0.0) || self.candela.is_empty() {
+            return None;
+        }
+        let sum: f64 = self.candela.iter().filter_map(|v| v.first()).sum();
+        Some(sum / self.candela.len() as f64 * self.multiplier)
+    }
+
+    /// 照射円錐図（距離 `distances`（m）ごとの光軸上の照度とビーム径）
+    ///
+    /// 照度は逆二乗の法則（E = I / d²）、ビーム径は 2 × d × tan(ビーム角 / 2) で求める。
+    /// 0以下の距離は除く。鉛直角0度のデータがない（真下を照らさない）器具はエラー。
+    pub fn cone_diagram(&self, distances: &[f64]) -> Result<ConeDiagram, String> {
+        let center_intensity = self
+            .center_intensity()
+            .ok_or("IES file has no intensity at nadir (vertical angle 0)")?;
+        let beam_angle = self.beam_angle();
+        let points = distances
+            .iter()
+            .filter(|&&d| d > 0.0)
+            .map(|&distance| ConePoint {
+                distance,
+                center_illuminance: center_intensity / (distance * distance),
+                beam_diameter: beam_angle
+                    .map(|angle| 2.0 * distance * (angle / 2.0).to_radians().tan()),
+            })
+            .collect();
+        Ok(ConeDiagram {
+            center_intensity,
+            beam_angle,
+            points,
+        })
+    }
+
+    /// 区分ごとの光束（ゾーナル光束）
+    ///
+    /// 各C面が受け持つ水平角の範囲を前方・後方に振り分け、鉛直角の区分の境界では光度を
+    /// 線形補間して分割する。
+    pub fn zonal_lumens(&self) -> ZonalLumens {
+        let c_angles = self.c_angles();
+        let n = c_angles.len();
+        let mut zones = ZonalLumens::default();
+        for (i, &c) in c_angles.iter().enumerate() {
+            // C面が受け持つ水平角の範囲（隣の面との中点まで）
+            let (lo, hi) = if n == 1 {
+                (c - 180.0, c + 180.0)
+            } else {
+                let prev = c_angles[(i + n - 1) % n];
+                let next = c_angles[(i + 1) % n];
+                (
+                    c - (c - prev).rem_euclid(360.0) / 2.0,
+                    c + (next - c).rem_euclid(360.0) / 2.0,
+                )
+            };
+            if hi <= lo {
+                continue;
+            }
+            let front: f64 = [-360.0, 0.0, 360.0]
+                .iter()
+                .map(|offset| (hi.min(offset + 180.0) - lo.max(*offset)).max(0.0))
+                .sum();
+            let front_ratio = front / (hi - lo);
+            let width = (hi - lo).to_radians();
+
+            let values = &self.candela[self.plane_index(c)];
+            for j in 1..self.vertical_angles.len() {
+                let (g0, g1) = (self.vertical_angles[j - 1], self.vertical_angles[j]);
+                if g1 <= g0 {
+                    continue;
+                }
+                let (v0, v1) = (values[j - 1], values[j]);
+                let at = |g: f64| v0 + (v1 - v0) * (g - g0) / (g1 - g0);
+                let mut cuts = vec![g0];
+                cuts.extend(ZONE_BOUNDARIES.iter().filter(|&&b| g0 < b && b < g1));
+                cuts.push(g1);
+                for pair in cuts.windows(2) {
+                    let (a, b) = (pair[0], pair[1]);
+                    let solid = width * (a.to_radians().cos() - b.to_radians().cos());
+                    let flux = (at(a) + at(b)) / 2.0 * self.multiplier * solid;
+                    zones.add(
+                        (a + b) / 2.0,
+                        flux * front_ratio,
+                        flux * (1.0 - front_ratio),
+                    );
+                }
+            }
+        }
+        zones
+    }
+
+    /// 光度分布を積分した器具光束（lm）と、そのうち下方（γ < 90度）の光束
+    fn luminaire_flux(&self, c_angles: &[f64]) -> (f64, f64) {
+        let mut total = 0.0;
+        let mut downward = 0.0;
+        for (i, &c) in c_angles.iter().enumerate() {
+            // C面が受け持つ角度幅（隣の面との中点まで）
+            let width = if c_angles.len() == 1 {
+                2.0 * PI
+            } else {
+                let prev = c_angles[(i + c_angles.len() - 1) % c_angles.len()];
+                let next = c_angles[(i + 1) % c_angles.len()];
+                ((next - prev).rem_euclid(360.0) / 2.0).to_radians()
+            };
+            let values = &self.candela[self.plane_index(c)];
+            for j in 1..self.vertical_angles.len() {
+                let (g0, g1) = (self.vertical_angles[j - 1], self.vertical_angles[j]);
+                let solid = width * (g0.to_radians().cos() - g1.to_radians().cos());
+                let flux = (values[j - 1] + values[j]) / 2.0 * self.multiplier * solid;
+                total += flux;
+                if g1 <= 90.0 {
+                    downward += flux;
+                }
+            }
+        }
+        (total, downward)
+    }
+
+    /// EULUMDAT（LDT）形式に変換
+    ///
+    /// `name` は器具名（LDTの9行目）、`file_name` はLDTのファイル名（11行目）に使用する。
+    /// 光度は1000lmあたりの値（cd/klm）に換算する。
+    /// 絶対測光の場合は器具光束をランプ光束とし（器具効率100%）、DIALux・Reluxの慣例に従い
+    /// ランプ数を負の値で出力する。複数灯の器具はランプ光束の合計で換算する。
+    pub fn to_eulumdat(&self, name: &str, file_name: &str) -> String {
+        let symmetric = self.symmetry() == Symmetry::Axial;
+        let c_angles = self.c_angles();
+        let (luminaire_flux, downward_flux) = self.luminaire_flux(&c_angles);
+        // 絶対測光（ランプ光束が負）の場合は器具光束をランプ光束とみなす
+        let lamp_flux = self.lamp_lumens().unwrap_or(luminaire_flux);
+        let lamps = if self.is_absolute() {
+            -(self.lamps() as i64)
+        } else {
+            self.lamps() as i64
+        };
+        let to_cd_klm = if lamp_flux > 0.0 {
+            self.multiplier * 1000.0 / lamp_flux
+        } else {
+            0.0
+        };
+        let percent = |value: f64| {
+            if lamp_flux > 0.0 && luminaire_flux > 0.0 {
+                value
+            } else {
+                0.0
+            }
+        };
+        let mm = |m: f64| (m.abs() * 1000.0).round();
+        let keyword = |key: &str| self.keywords.get(key).map(String::as_str).unwrap_or("");
+        let spacing = |angles: &[f64]| match angles {
+            [first, second, ..] => {
+                let step = second - first;
+                let equidistant = angles
+                    .windows(2)
+                    .all(|w| ((w[1] - w[0]) - step).abs() < 1e-6);
+                if equidistant {
+                    step
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        // 円形の器具（幅・長さが負）は直径を長さとし、幅を0とする
+        let (length_mm, width_mm) = if self.width_m < 0.0 || self.length_m < 0.0 {
+            (mm(self.width_m).max(mm(self.length_m)), 0.0)
+        } else {
+            (mm(self.length_m), mm(self.width_m))
+        };
+
+        let mut lines: Vec<String> = vec![
+            keyword("MANUFAC").to_string(),
+            if symmetric { "1" } else { "3" }.to_string(),
+            if symmetric { "1" } else { "0" }.to_string(),
+            c_angles.len().to_string(),
+            format_number(spacing(&c_angles)),
+            self.vertical_angles.len().to_string(),
+            format_number(spacing(&self.vertical_angles)),
+            keyword("TEST").to_string(),
+            name.to_string(),
+            keyword("LUMCAT").to_string(),
+            file_name.to_string(),
+            keyword("ISSUEDATE").to_string(),
+            format_number(length_mm),
+            format_number(width_mm),
+            format_number(mm(self.height_m)),
+            format_number(length_mm),
+            format_number(width_mm),
+        ];
+        // 発光部の高さ（C0・C90・C180・C270面）
+        lines.extend((0..4).map(|_| format_number(mm(self.height_m))));
+        lines.extend([
+            format_number(percent(downward_flux / luminaire_flux * 100.0)),
+            format_number(percent(luminaire_flux / lamp_flux * 100.0)),
+            "1".to_string(),
+            "0".to_string(),
+            // ランプ（1組）
+            "1".to_string(),
+            lamps.to_string(),
+            keyword("LAMP").to_string(),
+            format_number(lamp_flux.round()),
+            keyword("COLORTEMP").to_string(),
+            keyword("CRI").to_string(),
+            format_number(self.input_watts),
+        ]);
+        // 照明率の直接比（室指数 0.6〜5）は計算しない
+        lines.extend((0..10).map(|_| "0".to_string()));
+        lines.extend(c_angles.iter().map(|&c| format_number(c)));
+        lines.extend(self.vertical_angles.iter().map(|&g| format_number(g)));
+        for &c in &c_angles {
+            let values = &self.candela[self.plane_index(c)];
+            lines.extend(values.iter().map(|&v| format_number(v * to_cd_klm)));
+        }
+
+        let mut ldt = String::new();
+        for line in lines {
+            // 文字列の項目は78文字まで
+            ldt.extend(line.chars().take(78));
+            ldt.push_str("\r\n");
+        }
+        ldt
+    }
+
+    /// IES（LM-63-2002）形式で出力
+    ///
+    /// 寸法はメートル単位、バラスト係数は倍率に含めて1とする。数値は1行に10個までとする。
+    pub fn to_ies(&self) -> String {
+        let mut lines = vec!["IESNA:LM-63-2002".to_string()];
+        lines.extend(
+            self.keywords
+                .iter()
+                .map(|(key, value)| format!("[{}] {}", key, value)),
+        );
+        lines.push("TILT=NONE".to_string());
+        let lumens_per_lamp = if self.is_absolute() {
+            -1.0
+        } else {
+            self.lumens_per_lamp
+        };
+        lines.push(
+            [
+                self.lamps() as f64,
+                lumens_per_lamp,
+                self.multiplier,
+                self.vertical_angles.len() as f64,
+                self.horizontal_angles.len() as f64,
+                1.0,
+                2.0,
+                self.width_m,
+                self.length_m,
+                self.height_m,
+            ]
+            .map(format_number)
+            .join(" "),
+        );
+        lines.push(format!("1 1 {}", format_number(self.input_watts)));
+        let rows = std::iter::once(&self.vertical_angles)
+            .chain(std::iter::once(&self.horizontal_angles))
+            .chain(&self.candela);
+        for values in rows {
+            for chunk in values.chunks(10) {
+                let chunk: Vec<String> = chunk.iter().map(|&v| format_number(v)).collect();
+                lines.push(chunk.join(" "));
+            }
+        }
+
+        let mut ies = lines.join("\r\n");
+        ies.push_str("\r\n");
+        ies
+    }
+}
+
+/// 光度が最大光度の `ratio` 倍まで下がる鉛直角（度。前後の角度の間は線形補間）
+fn falloff_angle(angles: &[f64], values: &[f64], ratio: f64) -> Option<f64> {
+    let (peak, &max) = values
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    if max <= 0.0 {
+        return None;
+    }
+    let threshold = max * ratio;
+    let j = (peak + 1..values.len().min(angles.len())).find(|&j| values[j] <= threshold)?;
+    let (g0, g1) = (angles[j - 1], angles[j]);
+    let (v0, v1) = (values[j - 1], values[j]);
+    Some(g0 + (v0 - threshold) / (v0 - v1) * (g1 - g0))
+}
+
+/// 鉛直角 `angle` の光度（前後の角度の間は線形補間。範囲外は端の値）
+fn interpolate(angles: &[f64], values: &[f64], angle: f64) -> f64 {
+    let n = values.len().min(angles.len());
+    if n == 0 {
+        return 0.0;
+    }
+    match (0..n).find(|&j| angles[j] >= angle) {
+        Some(0) => values[0],
+        Some(j) => {
+            let (g0, g1) = (angles[j - 1], angles[j]);
+            let (v0, v1) = (values[j - 1], values[j]);
+            v0 + (angle - g0) / (g1 - g0) * (v1 - v0)
+        }
+        None => values[n - 1],
+    }
+}
+
+/// IESファイルを読み込み、ファイル名テンプレートに使用する値を取得（読み込めない場合は None）
+pub fn read_filename_values(path: &Path) -> Option<PhotometricValues> {
+    let bytes = std::fs::read(path).ok()?;
+    parse_ies(&String::from_utf8_lossy(&bytes))
+        .ok()
+        .map(|ies| ies.filename_values())
+}
+
+/// 変換後のファイルのパス（変換元と同じ名前で、LDTはIESに、それ以外はLDTに拡張子を変える）
+pub fn converted_path(path: &Path) -> PathBuf {
+    let is_ldt = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ldt"));
+    path.with_extension(if is_ldt { "ies" } else { "ldt" })
+}
+
+/// 配光データのファイルを変換して `dest` に保存
+///
+/// `dest` の拡張子が `.ldt` の場合はIESファイルとして読み込んでEULUMDAT形式に、
+/// それ以外はLDTとして読み込んでIES形式に変換する。`name` はLDTの器具名に使用する。
+pub fn convert_file(source: &Path, dest: &Path, name: &str) -> Result<(), String> {
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("Failed to read photometry file: {}", e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let to_ldt = dest
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ldt"));
+    let converted = if to_ldt {
+        let file_name = dest
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        parse_ies(&text)?.to_eulumdat(name, &file_name)
+    } else {
+        parse_ldt(&text)?.to_ies()
+    };
+    std::fs::write(dest, converted).map_err(|e| format!("Failed to write converted file: {}", e))
+}
+
+/// 数値を小数点以下2桁までで出力（末尾の0は省く）
+fn format_number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IES: &str = "IESNA:LM-63-2002\r\n[TEST] T-001\r\n[MANUFAC] KOIZUMI\r\n\
+        [LUMCAT] AD12345\r\nTILT=NONE\r\n\
+        1 1000 1 3 1 1 2 -0.1 0 0\r\n1 1 12.5\r\n\
+        0 45 90\r\n0\r\n300 200 0\r\n";
+
+    #[test]
+    fn test_parse_ies() {
+        let ies = parse_ies(IES).unwrap();
+        assert_eq!(ies.keywords["LUMCAT"], "AD12345");
+        assert_eq!(ies.lumens_per_lamp, 1000.0);
+        assert_eq!(ies.vertical_angles, vec![0.0, 45.0, 90.0]);
+        assert_eq!(ies.candela, vec![vec![300.0, 200.0, 0.0]]);
+        assert_eq!(ies.input_watts, 12.5);
+        assert!(ies.luminaire_lumens() > 0.0);
+        // 300cd → 150cd になるのは45度と90度の間（56.25度）
+        assert_eq!(ies.beam_angle(), Some(112.5));
+
+        assert!(parse_ies("IESNA:LM-63-2002\r\n[TEST] x\r\n").is_err());
+        assert!(parse_ies(&IES.replace("1 1 2 -0.1", "1 2 2 -0.1")).is_err());
+    }
+
+    #[test]
+    fn test_validate_ies() {
+        let metadata = validate_ies(IES.as_bytes()).unwrap();
+        assert_eq!(metadata.format.as_deref(), Some("IESNA:LM-63-2002"));
+        assert_eq!(metadata.photometric_type, "C");
+        assert_eq!(metadata.input_watts, Some(12.5));
+        assert_eq!(
+            (
+                metadata.vertical_angle_count,
+                metadata.horizontal_angle_count
+            ),
+            (3, 1)
+        );
+        assert!(metadata.lumens.is_some_and(|lm| lm > 0.0));
+
+        // タイプBはランプ光束の合計
+        let type_b = validate_ies(IES.replace("1 1 2 -0.1", "1 2 2 -0.1").as_bytes()).unwrap();
+        assert_eq!(type_b.photometric_type, "B");
+        assert_eq!(type_b.lumens, Some(1000.0));
+
+        // エラーページ・途中で切れたファイル
+        let error = validate_ies(b"<!DOCTYPE html><html><body>404</body></html>").unwrap_err();
+        assert_eq!(
+            error,
+            "Downloaded file is not a valid IES file: received an HTML page"
+        );
+        let truncated = &IES[..IES.len() - "0\r\n300 200 0\r\n".len()];
+        let error = validate_ies(truncated.as_bytes()).unwrap_err();
+        assert!(error.ends_with("expected 7 angle and candela values, found 3"));
+        assert!(validate_ies(b"").is_err());
+        assert!(validate_ies(b"IESNA:LM-63-2002\r\n[TEST] x\r\n").is_err());
+    }
+
+    #[test]
+    fn test_filename_values() {
+        let mut ies = parse_ies(IES).unwrap();
+        assert_eq!(ies.color_temperature(), None);
+        ies.keywords
+            .insert("MORE".to_string(), "Ra83 2700 K 15°".to_string());
+        let values = ies.filename_values();
+        assert_eq!(values.cct, Some(2700));
+        assert_eq!(values.beam_angle, Some(112.5));
+        assert!(values.lumens.is_some_and(|lm| lm > 0.0));
+    }
+
+    #[test]
+    fn test_cone_diagram() {
+        let ies = parse_ies(IES).unwrap();
+        let cone = ies.cone_diagram(&[2.0, 0.0, 3.0]).unwrap();
+        assert_eq!(cone.center_intensity, 300.0);
+        assert_eq!(cone.points.len(), 2);
+        assert_eq!(cone.points[0].center_illuminance, 75.0);
+        assert!((cone.points[1].center_illuminance - 300.0 / 9.0).abs() < 1e-9);
+        // ビーム角112.5度 → 2m で 2 × 2 × tan(56.25°)
+        let diameter = cone.points[0].beam_diameter.unwrap();
+        assert!((diameter - 4.0 * 56.25_f64.to_radians().tan()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_summary() {
+        let ies = parse_ies(IES).unwrap();
+        let summary = ies.summary(15.0);
+        assert_eq!(summary.analysis, ies.analyze());
+        assert_eq!(summary.max_candela, 300.0);
+        assert_eq!(
+            (
+                summary.max_candela_vertical_angle,
+                summary.max_candela_horizontal_angle
+            ),
+            (0.0, 0.0)
+        );
+        assert_eq!(summary.symmetry, Symmetry::Axial);
+        // 300cd → 30cd になるのは45度と90度の間（83.25度）
+        assert!((summary.field_angle.unwrap() - 166.5).abs() < 1e-9);
+
+        let curve = &summary.polar_curve;
+        assert_eq!(
+            curve.vertical_angles,
+            vec![0.0, 15.0, 30.0, 45.0, 60.0, 75.0, 90.0]
+        );
+        // 軸対称の器具は4面とも同じ光度
+        assert_eq!(curve.planes.len(), 4);
+        assert!(curve
+            .planes
+            .iter()
+            .all(|p| p.candela == curve.planes[0].candela));
+        assert_eq!(curve.planes[0].candela[3], 200.0);
+        assert!((curve.planes[0].candela[1] - 300.0 + 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(curve.planes[0].candela[6], 0.0);
+    }
+
+    #[test]
+    fn test_zonal_lumens() {
+        let ies = parse_ies(IES).unwrap();
+        let zones = ies.zonal_lumens();
+        let total = zones.front_low
+            + zones.front_mid
+            + zones.front_high
+            + zones.front_very_high
+            + zones.back_low
+            + zones.back_mid
+            + zones.back_high
+            + zones.back_very_high
+            + zones.uplight_low
+            + zones.uplight_high;
+        // 区分の境界で分割しても器具光束とほぼ一致する
+        assert!((total - ies.luminaire_lumens()).abs() / total < 0.05);
+        // 軸対称のため前方・後方は同じ、上方光束はない
+        assert!((zones.front_low - zones.back_low).abs() < 1e-9);
+        assert_eq!(zones.uplight_low + zones.uplight_high, 0.0);
+    }
+
+    #[test]
+    fn test_bug_rating() {
+        let zones = ZonalLumens {
+            back_low: 400.0,
+            uplight_high: 20.0,
+            front_high: 6000.0,
+            ..Default::default()
+        };
+        let rating = zones.bug_rating();
+        assert_eq!(
+            rating,
+            BugRating {
+                backlight: 1,
+                uplight: 2,
+                glare: 3
+            }
+        );
+        assert_eq!(rating.to_string(), "B1-U2-G3");
+        assert_eq!(ZonalLumens::default().bug_rating().to_string(), "B0-U0-G0");
+    }
+
+    #[test]
+    fn test_to_eulumdat() {
+        let ies = parse_ies(IES).unwrap();
+        let ldt = ies.to_eulumdat("AD12345 ダウンライト", "AD12345.ldt");
+        let lines: Vec<&str> = ldt.split("\r\n").collect();
+        assert_eq!(lines[0], "KOIZUMI");
+        // 軸対称: Ityp 1・Isym 1・C面1つ
+        assert_eq!(&lines[1..4], ["1", "1", "1"]);
+        assert_eq!(lines[5], "3");
+        assert_eq!(lines[6], "45");
+        assert_eq!(lines[8], "AD12345 ダウンライト");
+        // 直径100mm
+        assert_eq!(&lines[12..14], ["100", "0"]);
+        // 下方光束比100%
+        assert_eq!(lines[21], "100");
+        // ランプ光束・電力
+        assert_eq!(lines[28], "1000");
+        assert_eq!(lines[31], "12.5");
+        // 光度（cd/klm）
+        assert_eq!(&lines[lines.len() - 4..], ["300", "200", "0", ""]);
+    }
+
+    #[test]
+    fn test_absolute_and_multi_lamp() {
+        // 2灯 × 500lm（相対測光）
+        let ies = parse_ies(&IES.replace("1 1000 1 3", "2 500 1 3")).unwrap();
+        assert!(!ies.is_absolute());
+        assert_eq!(ies.lamp_lumens(), Some(1000.0));
+        let lumens = ies.luminaire_lumens();
+        let lor = ies.light_output_ratio().unwrap();
+        assert!((lor - lumens / 1000.0 * 100.0).abs() < 1e-9);
+        assert!((ies.efficacy().unwrap() - lumens / 12.5).abs() < 1e-9);
+        let ldt = ies.to_eulumdat("AD12345", "AD12345.ldt");
+        let lines: Vec<&str> = ldt.split("\r\n").collect();
+        assert_eq!(lines[26], "2");
+        assert_eq!(lines[28], "1000");
+
+        // 絶対測光（ランプ光束 -1）
+        let ies = parse_ies(&IES.replace("1 1000 1 3", "1 -1 1 3")).unwrap();
+        assert!(ies.is_absolute());
+        assert_eq!(ies.lamp_lumens(), None);
+        assert_eq!(ies.light_output_ratio(), None);
+        assert!(ies.efficacy().is_some_and(|e| e > 0.0));
+        let analysis = ies.analyze();
+        assert!(analysis.absolute_photometry);
+        assert_eq!(analysis.lamp_lumens, None);
+        let ldt = ies.to_eulumdat("AD12345", "AD12345.ldt");
+        let lines: Vec<&str> = ldt.split("\r\n").collect();
+        // ランプ数は負の値、ランプ光束は器具光束、器具効率100%
+        assert_eq!(lines[26], "-1");
+        assert_eq!(lines[28], format!("{:.0}", ies.luminaire_lumens()));
+        assert_eq!(lines[22], "100");
+    }
+
+    #[test]
+    fn test_ldt_to_ies() {
+        let ies = parse_ies(IES).unwrap();
+        let ldt = ies.to_eulumdat("AD12345 ダウンライト", "AD12345.ldt");
+
+        let converted = parse_ldt(&ldt).unwrap();
+        assert_eq!(converted.keywords["MANUFAC"], "KOIZUMI");
+        assert_eq!(converted.keywords["LUMINAIRE"], "AD12345 ダウンライト");
+        assert_eq!(converted.lamp_lumens(), Some(1000.0));
+        assert_eq!(converted.input_watts, 12.5);
+        assert_eq!(converted.horizontal_angles, vec![0.0]);
+        assert_eq!(converted.candela, vec![vec![300.0, 200.0, 0.0]]);
+        // 円形（直径100mm）
+        assert_eq!(converted.width_m, -0.1);
+
+        // IESとして出力したものを読み込み直しても配光データは変わらない
+        let reparsed = parse_ies(&converted.to_ies()).unwrap();
+        assert_eq!(reparsed.candela, converted.candela);
+        assert_eq!(reparsed.vertical_angles, converted.vertical_angles);
+        assert!((reparsed.luminaire_lumens() - ies.luminaire_lumens()).abs() < 1e-6);
+        assert_eq!(reparsed.keywords["OTHER"], "Converted from EULUMDAT");
+
+        // 小数点にカンマを使ったLDT
+        let comma = ldt.replace("12.5", "12,5");
+        assert_eq!(parse_ldt(&comma).unwrap().input_watts, 12.5);
+        assert!(parse_ldt("not an ldt file").is_err());
+    }
+
+    #[test]
+    fn test_convert_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("A01_AD12345.ies");
+        std::fs::write(&source, IES).unwrap();
+
+        let ldt = converted_path(&source);
+        assert_eq!(ldt, dir.path().join("A01_AD12345.ldt"));
+        convert_file(&source, &ldt, "AD12345").unwrap();
+        let text = std::fs::read_to_string(&ldt).unwrap();
+        assert_eq!(text.split("\r\n").nth(10), Some("A01_AD12345.ldt"));
+
+        // LDTからはIESに戻す
+        let ies = converted_path(&ldt);
+        assert_eq!(ies, source);
+        convert_file(&ldt, &ies, "AD12345").unwrap();
+        let reparsed = parse_ies(&std::fs::read_to_string(&ies).unwrap()).unwrap();
+        assert_eq!(reparsed.candela, vec![vec![300.0, 200.0, 0.0]]);
+
+        std::fs::write(&source, "<html></html>").unwrap();
+        assert!(convert_file(&source, &ldt, "AD12345").is_err());
+    }
+
+    #[test]
+    fn test_full_c_angles() {
+        let mut ies = parse_ies(IES).unwrap();
+        ies.horizontal_angles = vec![0.0, 45.0, 90.0];
+        ies.candela = vec![vec![1.0; 3], vec![2.0; 3], vec![3.0; 3]];
+        assert_eq!(
+            ies.full_c_angles(),
+            vec![0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0]
+        );
+        // 4象限対称の折り返し
+        assert_eq!(ies.plane_index(135.0), 1);
+        assert_eq!(ies.plane_index(270.0), 2);
+        assert_eq!(ies.plane_index(180.0), 0);
+    }
+}
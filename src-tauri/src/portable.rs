@@ -0,0 +1,200 @@
+//! ポータブルモード
+//!
+//! 実行ファイルと同じフォルダに `autosight.portable` を置くか、環境変数 `AUTOSIGHT_PORTABLE_DIR` を
+//! 指定すると、ストア（設定・ダウンロード履歴・プロジェクト）・キャッシュ・IESライブラリ・ログ等を
+//! OSのユーザーディレクトリではなくそのフォルダ配下に保存する。USBメモリや共有ドライブから起動し、
+//! インストールが制限されたクライアント先のPCでも同じ環境で使えるようにする。
+//!
+//! `autosight.portable` には保存先フォルダ（実行ファイルのフォルダからの相対パスも可）を1行で書ける。
+//! 空の場合は実行ファイルと同じフォルダの `AutoSight Data` を使う。相対パスにしておけば、
+//! USBメモリのドライブ文字が変わっても同じデータを使える。
+//!
+//! 保存先はプロセスの起動時に1度だけ決定し、切り替えはアプリの再起動後に反映する。
+//! WebView自体のデータ（localStorage等）はOSの既定の場所に残る。
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// 実行ファイルと同じフォルダに置く目印のファイル
+const MARKER_FILE: &str = "autosight.portable";
+/// 保存先フォルダを指定する環境変数
+const ENV_VAR: &str = "AUTOSIGHT_PORTABLE_DIR";
+/// 目印のファイルで保存先を指定しない場合のフォルダ名
+const DEFAULT_DIR: &str = "AutoSight Data";
+
+/// ポータブルモードの指定元
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortableSource {
+    /// 環境変数 `AUTOSIGHT_PORTABLE_DIR`
+    Environment,
+    /// 実行ファイルと同じフォルダの `autosight.portable`
+    Marker,
+}
+
+/// ポータブルモードの状態（`get_portable_info` の戻り値）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortableInfo {
+    /// 実行中のプロセスがポータブルモードか
+    pub enabled: bool,
+    /// 保存先フォルダ（ポータブルモードの場合）
+    pub root: Option<String>,
+    pub source: Option<PortableSource>,
+    /// 目印のファイルのパス（実行ファイルの場所が分からない場合は None）
+    pub marker_path: Option<String>,
+    /// 目印のファイルを変更したため、反映に再起動が必要か
+    pub restart_required: bool,
+}
+
+/// 起動時に決定した保存先フォルダ
+static ROOT: OnceLock<Option<(PathBuf, PortableSource)>> = OnceLock::new();
+
+/// 目印のファイルのパス
+fn marker_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(MARKER_FILE))
+}
+
+/// 目印のファイルの内容から保存先フォルダを決める
+fn resolve_marker(exe_dir: &Path, content: &str) -> PathBuf {
+    let dir = content.lines().next().unwrap_or_default().trim();
+    if dir.is_empty() {
+        exe_dir.join(DEFAULT_DIR)
+    } else {
+        // 絶対パスの場合は `join` がそのまま返す
+        exe_dir.join(dir)
+    }
+}
+
+fn detect() -> Option<(PathBuf, PortableSource)> {
+    if let Some(dir) = std::env::var_os(ENV_VAR).filter(|dir| !dir.is_empty()) {
+        return Some((PathBuf::from(dir), PortableSource::Environment));
+    }
+    let marker = marker_path()?;
+    let content = std::fs::read_to_string(&marker).ok()?;
+    Some((
+        resolve_marker(marker.parent()?, &content),
+        PortableSource::Marker,
+    ))
+}
+
+fn current() -> Option<&'static (PathBuf, PortableSource)> {
+    ROOT.get_or_init(detect).as_ref()
+}
+
+/// ポータブルモードの保存先フォルダ（ポータブルモードでない場合は None）
+pub fn root() -> Option<&'static Path> {
+    current().map(|(root, _)| root.as_path())
+}
+
+/// アプリのデータディレクトリ（クラッシュレポート・IESライブラリ・プロバイダールール等）
+pub fn data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    match root() {
+        Some(root) => Ok(root.join("data")),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e)),
+    }
+}
+
+/// アプリのキャッシュディレクトリ
+pub fn cache_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    match root() {
+        Some(root) => Ok(root.join("cache")),
+        None => app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| format!("Failed to resolve cache directory: {}", e)),
+    }
+}
+
+/// ログの保存先ディレクトリ
+pub fn log_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    match root() {
+        Some(root) => Ok(root.join("logs")),
+        None => app
+            .path()
+            .app_log_dir()
+            .map_err(|e| format!("Failed to resolve log directory: {}", e)),
+    }
+}
+
+/// ストアファイル名（フロントエンドのプロジェクトストアと共有）
+pub const STORE_NAME: &str = "autosight.store.json";
+
+/// tauri-plugin-store に渡すストアファイルのパス
+///
+/// ポータブルモードでない場合はファイル名のまま（プラグインがアプリのデータディレクトリに解決する）。
+pub fn store_path(name: &str) -> PathBuf {
+    match root() {
+        Some(root) => root.join("data").join(name),
+        None => PathBuf::from(name),
+    }
+}
+
+/// ポータブルモードの状態を取得
+pub fn info() -> PortableInfo {
+    let marker = marker_path();
+    let configured = marker.as_ref().is_some_and(|m| m.is_file());
+    let source = current().map(|(_, source)| *source);
+    PortableInfo {
+        enabled: source.is_some(),
+        root: root().map(|root| root.to_string_lossy().into_owned()),
+        source,
+        marker_path: marker.map(|m| m.to_string_lossy().into_owned()),
+        // 環境変数による指定は目印のファイルより優先する
+        restart_required: source != Some(PortableSource::Environment)
+            && configured != (source == Some(PortableSource::Marker)),
+    }
+}
+
+/// 次回の起動からポータブルモードを有効・無効にする
+///
+/// 実行ファイルと同じフォルダの目印のファイルを作成・削除する。`dir` は保存先フォルダ
+/// （実行ファイルのフォルダからの相対パスも可。省略時は `AutoSight Data`）。
+/// 既存のデータは移動しないため、必要に応じてユーザーがコピーする。
+pub fn set_enabled(enabled: bool, dir: Option<&str>) -> Result<PortableInfo, String> {
+    let marker = marker_path().ok_or("Failed to resolve executable directory")?;
+    if enabled {
+        let dir = dir.map(str::trim).unwrap_or_default();
+        if dir.contains(['\n', '\r']) {
+            return Err("Portable directory must be a single line".to_string());
+        }
+        std::fs::write(&marker, format!("{}\n", dir)).map_err(|e| {
+            format!(
+                "Failed to write {} (the folder may be read-only): {}",
+                marker.display(),
+                e
+            )
+        })?;
+    } else if marker.exists() {
+        std::fs::remove_file(&marker)
+            .map_err(|e| format!("Failed to remove {}: {}", marker.display(), e))?;
+    }
+    Ok(info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_marker() {
+        let exe_dir = Path::new("/media/usb/AutoSight");
+        assert_eq!(resolve_marker(exe_dir, ""), exe_dir.join(DEFAULT_DIR));
+        assert_eq!(resolve_marker(exe_dir, "\n"), exe_dir.join(DEFAULT_DIR));
+        assert_eq!(resolve_marker(exe_dir, "data\n"), exe_dir.join("data"));
+        assert_eq!(
+            resolve_marker(exe_dir, "../Shared/AutoSight \n# comment"),
+            exe_dir.join("../Shared/AutoSight")
+        );
+        assert_eq!(
+            resolve_marker(exe_dir, "/srv/autosight"),
+            PathBuf::from("/srv/autosight")
+        );
+    }
+}
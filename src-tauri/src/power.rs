@@ -0,0 +1,203 @@
+//! バッテリー駆動時の負荷の軽減
+//!
+//! ノートPCがバッテリーで動作している間は、製品情報の取得・ジョブファイルのダウンロード等の
+//! 同時実行数を設定の値まで下げ、一括ダウンロードではアイテムの間に待機を入れて通信量を抑える。
+//! 電源の状態はOSから取得する（Windows: `GetSystemPowerStatus`、macOS: `pmset`、
+//! Linux: `/sys/class/power_supply`）。取得できない環境ではAC電源とみなす。
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 電源の状態を再取得するまでの間隔
+const CACHE_TTL: Duration = Duration::from_secs(30);
+/// 同時実行数の上限（設定の `concurrency` と同じ）
+const MAX_CONCURRENCY: usize = 16;
+/// アイテム間の待機の上限（ミリ秒）
+const MAX_ITEM_DELAY_MS: u64 = 10_000;
+
+/// バッテリー駆動時の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct BatterySettings {
+    /// バッテリー駆動時に負荷を下げる（既定は有効）
+    pub enabled: bool,
+    /// バッテリー駆動時の同時実行数の上限
+    pub concurrency: usize,
+    /// バッテリー駆動時に一括ダウンロードのアイテムの間に入れる待機（ミリ秒）
+    pub item_delay_ms: u64,
+}
+
+impl Default for BatterySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            concurrency: 1,
+            item_delay_ms: 500,
+        }
+    }
+}
+
+impl BatterySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CONCURRENCY).contains(&self.concurrency) {
+            return Err(format!(
+                "battery.concurrency must be between 1 and {}",
+                MAX_CONCURRENCY
+            ));
+        }
+        if self.item_delay_ms > MAX_ITEM_DELAY_MS {
+            return Err(format!(
+                "battery.itemDelayMs must be at most {}",
+                MAX_ITEM_DELAY_MS
+            ));
+        }
+        Ok(())
+    }
+
+    /// 電源の状態に応じた同時実行数
+    pub fn concurrency(&self, concurrency: usize, on_battery: bool) -> usize {
+        if self.enabled && on_battery {
+            concurrency.min(self.concurrency).max(1)
+        } else {
+            concurrency.max(1)
+        }
+    }
+
+    /// 電源の状態に応じたアイテム間の待機
+    pub fn item_delay(&self, on_battery: bool) -> Option<Duration> {
+        (self.enabled && on_battery && self.item_delay_ms > 0)
+            .then(|| Duration::from_millis(self.item_delay_ms))
+    }
+}
+
+/// 電源の状態（`get_power_status` の戻り値）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    /// バッテリーで動作しているか（取得できない場合は None）
+    pub on_battery: Option<bool>,
+    /// 負荷を下げているか
+    pub reduced: bool,
+    /// 現在の同時実行数
+    pub concurrency: usize,
+}
+
+static CACHE: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+
+/// バッテリーで動作しているか（取得できない場合は None）
+///
+/// OSへの問い合わせは一定時間キャッシュする。
+pub fn on_battery() -> Option<bool> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((checked_at, value)) = *cache {
+        if checked_at.elapsed() < CACHE_TTL {
+            return value;
+        }
+    }
+    let value = detect();
+    *cache = Some((Instant::now(), value));
+    value
+}
+
+#[cfg(windows)]
+fn detect() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // SAFETY: 構造体はゼロ初期化でよく、関数は渡したポインタに書き込むだけ
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // ACLineStatus: 0 = バッテリー、1 = AC電源、255 = 不明
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `pmset -g batt` の出力の1行目（例: `Now drawing from 'Battery Power'`）を解釈
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let line = output.lines().next()?;
+    if line.contains("'Battery Power'") {
+        Some(true)
+    } else if line.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<bool> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut has_battery = false;
+    for entry in supplies.flatten() {
+        let read = |name: &str| {
+            std::fs::read_to_string(entry.path().join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            // 外部電源が1つでも接続されていればAC電源
+            "Mains" | "USB" if read("online") == "1" => return Some(false),
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    // バッテリーのないデスクトップPCは常にAC電源
+    Some(has_battery)
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn detect() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrency() {
+        let settings = BatterySettings::default();
+        assert_eq!(settings.concurrency(4, false), 4);
+        assert_eq!(settings.concurrency(4, true), 1);
+        assert_eq!(settings.item_delay(true), Some(Duration::from_millis(500)));
+        assert_eq!(settings.item_delay(false), None);
+
+        let disabled = BatterySettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.concurrency(4, true), 4);
+        assert_eq!(disabled.item_delay(true), None);
+
+        let invalid = BatterySettings {
+            concurrency: 0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        assert_eq!(
+            parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0\t85%"),
+            Some(true)
+        );
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'"), Some(false));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! 製品情報の先読み
+//!
+//! 器具リストの取り込み後、プレビュー表の全行の製品情報をバックグラウンドで並列に取得する。
+//! WebViewから1行ずつ順に取得するより早く表が埋まり、メーカーサイトへの同時アクセス数も
+//! 設定の同時実行数に制限される。取得した製品情報はアプリのキャッシュディレクトリに保存し、
+//! 一定時間内の再取得（取り込み直し・一括取得）ではメーカーサイトにアクセスしない。
+
+use crate::cache::{self, CacheScope};
+use crate::providers::ProductInfo;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+
+/// キャッシュの有効期間（時間）
+const CACHE_TTL_HOURS: i64 = 24;
+
+/// キャッシュファイルの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedProductInfo {
+    fetched_at: DateTime<Utc>,
+    info: ProductInfo,
+}
+
+/// キャッシュファイルのパス
+///
+/// 形式: {キャッシュディレクトリ}/product_info/{プロバイダーID}/{型番}.json
+pub fn cache_path(cache_dir: &Path, provider_id: &str, model_number: &str) -> PathBuf {
+    CacheScope::ProductInfo
+        .dir(cache_dir)
+        .join(provider_id)
+        .join(format!("{}.json", cache::file_stem(model_number)))
+}
+
+fn read_cached(path: &Path) -> Option<CachedProductInfo> {
+    let contents = std::fs::read(path).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+/// キャッシュ済みの製品情報を読み込む（有効期間を過ぎたものは None）
+pub fn load_cached(path: &Path, now: DateTime<Utc>) -> Option<ProductInfo> {
+    let cached = read_cached(path)?;
+    if now - cached.fetched_at >= Duration::hours(CACHE_TTL_HOURS) {
+        return None;
+    }
+    Some(cached.info)
+}
+
+/// キャッシュ済みの製品情報を有効期間に関わらず読み込む（オフラインモード用）
+pub fn load_stale(path: &Path) -> Option<ProductInfo> {
+    read_cached(path).map(|cached| cached.info)
+}
+
+/// 製品情報をキャッシュに保存（失敗しても取得結果には影響しない）
+pub fn store_cached(path: &Path, info: &ProductInfo) {
+    let cached = CachedProductInfo {
+        fetched_at: Utc::now(),
+        info: info.clone(),
+    };
+    let Ok(json) = serde_json::to_vec(&cached) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, json);
+}
+
+/// 先読みの完了通知（`product-info-prefetch-finished` イベントのペイロード）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchSummary {
+    /// 通知した行数
+    pub total: usize,
+    /// キャッシュから返した行数
+    pub cached: usize,
+    /// 取得に失敗した行数
+    pub failed: usize,
+}
+
+/// 実行中の先読み（Tauriの管理状態として保持する）
+#[derive(Default)]
+pub struct PrefetchState {
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PrefetchState {
+    /// 先読みを開始（実行中の先読みがあれば中止する）
+    pub fn start(&self, task: JoinHandle<()>) {
+        if let Ok(mut current) = self.task.lock() {
+            if let Some(previous) = current.replace(task) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// 実行中の先読みを中止
+    pub fn cancel(&self) {
+        if let Ok(mut current) = self.task.lock() {
+            if let Some(task) = current.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::Price;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = cache_path(temp.path(), "koizumi", "AD12345+XE92701");
+        assert!(path.ends_with("product_info/koizumi/AD12345_XE92701.json"));
+        assert!(load_cached(&path, Utc::now()).is_none());
+
+        let info = ProductInfo {
+            model_number: "AD12345".to_string(),
+            product_name: None,
+            price: Some(Price::jpy(12800)),
+            ies_file_url: Some("https://example.com/ies".to_string()),
+            image_url: None,
+            product_page_url: None,
+            accessories: vec![],
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
+        };
+        store_cached(&path, &info);
+
+        let cached = load_cached(&path, Utc::now()).unwrap();
+        assert_eq!(cached.price, Some(Price::jpy(12800)));
+        // 有効期間を過ぎたものは使用しない
+        assert!(load_cached(&path, Utc::now() + Duration::hours(CACHE_TTL_HOURS)).is_none());
+        assert!(load_stale(&path).is_some());
+    }
+}
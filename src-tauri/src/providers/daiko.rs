@@ -0,0 +1,855 @@
+//! 大光電機プロバイダー
+//!
+//! 大光電機 照明器具カタログ (lighting.daiko-denki.co.jp) からの
+//! 製品情報・IESファイル取得を担当する。
+//! 配光データはIESファイルが直接掲載されている場合と、シリーズごとのZIPで掲載されている場合が
+//! ある。ZIPの場合は品番に最も一致するIESファイルを取り出して保存する（PSUは使用しない）。
+
+use super::html::{self, DAIKO_IES_LINK, DAIKO_ITEM_LINK, DAIKO_PRODUCT_IMAGE, DAIKO_SPEC_LABEL};
+use super::{
+    check_url, download_product_image, fetch_content_length, filename_from_content_disposition,
+    not_available, not_provided, page_mentions, parse_price, price_from_candidates,
+    provider_outdated, report_phase, retry_incomplete, run_blocking, send_request, status_error,
+    verify_length, verify_written, AssetType, CancelToken, Diagnosis, Discontinuation,
+    DownloadPhase, DownloadResult, DownloadSource, ManufacturerProvider, Price, ProductCandidate,
+    ProductInfo, ProviderConfig, ResolvedIesUrl,
+};
+use crate::buffer;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::StatusCode;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+/// 大光電機の照明器具カタログの既定のベースURL（設定の `providers.daiko.baseUrl` で変更できる）
+const BASE_URL: &str = "https://lighting.daiko-denki.co.jp";
+
+/// 生産終了の表記（"この商品は生産終了品です" 等）
+static DISCONTINUED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"生産終了(?:品|商品|しました|いたしました)|廃番(?:品|となりました)").unwrap()
+});
+/// 後継品の品番（"後継品：LZD-93195XW" / "代替品は LZD-93195XW" 等）
+static SUCCESSOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:後継品|後継機種|代替品|推奨品)\s*(?:[:：]|は)?\s*([A-Z]{2,4}-?[0-9]{3,}[A-Z0-9]*)",
+    )
+    .unwrap()
+});
+
+/// 大光電機プロバイダー
+pub struct DaikoProvider {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl DaikoProvider {
+    pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
+        Self {
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+        }
+    }
+
+    /// 器具リストの型番をカタログの品番に正規化
+    /// 全角英数字・記号を半角にし、空白を除いて大文字にする（'-' は残す）
+    /// 例: "lzd-93195 xw" → "LZD-93195XW"
+    fn normalize_hinban(model_number: &str) -> String {
+        model_number
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                _ => c,
+            })
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+
+    /// ファイル名（パス・拡張子を除く）を品番と比較できる形にする
+    fn file_stem(name: &str) -> String {
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        let stem = Path::new(name)
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or(name);
+        Self::normalize_hinban(stem)
+    }
+
+    /// 製品詳細ページのURL
+    fn detail_url(&self, hinban: &str) -> String {
+        format!("{}/products/detail/?pno={}", self.base_url, hinban)
+    }
+
+    /// 相対URLを絶対URLにする
+    fn absolute_url(&self, url: &str) -> Option<String> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Some(url.to_string())
+        } else if let Some(rest) = url.strip_prefix("//") {
+            Some(format!("https://{}", rest))
+        } else if url.starts_with('/') {
+            Some(format!("{}{}", self.base_url, url))
+        } else {
+            None
+        }
+    }
+
+    /// 製品詳細ページのHTMLを取得
+    async fn fetch_detail_page(&self, hinban: &str) -> ProviderResult<String> {
+        let response = send_request(self.client.get(self.detail_url(hinban)))
+            .await
+            .map_err(|e| e.context("Detail request failed"))?;
+
+        // 掲載のない品番は 404 になる（後継品での取得し直しの対象）
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(status_error(
+                response.status(),
+                format!(
+                    "Detail page for {} returned status: {}",
+                    hinban,
+                    response.status()
+                ),
+            ));
+        }
+        response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))
+    }
+
+    /// 詳細ページのHTMLから配光データ（.ies / .zip）のURLをすべて抽出（重複排除）
+    fn extract_ies_urls(&self, html: &str) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for link in html::links(html, &DAIKO_IES_LINK) {
+            let path = link.href.split(['?', '#']).next().unwrap_or_default();
+            let lower = path.to_lowercase();
+            if !lower.ends_with(".ies") && !lower.ends_with(".zip") {
+                continue;
+            }
+            let Some(url) = self.absolute_url(&link.href) else {
+                continue;
+            };
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 配光データのURLから品番に対応するものを選ぶ
+    ///
+    /// ファイル名が品番と一致するもの、品番がファイル名で始まるもの（シリーズのZIP）の順に選ぶ。
+    /// 1つしかない場合はファイル名によらず使う。選べない場合は候補を含むエラーにする。
+    fn select_ies_url(urls: &[String], hinban: &str) -> ProviderResult<Option<String>> {
+        if let Some(url) = urls.iter().find(|url| Self::file_stem(url) == hinban) {
+            return Ok(Some(url.clone()));
+        }
+        if let Some(url) = urls
+            .iter()
+            .filter(|url| hinban.starts_with(&Self::file_stem(url)))
+            .max_by_key(|url| Self::file_stem(url).len())
+        {
+            return Ok(Some(url.clone()));
+        }
+        match urls {
+            [] => Ok(None),
+            [url] => Ok(Some(url.clone())),
+            _ => Err(ProviderError::new(
+                ErrorCode::IesAmbiguous,
+                format!(
+                    "Multiple IES files found for {}: {}",
+                    hinban,
+                    urls.join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// ZIP内のIESファイルから品番に最も一致するものを選ぶ
+    ///
+    /// ファイル名が品番と一致するもの、品番がファイル名で始まるもののうち最も長いものの順に選ぶ。
+    /// IESファイルが1つしかない場合はファイル名によらず使う。
+    fn select_zip_entry<'a>(files: &'a [String], hinban: &str) -> Option<&'a String> {
+        files
+            .iter()
+            .find(|f| Self::file_stem(f) == hinban)
+            .or_else(|| {
+                files
+                    .iter()
+                    .filter(|f| hinban.starts_with(&Self::file_stem(f)))
+                    .max_by_key(|f| Self::file_stem(f).len())
+            })
+            .or(match files {
+                [file] => Some(file),
+                _ => None,
+            })
+    }
+
+    /// 製品ページから配光データのURLを取得
+    async fn find_ies_url(&self, hinban: &str) -> ProviderResult<String> {
+        let html = self.fetch_detail_page(hinban).await?;
+        let urls = self.extract_ies_urls(&html);
+        if let Some(url) = Self::select_ies_url(&urls, hinban)? {
+            return Ok(url);
+        }
+        // 生産終了で配光データが掲載されていない場合は後継品の品番を含むエラーにする
+        if let Some(discontinued) = self.extract_discontinuation(&html) {
+            return Err(discontinued.error(hinban));
+        }
+        if Self::is_unrecognized_detail_page(&html, hinban) {
+            return Err(provider_outdated(
+                self.id(),
+                "IES link or spec table",
+                hinban,
+                &html,
+            ));
+        }
+        Err(not_available(AssetType::Ies, hinban))
+    }
+
+    /// 品番が掲載されているのに、配光データのリンクも仕様表も見つからない詳細ページか
+    /// （サイトの構成が変わり、セレクターが一致しなくなった可能性が高い）
+    fn is_unrecognized_detail_page(html: &str, hinban: &str) -> bool {
+        html::links(html, &DAIKO_IES_LINK).is_empty()
+            && Self::extract_product_name(html).is_none()
+            && page_mentions(html, hinban)
+    }
+
+    /// 詳細ページのHTMLから生産終了の表記と後継品の品番を抽出（生産終了でない場合は None）
+    fn extract_discontinuation(&self, html: &str) -> Option<Discontinuation> {
+        let text = html::body_text(html);
+        if !DISCONTINUED_RE.is_match(&text) {
+            return None;
+        }
+        let successor = SUCCESSOR_RE.captures(&text).map(|caps| caps[1].to_string());
+        Some(Discontinuation {
+            successor_page_url: successor.as_deref().map(|s| self.detail_url(s)),
+            successor,
+        })
+    }
+
+    /// 詳細ページのHTMLから品名を抽出
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &DAIKO_SPEC_LABEL, &["品名", "商品名"])
+    }
+
+    /// 大光電機の定価は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 詳細ページのHTMLから定価を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(html, &DAIKO_SPEC_LABEL, &["定価", "価格"])
+            .as_deref()
+            .and_then(Self::parse_list_price)
+    }
+
+    /// 詳細ページのHTMLから製品画像のURLを抽出（相対URLは絶対URLにする）
+    fn extract_image_url(&self, html: &str) -> Option<String> {
+        self.absolute_url(&html::image_url(html, &DAIKO_PRODUCT_IMAGE)?)
+    }
+
+    /// HTMLから製品詳細ページへのリンクを抽出
+    /// 戻り値: (品番, リンクテキスト) の一覧（品番で重複排除）
+    fn extract_item_links(html: &str) -> Vec<(String, Option<String>)> {
+        let mut links: Vec<(String, Option<String>)> = Vec::new();
+        for link in html::links(html, &DAIKO_ITEM_LINK) {
+            let Some((_, rest)) = link.href.split_once("pno=") else {
+                continue;
+            };
+            let hinban: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if hinban.is_empty() || links.iter().any(|(h, _)| *h == hinban) {
+                continue;
+            }
+            let text = Some(link.text).filter(|text| !text.is_empty() && *text != hinban);
+            links.push((hinban, text));
+        }
+        links
+    }
+
+    /// ZIPから品番に最も一致するIESファイルを取り出して保存
+    fn extract_ies_from_zip(
+        bytes: &[u8],
+        hinban: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(ProviderError::wrap(
+            ErrorCode::ZipInvalid,
+            "Failed to open ZIP",
+        ))?;
+        let files: Vec<String> = archive
+            .file_names()
+            .filter(|name| name.to_lowercase().ends_with(".ies"))
+            .map(str::to_string)
+            .collect();
+        if files.is_empty() {
+            return Ok(DownloadResult::failure(
+                "No .ies files found in ZIP".to_string(),
+            ));
+        }
+        let best_file = Self::select_zip_entry(&files, hinban).ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::ZipNoMatch,
+                format!("No matching .ies file found for: {}", hinban),
+            )
+        })?;
+
+        let mut contents = Vec::new();
+        archive
+            .by_name(best_file)
+            .and_then(|mut entry| Ok(entry.read_to_end(&mut contents)?))
+            .map_err(|e| {
+                ProviderError::new(
+                    ErrorCode::ZipInvalid,
+                    format!("Failed to read {} from ZIP: {}", best_file, e),
+                )
+            })?;
+        // 空のファイルを成功として残さない
+        if contents.is_empty() {
+            return Err(ProviderError::new(
+                ErrorCode::InvalidContent,
+                format!("{} in ZIP is empty", best_file),
+            ));
+        }
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
+        }
+        std::fs::write(&dest, &contents).map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+
+        let original_filename = best_file.rsplit(['/', '\\']).next().map(str::to_string);
+        Ok(DownloadResult::success(
+            dest_path.to_string(),
+            contents.len() as u64,
+            original_filename,
+        ))
+    }
+
+    /// 配光データをダウンロードして保存（受信が不完全な場合は1回だけやり直す）
+    async fn download_ies(
+        &self,
+        url: &str,
+        hinban: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        retry_incomplete(|| self.download_ies_once(url, hinban, dest_path)).await
+    }
+
+    async fn download_ies_once(
+        &self,
+        url: &str,
+        hinban: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+
+        if !response.status().is_success() {
+            return Ok(DownloadResult::http_failure(response.status()));
+        }
+        let source = DownloadSource::from_response(&response);
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+        // Content-Dispositionヘッダーから元のファイル名を取得
+        let original_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|h| h.to_str().ok())
+            .and_then(filename_from_content_disposition);
+
+        // 保存し終えるまでバッファの使用枠を保持する
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+
+        let file_size = bytes.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+        verify_length(total_bytes, file_size)?;
+
+        // シリーズのZIPの場合は品番に一致するIESファイルを取り出す
+        if bytes.starts_with(b"PK\x03\x04") {
+            report_phase(DownloadPhase::Extracting, None, None);
+            let (hinban, dest_path) = (hinban.to_string(), dest_path.to_string());
+            let result =
+                run_blocking(move || Self::extract_ies_from_zip(&bytes, &hinban, &dest_path))
+                    .await?;
+            return Ok(result.with_source(source));
+        }
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        verify_written(&dest, file_size).await?;
+
+        Ok(
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+                .with_source(source),
+        )
+    }
+}
+
+impl Default for DaikoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ManufacturerProvider for DaikoProvider {
+    fn id(&self) -> &str {
+        "daiko"
+    }
+
+    fn display_name(&self) -> &str {
+        "大光電機"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["大光電機", "大光", "daiko", "だいこう"]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        // 品番から直接製品ページにアクセス
+        // 品名・定価・製品画像・配光データのURL・生産終了の情報を取得
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        let ies_file_url = Self::select_ies_url(&self.extract_ies_urls(&html), &hinban)
+            .ok()
+            .flatten();
+
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: Self::extract_product_name(&html),
+            price: Self::extract_price(&html),
+            ies_file_url,
+            image_url: self.extract_image_url(&html),
+            product_page_url: Some(self.detail_url(&hinban)),
+            accessories: Vec::new(),
+            discontinued: self.extract_discontinuation(&html),
+            replaces: None,
+            match_quality: None,
+        })
+    }
+
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        let search_url = format!("{}/products/search/", self.base_url);
+
+        let response = send_request(
+            self.client
+                .get(&search_url)
+                .query(&[("keyword", Self::normalize_hinban(keyword))]),
+        )
+        .await
+        .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_item_links(&html)
+            .into_iter()
+            .map(|(hinban, text)| ProductCandidate {
+                product_page_url: Some(self.detail_url(&hinban)),
+                price: text.as_deref().and_then(Self::parse_list_price),
+                product_name: text,
+                model_number: hinban,
+            })
+            .collect())
+    }
+
+    /// 詳細ページに定価が掲載されていない場合は検索結果一覧から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        if let Some(price) = Self::extract_price(&html) {
+            return Ok(Some(price));
+        }
+        let candidates = self.search_products(&hinban).await?;
+        Ok(price_from_candidates(&candidates, &hinban))
+    }
+
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let started = Instant::now();
+        let hinban = Self::normalize_hinban(model_number);
+        let url = cancel.run(self.find_ies_url(&hinban)).await?;
+        let lookup_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let result = cancel
+            .run(self.download_ies(&url, &hinban, dest_path))
+            .await?;
+        Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+    }
+
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.find_ies_url(&Self::normalize_hinban(model_number))
+            .await
+            .map(ResolvedIesUrl::direct)
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies, AssetType::Image]
+    }
+
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type != AssetType::Image {
+            return Err(not_provided(self.display_name(), asset_type));
+        }
+        download_product_image(self, &self.client, model_number, dest_path, cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filename::{self, FilenameContext};
+    use std::io::Write;
+
+    #[test]
+    fn test_can_handle() {
+        let provider = DaikoProvider::new();
+        assert!(provider.can_handle("大光電機"));
+        assert!(provider.can_handle("DAIKO"));
+        assert!(provider.can_handle("Daiko Electric"));
+        assert!(!provider.can_handle("コイズミ照明"));
+        assert!(!provider.can_handle("パナソニック"));
+    }
+
+    #[test]
+    fn test_normalize_hinban() {
+        assert_eq!(
+            DaikoProvider::normalize_hinban("lzd-93195 xw"),
+            "LZD-93195XW"
+        );
+        assert_eq!(
+            DaikoProvider::normalize_hinban("ＬＺＤ－９３１９５ＸＷ"),
+            "LZD-93195XW"
+        );
+    }
+
+    #[test]
+    fn test_extract_ies_urls() {
+        let provider = DaikoProvider::new();
+        let html = r#"
+            <a href="/download/ies/LZD-93195XW.ies">配光データ（IES）</a>
+            <a class="dl" href='https://lighting.daiko-denki.co.jp/download/ies/LZD-93195.zip'>シリーズ一括</a>
+            <a href="/download/ies/LZD-93195XW.pdf">配光図</a>
+            <a href="/download/ies/LZD-93195XW.ies">配光データ（IES）</a>
+        "#;
+        assert_eq!(
+            provider.extract_ies_urls(html),
+            vec![
+                "https://lighting.daiko-denki.co.jp/download/ies/LZD-93195XW.ies",
+                "https://lighting.daiko-denki.co.jp/download/ies/LZD-93195.zip",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_ies_url() {
+        let urls = vec![
+            "https://example.com/download/ies/LZD-93195XW.ies".to_string(),
+            "https://example.com/download/ies/LZD-93195.zip".to_string(),
+        ];
+
+        // ファイル名が品番と一致するもの
+        assert_eq!(
+            DaikoProvider::select_ies_url(&urls, "LZD-93195XW").unwrap(),
+            Some(urls[0].clone())
+        );
+        // 品番がファイル名で始まるシリーズのZIP
+        assert_eq!(
+            DaikoProvider::select_ies_url(&urls, "LZD-93195YW").unwrap(),
+            Some(urls[1].clone())
+        );
+        // 選べない場合は候補を含むエラー
+        let error = DaikoProvider::select_ies_url(&urls, "DDL-5102YW").unwrap_err();
+        assert_eq!(error.code, ErrorCode::IesAmbiguous);
+        assert!(error
+            .message
+            .starts_with("Multiple IES files found for DDL-5102YW"));
+        // 1つしかない場合はファイル名によらず使う
+        assert_eq!(
+            DaikoProvider::select_ies_url(&urls[..1], "DDL-5102YW").unwrap(),
+            Some(urls[0].clone())
+        );
+        assert_eq!(
+            DaikoProvider::select_ies_url(&[], "DDL-5102YW").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_ies_from_zip() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for name in ["LZD-93195/LZD-93195XW.IES", "LZD-93195/LZD-93195YW.ies"] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(format!("IESNA:LM-63-2002\n{}", name).as_bytes())
+                .unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("A01.ies");
+        let result =
+            DaikoProvider::extract_ies_from_zip(&bytes, "LZD-93195YW", dest.to_str().unwrap())
+                .unwrap();
+        assert!(result.success);
+        assert_eq!(result.original_filename.as_deref(), Some("LZD-93195YW.ies"));
+        assert!(std::fs::read_to_string(&dest)
+            .unwrap()
+            .ends_with("LZD-93195YW.ies"));
+
+        // 一致するファイルがない
+        let error =
+            DaikoProvider::extract_ies_from_zip(&bytes, "DDL-5102YW", dest.to_str().unwrap())
+                .unwrap_err();
+        assert_eq!(error.code, ErrorCode::ZipNoMatch);
+        assert_eq!(error.message, "No matching .ies file found for: DDL-5102YW");
+    }
+
+    #[test]
+    fn test_extract_product_details() {
+        let provider = DaikoProvider::new();
+        let html = r#"
+            <html><head>
+              <meta property="og:image" content="/images/products/LZD-93195XW.jpg">
+            </head><body>
+              <table>
+                <tr><th>品名</th><td>LEDダウンライト</td></tr>
+                <tr><th>定価</th><td>¥18,400</td></tr>
+              </table>
+              <p>この商品は生産終了品です。後継品：LZD-93196XW</p>
+            </body></html>
+        "#;
+        assert_eq!(
+            DaikoProvider::extract_product_name(html).as_deref(),
+            Some("LEDダウンライト")
+        );
+        assert_eq!(
+            DaikoProvider::extract_price(html),
+            Some(Price::jpy(18400).assume_tax_included(false))
+        );
+        assert_eq!(
+            provider.extract_image_url(html).as_deref(),
+            Some("https://lighting.daiko-denki.co.jp/images/products/LZD-93195XW.jpg")
+        );
+        assert_eq!(
+            provider
+                .extract_discontinuation(html)
+                .and_then(|d| d.successor)
+                .as_deref(),
+            Some("LZD-93196XW")
+        );
+    }
+
+    #[test]
+    fn test_default_filename_template() {
+        let provider = DaikoProvider::new();
+        let generate_filename = |model_number, psu, original_filename| {
+            let context = FilenameContext {
+                spec_no: "1001",
+                manufacturer: provider.display_name(),
+                model_number,
+                psu,
+                original_filename,
+                photometry: None,
+            };
+            filename::render(
+                provider.default_filename_template(AssetType::Ies),
+                AssetType::Ies,
+                &context,
+            )
+        };
+
+        // 元ファイル名あり（ZIP内のファイル名）
+        assert_eq!(
+            generate_filename("LZD-93195XW", None, Some("LZD-93195XW.IES")),
+            "1001_LZD-93195XW.ies"
+        );
+
+        // 元ファイル名なし
+        assert_eq!(
+            generate_filename("LZD-93195XW", None, None),
+            "1001_LZD-93195XW.ies"
+        );
+
+        // PSUは無視される
+        assert_eq!(
+            generate_filename("LZD-93195XW", Some("PSU123"), Some("LZD-93195XW.ies")),
+            "1001_LZD-93195XW.ies"
+        );
+    }
+
+    #[test]
+    fn test_fixture_site() {
+        use crate::providers::fixture_server::{fixture, run, FixtureResponse, FixtureServer};
+
+        run(async {
+            let server = FixtureServer::start(vec![
+                (
+                    "/products/detail/?pno=LZD-93195XW",
+                    FixtureResponse::html("daiko/detail_LZD-93195XW.html"),
+                ),
+                (
+                    "/products/detail/?pno=LZD-90001XW",
+                    FixtureResponse::html("daiko/detail_LZD-90001XW.html"),
+                ),
+                (
+                    "/download/ies/LZD-93195XW.ies",
+                    FixtureResponse::file("daiko/LZD-93195XW.ies"),
+                ),
+            ])
+            .await;
+            let provider = DaikoProvider::with_config(&server.config());
+            let ies_url = format!("{}/download/ies/LZD-93195XW.ies", server.base_url());
+
+            // 製品情報（全角・小文字の品番も正規化して取得する）
+            let info = provider.fetch_product_info("ｌｚｄ-93195xw").await.unwrap();
+            assert_eq!(info.product_name.as_deref(), Some("LEDダウンライト"));
+            assert_eq!(
+                info.price,
+                Some(Price::jpy(15400).assume_tax_included(false))
+            );
+            assert_eq!(
+                info.image_url,
+                Some(format!(
+                    "{}/images/products/LZD-93195XW.jpg",
+                    server.base_url()
+                ))
+            );
+            assert_eq!(info.ies_file_url.as_deref(), Some(ies_url.as_str()));
+
+            // IESファイルのURLの解決とダウンロード
+            assert_eq!(
+                provider
+                    .resolve_ies_url("LZD-93195XW", None)
+                    .await
+                    .unwrap()
+                    .url,
+                ies_url
+            );
+            let temp = tempfile::tempdir().unwrap();
+            let dest = temp.path().join("1001_LZD-93195XW.ies");
+            let result = provider
+                .download_ies_file(
+                    "LZD-93195XW",
+                    None,
+                    dest.to_str().unwrap(),
+                    &CancelToken::new(),
+                )
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(result.original_filename.as_deref(), Some("LZD-93195XW.ies"));
+            assert_eq!(
+                std::fs::read(&dest).unwrap(),
+                fixture("daiko/LZD-93195XW.ies")
+            );
+
+            // 生産終了品は後継品の品番を含むエラー
+            let info = provider.fetch_product_info("LZD-90001XW").await.unwrap();
+            assert_eq!(
+                info.discontinued.and_then(|d| d.successor).as_deref(),
+                Some("LZD-93195XW")
+            );
+            assert_eq!(
+                provider
+                    .resolve_ies_url("LZD-90001XW", None)
+                    .await
+                    .unwrap_err(),
+                ProviderError::new(
+                    ErrorCode::Discontinued,
+                    "Discontinued: LZD-90001XW, successor: LZD-93195XW"
+                )
+            );
+
+            // 掲載のない品番（404）
+            assert_eq!(
+                provider
+                    .resolve_ies_url("LZD-99999", None)
+                    .await
+                    .unwrap_err(),
+                ProviderError::new(
+                    ErrorCode::HttpStatus,
+                    "Detail page for LZD-99999 returned status: 404 Not Found"
+                )
+            );
+        });
+    }
+}
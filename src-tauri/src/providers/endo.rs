@@ -0,0 +1,838 @@
+//! 遠藤照明プロバイダー
+//!
+//! 遠藤照明 Webカタログ (www.endo-lighting.co.jp) からの
+//! 製品情報・IESファイル取得を担当する。
+//! 配光データは配光角違い（狭角・中角・広角等）の器具をまとめたZIPで掲載されていることが多い。
+//! ZIPの場合はZIP内のファイル名から型番（配光角・色温度の指定を含む）に最も一致するものを選んで
+//! 保存する（PSUは使用しない）。
+
+use super::html::{self, ENDO_IES_LINK, ENDO_ITEM_LINK, ENDO_PRODUCT_IMAGE, ENDO_SPEC_LABEL};
+use super::{
+    can_request_decision, check_url, download_product_image, fetch_content_length,
+    filename_from_content_disposition, matching, not_available, not_provided, page_mentions,
+    parse_price, price_from_candidates, provider_outdated, report_phase, request_decision,
+    retry_incomplete, run_blocking, send_request, status_error, verify_length, verify_written,
+    zip_member, AssetType, CancelToken, Diagnosis, DownloadPhase, DownloadResult, DownloadSource,
+    DownloadWarning, DownloadWarningKind, ManufacturerProvider, Price, ProductCandidate,
+    ProductInfo, ProviderConfig, ResolvedIesUrl, ZipCandidate,
+};
+use crate::buffer;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::io::{Cursor, Read};
+use std::time::Instant;
+
+/// 遠藤照明のWebカタログの既定のベースURL（設定の `providers.endo.baseUrl` で変更できる）
+const BASE_URL: &str = "https://www.endo-lighting.co.jp";
+
+/// 遠藤照明プロバイダー
+pub struct EndoProvider {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl EndoProvider {
+    pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
+        Self {
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+        }
+    }
+
+    /// 器具リストの型番を正規化
+    /// 全角英数字・記号を半角にして大文字にし、空白は '-' にする（配光角の "°" / "度" は "D" にする）
+    /// 例: "ers6288w　15°" → "ERS6288W-15D"
+    fn normalize_model_number(model_number: &str) -> String {
+        let halfwidth: String = model_number
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '°' | '度' => 'D',
+                _ => c,
+            })
+            .flat_map(char::to_uppercase)
+            .collect();
+        halfwidth.split_whitespace().collect::<Vec<_>>().join("-")
+    }
+
+    /// 型番からカタログの品番（製品ページの単位。最初の '-' / '_' より前）を取り出す
+    /// 例: "ERS6288W-15D" → "ERS6288W"
+    fn hinban(model_number: &str) -> &str {
+        model_number
+            .split(['-', '_'])
+            .next()
+            .unwrap_or(model_number)
+    }
+
+    /// 製品詳細ページのURL
+    fn detail_url(&self, hinban: &str) -> String {
+        format!("{}/products/detail/?hinban={}", self.base_url, hinban)
+    }
+
+    /// 相対URLを絶対URLにする
+    fn absolute_url(&self, url: &str) -> Option<String> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Some(url.to_string())
+        } else if let Some(rest) = url.strip_prefix("//") {
+            Some(format!("https://{}", rest))
+        } else if url.starts_with('/') {
+            Some(format!("{}{}", self.base_url, url))
+        } else {
+            None
+        }
+    }
+
+    /// URLのパスがZIPか
+    fn is_zip_url(url: &str) -> bool {
+        url.split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+            .ends_with(".zip")
+    }
+
+    /// 製品詳細ページのHTMLを取得
+    async fn fetch_detail_page(&self, hinban: &str) -> ProviderResult<String> {
+        let response = send_request(self.client.get(self.detail_url(hinban)))
+            .await
+            .map_err(|e| e.context("Detail request failed"))?;
+
+        // 掲載のない品番は 404 になる
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(status_error(
+                response.status(),
+                format!(
+                    "Detail page for {} returned status: {}",
+                    hinban,
+                    response.status()
+                ),
+            ));
+        }
+        response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))
+    }
+
+    /// 詳細ページのHTMLから配光データ（.ies / .zip）のURLをすべて抽出（重複排除）
+    fn extract_ies_urls(&self, html: &str) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for link in html::links(html, &ENDO_IES_LINK) {
+            let path = link.href.split(['?', '#']).next().unwrap_or_default();
+            let lower = path.to_lowercase();
+            if !lower.ends_with(".ies") && !lower.ends_with(".zip") {
+                continue;
+            }
+            let Some(url) = self.absolute_url(&link.href) else {
+                continue;
+            };
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 配光データのURLから型番に対応するものを選ぶ
+    ///
+    /// 配光角ごとにリンクがある場合はファイル名が型番に最も一致するものを選ぶ（ZIPと同じ規則）。
+    /// 1つしかない場合はファイル名によらず使う。選べない場合は候補を含むエラーにする。
+    fn select_ies_url(urls: &[String], model_number: &str) -> ProviderResult<Option<String>> {
+        if let Some(url) = matching::select_best_file(model_number, urls) {
+            return Ok(Some(url));
+        }
+        match urls {
+            [] => Ok(None),
+            [url] => Ok(Some(url.clone())),
+            _ => Err(ProviderError::new(
+                ErrorCode::IesAmbiguous,
+                format!(
+                    "Multiple IES files found for {}: {}",
+                    model_number,
+                    urls.join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// 製品ページから配光データのURLを取得
+    async fn find_ies_url(&self, model_number: &str) -> ProviderResult<String> {
+        let hinban = Self::hinban(model_number);
+        let html = self.fetch_detail_page(hinban).await?;
+        let urls = self.extract_ies_urls(&html);
+        if let Some(url) = Self::select_ies_url(&urls, model_number)? {
+            return Ok(url);
+        }
+        if Self::is_unrecognized_detail_page(&html, hinban) {
+            return Err(provider_outdated(
+                self.id(),
+                "IES link or spec table",
+                hinban,
+                &html,
+            ));
+        }
+        Err(not_available(AssetType::Ies, hinban))
+    }
+
+    /// 品番が掲載されているのに、配光データのリンクも仕様表も見つからない詳細ページか
+    /// （サイトの構成が変わり、セレクターが一致しなくなった可能性が高い）
+    fn is_unrecognized_detail_page(html: &str, hinban: &str) -> bool {
+        html::links(html, &ENDO_IES_LINK).is_empty()
+            && Self::extract_product_name(html).is_none()
+            && page_mentions(html, hinban)
+    }
+
+    /// 詳細ページのHTMLから品名を抽出
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &ENDO_SPEC_LABEL, &["品名", "商品名"])
+    }
+
+    /// 遠藤照明の定価は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 詳細ページのHTMLから定価を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(html, &ENDO_SPEC_LABEL, &["定価", "価格"])
+            .as_deref()
+            .and_then(Self::parse_list_price)
+    }
+
+    /// 詳細ページのHTMLから製品画像のURLを抽出（相対URLは絶対URLにする）
+    fn extract_image_url(&self, html: &str) -> Option<String> {
+        self.absolute_url(&html::image_url(html, &ENDO_PRODUCT_IMAGE)?)
+    }
+
+    /// HTMLから製品詳細ページへのリンクを抽出
+    /// 戻り値: (品番, リンクテキスト) の一覧（品番で重複排除）
+    fn extract_item_links(html: &str) -> Vec<(String, Option<String>)> {
+        let mut links: Vec<(String, Option<String>)> = Vec::new();
+        for link in html::links(html, &ENDO_ITEM_LINK) {
+            let Some((_, rest)) = link.href.split_once("hinban=") else {
+                continue;
+            };
+            let hinban: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if hinban.is_empty() || links.iter().any(|(h, _)| *h == hinban) {
+                continue;
+            }
+            let text = Some(link.text).filter(|text| !text.is_empty() && *text != hinban);
+            links.push((hinban, text));
+        }
+        links
+    }
+
+    /// ZIP内のIESファイル一覧（ZIP内の順）
+    fn list_ies_files(bytes: &[u8]) -> ProviderResult<Vec<String>> {
+        let archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(ProviderError::wrap(
+            ErrorCode::ZipInvalid,
+            "Failed to open ZIP",
+        ))?;
+        Ok(archive
+            .file_names()
+            .filter(|name| name.to_lowercase().ends_with(".ies"))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// ZIPをダウンロードしてIESファイル一覧を取得（展開・保存はしない）
+    async fn fetch_zip_listing(&self, url: &str) -> ProviderResult<Vec<String>> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+        if !response.status().is_success() {
+            return Err(status_error(
+                response.status(),
+                format!("Download failed with status: {}", response.status()),
+            ));
+        }
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+        run_blocking(move || Self::list_ies_files(&bytes)).await
+    }
+
+    /// ZIPから型番に最も一致するIESファイル（利用者が選んだファイルがあればそれ）を取り出して保存
+    ///
+    /// 同程度に一致するファイル（型番に配光角の指定がない等）がある場合は、
+    /// 手動で選び直せるよう候補と警告を付ける。
+    fn extract_ies_from_zip(
+        bytes: &[u8],
+        model_number: &str,
+        dest_path: &str,
+        chosen: Option<&str>,
+    ) -> ProviderResult<DownloadResult> {
+        let files = Self::list_ies_files(bytes)?;
+        if files.is_empty() {
+            return Ok(DownloadResult::failure(
+                "No .ies files found in ZIP".to_string(),
+            ));
+        }
+        if let Some(name) = chosen.filter(|name| !files.iter().any(|f| f == name)) {
+            return Err(ProviderError::new(
+                ErrorCode::ZipNoMatch,
+                format!("No matching file {} in ZIP", name),
+            ));
+        }
+        let best_file = match chosen {
+            Some(name) => name.to_string(),
+            None => matching::select_best_file(model_number, &files)
+                .or_else(|| (files.len() == 1).then(|| files[0].clone()))
+                .ok_or_else(|| {
+                    ProviderError::new(
+                        ErrorCode::ZipNoMatch,
+                        format!("No matching .ies file found for: {}", model_number),
+                    )
+                })?,
+        };
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(ProviderError::wrap(
+            ErrorCode::ZipInvalid,
+            "Failed to open ZIP",
+        ))?;
+        let mut contents = Vec::new();
+        archive
+            .by_name(&best_file)
+            .and_then(|mut entry| Ok(entry.read_to_end(&mut contents)?))
+            .map_err(|e| {
+                ProviderError::new(
+                    ErrorCode::ZipInvalid,
+                    format!("Failed to read {} from ZIP: {}", best_file, e),
+                )
+            })?;
+        // 空のファイルを成功として残さない
+        if contents.is_empty() {
+            return Err(ProviderError::new(
+                ErrorCode::InvalidContent,
+                format!("{} in ZIP is empty", best_file),
+            ));
+        }
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
+        }
+        std::fs::write(&dest, &contents).map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+
+        let original_filename = best_file.rsplit(['/', '\\']).next().map(str::to_string);
+        let mut result = DownloadResult::success(
+            dest_path.to_string(),
+            contents.len() as u64,
+            original_filename,
+        );
+        let others = matching::ambiguous_matches(model_number, &files, &best_file);
+        if others.is_empty() || chosen.is_some() {
+            return Ok(result);
+        }
+        result.candidates = std::iter::once(best_file.clone())
+            .chain(others.iter().cloned())
+            .collect();
+        Ok(result.with_warning(DownloadWarning::new(
+            DownloadWarningKind::AmbiguousZipMatch,
+            format!(
+                "Selected {} for {}, but {} also matched",
+                best_file,
+                model_number,
+                others.join(", ")
+            ),
+        )))
+    }
+
+    /// 配光データをダウンロードして保存（受信が不完全な場合は1回だけやり直す）
+    async fn download_ies(
+        &self,
+        url: &str,
+        model_number: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        retry_incomplete(|| self.download_ies_once(url, model_number, dest_path)).await
+    }
+
+    async fn download_ies_once(
+        &self,
+        url: &str,
+        model_number: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+
+        if !response.status().is_success() {
+            return Ok(DownloadResult::http_failure(response.status()));
+        }
+        let source = DownloadSource::from_response(&response);
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+        // Content-Dispositionヘッダーから元のファイル名を取得
+        let original_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|h| h.to_str().ok())
+            .and_then(filename_from_content_disposition);
+
+        // 保存し終えるまでバッファの使用枠を保持する
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+
+        let file_size = bytes.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+        verify_length(total_bytes, file_size)?;
+
+        // 配光角違いをまとめたZIPの場合は型番に一致するIESファイルを取り出す
+        if bytes.starts_with(b"PK\x03\x04") {
+            // 利用者が指定したファイルを使う。指定がなく、同程度に一致するIESファイルが複数あれば、
+            // 展開前に利用者に選んでもらう
+            let chosen = if let Some(member) = zip_member() {
+                Some(member)
+            } else if can_request_decision() {
+                let (listed, model) = (bytes.clone(), model_number.to_string());
+                let request = run_blocking(move || -> ProviderResult<_> {
+                    let files = Self::list_ies_files(&listed)?;
+                    Ok(matching::decision_request(&model, &files))
+                })
+                .await?;
+                match request {
+                    Some(request) => request_decision(request).await,
+                    None => None,
+                }
+            } else {
+                None
+            };
+
+            report_phase(DownloadPhase::Extracting, None, None);
+            let (model, dest_path) = (model_number.to_string(), dest_path.to_string());
+            let result = run_blocking(move || {
+                Self::extract_ies_from_zip(&bytes, &model, &dest_path, chosen.as_deref())
+            })
+            .await?;
+            return Ok(result.with_source(source));
+        }
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        verify_written(&dest, file_size).await?;
+
+        Ok(
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+                .with_source(source),
+        )
+    }
+}
+
+impl Default for EndoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ManufacturerProvider for EndoProvider {
+    fn id(&self) -> &str {
+        "endo"
+    }
+
+    fn display_name(&self) -> &str {
+        "遠藤照明"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec![
+            "遠藤照明",
+            "遠藤",
+            "endo lighting",
+            "endo-lighting",
+            "えんどう",
+        ]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        // 品番から直接製品ページにアクセス
+        // 品名・定価・製品画像・配光データのURLを取得
+        let normalized = Self::normalize_model_number(model_number);
+        let hinban = Self::hinban(&normalized);
+        let html = self.fetch_detail_page(hinban).await?;
+        let ies_file_url = Self::select_ies_url(&self.extract_ies_urls(&html), &normalized)
+            .ok()
+            .flatten();
+
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: Self::extract_product_name(&html),
+            price: Self::extract_price(&html),
+            ies_file_url,
+            image_url: self.extract_image_url(&html),
+            product_page_url: Some(self.detail_url(hinban)),
+            accessories: Vec::new(),
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
+        })
+    }
+
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        let search_url = format!("{}/products/search/", self.base_url);
+
+        let response = send_request(
+            self.client
+                .get(&search_url)
+                .query(&[("keyword", Self::normalize_model_number(keyword))]),
+        )
+        .await
+        .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_item_links(&html)
+            .into_iter()
+            .map(|(hinban, text)| ProductCandidate {
+                product_page_url: Some(self.detail_url(&hinban)),
+                price: text.as_deref().and_then(Self::parse_list_price),
+                product_name: text,
+                model_number: hinban,
+            })
+            .collect())
+    }
+
+    /// 詳細ページに定価が掲載されていない場合は検索結果一覧から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let normalized = Self::normalize_model_number(model_number);
+        let hinban = Self::hinban(&normalized);
+        let html = self.fetch_detail_page(hinban).await?;
+        if let Some(price) = Self::extract_price(&html) {
+            return Ok(Some(price));
+        }
+        let candidates = self.search_products(hinban).await?;
+        Ok(price_from_candidates(&candidates, hinban))
+    }
+
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let started = Instant::now();
+        let normalized = Self::normalize_model_number(model_number);
+        let url = cancel.run(self.find_ies_url(&normalized)).await?;
+        let lookup_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let result = cancel
+            .run(self.download_ies(&url, &normalized, dest_path))
+            .await?;
+        Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+    }
+
+    /// ZIPで配布される場合は、ZIPのURLとZIP内の.iesファイル候補を返す
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        let normalized = Self::normalize_model_number(model_number);
+        let url = self.find_ies_url(&normalized).await?;
+        if !Self::is_zip_url(&url) {
+            return Ok(ResolvedIesUrl::direct(url));
+        }
+        let candidates = self.fetch_zip_listing(&url).await?;
+        let selected = matching::select_best_file(&normalized, &candidates);
+        Ok(ResolvedIesUrl {
+            url,
+            candidates,
+            selected,
+        })
+    }
+
+    async fn list_zip_candidates(&self, model_number: &str) -> ProviderResult<Vec<ZipCandidate>> {
+        let normalized = Self::normalize_model_number(model_number);
+        let url = self.find_ies_url(&normalized).await?;
+        if !Self::is_zip_url(&url) {
+            return Ok(Vec::new());
+        }
+        let files = self.fetch_zip_listing(&url).await?;
+        Ok(matching::rank_candidates(&normalized, &files))
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies, AssetType::Image]
+    }
+
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type != AssetType::Image {
+            return Err(not_provided(self.display_name(), asset_type));
+        }
+        let normalized = Self::normalize_model_number(model_number);
+        download_product_image(self, &self.client, &normalized, dest_path, cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// 指定したファイル名・内容のZIP
+    fn zip_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, contents) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_can_handle() {
+        let provider = EndoProvider::new();
+        assert!(provider.can_handle("遠藤照明"));
+        assert!(provider.can_handle("ENDO LIGHTING"));
+        assert!(provider.can_handle("Endo-Lighting Corp."));
+        assert!(!provider.can_handle("Vendor TBD"));
+        assert!(!provider.can_handle("コイズミ照明"));
+    }
+
+    #[test]
+    fn test_normalize_model_number() {
+        assert_eq!(EndoProvider::normalize_model_number("ers6288w"), "ERS6288W");
+        assert_eq!(
+            EndoProvider::normalize_model_number("ＥＲＳ６２８８Ｗ　15°"),
+            "ERS6288W-15D"
+        );
+        assert_eq!(
+            EndoProvider::normalize_model_number(" ERS6288W  30度 "),
+            "ERS6288W-30D"
+        );
+        assert_eq!(EndoProvider::hinban("ERS6288W-15D"), "ERS6288W");
+        assert_eq!(EndoProvider::hinban("ERS6288W"), "ERS6288W");
+    }
+
+    #[test]
+    fn test_extract_and_select_ies_urls() {
+        let provider = EndoProvider::new();
+        let html = r#"
+            <a href="/download/haikou/ERS6288W_15D.ies">配光データ（狭角）</a>
+            <a href="https://www.endo-lighting.co.jp/download/haikou/ERS6288W_30D.ies">配光データ（中角）</a>
+            <a href="/download/haikou/ERS6288W_15D.ies">配光データ（狭角）</a>
+            <a href="/download/cad/ERS6288W.dxf">CAD</a>
+        "#;
+        let urls = provider.extract_ies_urls(html);
+        assert_eq!(
+            urls,
+            vec![
+                "https://www.endo-lighting.co.jp/download/haikou/ERS6288W_15D.ies".to_string(),
+                "https://www.endo-lighting.co.jp/download/haikou/ERS6288W_30D.ies".to_string(),
+            ]
+        );
+
+        // 配光角の指定に一致するリンク
+        assert_eq!(
+            EndoProvider::select_ies_url(&urls, "ERS6288W-30D").unwrap(),
+            Some(urls[1].clone())
+        );
+        // 配光角の指定がなければ先に掲載されたもの
+        assert_eq!(
+            EndoProvider::select_ies_url(&urls, "ERS6288W").unwrap(),
+            Some(urls[0].clone())
+        );
+        // 一致しない場合、リンクが1つならそれを使う
+        let zip = vec!["https://www.endo-lighting.co.jp/download/haikou/SERIES_A.zip".to_string()];
+        assert_eq!(
+            EndoProvider::select_ies_url(&zip, "ERS6288W").unwrap(),
+            Some(zip[0].clone())
+        );
+        assert!(EndoProvider::select_ies_url(&urls, "ERD1234W").is_err());
+        assert_eq!(EndoProvider::select_ies_url(&[], "ERS6288W").unwrap(), None);
+    }
+
+    #[test]
+    fn test_extract_ies_from_zip() {
+        let bytes = zip_bytes(&[
+            ("IES/ERS6288W_15D.ies", b"narrow"),
+            ("IES/ERS6288W_30D.ies", b"medium"),
+            ("IES/ERS6288W_30D.pdf", b"report"),
+        ]);
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("1001_ERS6288W.ies");
+        let dest_path = dest.to_str().unwrap();
+
+        // 配光角の指定に一致するファイル
+        let result =
+            EndoProvider::extract_ies_from_zip(&bytes, "ERS6288W-30D", dest_path, None).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.original_filename.as_deref(),
+            Some("ERS6288W_30D.ies")
+        );
+        assert_eq!(std::fs::read(&dest).unwrap(), b"medium");
+        assert!(result.warnings.is_empty());
+
+        // 配光角の指定がなければ先のファイルを選び、同程度に一致する候補を付ける
+        let result =
+            EndoProvider::extract_ies_from_zip(&bytes, "ERS6288W", dest_path, None).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"narrow");
+        assert_eq!(
+            result.candidates,
+            vec![
+                "IES/ERS6288W_15D.ies".to_string(),
+                "IES/ERS6288W_30D.ies".to_string()
+            ]
+        );
+        assert_eq!(
+            result.warnings[0].kind,
+            DownloadWarningKind::AmbiguousZipMatch
+        );
+
+        // 利用者が選んだファイル
+        let result = EndoProvider::extract_ies_from_zip(
+            &bytes,
+            "ERS6288W",
+            dest_path,
+            Some("IES/ERS6288W_30D.ies"),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"medium");
+        assert!(result.warnings.is_empty());
+        // 指定したファイルがZIPにない
+        assert!(EndoProvider::extract_ies_from_zip(
+            &bytes,
+            "ERS6288W",
+            dest_path,
+            Some("IES/ERS6288W_60D.ies"),
+        )
+        .is_err());
+
+        // 一致するファイルがない
+        assert!(EndoProvider::extract_ies_from_zip(&bytes, "ERD1234W", dest_path, None).is_err());
+    }
+
+    #[test]
+    fn test_fixture_site() {
+        use crate::providers::fixture_server::{fixture, run, FixtureResponse, FixtureServer};
+
+        run(async {
+            let server = FixtureServer::start(vec![
+                (
+                    "/products/detail/?hinban=ERS6288W",
+                    FixtureResponse::html("endo/detail_ERS6288W.html"),
+                ),
+                (
+                    "/download/haikou/ERS6288W.zip",
+                    FixtureResponse::file("endo/ERS6288W.zip"),
+                ),
+            ])
+            .await;
+            let provider = EndoProvider::with_config(&server.config());
+            let zip_url = format!("{}/download/haikou/ERS6288W.zip", server.base_url());
+
+            let info = provider.fetch_product_info("ers6288w 30°").await.unwrap();
+            assert_eq!(info.product_name.as_deref(), Some("LEDスポットライト"));
+            assert_eq!(
+                info.price,
+                Some(Price::jpy(32000).assume_tax_included(false))
+            );
+            assert_eq!(info.ies_file_url.as_deref(), Some(zip_url.as_str()));
+
+            // ZIP内の候補と選択されるファイル
+            let resolved = provider
+                .resolve_ies_url("ERS6288W-30D", None)
+                .await
+                .unwrap();
+            assert_eq!(resolved.url, zip_url);
+            assert_eq!(resolved.candidates.len(), 3);
+            assert_eq!(resolved.selected.as_deref(), Some("ERS6288W_30D.ies"));
+            let ranked = provider.list_zip_candidates("ERS6288W").await.unwrap();
+            assert!(ranked[1].close_match);
+
+            // 配光角の指定に一致するファイルを取り出して保存
+            let temp = tempfile::tempdir().unwrap();
+            let dest = temp.path().join("1001_ERS6288W.ies");
+            let result = provider
+                .download_ies_file(
+                    "ERS6288W-30D",
+                    None,
+                    dest.to_str().unwrap(),
+                    &CancelToken::new(),
+                )
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(
+                result.original_filename.as_deref(),
+                Some("ERS6288W_30D.ies")
+            );
+            assert_eq!(
+                std::fs::read(&dest).unwrap(),
+                fixture("endo/ERS6288W_30D.ies")
+            );
+        });
+    }
+}
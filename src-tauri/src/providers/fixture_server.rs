@@ -0,0 +1,167 @@
+//! プロバイダーのテスト用HTTPサーバー
+//!
+//! 記録したメーカーサイトの応答（`tests/fixtures/` 以下のHTML・IESファイル）をローカルのポートで返す。
+//! プロバイダーのベースURLを [`FixtureServer::config`] で差し替えて作成すると、
+//! 詳細ページの取得から抽出・ダウンロードまでをメーカーサイトに接続せずに確認できる。
+
+use super::ProviderConfig;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// パスに対して返す応答
+#[derive(Debug, Clone)]
+pub struct FixtureResponse {
+    status: u16,
+    content_type: &'static str,
+    content_disposition: Option<String>,
+    body: Vec<u8>,
+}
+
+impl FixtureResponse {
+    /// 記録したHTML（`tests/fixtures/{path}`）
+    pub fn html(path: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            content_disposition: None,
+            body: fixture(path),
+        }
+    }
+
+    /// 記録したファイル（Content-Dispositionに元のファイル名を付ける）
+    pub fn file(path: &str) -> Self {
+        let filename = path.rsplit('/').next().unwrap_or(path);
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            content_disposition: Some(format!("attachment; filename=\"{}\"", filename)),
+            body: fixture(path),
+        }
+    }
+
+    /// 本文のない応答（404 等）
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8",
+            content_disposition: None,
+            body: Vec::new(),
+        }
+    }
+
+    fn to_bytes(&self, head_only: bool) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            404 => "Not Found",
+            429 => "Too Many Requests",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        };
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        );
+        if let Some(disposition) = &self.content_disposition {
+            head.push_str(&format!("Content-Disposition: {}\r\n", disposition));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        if !head_only {
+            bytes.extend_from_slice(&self.body);
+        }
+        bytes
+    }
+}
+
+/// 記録したファイル（`tests/fixtures/{path}`）を読み込む
+pub fn fixture(path: &str) -> Vec<u8> {
+    let full = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path);
+    std::fs::read(&full).unwrap_or_else(|e| panic!("{}: {}", full.display(), e))
+}
+
+/// 記録した応答を返すサーバー（登録のないパスは 404）
+pub struct FixtureServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FixtureServer {
+    /// パス（クエリを含む）ごとの応答を登録して起動（Tokioランタイム上で呼ぶ）
+    pub async fn start(routes: Vec<(&str, FixtureResponse)>) -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<HashMap<String, FixtureResponse>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(path, response)| (path.to_string(), response))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let (routes, log) = (routes.clone(), log.clone());
+                tokio::spawn(async move {
+                    // リクエストラインとヘッダーを読む（テストのリクエストは本文を持たない）
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&buf).into_owned();
+                    let mut parts = head.lines().next().unwrap_or_default().split(' ');
+                    let method = parts.next().unwrap_or_default();
+                    let target = parts.next().unwrap_or_default().to_string();
+                    let response = routes
+                        .get(&target)
+                        .cloned()
+                        .unwrap_or_else(|| FixtureResponse::status(404));
+                    log.lock().unwrap().push(target);
+                    let _ = stream.write_all(&response.to_bytes(method == "HEAD")).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        Self { base_url, requests }
+    }
+
+    /// サーバーのベースURL（`http://127.0.0.1:{port}`）
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// ベースURLをこのサーバーにしたプロバイダー設定（応答がない場合に待ち続けないようタイムアウトを付ける）
+    pub fn config(&self) -> ProviderConfig {
+        ProviderConfig {
+            base_url: Some(self.base_url.clone()),
+            timeout_secs: Some(10),
+            ..ProviderConfig::default()
+        }
+    }
+
+    /// 受け取ったリクエストのパス（受け取った順）
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// テスト用のTokioランタイム上で実行
+pub fn run<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
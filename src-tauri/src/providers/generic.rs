@@ -0,0 +1,679 @@
+//! 定義ファイルから作成するプロバイダー（カスタムプロバイダー）
+//!
+//! 組み込みのプロバイダーがない小規模なメーカーのサイトを、Rustのプロバイダーを書かずに
+//! 扱うためのもの。アプリのデータディレクトリの `providers/` に置いた定義ファイル
+//! （JSONまたはTOML）を起動時に読み込み、組み込みのプロバイダーと一緒にレジストリに登録する。
+//! プロバイダールールの更新（[`crate::rules_update`]）で取得したバンドルにも同じ形式の定義を含め、
+//! 同じIDの定義ファイルがない場合に登録する（手元の定義ファイルを優先する）。
+//!
+//! 型番から製品ページ（または検索結果のページ）のURLを作り、CSSセレクターまたは正規表現で
+//! IESファイル（ZIP）へのリンクを探す。リンクが複数ある場合はファイル名が型番に最も
+//! 一致するものを選ぶ。ZIPの場合は型番に最も一致するIESファイルを取り出す。
+//!
+//! 定義ファイルの例（JSON）:
+//!
+//! ```json
+//! {
+//!   "id": "example",
+//!   "displayName": "Example照明",
+//!   "baseUrl": "https://www.example.co.jp",
+//!   "aliases": ["example照明", "example"],
+//!   "searchUrl": "/products/?q={model}",
+//!   "iesLinkSelector": "a[href$=\".ies\"], a[href$=\".zip\"]",
+//!   "zip": true,
+//!   "filenameTemplate": "{spec_no}_{model}"
+//! }
+//! ```
+
+use super::html;
+use super::matching::select_best_file;
+use super::{
+    check_url, fetch_content_length, send_request, status_error, url_encode, AssetType,
+    CancelToken, Diagnosis, DownloadResult, DownloadWarning, DownloadWarningKind,
+    ManufacturerProvider, ProductInfo, ProviderConfig, ResolvedIesUrl,
+};
+use crate::direct;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::filename;
+use async_trait::async_trait;
+use regex::Regex;
+use scraper::Selector;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Instant;
+
+/// 定義ファイルの拡張子
+const DEFINITION_EXTENSIONS: [&str; 2] = ["json", "toml"];
+
+/// カスタムプロバイダーの定義
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProviderDefinition {
+    /// プロバイダーID（英小文字・数字・`-` `_`。組み込みのプロバイダーと重複しないもの）
+    pub id: String,
+    /// 表示名
+    pub display_name: String,
+    /// WebサイトのベースURL
+    pub base_url: String,
+    /// メーカー名の判定に使用する別名（省略時は表示名）
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// 型番から製品ページ・検索結果のページのURLを作るテンプレート
+    /// （`{model}` を型番に置き換える。`/` で始まる場合はベースURLからのパス）
+    pub search_url: String,
+    /// IESファイルへのリンクのCSSセレクター（`iesLinkPattern` といずれか一方を指定）
+    #[serde(default)]
+    pub ies_link_selector: Option<String>,
+    /// IESファイルのURLを抽出する正規表現（ページのHTMLに適用し、1つ目のグループ、
+    /// グループがなければ一致した全体をURLとする）
+    #[serde(default)]
+    pub ies_link_pattern: Option<String>,
+    /// ZIPへのリンクもIESファイルの取得元とするか（ZIPの場合は型番に最も一致するIESファイルを取り出す）
+    #[serde(default)]
+    pub zip: bool,
+    /// 保存先ファイル名の既定テンプレート（省略時はアプリの既定）
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    /// 疎通確認に使用する型番
+    #[serde(default)]
+    pub sample_model_number: Option<String>,
+}
+
+impl ProviderDefinition {
+    /// 定義ファイルの内容を解析（`{` で始まる場合はJSON、それ以外はTOML）
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        if contents.trim_start().starts_with('{') {
+            serde_json::from_str(contents)
+                .map_err(|e| format!("Invalid provider definition: {}", e))
+        } else {
+            toml::from_str(contents).map_err(|e| format!("Invalid provider definition: {}", e))
+        }
+    }
+}
+
+/// IESファイルへのリンクの探し方
+#[derive(Debug, Clone)]
+enum LinkRule {
+    Selector(Selector),
+    Pattern(Regex),
+}
+
+/// 定義ファイルから作成したプロバイダー
+#[derive(Debug, Clone)]
+pub struct GenericProvider {
+    definition: ProviderDefinition,
+    /// 判定に使用する別名（小文字）
+    aliases: Vec<String>,
+    link: LinkRule,
+    client: reqwest::Client,
+}
+
+impl GenericProvider {
+    /// 定義を検証してプロバイダーを作成
+    ///
+    /// # Arguments
+    /// * `definition` - 定義
+    /// * `reserved` - 使用できないID（組み込みのプロバイダーのID）
+    pub fn new(definition: ProviderDefinition, reserved: &[&str]) -> Result<Self, String> {
+        let id = &definition.id;
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Provider id must consist of lowercase letters, digits, '-' and '_': {}",
+                id
+            ));
+        }
+        if reserved.contains(&id.as_str()) {
+            return Err(format!("Provider id is already used: {}", id));
+        }
+        if definition.display_name.trim().is_empty() {
+            return Err("displayName is empty".to_string());
+        }
+        let base_url = reqwest::Url::parse(&definition.base_url)
+            .map_err(|e| format!("baseUrl is invalid: {}", e))?;
+        if !matches!(base_url.scheme(), "http" | "https") || base_url.host_str().is_none() {
+            return Err("baseUrl must be an http(s) URL".to_string());
+        }
+        if !definition.search_url.contains("{model}") {
+            return Err("searchUrl must contain {model}".to_string());
+        }
+        let link = match (&definition.ies_link_selector, &definition.ies_link_pattern) {
+            (Some(css), None) => LinkRule::Selector(html::parse_selector(css)?),
+            (None, Some(pattern)) => LinkRule::Pattern(
+                Regex::new(pattern).map_err(|e| format!("Invalid iesLinkPattern: {}", e))?,
+            ),
+            _ => {
+                return Err("Specify either iesLinkSelector or iesLinkPattern".to_string());
+            }
+        };
+        if let Some(template) = &definition.filename_template {
+            filename::validate(template)?;
+        }
+        let mut aliases: Vec<String> = definition
+            .aliases
+            .iter()
+            .map(|alias| alias.trim().to_lowercase())
+            .filter(|alias| !alias.is_empty())
+            .collect();
+        if aliases.is_empty() {
+            aliases.push(definition.display_name.trim().to_lowercase());
+        }
+
+        let provider = Self {
+            definition,
+            aliases,
+            link,
+            client: Self::client(),
+        };
+        // テンプレートから正しいURLを作れるか確認する
+        provider.search_url("TEST")?;
+        Ok(provider)
+    }
+
+    /// 定義
+    pub fn definition(&self) -> &ProviderDefinition {
+        &self.definition
+    }
+
+    /// 現在のプロキシ・TLSの設定でHTTPクライアントを作り直したプロバイダー
+    pub fn reconnect(&self) -> Self {
+        Self {
+            client: Self::client(),
+            ..self.clone()
+        }
+    }
+
+    fn client() -> reqwest::Client {
+        ProviderConfig::default().client()
+    }
+
+    /// 型番の製品ページ・検索結果のページのURL
+    fn search_url(&self, model_number: &str) -> ProviderResult<reqwest::Url> {
+        let model: String = model_number
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let url = self
+            .definition
+            .search_url
+            .replace("{model}", &url_encode(&model));
+        self.base().join(&url).map_err(ProviderError::wrap(
+            ErrorCode::InvalidInput,
+            "searchUrl is invalid",
+        ))
+    }
+
+    fn base(&self) -> reqwest::Url {
+        reqwest::Url::parse(&self.definition.base_url).expect("baseUrl is validated")
+    }
+
+    /// ページのHTMLからIESファイル（ZIP）のURLを抽出（文書順、重複排除）
+    fn extract_ies_urls(&self, page_url: &reqwest::Url, html: &str) -> Vec<String> {
+        let hrefs: Vec<String> = match &self.link {
+            LinkRule::Selector(selector) => html::links(html, selector)
+                .into_iter()
+                .map(|link| link.href)
+                .collect(),
+            LinkRule::Pattern(pattern) => pattern
+                .captures_iter(html)
+                .filter_map(|captures| captures.get(1).or_else(|| captures.get(0)))
+                .map(|m| m.as_str().replace("&amp;", "&"))
+                .collect(),
+        };
+        let mut urls = Vec::new();
+        for href in hrefs {
+            let Ok(url) = page_url.join(href.trim()) else {
+                continue;
+            };
+            if !matches!(url.scheme(), "http" | "https") {
+                continue;
+            }
+            if !self.definition.zip && url.path().to_lowercase().ends_with(".zip") {
+                continue;
+            }
+            let url = url.to_string();
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 型番のIESファイル（ZIP）のURLを探す（見つからない場合はエラー）
+    ///
+    /// 型番と照合できないリンクを使用した場合は警告を返す（[`select_ies_url`]）。
+    async fn find_ies(
+        &self,
+        model_number: &str,
+    ) -> ProviderResult<(String, Option<DownloadWarning>)> {
+        let page_url = self.search_url(model_number)?;
+        let response = send_request(self.client.get(page_url.clone()))
+            .await
+            .map_err(|e| e.context("Search request failed"))?;
+        if !response.status().is_success() {
+            return Err(status_error(
+                response.status(),
+                format!(
+                    "Product page for {} returned status: {}",
+                    model_number,
+                    response.status()
+                ),
+            ));
+        }
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        let urls = self.extract_ies_urls(&page_url, &html);
+        if urls.is_empty() {
+            tracing::warn!(
+                provider = %self.definition.id,
+                url = %page_url,
+                model_number,
+                "no IES links matched the definition"
+            );
+        }
+        select_ies_url(model_number, &urls)
+    }
+}
+
+/// 製品ページのIESファイル（ZIP）のリンクから、ファイル名が型番に最も一致するものを選ぶ
+///
+/// 一致するリンクがない場合はエラーにする（型番と無関係なファイルを保存しないため）。
+/// ただしリンクが1つだけの場合はそのリンクを使用し、型番と照合できなかったことを警告する。
+fn select_ies_url(
+    model_number: &str,
+    urls: &[String],
+) -> ProviderResult<(String, Option<DownloadWarning>)> {
+    let names: Vec<String> = urls.iter().map(|url| file_name(url)).collect();
+    if let Some(best) = select_best_file(model_number, &names)
+        .and_then(|name| names.iter().position(|n| *n == name))
+    {
+        return Ok((urls[best].clone(), None));
+    }
+    match urls {
+        [] => Err(ProviderError::new(
+            ErrorCode::IesNotAvailable,
+            format!("IES link not found for: {}", model_number),
+        )),
+        [url] => {
+            let message = format!(
+                "Used the only IES link on the page ({}), but its file name does not match {}",
+                names[0], model_number
+            );
+            Ok((
+                url.clone(),
+                Some(DownloadWarning::new(
+                    DownloadWarningKind::UnmatchedLink,
+                    message,
+                )),
+            ))
+        }
+        _ => Err(ProviderError::new(
+            ErrorCode::IesNotAvailable,
+            format!("No IES link matched {}: {}", model_number, names.join(", ")),
+        )),
+    }
+}
+
+/// URLのファイル名（クエリを除く）
+fn file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+/// 定義ファイルの読み込みに失敗したファイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionError {
+    pub file: String,
+    pub error: String,
+}
+
+/// ディレクトリの定義ファイルからプロバイダーを作成（ファイル名順。ディレクトリがない場合は空）
+///
+/// 読み込めない定義は飛ばし、エラーとして返す。同じIDの定義が複数ある場合は先のものを使う。
+pub fn load_dir(dir: &Path, reserved: &[&str]) -> (Vec<GenericProvider>, Vec<DefinitionError>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| DEFINITION_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    paths.sort();
+
+    let definitions = paths.into_iter().map(|path| {
+        let definition = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read provider definition: {}", e))
+            .and_then(|contents| ProviderDefinition::parse(&contents));
+        (path.to_string_lossy().into_owned(), definition)
+    });
+    build_providers(definitions, reserved)
+}
+
+/// プロバイダールールのバンドル（[`crate::rules_update`] で取得するJSON）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesBundle {
+    /// 定義ファイルと同じ形式のプロバイダーの定義
+    #[serde(default)]
+    pub providers: Vec<ProviderDefinition>,
+}
+
+impl RulesBundle {
+    pub fn parse(contents: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(contents).map_err(|e| format!("Invalid rules bundle: {}", e))
+    }
+}
+
+/// プロバイダールールのバンドルからプロバイダーを作成（ファイルがない場合は空）
+///
+/// 読み込めない定義は飛ばし、`{バンドルのパス}#{ID}` のエラーとして返す。
+pub fn load_bundle(path: &Path, reserved: &[&str]) -> (Vec<GenericProvider>, Vec<DefinitionError>) {
+    let file = path.to_string_lossy().into_owned();
+    let bundle = match std::fs::read(path) {
+        Ok(contents) => RulesBundle::parse(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (Vec::new(), Vec::new()),
+        Err(e) => Err(format!("Failed to read rules bundle: {}", e)),
+    };
+    match bundle {
+        Ok(bundle) => {
+            let definitions = bundle
+                .providers
+                .into_iter()
+                .map(|definition| (format!("{}#{}", file, definition.id), Ok(definition)));
+            build_providers(definitions, reserved)
+        }
+        Err(error) => (Vec::new(), vec![DefinitionError { file, error }]),
+    }
+}
+
+/// 定義からプロバイダーを作成（同じIDの定義が複数ある場合は先のものを使う）
+fn build_providers(
+    definitions: impl IntoIterator<Item = (String, Result<ProviderDefinition, String>)>,
+    reserved: &[&str],
+) -> (Vec<GenericProvider>, Vec<DefinitionError>) {
+    let mut providers: Vec<GenericProvider> = Vec::new();
+    let mut errors = Vec::new();
+    let mut ids: HashSet<String> = HashSet::new();
+    for (file, definition) in definitions {
+        let loaded = definition.and_then(|definition| {
+            let taken: Vec<&str> = reserved
+                .iter()
+                .copied()
+                .chain(ids.iter().map(String::as_str))
+                .collect();
+            GenericProvider::new(definition, &taken)
+        });
+        match loaded {
+            Ok(provider) => {
+                ids.insert(provider.definition.id.clone());
+                providers.push(provider);
+            }
+            Err(error) => errors.push(DefinitionError { file, error }),
+        }
+    }
+    (providers, errors)
+}
+
+#[async_trait]
+impl ManufacturerProvider for GenericProvider {
+    fn id(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.definition.display_name
+    }
+
+    fn base_url(&self) -> &str {
+        &self.definition.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.aliases.iter().map(String::as_str).collect()
+    }
+
+    fn sample_model_number(&self) -> Option<&str> {
+        self.definition.sample_model_number.as_deref()
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.definition.base_url).await
+    }
+
+    /// 製品ページ・検索結果のページのURLとIESファイルのURLのみ（製品名・定価は取得しない）
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        let (ies_file_url, _) = self.find_ies(model_number).await?;
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: None,
+            price: None,
+            ies_file_url: Some(ies_file_url),
+            image_url: None,
+            product_page_url: self
+                .search_url(model_number)
+                .ok()
+                .map(|url| url.to_string()),
+            accessories: Vec::new(),
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
+        })
+    }
+
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.find_ies(model_number)
+            .await
+            .map(|(url, _)| ResolvedIesUrl::direct(url))
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    /// ZIPの場合は型番に最も一致するIESファイルを取り出す（PSUは使用しない）
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let started = Instant::now();
+        let (url, warning) = cancel.run(self.find_ies(model_number)).await?;
+        let lookup_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let mut result = cancel
+            .run(direct::download_ies_to(
+                &self.client,
+                &url,
+                model_number,
+                dest_path,
+            ))
+            .await?;
+        if let Some(warning) = warning {
+            result = result.with_warning(warning);
+        }
+        Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+    }
+
+    fn default_filename_template(&self, asset_type: AssetType) -> &str {
+        match (&self.definition.filename_template, asset_type) {
+            (Some(template), AssetType::Ies) => template.as_str(),
+            _ => filename::DEFAULT_TEMPLATE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> ProviderDefinition {
+        ProviderDefinition::parse(
+            r#"{
+                "id": "example",
+                "displayName": "Example照明",
+                "baseUrl": "https://www.example.co.jp",
+                "aliases": ["Example照明", "example"],
+                "searchUrl": "/products/?q={model}",
+                "iesLinkSelector": "a.ies"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let toml = r#"
+            id = "example"
+            displayName = "Example照明"
+            baseUrl = "https://www.example.co.jp"
+            aliases = ["Example照明", "example"]
+            searchUrl = "/products/?q={model}"
+            iesLinkSelector = "a.ies"
+        "#;
+        assert_eq!(ProviderDefinition::parse(toml).unwrap(), definition());
+        assert!(ProviderDefinition::parse(r#"{"id": "example"}"#).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let provider = GenericProvider::new(definition(), &["koizumi"]).unwrap();
+        assert!(provider.can_handle("EXAMPLE照明"));
+        assert!(!provider.can_handle("大光電機"));
+        assert_eq!(
+            provider.search_url("XD 123/45").unwrap().as_str(),
+            "https://www.example.co.jp/products/?q=XD123%2F45"
+        );
+
+        let invalid = [
+            ProviderDefinition {
+                id: "koizumi".to_string(),
+                ..definition()
+            },
+            ProviderDefinition {
+                id: "Example".to_string(),
+                ..definition()
+            },
+            ProviderDefinition {
+                search_url: "/products/".to_string(),
+                ..definition()
+            },
+            ProviderDefinition {
+                ies_link_pattern: Some(r#"href="([^"]+\.ies)""#.to_string()),
+                ..definition()
+            },
+            ProviderDefinition {
+                ies_link_selector: Some("a[".to_string()),
+                ..definition()
+            },
+        ];
+        for definition in invalid {
+            assert!(GenericProvider::new(definition.clone(), &["koizumi"]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_extract_ies_urls() {
+        let html = r#"
+            <a class="ies" href="/ies/XD123.ies">XD123</a>
+            <a class="ies" href="files/XD123-W.ies">XD123-W</a>
+            <a class="ies" href="/ies/XD123.zip">ZIP</a>
+            <a class="ies" href="/ies/XD123.ies">XD123</a>
+        "#;
+        let page_url = reqwest::Url::parse("https://www.example.co.jp/products/").unwrap();
+        let provider = GenericProvider::new(definition(), &[]).unwrap();
+        assert_eq!(
+            provider.extract_ies_urls(&page_url, html),
+            vec![
+                "https://www.example.co.jp/ies/XD123.ies",
+                "https://www.example.co.jp/products/files/XD123-W.ies",
+            ]
+        );
+
+        let provider = GenericProvider::new(
+            ProviderDefinition {
+                ies_link_selector: None,
+                ies_link_pattern: Some(r#"href="([^"]+\.(?:ies|zip))""#.to_string()),
+                zip: true,
+                ..definition()
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            provider.extract_ies_urls(&page_url, html),
+            vec![
+                "https://www.example.co.jp/ies/XD123.ies",
+                "https://www.example.co.jp/products/files/XD123-W.ies",
+                "https://www.example.co.jp/ies/XD123.zip",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_ies_url() {
+        let urls = |names: &[&str]| -> Vec<String> {
+            names
+                .iter()
+                .map(|name| format!("https://www.example.co.jp/ies/{}", name))
+                .collect()
+        };
+
+        let (url, warning) = select_ies_url("XD123", &urls(&["AB999.ies", "XD123.ies"])).unwrap();
+        assert!(url.ends_with("/XD123.ies"));
+        assert!(warning.is_none());
+
+        // 一致するリンクがない場合は先頭のリンクを使わない
+        let error = select_ies_url("XD123", &urls(&["AB999.ies", "CD888.ies"])).unwrap_err();
+        assert_eq!(error.code, ErrorCode::IesNotAvailable);
+        assert_eq!(
+            select_ies_url("XD123", &[]).unwrap_err().code,
+            ErrorCode::IesNotAvailable
+        );
+
+        // リンクが1つだけの場合は警告を付けて使用する
+        let (url, warning) = select_ies_url("XD123", &urls(&["download.zip"])).unwrap();
+        assert!(url.ends_with("/download.zip"));
+        assert_eq!(warning.unwrap().kind, DownloadWarningKind::UnmatchedLink);
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = serde_json::to_string(&definition()).unwrap();
+        std::fs::write(dir.path().join("a.json"), &json).unwrap();
+        // 同じIDの定義は先のものを使う
+        std::fs::write(dir.path().join("b.json"), &json).unwrap();
+        std::fs::write(dir.path().join("c.toml"), "id = ").unwrap();
+        std::fs::write(dir.path().join("readme.txt"), "not a definition").unwrap();
+
+        let (providers, errors) = load_dir(dir.path(), &["koizumi"]);
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].id(), "example");
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].file.ends_with("b.json"));
+        assert!(errors[1].file.ends_with("c.toml"));
+
+        let (providers, errors) = load_dir(&dir.path().join("missing"), &[]);
+        assert!(providers.is_empty() && errors.is_empty());
+    }
+}
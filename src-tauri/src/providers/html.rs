@@ -0,0 +1,289 @@
+//! HTML解析
+//!
+//! メーカーサイトのHTMLからリンクを抽出する際のCSSセレクターをここにまとめて定義する。
+//! サイトの構成が変わった場合は、まずこのファイルのセレクターを確認する。
+//! 属性の順序や空白の違いに影響されないよう、正規表現ではなくHTMLパーサーで解析する。
+
+use scraper::{ElementRef, Html, Selector};
+use std::sync::LazyLock;
+
+/// コイズミ照明: 製品詳細ページへのリンク（`/kensaku/item/detail/?itemid=XXXX`）
+pub static KOIZUMI_ITEM_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/kensaku/item/detail/?itemid="]"#));
+
+/// コイズミ照明: 資料のダウンロードリンク（`/kensaku/download/file/file_type/{種別}/id/NNNN`）
+pub static KOIZUMI_DOWNLOAD_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/kensaku/download/file/file_type/"]"#));
+
+/// コイズミ照明: 詳細ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>定価</dt><dd>...</dd>`）
+pub static KOIZUMI_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// コイズミ照明: 製品画像（OGP画像、なければ商品写真の領域の画像）
+pub static KOIZUMI_PRODUCT_IMAGE: LazyLock<Selector> = LazyLock::new(|| {
+    selector(r#"meta[property="og:image"], .item_photo img, .item-photo img, #item_image img"#)
+});
+
+/// TOKISTAR: 検索結果のIES ZIPへのリンク（`.../wp-content/uploads/YYYY/MM/IES_XXX.zip`）
+pub static TOKISTAR_IES_ZIP_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/IES_"][href$=".zip"]"#));
+
+/// TOKISTAR: サイト内検索結果の製品ページへのリンク（`.../tokistar/products/osp01/`）
+pub static TOKISTAR_PRODUCT_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/tokistar/products/"]"#));
+
+/// TOKISTAR: 検索結果の次のページへのリンク（WordPressのページ送り）
+pub static TOKISTAR_NEXT_PAGE: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a.next.page-numbers, a.nextpostslink, a[rel="next"]"#));
+
+/// TOKISTAR: 製品ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>価格</dt><dd>...</dd>`）
+pub static TOKISTAR_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// TOKISTAR: 製品ページの見出し（仕様表に品名がない場合の製品名）
+pub static TOKISTAR_PRODUCT_TITLE: LazyLock<Selector> =
+    LazyLock::new(|| selector("h1.entry-title, .product-title, h1"));
+
+/// TOKISTAR: ZIPファイルへのリンク（IES ZIPのリンクが見つからない場合に、サイトの構成の変更を検出するため）
+pub static TOKISTAR_ZIP_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href$=".zip"], a[href*=".zip?"]"#));
+
+/// TOKISTAR: 製品画像（OGP画像、なければアイキャッチ画像）
+pub static TOKISTAR_PRODUCT_IMAGE: LazyLock<Selector> = LazyLock::new(|| {
+    selector(r#"meta[property="og:image"], img.wp-post-image, .product-image img"#)
+});
+
+/// パナソニック: 製品詳細ページへのリンク（`/products/detail/?hinban=XXXX`）
+pub static PANASONIC_ITEM_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/products/detail/?hinban="]"#));
+
+/// パナソニック: 資料のダウンロードリンク（`/download/?type={種別}&hinban=XXXX`）
+pub static PANASONIC_DOWNLOAD_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/download/?type="]"#));
+
+/// パナソニック: 詳細ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>希望小売価格</dt><dd>...</dd>`）
+pub static PANASONIC_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// パナソニック: 製品画像（OGP画像、なければ商品写真の領域の画像）
+pub static PANASONIC_PRODUCT_IMAGE: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"meta[property="og:image"], .product-image img, .item-image img"#));
+
+/// 大光電機: 製品詳細ページへのリンク（`/products/detail/?pno=XXXX`）
+pub static DAIKO_ITEM_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/products/detail/?pno="]"#));
+
+/// 大光電機: 配光データへのリンク（`/download/ies/XXXX.ies` / シリーズごとの `/download/ies/XXXX.zip`）
+pub static DAIKO_IES_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/download/ies/"]"#));
+
+/// 大光電機: 詳細ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>定価</dt><dd>...</dd>`）
+pub static DAIKO_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// 大光電機: 製品画像（OGP画像、なければ商品写真の領域の画像）
+pub static DAIKO_PRODUCT_IMAGE: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"meta[property="og:image"], .product-image img, .item-photo img"#));
+
+/// 遠藤照明: 製品詳細ページへのリンク（`/products/detail/?hinban=XXXX`）
+pub static ENDO_ITEM_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/products/detail/?hinban="]"#));
+
+/// 遠藤照明: 配光データへのリンク（`/download/haikou/XXXX.ies` / 配光角違いをまとめた `/download/haikou/XXXX.zip`）
+pub static ENDO_IES_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/download/haikou/"]"#));
+
+/// 遠藤照明: 詳細ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>定価</dt><dd>...</dd>`）
+pub static ENDO_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// 遠藤照明: 製品画像（OGP画像、なければ商品写真の領域の画像）
+pub static ENDO_PRODUCT_IMAGE: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"meta[property="og:image"], .product-image img, .item-photo img"#));
+
+/// オーデリック: 品番ページへのリンク（`/products/item/?hinban=XXXX`）
+pub static ODELIC_ITEM_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/products/item/?hinban="]"#));
+
+/// オーデリック: 品番ページから親シリーズのページへのリンク（`/products/series/?id=XXXX`）
+pub static ODELIC_SERIES_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/products/series/"]"#));
+
+/// オーデリック: 配光データへのリンク（`/download/ies/XXXX.ies`）
+pub static ODELIC_IES_LINK: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"a[href*="/download/ies/"]"#));
+
+/// オーデリック: 品番ページの仕様表の見出し（`<th>品名</th><td>...</td>` / `<dt>定価</dt><dd>...</dd>`）
+pub static ODELIC_SPEC_LABEL: LazyLock<Selector> = LazyLock::new(|| selector("th, dt"));
+
+/// オーデリック: 製品画像（OGP画像、なければ商品写真の領域の画像）
+pub static ODELIC_PRODUCT_IMAGE: LazyLock<Selector> =
+    LazyLock::new(|| selector(r#"meta[property="og:image"], .product-image img, .item-photo img"#));
+
+static BODY: LazyLock<Selector> = LazyLock::new(|| selector("body"));
+static TITLE: LazyLock<Selector> = LazyLock::new(|| selector("title"));
+
+/// 証拠として本文から切り出す文字数（`needle` の前後）
+const EVIDENCE_CONTEXT_CHARS: usize = 60;
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("Invalid CSS selector")
+}
+
+/// 利用者が定義したCSSセレクターを解析（カスタムプロバイダーの定義用）
+pub fn parse_selector(css: &str) -> Result<Selector, String> {
+    Selector::parse(css).map_err(|e| format!("Invalid CSS selector {}: {}", css, e))
+}
+
+/// HTML中のリンク
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub href: String,
+    /// リンクテキスト（タグを除き、連続する空白を1つにまとめたもの）
+    pub text: String,
+}
+
+/// セレクターに一致するリンクを文書順に抽出
+pub fn links(html: &str, selector: &Selector) -> Vec<Link> {
+    let document = Html::parse_document(html);
+    document
+        .select(selector)
+        .filter_map(|element| {
+            let href = element.value().attr("href")?.to_string();
+            Some(Link {
+                href,
+                text: text(element),
+            })
+        })
+        .collect()
+}
+
+/// 要素のテキスト（タグを除き、連続する空白を1つにまとめたもの）
+fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// セレクターに一致する最初の要素のテキスト（空のものは除く）
+pub fn first_text(html: &str, selector: &Selector) -> Option<String> {
+    let document = Html::parse_document(html);
+    document
+        .select(selector)
+        .map(text)
+        .find(|text| !text.is_empty())
+}
+
+/// 文書全体の本文のテキスト（`script` / `style` を除き、連続する空白を1つにまとめたもの）
+pub fn body_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    document
+        .select(&BODY)
+        .next()
+        .map(|body| {
+            body.descendants()
+                .filter_map(|node| {
+                    let text = node.value().as_text()?;
+                    let parent = node.parent().and_then(ElementRef::wrap)?;
+                    (!matches!(parent.value().name(), "script" | "style")).then_some(&**text)
+                })
+                .flat_map(str::split_whitespace)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default()
+}
+
+/// 見出しが `labels` のいずれかで始まる項目の値を抽出
+///
+/// 見出し（セレクターに一致する `th` / `dt`）の直後の要素（`td` / `dd`）のテキストを返す。
+/// 見出しの空白は無視する（"品　名" も "品名" とみなす）。
+pub fn labeled_value(html: &str, selector: &Selector, labels: &[&str]) -> Option<String> {
+    let document = Html::parse_document(html);
+    document.select(selector).find_map(|element| {
+        let label: String = text(element).split_whitespace().collect();
+        if !labels.iter().any(|l| label.starts_with(l)) {
+            return None;
+        }
+        let value = element.next_siblings().find_map(ElementRef::wrap)?;
+        Some(text(value)).filter(|v| !v.is_empty())
+    })
+}
+
+/// サイトの構成の変更を報告する際の証拠（ページのタイトルと、本文の `needle` 周辺の抜粋）
+///
+/// 本文に `needle` がない場合は本文の先頭を抜粋する。
+pub fn evidence(html: &str, needle: &str) -> String {
+    let title = first_text(html, &TITLE).unwrap_or_default();
+    let text = body_text(html);
+    let chars: Vec<char> = text.chars().collect();
+    // 英字の大文字・小文字を無視して探す（ASCIIのみ変換するため位置はずれない）
+    let center = Some(needle)
+        .filter(|needle| !needle.is_empty())
+        .and_then(|needle| text.to_ascii_uppercase().find(&needle.to_ascii_uppercase()))
+        .map(|byte| text[..byte].chars().count())
+        .unwrap_or(0);
+    let start = center.saturating_sub(EVIDENCE_CONTEXT_CHARS);
+    let end = (center + EVIDENCE_CONTEXT_CHARS * 2).min(chars.len());
+    let excerpt: String = chars[start..end].iter().collect();
+    format!(
+        "title \"{}\", text \"{}{}{}\"",
+        title,
+        if start > 0 { "…" } else { "" },
+        excerpt,
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+/// セレクターに一致する最初の画像のURL（`meta` は `content`、`img` は `src`）
+pub fn image_url(html: &str, selector: &Selector) -> Option<String> {
+    let document = Html::parse_document(html);
+    document.select(selector).find_map(|element| {
+        let value = element.value();
+        let url = match value.name() {
+            "meta" => value.attr("content"),
+            _ => value.attr("src"),
+        }?;
+        Some(url.trim().to_string()).filter(|url| !url.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links() {
+        // 属性の順序・引用符・改行の違いに影響されない
+        let html = r#"
+            <a class="btn"
+               href='/kensaku/item/detail/?itemid=XE92701' target="_blank">
+              <span>DALI調光電源</span>XE92701
+            </a>
+            <a href="/other/">other</a>
+        "#;
+
+        let links = links(html, &KOIZUMI_ITEM_LINK);
+        assert_eq!(
+            links,
+            vec![Link {
+                href: "/kensaku/item/detail/?itemid=XE92701".to_string(),
+                text: "DALI調光電源 XE92701".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_evidence() {
+        let html = format!(
+            "<html><head><title>商品詳細</title></head><body><p>{}</p><p>型番 AD12345 LEDダウンライト</p></body></html>",
+            "あ".repeat(100)
+        );
+        let excerpt = evidence(&html, "ad12345");
+        assert!(excerpt.starts_with("title \"商品詳細\", text \"…"));
+        assert!(excerpt.contains("型番 AD12345 LEDダウンライト\""));
+
+        // 本文に見つからない場合は先頭を抜粋する
+        assert_eq!(
+            evidence("<body>empty</body>", "AD12345"),
+            "title \"\", text \"empty\""
+        );
+    }
+}
@@ -3,25 +3,86 @@
 //! コイズミ照明 Webカタログ (webcatalog.koizumi-lt.co.jp) からの
 //! 製品情報・IESファイル取得を担当する。
 
-use super::{DownloadResult, ManufacturerProvider, ProductInfo};
+use super::html::{
+    self, KOIZUMI_DOWNLOAD_LINK, KOIZUMI_ITEM_LINK, KOIZUMI_PRODUCT_IMAGE, KOIZUMI_SPEC_LABEL,
+};
+use super::{
+    can_request_decision, check_url, closest_candidates, describe_candidates,
+    download_product_image, fetch_content_length, filename_from_content_disposition, not_available,
+    page_mentions, parse_price, price_from_candidates, provider_outdated, report_phase,
+    request_decision, retry_incomplete, send_request, verify_length, verify_written, Accessory,
+    AccessoryKind, AssetType, BatchCache, CancelToken, DecisionCandidate, DecisionKind,
+    DecisionRequest, Diagnosis, Discontinuation, DownloadPhase, DownloadResult, DownloadSource,
+    DownloadWarning, DownloadWarningKind, ManufacturerProvider, Price, ProductCandidate,
+    ProductInfo, ProviderConfig, ResolvedIesUrl,
+};
+use crate::buffer;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::filename;
+use crate::longpath;
 use async_trait::async_trait;
 use regex::Regex;
-use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use std::time::Instant;
+use tokio::sync::OnceCell;
+
+/// コイズミ照明のWebカタログの既定のベースURL（設定の `providers.koizumi.baseUrl` で変更できる）
+const BASE_URL: &str = "https://webcatalog.koizumi-lt.co.jp";
+
+/// PSU文字列末尾の型番（"DALI調光電源：XE92701" の "XE92701"）
+static PSU_MODEL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[:：]\s*([A-Za-z0-9]+)$").unwrap());
+/// FIXTURE文字列中のコロン区切りの型番（"本体：AH92025L" の "AH92025L"）
+static FIXTURE_MODEL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[:：]\s*([A-Za-z0-9]+)").unwrap());
+
+/// 廃番の表記（"この商品は廃番となりました" 等）
+static DISCONTINUED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"廃番(?:品|商品です|となりました|になりました)|(?:生産|販売)を?終了(?:品|しました|いたしました)")
+        .unwrap()
+});
+/// 後継品の型番（"後継品：AD12346" / "代替品は AD12346" 等）
+static SUCCESSOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:後継品|後継機種|代替品|推奨品)\s*(?:[:：]|は)?\s*([A-Z]{1,4}[0-9]{3,}[A-Z0-9]*)",
+    )
+    .unwrap()
+});
+
+/// 型番が見つからない場合に提示する候補の数
+const MAX_SUGGESTIONS: usize = 5;
+/// 候補の検索で型番を短くする場合の最小の文字数
+const MIN_SUGGESTION_KEYWORD_LEN: usize = 4;
 
 /// コイズミ照明プロバイダー
 pub struct KoizumiProvider {
     base_url: String,
     client: reqwest::Client,
+    /// 一括ダウンロード中に解決したIESファイルのURL（item_id ごと）
+    /// Spec No.違いで同じ器具が並ぶスケジュールで、同じ詳細ページを何度も取得しないためのもの。
+    /// 同時に処理中の行は先に始めた取得の完了を待つ。一括ダウンロード中以外は保持しない
+    ies_url_cache: BatchCache<Arc<OnceCell<Vec<DownloadLink>>>>,
+}
+
+/// 詳細ページの資料のダウンロードリンク
+#[derive(Debug, Clone, PartialEq)]
+struct DownloadLink {
+    url: String,
+    /// リンクテキスト（出力・電源の違い等の区別に使う）
+    label: String,
 }
 
 impl KoizumiProvider {
     pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
         Self {
-            base_url: "https://webcatalog.koizumi-lt.co.jp".to_string(),
-            client: reqwest::Client::builder()
-                .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
-                .build()
-                .expect("Failed to create HTTP client"),
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+            ies_url_cache: BatchCache::default(),
         }
     }
 
@@ -30,8 +91,9 @@ impl KoizumiProvider {
     /// 例: "DALI調光電源" → None
     fn extract_psu_model_number(psu: &str) -> Option<&str> {
         // 半角または全角コロンで分割し、末尾の英数字部分を抽出
-        let re = Regex::new(r"[:：]\s*([A-Za-z0-9]+)$").unwrap();
-        re.captures(psu).map(|caps| caps.get(1).unwrap().as_str())
+        PSU_MODEL_RE
+            .captures(psu)
+            .map(|caps| caps.get(1).unwrap().as_str())
     }
 
     /// FIXTURE文字列から型番を抽出
@@ -39,8 +101,7 @@ impl KoizumiProvider {
     /// 単一の型番の場合（例: "XD93319"）はそのまま返す
     fn extract_fixture_model_numbers(fixture: &str) -> Vec<String> {
         // コロン区切りパターン（本体：XX, ユニット：XX）を検索
-        let re = Regex::new(r"[:：]\s*([A-Za-z0-9]+)").unwrap();
-        let matches: Vec<String> = re
+        let matches: Vec<String> = FIXTURE_MODEL_RE
             .captures_iter(fixture)
             .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
             .collect();
@@ -66,60 +127,501 @@ impl KoizumiProvider {
         parts.join("+")
     }
 
-    /// 製品ページからIESファイルのダウンロードURLを取得
+    /// アセット種別に対応する詳細ページ上のダウンロード種別
+    /// パターン: /kensaku/download/file/file_type/{種別}/id/xxxxx
+    /// 確認済みの種別は配光データ（spec/providers/KOIZUMI.md）のみ。
+    /// 製品画像はダウンロードリンクとして公開されていないため対象外
+    fn file_type(asset_type: AssetType) -> Option<&'static str> {
+        match asset_type {
+            AssetType::Ies => Some("haikou_data"),
+            _ => None,
+        }
+    }
+
+    /// 製品ページからIESファイル（配光データ）のダウンロードリンクを取得
     /// item_id: 型番（PSUがある場合は "型番+PSU型番" 形式）
-    async fn get_ies_download_url(&self, item_id: &str) -> Result<Option<String>, String> {
+    /// 一括ダウンロード中は item_id ごとに結果を再利用する（失敗した場合は再利用しない）
+    async fn get_ies_download_links(&self, item_id: &str) -> ProviderResult<Vec<DownloadLink>> {
+        match self
+            .ies_url_cache
+            .get_or_insert_with(item_id, Default::default)
+        {
+            Some(cell) => cell
+                .get_or_try_init(|| self.get_download_links(item_id, AssetType::Ies))
+                .await
+                .cloned(),
+            None => self.get_download_links(item_id, AssetType::Ies).await,
+        }
+    }
+
+    /// 製品ページからIESファイルのダウンロードURLを取得
+    ///
+    /// 配光データのリンクが複数ある場合（出力・電源違い）は、リンクテキストにPSUの型番・名称、
+    /// 器具の型番を含むものに絞り込む。1つに絞り込めない場合は利用者に選んでもらい、
+    /// 選ばれなければ候補を含むエラーにする。
+    async fn get_ies_download_url(
+        &self,
+        item_id: &str,
+        model_number: &str,
+        psu: Option<&str>,
+    ) -> ProviderResult<Option<String>> {
+        let links = self.get_ies_download_links(item_id).await?;
+        let candidates = Self::narrow_ies_links(&links, model_number, psu);
+        if candidates.len() > 1 {
+            let request = DecisionRequest {
+                kind: DecisionKind::IesFile,
+                candidates: candidates
+                    .iter()
+                    .map(|link| DecisionCandidate {
+                        value: link.url.clone(),
+                        label: link.label.clone(),
+                    })
+                    .collect(),
+                selected: None,
+            };
+            if let Some(url) = request_decision(request).await {
+                return Ok(Some(url));
+            }
+        }
+        Self::select_ies_link(&links, item_id, model_number, psu)
+    }
+
+    /// 配光データのリンクから型番・PSUに対応するものを選ぶ
+    fn select_ies_link(
+        links: &[DownloadLink],
+        item_id: &str,
+        model_number: &str,
+        psu: Option<&str>,
+    ) -> ProviderResult<Option<String>> {
+        let candidates = Self::narrow_ies_links(links, model_number, psu);
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [link] => Ok(Some(link.url.clone())),
+            _ => Err(ProviderError::new(
+                ErrorCode::IesAmbiguous,
+                format!(
+                    "Multiple IES files found for {}: {}",
+                    item_id,
+                    candidates
+                        .iter()
+                        .map(|link| format!("{} ({})", link.label, link.url))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// 配光データのリンクを、リンクテキストにPSU・型番を含むものに絞り込む
+    /// （絞り込むと候補がなくなるヒントは使わない）
+    fn narrow_ies_links<'a>(
+        links: &'a [DownloadLink],
+        model_number: &str,
+        psu: Option<&str>,
+    ) -> Vec<&'a DownloadLink> {
+        let normalize = |s: &str| s.split_whitespace().collect::<String>().to_uppercase();
+        let psu = psu.map(str::trim).filter(|p| !p.is_empty());
+        let hints = [
+            psu.and_then(Self::extract_psu_model_number),
+            psu.map(|p| p.split([':', '：']).next().unwrap_or(p)),
+            Some(model_number),
+        ];
+
+        let mut candidates: Vec<&DownloadLink> = links.iter().collect();
+        for hint in hints.into_iter().flatten().map(normalize) {
+            if candidates.len() <= 1 || hint.is_empty() {
+                break;
+            }
+            let narrowed: Vec<_> = candidates
+                .iter()
+                .copied()
+                .filter(|link| normalize(&link.label).contains(&hint))
+                .collect();
+            if !narrowed.is_empty() {
+                candidates = narrowed;
+            }
+        }
+        candidates
+    }
+
+    /// 型番・PSUからIESファイルのダウンロードURLを取得
+    /// PSU指定時は見つからなければ型番のみで再検索する（その場合は警告を返す）
+    async fn find_ies_download_url(
+        &self,
+        model_number: &str,
+        psu: Option<&str>,
+    ) -> ProviderResult<(String, Option<DownloadWarning>)> {
+        // item_idを生成（PSUがある場合は結合）
+        let item_id = Self::build_item_id(model_number, psu);
+
+        match self
+            .get_ies_download_url(&item_id, model_number, psu)
+            .await?
+        {
+            Some(url) => Ok((url, None)),
+            None => {
+                // PSU指定ありで見つからない場合、型番のみで再検索
+                if psu.is_some_and(|p| !p.is_empty()) {
+                    let Some(url) = self
+                        .get_ies_download_url(model_number, model_number, psu)
+                        .await?
+                    else {
+                        let error = ProviderError::new(
+                            ErrorCode::IesNotAvailable,
+                            format!("IES file not found for: {} nor {}", item_id, model_number),
+                        );
+                        return Err(self.with_suggestions(error, model_number).await);
+                    };
+                    let warning = DownloadWarning::new(
+                        DownloadWarningKind::PsuFallback,
+                        format!(
+                            "IES file not found for {}, used {} without PSU",
+                            item_id, model_number
+                        ),
+                    );
+                    Ok((url, Some(warning)))
+                } else {
+                    let error = not_available(AssetType::Ies, &item_id);
+                    Err(self.with_suggestions(error, model_number).await)
+                }
+            }
+        }
+    }
+
+    /// 型番が見つからない場合のエラーメッセージに、キーワード検索で見つけた近い型番の候補を付ける
+    ///
+    /// 型番の誤記・末尾の記号の抜け等を想定し、型番で見つからなければ末尾の2文字を除いて検索する。
+    /// 型番が完全一致する製品がある場合（掲載はあるがIESがない）や検索に失敗した場合は
+    /// 元のメッセージを返す。
+    async fn with_suggestions(&self, error: ProviderError, model_number: &str) -> ProviderError {
+        let closest = self.closest_products(model_number).await;
+        if closest.is_empty() {
+            return error;
+        }
+        ProviderError::new(
+            error.code,
+            format!(
+                "{}; closest matches: {}",
+                error,
+                describe_candidates(&closest)
+            ),
+        )
+    }
+
+    /// キーワード検索で見つけた近い型番の製品（型番が完全一致する製品がある場合・検索に失敗した場合は空）
+    async fn closest_products(&self, model_number: &str) -> Vec<ProductCandidate> {
+        let mut keywords = vec![model_number];
+        let chars: Vec<(usize, char)> = model_number.char_indices().collect();
+        if chars.len() >= MIN_SUGGESTION_KEYWORD_LEN + 2 {
+            keywords.push(&model_number[..chars[chars.len() - 2].0]);
+        }
+
+        for keyword in keywords {
+            let candidates = match self.search_products(keyword).await {
+                Ok(candidates) => candidates,
+                Err(e) => {
+                    tracing::debug!(error = %e, keyword, "candidate search failed");
+                    return Vec::new();
+                }
+            };
+            if candidates
+                .iter()
+                .any(|c| c.model_number.eq_ignore_ascii_case(model_number))
+            {
+                return Vec::new();
+            }
+            if !candidates.is_empty() {
+                return closest_candidates(candidates, model_number, MAX_SUGGESTIONS);
+            }
+        }
+        Vec::new()
+    }
+
+    /// 型番でIESファイルが見つからない場合に、近い型番の製品から利用者に選んでもらう
+    /// （判断の依頼先がない・候補がない・選ばれなかった場合は `None`）
+    async fn choose_product(&self, model_number: &str, error: &ProviderError) -> Option<String> {
+        if !can_request_decision() || error.code != ErrorCode::IesNotAvailable {
+            return None;
+        }
+        let closest = self.closest_products(model_number).await;
+        if closest.is_empty() {
+            return None;
+        }
+        let candidates = closest
+            .iter()
+            .map(|c| DecisionCandidate {
+                value: c.model_number.clone(),
+                label: describe_candidates(std::slice::from_ref(c)),
+            })
+            .collect();
+        request_decision(DecisionRequest {
+            kind: DecisionKind::Product,
+            candidates,
+            selected: None,
+        })
+        .await
+    }
+
+    /// 製品ページから指定アセットのダウンロードURLを取得（複数ある場合は最初のもの）
+    async fn get_download_url(
+        &self,
+        item_id: &str,
+        asset_type: AssetType,
+    ) -> ProviderResult<Option<String>> {
+        let links = self.get_download_links(item_id, asset_type).await?;
+        Ok(links.into_iter().next().map(|link| link.url))
+    }
+
+    /// 製品ページから指定アセットのダウンロードリンクをすべて取得
+    async fn get_download_links(
+        &self,
+        item_id: &str,
+        asset_type: AssetType,
+    ) -> ProviderResult<Vec<DownloadLink>> {
+        if Self::file_type(asset_type).is_none() {
+            return Ok(Vec::new());
+        }
+
+        let html = self.fetch_detail_page(item_id).await?;
+        let links = self.extract_download_links(&html, asset_type);
+        if links.is_empty() {
+            // 廃番で資料が掲載されていない場合は後継品の型番を含むエラーにする
+            if let Some(discontinued) = self.extract_discontinuation(&html) {
+                return Err(discontinued.error(item_id));
+            }
+            if Self::is_unrecognized_detail_page(&html, item_id) {
+                return Err(provider_outdated(
+                    self.id(),
+                    "download link or spec table",
+                    item_id,
+                    &html,
+                ));
+            }
+        }
+        Ok(links)
+    }
+
+    /// 型番が掲載されているのに、資料のダウンロードリンクも仕様表も見つからない詳細ページか
+    /// （サイトの構成が変わり、セレクターが一致しなくなった可能性が高い）
+    fn is_unrecognized_detail_page(html: &str, item_id: &str) -> bool {
+        let model_number = item_id.split('+').next().unwrap_or(item_id);
+        html::links(html, &KOIZUMI_DOWNLOAD_LINK).is_empty()
+            && Self::extract_product_name(html).is_none()
+            && page_mentions(html, model_number)
+    }
+
+    /// 製品詳細ページのURL
+    fn detail_url(&self, item_id: &str) -> String {
         // itemid パラメータで直接アクセス（+ は %2B にエンコード）
         let encoded_id = item_id.replace('+', "%2B");
-        let detail_url = format!(
+        format!(
             "{}/kensaku/item/detail/?itemid={}",
             self.base_url, encoded_id
-        );
+        )
+    }
 
-        let response = self
-            .client
-            .get(&detail_url)
-            .send()
+    /// 製品詳細ページのHTMLを取得
+    async fn fetch_detail_page(&self, item_id: &str) -> ProviderResult<String> {
+        let response = send_request(self.client.get(self.detail_url(item_id)))
             .await
-            .map_err(|e| format!("Detail request failed: {}", e))?;
+            .map_err(|e| e.context("Detail request failed"))?;
 
-        let html = response
-            .text()
-            .await
-            .map_err(|e| format!("Failed to read response: {}", e))?;
-
-        // IESダウンロードリンクを抽出（配光データIES）
-        // パターン: /kensaku/download/file/file_type/haikou_data/id/xxxxx
-        let re = Regex::new(r#"/kensaku/download/file/file_type/haikou_data/id/(\d+)"#).unwrap();
-        if let Some(caps) = re.captures(&html) {
-            let file_id = &caps[1];
-            return Ok(Some(format!(
-                "{}/kensaku/download/file/file_type/haikou_data/id/{}",
-                self.base_url, file_id
-            )));
+        response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))
+    }
+
+    /// 詳細ページのHTMLから指定アセットのダウンロードURLを抽出（複数ある場合は最初のもの）
+    fn extract_download_url(&self, html: &str, asset_type: AssetType) -> Option<String> {
+        self.extract_download_links(html, asset_type)
+            .into_iter()
+            .next()
+            .map(|link| link.url)
+    }
+
+    /// 詳細ページのHTMLから指定アセットのダウンロードリンクをすべて抽出（URLで重複排除）
+    fn extract_download_links(&self, html: &str, asset_type: AssetType) -> Vec<DownloadLink> {
+        // ダウンロードリンクを抽出（IESの場合は配光データ）
+        let Some(file_type) = Self::file_type(asset_type) else {
+            return Vec::new();
+        };
+        let path = format!("/kensaku/download/file/file_type/{}/id/", file_type);
+        let mut links: Vec<DownloadLink> = Vec::new();
+        for link in html::links(html, &KOIZUMI_DOWNLOAD_LINK) {
+            let Some((_, rest)) = link.href.split_once(&path) else {
+                continue;
+            };
+            let id: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            if id.is_empty() {
+                continue;
+            }
+            let url = format!("{}{}{}", self.base_url, path, id);
+            if !links.iter().any(|l| l.url == url) {
+                links.push(DownloadLink {
+                    url,
+                    label: link.text,
+                });
+            }
+        }
+        links
+    }
+
+    /// 詳細ページのHTMLから廃番の表記と後継品の型番を抽出（廃番でない場合は None）
+    fn extract_discontinuation(&self, html: &str) -> Option<Discontinuation> {
+        let text = html::body_text(html);
+        if !DISCONTINUED_RE.is_match(&text) {
+            return None;
         }
+        let successor = SUCCESSOR_RE.captures(&text).map(|caps| caps[1].to_string());
+        Some(Discontinuation {
+            successor_page_url: successor.as_deref().map(|s| self.detail_url(s)),
+            successor,
+        })
+    }
+
+    /// 詳細ページのHTMLから品名を抽出
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &KOIZUMI_SPEC_LABEL, &["品名", "商品名"])
+    }
 
-        Ok(None)
+    /// コイズミ照明の定価は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 詳細ページのHTMLから定価を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(html, &KOIZUMI_SPEC_LABEL, &["定価", "希望小売価格"])
+            .as_deref()
+            .and_then(Self::parse_list_price)
+    }
+
+    /// 詳細ページのHTMLから製品画像のURLを抽出（相対URLは絶対URLにする）
+    fn extract_image_url(&self, html: &str) -> Option<String> {
+        let url = html::image_url(html, &KOIZUMI_PRODUCT_IMAGE)?;
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Some(url)
+        } else if let Some(rest) = url.strip_prefix("//") {
+            Some(format!("https://{}", rest))
+        } else if url.starts_with('/') {
+            Some(format!("{}{}", self.base_url, url))
+        } else {
+            None
+        }
     }
 
-    /// Content-Dispositionヘッダーからファイル名を抽出
-    fn extract_filename_from_header(header_value: &str) -> Option<String> {
-        // パターン: filename="xxx.ies" または filename*=UTF-8''xxx.ies
-        if let Some(start) = header_value.find("filename=") {
-            let rest = &header_value[start + 9..];
-            let filename = if rest.starts_with('"') {
-                // filename="xxx.ies"
-                rest.trim_start_matches('"')
-                    .split('"')
-                    .next()
-                    .map(|s| s.to_string())
+    /// HTMLから製品詳細ページへのリンクを抽出
+    /// 戻り値: (型番, リンクテキスト) の一覧（型番で重複排除）
+    fn extract_item_links(html: &str) -> Vec<(String, Option<String>)> {
+        let mut links: Vec<(String, Option<String>)> = Vec::new();
+        for link in html::links(html, &KOIZUMI_ITEM_LINK) {
+            // itemid が英数字のみのリンク（単一の型番）が対象
+            let Some((_, model_number)) = link.href.split_once("itemid=") else {
+                continue;
+            };
+            if model_number.is_empty() || !model_number.chars().all(|c| c.is_ascii_alphanumeric()) {
+                continue;
+            }
+            let model_number = model_number.to_string();
+            if links.iter().any(|(m, _)| *m == model_number) {
+                continue;
+            }
+
+            let text = link.text;
+            let text = if text.is_empty() || text == model_number {
+                None
             } else {
-                // filename=xxx.ies
-                rest.split(';').next().map(|s| s.trim().to_string())
+                Some(text)
             };
-            return filename;
+            links.push((model_number, text));
         }
-        None
+
+        links
+    }
+
+    /// 詳細ページのHTMLから適合部材（別売電源・フレーム等）へのリンクを抽出
+    /// 自身の型番へのリンクは除外する
+    fn extract_accessories(&self, html: &str, item_id: &str) -> Vec<Accessory> {
+        let own_parts: Vec<&str> = item_id.split('+').collect();
+
+        Self::extract_item_links(html)
+            .into_iter()
+            .filter(|(model_number, _)| !own_parts.contains(&model_number.as_str()))
+            .map(|(model_number, name)| Accessory {
+                product_page_url: Some(self.detail_url(&model_number)),
+                kind: name
+                    .as_deref()
+                    .map(AccessoryKind::classify)
+                    .unwrap_or(AccessoryKind::Other),
+                model_number,
+                name,
+            })
+            .collect()
+    }
+
+    /// ファイルをダウンロードして保存（受信が不完全な場合は1回だけやり直す）
+    async fn download_file(&self, url: &str, dest_path: &str) -> ProviderResult<DownloadResult> {
+        retry_incomplete(|| self.download_file_once(url, dest_path)).await
+    }
+
+    async fn download_file_once(
+        &self,
+        url: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+
+        if !response.status().is_success() {
+            return Ok(DownloadResult::http_failure(response.status()));
+        }
+        let source = DownloadSource::from_response(&response);
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+        // Content-Dispositionヘッダーから元のファイル名を取得
+        let original_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|h| h.to_str().ok())
+            .and_then(filename_from_content_disposition);
+
+        // 保存し終えるまでバッファの使用枠を保持する
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+
+        let file_size = bytes.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+        verify_length(total_bytes, file_size)?;
+
+        // ファイルを保存
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        verify_written(&dest, file_size).await?;
+
+        Ok(
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+                .with_source(source),
+        )
     }
 }
 
@@ -131,60 +633,97 @@ impl Default for KoizumiProvider {
 
 #[async_trait]
 impl ManufacturerProvider for KoizumiProvider {
+    fn id(&self) -> &str {
+        "koizumi"
+    }
+
     fn display_name(&self) -> &str {
         "コイズミ照明"
     }
 
-    fn can_handle(&self, manufacturer: &str) -> bool {
-        let lower = manufacturer.to_lowercase();
-        lower.contains("コイズミ")
-            || lower.contains("koizumi")
-            || lower.contains("こいずみ")
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["コイズミ", "koizumi", "こいずみ"]
     }
 
-    async fn fetch_product_info(&self, model_number: &str) -> Result<ProductInfo, String> {
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    fn sample_model_number(&self) -> Option<&str> {
+        Some("XD93319")
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
         // 型番から直接製品ページにアクセス
-        // IESファイルURLを取得
-        let ies_file_url = self.get_ies_download_url(model_number).await?;
+        // 品名・定価・製品画像・IESファイルURL・適合部材・廃番の情報を取得
+        let html = self.fetch_detail_page(model_number).await?;
+        let ies_file_url = self.extract_download_url(&html, AssetType::Ies);
+        let accessories = self.extract_accessories(&html, model_number);
 
         Ok(ProductInfo {
             model_number: model_number.to_string(),
-            product_name: None,
-            price: None,
+            product_name: Self::extract_product_name(&html),
+            price: Self::extract_price(&html),
             ies_file_url,
-            image_url: None,
-            product_page_url: Some(format!(
-                "{}/kensaku/item/detail/?itemid={}",
-                self.base_url, model_number
-            )),
+            image_url: self.extract_image_url(&html),
+            product_page_url: Some(self.detail_url(model_number)),
+            accessories,
+            discontinued: self.extract_discontinuation(&html),
+            replaces: None,
+            match_quality: None,
         })
     }
 
-    fn generate_filename(
-        &self,
-        spec_no: &str,
-        model_number: &str,
-        psu: Option<&str>,
-        original_filename: Option<&str>,
-    ) -> String {
-        // コイズミの場合、元ファイル名に型番+PSUが含まれている
-        // 形式: {Spec No.}_{元ファイル名}
-        // 元ファイル名がない場合: {Spec No.}_{型番}_{PSU}.ies
-        match original_filename {
-            Some(orig) => {
-                let name = orig.trim_end_matches(".ies").trim_end_matches(".IES");
-                format!("{}_{}.ies", spec_no, name)
-            }
-            None => {
-                let safe_model = model_number.replace("/", "_").replace("\\", "_");
-                match psu {
-                    Some(p) if !p.is_empty() => {
-                        let safe_psu = p.replace("/", "_").replace("\\", "_");
-                        format!("{}_{}+{}.ies", spec_no, safe_model, safe_psu)
-                    }
-                    _ => format!("{}_{}.ies", spec_no, safe_model),
-                }
-            }
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        let search_url = format!("{}/kensaku/item/list/", self.base_url);
+
+        let response = send_request(self.client.get(&search_url).query(&[("keyword", keyword)]))
+            .await
+            .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_item_links(&html)
+            .into_iter()
+            .map(|(model_number, text)| ProductCandidate {
+                product_page_url: Some(self.detail_url(&model_number)),
+                price: text.as_deref().and_then(Self::parse_list_price),
+                product_name: text,
+                model_number,
+            })
+            .collect())
+    }
+
+    /// 詳細ページに定価が掲載されていない場合（セット品等）は検索結果一覧から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let html = self.fetch_detail_page(model_number).await?;
+        if let Some(price) = Self::extract_price(&html) {
+            return Ok(Some(price));
+        }
+        let candidates = self.search_products(model_number).await?;
+        Ok(price_from_candidates(&candidates, model_number))
+    }
+
+    /// 元ファイル名に型番+PSUが含まれているため、元ファイル名がない場合も型番+PSUとする
+    fn default_filename_template(&self, asset_type: AssetType) -> &str {
+        match asset_type {
+            AssetType::Ies => "{spec_no}_{original|item}",
+            _ => filename::DEFAULT_TEMPLATE,
         }
     }
 
@@ -193,77 +732,114 @@ impl ManufacturerProvider for KoizumiProvider {
         model_number: &str,
         psu: Option<&str>,
         dest_path: &str,
-    ) -> Result<DownloadResult, String> {
-        // item_idを生成（PSUがある場合は結合）
-        let item_id = Self::build_item_id(model_number, psu);
-
-        // IESファイルURLを取得（PSU指定時は見つからなければ型番のみで再検索）
-        let ies_url = match self.get_ies_download_url(&item_id).await? {
-            Some(url) => url,
-            None => {
-                // PSU指定ありで見つからない場合、型番のみで再検索
-                if psu.is_some_and(|p| !p.is_empty()) {
-                    self.get_ies_download_url(model_number)
-                        .await?
-                        .ok_or_else(|| {
-                            format!("IES file not found for: {} nor {}", item_id, model_number)
-                        })?
-                } else {
-                    return Err(format!("IES file not available for: {}", item_id));
-                }
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let started = Instant::now();
+        let (ies_url, warning) = match cancel
+            .run(self.find_ies_download_url(model_number, psu))
+            .await
+        {
+            Ok(found) => found,
+            Err(e) => {
+                // 型番が見つからなければ、利用者が選んだ近い型番の製品から取得する
+                let choice = cancel
+                    .run(async {
+                        Ok::<_, ProviderError>(self.choose_product(model_number, &e).await)
+                    })
+                    .await?;
+                let Some(chosen) = choice else {
+                    return Err(e);
+                };
+                let (url, _) = cancel
+                    .run(self.find_ies_download_url(&chosen, None))
+                    .await?;
+                let warning = DownloadWarning::new(
+                    DownloadWarningKind::ProductSubstituted,
+                    format!("{} not found, used {} instead", model_number, chosen),
+                );
+                (url, Some(warning))
             }
         };
+        let lookup_ms = started.elapsed().as_millis() as u64;
 
         // IESファイルをダウンロード
-        let response = self
-            .client
-            .get(&ies_url)
-            .send()
+        let started = Instant::now();
+        let result = cancel
+            .run(self.download_file(&ies_url, dest_path))
+            .await?
+            .with_timing(lookup_ms, started.elapsed().as_millis() as u64);
+        Ok(match warning {
+            Some(warning) if result.success => result.with_warning(warning),
+            _ => result,
+        })
+    }
+
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.find_ies_download_url(model_number, psu)
             .await
-            .map_err(|e| format!("Download request failed: {}", e))?;
+            .map(|(url, _)| ResolvedIesUrl::direct(url))
+    }
 
-        if !response.status().is_success() {
-            return Ok(DownloadResult::failure(format!(
-                "Download failed with status: {}",
-                response.status()
-            )));
-        }
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
 
-        // Content-Dispositionヘッダーから元のファイル名を取得
-        let original_filename = response
-            .headers()
-            .get("content-disposition")
-            .and_then(|h| h.to_str().ok())
-            .and_then(Self::extract_filename_from_header);
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies, AssetType::Image]
+    }
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| format!("Failed to read file content: {}", e))?;
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type == AssetType::Image {
+            return download_product_image(self, &self.client, model_number, dest_path, cancel)
+                .await;
+        }
 
-        let file_size = bytes.len() as u64;
+        // IES以外のアセットは器具本体単位で公開されていることが多いため、
+        // PSU付きで見つからなければ型番のみで再検索
+        let started = Instant::now();
+        let item_id = Self::build_item_id(model_number, psu);
+        let url = match cancel
+            .run(self.get_download_url(&item_id, asset_type))
+            .await?
+        {
+            Some(url) => url,
+            None if item_id != model_number => cancel
+                .run(self.get_download_url(model_number, asset_type))
+                .await?
+                .ok_or_else(|| not_available(asset_type, &item_id))?,
+            None => return Err(not_available(asset_type, &item_id)),
+        };
+        let lookup_ms = started.elapsed().as_millis() as u64;
 
-        // ファイルを保存
-        let dest = Path::new(dest_path);
-        if let Some(parent) = dest.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        }
+        let started = Instant::now();
+        let result = cancel.run(self.download_file(&url, dest_path)).await?;
+        Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+    }
 
-        std::fs::write(dest_path, &bytes)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+    fn begin_batch(&self) {
+        self.ies_url_cache.begin();
+    }
 
-        Ok(DownloadResult::success(
-            dest_path.to_string(),
-            file_size,
-            original_filename,
-        ))
+    fn end_batch(&self) {
+        self.ies_url_cache.end();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filename::FilenameContext;
 
     #[test]
     fn test_can_handle() {
@@ -380,4 +956,337 @@ mod tests {
             "AH92025L+AE49422L+XE92701"
         );
     }
+
+    #[test]
+    fn test_extract_accessories() {
+        let provider = KoizumiProvider::new();
+        let html = r#"
+            <a href="/kensaku/item/detail/?itemid=AD12345">AD12345</a>
+            <td>適合電源</td>
+            <a class="link" href="/kensaku/item/detail/?itemid=XE92701">
+              <span>DALI調光電源</span> XE92701
+            </a>
+            <a href="/kensaku/item/detail/?itemid=AE49422L">AE49422L</a>
+            <a href="/kensaku/item/detail/?itemid=XE92701">XE92701</a>
+        "#;
+
+        let accessories = provider.extract_accessories(html, "AD12345");
+        assert_eq!(accessories.len(), 2);
+
+        // 自身の型番は除外し、重複は1件にまとめる
+        assert_eq!(accessories[0].model_number, "XE92701");
+        assert_eq!(accessories[0].name.as_deref(), Some("DALI調光電源 XE92701"));
+        assert_eq!(accessories[0].kind, AccessoryKind::PowerSupply);
+
+        // 名称が型番のみの場合は None
+        assert_eq!(accessories[1].model_number, "AE49422L");
+        assert_eq!(accessories[1].name, None);
+        assert_eq!(accessories[1].kind, AccessoryKind::Other);
+    }
+
+    #[test]
+    fn test_extract_product_details() {
+        let provider = KoizumiProvider::new();
+        let html = r#"
+            <html><head>
+              <meta property="og:image" content="/kensaku/images/item/AD12345.jpg">
+            </head><body>
+              <table>
+                <tr><th>品　名</th><td> LEDダウンライト </td></tr>
+                <tr><th>定価（税抜）</th>
+                    <td>¥12,800</td></tr>
+              </table>
+            </body></html>
+        "#;
+
+        assert_eq!(
+            KoizumiProvider::extract_product_name(html).as_deref(),
+            Some("LEDダウンライト")
+        );
+        assert_eq!(
+            KoizumiProvider::extract_price(html),
+            Some(Price::jpy(12800).assume_tax_included(false))
+        );
+        assert_eq!(
+            provider.extract_image_url(html).as_deref(),
+            Some("https://webcatalog.koizumi-lt.co.jp/kensaku/images/item/AD12345.jpg")
+        );
+
+        let html = r#"<dl><dt>品名</dt><dd></dd></dl><div class="item_photo"><img src="//cdn.example.com/a.png"></div>"#;
+        assert_eq!(KoizumiProvider::extract_product_name(html), None);
+        assert_eq!(KoizumiProvider::extract_price(html), None);
+        assert_eq!(
+            provider.extract_image_url(html).as_deref(),
+            Some("https://cdn.example.com/a.png")
+        );
+    }
+
+    #[test]
+    fn test_is_unrecognized_detail_page() {
+        // 仕様表のマークアップが変わり、型番は掲載されているのに何も抽出できない
+        let html = r#"<div class="spec-grid"><span>品名</span><span>LEDダウンライト</span>
+            <span>型番</span><span>AD12345</span></div>"#;
+        assert!(KoizumiProvider::is_unrecognized_detail_page(
+            html,
+            "AD12345+XE92701"
+        ));
+
+        // 仕様表を読み取れる（配光データが掲載されていないだけ）
+        let html = r#"<table><tr><th>品名</th><td>LEDダウンライト AD12345</td></tr></table>"#;
+        assert!(!KoizumiProvider::is_unrecognized_detail_page(
+            html, "AD12345"
+        ));
+        // 別のページ（型番の掲載がない）
+        let html = "<p>該当する商品はありません</p>";
+        assert!(!KoizumiProvider::is_unrecognized_detail_page(
+            html, "AD12345"
+        ));
+    }
+
+    #[test]
+    fn test_extract_discontinuation() {
+        let provider = KoizumiProvider::new();
+        let html = r#"
+            <div class="notice">この商品は廃番となりました。</div>
+            <p>後継品：<a href="/kensaku/item/detail/?itemid=AD12346">AD12346</a></p>
+        "#;
+        let discontinued = provider.extract_discontinuation(html).unwrap();
+        assert_eq!(discontinued.successor.as_deref(), Some("AD12346"));
+        assert_eq!(
+            discontinued.successor_page_url.as_deref(),
+            Some("https://webcatalog.koizumi-lt.co.jp/kensaku/item/detail/?itemid=AD12346")
+        );
+        assert_eq!(
+            discontinued.error("AD12345"),
+            ProviderError::new(
+                ErrorCode::Discontinued,
+                "Discontinued: AD12345, successor: AD12346"
+            )
+        );
+
+        // 後継品の掲載がない廃番
+        let html = "<p>生産終了品</p>";
+        assert_eq!(
+            provider.extract_discontinuation(html),
+            Some(Discontinuation {
+                successor: None,
+                successor_page_url: None,
+            })
+        );
+
+        // 廃番の検索ページへのリンク等は廃番の表記とみなさない
+        let html = r#"<a href="/haiban/">廃番商品検索</a><script>var s = "廃番品";</script>"#;
+        assert_eq!(provider.extract_discontinuation(html), None);
+    }
+
+    #[test]
+    fn test_extract_download_url() {
+        let provider = KoizumiProvider::new();
+        let html = r#"
+            <a target="_blank"
+               href="/kensaku/download/file/file_type/shiyousho/id/111" class="dl">仕様書</a>
+            <a class='dl' href='/kensaku/download/file/file_type/haikou_data/id/222'>配光データ</a>
+        "#;
+
+        assert_eq!(
+            provider.extract_download_url(html, AssetType::Ies).as_deref(),
+            Some("https://webcatalog.koizumi-lt.co.jp/kensaku/download/file/file_type/haikou_data/id/222")
+        );
+        // 未確認の種別（仕様書等）のリンクは取得しない
+        assert_eq!(
+            provider.extract_download_url(html, AssetType::SpecSheet),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_ies_link() {
+        let link = |id: &str, label: &str| DownloadLink {
+            url: format!("https://example.com/id/{}", id),
+            label: label.to_string(),
+        };
+        let links = vec![
+            link("1", "配光データ（位相制御調光 XE91234）"),
+            link("2", "配光データ（DALI調光 XE92701）"),
+        ];
+        let select = |psu| KoizumiProvider::select_ies_link(&links, "AD12345", "AD12345", psu);
+
+        // PSUの型番で絞り込む
+        assert_eq!(
+            select(Some("DALI調光電源：XE92701")).unwrap().as_deref(),
+            Some("https://example.com/id/2")
+        );
+        // 絞り込めない場合は候補を含むエラー
+        let error = select(None).unwrap_err();
+        assert_eq!(error.code, ErrorCode::IesAmbiguous);
+        assert!(error
+            .message
+            .starts_with("Multiple IES files found for AD12345"));
+        assert!(error.message.contains("配光データ（DALI調光 XE92701）"));
+
+        // リンクが1つだけなら型番・PSUに関わらず使う
+        assert_eq!(
+            KoizumiProvider::select_ies_link(&links[..1], "AD12345", "AD12345", None).unwrap(),
+            Some("https://example.com/id/1".to_string())
+        );
+        assert_eq!(
+            KoizumiProvider::select_ies_link(&[], "AD12345", "AD12345", None).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ies_url_cache_within_batch() {
+        use futures::executor::block_on;
+
+        let url = "https://webcatalog.koizumi-lt.co.jp/kensaku/download/file/file_type/haikou_data/id/222";
+
+        // 一括ダウンロード中以外は保持しない
+        let provider = KoizumiProvider::new();
+        assert!(provider
+            .ies_url_cache
+            .get_or_insert_with("AD12345+XE92701", Default::default)
+            .is_none());
+
+        // 一括ダウンロード中は解決済みのURLを再利用する（通信しない）
+        provider.begin_batch();
+        provider.ies_url_cache.insert(
+            "AD12345+XE92701".to_string(),
+            Arc::new(OnceCell::new_with(Some(vec![DownloadLink {
+                url: url.to_string(),
+                label: "配光データ".to_string(),
+            }]))),
+        );
+        assert_eq!(
+            block_on(provider.get_ies_download_url(
+                "AD12345+XE92701",
+                "AD12345",
+                Some("DALI調光電源：XE92701")
+            ))
+            .unwrap(),
+            Some(url.to_string())
+        );
+
+        provider.end_batch();
+        assert!(provider.ies_url_cache.get("AD12345+XE92701").is_none());
+    }
+
+    #[test]
+    fn test_default_filename_template() {
+        let provider = KoizumiProvider::new();
+        let render = |asset_type, model_number, psu, original_filename| {
+            let context = FilenameContext {
+                spec_no: "1001",
+                manufacturer: provider.display_name(),
+                model_number,
+                psu,
+                original_filename,
+                photometry: None,
+            };
+            filename::render(
+                provider.default_filename_template(asset_type),
+                asset_type,
+                &context,
+            )
+        };
+
+        // IES: 元ファイル名あり
+        assert_eq!(
+            render(AssetType::Ies, "AD12345", None, Some("AD12345+XE92701.IES")),
+            "1001_AD12345+XE92701.ies"
+        );
+
+        // IES: 元ファイル名なし（型番+PSU）
+        assert_eq!(
+            render(AssetType::Ies, "AD12345", Some("XE92701"), None),
+            "1001_AD12345+XE92701.ies"
+        );
+
+        // アセット: 元ファイル名あり（拡張子を保持）
+        assert_eq!(
+            render(AssetType::Image, "XD93319", None, Some("XD93319.jpg")),
+            "1001_XD93319.jpg"
+        );
+
+        // アセット: 元ファイル名なし
+        assert_eq!(
+            render(AssetType::Image, "XD93319/B", None, None),
+            "1001_XD93319_B"
+        );
+    }
+
+    #[test]
+    fn test_fixture_site() {
+        use crate::providers::fixture_server::{fixture, run, FixtureResponse, FixtureServer};
+
+        run(async {
+            let server = FixtureServer::start(vec![
+                (
+                    "/kensaku/item/detail/?itemid=AD12345",
+                    FixtureResponse::html("koizumi/detail_AD12345.html"),
+                ),
+                (
+                    "/kensaku/download/file/file_type/haikou_data/id/222",
+                    FixtureResponse::file("koizumi/AD12345.ies"),
+                ),
+            ])
+            .await;
+            let provider = KoizumiProvider::with_config(&server.config());
+            let ies_url = format!(
+                "{}/kensaku/download/file/file_type/haikou_data/id/222",
+                server.base_url()
+            );
+
+            // 製品情報（品名・定価・製品画像・IESファイルURL）
+            let info = provider.fetch_product_info("AD12345").await.unwrap();
+            assert_eq!(info.product_name.as_deref(), Some("LEDダウンライト"));
+            assert_eq!(
+                info.price,
+                Some(Price::jpy(12800).assume_tax_included(false))
+            );
+            assert_eq!(
+                info.image_url,
+                Some(format!(
+                    "{}/kensaku/images/item/AD12345.jpg",
+                    server.base_url()
+                ))
+            );
+            assert_eq!(info.ies_file_url.as_deref(), Some(ies_url.as_str()));
+            assert!(info.discontinued.is_none());
+
+            // IESファイルのURLの解決
+            assert_eq!(
+                provider.resolve_ies_url("AD12345", None).await.unwrap().url,
+                ies_url
+            );
+
+            // IESファイルのダウンロード（元ファイル名は Content-Disposition から）
+            let temp = tempfile::tempdir().unwrap();
+            let dest = temp.path().join("1001_AD12345.ies");
+            let result = provider
+                .download_ies_file("AD12345", None, dest.to_str().unwrap(), &CancelToken::new())
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(result.original_filename.as_deref(), Some("AD12345.ies"));
+            assert_eq!(
+                std::fs::read(&dest).unwrap(),
+                fixture("koizumi/AD12345.ies")
+            );
+
+            // 掲載のない型番（キーワード検索でも候補が見つからない）
+            let error = provider.resolve_ies_url("AD99999", None).await.unwrap_err();
+            assert_eq!(
+                error,
+                ProviderError::new(
+                    ErrorCode::IesNotAvailable,
+                    "IES file not available for: AD99999"
+                )
+            );
+            assert!(server
+                .requests()
+                .iter()
+                .any(|path| path.starts_with("/kensaku/item/list/?keyword=AD99999")));
+        });
+    }
 }
@@ -0,0 +1,394 @@
+//! 型番とファイル名の照合
+//!
+//! 複数の器具（色温度・配光角違い）の配光データをまとめて配布するメーカー向けに、
+//! ZIP内のファイル名から型番に最も一致するものを選ぶ。TOKISTAR・遠藤照明で共用する。
+
+use super::{DecisionCandidate, DecisionKind, DecisionRequest, ZipCandidate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::Path;
+
+/// 型番・ファイル名の要素（'-' / '_' 区切り）
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// 色温度（百K単位。27K・2700K → 27）
+    Cct(u32),
+    /// 配光角（度。15D → 15）
+    Beam(u32),
+    /// その他（シリーズ・仕上げ色・オプション等。大文字）
+    Other(String),
+}
+
+impl Segment {
+    /// 要素に分ける（例: "OSP01-30K-15D-B" → [OSP01, 30K, 15D, B]）
+    fn split(name: &str) -> Vec<Segment> {
+        name.split(['-', '_'])
+            .filter(|s| !s.is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    fn parse(segment: &str) -> Segment {
+        let segment = segment.to_uppercase();
+        let number = |suffix: char, digits: std::ops::RangeInclusive<usize>| {
+            let value = segment.strip_suffix(suffix)?;
+            (digits.contains(&value.len()) && value.chars().all(|c| c.is_ascii_digit()))
+                .then(|| value.parse::<u32>().ok())
+                .flatten()
+        };
+        if let Some(cct) = number('K', 2..=4) {
+            return Segment::Cct(if cct >= 1000 { cct / 100 } else { cct });
+        }
+        if let Some(beam) = number('D', 1..=3) {
+            return Segment::Beam(beam);
+        }
+        Segment::Other(segment)
+    }
+
+    /// 同じ種類（色温度同士・配光角同士）の要素か（その他の要素は比較しない）
+    fn same_kind(&self, other: &Segment) -> bool {
+        matches!(
+            (self, other),
+            (Segment::Cct(_), Segment::Cct(_)) | (Segment::Beam(_), Segment::Beam(_))
+        )
+    }
+}
+
+/// ファイルと型番の一致度（フィールドの順に比較する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchScore {
+    /// 一致した色温度・配光角の数
+    semantic: usize,
+    /// 一致したその他の要素の数（シリーズを除く）
+    others: usize,
+    /// 型番にない要素の数（少ないほど高い）
+    extra: Reverse<usize>,
+    /// 前方一致長（ここまで同じ場合の目安）
+    prefix: usize,
+}
+
+impl MatchScore {
+    /// 同程度に一致するか（色温度・配光角・その他の要素の一致数が同じで、
+    /// 余分な要素の数・前方一致長の違いでしか区別できない）
+    fn is_close(&self, other: &MatchScore) -> bool {
+        self.semantic == other.semantic && self.others == other.others
+    }
+}
+
+/// 2つの文字列の前方一致長を計算
+fn common_prefix_length(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(ca, cb)| ca == cb)
+        .count()
+}
+
+/// ファイルと型番の一致度（一致しない・矛盾する場合は None）
+///
+/// 型番とパスから拡張子を除いたファイル名（IES_OSP/OSP01_30K.ies → OSP01_30K）を
+/// '-' / '_' で要素に分けて比較する。シリーズ（先頭の要素）が異なるもの、色温度・配光角が
+/// 型番と異なるものは対象外とする。
+pub fn match_score(model_number: &str, file: &str) -> Option<MatchScore> {
+    let name = Path::new(file)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or(file);
+    let wanted = Segment::split(model_number);
+    let found = Segment::split(name);
+    let (series, wanted) = wanted.split_first()?;
+    let (file_series, found) = found.split_first()?;
+    if series != file_series {
+        return None;
+    }
+
+    let mut score = MatchScore {
+        semantic: 0,
+        others: 0,
+        extra: Reverse(found.iter().filter(|s| !wanted.contains(s)).count()),
+        prefix: common_prefix_length(&model_number.replace('-', "_"), name),
+    };
+    for segment in wanted {
+        if found.contains(segment) {
+            match segment {
+                Segment::Cct(_) | Segment::Beam(_) => score.semantic += 1,
+                Segment::Other(_) => score.others += 1,
+            }
+        } else if found.iter().any(|s| s.same_kind(segment)) {
+            // 色温度・配光角の指定が異なる（27K のファイルを 30K に使わない）
+            return None;
+        }
+    }
+    Some(score)
+}
+
+/// ファイル一覧（ZIP内の .ies / .pdf 等）から型番に最適なファイルを選択
+/// 一致度が最も高いファイルを選択（同じ場合は先に見つかったもの）
+pub fn select_best_file(model_number: &str, files: &[String]) -> Option<String> {
+    files
+        .iter()
+        .filter_map(|f| Some((f, match_score(model_number, f)?)))
+        .rev()
+        .max_by_key(|(_, score)| *score)
+        .map(|(f, _)| f.clone())
+}
+
+/// 選択したファイルと同程度に一致する他のファイルを取得
+/// （型番だけでは区別できず、選択が正しいか確認が必要な候補）
+pub fn ambiguous_matches(model_number: &str, files: &[String], selected: &str) -> Vec<String> {
+    let Some(selected_score) = match_score(model_number, selected) else {
+        return Vec::new();
+    };
+    files
+        .iter()
+        .filter(|f| {
+            f.as_str() != selected
+                && match_score(model_number, f).is_some_and(|score| score.is_close(&selected_score))
+        })
+        .cloned()
+        .collect()
+}
+
+/// 同程度に一致するファイルが複数ある場合に、利用者に選択を求める内容
+/// （自動で選ぶのは `select_best_file` の結果）
+pub fn decision_request(model_number: &str, files: &[String]) -> Option<DecisionRequest> {
+    let best = select_best_file(model_number, files)?;
+    let others = ambiguous_matches(model_number, files, &best);
+    if others.is_empty() {
+        return None;
+    }
+    let candidates = std::iter::once(best.clone())
+        .chain(others)
+        .map(|name| DecisionCandidate {
+            label: Path::new(&name)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&name)
+                .to_string(),
+            value: name,
+        })
+        .collect();
+    Some(DecisionRequest {
+        kind: DecisionKind::IesFile,
+        candidates,
+        selected: Some(best),
+    })
+}
+
+/// ファイルを一致度の高い順に並べた候補一覧
+/// （型番と一致しないファイルは末尾。一致度が同じ場合は一覧の順）
+pub fn rank_candidates(model_number: &str, files: &[String]) -> Vec<ZipCandidate> {
+    let selected = select_best_file(model_number, files);
+    let selected_score = selected
+        .as_deref()
+        .and_then(|f| match_score(model_number, f));
+    let mut scored: Vec<(&String, Option<MatchScore>)> = files
+        .iter()
+        .map(|f| (f, match_score(model_number, f)))
+        .collect();
+    scored.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    scored
+        .into_iter()
+        .map(|(name, score)| ZipCandidate {
+            name: name.clone(),
+            selected: selected.as_ref() == Some(name),
+            close_match: selected.as_ref() != Some(name)
+                && score
+                    .zip(selected_score)
+                    .is_some_and(|(score, selected)| score.is_close(&selected)),
+            matches: score.is_some(),
+            score,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_prefix_length() {
+        assert_eq!(common_prefix_length("OSP01_30K", "OSP01_30K_30D"), 9);
+        assert_eq!(common_prefix_length("OSP01_30K_30D", "OSP01_27K"), 6);
+        assert_eq!(common_prefix_length("ABC", "XYZ"), 0);
+        assert_eq!(common_prefix_length("", "OSP01"), 0);
+    }
+
+    #[test]
+    fn test_select_best_ies_file() {
+        // フラットなファイル名
+        let ies_files = vec![
+            "OSP01_27K.ies".to_string(),
+            "OSP01_30K_30D.ies".to_string(),
+            "OSP01.ies".to_string(),
+        ];
+
+        // OSP01-30K-30D-B-TB → OSP01_30K_30D_B_TB
+        // 最も長く一致する OSP01_30K_30D.ies が選ばれるべき
+        let result = select_best_file("OSP01-30K-30D-B-TB", &ies_files);
+        assert_eq!(result, Some("OSP01_30K_30D.ies".to_string()));
+
+        // OSP01-27K → OSP01_27K
+        let result = select_best_file("OSP01-27K", &ies_files);
+        assert_eq!(result, Some("OSP01_27K.ies".to_string()));
+
+        // OSP01 → OSP01
+        // 余分な要素のない OSP01.ies が選ばれるべき
+        let result = select_best_file("OSP01", &ies_files);
+        assert_eq!(result, Some("OSP01.ies".to_string()));
+    }
+
+    #[test]
+    fn test_select_best_ies_file_by_cct_and_beam() {
+        // 前方一致の長さが同じでも、色温度が異なるファイルは選ばない
+        let ies_files = vec![
+            "IES_OSP/OSP01_15D_30K.ies".to_string(),
+            "IES_OSP/OSP01_15D_27K.ies".to_string(),
+        ];
+        let result = select_best_file("OSP01-30K-15D", &ies_files);
+        assert_eq!(result, Some("IES_OSP/OSP01_15D_30K.ies".to_string()));
+
+        // 色温度・配光角のどちらも一致するものがなければ選ばない
+        let ies_files = vec![
+            "IES_OSP/OSP01_27K_15D.ies".to_string(),
+            "IES_OSP/OSP01_30K_30D.ies".to_string(),
+        ];
+        assert_eq!(select_best_file("OSP01-30K-15D", &ies_files), None);
+
+        // 2700K 表記も 27K とみなす
+        let ies_files = vec![
+            "OSP01_3000K_15D.ies".to_string(),
+            "OSP01_2700K_15D.ies".to_string(),
+        ];
+        let result = select_best_file("OSP01-27K-15D-B", &ies_files);
+        assert_eq!(result, Some("OSP01_2700K_15D.ies".to_string()));
+    }
+
+    #[test]
+    fn test_segment_split() {
+        assert_eq!(
+            Segment::split("OSP01-30K-15D-B"),
+            vec![
+                Segment::Other("OSP01".to_string()),
+                Segment::Cct(30),
+                Segment::Beam(15),
+                Segment::Other("B".to_string()),
+            ]
+        );
+        assert_eq!(
+            Segment::split("osp01_2700k-hl"),
+            vec![
+                Segment::Other("OSP01".to_string()),
+                Segment::Cct(27),
+                Segment::Other("HL".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_best_ies_file_with_path() {
+        // 実際のZIPのようにパス付きファイル名
+        let ies_files = vec![
+            "IES_OSP/OSP01_27K_15D.ies".to_string(),
+            "IES_OSP/OSP01_30K_30D.ies".to_string(),
+            "IES_OSP/HL/OSP01_30K-HL_30D_HL.ies".to_string(),
+        ];
+
+        // OSP01-30K-30D → OSP01_30K_30D
+        let result = select_best_file("OSP01-30K-30D", &ies_files);
+        assert_eq!(result, Some("IES_OSP/OSP01_30K_30D.ies".to_string()));
+
+        // OSP01-27K-15D → OSP01_27K_15D
+        let result = select_best_file("OSP01-27K-15D", &ies_files);
+        assert_eq!(result, Some("IES_OSP/OSP01_27K_15D.ies".to_string()));
+    }
+
+    #[test]
+    fn test_select_best_file_pdf() {
+        // 配光測定成績書（PDF）も同じ規則で選択する
+        let pdf_files = vec![
+            "IES_OSP/OSP01_27K_15D.pdf".to_string(),
+            "IES_OSP/OSP01_30K_30D.pdf".to_string(),
+        ];
+
+        let result = select_best_file("OSP01-30K-30D-B-TB", &pdf_files);
+        assert_eq!(result, Some("IES_OSP/OSP01_30K_30D.pdf".to_string()));
+    }
+
+    #[test]
+    fn test_ambiguous_matches() {
+        let files = vec![
+            "IES_OSP/OSP01_30K_15D.ies".to_string(),
+            "IES_OSP/OSP01_30K_30D.ies".to_string(),
+            "IES_OSP/OSP01_27K_30D.ies".to_string(),
+        ];
+
+        // 配光角まで一致する場合は候補が1つに絞れる
+        let selected = "IES_OSP/OSP01_30K_30D.ies";
+        assert!(ambiguous_matches("OSP01-30K-30D-B", &files, selected).is_empty());
+
+        // 色温度までしか一致しない場合は同程度の候補が残る
+        let selected = select_best_file("OSP01-30K", &files).unwrap();
+        assert_eq!(ambiguous_matches("OSP01-30K", &files, &selected).len(), 1);
+    }
+
+    #[test]
+    fn test_decision_request() {
+        let files = vec![
+            "IES_OSP/OSP01_30K_15D.ies".to_string(),
+            "IES_OSP/OSP01_30K_30D.ies".to_string(),
+            "IES_OSP/OSP01_27K_30D.ies".to_string(),
+        ];
+        assert_eq!(decision_request("OSP01-30K-30D-B", &files), None);
+
+        // 自動で選ぶファイルを先頭に、同程度に一致するファイルを候補にする
+        let request = decision_request("OSP01-30K", &files).unwrap();
+        let selected = select_best_file("OSP01-30K", &files);
+        assert_eq!(request.kind, DecisionKind::IesFile);
+        assert_eq!(request.candidates.len(), 2);
+        assert_eq!(Some(&request.candidates[0].value), selected.as_ref());
+        assert!(!request.candidates[1].label.contains('/'));
+        assert_eq!(request.selected, selected);
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let files = vec![
+            "IES_OSP/OSP01_27K_30D.ies".to_string(),
+            "IES_OSP/HL/OSP01_30K-HL_30D_HL.ies".to_string(),
+            "IES_OSP/OSP01_30K_30D.ies".to_string(),
+        ];
+
+        let candidates = rank_candidates("OSP01-30K-30D", &files);
+        let names: Vec<&str> = candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "IES_OSP/OSP01_30K_30D.ies",
+                "IES_OSP/HL/OSP01_30K-HL_30D_HL.ies",
+                "IES_OSP/OSP01_27K_30D.ies",
+            ]
+        );
+        assert!(candidates[0].selected && !candidates[0].close_match);
+        // 色温度・配光角まで一致し、余分な要素（HL）でしか区別できない
+        assert!(!candidates[1].selected && candidates[1].close_match);
+        // 色温度が異なる
+        assert!(!candidates[2].matches && !candidates[2].close_match);
+        assert_eq!(candidates[2].score, None);
+        assert!(candidates[0].score > candidates[1].score);
+
+        assert_eq!(
+            ambiguous_matches("OSP01-30K-30D", &files, names[0]),
+            vec!["IES_OSP/HL/OSP01_30K-HL_30D_HL.ies".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_select_best_ies_file_no_match() {
+        let ies_files = vec!["ABC123.ies".to_string()];
+
+        let result = select_best_file("XYZ999", &ies_files);
+        assert_eq!(result, None);
+    }
+}
@@ -0,0 +1,238 @@
+//! モックプロバイダー
+//!
+//! メーカーサイトにアクセスせず、型番から決まった製品情報・IESファイルを返す。
+//! デモ・研修・結合テストで一括ダウンロードの処理全体を動かすためのもの。
+//! メーカー名が「MOCK」「モック」の行のほか、設定の `cassette.mode` が `mock` の間は
+//! すべてのメーカーの行を処理する。
+
+use super::{
+    report_phase, AssetType, CancelToken, Diagnosis, DownloadPhase, DownloadResult,
+    ManufacturerProvider, Price, ProductCandidate, ProductInfo,
+};
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use async_trait::async_trait;
+
+/// プロバイダーID
+pub const ID: &str = "mock";
+
+/// 見つからない型番として扱う接頭辞（失敗時の表示の確認用）
+const NOT_FOUND_PREFIX: &str = "NOTFOUND";
+
+/// 検索結果として返す型番の接尾辞
+const VARIANT_SUFFIXES: [&str; 3] = ["-27K", "-30K", "-40K"];
+
+/// 生成するIESファイルの内容（`{model_number}` を型番に置き換える）
+const IES_TEMPLATE: &str = "IESNA:LM-63-2002
+[TEST] MOCK
+[MANUFAC] AutoSight Mock
+[LUMCAT] {model_number}
+[LUMINAIRE] Mock downlight
+TILT=NONE
+1 1000 1 5 1 1 2 0.1 0.1 0
+1.0 1.0 10
+0 22.5 45 67.5 90
+0
+1000 924 707 383 0
+";
+
+/// モックプロバイダー
+#[derive(Debug, Default)]
+pub struct MockProvider;
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 型番が見つからない扱いか
+    fn is_not_found(model_number: &str) -> bool {
+        model_number.to_uppercase().starts_with(NOT_FOUND_PREFIX)
+    }
+
+    /// 型番から決まる定価（円・税抜）
+    fn price(model_number: &str) -> Price {
+        let sum: u64 = model_number.bytes().map(u64::from).sum();
+        Price::jpy(10_000 + (sum % 50) * 1_000).assume_tax_included(false)
+    }
+
+    /// 型番に対応するIESファイルの内容
+    fn ies_content(model_number: &str) -> String {
+        IES_TEMPLATE.replace("{model_number}", model_number)
+    }
+}
+
+#[async_trait]
+impl ManufacturerProvider for MockProvider {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn display_name(&self) -> &str {
+        "モック"
+    }
+
+    fn base_url(&self) -> &str {
+        "https://mock.invalid"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["mock", "モック"]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    fn sample_model_number(&self) -> Option<&str> {
+        Some("MOCK-001")
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        Ok(())
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        if Self::is_not_found(model_number) {
+            return Err(ProviderError::new(
+                ErrorCode::Unknown,
+                format!("Product not found: {}", model_number),
+            ));
+        }
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: Some(format!("モック照明 {}", model_number)),
+            price: Some(Self::price(model_number)),
+            ies_file_url: Some(format!("{}/ies/{}.ies", self.base_url(), model_number)),
+            image_url: None,
+            product_page_url: Some(format!("{}/products/{}", self.base_url(), model_number)),
+            accessories: Vec::new(),
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
+        })
+    }
+
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        if keyword.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(VARIANT_SUFFIXES
+            .iter()
+            .map(|suffix| {
+                let model_number = format!("{}{}", keyword.trim(), suffix);
+                ProductCandidate {
+                    product_name: Some(format!("モック照明 {}", model_number)),
+                    price: Some(Self::price(&model_number)),
+                    product_page_url: Some(format!(
+                        "{}/products/{}",
+                        self.base_url(),
+                        model_number
+                    )),
+                    model_number,
+                }
+            })
+            .collect())
+    }
+
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        Ok((!Self::is_not_found(model_number)).then(|| Self::price(model_number)))
+    }
+
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        cancel.check()?;
+        if Self::is_not_found(model_number) {
+            return Err(ProviderError::new(
+                ErrorCode::IesNotAvailable,
+                format!("IES file not found for: {}", model_number),
+            ));
+        }
+        let content = Self::ies_content(model_number);
+        let size = content.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(size), Some(size));
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+        tokio::fs::write(&dest, &content)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        Ok(DownloadResult::success(
+            dest_path.to_string(),
+            size,
+            Some(format!("{}.ies", model_number)),
+        ))
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::photometry;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_download_ies_file() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let provider = MockProvider::new();
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("A-1.ies");
+        let result = runtime
+            .block_on(provider.download_ies_file(
+                "MOCK-001",
+                None,
+                &dest.to_string_lossy(),
+                &CancelToken::new(),
+            ))
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.original_filename.as_deref(), Some("MOCK-001.ies"));
+        let ies = photometry::parse_ies(&std::fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(ies.lamps(), 1);
+
+        // 見つからない扱いの型番は失敗する
+        let result = runtime.block_on(provider.download_ies_file(
+            "NOTFOUND-1",
+            None,
+            &temp.path().join("A-2.ies").to_string_lossy(),
+            &CancelToken::new(),
+        ));
+        assert_eq!(result.unwrap_err().code, ErrorCode::IesNotAvailable);
+    }
+
+    #[test]
+    fn test_fetch_price() {
+        let price = MockProvider::price("MOCK-001");
+        assert!((10_000..60_000).contains(&price.minor_units));
+        assert_eq!(price.tax_included, Some(false));
+        assert_eq!(
+            block_on(MockProvider::new().fetch_price("NOTFOUND-1")),
+            Ok(None)
+        );
+    }
+}
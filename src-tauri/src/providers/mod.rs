@@ -3,12 +3,46 @@
 //! 照明器具メーカーごとに異なるデータ取得ロジックを抽象化し、
 //! プラグイン的に追加可能なアーキテクチャを提供する。
 
+pub mod daiko;
+pub mod endo;
+#[cfg(test)]
+mod fixture_server;
+pub mod generic;
+mod html;
 pub mod koizumi;
+mod matching;
+pub mod mock;
+pub mod odelic;
+pub mod panasonic;
 pub mod tokistar;
 
+pub use matching::{ambiguous_matches, select_best_file, MatchScore};
+
+use generic::{DefinitionError, GenericProvider};
+
+use crate::audit;
+use crate::buffer;
+use crate::cassette::{self, CassetteMode};
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::filename;
+use crate::longpath;
+use crate::offline;
+use crate::photometry::IesMetadata;
+use crate::settings::{ProxySettings, TlsSettings};
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
+use futures::future::{BoxFuture, Either};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tracing::Instrument;
+use unicode_normalization::UnicodeNormalization;
 
 /// 製品情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,14 +52,1784 @@ pub struct ProductInfo {
     pub model_number: String,
     /// 製品名
     pub product_name: Option<String>,
-    /// 定価（円）
-    pub price: Option<u32>,
+    /// 定価
+    pub price: Option<Price>,
     /// IESファイルのURL
     pub ies_file_url: Option<String>,
     /// 製品画像のURL
     pub image_url: Option<String>,
     /// 製品ページのURL
     pub product_page_url: Option<String>,
+    /// 製品ページに掲載されている適合部材（電源・フレーム・レンズ等）
+    #[serde(default)]
+    pub accessories: Vec<Accessory>,
+    /// 廃番の場合の情報
+    #[serde(default)]
+    pub discontinued: Option<Discontinuation>,
+    /// 廃番の型番から後継品の製品情報に切り替えた場合の元の型番
+    #[serde(default)]
+    pub replaces: Option<String>,
+    /// IESファイルが型番の製品のものか、シリーズの代表による近似か（区別できるプロバイダーのみ）
+    #[serde(default)]
+    pub match_quality: Option<MatchQuality>,
+}
+
+/// IESファイルと型番の対応の確かさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchQuality {
+    /// 型番の製品のIESファイル
+    Exact,
+    /// 型番の製品のIESファイルが掲載されておらず、シリーズの代表のIESファイルで代用した
+    Series,
+}
+
+/// 廃番の情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discontinuation {
+    /// 後継品の型番（製品ページに掲載されている場合）
+    pub successor: Option<String>,
+    /// 後継品の製品ページのURL
+    pub successor_page_url: Option<String>,
+}
+
+impl Discontinuation {
+    /// 廃番のためダウンロードできない場合のエラー
+    pub fn error(&self, model_number: &str) -> ProviderError {
+        let message = match &self.successor {
+            Some(successor) => {
+                format!("Discontinued: {}, successor: {}", model_number, successor)
+            }
+            None => format!("Discontinued: {}, no successor listed", model_number),
+        };
+        ProviderError::new(ErrorCode::Discontinued, message)
+    }
+}
+
+/// 適合部材の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessoryKind {
+    /// 電源ユニット（PSU）
+    PowerSupply,
+    /// 取付枠・フレーム
+    Frame,
+    /// レンズ・フィルター・ルーバー
+    Lens,
+    /// その他
+    Other,
+}
+
+impl AccessoryKind {
+    /// 製品ページ上の名称から種別を推定
+    pub fn classify(name: &str) -> Self {
+        if name.contains("電源") || name.to_lowercase().contains("driver") {
+            AccessoryKind::PowerSupply
+        } else if name.contains("フレーム") || name.contains("枠") {
+            AccessoryKind::Frame
+        } else if name.contains("レンズ")
+            || name.contains("フィルター")
+            || name.contains("ルーバー")
+        {
+            AccessoryKind::Lens
+        } else {
+            AccessoryKind::Other
+        }
+    }
+}
+
+/// 適合部材
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Accessory {
+    /// 型番
+    pub model_number: String,
+    /// 名称（製品ページ上の表記）
+    pub name: Option<String>,
+    /// 種別
+    pub kind: AccessoryKind,
+    /// 製品ページのURL
+    pub product_page_url: Option<String>,
+}
+
+/// 製品検索の候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductCandidate {
+    /// 型番
+    pub model_number: String,
+    /// 製品名
+    pub product_name: Option<String>,
+    /// 定価
+    pub price: Option<Price>,
+    /// 製品ページのURL
+    pub product_page_url: Option<String>,
+}
+
+/// 通貨
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    /// 日本円
+    #[default]
+    Jpy,
+    /// 米ドル
+    Usd,
+    /// ユーロ
+    Eur,
+}
+
+impl Currency {
+    /// ISO 4217 の通貨コード
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::Jpy => "JPY",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    /// 補助単位の桁数（円は0、ドル・ユーロはセント単位の2）
+    pub fn decimals(self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            Currency::Usd | Currency::Eur => 2,
+        }
+    }
+}
+
+/// 価格
+///
+/// 浮動小数点の誤差が出ないよう、金額は通貨の補助単位の整数（円は1円、ドル・ユーロは1セント）で持つ。
+/// 以前の形式（円の整数）も読み込める（キャッシュ・セッションに保存した製品情報）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "PriceRepr")]
+pub struct Price {
+    /// 金額（通貨の補助単位）
+    pub minor_units: u64,
+    pub currency: Currency,
+    /// 税込価格か（表記がなく判断できない場合は None）
+    #[serde(default)]
+    pub tax_included: Option<bool>,
+}
+
+/// 価格の保存形式（以前の円の整数を含む）
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriceRepr {
+    Yen(u64),
+    #[serde(rename_all = "camelCase")]
+    Price {
+        minor_units: u64,
+        currency: Currency,
+        #[serde(default)]
+        tax_included: Option<bool>,
+    },
+}
+
+impl From<PriceRepr> for Price {
+    fn from(repr: PriceRepr) -> Self {
+        match repr {
+            PriceRepr::Yen(yen) => Price::jpy(yen),
+            PriceRepr::Price {
+                minor_units,
+                currency,
+                tax_included,
+            } => Price {
+                minor_units,
+                currency,
+                tax_included,
+            },
+        }
+    }
+}
+
+impl Price {
+    /// 円の価格（税込・税抜は不明）
+    pub fn jpy(yen: u64) -> Self {
+        Price {
+            minor_units: yen,
+            currency: Currency::Jpy,
+            tax_included: None,
+        }
+    }
+
+    /// 表記がない場合の税込・税抜を補う（メーカーの定価は通常税抜で掲載されている等）
+    pub fn assume_tax_included(mut self, tax_included: bool) -> Self {
+        self.tax_included.get_or_insert(tax_included);
+        self
+    }
+
+    /// 台数分の金額（定価 × 台数）
+    pub fn times(self, quantity: u32) -> Self {
+        Price {
+            minor_units: self.minor_units.saturating_mul(u64::from(quantity)),
+            ..self
+        }
+    }
+
+    /// 金額の文字列（補助単位を小数で表す。例: "12800"、"12.50"）
+    pub fn amount(&self) -> String {
+        let decimals = self.currency.decimals();
+        if decimals == 0 {
+            return self.minor_units.to_string();
+        }
+        let unit = 10u64.pow(decimals);
+        format!(
+            "{}.{:0width$}",
+            self.minor_units / unit,
+            self.minor_units % unit,
+            width = decimals as usize
+        )
+    }
+}
+
+/// 価格表記（"¥12,800" / "9,500円" / "$1,250.50" / "€99"）
+static PRICE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)[¥￥]\s*(?P<a>[0-9,]+)|(?P<b>[0-9,]+)\s*円|(?:\$|USD\s*)(?P<c>[0-9,]+(?:\.[0-9]+)?)|(?:€|EUR\s*)(?P<d>[0-9,]+(?:\.[0-9]+)?)",
+    )
+    .unwrap()
+});
+
+/// 税込の表記
+static TAX_INCLUDED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)税込|incl(?:\.|uding)?\s*(?:of\s*)?tax").unwrap());
+/// 税抜の表記
+static TAX_EXCLUDED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)税抜|税別|本体価格|excl(?:\.|uding)?\s*(?:of\s*)?tax|plus\s*tax").unwrap()
+});
+
+/// 価格表記から価格を抽出（「税込」「税抜」等の表記があれば税込・税抜も判断する）
+/// 例: "¥12,800" → 12800円、"定価 9,500円（税抜）" → 9500円（税抜）、"$12.5" → 12.50ドル
+pub fn parse_price(text: &str) -> Option<Price> {
+    let caps = PRICE_RE.captures(text)?;
+    let (currency, amount) = if let Some(amount) = caps.name("a").or_else(|| caps.name("b")) {
+        (Currency::Jpy, amount)
+    } else if let Some(amount) = caps.name("c") {
+        (Currency::Usd, amount)
+    } else {
+        (Currency::Eur, caps.name("d")?)
+    };
+    let amount = amount.as_str().replace(',', "");
+    let (integer, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+    // 補助単位より細かい金額（"$1.005" 等）は価格として扱わない
+    let decimals = currency.decimals();
+    if fraction.len() > decimals as usize {
+        return None;
+    }
+    let fraction: u64 = match decimals {
+        0 => 0,
+        width => format!("{:0<width$}", fraction, width = width as usize)
+            .parse()
+            .ok()?,
+    };
+    let minor_units = integer
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(10u64.pow(decimals))?
+        .checked_add(fraction)?;
+
+    let tax_included = if TAX_INCLUDED_RE.is_match(text) {
+        Some(true)
+    } else if TAX_EXCLUDED_RE.is_match(text) {
+        Some(false)
+    } else {
+        None
+    };
+    Some(Price {
+        minor_units,
+        currency,
+        tax_included,
+    })
+}
+
+/// 検索候補から型番に対応する定価を取得
+///
+/// 型番が完全一致する候補を優先し、なければ型番の先頭に最も長く一致する候補
+/// （シリーズ単位で掲載されている場合）の定価を返す。
+pub fn price_from_candidates(candidates: &[ProductCandidate], model_number: &str) -> Option<Price> {
+    let model_number = model_number.to_uppercase();
+    candidates
+        .iter()
+        .find(|c| c.model_number.to_uppercase() == model_number)
+        .or_else(|| {
+            candidates
+                .iter()
+                .filter(|c| model_number.starts_with(&c.model_number.to_uppercase()))
+                .max_by_key(|c| c.model_number.len())
+        })
+        .and_then(|c| c.price)
+}
+
+/// 検索候補を型番の近い順（編集距離の小さい順）に並べ、上位 `limit` 件を返す
+///
+/// 型番の比較では大文字・小文字と英数字以外の文字を無視する。
+pub fn closest_candidates(
+    candidates: Vec<ProductCandidate>,
+    model_number: &str,
+    limit: usize,
+) -> Vec<ProductCandidate> {
+    let normalize = |s: &str| -> Vec<char> {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    };
+    let target = normalize(model_number);
+    let mut scored: Vec<_> = candidates
+        .into_iter()
+        .map(|c| (edit_distance(&normalize(&c.model_number), &target), c))
+        .collect();
+    scored.sort_by_key(|(distance, c)| (*distance, c.model_number.len()));
+    scored.into_iter().take(limit).map(|(_, c)| c).collect()
+}
+
+/// 編集距離（レーベンシュタイン距離）
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// メーカー欄の区切り（"コイズミ or 同等品" "コイズミ／トキスター" 等）
+static MANUFACTURER_SEPARATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s+or\s+|または|もしくは|[/／、,，\n]").unwrap());
+
+/// 同等品の指定（メーカー名ではないため除く）
+const EQUIVALENT_MARKERS: [&str; 3] = ["同等品", "相当品", "同等"];
+
+/// メーカー欄に記載された複数のメーカー名を記載順に分割
+///
+/// 例: "コイズミ or 同等品" → ["コイズミ"]、"コイズミ／トキスター" → ["コイズミ", "トキスター"]
+pub fn split_manufacturers(field: &str) -> Vec<String> {
+    MANUFACTURER_SEPARATOR_RE
+        .split(field)
+        .map(|name| {
+            EQUIVALENT_MARKERS
+                .iter()
+                .fold(name.to_string(), |name, marker| name.replace(marker, ""))
+                .trim()
+                .to_string()
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// メーカー名から取り除く法人格（NFKC正規化・小文字化した表記。名前の中のどこにあっても取り除く）
+const CORPORATE_MARKS: [&str; 4] = ["株式会社", "有限会社", "(株)", "(有)"];
+/// メーカー名の末尾から取り除く英語の法人格（長いものから順に照合する）
+const CORPORATE_SUFFIXES: [&str; 10] = [
+    "co., ltd.",
+    "co.,ltd.",
+    "co., ltd",
+    "co.,ltd",
+    "corporation",
+    "corp.",
+    "ltd.",
+    "ltd",
+    "inc.",
+    "inc",
+];
+
+/// メーカー名を比較用に正規化
+///
+/// NFKC正規化（半角カナ・全角英数字の統一）と小文字化の後、法人格（株式会社・(株)・Co., Ltd. 等）を
+/// 取り除き、前後の空白を除いて連続する空白を1つにまとめる。
+/// 例: "ｺｲｽﾞﾐ照明株式会社 " → "コイズミ照明"、"KOIZUMI LIGHTING CO., LTD." → "koizumi lighting"
+pub fn normalize_manufacturer(name: &str) -> String {
+    let name = CORPORATE_MARKS.iter().fold(
+        name.nfkc().collect::<String>().to_lowercase(),
+        |name, mark| name.replace(mark, " "),
+    );
+    let mut name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    while let Some(stripped) = CORPORATE_SUFFIXES
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix))
+        // 単語の途中（"zinc" の "inc" 等）では区切らない
+        .find(|rest| rest.is_empty() || rest.ends_with([' ', ',']))
+    {
+        name = stripped.trim_end_matches([' ', ',']).to_string();
+    }
+    name
+}
+
+/// ページの本文に型番が掲載されているか（英字の大文字・小文字と空白を無視）
+///
+/// 抽出用のセレクターが何も一致しなかった場合に、正しいページを取得できているかの判断に使う。
+pub fn page_mentions(html: &str, model_number: &str) -> bool {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase()
+    };
+    let model_number = normalize(model_number);
+    !model_number.is_empty() && normalize(&html::body_text(html)).contains(&model_number)
+}
+
+/// サイトの構成の変更が疑われる場合のエラー（[`ErrorCode::ProviderOutdated`]）
+///
+/// HTTPでは取得でき、型番も掲載されているページで、セレクター・正規表現が何も一致しない場合に使う。
+/// 掲載がないのではなくプロバイダーの更新が必要なことを区別できるよう、ページの抜粋を証拠として付ける。
+pub fn provider_outdated(
+    provider: &str,
+    what: &str,
+    model_number: &str,
+    html: &str,
+) -> ProviderError {
+    tracing::warn!(
+        provider,
+        what,
+        model_number,
+        "page structure not recognized"
+    );
+    ProviderError::new(
+        ErrorCode::ProviderOutdated,
+        format!(
+            "Provider outdated: no {} recognized on {} page for {} (evidence: {})",
+            what,
+            provider,
+            model_number,
+            html::evidence(html, model_number)
+        ),
+    )
+}
+
+/// 製品にアセットが掲載されていない場合のエラー（IESは [`ErrorCode::IesNotAvailable`]）
+pub fn not_available(asset_type: AssetType, model_number: &str) -> ProviderError {
+    match asset_type {
+        AssetType::Ies => ProviderError::new(
+            ErrorCode::IesNotAvailable,
+            format!("IES file not available for: {}", model_number),
+        ),
+        _ => ProviderError::new(
+            ErrorCode::AssetNotAvailable,
+            format!("{:?} file not available for: {}", asset_type, model_number),
+        ),
+    }
+}
+
+/// メーカーがアセットを提供していない場合のエラー
+pub fn not_provided(provider: &str, asset_type: AssetType) -> ProviderError {
+    ProviderError::new(
+        ErrorCode::AssetNotAvailable,
+        format!("{} does not provide {:?} files", provider, asset_type),
+    )
+}
+
+/// ページ・APIがエラーステータスを返した場合のエラー（429 はレート制限）
+pub fn status_error(status: reqwest::StatusCode, message: String) -> ProviderError {
+    let code = if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        ErrorCode::RateLimited
+    } else {
+        ErrorCode::HttpStatus
+    };
+    ProviderError::new(code, message)
+}
+
+/// 検索候補をエラーメッセージ用に列挙（例: "AD12346 (LEDダウンライト), AD12347"）
+pub fn describe_candidates(candidates: &[ProductCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|c| match &c.product_name {
+            Some(name) => format!("{} ({})", c.model_number, name),
+            None => c.model_number.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// メーカーサイトへのアクセスに使用するUser-Agent
+const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
+/// アイドル状態の接続を保持する時間（秒）
+/// 一括ダウンロードでは同じホストに連続してアクセスするため、接続を使い回せる程度に長くする
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+/// ホストごとに保持するアイドル接続数の上限（同時実行数の上限程度）
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+/// TCPキープアライブの間隔（秒）
+const TCP_KEEPALIVE_SECS: u64 = 60;
+
+/// HTTPクライアントの共通設定
+///
+/// gzip/brotli 圧縮の展開（HTMLの多い製品詳細ページの転送量を減らす）、HTTP/2
+/// （ALPNで対応しているサーバーのみ）、一括ダウンロード向けの接続プール設定と、
+/// 設定のプロキシ・TLSの設定（[`apply_network_settings`]）を反映する。
+/// 各プロバイダーはこれにタイムアウト等を追加してクライアントを作成する。
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .brotli(true)
+        .http2_adaptive_window(true)
+        .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(Duration::from_secs(TCP_KEEPALIVE_SECS));
+    NETWORK
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .apply(builder)
+}
+
+/// プロキシ・TLSの設定（HTTPクライアントの作成時に反映する）
+#[derive(Clone, Default)]
+struct NetworkConfig {
+    proxy: Option<reqwest::Proxy>,
+    root_certificate: Option<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+}
+
+impl NetworkConfig {
+    fn new(proxy: Option<&ProxySettings>, tls: &TlsSettings) -> Result<Self, String> {
+        let proxy = proxy.map(build_proxy).transpose()?;
+        let root_certificate = tls
+            .root_certificate_path
+            .as_deref()
+            .map(load_certificate)
+            .transpose()?;
+        Ok(Self {
+            proxy,
+            root_certificate,
+            accept_invalid_certs: tls.accept_invalid_certs,
+        })
+    }
+
+    fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(certificate) = &self.root_certificate {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs)
+    }
+}
+
+/// プロキシ設定からプロキシを作成（認証・経由しないホストを含む）
+fn build_proxy(settings: &ProxySettings) -> Result<reqwest::Proxy, String> {
+    let mut proxy =
+        reqwest::Proxy::all(&settings.url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if let Some(username) = settings.username.as_deref().filter(|u| !u.is_empty()) {
+        proxy = proxy.basic_auth(username, settings.password.as_deref().unwrap_or(""));
+    }
+    let no_proxy = settings
+        .no_proxy
+        .as_deref()
+        .and_then(reqwest::NoProxy::from_string);
+    Ok(proxy.no_proxy(no_proxy))
+}
+
+/// ルート証明書のファイルを読み込む（PEMまたはDER）
+fn load_certificate(path: &str) -> Result<reqwest::Certificate, String> {
+    let bytes = std::fs::read(longpath::extended(path))
+        .map_err(|e| format!("Failed to read root certificate {}: {}", path, e))?;
+    let certificate = if bytes.starts_with(b"-----BEGIN") {
+        reqwest::Certificate::from_pem(&bytes)
+    } else {
+        reqwest::Certificate::from_der(&bytes)
+    };
+    certificate.map_err(|e| format!("Invalid root certificate {}: {}", path, e))
+}
+
+/// プロキシ・TLSの設定（起動時・設定の保存時に反映する）
+static NETWORK: LazyLock<RwLock<NetworkConfig>> = LazyLock::new(Default::default);
+
+/// プロキシ・TLSの設定を反映（起動時・設定の保存時）
+///
+/// 以降に作成するHTTPクライアントに反映する。作成済みのプロバイダーのクライアントには
+/// 反映しないため、続けて [`apply_provider_config`] でプロバイダーを作り直す。
+/// ルート証明書を読み込めない場合はエラーとし、それまでの設定のままにする。
+pub fn apply_network_settings(
+    proxy: Option<&ProxySettings>,
+    tls: &TlsSettings,
+) -> Result<(), String> {
+    let config = NetworkConfig::new(proxy, tls)?;
+    *NETWORK.write().unwrap_or_else(|e| e.into_inner()) = config;
+    Ok(())
+}
+
+/// 接続設定を変更できるプロバイダー（組み込みのメーカーサイトのプロバイダー）
+pub const CONFIGURABLE_PROVIDERS: [&str; 6] = [
+    "koizumi",
+    "tokistar",
+    "panasonic",
+    "daiko",
+    "endo",
+    "odelic",
+];
+
+/// プロバイダーの接続設定（未指定の項目は既定値を使う。プロバイダーの作成時に読み込む）
+///
+/// メーカーサイトのドメインが変わった場合等に、アプリを更新せずに対応するためのもの。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ProviderConfig {
+    /// メーカーサイトのベースURL（例: "https://webcatalog.koizumi-lt.co.jp"）
+    pub base_url: Option<String>,
+    /// User-Agent
+    pub user_agent: Option<String>,
+    /// リクエストのタイムアウト（秒。未指定の場合は設定の `requestTimeoutSecs`）
+    pub timeout_secs: Option<u64>,
+    /// 送信の最大試行回数（未指定の場合は `retry.maxAttempts`）
+    pub max_attempts: Option<u32>,
+}
+
+impl ProviderConfig {
+    pub fn validate(&self, id: &str) -> Result<(), String> {
+        if let Some(base_url) = &self.base_url {
+            let url = reqwest::Url::parse(base_url)
+                .map_err(|e| format!("providers.{}.baseUrl is invalid: {}", id, e))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(format!("providers.{}.baseUrl must be an http(s) URL", id));
+            }
+        }
+        if self
+            .user_agent
+            .as_deref()
+            .is_some_and(|ua| ua.trim().is_empty() || ua.contains(['\r', '\n']))
+        {
+            return Err(format!("providers.{}.userAgent is invalid", id));
+        }
+        if self
+            .timeout_secs
+            .is_some_and(|secs| !(1..=MAX_PROVIDER_TIMEOUT_SECS).contains(&secs))
+        {
+            return Err(format!(
+                "providers.{}.timeoutSecs must be between 1 and {}",
+                id, MAX_PROVIDER_TIMEOUT_SECS
+            ));
+        }
+        if self
+            .max_attempts
+            .is_some_and(|attempts| !(1..=MAX_RETRY_ATTEMPTS).contains(&attempts))
+        {
+            return Err(format!(
+                "providers.{}.maxAttempts must be between 1 and {}",
+                id, MAX_RETRY_ATTEMPTS
+            ));
+        }
+        Ok(())
+    }
+
+    /// ベースURL（未指定の場合は `default`。末尾の '/' は除く）
+    pub fn base_url(&self, default: &str) -> String {
+        self.base_url
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// User-Agent・タイムアウトを反映したHTTPクライアント
+    pub fn client(&self) -> reqwest::Client {
+        let mut builder = client_builder();
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent.trim());
+        }
+        if let Some(timeout) = self
+            .timeout_secs
+            .map(Duration::from_secs)
+            .or_else(default_timeout)
+        {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().expect("Failed to create HTTP client")
+    }
+}
+
+/// 接続設定でタイムアウトを指定していないプロバイダーのタイムアウト（設定の `requestTimeoutSecs`）
+static DEFAULT_TIMEOUT: RwLock<Option<Duration>> = RwLock::new(None);
+
+/// リクエストのタイムアウトの既定値を反映（起動時・設定の保存時）
+///
+/// 作成済みのプロバイダーのクライアントには反映しないため、続けて [`apply_provider_config`] で
+/// プロバイダーを作り直す。
+pub fn apply_request_timeout(secs: u64) {
+    *DEFAULT_TIMEOUT.write().unwrap_or_else(|e| e.into_inner()) = Some(Duration::from_secs(secs));
+}
+
+fn default_timeout() -> Option<Duration> {
+    *DEFAULT_TIMEOUT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// プロバイダーのタイムアウトの上限（秒）
+const MAX_PROVIDER_TIMEOUT_SECS: u64 = 600;
+
+/// ブロッキングする処理（ZIPの展開等）を専用スレッドで実行
+///
+/// 非同期ランタイムのスレッドを占有すると、進捗イベントの送信や他のコマンドが止まるため。
+pub async fn run_blocking<T, E, F>(f: F) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<ProviderError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(ProviderError::wrap(
+            ErrorCode::Unknown,
+            "Blocking task failed",
+        ))?
+}
+
+/// ダウンロードの段階（進捗表示用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadPhase {
+    /// ダウンロードURLの解決（製品ページ・検索ページの取得）
+    Lookup,
+    /// ファイル（ZIP）の受信
+    Downloading,
+    /// ZIPの展開・形式の変換
+    Extracting,
+    /// ファイル名の変更・保存先への移動
+    Renaming,
+    /// 曖昧な一致について利用者の判断を待っている
+    AwaitingDecision,
+}
+
+/// ダウンロードの段階の通知先（段階・受信したバイト数・全体のバイト数）
+pub type PhaseNotifier = Arc<dyn Fn(DownloadPhase, Option<u64>, Option<u64>) + Send + Sync>;
+
+tokio::task_local! {
+    /// 実行中のアイテムの段階の通知先
+    static PHASE_NOTIFIER: PhaseNotifier;
+}
+
+/// ダウンロードの段階を通知しながら処理を実行
+///
+/// 処理中に [`report_phase`] で通知した段階は `notifier` に渡す。
+pub async fn with_phase_notifier<F: Future>(notifier: PhaseNotifier, future: F) -> F::Output {
+    PHASE_NOTIFIER.scope(notifier, future).await
+}
+
+/// ダウンロードの段階を通知（通知先がなければ何もしない）
+///
+/// 受信中は受信したバイト数と、分かる場合は全体のバイト数（Content-Length）を渡す。
+pub fn report_phase(phase: DownloadPhase, bytes: Option<u64>, total_bytes: Option<u64>) {
+    let _ = PHASE_NOTIFIER.try_with(|notify| notify(phase, bytes, total_bytes));
+}
+
+/// 利用者の判断が必要な曖昧な一致の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecisionKind {
+    /// 同程度に一致するIESファイルが複数ある（ZIP内のファイル・製品ページのリンク）
+    IesFile,
+    /// 型番が見つからず、近い型番の製品が複数ある
+    Product,
+}
+
+/// 判断を求める候補
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionCandidate {
+    /// 選択時に返す値（ZIP内のファイル名・ダウンロードURL・型番）
+    pub value: String,
+    /// 表示名
+    pub label: String,
+}
+
+/// 利用者に判断を求める内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRequest {
+    pub kind: DecisionKind,
+    pub candidates: Vec<DecisionCandidate>,
+    /// 判断されなかった場合に自動で選ぶ候補の値（ない場合は失敗にする）
+    pub selected: Option<String>,
+}
+
+/// 判断の依頼先（選ばれた候補の値を返す。選ばれなければ `None`）
+pub type DecisionResolver =
+    Arc<dyn Fn(DecisionRequest) -> BoxFuture<'static, Option<String>> + Send + Sync>;
+
+tokio::task_local! {
+    /// 実行中のアイテムの判断の依頼先
+    static DECISION_RESOLVER: Option<DecisionResolver>;
+}
+
+/// 曖昧な一致を利用者に判断してもらいながら処理を実行
+///
+/// `resolver` が `None` の場合は判断を求めず、従来どおり自動で選ぶ（失敗にする）。
+pub async fn with_decision_resolver<F: Future>(
+    resolver: Option<DecisionResolver>,
+    future: F,
+) -> F::Output {
+    DECISION_RESOLVER.scope(resolver, future).await
+}
+
+/// 判断の依頼先があるか（候補の一覧に追加の処理が必要な場合に、事前に確認する）
+pub fn can_request_decision() -> bool {
+    DECISION_RESOLVER
+        .try_with(|resolver| resolver.is_some())
+        .unwrap_or(false)
+}
+
+/// 利用者に候補の選択を求める
+///
+/// 依頼先がない・選ばれなかった・候補にない値が返された場合は `None`。
+pub async fn request_decision(request: DecisionRequest) -> Option<String> {
+    let resolver = DECISION_RESOLVER.try_with(Clone::clone).ok().flatten()?;
+    let candidates = request.candidates.clone();
+    let choice = resolver(request).await?;
+    candidates
+        .iter()
+        .any(|c| c.value == choice)
+        .then_some(choice)
+}
+
+tokio::task_local! {
+    /// 実行中のアイテムで利用者が指定したZIP内のIESファイル
+    static ZIP_MEMBER: Option<String>;
+}
+
+/// ZIP内のIESファイルを指定して処理を実行
+///
+/// ZIPで配布するプロバイダーは、一致度・判断の依頼によらず指定されたファイルを保存する
+/// （ZIPにない場合は失敗にする）。
+pub async fn with_zip_member<F: Future>(member: Option<String>, future: F) -> F::Output {
+    ZIP_MEMBER.scope(member, future).await
+}
+
+/// 指定されたZIP内のIESファイル（指定がなければ `None`）
+pub fn zip_member() -> Option<String> {
+    ZIP_MEMBER.try_with(Clone::clone).ok().flatten()
+}
+
+/// 一括ダウンロード中だけ保持するプロバイダーのキャッシュ（キー → 値）
+///
+/// 同時に実行中のバッチで共有する。開始・終了したバッチの数を数え、最後のバッチが終了したときに
+/// 破棄する（先に終わったバッチが、実行中の別のバッチのキャッシュを消さないようにする）。
+pub struct BatchCache<V> {
+    state: Mutex<BatchCacheState<V>>,
+}
+
+struct BatchCacheState<V> {
+    /// 実行中のバッチの数
+    active: usize,
+    entries: HashMap<String, V>,
+}
+
+impl<V> Default for BatchCache<V> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(BatchCacheState {
+                active: 0,
+                entries: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl<V: Clone> BatchCache<V> {
+    fn state(&self) -> std::sync::MutexGuard<'_, BatchCacheState<V>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// バッチの開始時に呼ぶ
+    pub fn begin(&self) {
+        self.state().active += 1;
+    }
+
+    /// バッチの終了時に呼ぶ（実行中のバッチがなくなれば保持した値を破棄する）
+    pub fn end(&self) {
+        let mut state = self.state();
+        state.active = state.active.saturating_sub(1);
+        if state.active == 0 {
+            state.entries.clear();
+        }
+    }
+
+    /// 保持している値
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.state().entries.get(key).cloned()
+    }
+
+    /// 値を保持（一括ダウンロード中以外は保持しない）
+    pub fn insert(&self, key: String, value: V) {
+        let mut state = self.state();
+        if state.active > 0 {
+            state.entries.insert(key, value);
+        }
+    }
+
+    /// 保持している値を返し、なければ `init` の値を保持して返す（一括ダウンロード中以外は None）
+    pub fn get_or_insert_with(&self, key: &str, init: impl FnOnce() -> V) -> Option<V> {
+        let mut state = self.state();
+        if state.active == 0 {
+            return None;
+        }
+        Some(
+            state
+                .entries
+                .entry(key.to_string())
+                .or_insert_with(init)
+                .clone(),
+        )
+    }
+}
+
+/// 中断した処理のエラーメッセージ（`ErrorCode::Cancelled` に分類される）
+pub const CANCELLED: &str = "Cancelled";
+
+/// 中断した処理のエラー
+pub fn cancelled() -> ProviderError {
+    ProviderError::new(ErrorCode::Cancelled, CANCELLED)
+}
+
+/// 処理の中断指示
+///
+/// 一括ダウンロードのアイテムをキャンセルしたときに、プロバイダーの処理を途中で止めるために使う。
+/// 複製したトークンは同じ指示を共有する。非同期の処理（HTTPリクエスト等）は [`CancelToken::run`]
+/// で中断し、専用スレッドで実行する処理（ZIPの展開等）は [`CancelToken::check`] で確認する。
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 中断を指示
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// 中断が指示されたか
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 中断が指示されていればエラーを返す
+    pub fn check(&self) -> ProviderResult<()> {
+        if self.is_cancelled() {
+            Err(cancelled())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 中断が指示されるまで待つ
+    pub async fn cancelled(&self) {
+        loop {
+            // 判定の前に待機を登録し、判定との間の指示を取りこぼさない
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// 中断が指示されたら処理を破棄してエラーを返す
+    pub async fn run<T, E: From<ProviderError>>(
+        &self,
+        fut: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        self.check()?;
+        let fut = std::pin::pin!(fut);
+        let stopped = std::pin::pin!(self.cancelled());
+        match futures::future::select(fut, stopped).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(cancelled().into()),
+        }
+    }
+}
+
+/// Retry-After ヘッダーがない場合の待機時間（秒）
+const DEFAULT_RETRY_WAIT_SECS: u64 = 5;
+/// 設定できる試行回数の上限
+const MAX_RETRY_ATTEMPTS: u32 = 10;
+/// 設定できる待機時間の上限（秒）
+const MAX_RETRY_WAIT_LIMIT_SECS: u64 = 600;
+
+/// 再試行の設定（レート制限・一時的なエラー時）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RetrySettings {
+    /// 送信の最大試行回数（1 で再試行しない）
+    pub max_attempts: u32,
+    /// 接続エラー・タイムアウト時の最初の待機時間（秒。試行ごとに2倍にする）
+    pub base_wait_secs: u64,
+    /// 待機時間の上限（秒。Retry-After ヘッダーの値にも適用する）
+    pub max_wait_secs: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_wait_secs: 2,
+            max_wait_secs: 60,
+        }
+    }
+}
+
+impl RetrySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_RETRY_ATTEMPTS).contains(&self.max_attempts) {
+            return Err(format!(
+                "retry.maxAttempts must be between 1 and {}",
+                MAX_RETRY_ATTEMPTS
+            ));
+        }
+        if !(1..=MAX_RETRY_WAIT_LIMIT_SECS).contains(&self.max_wait_secs) {
+            return Err(format!(
+                "retry.maxWaitSecs must be between 1 and {}",
+                MAX_RETRY_WAIT_LIMIT_SECS
+            ));
+        }
+        if !(1..=self.max_wait_secs).contains(&self.base_wait_secs) {
+            return Err("retry.baseWaitSecs must be between 1 and retry.maxWaitSecs".to_string());
+        }
+        Ok(())
+    }
+
+    /// 接続エラー・タイムアウトの後、次の試行までの待機時間（秒。`attempt` は失敗した試行）
+    fn error_wait_secs(&self, attempt: u32) -> u64 {
+        self.base_wait_secs
+            .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_wait_secs)
+    }
+}
+
+/// 再試行の設定（設定の保存時に反映する）
+static RETRY: LazyLock<RwLock<RetrySettings>> = LazyLock::new(Default::default);
+
+/// 再試行の設定を反映（起動時・設定の保存時）
+pub fn apply_retry_settings(settings: &RetrySettings) {
+    *RETRY.write().unwrap_or_else(|e| e.into_inner()) = settings.clone();
+}
+
+/// ホストへの送信の再試行の設定（プロバイダーの試行回数の指定があれば反映する）
+fn retry_settings(host: &str) -> RetrySettings {
+    let mut retry = RETRY.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(max_attempts) = match_domain(
+        &PROVIDER_ATTEMPTS.read().unwrap_or_else(|e| e.into_inner()),
+        host,
+    ) {
+        retry.max_attempts = max_attempts;
+    }
+    retry
+}
+
+/// プロバイダーのベースURLのホストごとの試行回数（プロバイダーの作成時に反映する）
+static PROVIDER_ATTEMPTS: LazyLock<RwLock<BTreeMap<String, u32>>> = LazyLock::new(Default::default);
+
+/// 待機の理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackoffReason {
+    /// 429 Too Many Requests
+    RateLimited,
+    /// 503 Service Unavailable
+    ServerBusy,
+    /// 接続エラー・タイムアウト
+    NetworkError,
+}
+
+/// 再試行のための待機の通知（`download-backoff` イベントのペイロード）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackoffEvent {
+    /// 待機の対象ホスト
+    pub host: String,
+    pub reason: BackoffReason,
+    /// サーバーが返したステータス（接続エラーの場合は None）
+    pub status: Option<u16>,
+    /// 待機時間（秒）
+    pub wait_secs: u64,
+    /// 次が何回目の試行か
+    pub attempt: u32,
+    /// 表示用メッセージ（例: "waiting 30s due to 429 from webcatalog.koizumi-lt.co.jp"）
+    pub message: String,
+}
+
+/// 待機の通知先
+type BackoffNotifier = Box<dyn Fn(&BackoffEvent) + Send + Sync>;
+
+static BACKOFF_NOTIFIER: OnceLock<BackoffNotifier> = OnceLock::new();
+
+/// 待機の通知先を登録（起動時に1回だけ）
+pub fn set_backoff_notifier(notifier: impl Fn(&BackoffEvent) + Send + Sync + 'static) {
+    let _ = BACKOFF_NOTIFIER.set(Box::new(notifier));
+}
+
+/// 再試行が必要な応答の場合、待機の理由と時間（秒）を返す
+fn backoff_for_response(
+    response: &reqwest::Response,
+    retry: &RetrySettings,
+) -> Option<(BackoffReason, u64)> {
+    let reason = match response.status() {
+        reqwest::StatusCode::TOO_MANY_REQUESTS => BackoffReason::RateLimited,
+        reqwest::StatusCode::SERVICE_UNAVAILABLE => BackoffReason::ServerBusy,
+        _ => return None,
+    };
+    Some((reason, retry_after_secs(response.headers(), retry)))
+}
+
+/// Retry-After ヘッダー（秒数指定のみ対応）から待機時間（秒）を取得
+fn retry_after_secs(headers: &reqwest::header::HeaderMap, retry: &RetrySettings) -> u64 {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_RETRY_WAIT_SECS)
+        .min(retry.max_wait_secs)
+}
+
+/// 再試行が必要なエラーの場合、待機の理由と時間（秒）を返す
+fn backoff_for_error(
+    error: &reqwest::Error,
+    attempt: u32,
+    retry: &RetrySettings,
+) -> Option<(BackoffReason, u64)> {
+    if error.is_connect() || error.is_timeout() {
+        Some((BackoffReason::NetworkError, retry.error_wait_secs(attempt)))
+    } else {
+        None
+    }
+}
+
+/// 上限の設定がないホストへの同時リクエスト数の上限
+///
+/// バッチの同時実行数を上げても、1つのメーカーサイトに集中しないようにする。
+pub const DEFAULT_DOMAIN_CONCURRENCY: usize = 4;
+
+/// ドメインごとの同時リクエスト数の上限（ドメイン → 上限のセマフォ。設定の保存時に反映する）
+static DOMAIN_LIMITS: LazyLock<RwLock<BTreeMap<String, Arc<Semaphore>>>> =
+    LazyLock::new(Default::default);
+
+/// 上限の設定がないホストのセマフォ（ホスト → 既定の上限のセマフォ。最初の送信時に作成する）
+static DEFAULT_HOST_LIMITS: LazyLock<Mutex<HashMap<String, Arc<Semaphore>>>> =
+    LazyLock::new(Default::default);
+
+/// ドメインごとの同時リクエスト数の上限を反映（起動時・設定の保存時）
+///
+/// 反映前に送信中のリクエストは以前の上限のまま完了する。
+pub fn apply_domain_concurrency(limits: &BTreeMap<String, usize>) {
+    let limits = limits
+        .iter()
+        .map(|(domain, &limit)| {
+            (
+                domain.trim().to_lowercase(),
+                Arc::new(Semaphore::new(limit.max(1))),
+            )
+        })
+        .collect();
+    *DOMAIN_LIMITS.write().unwrap_or_else(|e| e.into_inner()) = limits;
+}
+
+/// ホストと一致するか、サブドメインとして最も長く一致するドメインの値
+fn match_domain<T: Clone>(map: &BTreeMap<String, T>, host: &str) -> Option<T> {
+    let host = host.to_lowercase();
+    map.iter()
+        .filter(|(domain, _)| {
+            host == **domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+        .max_by_key(|(domain, _)| domain.len())
+        .map(|(_, value)| value.clone())
+}
+
+/// ホストに適用する上限のセマフォ（設定がないホストは既定の上限）
+fn domain_limit(host: &str) -> Arc<Semaphore> {
+    if let Some(limit) = match_domain(
+        &DOMAIN_LIMITS.read().unwrap_or_else(|e| e.into_inner()),
+        host,
+    ) {
+        return limit;
+    }
+    DEFAULT_HOST_LIMITS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(host.to_lowercase())
+        .or_insert_with(|| Arc::new(Semaphore::new(DEFAULT_DOMAIN_CONCURRENCY)))
+        .clone()
+}
+
+/// ホストへの送信の許可を待つ
+async fn acquire_domain_permit(host: &str) -> Option<OwnedSemaphorePermit> {
+    domain_limit(host).acquire_owned().await.ok()
+}
+
+/// ドメインごとのリクエストの最小間隔（ドメイン → 間隔と前回の送信時刻。設定の保存時に反映する）
+type DomainInterval = Arc<(Duration, tokio::sync::Mutex<Option<Instant>>)>;
+
+static DOMAIN_INTERVALS: LazyLock<RwLock<BTreeMap<String, DomainInterval>>> =
+    LazyLock::new(Default::default);
+
+/// ドメインごとのリクエストの最小間隔（ミリ秒）を反映（起動時・設定の保存時）
+pub fn apply_domain_request_interval(intervals: &BTreeMap<String, u64>) {
+    let intervals = intervals
+        .iter()
+        .filter(|(_, &ms)| ms > 0)
+        .map(|(domain, &ms)| {
+            (
+                domain.trim().to_lowercase(),
+                Arc::new((Duration::from_millis(ms), tokio::sync::Mutex::new(None))),
+            )
+        })
+        .collect();
+    *DOMAIN_INTERVALS.write().unwrap_or_else(|e| e.into_inner()) = intervals;
+}
+
+/// ホストに適用する最小間隔
+fn domain_interval(host: &str) -> Option<DomainInterval> {
+    match_domain(
+        &DOMAIN_INTERVALS.read().unwrap_or_else(|e| e.into_inner()),
+        host,
+    )
+}
+
+/// 前回の送信から最小間隔が経つまで待つ（間隔の設定がないホストはすぐに戻る）
+///
+/// 同じドメインへの送信は順に待つため、同時実行数によらず間隔が空く。
+async fn wait_domain_interval(host: &str) {
+    let Some(interval) = domain_interval(host) else {
+        return;
+    };
+    let (min_interval, last_sent) = &*interval;
+    let mut last_sent = last_sent.lock().await;
+    if let Some(wait) = last_sent.and_then(|t| min_interval.checked_sub(t.elapsed())) {
+        tracing::debug!(host, wait_ms = wait.as_millis() as u64, "rate limiting");
+        tokio::time::sleep(wait).await;
+    }
+    *last_sent = Some(Instant::now());
+}
+
+/// リクエストの送信エラー
+#[derive(Debug)]
+pub enum RequestError {
+    /// オフラインモードのため送信しなかった（URL）
+    Offline(String),
+    /// 再生モードで、記録にないか記録を読み込めなかった（エラーメッセージ）
+    Cassette(String),
+    Http(reqwest::Error),
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Offline(url) => f.write_str(&offline::not_cached(url).message),
+            RequestError::Cassette(message) => f.write_str(message),
+            RequestError::Http(e) => e.fmt(f),
+        }
+    }
+}
+
+impl RequestError {
+    /// エラーの種別（オフライン・記録にない・タイムアウト・その他の通信エラー）
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RequestError::Offline(_) | RequestError::Cassette(_) => ErrorCode::OfflineNotCached,
+            RequestError::Http(e) if e.is_timeout() => ErrorCode::NetworkTimeout,
+            RequestError::Http(_) => ErrorCode::NetworkError,
+        }
+    }
+
+    /// 処理の説明（例: `"Detail request failed"`）を付けたエラー
+    pub fn context(self, what: &str) -> ProviderError {
+        ProviderError::new(self.code(), format!("{}: {}", what, self))
+    }
+}
+
+impl std::error::Error for RequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RequestError::Offline(_) | RequestError::Cassette(_) => None,
+            RequestError::Http(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(e: reqwest::Error) -> Self {
+        RequestError::Http(e)
+    }
+}
+
+/// HTTPリクエストを送信
+///
+/// `http_request` スパン内で送信し、ステータスと所要時間をログに記録する。
+/// 設定でドメインごとの同時リクエスト数の上限がある場合は、上限を超えないよう送信を待つ
+/// （応答のヘッダーを受け取るまで。本文の読み込み中は数えない）。
+/// 設定でドメインごとのリクエストの最小間隔がある場合は、前回の送信から間隔を空ける。
+/// 429 / 503 や接続エラーの場合は待機して再試行し（接続エラーは待機時間を指数的に延ばす。
+/// 回数・待機時間は設定の `retry`）、待機することを通知する。
+/// 一括ダウンロードの監査ログの記録中は、各試行のURLとステータスを記録する。
+/// オフラインモードでは送信せず、すぐに [`RequestError::Offline`] を返す。
+/// 通信の再生モードでは送信せずに記録した応答を返し、記録モードでは応答を記録する（`cassette` モジュール）。
+pub async fn send_request(
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, RequestError> {
+    let (client, request) = request.build_split();
+    let mut request = request?;
+    if offline::is_enabled() {
+        return Err(RequestError::Offline(request.url().to_string()));
+    }
+    if let Some(replayed) = cassette::replay(&request) {
+        return replayed.map_err(RequestError::Cassette);
+    }
+    let (method, url) = (request.method().to_string(), request.url().clone());
+    let span = tracing::debug_span!(
+        "http_request",
+        method = %request.method(),
+        url = %request.url()
+    );
+
+    let response: Result<reqwest::Response, RequestError> = async move {
+        let retry_settings = retry_settings(request.url().host_str().unwrap_or_default());
+        let mut attempt = 1;
+        loop {
+            let retry = request.try_clone();
+            let host = request.url().host_str().unwrap_or_default().to_string();
+            let method = request.method().to_string();
+            let url = request.url().to_string();
+
+            let permit = acquire_domain_permit(&host).await;
+            wait_domain_interval(&host).await;
+            let started = Instant::now();
+            let result = client.execute(request).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            drop(permit);
+            audit::record_request(
+                &method,
+                &url,
+                result.as_ref().ok().map(|r| r.status().as_u16()),
+                result.as_ref().err().map(|e| e.to_string()),
+            );
+            let backoff = match &result {
+                Ok(response) if !response.status().is_success() => {
+                    // リクエストのスパンはDEBUGレベルのため、既定のログレベルでもURLが残るよう含める
+                    tracing::info!(
+                        %method,
+                        %url,
+                        status = %response.status(),
+                        elapsed_ms,
+                        "unsuccessful response"
+                    );
+                    backoff_for_response(response, &retry_settings)
+                }
+                Ok(response) => {
+                    tracing::debug!(status = %response.status(), elapsed_ms, "response received");
+                    backoff_for_response(response, &retry_settings)
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, %method, %url, elapsed_ms, "request failed");
+                    backoff_for_error(e, attempt, &retry_settings)
+                }
+            };
+
+            let (Some((reason, wait_secs)), Some(next)) = (backoff, retry) else {
+                return Ok(result?);
+            };
+            if attempt >= retry_settings.max_attempts {
+                return Ok(result?);
+            }
+
+            let status = result.as_ref().ok().map(|r| r.status().as_u16());
+            let cause = match status {
+                Some(status) => status.to_string(),
+                None => "network error".to_string(),
+            };
+            let event = BackoffEvent {
+                message: format!("waiting {}s due to {} from {}", wait_secs, cause, host),
+                host,
+                reason,
+                status,
+                wait_secs,
+                attempt: attempt + 1,
+            };
+            tracing::warn!(wait_secs, attempt, "{}", event.message);
+            if let Some(notify) = BACKOFF_NOTIFIER.get() {
+                notify(&event);
+            }
+
+            tokio::time::sleep(Duration::from_secs(wait_secs)).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+    .instrument(span)
+    .await;
+    Ok(cassette::record(&method, &url, response?).await?)
+}
+
+/// HEADリクエストでファイルサイズ（Content-Length）を取得
+///
+/// サーバーがサイズを返さない場合や、リクエストに失敗した場合は None を返す。
+pub async fn fetch_content_length(client: &reqwest::Client, url: &str) -> Option<u64> {
+    let response = send_request(client.head(url)).await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.content_length().filter(|&len| len > 0)
+}
+
+/// 受信が不完全だった場合にやり直す回数
+const INCOMPLETE_RETRIES: u32 = 1;
+
+/// 受信したサイズを Content-Length と照合（途中で切れた本文・空の本文はエラー）
+pub fn verify_length(expected: Option<u64>, received: u64) -> ProviderResult<()> {
+    match expected {
+        Some(expected) if received != expected => Err(incomplete(format!(
+            "Incomplete download: received {} of {} bytes",
+            received, expected
+        ))),
+        _ if received == 0 => Err(incomplete("Incomplete download: received 0 bytes")),
+        _ => Ok(()),
+    }
+}
+
+/// 受信・保存が途中で切れた場合のエラー（[`retry_incomplete`] でやり直す対象）
+fn incomplete(message: impl Into<String>) -> ProviderError {
+    ProviderError::new(ErrorCode::IncompleteDownload, message)
+}
+
+/// 保存したファイルのサイズを書き込んだサイズと照合（一致しなければファイルを削除してエラー）
+pub async fn verify_written(path: &std::path::Path, expected: u64) -> ProviderResult<()> {
+    let written = tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    if written == expected {
+        return Ok(());
+    }
+    let _ = tokio::fs::remove_file(path).await;
+    Err(incomplete(format!(
+        "Incomplete download: wrote {} of {} bytes",
+        written, expected
+    )))
+}
+
+/// 受信が不完全だった場合に1回だけやり直す（それ以外の結果はそのまま返す）
+pub async fn retry_incomplete<T, F, Fut>(mut attempt: F) -> ProviderResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ProviderResult<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match attempt().await {
+            Err(e) if e.code == ErrorCode::IncompleteDownload && retries < INCOMPLETE_RETRIES => {
+                tracing::warn!(error = %e, "retrying incomplete download");
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Content-Dispositionヘッダーからファイル名を抽出
+pub fn filename_from_content_disposition(header_value: &str) -> Option<String> {
+    // パターン: filename="xxx.ies" または filename*=UTF-8''xxx.ies
+    if let Some(start) = header_value.find("filename=") {
+        let rest = &header_value[start + 9..];
+        let filename = if rest.starts_with('"') {
+            // filename="xxx.ies"
+            rest.trim_start_matches('"')
+                .split('"')
+                .next()
+                .map(|s| s.to_string())
+        } else {
+            // filename=xxx.ies
+            rest.split(';').next().map(|s| s.trim().to_string())
+        };
+        return filename;
+    }
+    None
+}
+
+/// URLのパスの末尾からファイル名を取得
+pub fn filename_from_url(url: &reqwest::Url) -> Option<String> {
+    url.path_segments()?
+        .next_back()
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// URLに埋め込む値をパーセントエンコード（RFC 3986 の非予約文字以外）
+pub fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Content-Type から画像の拡張子を判定（画像以外・不明な形式は None）
+fn image_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        _ => None,
+    }
+}
+
+/// 製品画像をダウンロード（`AssetType::Image` の各プロバイダー共通の処理）
+///
+/// 製品画像はダウンロードリンクとして公開されていないため、製品情報の画像URL
+/// （[`ProductInfo::image_url`]）から取得する。
+pub async fn download_product_image(
+    provider: &dyn ManufacturerProvider,
+    client: &reqwest::Client,
+    model_number: &str,
+    dest_path: &str,
+    cancel: &CancelToken,
+) -> ProviderResult<DownloadResult> {
+    let started = Instant::now();
+    let url = cancel
+        .run(provider.fetch_product_info(model_number))
+        .await?
+        .image_url
+        .ok_or_else(|| not_available(AssetType::Image, model_number))?;
+    let lookup_ms = started.elapsed().as_millis() as u64;
+
+    let started = Instant::now();
+    let result = cancel
+        .run(retry_incomplete(|| save_image(client, &url, dest_path)))
+        .await?;
+    Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+}
+
+/// 画像を取得して保存
+///
+/// 元ファイル名は Content-Disposition、なければURLの末尾から取得し、拡張子がなければ
+/// Content-Type から補う（保存先のファイル名の拡張子に使われるため）。
+async fn save_image(
+    client: &reqwest::Client,
+    url: &str,
+    dest_path: &str,
+) -> ProviderResult<DownloadResult> {
+    let response = send_request(client.get(url))
+        .await
+        .map_err(|e| e.context("Download request failed"))?;
+    if !response.status().is_success() {
+        return Ok(DownloadResult::http_failure(response.status()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if content_type.to_ascii_lowercase().starts_with("text/html") {
+        return Err(ProviderError::new(
+            ErrorCode::InvalidContent,
+            "Image URL returned an HTML page instead of an image",
+        ));
+    }
+    let original_filename = response
+        .headers()
+        .get("content-disposition")
+        .and_then(|h| h.to_str().ok())
+        .and_then(filename_from_content_disposition)
+        .or_else(|| filename_from_url(response.url()))
+        .map(|name| {
+            match (
+                std::path::Path::new(&name).extension(),
+                image_extension(&content_type),
+            ) {
+                (None, Some(extension)) => format!("{}.{}", name, extension),
+                _ => name,
+            }
+        });
+    let source = DownloadSource::from_response(&response);
+    let total_bytes = response.content_length();
+    report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+    // 保存し終えるまでバッファの使用枠を保持する
+    let _permit = buffer::acquire(total_bytes).await;
+    let bytes = response.bytes().await.map_err(ProviderError::wrap(
+        ErrorCode::NetworkError,
+        "Failed to read file content",
+    ))?;
+    let file_size = bytes.len() as u64;
+    report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+    verify_length(total_bytes, file_size)?;
+
+    let dest = longpath::extended(dest_path);
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
+    }
+    tokio::fs::write(&dest, &bytes)
+        .await
+        .map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+    verify_written(&dest, file_size).await?;
+
+    Ok(
+        DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+            .with_source(source),
+    )
+}
+
+/// 疎通確認の診断結果の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosisStatus {
+    /// 正常
+    Ok,
+    /// 名前解決に失敗（DNS）
+    DnsFailure,
+    /// 接続に失敗（ファイアウォール・オフライン等）
+    ConnectionFailed,
+    /// タイムアウト
+    Timeout,
+    /// プロキシ認証が必要（HTTP 407）
+    ProxyAuthRequired,
+    /// サーバーがエラーを返した
+    HttpError,
+    /// ページ構成が変わり、必要な情報を抽出できない
+    HtmlChanged,
+    /// 製品情報の取得に失敗
+    LookupFailed,
+}
+
+/// 疎通確認の診断結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnosis {
+    pub status: DiagnosisStatus,
+    /// 詳細メッセージ
+    pub message: String,
+}
+
+impl Diagnosis {
+    pub fn new(status: DiagnosisStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// リクエストエラーを診断結果の種別に分類
+fn classify_request_error(error: &reqwest::Error) -> DiagnosisStatus {
+    if error.is_timeout() {
+        return DiagnosisStatus::Timeout;
+    }
+
+    // 名前解決の失敗はエラーの原因チェーンのメッセージで判別する
+    let mut source: Option<&dyn std::error::Error> = Some(error);
+    while let Some(e) = source {
+        let message = e.to_string().to_lowercase();
+        if message.contains("dns error")
+            || message.contains("failed to lookup address")
+            || message.contains("no such host")
+        {
+            return DiagnosisStatus::DnsFailure;
+        }
+        source = e.source();
+    }
+
+    if error.is_connect() {
+        DiagnosisStatus::ConnectionFailed
+    } else {
+        DiagnosisStatus::HttpError
+    }
+}
+
+/// URLへの疎通を確認し、失敗した場合は原因を分類して返す
+pub async fn check_url(client: &reqwest::Client, url: &str) -> Result<(), Diagnosis> {
+    let response = send_request(client.get(url)).await.map_err(|e| {
+        let status = match &e {
+            RequestError::Offline(_) | RequestError::Cassette(_) => {
+                DiagnosisStatus::ConnectionFailed
+            }
+            RequestError::Http(http) => classify_request_error(http),
+        };
+        Diagnosis::new(status, e.to_string())
+    })?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+        Err(Diagnosis::new(
+            DiagnosisStatus::ProxyAuthRequired,
+            format!("Proxy authentication required: {}", url),
+        ))
+    } else if !status.is_success() {
+        Err(Diagnosis::new(
+            DiagnosisStatus::HttpError,
+            format!("{} returned status: {}", url, status),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// ダウンロード対象のアセット種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetType {
+    /// 配光データ（IES）
+    Ies,
+    /// 配光測定成績書（PDF）
+    PhotometricReport,
+    /// BIMデータ（Revit RFA / IFC）
+    Bim,
+    /// 3Dモデル（SKP / 3DS / OBJ）
+    Model3d,
+    /// 仕様書（PDF）
+    SpecSheet,
+    /// 製品画像
+    Image,
+    /// CADデータ（DWG / DXF）
+    Cad,
+    /// 取扱説明書・施工説明書（PDF）
+    Manual,
+}
+
+impl AssetType {
+    /// 保存先ディレクトリ直下のサブフォルダ名（IESは直下に保存）
+    pub fn subdir(&self) -> Option<&'static str> {
+        match self {
+            AssetType::Ies => None,
+            AssetType::PhotometricReport => Some("Photometric Report"),
+            AssetType::SpecSheet => Some("Spec"),
+            AssetType::Image => Some("Image"),
+            AssetType::Cad => Some("CAD"),
+            AssetType::Bim => Some("BIM"),
+            AssetType::Model3d => Some("3D"),
+            AssetType::Manual => Some("Manual"),
+        }
+    }
 }
 
 /// ダウンロード結果
@@ -42,6 +1846,90 @@ pub struct DownloadResult {
     pub original_filename: Option<String>,
     /// エラーメッセージ
     pub error: Option<String>,
+    /// エラーコード（失敗時のみ）
+    #[serde(default)]
+    pub code: Option<ErrorCode>,
+    /// 再試行で解決する可能性がある失敗か（成功時・恒久的なエラーでは false）
+    #[serde(default)]
+    pub retryable: bool,
+    /// 成功したが確認が必要な点
+    #[serde(default)]
+    pub warnings: Vec<DownloadWarning>,
+    /// ZIP内で同程度に一致したファイルの候補（選択したファイルを含む。曖昧でない場合は空）
+    #[serde(default)]
+    pub candidates: Vec<String>,
+    /// IES以外の形式（LDT）から変換した場合、変換元のファイルの保存先
+    #[serde(default)]
+    pub converted_from: Option<String>,
+    /// 取得元（URL・HTTP応答の情報。オフラインモード等、通信せずに取得した場合は None）
+    #[serde(default)]
+    pub source: Option<DownloadSource>,
+    /// 所要時間・転送量
+    #[serde(default)]
+    pub timing: DownloadTiming,
+    /// IESファイルの概要（IESファイルの取得に成功した場合のみ）
+    #[serde(default)]
+    pub ies_metadata: Option<IesMetadata>,
+    /// ダウンロードキャッシュから取り出したか（メーカーサイトに問い合わせていない）
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+/// ダウンロードしたファイルの取得元
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSource {
+    /// 取得元のURL（リダイレクト後。ZIPで配布される場合はZIPのURL）
+    pub url: String,
+    /// HTTPステータス
+    pub status: Option<u16>,
+    /// Content-Type ヘッダー
+    pub content_type: Option<String>,
+    /// サーバー上の最終更新日時（Last-Modified ヘッダーの値）
+    pub last_modified: Option<String>,
+}
+
+impl DownloadSource {
+    /// レスポンスから取得元の情報を取得
+    pub fn from_response(response: &reqwest::Response) -> Self {
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            url: response.url().to_string(),
+            status: Some(response.status().as_u16()),
+            content_type: header(reqwest::header::CONTENT_TYPE),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+}
+
+/// ダウンロードの所要時間・転送量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadTiming {
+    /// ダウンロードURLの解決（製品ページ・検索ページの取得）にかかった時間（ミリ秒）
+    pub lookup_ms: u64,
+    /// ファイルの取得・保存にかかった時間（ミリ秒）
+    pub download_ms: u64,
+    /// 全体の所要時間（ミリ秒。リネーム等の後処理を含む）
+    pub total_ms: u64,
+    /// 受信したバイト数（ZIPで配布される場合はZIP全体のサイズ）
+    pub bytes_transferred: u64,
+}
+
+impl DownloadTiming {
+    /// 計測値を合算
+    pub fn add(&mut self, other: &DownloadTiming) {
+        self.lookup_ms += other.lookup_ms;
+        self.download_ms += other.download_ms;
+        self.total_ms += other.total_ms;
+        self.bytes_transferred += other.bytes_transferred;
+    }
 }
 
 impl DownloadResult {
@@ -52,20 +1940,177 @@ impl DownloadResult {
             file_size: Some(file_size),
             original_filename,
             error: None,
+            code: None,
+            retryable: false,
+            warnings: Vec::new(),
+            candidates: Vec::new(),
+            converted_from: None,
+            source: None,
+            timing: DownloadTiming {
+                bytes_transferred: file_size,
+                ..Default::default()
+            },
+            ies_metadata: None,
+            from_cache: false,
+        }
+    }
+
+    /// 警告を追加
+    pub fn with_warning(mut self, warning: DownloadWarning) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// 取得元を記録
+    pub fn with_source(mut self, source: DownloadSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// URLの解決・ファイルの取得にかかった時間を記録
+    pub fn with_timing(mut self, lookup_ms: u64, download_ms: u64) -> Self {
+        self.timing.lookup_ms = lookup_ms;
+        self.timing.download_ms = download_ms;
+        self
+    }
+
+    /// 失敗（文字列のエラーの場合、エラーコードはメッセージから判定）
+    pub fn failure(error: impl Into<ProviderError>) -> Self {
+        let error = error.into();
+        Self::failure_with_code(error.code, error.message)
+    }
+
+    /// サーバーがエラーステータスを返した場合の失敗（429 はレート制限）
+    pub fn http_failure(status: reqwest::StatusCode) -> Self {
+        Self::failure(status_error(
+            status,
+            format!("Download failed with status: {}", status),
+        ))
+    }
+
+    pub fn failure_with_code(code: ErrorCode, error: String) -> Self {
+        Self {
+            success: false,
+            file_path: None,
+            file_size: None,
+            original_filename: None,
+            error: Some(error),
+            code: Some(code),
+            retryable: code.is_retryable(),
+            warnings: Vec::new(),
+            candidates: Vec::new(),
+            converted_from: None,
+            source: None,
+            timing: DownloadTiming::default(),
+            ies_metadata: None,
+            from_cache: false,
+        }
+    }
+}
+
+/// ダウンロード時の警告の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadWarningKind {
+    /// ZIP内に同程度に一致するファイルが複数あり、そのうち1つを選択した
+    AmbiguousZipMatch,
+    /// PSU指定ありで見つからず、型番のみで取得した
+    PsuFallback,
+    /// IESファイルがなく、EULUMDAT（LDT）から変換した
+    ConvertedFromLdt,
+    /// 型番が見つからず、利用者が選んだ近い型番の製品から取得した
+    ProductSubstituted,
+    /// 型番が見つからず、後継品の対応表に記録されている後継品から取得した
+    SuccessorSubstituted,
+    /// 型番の製品のIESファイルがなく、シリーズの代表のIESファイルを取得した
+    SeriesApproximation,
+    /// 出力形式にLDTを指定したが、IESファイルをLDTに変換できなかった（IESファイルは残す）
+    LdtConversionFailed,
+    /// 製品ページのIESファイルのリンクが1つだけで、ファイル名を型番と照合できなかった（そのリンクから取得した）
+    UnmatchedLink,
+}
+
+/// ダウンロード時の警告（失敗ではないが、結果の確認が必要なもの）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadWarning {
+    pub kind: DownloadWarningKind,
+    pub message: String,
+}
+
+impl DownloadWarning {
+    pub fn new(kind: DownloadWarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
         }
     }
+}
+
+/// IESファイルのダウンロードURLの解決結果（`resolve_ies_url` の戻り値）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedIesUrl {
+    /// ダウンロードURL（ZIPで配布される場合はZIPのURL）
+    pub url: String,
+    /// ZIP内のIESファイル候補（ZIPで配布されない場合は空）
+    pub candidates: Vec<String>,
+    /// 候補のうちダウンロード時に選択されるファイル
+    pub selected: Option<String>,
+}
 
-    pub fn failure(error: String) -> Self {
+impl ResolvedIesUrl {
+    /// ZIPを介さず直接ダウンロードできるURL
+    pub fn direct(url: String) -> Self {
         Self {
-            success: false,
-            file_path: None,
-            file_size: None,
-            original_filename: None,
-            error: Some(error),
+            url,
+            candidates: vec![],
+            selected: None,
         }
     }
 }
 
+/// ZIP内のファイル候補（`list_zip_candidates` の戻り値）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipCandidate {
+    /// ZIP内のパス
+    pub name: String,
+    /// ダウンロード時に選択されるファイルか
+    pub selected: bool,
+    /// 選択されるファイルと同程度に一致するか（型番だけでは区別できない候補）
+    pub close_match: bool,
+    /// 型番と一致するか（シリーズ・色温度・配光角が異なるものは false）
+    pub matches: bool,
+    /// 型番との一致度（型番と一致しない場合は None）
+    pub score: Option<MatchScore>,
+}
+
+/// プロバイダーのメタデータ（`list_providers` の戻り値）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderInfo {
+    /// プロバイダーID
+    pub id: String,
+    /// 表示名
+    pub display_name: String,
+    /// メーカー名の判定に使用する別名
+    pub aliases: Vec<String>,
+    /// WebサイトのベースURL
+    pub base_url: String,
+    /// 対応しているアセット種別
+    pub supported_assets: Vec<AssetType>,
+    /// キーワード検索に対応しているか
+    pub supports_search: bool,
+    /// 定価の取得に対応しているか
+    pub supports_pricing: bool,
+    /// 有効かどうか（無効なプロバイダーはメーカー名の判定に使用しない）
+    pub enabled: bool,
+    /// 定義ファイルから作成したプロバイダーか（[`generic`]）
+    #[serde(default)]
+    pub custom: bool,
+}
+
 /// メーカープロバイダー trait
 ///
 /// 各メーカーはこのtraitを実装することで、AutoSightに統合される。
@@ -73,20 +2118,114 @@ impl DownloadResult {
 /// ProviderRegistryに登録するだけでよい。
 #[async_trait]
 pub trait ManufacturerProvider: Send + Sync {
+    /// プロバイダーID（英小文字。設定等での識別用）
+    fn id(&self) -> &str;
+
     /// 表示名（日本語）
     fn display_name(&self) -> &str;
 
+    /// WebサイトのベースURL
+    fn base_url(&self) -> &str;
+
+    /// メーカー名の判定に使用する別名（小文字で記述）
+    fn aliases(&self) -> Vec<&str>;
+
     /// このプロバイダーが指定されたメーカー名を処理できるか判定
     ///
+    /// デフォルトでは、メーカー名（小文字化）がいずれかの別名を含むかで判定する。
+    ///
     /// # Arguments
     /// * `manufacturer` - Excelの「メーカー」列の値
-    fn can_handle(&self, manufacturer: &str) -> bool;
+    fn can_handle(&self, manufacturer: &str) -> bool {
+        let lower = manufacturer.to_lowercase();
+        self.aliases().iter().any(|alias| lower.contains(alias))
+    }
+
+    /// キーワード検索（`search_products`）に対応しているか
+    fn supports_search(&self) -> bool {
+        false
+    }
+
+    /// 定価の取得（`fetch_price`）に対応しているか
+    fn supports_pricing(&self) -> bool {
+        false
+    }
+
+    /// 疎通確認に使用する型番（IESファイルが掲載されている既知の製品）
+    fn sample_model_number(&self) -> Option<&str> {
+        None
+    }
+
+    /// Webサイトへの疎通を確認
+    ///
+    /// 失敗した場合は原因（DNS・接続・プロキシ認証等）を分類した診断結果を返す。
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        Ok(())
+    }
 
     /// 製品情報を取得
     ///
     /// # Arguments
     /// * `model_number` - 型番（Excelの「FIXTURE」列の値）
-    async fn fetch_product_info(&self, model_number: &str) -> Result<ProductInfo, String>;
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo>;
+
+    /// キーワードで製品を検索し、候補一覧を取得
+    ///
+    /// # Arguments
+    /// * `keyword` - 検索キーワード（型番の一部等）
+    async fn search_products(&self, _keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        Err(ProviderError::new(
+            ErrorCode::InvalidInput,
+            format!("{} does not support product search", self.display_name()),
+        ))
+    }
+
+    /// 定価を取得
+    ///
+    /// デフォルトでは製品情報の `price` を返す。定価が掲載されていない場合は None。
+    ///
+    /// # Arguments
+    /// * `model_number` - 型番
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        Ok(self.fetch_product_info(model_number).await?.price)
+    }
+
+    /// ファイルサイズ（バイト）をダウンロードせずに取得
+    ///
+    /// 一括ダウンロードの事前見積もりに使用する。取得できない場合は None。
+    ///
+    /// # Arguments
+    /// * `url` - ファイルのURL（`ProductInfo::ies_file_url` 等）
+    async fn fetch_file_size(&self, _url: &str) -> Option<u64> {
+        None
+    }
+
+    /// IESファイルのダウンロードURLを解決（ダウンロードはしない）
+    ///
+    /// デフォルトでは製品情報の `ies_file_url` を返す。
+    ///
+    /// # Arguments
+    /// * `model_number` - 型番
+    /// * `psu` - PSU型番（オプション）
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.fetch_product_info(model_number)
+            .await?
+            .ies_file_url
+            .map(ResolvedIesUrl::direct)
+            .ok_or_else(|| not_available(AssetType::Ies, model_number))
+    }
+
+    /// IESファイルを配布するZIP内の候補を一致度の高い順に返す（ダウンロード時の選択を確認・
+    /// 手動で選び直すためのもの）
+    ///
+    /// デフォルトではZIPで配布しないため空を返す。
+    async fn list_zip_candidates(&self, _model_number: &str) -> ProviderResult<Vec<ZipCandidate>> {
+        Ok(Vec::new())
+    }
 
     /// IESファイルをダウンロード
     ///
@@ -94,37 +2233,79 @@ pub trait ManufacturerProvider: Send + Sync {
     /// * `model_number` - 型番
     /// * `psu` - PSU型番（オプション）
     /// * `dest_path` - 保存先ファイルパス
+    /// * `cancel` - 中断指示（指示されたら実行中のリクエスト・ZIPの展開を中断して
+    ///   `CANCELLED` のエラーを返す）
     async fn download_ies_file(
         &self,
         model_number: &str,
         psu: Option<&str>,
         dest_path: &str,
-    ) -> Result<DownloadResult, String>;
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult>;
+
+    /// 保存先ファイル名の既定テンプレート（設定でテンプレートが指定されていない場合に使用）
+    ///
+    /// プロバイダーごとの命名規則を `filename` モジュールのテンプレートで表す。
+    fn default_filename_template(&self, _asset_type: AssetType) -> &str {
+        filename::DEFAULT_TEMPLATE
+    }
+
+    /// 対応しているアセット種別
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies]
+    }
 
-    /// ダウンロード後のファイル名を生成
+    /// 指定したアセットと同じアイテムの処理で一緒に取得するアセット種別
     ///
-    /// プロバイダーごとに最適な命名規則を実装する。
+    /// 同じ製品ページに掲載されている資料を、見積もり等のためにまとめて取得する場合に使用する。
+    fn companion_assets(&self, _asset_type: AssetType) -> Vec<AssetType> {
+        Vec::new()
+    }
+
+    /// IES以外のアセットをダウンロード
     ///
     /// # Arguments
-    /// * `spec_no` - Spec No.
     /// * `model_number` - 型番
     /// * `psu` - PSU型番（オプション）
-    /// * `original_filename` - サーバーから取得した元ファイル名（オプション）
-    fn generate_filename(
+    /// * `asset_type` - アセット種別
+    /// * `dest_path` - 保存先ファイルパス
+    /// * `cancel` - 中断指示（`download_ies_file` と同じ）
+    async fn download_asset(
         &self,
-        spec_no: &str,
-        model_number: &str,
-        psu: Option<&str>,
-        original_filename: Option<&str>,
-    ) -> String;
+        _model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        _dest_path: &str,
+        _cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        Err(not_provided(self.display_name(), asset_type))
+    }
+
+    /// 一括ダウンロードの開始時に呼び出される
+    ///
+    /// バッチ内の複数アイテムで共有できるデータ（同じシリーズのZIP等）の保持を開始する。
+    /// 複数のバッチが同時に実行される場合はバッチごとに呼び出される（[`BatchCache`] で数える）。
+    fn begin_batch(&self) {}
+
+    /// 一括ダウンロードの終了時に呼び出される
+    ///
+    /// 実行中のバッチがなくなったときに `begin_batch` で保持したデータを破棄する。
+    fn end_batch(&self) {}
 }
 
 /// プロバイダーレジストリ
 ///
 /// 登録されたメーカープロバイダーを管理し、
 /// メーカー名から適切なプロバイダーを取得する。
+#[derive(Clone)]
 pub struct ProviderRegistry {
     providers: Vec<Arc<dyn ManufacturerProvider>>,
+    /// 無効化されたプロバイダーのID
+    disabled: HashSet<String>,
+    /// 利用者が登録したメーカー名の別名（正規化したメーカー名 → プロバイダーID）
+    aliases: BTreeMap<String, String>,
+    /// 定義ファイルから作成したプロバイダー（`providers` にも登録する）
+    custom: Vec<Arc<GenericProvider>>,
 }
 
 impl Default for ProviderRegistry {
@@ -136,30 +2317,1051 @@ impl Default for ProviderRegistry {
 impl ProviderRegistry {
     /// 新しいレジストリを作成（デフォルトプロバイダーを登録）
     pub fn new() -> Self {
-        let mut registry = Self { providers: vec![] };
-        registry.register(Arc::new(koizumi::KoizumiProvider::new()));
-        registry.register(Arc::new(tokistar::TokistarProvider::new()));
+        Self::with_config(&BTreeMap::new())
+    }
+
+    /// 接続設定（プロバイダーIDをキーとするマップ）を反映したレジストリを作成
+    pub fn with_config(configs: &BTreeMap<String, ProviderConfig>) -> Self {
+        let mut registry = Self {
+            providers: vec![],
+            disabled: HashSet::new(),
+            aliases: BTreeMap::new(),
+            custom: Vec::new(),
+        };
+        for provider in configured_providers(configs) {
+            registry.register(provider);
+        }
+        registry.set_mock_enabled(cassette::mode() == CassetteMode::Mock);
         registry
     }
 
+    /// モックプロバイダーを登録・登録解除（通信の記録・再生の設定がモックの場合のみ登録する）
+    pub fn set_mock_enabled(&mut self, enabled: bool) {
+        self.providers.retain(|p| p.id() != mock::ID);
+        if enabled {
+            self.register(Arc::new(mock::MockProvider::new()));
+        }
+    }
+
     /// プロバイダーを登録
     pub fn register(&mut self, provider: Arc<dyn ManufacturerProvider>) {
         self.providers.push(provider);
     }
 
-    /// メーカー名から適切なプロバイダーを取得
+    /// メーカー名から適切なプロバイダーを取得（無効なプロバイダーは除く）
+    ///
+    /// メーカー名は正規化（[`normalize_manufacturer`]）してから判定する。利用者が登録した別名と
+    /// 一致する場合はそのプロバイダーを、それ以外は組み込みの別名で判定したプロバイダーを返す。
+    /// 通信の記録・再生の設定がモックの場合は、メーカーによらずモックプロバイダーを返す。
     pub fn get_provider(&self, manufacturer: &str) -> Option<Arc<dyn ManufacturerProvider>> {
+        if cassette::mode() == CassetteMode::Mock {
+            return self.get_provider_by_id(mock::ID);
+        }
+        let name = normalize_manufacturer(manufacturer);
+        if let Some(id) = self.aliases.get(&name) {
+            if let Some(provider) = self
+                .get_provider_by_id(id)
+                .filter(|p| !self.disabled.contains(p.id()))
+            {
+                return Some(provider);
+            }
+        }
         self.providers
             .iter()
-            .find(|p| p.can_handle(manufacturer))
+            .filter(|p| !self.disabled.contains(p.id()))
+            .find(|p| p.can_handle(&name) || p.can_handle(manufacturer))
             .cloned()
     }
 
-    /// 対応メーカー名一覧を取得
+    /// 行のプロバイダーを取得（プロバイダーIDの指定があればメーカー名より優先する）
+    ///
+    /// 指定したプロバイダーが無効・未登録の場合は `None`（メーカー名では探さない）。
+    /// メーカー欄に複数のメーカーが記載されている場合は最初に対応するもの。
+    pub fn get_provider_for(
+        &self,
+        manufacturer: &str,
+        provider_id: Option<&str>,
+    ) -> Option<Arc<dyn ManufacturerProvider>> {
+        match provider_id {
+            Some(_) if cassette::mode() == CassetteMode::Mock => self.get_provider_by_id(mock::ID),
+            Some(id) => self
+                .get_provider_by_id(id)
+                .filter(|p| !self.disabled.contains(p.id())),
+            None => self
+                .get_providers_for(manufacturer, None)
+                .into_iter()
+                .next(),
+        }
+    }
+
+    /// 行のプロバイダーを試す順に取得
+    ///
+    /// メーカー欄に複数のメーカーが記載されている場合（"コイズミ or トキスター" 等）は
+    /// 記載順に並べる。プロバイダーIDの指定がある場合はそのプロバイダーのみ。
+    pub fn get_providers_for(
+        &self,
+        manufacturer: &str,
+        provider_id: Option<&str>,
+    ) -> Vec<Arc<dyn ManufacturerProvider>> {
+        if provider_id.is_some() {
+            return self
+                .get_provider_for(manufacturer, provider_id)
+                .into_iter()
+                .collect();
+        }
+        let mut providers: Vec<Arc<dyn ManufacturerProvider>> = Vec::new();
+        for name in split_manufacturers(manufacturer) {
+            if let Some(provider) = self.get_provider(&name) {
+                if !providers.iter().any(|p| p.id() == provider.id()) {
+                    providers.push(provider);
+                }
+            }
+        }
+        if providers.is_empty() {
+            providers.extend(self.get_provider(manufacturer));
+        }
+        providers
+    }
+
+    /// 対応メーカー名一覧を取得（無効なプロバイダーは除く）
     pub fn get_supported_manufacturers(&self) -> Vec<String> {
         self.providers
             .iter()
+            .filter(|p| !self.disabled.contains(p.id()))
             .map(|p| p.display_name().to_string())
             .collect()
     }
+
+    /// IDからプロバイダーを取得（無効なプロバイダーを含む）
+    pub fn get_provider_by_id(&self, id: &str) -> Option<Arc<dyn ManufacturerProvider>> {
+        self.providers.iter().find(|p| p.id() == id).cloned()
+    }
+
+    /// 登録済みプロバイダーのメタデータ一覧を取得（無効なプロバイダーを含む）
+    pub fn list_providers(&self) -> Vec<ProviderInfo> {
+        self.providers
+            .iter()
+            .map(|p| ProviderInfo {
+                id: p.id().to_string(),
+                display_name: p.display_name().to_string(),
+                aliases: p.aliases().iter().map(|a| a.to_string()).collect(),
+                base_url: p.base_url().to_string(),
+                supported_assets: p.supported_assets(),
+                supports_search: p.supports_search(),
+                supports_pricing: p.supports_pricing(),
+                enabled: !self.disabled.contains(p.id()),
+                custom: self.custom.iter().any(|c| c.id() == p.id()),
+            })
+            .collect()
+    }
+
+    /// 全プロバイダーに一括ダウンロードの開始を通知
+    pub fn begin_batch(&self) {
+        for provider in &self.providers {
+            provider.begin_batch();
+        }
+    }
+
+    /// 全プロバイダーに一括ダウンロードの終了を通知
+    pub fn end_batch(&self) {
+        for provider in &self.providers {
+            provider.end_batch();
+        }
+    }
+
+    /// 接続設定を反映したプロバイダーに差し替え（有効・無効の状態は引き継ぐ）
+    ///
+    /// 定義ファイルから作成したプロバイダーは、プロキシ・TLSの設定を反映するためにHTTPクライアントを作り直す。
+    pub fn configure(&mut self, configs: &BTreeMap<String, ProviderConfig>) {
+        self.custom = self
+            .custom
+            .iter()
+            .map(|provider| Arc::new(provider.reconnect()))
+            .collect();
+        let custom = self
+            .custom
+            .iter()
+            .map(|provider| provider.clone() as Arc<dyn ManufacturerProvider>);
+        for provider in configured_providers(configs).into_iter().chain(custom) {
+            if let Some(slot) = self.providers.iter_mut().find(|p| p.id() == provider.id()) {
+                *slot = provider;
+            }
+        }
+    }
+
+    /// 定義ファイルから作成したプロバイダーを差し替え（以前のものは登録を解除する）
+    ///
+    /// 組み込みのプロバイダーの後に登録するため、メーカー名の判定では組み込みのプロバイダーが優先される。
+    pub fn set_custom_providers(&mut self, providers: Vec<GenericProvider>) {
+        let previous: HashSet<String> = self.custom.iter().map(|p| p.id().to_string()).collect();
+        self.providers.retain(|p| !previous.contains(p.id()));
+        self.custom = providers.into_iter().map(Arc::new).collect();
+        for provider in &self.custom {
+            self.providers.push(provider.clone());
+        }
+    }
+
+    /// 組み込みのプロバイダーのID（定義ファイルのプロバイダーに使用できない）
+    fn builtin_ids(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|p| p.id())
+            .filter(|id| !self.custom.iter().any(|c| c.id() == *id))
+            .collect()
+    }
+
+    /// 利用者が登録したメーカー名の別名（別名 → プロバイダーID）を差し替え
+    pub fn set_aliases<'a>(&mut self, aliases: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        self.aliases = aliases
+            .into_iter()
+            .map(|(alias, id)| (normalize_manufacturer(alias), id.to_string()))
+            .filter(|(alias, _)| !alias.is_empty())
+            .collect();
+    }
+
+    /// プロバイダーの有効・無効を切り替え
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> Result<(), String> {
+        if !self.providers.iter().any(|p| p.id() == id) {
+            return Err(format!("Unknown provider: {}", id));
+        }
+        if enabled {
+            self.disabled.remove(id);
+        } else {
+            self.disabled.insert(id.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 接続設定を反映した組み込みのプロバイダー（[`CONFIGURABLE_PROVIDERS`] の順）
+fn configured_providers(
+    configs: &BTreeMap<String, ProviderConfig>,
+) -> Vec<Arc<dyn ManufacturerProvider>> {
+    let config = |id: &str| configs.get(id).cloned().unwrap_or_default();
+    vec![
+        Arc::new(koizumi::KoizumiProvider::with_config(&config("koizumi"))),
+        Arc::new(tokistar::TokistarProvider::with_config(&config("tokistar"))),
+        Arc::new(panasonic::PanasonicProvider::with_config(&config(
+            "panasonic",
+        ))),
+        Arc::new(daiko::DaikoProvider::with_config(&config("daiko"))),
+        Arc::new(endo::EndoProvider::with_config(&config("endo"))),
+        Arc::new(odelic::OdelicProvider::with_config(&config("odelic"))),
+    ]
+}
+
+/// プロバイダーの接続設定を反映（起動時・設定の保存時）
+///
+/// プロバイダーを作り直してレジストリを差し替える。実行中の一括ダウンロードは開始時の
+/// プロバイダーのまま続ける。試行回数はプロバイダーのベースURLのホストへの送信に適用する。
+pub fn apply_provider_config(
+    registry: &SharedRegistry,
+    configs: &BTreeMap<String, ProviderConfig>,
+) -> Result<(), String> {
+    registry.update(|registry| {
+        registry.configure(configs);
+        Ok(())
+    })?;
+    let registry = registry.load();
+    let attempts = configs
+        .iter()
+        .filter_map(|(id, config)| {
+            let provider = registry.get_provider_by_id(id)?;
+            let host = reqwest::Url::parse(provider.base_url())
+                .ok()?
+                .host_str()?
+                .to_lowercase();
+            Some((host, config.max_attempts?))
+        })
+        .collect();
+    *PROVIDER_ATTEMPTS.write().unwrap_or_else(|e| e.into_inner()) = attempts;
+    Ok(())
+}
+
+/// 通信の記録・再生のモードを反映（起動時・設定の保存時）
+///
+/// モックの場合のみモックプロバイダーを登録する（[`cassette::apply`] の後に呼び出す）。
+pub fn apply_cassette_mode(registry: &SharedRegistry) -> Result<(), String> {
+    registry.update(|registry| {
+        registry.set_mock_enabled(cassette::mode() == CassetteMode::Mock);
+        Ok(())
+    })
+}
+
+/// 定義ファイルの読み込み結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomProvidersResult {
+    /// 定義ファイルのディレクトリ
+    pub dir: String,
+    /// 登録したプロバイダーのID
+    pub loaded: Vec<String>,
+    /// 読み込めなかった定義ファイル
+    pub errors: Vec<DefinitionError>,
+}
+
+/// ディレクトリの定義ファイルとプロバイダールールのバンドルからプロバイダーを作成して登録
+/// （起動時・再読み込み時・ルールの更新時）
+///
+/// 以前に定義ファイル・バンドルから登録したプロバイダーは置き換える。バンドルの定義は
+/// 同じIDの定義ファイルがない場合に登録する。読み込めなかった定義は登録せずに結果の
+/// `errors` で返す。実行中の一括ダウンロードは開始時のプロバイダーのまま続ける。
+pub fn apply_custom_providers(
+    registry: &SharedRegistry,
+    dir: &Path,
+    rules: Option<&Path>,
+) -> Result<CustomProvidersResult, String> {
+    registry.update(|registry| {
+        let builtin = registry.builtin_ids();
+        let (mut providers, mut errors) = generic::load_dir(dir, &builtin);
+        if let Some(rules) = rules {
+            let taken: Vec<&str> = builtin
+                .iter()
+                .copied()
+                .chain(providers.iter().map(|p| p.id()))
+                .collect();
+            let (bundled, bundle_errors) = generic::load_bundle(rules, &taken);
+            providers.extend(bundled);
+            errors.extend(bundle_errors);
+        }
+        let loaded = providers.iter().map(|p| p.id().to_string()).collect();
+        registry.set_custom_providers(providers);
+        Ok(CustomProvidersResult {
+            dir: dir.to_string_lossy().into_owned(),
+            loaded,
+            errors,
+        })
+    })
+}
+
+/// 実行中に共有するプロバイダーレジストリ（Tauriの管理状態として保持する）
+///
+/// 参照時はロックを取らずにその時点のレジストリを取得するため、一括ダウンロード等の
+/// 長い処理と他のコマンドが互いを待たない。変更時は複製したレジストリを差し替える。
+pub struct SharedRegistry {
+    current: ArcSwap<ProviderRegistry>,
+    /// 変更同士が競合して一方の変更が失われないようにするためのロック（参照時は使用しない）
+    writer: Mutex<()>,
+}
+
+impl SharedRegistry {
+    pub fn new(registry: ProviderRegistry) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(registry),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// 現在のレジストリを取得（取得後の変更は反映されない）
+    pub fn load(&self) -> Arc<ProviderRegistry> {
+        self.current.load_full()
+    }
+
+    /// レジストリを変更して差し替え（`f` がエラーを返した場合は差し替えない）
+    pub fn update<T>(
+        &self,
+        f: impl FnOnce(&mut ProviderRegistry) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _writer = self
+            .writer
+            .lock()
+            .map_err(|_| "Provider registry is poisoned".to_string())?;
+        let mut next = ProviderRegistry::clone(&self.current.load());
+        let value = f(&mut next)?;
+        self.current.store(Arc::new(next));
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_length() {
+        assert!(verify_length(Some(10), 10).is_ok());
+        assert!(verify_length(None, 10).is_ok());
+        let err = verify_length(Some(10), 4).unwrap_err();
+        assert_eq!(err.code, ErrorCode::IncompleteDownload);
+        assert_eq!(err.message, "Incomplete download: received 4 of 10 bytes");
+        assert!(verify_length(None, 0).is_err());
+        assert!(verify_length(Some(0), 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_incomplete() {
+        let mut calls = 0;
+        let result = retry_incomplete(|| {
+            calls += 1;
+            async { verify_length(Some(10), 4) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 2);
+
+        let mut calls = 0;
+        let result: ProviderResult<()> = retry_incomplete(|| {
+            calls += 1;
+            async {
+                Err(status_error(
+                    reqwest::StatusCode::NOT_FOUND,
+                    "Download failed with status: 404".to_string(),
+                ))
+            }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+    use futures::executor::block_on;
+    use futures::FutureExt;
+
+    #[test]
+    fn test_cancel_token() {
+        let cancel = CancelToken::new();
+        assert_eq!(
+            block_on(cancel.run(async { Ok::<_, ProviderError>(1) })),
+            Ok(1)
+        );
+
+        // 実行中に中断が指示されたら、完了を待たずにエラーを返す
+        let cloned = cancel.clone();
+        let result = block_on(cancel.run(async move {
+            cloned.cancel();
+            futures::future::pending::<ProviderResult<i32>>().await
+        }));
+        assert_eq!(result, Err(cancelled()));
+        assert_eq!(cancel.check(), Err(cancelled()));
+        assert_eq!(cancelled().code, ErrorCode::Cancelled);
+    }
+
+    #[test]
+    fn test_request_decision() {
+        let request = DecisionRequest {
+            kind: DecisionKind::IesFile,
+            candidates: ["a.ies", "b.ies"]
+                .iter()
+                .map(|name| DecisionCandidate {
+                    value: name.to_string(),
+                    label: name.to_string(),
+                })
+                .collect(),
+            selected: Some("a.ies".to_string()),
+        };
+        let answer = |choice: &'static str| -> Option<DecisionResolver> {
+            Some(Arc::new(move |_| {
+                async move { Some(choice.to_string()) }.boxed()
+            }))
+        };
+
+        // 依頼先がなければ判断を求めない
+        assert!(!can_request_decision());
+        assert_eq!(block_on(request_decision(request.clone())), None);
+        assert_eq!(
+            block_on(with_decision_resolver(
+                answer("b.ies"),
+                request_decision(request.clone())
+            )),
+            Some("b.ies".to_string())
+        );
+        // 候補にない値は選ばれなかったものとする
+        assert_eq!(
+            block_on(with_decision_resolver(
+                answer("c.ies"),
+                request_decision(request)
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(parse_price("¥12,800"), Some(Price::jpy(12800)));
+        assert_eq!(
+            parse_price("￥ 3,200（税抜）"),
+            Some(Price::jpy(3200).assume_tax_included(false))
+        );
+        assert_eq!(
+            parse_price("定価 9,500円（税込）"),
+            Some(Price::jpy(9500).assume_tax_included(true))
+        );
+        assert_eq!(parse_price("オープン価格"), None);
+
+        let usd = parse_price("$1,250.5 excl. tax").unwrap();
+        assert_eq!(usd.currency, Currency::Usd);
+        assert_eq!(usd.minor_units, 125050);
+        assert_eq!(usd.tax_included, Some(false));
+        assert_eq!(usd.amount(), "1250.50");
+        assert_eq!(parse_price("EUR 99").unwrap().minor_units, 9900);
+        assert_eq!(parse_price("$1.005"), None);
+    }
+
+    #[test]
+    fn test_price_deserialize() {
+        // 以前の形式（円の整数）も読み込める
+        let price: Price = serde_json::from_str("12800").unwrap();
+        assert_eq!(price, Price::jpy(12800));
+
+        let price = Price {
+            minor_units: 1999,
+            currency: Currency::Usd,
+            tax_included: Some(true),
+        };
+        let json = serde_json::to_string(&price).unwrap();
+        assert_eq!(
+            json,
+            r#"{"minorUnits":1999,"currency":"USD","taxIncluded":true}"#
+        );
+        assert_eq!(serde_json::from_str::<Price>(&json).unwrap(), price);
+    }
+
+    #[test]
+    fn test_price_from_candidates() {
+        let candidate = |model_number: &str, price: Option<u64>| ProductCandidate {
+            model_number: model_number.to_string(),
+            product_name: None,
+            price: price.map(Price::jpy),
+            product_page_url: None,
+        };
+        let candidates = vec![
+            candidate("OSP", Some(10000)),
+            candidate("OSP01", Some(28000)),
+            candidate("AD12345", None),
+        ];
+
+        assert_eq!(
+            price_from_candidates(&candidates, "osp01"),
+            Some(Price::jpy(28000))
+        );
+        assert_eq!(
+            price_from_candidates(&candidates, "OSP01-30K-30D"),
+            Some(Price::jpy(28000))
+        );
+        assert_eq!(price_from_candidates(&candidates, "AD12345"), None);
+        assert_eq!(price_from_candidates(&candidates, "MRD01"), None);
+    }
+
+    #[test]
+    fn test_closest_candidates() {
+        let candidate = |model_number: &str, product_name: Option<&str>| ProductCandidate {
+            model_number: model_number.to_string(),
+            product_name: product_name.map(str::to_string),
+            price: None,
+            product_page_url: None,
+        };
+        let candidates = vec![
+            candidate("AH92025L", None),
+            candidate("AD12346", Some("LEDダウンライト")),
+            candidate("AD1234", None),
+        ];
+
+        let closest = closest_candidates(candidates, "ad-12345", 2);
+        assert_eq!(
+            describe_candidates(&closest),
+            "AD1234, AD12346 (LEDダウンライト)"
+        );
+    }
+
+    #[test]
+    fn test_batch_cache_overlapping_batches() {
+        let cache = BatchCache::<u32>::default();
+        // 一括ダウンロード中以外は保持しない
+        cache.insert("a".to_string(), 1);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get_or_insert_with("a", || 1), None);
+
+        // バッチAの実行中にバッチBが始まり、Aが先に終わってもBのキャッシュは残る
+        cache.begin();
+        cache.insert("a".to_string(), 1);
+        cache.begin();
+        assert_eq!(cache.get_or_insert_with("b", || 2), Some(2));
+        cache.end();
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get_or_insert_with("b", || 3), Some(2));
+
+        // 最後のバッチが終わったら破棄する
+        cache.end();
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), None);
+        // 対応しない終了の通知で数がずれない
+        cache.end();
+        cache.begin();
+        cache.insert("c".to_string(), 3);
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn test_domain_limit() {
+        apply_domain_concurrency(&BTreeMap::from([
+            ("Toki.co.jp".to_string(), 1),
+            ("webcatalog.koizumi-lt.co.jp".to_string(), 2),
+        ]));
+
+        let limit = domain_limit("toki.co.jp");
+        assert_eq!(limit.available_permits(), 1);
+        // サブドメインにも適用する
+        assert!(Arc::ptr_eq(&domain_limit("www.toki.co.jp"), &limit));
+        assert_eq!(
+            domain_limit("webcatalog.koizumi-lt.co.jp").available_permits(),
+            2
+        );
+        // 設定がないホストはホストごとに既定の上限で制限する
+        let unlisted = domain_limit("notoki.co.jp");
+        assert_eq!(unlisted.available_permits(), DEFAULT_DOMAIN_CONCURRENCY);
+        assert!(Arc::ptr_eq(&domain_limit("NoToki.co.jp"), &unlisted));
+        assert!(!Arc::ptr_eq(&domain_limit("koizumi-lt.co.jp"), &unlisted));
+    }
+
+    #[test]
+    fn test_retry_after_secs() {
+        use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+        let retry = RetrySettings::default();
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after_secs(&headers, &retry), DEFAULT_RETRY_WAIT_SECS);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_after_secs(&headers, &retry), 30);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(retry_after_secs(&headers, &retry), retry.max_wait_secs);
+
+        // HTTP日付形式は未対応のため既定値
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2026 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after_secs(&headers, &retry), DEFAULT_RETRY_WAIT_SECS);
+    }
+
+    #[test]
+    fn test_retry_settings() {
+        let retry = RetrySettings::default();
+        assert!(retry.validate().is_ok());
+        assert_eq!(retry.error_wait_secs(1), 2);
+        assert_eq!(retry.error_wait_secs(2), 4);
+        assert_eq!(retry.error_wait_secs(10), 60);
+
+        let retry = RetrySettings {
+            base_wait_secs: 0,
+            ..Default::default()
+        };
+        assert!(retry.validate().is_err());
+        let retry = RetrySettings {
+            max_attempts: 0,
+            ..Default::default()
+        };
+        assert!(retry.validate().is_err());
+        let retry = RetrySettings {
+            base_wait_secs: 30,
+            max_wait_secs: 10,
+            ..Default::default()
+        };
+        assert!(retry.validate().is_err());
+    }
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension("image/jpeg"), Some("jpg"));
+        assert_eq!(image_extension("Image/PNG; charset=binary"), Some("png"));
+        assert_eq!(image_extension("image/webp"), Some("webp"));
+        assert_eq!(image_extension("application/octet-stream"), None);
+        assert_eq!(image_extension(""), None);
+    }
+
+    #[test]
+    fn test_url_encode() {
+        assert_eq!(url_encode("XD-123_a.b~c"), "XD-123_a.b~c");
+        assert_eq!(url_encode("a b;c@d"), "a%20b%3Bc%40d");
+        assert_eq!(url_encode("XD 123/45"), "XD%20123%2F45");
+    }
+
+    #[test]
+    fn test_network_config() {
+        let proxy = ProxySettings {
+            url: "http://proxy.example.com:8080".to_string(),
+            username: Some("user".to_string()),
+            password: Some("secret".to_string()),
+            no_proxy: Some("localhost,.intra.example.com".to_string()),
+        };
+        let config = NetworkConfig::new(Some(&proxy), &TlsSettings::default()).unwrap();
+        assert!(config.proxy.is_some());
+        assert!(config.apply(reqwest::Client::builder()).build().is_ok());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("root.pem");
+        std::fs::write(&path, "-----BEGIN CERTIFICATE-----\nnot a certificate\n").unwrap();
+        let tls = |path: &std::path::Path| TlsSettings {
+            root_certificate_path: Some(path.to_string_lossy().into_owned()),
+            accept_invalid_certs: false,
+        };
+        assert!(NetworkConfig::new(None, &tls(&path)).is_err());
+        assert!(NetworkConfig::new(None, &tls(&dir.path().join("missing.pem"))).is_err());
+    }
+
+    #[test]
+    fn test_provider_config() {
+        assert!(ProviderConfig::default().validate("koizumi").is_ok());
+        let config = ProviderConfig {
+            base_url: Some("https://catalog.example.com/lighting/".to_string()),
+            user_agent: Some("AutoSight/1.0".to_string()),
+            timeout_secs: Some(20),
+            max_attempts: Some(5),
+        };
+        assert!(config.validate("koizumi").is_ok());
+        assert_eq!(
+            config.base_url("https://webcatalog.koizumi-lt.co.jp"),
+            "https://catalog.example.com/lighting"
+        );
+        assert_eq!(
+            ProviderConfig::default().base_url("https://webcatalog.koizumi-lt.co.jp"),
+            "https://webcatalog.koizumi-lt.co.jp"
+        );
+
+        let invalid = [
+            ProviderConfig {
+                base_url: Some("ftp://catalog.example.com".to_string()),
+                ..Default::default()
+            },
+            ProviderConfig {
+                user_agent: Some("AutoSight\r\nX-Test: 1".to_string()),
+                ..Default::default()
+            },
+            ProviderConfig {
+                timeout_secs: Some(0),
+                ..Default::default()
+            },
+            ProviderConfig {
+                max_attempts: Some(11),
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate("koizumi").is_err(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_apply_request_timeout() {
+        apply_request_timeout(45);
+        assert_eq!(default_timeout(), Some(Duration::from_secs(45)));
+        apply_request_timeout(30);
+        assert_eq!(default_timeout(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_apply_provider_config() {
+        let shared = SharedRegistry::new(ProviderRegistry::new());
+        shared
+            .update(|registry| registry.set_enabled("koizumi", false))
+            .unwrap();
+
+        let configs = BTreeMap::from([(
+            "tokistar".to_string(),
+            ProviderConfig {
+                base_url: Some("https://www.toki.co.jp/tokistar/".to_string()),
+                max_attempts: Some(1),
+                ..Default::default()
+            },
+        )]);
+        apply_provider_config(&shared, &configs).unwrap();
+
+        let providers = shared.load().list_providers();
+        let provider = |id: &str| providers.iter().find(|p| p.id == id).unwrap();
+        assert_eq!(
+            provider("tokistar").base_url,
+            "https://www.toki.co.jp/tokistar"
+        );
+        assert_eq!(
+            provider("koizumi").base_url,
+            "https://webcatalog.koizumi-lt.co.jp"
+        );
+        // 有効・無効の状態は引き継ぐ
+        assert!(!provider("koizumi").enabled);
+        assert_eq!(retry_settings("www.toki.co.jp").max_attempts, 1);
+        assert_eq!(
+            retry_settings("webcatalog.koizumi-lt.co.jp").max_attempts,
+            RetrySettings::default().max_attempts
+        );
+    }
+
+    #[test]
+    fn test_domain_interval() {
+        apply_domain_request_interval(&BTreeMap::from([
+            ("Daiko-denki.co.jp".to_string(), 500),
+            ("toki.co.jp".to_string(), 0),
+        ]));
+
+        let interval = domain_interval("lighting.daiko-denki.co.jp").unwrap();
+        assert_eq!(interval.0, Duration::from_millis(500));
+        // 0 は間隔を空けない
+        assert!(domain_interval("toki.co.jp").is_none());
+        assert!(domain_interval("www2.panasonic.biz").is_none());
+    }
+
+    #[test]
+    fn test_set_mock_enabled() {
+        let mut registry = ProviderRegistry::new();
+        assert!(registry.get_provider_by_id(mock::ID).is_none());
+
+        registry.set_mock_enabled(true);
+        registry.set_mock_enabled(true);
+        assert_eq!(
+            registry.list_providers().len(),
+            CONFIGURABLE_PROVIDERS.len() + 1
+        );
+        assert!(registry
+            .get_provider("MOCK")
+            .is_some_and(|p| p.id() == mock::ID));
+
+        registry.set_mock_enabled(false);
+        assert!(registry.get_provider_by_id(mock::ID).is_none());
+    }
+
+    #[test]
+    fn test_set_enabled() {
+        let mut registry = ProviderRegistry::new();
+        assert!(registry.get_provider("コイズミ照明").is_some());
+
+        registry.set_enabled("koizumi", false).unwrap();
+        assert!(registry.get_provider("コイズミ照明").is_none());
+        assert!(!registry
+            .get_supported_manufacturers()
+            .contains(&"コイズミ照明".to_string()));
+        let koizumi = registry
+            .list_providers()
+            .into_iter()
+            .find(|p| p.id == "koizumi")
+            .unwrap();
+        assert!(!koizumi.enabled);
+
+        registry.set_enabled("koizumi", true).unwrap();
+        assert!(registry.get_provider("コイズミ照明").is_some());
+        assert!(registry.set_enabled("unknown", false).is_err());
+    }
+
+    #[test]
+    fn test_set_custom_providers() {
+        let definition = |id: &str, alias: &str| generic::ProviderDefinition {
+            id: id.to_string(),
+            display_name: alias.to_string(),
+            base_url: "https://www.example.co.jp".to_string(),
+            aliases: vec![alias.to_string()],
+            search_url: "/products/?q={model}".to_string(),
+            ies_link_selector: Some("a.ies".to_string()),
+            ies_link_pattern: None,
+            zip: false,
+            filename_template: None,
+            sample_model_number: None,
+        };
+        let custom =
+            |id: &str, alias: &str| GenericProvider::new(definition(id, alias), &[]).unwrap();
+
+        let mut registry = ProviderRegistry::new();
+        let builtin = registry.list_providers().len();
+        registry.set_custom_providers(vec![custom("example", "example照明")]);
+        assert!(registry
+            .get_provider("Example照明")
+            .is_some_and(|p| p.id() == "example"));
+        assert!(registry
+            .list_providers()
+            .iter()
+            .any(|p| p.id == "example" && p.custom));
+        assert!(!registry.builtin_ids().contains(&"example"));
+
+        // 以前のものは登録を解除する
+        registry.set_custom_providers(vec![custom("sample", "sample照明")]);
+        assert!(registry.get_provider("Example照明").is_none());
+        assert!(registry.get_provider("Sample照明").is_some());
+        registry.configure(&BTreeMap::new());
+        assert!(registry.get_provider("Sample照明").is_some());
+        assert_eq!(registry.list_providers().len(), builtin + 1);
+    }
+
+    #[test]
+    fn test_apply_custom_providers_rules() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("providers");
+        let definition = |alias: &str| {
+            serde_json::json!({
+                "id": "example",
+                "displayName": "Example照明",
+                "baseUrl": "https://www.example.co.jp",
+                "aliases": [alias],
+                "searchUrl": "/products/?q={model}",
+                "iesLinkSelector": "a.ies",
+            })
+        };
+        let write_bundle = |version: &str, alias: &str| {
+            let path = temp.path().join(format!("{}.json", version));
+            let bundle = serde_json::json!({ "providers": [definition(alias)] });
+            std::fs::write(&path, bundle.to_string()).unwrap();
+            path
+        };
+        let registry = SharedRegistry::new(ProviderRegistry::new());
+        let resolves = |name: &str| {
+            registry
+                .load()
+                .get_provider(name)
+                .is_some_and(|p| p.id() == "example")
+        };
+
+        // ルールのバージョンを切り替えると判定に使う別名が変わる
+        let v1 = write_bundle("2024.06.01", "旧社名");
+        let result = apply_custom_providers(&registry, &dir, Some(&v1)).unwrap();
+        assert_eq!(result.loaded, ["example"]);
+        assert!(resolves("旧社名"));
+        let v2 = write_bundle("2024.06.02", "新社名");
+        apply_custom_providers(&registry, &dir, Some(&v2)).unwrap();
+        assert!(!resolves("旧社名"));
+        assert!(resolves("新社名"));
+        // ロールバック（組み込みのルールのみ）
+        apply_custom_providers(&registry, &dir, None).unwrap();
+        assert!(!resolves("新社名"));
+
+        // 同じIDの定義ファイルを優先する
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("example.json"), definition("手元").to_string()).unwrap();
+        let result = apply_custom_providers(&registry, &dir, Some(&v2)).unwrap();
+        assert!(resolves("手元"));
+        assert!(!resolves("新社名"));
+        assert!(result.errors[0].file.ends_with("2024.06.02.json#example"));
+
+        let broken = temp.path().join("broken.json");
+        std::fs::write(&broken, "{ \"providers\": ").unwrap();
+        let result = apply_custom_providers(&registry, &dir, Some(&broken)).unwrap();
+        assert_eq!(result.loaded, ["example"]);
+        assert!(result.errors[0].error.starts_with("Invalid rules bundle"));
+    }
+
+    #[test]
+    fn test_split_manufacturers() {
+        assert_eq!(split_manufacturers("コイズミ or 同等品"), vec!["コイズミ"]);
+        assert_eq!(
+            split_manufacturers("トキスター／コイズミ照明"),
+            vec!["トキスター", "コイズミ照明"]
+        );
+        assert_eq!(
+            split_manufacturers("KOIZUMI OR Tokistar"),
+            vec!["KOIZUMI", "Tokistar"]
+        );
+        assert_eq!(split_manufacturers("Koizumi"), vec!["Koizumi"]);
+    }
+
+    #[test]
+    fn test_get_providers_for() {
+        let registry = ProviderRegistry::new();
+        let ids = |manufacturer: &str| -> Vec<String> {
+            registry
+                .get_providers_for(manufacturer, None)
+                .iter()
+                .map(|p| p.id().to_string())
+                .collect()
+        };
+        // 記載順に試す（対応していないメーカー・重複は除く）
+        assert_eq!(ids("トキスター or コイズミ"), vec!["tokistar", "koizumi"]);
+        assert_eq!(
+            ids("遠藤照明 / コイズミ / KOIZUMI"),
+            vec!["endo", "koizumi"]
+        );
+        assert_eq!(ids("大光電機 / コイズミ"), vec!["daiko", "koizumi"]);
+        assert_eq!(ids("コイズミ or 同等品"), vec!["koizumi"]);
+        assert_eq!(ids("パナソニック / コイズミ"), vec!["panasonic", "koizumi"]);
+        assert_eq!(ids("遠藤照明"), vec!["endo"]);
+        assert!(ids("山田照明").is_empty());
+        assert_eq!(
+            registry
+                .get_provider_for("トキスター or コイズミ", None)
+                .map(|p| p.id().to_string()),
+            Some("tokistar".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_manufacturer() {
+        assert_eq!(normalize_manufacturer(" ｺｲｽﾞﾐ照明株式会社 "), "コイズミ照明");
+        assert_eq!(normalize_manufacturer("(株)遠藤照明"), "遠藤照明");
+        assert_eq!(
+            normalize_manufacturer("KOIZUMI  LIGHTING TECHNOLOGY CORP."),
+            "koizumi lighting technology"
+        );
+        assert_eq!(normalize_manufacturer("Zinc"), "zinc");
+        assert_eq!(normalize_manufacturer("ＯＤＥＬＩＣ Co., Ltd."), "odelic");
+    }
+
+    #[test]
+    fn test_get_provider_normalized() {
+        let id = |registry: &ProviderRegistry, manufacturer: &str| {
+            registry
+                .get_provider(manufacturer)
+                .map(|p| p.id().to_string())
+        };
+        let mut registry = ProviderRegistry::new();
+        assert_eq!(id(&registry, "ｺｲｽﾞﾐ").as_deref(), Some("koizumi"));
+        assert_eq!(
+            id(&registry, "KOIZUMI LIGHTING TECH ").as_deref(),
+            Some("koizumi")
+        );
+        assert_eq!(id(&registry, "KL照明"), None);
+
+        // 登録した別名は正規化して照合する
+        registry.set_aliases([("ＫＬ照明", "koizumi")]);
+        assert_eq!(id(&registry, " kl照明 ").as_deref(), Some("koizumi"));
+        // 無効なプロバイダーの別名は使わない
+        registry.set_enabled("koizumi", false).unwrap();
+        assert_eq!(id(&registry, "KL照明"), None);
+    }
+
+    #[test]
+    fn test_get_provider_for() {
+        let mut registry = ProviderRegistry::new();
+        // プロバイダーの指定はメーカー名より優先する
+        assert!(registry
+            .get_provider_for("コイズミ照明", Some("tokistar"))
+            .is_some_and(|p| p.id() == "tokistar"));
+        assert!(registry
+            .get_provider_for("コイズミ照明", None)
+            .is_some_and(|p| p.id() == "koizumi"));
+        // 未登録・無効なプロバイダーの指定はメーカー名で探さない
+        assert!(registry
+            .get_provider_for("コイズミ照明", Some("unknown"))
+            .is_none());
+        registry.set_enabled("tokistar", false).unwrap();
+        assert!(registry
+            .get_provider_for("コイズミ照明", Some("tokistar"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_shared_registry_update() {
+        let shared = SharedRegistry::new(ProviderRegistry::new());
+        let before = shared.load();
+
+        shared
+            .update(|registry| registry.set_enabled("koizumi", false))
+            .unwrap();
+        assert!(shared.load().get_provider("コイズミ照明").is_none());
+        // 変更前に取得したレジストリは変わらない
+        assert!(before.get_provider("コイズミ照明").is_some());
+
+        // 失敗した変更は反映しない
+        assert!(shared
+            .update(|registry| registry.set_enabled("unknown", false))
+            .is_err());
+        assert_eq!(
+            shared.load().list_providers().len(),
+            CONFIGURABLE_PROVIDERS.len()
+        );
+    }
+
+    #[test]
+    fn test_accessory_kind_classify() {
+        assert_eq!(
+            AccessoryKind::classify("DALI調光電源"),
+            AccessoryKind::PowerSupply
+        );
+        assert_eq!(AccessoryKind::classify("取付枠"), AccessoryKind::Frame);
+        assert_eq!(AccessoryKind::classify("拡散レンズ"), AccessoryKind::Lens);
+        assert_eq!(AccessoryKind::classify("ワイヤー"), AccessoryKind::Other);
+    }
 }
@@ -0,0 +1,653 @@
+//! オーデリックプロバイダー
+//!
+//! オーデリック 住宅照明・施設照明カタログ (www.odelic.co.jp) からの
+//! 製品情報・IESファイル取得を担当する。
+//! 配光データは品番のページではなくシリーズのページにしか掲載されていないことが多いため、
+//! 品番のページ → 親シリーズのページの順に探す。シリーズのページに品番のIESファイルがなければ
+//! シリーズの代表のIESファイルで代用し、近似であることを `ProductInfo::match_quality` と
+//! ダウンロード結果の警告で知らせる（PSUは使用しない）。
+
+use super::html::{
+    self, ODELIC_IES_LINK, ODELIC_ITEM_LINK, ODELIC_PRODUCT_IMAGE, ODELIC_SERIES_LINK,
+    ODELIC_SPEC_LABEL,
+};
+use super::{
+    check_url, download_product_image, fetch_content_length, filename_from_content_disposition,
+    not_available, not_provided, page_mentions, parse_price, price_from_candidates,
+    provider_outdated, report_phase, retry_incomplete, send_request, status_error, verify_length,
+    verify_written, AssetType, CancelToken, Diagnosis, DownloadPhase, DownloadResult,
+    DownloadSource, DownloadWarning, DownloadWarningKind, ManufacturerProvider, MatchQuality,
+    Price, ProductCandidate, ProductInfo, ProviderConfig, ResolvedIesUrl,
+};
+use crate::buffer;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::path::Path;
+use std::time::Instant;
+
+/// オーデリックのWebカタログの既定のベースURL（設定の `providers.odelic.baseUrl` で変更できる）
+const BASE_URL: &str = "https://www.odelic.co.jp";
+
+/// オーデリックプロバイダー
+pub struct OdelicProvider {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+/// 製品ページ・シリーズページで見つけたIESファイル
+#[derive(Debug, Clone, PartialEq)]
+struct IesLookup {
+    url: String,
+    quality: MatchQuality,
+    /// シリーズのページで見つけた場合のページのURL
+    series_url: Option<String>,
+}
+
+impl OdelicProvider {
+    pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
+        Self {
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+        }
+    }
+
+    /// 器具リストの型番をカタログの品番に正規化
+    /// 全角英数字・記号を半角にし、空白を除いて大文字にする
+    /// 例: "od 361 234ld" → "OD361234LD"
+    fn normalize_hinban(model_number: &str) -> String {
+        model_number
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                _ => c,
+            })
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+
+    /// ファイル名（パス・クエリ・拡張子を除く）を品番と比較できる形にする
+    fn file_stem(url: &str) -> String {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let stem = Path::new(name)
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or(name);
+        Self::normalize_hinban(stem)
+    }
+
+    /// 品番ページのURL
+    fn detail_url(&self, hinban: &str) -> String {
+        format!("{}/products/item/?hinban={}", self.base_url, hinban)
+    }
+
+    /// 相対URLを絶対URLにする
+    fn absolute_url(&self, url: &str) -> Option<String> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Some(url.to_string())
+        } else if let Some(rest) = url.strip_prefix("//") {
+            Some(format!("https://{}", rest))
+        } else if url.starts_with('/') {
+            Some(format!("{}{}", self.base_url, url))
+        } else {
+            None
+        }
+    }
+
+    /// ページのHTMLを取得（`not_found` は 404 の場合のエラーメッセージ）
+    async fn fetch_page(&self, url: &str, not_found: &str) -> ProviderResult<String> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Detail request failed"))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(status_error(
+                response.status(),
+                format!("{} returned status: {}", not_found, response.status()),
+            ));
+        }
+        response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))
+    }
+
+    /// 品番ページのHTMLを取得（掲載のない品番は 404 になる）
+    async fn fetch_detail_page(&self, hinban: &str) -> ProviderResult<String> {
+        self.fetch_page(
+            &self.detail_url(hinban),
+            &format!("Detail page for {}", hinban),
+        )
+        .await
+    }
+
+    /// ページのHTMLから配光データ（.ies）のURLをすべて抽出（重複排除）
+    fn extract_ies_urls(&self, html: &str) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for link in html::links(html, &ODELIC_IES_LINK) {
+            let path = link.href.split(['?', '#']).next().unwrap_or_default();
+            if !path.to_lowercase().ends_with(".ies") {
+                continue;
+            }
+            let Some(url) = self.absolute_url(&link.href) else {
+                continue;
+            };
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 品番ページのHTMLから親シリーズのページのURLを抽出
+    fn extract_series_url(&self, html: &str) -> Option<String> {
+        html::links(html, &ODELIC_SERIES_LINK)
+            .into_iter()
+            .find_map(|link| self.absolute_url(&link.href))
+    }
+
+    /// ファイル名が品番と一致するIESファイル
+    fn exact_ies_url(urls: &[String], hinban: &str) -> Option<String> {
+        urls.iter()
+            .find(|url| Self::file_stem(url) == hinban)
+            .cloned()
+    }
+
+    /// シリーズの代表とするIESファイル（ファイル名が品番と最も長く前方一致するもの。
+    /// 同じ場合は先に掲載されたもの）
+    fn series_ies_url(urls: &[String], hinban: &str) -> Option<String> {
+        let prefix = |url: &String| {
+            Self::file_stem(url)
+                .chars()
+                .zip(hinban.chars())
+                .take_while(|(a, b)| a == b)
+                .count()
+        };
+        urls.iter().rev().max_by_key(|url| prefix(url)).cloned()
+    }
+
+    /// 品番ページ → 親シリーズのページの順にIESファイルを探す（見つからない場合は None）
+    ///
+    /// 品番ページに掲載されているIESファイルは品番のものとする。シリーズのページでは、
+    /// ファイル名が品番と一致するものがあれば品番のもの、なければシリーズの代表のものとする。
+    async fn lookup_ies(&self, hinban: &str, html: &str) -> ProviderResult<Option<IesLookup>> {
+        let urls = self.extract_ies_urls(html);
+        if let Some(url) = Self::exact_ies_url(&urls, hinban).or_else(|| urls.first().cloned()) {
+            return Ok(Some(IesLookup {
+                url,
+                quality: MatchQuality::Exact,
+                series_url: None,
+            }));
+        }
+
+        let Some(series_url) = self.extract_series_url(html) else {
+            return Ok(None);
+        };
+        let series_html = self
+            .fetch_page(&series_url, &format!("Series page for {}", hinban))
+            .await?;
+        let urls = self.extract_ies_urls(&series_html);
+        let found = match Self::exact_ies_url(&urls, hinban) {
+            Some(url) => Some((url, MatchQuality::Exact)),
+            None => Self::series_ies_url(&urls, hinban).map(|url| (url, MatchQuality::Series)),
+        };
+        Ok(found.map(|(url, quality)| IesLookup {
+            url,
+            quality,
+            series_url: Some(series_url),
+        }))
+    }
+
+    /// 品番のIESファイルを探す（見つからない場合はエラー）
+    async fn find_ies(&self, hinban: &str) -> ProviderResult<IesLookup> {
+        let html = self.fetch_detail_page(hinban).await?;
+        if let Some(found) = self.lookup_ies(hinban, &html).await? {
+            return Ok(found);
+        }
+        if Self::is_unrecognized_detail_page(&html, hinban) {
+            return Err(provider_outdated(
+                self.id(),
+                "IES link, series link or spec table",
+                hinban,
+                &html,
+            ));
+        }
+        Err(not_available(AssetType::Ies, hinban))
+    }
+
+    /// 品番が掲載されているのに、配光データ・シリーズへのリンクも仕様表も見つからない品番ページか
+    /// （サイトの構成が変わり、セレクターが一致しなくなった可能性が高い）
+    fn is_unrecognized_detail_page(html: &str, hinban: &str) -> bool {
+        html::links(html, &ODELIC_IES_LINK).is_empty()
+            && html::links(html, &ODELIC_SERIES_LINK).is_empty()
+            && Self::extract_product_name(html).is_none()
+            && page_mentions(html, hinban)
+    }
+
+    /// 品番ページのHTMLから品名を抽出
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &ODELIC_SPEC_LABEL, &["品名", "商品名"])
+    }
+
+    /// オーデリックの定価は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 品番ページのHTMLから定価を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(html, &ODELIC_SPEC_LABEL, &["定価", "価格"])
+            .as_deref()
+            .and_then(Self::parse_list_price)
+    }
+
+    /// 品番ページのHTMLから製品画像のURLを抽出（相対URLは絶対URLにする）
+    fn extract_image_url(&self, html: &str) -> Option<String> {
+        self.absolute_url(&html::image_url(html, &ODELIC_PRODUCT_IMAGE)?)
+    }
+
+    /// HTMLから品番ページへのリンクを抽出
+    /// 戻り値: (品番, リンクテキスト) の一覧（品番で重複排除）
+    fn extract_item_links(html: &str) -> Vec<(String, Option<String>)> {
+        let mut links: Vec<(String, Option<String>)> = Vec::new();
+        for link in html::links(html, &ODELIC_ITEM_LINK) {
+            let Some((_, rest)) = link.href.split_once("hinban=") else {
+                continue;
+            };
+            let hinban: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if hinban.is_empty() || links.iter().any(|(h, _)| *h == hinban) {
+                continue;
+            }
+            let text = Some(link.text).filter(|text| !text.is_empty() && *text != hinban);
+            links.push((hinban, text));
+        }
+        links
+    }
+
+    /// IESファイルをダウンロードして保存（受信が不完全な場合は1回だけやり直す）
+    async fn download_file(&self, url: &str, dest_path: &str) -> ProviderResult<DownloadResult> {
+        retry_incomplete(|| self.download_file_once(url, dest_path)).await
+    }
+
+    async fn download_file_once(
+        &self,
+        url: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+
+        if !response.status().is_success() {
+            return Ok(DownloadResult::http_failure(response.status()));
+        }
+        let source = DownloadSource::from_response(&response);
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+        // Content-Dispositionヘッダーから元のファイル名を取得
+        let original_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|h| h.to_str().ok())
+            .and_then(filename_from_content_disposition);
+
+        // 保存し終えるまでバッファの使用枠を保持する
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+
+        let file_size = bytes.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+        verify_length(total_bytes, file_size)?;
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        verify_written(&dest, file_size).await?;
+
+        Ok(
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+                .with_source(source),
+        )
+    }
+}
+
+impl Default for OdelicProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ManufacturerProvider for OdelicProvider {
+    fn id(&self) -> &str {
+        "odelic"
+    }
+
+    fn display_name(&self) -> &str {
+        "オーデリック"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["オーデリック", "odelic", "おーでりっく"]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        // 品番から直接品番ページにアクセス
+        // 品名・定価・製品画像を取得し、配光データは品番ページ → シリーズのページの順に探す
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        let ies = self.lookup_ies(&hinban, &html).await.ok().flatten();
+
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: Self::extract_product_name(&html),
+            price: Self::extract_price(&html),
+            ies_file_url: ies.as_ref().map(|ies| ies.url.clone()),
+            image_url: self.extract_image_url(&html),
+            product_page_url: Some(self.detail_url(&hinban)),
+            accessories: Vec::new(),
+            discontinued: None,
+            replaces: None,
+            match_quality: ies.map(|ies| ies.quality),
+        })
+    }
+
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        let search_url = format!("{}/products/search/", self.base_url);
+
+        let response = send_request(
+            self.client
+                .get(&search_url)
+                .query(&[("keyword", Self::normalize_hinban(keyword))]),
+        )
+        .await
+        .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_item_links(&html)
+            .into_iter()
+            .map(|(hinban, text)| ProductCandidate {
+                product_page_url: Some(self.detail_url(&hinban)),
+                price: text.as_deref().and_then(Self::parse_list_price),
+                product_name: text,
+                model_number: hinban,
+            })
+            .collect())
+    }
+
+    /// 品番ページに定価が掲載されていない場合は検索結果一覧から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        if let Some(price) = Self::extract_price(&html) {
+            return Ok(Some(price));
+        }
+        let candidates = self.search_products(&hinban).await?;
+        Ok(price_from_candidates(&candidates, &hinban))
+    }
+
+    /// シリーズの代表のIESファイルで代用した場合は警告を付ける
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let started = Instant::now();
+        let hinban = Self::normalize_hinban(model_number);
+        let ies = cancel.run(self.find_ies(&hinban)).await?;
+        let lookup_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let result = cancel
+            .run(self.download_file(&ies.url, dest_path))
+            .await?
+            .with_timing(lookup_ms, started.elapsed().as_millis() as u64);
+        if ies.quality != MatchQuality::Series || !result.success {
+            return Ok(result);
+        }
+        let warning = DownloadWarning::new(
+            DownloadWarningKind::SeriesApproximation,
+            format!(
+                "No IES file for {}, used the series IES file {} from {}",
+                hinban,
+                ies.url,
+                ies.series_url.as_deref().unwrap_or_default()
+            ),
+        );
+        Ok(result.with_warning(warning))
+    }
+
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.find_ies(&Self::normalize_hinban(model_number))
+            .await
+            .map(|ies| ResolvedIesUrl::direct(ies.url))
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies, AssetType::Image]
+    }
+
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type != AssetType::Image {
+            return Err(not_provided(self.display_name(), asset_type));
+        }
+        download_product_image(self, &self.client, model_number, dest_path, cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_handle() {
+        let provider = OdelicProvider::new();
+        assert!(provider.can_handle("オーデリック"));
+        assert!(provider.can_handle("ODELIC"));
+        assert!(provider.can_handle("Odelic Co., Ltd."));
+        assert!(!provider.can_handle("大光電機"));
+    }
+
+    #[test]
+    fn test_normalize_hinban() {
+        assert_eq!(
+            OdelicProvider::normalize_hinban("od 361 234ld"),
+            "OD361234LD"
+        );
+        assert_eq!(
+            OdelicProvider::normalize_hinban("ＸＤ４５７０３４"),
+            "XD457034"
+        );
+        assert_eq!(
+            OdelicProvider::file_stem("https://www.odelic.co.jp/download/ies/od361234ld.IES?v=2"),
+            "OD361234LD"
+        );
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let provider = OdelicProvider::new();
+        let html = r#"
+            <a href="/products/series/?id=S0123">OD361シリーズ</a>
+            <a href="/download/ies/OD361234LD.ies">配光データ</a>
+            <a href="/download/ies/OD361234LD.ies">IES</a>
+            <a href="/download/cad/OD361234LD.dxf">CAD</a>
+        "#;
+        assert_eq!(
+            provider.extract_series_url(html).as_deref(),
+            Some("https://www.odelic.co.jp/products/series/?id=S0123")
+        );
+        assert_eq!(
+            provider.extract_ies_urls(html),
+            vec!["https://www.odelic.co.jp/download/ies/OD361234LD.ies".to_string()]
+        );
+        assert_eq!(provider.extract_series_url("<p>no series</p>"), None);
+    }
+
+    #[test]
+    fn test_exact_and_series_ies_url() {
+        let urls = vec![
+            "https://www.odelic.co.jp/download/ies/OD361230LD.ies".to_string(),
+            "https://www.odelic.co.jp/download/ies/OD361234LC.ies".to_string(),
+            "https://www.odelic.co.jp/download/ies/OD361234LD.ies".to_string(),
+        ];
+        assert_eq!(
+            OdelicProvider::exact_ies_url(&urls, "OD361234LD"),
+            Some(urls[2].clone())
+        );
+        assert_eq!(OdelicProvider::exact_ies_url(&urls, "OD361234LE"), None);
+
+        // 最も長く前方一致するもの（同じ場合は先に掲載されたもの）
+        assert_eq!(
+            OdelicProvider::series_ies_url(&urls, "OD361234LE"),
+            Some(urls[1].clone())
+        );
+        assert_eq!(
+            OdelicProvider::series_ies_url(&urls, "XD457034"),
+            Some(urls[0].clone())
+        );
+        assert_eq!(OdelicProvider::series_ies_url(&[], "OD361234LD"), None);
+    }
+
+    #[test]
+    fn test_fixture_site() {
+        use crate::providers::fixture_server::{fixture, run, FixtureResponse, FixtureServer};
+
+        run(async {
+            let server = FixtureServer::start(vec![
+                (
+                    "/products/item/?hinban=OD361234LD",
+                    FixtureResponse::html("odelic/item_OD361234LD.html"),
+                ),
+                (
+                    "/products/item/?hinban=OD361235LD",
+                    FixtureResponse::html("odelic/item_OD361235LD.html"),
+                ),
+                (
+                    "/products/series/?id=S0361",
+                    FixtureResponse::html("odelic/series_S0361.html"),
+                ),
+                (
+                    "/download/ies/OD361230LD.ies",
+                    FixtureResponse::file("odelic/OD361230LD.ies"),
+                ),
+            ])
+            .await;
+            let provider = OdelicProvider::with_config(&server.config());
+            let base = server.base_url();
+
+            // 品番のIESファイルがシリーズのページに掲載されている
+            let info = provider.fetch_product_info("OD361234LD").await.unwrap();
+            assert_eq!(info.product_name.as_deref(), Some("LEDダウンライト"));
+            assert_eq!(
+                info.ies_file_url,
+                Some(format!("{}/download/ies/OD361234LD.ies", base))
+            );
+            assert_eq!(info.match_quality, Some(MatchQuality::Exact));
+
+            // 品番のIESファイルがなく、シリーズの代表で代用する
+            let info = provider.fetch_product_info("OD361235LD").await.unwrap();
+            let series_ies = format!("{}/download/ies/OD361230LD.ies", base);
+            assert_eq!(info.ies_file_url.as_deref(), Some(series_ies.as_str()));
+            assert_eq!(info.match_quality, Some(MatchQuality::Series));
+
+            let temp = tempfile::tempdir().unwrap();
+            let dest = temp.path().join("1001_OD361235LD.ies");
+            let result = provider
+                .download_ies_file(
+                    "OD361235LD",
+                    None,
+                    dest.to_str().unwrap(),
+                    &CancelToken::new(),
+                )
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(
+                result.warnings[0].kind,
+                DownloadWarningKind::SeriesApproximation
+            );
+            assert_eq!(
+                std::fs::read(&dest).unwrap(),
+                fixture("odelic/OD361230LD.ies")
+            );
+
+            // 掲載のない品番（404）
+            assert_eq!(
+                provider
+                    .resolve_ies_url("OD999999", None)
+                    .await
+                    .unwrap_err(),
+                ProviderError::new(
+                    ErrorCode::HttpStatus,
+                    "Detail page for OD999999 returned status: 404 Not Found"
+                )
+            );
+        });
+    }
+}
@@ -0,0 +1,751 @@
+//! パナソニックプロバイダー
+//!
+//! パナソニック 照明器具カタログ (www2.panasonic.biz) からの
+//! 製品情報・IESファイル（配光データ）取得を担当する。
+//! 品番の詳細ページに掲載されている資料のリンクから直接ダウンロードする（PSUは使用しない）。
+
+use super::html::{
+    self, PANASONIC_DOWNLOAD_LINK, PANASONIC_ITEM_LINK, PANASONIC_PRODUCT_IMAGE,
+    PANASONIC_SPEC_LABEL,
+};
+use super::{
+    check_url, download_product_image, fetch_content_length, filename_from_content_disposition,
+    not_available, not_provided, page_mentions, parse_price, price_from_candidates,
+    provider_outdated, report_phase, retry_incomplete, send_request, status_error, verify_length,
+    verify_written, AssetType, CancelToken, Diagnosis, Discontinuation, DownloadPhase,
+    DownloadResult, DownloadSource, ManufacturerProvider, Price, ProductCandidate, ProductInfo,
+    ProviderConfig, ResolvedIesUrl,
+};
+use crate::buffer;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::StatusCode;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+/// パナソニックの照明器具カタログの既定のベースURL（設定の `providers.panasonic.baseUrl` で変更できる）
+const BASE_URL: &str = "https://www2.panasonic.biz/jp/catalog/lighting";
+
+/// 生産終了の表記（"この商品は生産終了品です" 等。"生産終了予定" は含まない）
+static DISCONTINUED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"生産終了(?:品|商品|しました|いたしました)|廃番(?:品|となりました)").unwrap()
+});
+/// 後継品の品番（"後継機種：XND1010WWKLE9" / "代替品は XND1010WWKLE9" 等）
+static SUCCESSOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:後継機種|後継品|代替品|推奨品)\s*(?:[:：]|は)?\s*([A-Z]{2,5}[0-9]{3,}[A-Z0-9]*)",
+    )
+    .unwrap()
+});
+
+/// パナソニックプロバイダー
+pub struct PanasonicProvider {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+/// 詳細ページの資料のダウンロードリンク
+#[derive(Debug, Clone, PartialEq)]
+struct DownloadLink {
+    url: String,
+    /// リンクテキスト（同じ種別の資料が複数ある場合の区別に使う）
+    label: String,
+}
+
+impl PanasonicProvider {
+    pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
+        Self {
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+        }
+    }
+
+    /// 器具リストの型番をカタログの品番に正規化
+    /// 全角英数字を半角にし、空白を除いて大文字にする
+    /// 例: "XND1010WWK LE9" → "XND1010WWKLE9"
+    fn normalize_hinban(model_number: &str) -> String {
+        model_number
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                _ => c,
+            })
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+
+    /// アセット種別に対応する詳細ページ上のダウンロード種別
+    /// パターン: /download/?type={種別}&hinban=XXXX
+    fn file_type(asset_type: AssetType) -> Option<&'static str> {
+        match asset_type {
+            AssetType::Ies => Some("ies"),
+            AssetType::PhotometricReport => Some("haikou"),
+            AssetType::SpecSheet => Some("spec"),
+            AssetType::Cad => Some("cad"),
+            AssetType::Bim => Some("bim"),
+            AssetType::Manual => Some("manual"),
+            AssetType::Model3d | AssetType::Image => None,
+        }
+    }
+
+    /// 製品詳細ページのURL
+    fn detail_url(&self, hinban: &str) -> String {
+        format!("{}/products/detail/?hinban={}", self.base_url, hinban)
+    }
+
+    /// 相対URLを絶対URLにする（サイト外のURLはそのまま）
+    fn absolute_url(&self, url: &str) -> Option<String> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Some(url.to_string())
+        } else if let Some(rest) = url.strip_prefix("//") {
+            Some(format!("https://{}", rest))
+        } else if url.starts_with('/') {
+            // base_url はパスを含むため、ホストまでを使う
+            let host_end = self
+                .base_url
+                .find("://")
+                .and_then(|scheme| {
+                    self.base_url[scheme + 3..]
+                        .find('/')
+                        .map(|i| scheme + 3 + i)
+                })
+                .unwrap_or(self.base_url.len());
+            Some(format!("{}{}", &self.base_url[..host_end], url))
+        } else {
+            None
+        }
+    }
+
+    /// 製品詳細ページのHTMLを取得
+    async fn fetch_detail_page(&self, hinban: &str) -> ProviderResult<String> {
+        let response = send_request(self.client.get(self.detail_url(hinban)))
+            .await
+            .map_err(|e| e.context("Detail request failed"))?;
+
+        // 掲載のない品番は 404 になる（後継品での取得し直しの対象）
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(status_error(
+                response.status(),
+                format!(
+                    "Detail page for {} returned status: {}",
+                    hinban,
+                    response.status()
+                ),
+            ));
+        }
+        response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))
+    }
+
+    /// 詳細ページのHTMLから指定アセットのダウンロードリンクをすべて抽出（URLで重複排除）
+    fn extract_download_links(&self, html: &str, asset_type: AssetType) -> Vec<DownloadLink> {
+        let Some(file_type) = Self::file_type(asset_type) else {
+            return Vec::new();
+        };
+        let param = format!("type={}", file_type);
+        let mut links: Vec<DownloadLink> = Vec::new();
+        for link in html::links(html, &PANASONIC_DOWNLOAD_LINK) {
+            let Some((_, query)) = link.href.split_once('?') else {
+                continue;
+            };
+            if !query.split('&').any(|pair| pair == param) {
+                continue;
+            }
+            let Some(url) = self.absolute_url(&link.href) else {
+                continue;
+            };
+            if !links.iter().any(|l| l.url == url) {
+                links.push(DownloadLink {
+                    url,
+                    label: link.text,
+                });
+            }
+        }
+        links
+    }
+
+    /// ダウンロードリンクから品番に対応するものを選ぶ
+    ///
+    /// 複数ある場合は、リンクテキストに品番を含むものに絞り込む。1つに絞り込めない場合は
+    /// 候補を含むエラーにする。
+    fn select_link(links: &[DownloadLink], hinban: &str) -> ProviderResult<Option<String>> {
+        let narrowed: Vec<&DownloadLink> = links
+            .iter()
+            .filter(|link| Self::normalize_hinban(&link.label).contains(hinban))
+            .collect();
+        let candidates: Vec<&DownloadLink> = if links.len() > 1 && !narrowed.is_empty() {
+            narrowed
+        } else {
+            links.iter().collect()
+        };
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [link] => Ok(Some(link.url.clone())),
+            _ => Err(ProviderError::new(
+                ErrorCode::IesAmbiguous,
+                format!(
+                    "Multiple IES files found for {}: {}",
+                    hinban,
+                    candidates
+                        .iter()
+                        .map(|link| format!("{} ({})", link.label, link.url))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// 製品ページから指定アセットのダウンロードURLを取得
+    async fn find_download_url(
+        &self,
+        hinban: &str,
+        asset_type: AssetType,
+    ) -> ProviderResult<String> {
+        let html = self.fetch_detail_page(hinban).await?;
+        let links = self.extract_download_links(&html, asset_type);
+        // IES以外の資料は複数ある場合も最初のもの
+        let url = match asset_type {
+            AssetType::Ies => Self::select_link(&links, hinban)?,
+            _ => links.into_iter().next().map(|link| link.url),
+        };
+        if let Some(url) = url {
+            return Ok(url);
+        }
+        // 生産終了で資料が掲載されていない場合は後継品の品番を含むエラーにする
+        if let Some(discontinued) = self.extract_discontinuation(&html) {
+            return Err(discontinued.error(hinban));
+        }
+        if Self::is_unrecognized_detail_page(&html, hinban) {
+            return Err(provider_outdated(
+                self.id(),
+                "download link or spec table",
+                hinban,
+                &html,
+            ));
+        }
+        Err(not_available(asset_type, hinban))
+    }
+
+    /// 品番が掲載されているのに、資料のダウンロードリンクも仕様表も見つからない詳細ページか
+    /// （サイトの構成が変わり、セレクターが一致しなくなった可能性が高い）
+    fn is_unrecognized_detail_page(html: &str, hinban: &str) -> bool {
+        html::links(html, &PANASONIC_DOWNLOAD_LINK).is_empty()
+            && Self::extract_product_name(html).is_none()
+            && page_mentions(html, hinban)
+    }
+
+    /// 詳細ページのHTMLから生産終了の表記と後継品の品番を抽出（生産終了でない場合は None）
+    fn extract_discontinuation(&self, html: &str) -> Option<Discontinuation> {
+        let text = html::body_text(html);
+        if !DISCONTINUED_RE.is_match(&text) {
+            return None;
+        }
+        let successor = SUCCESSOR_RE.captures(&text).map(|caps| caps[1].to_string());
+        Some(Discontinuation {
+            successor_page_url: successor.as_deref().map(|s| self.detail_url(s)),
+            successor,
+        })
+    }
+
+    /// 詳細ページのHTMLから品名を抽出
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &PANASONIC_SPEC_LABEL, &["品名", "商品名"])
+    }
+
+    /// パナソニックの希望小売価格は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 詳細ページのHTMLから希望小売価格を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(html, &PANASONIC_SPEC_LABEL, &["希望小売価格", "価格"])
+            .as_deref()
+            .and_then(Self::parse_list_price)
+    }
+
+    /// 詳細ページのHTMLから製品画像のURLを抽出（相対URLは絶対URLにする）
+    fn extract_image_url(&self, html: &str) -> Option<String> {
+        self.absolute_url(&html::image_url(html, &PANASONIC_PRODUCT_IMAGE)?)
+    }
+
+    /// HTMLから製品詳細ページへのリンクを抽出
+    /// 戻り値: (品番, リンクテキスト) の一覧（品番で重複排除）
+    fn extract_item_links(html: &str) -> Vec<(String, Option<String>)> {
+        let mut links: Vec<(String, Option<String>)> = Vec::new();
+        for link in html::links(html, &PANASONIC_ITEM_LINK) {
+            let Some((_, rest)) = link.href.split_once("hinban=") else {
+                continue;
+            };
+            let hinban: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if hinban.is_empty() || links.iter().any(|(h, _)| *h == hinban) {
+                continue;
+            }
+            let text = Some(link.text).filter(|text| !text.is_empty() && *text != hinban);
+            links.push((hinban, text));
+        }
+        links
+    }
+
+    /// ファイルをダウンロードして保存（受信が不完全な場合は1回だけやり直す）
+    async fn download_file(&self, url: &str, dest_path: &str) -> ProviderResult<DownloadResult> {
+        retry_incomplete(|| self.download_file_once(url, dest_path)).await
+    }
+
+    async fn download_file_once(
+        &self,
+        url: &str,
+        dest_path: &str,
+    ) -> ProviderResult<DownloadResult> {
+        let response = send_request(self.client.get(url))
+            .await
+            .map_err(|e| e.context("Download request failed"))?;
+
+        if !response.status().is_success() {
+            return Ok(DownloadResult::http_failure(response.status()));
+        }
+        let source = DownloadSource::from_response(&response);
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
+
+        // Content-Dispositionヘッダーから元のファイル名を取得
+        let original_filename = response
+            .headers()
+            .get("content-disposition")
+            .and_then(|h| h.to_str().ok())
+            .and_then(filename_from_content_disposition);
+
+        // 保存し終えるまでバッファの使用枠を保持する
+        let _permit = buffer::acquire(response.content_length()).await;
+        let bytes = response.bytes().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read file content",
+        ))?;
+
+        let file_size = bytes.len() as u64;
+        report_phase(DownloadPhase::Downloading, Some(file_size), total_bytes);
+        verify_length(total_bytes, file_size)?;
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create directory",
+                ))?;
+        }
+
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write file",
+            ))?;
+        verify_written(&dest, file_size).await?;
+
+        Ok(
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename)
+                .with_source(source),
+        )
+    }
+}
+
+impl Default for PanasonicProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ManufacturerProvider for PanasonicProvider {
+    fn id(&self) -> &str {
+        "panasonic"
+    }
+
+    fn display_name(&self) -> &str {
+        "パナソニック"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["パナソニック", "panasonic", "ぱなそにっく"]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
+        // 品番から直接製品ページにアクセス
+        // 品名・希望小売価格・製品画像・IESファイルURL・生産終了の情報を取得
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        let ies_file_url =
+            Self::select_link(&self.extract_download_links(&html, AssetType::Ies), &hinban)
+                .ok()
+                .flatten();
+
+        Ok(ProductInfo {
+            model_number: model_number.to_string(),
+            product_name: Self::extract_product_name(&html),
+            price: Self::extract_price(&html),
+            ies_file_url,
+            image_url: self.extract_image_url(&html),
+            product_page_url: Some(self.detail_url(&hinban)),
+            accessories: Vec::new(),
+            discontinued: self.extract_discontinuation(&html),
+            replaces: None,
+            match_quality: None,
+        })
+    }
+
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        let search_url = format!("{}/search/", self.base_url);
+
+        let response = send_request(
+            self.client
+                .get(&search_url)
+                .query(&[("keyword", Self::normalize_hinban(keyword))]),
+        )
+        .await
+        .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_item_links(&html)
+            .into_iter()
+            .map(|(hinban, text)| ProductCandidate {
+                product_page_url: Some(self.detail_url(&hinban)),
+                price: text.as_deref().and_then(Self::parse_list_price),
+                product_name: text,
+                model_number: hinban,
+            })
+            .collect())
+    }
+
+    /// 詳細ページに希望小売価格が掲載されていない場合（オープン価格等）は検索結果一覧から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let hinban = Self::normalize_hinban(model_number);
+        let html = self.fetch_detail_page(&hinban).await?;
+        if let Some(price) = Self::extract_price(&html) {
+            return Ok(Some(price));
+        }
+        let candidates = self.search_products(&hinban).await?;
+        Ok(price_from_candidates(&candidates, &hinban))
+    }
+
+    async fn download_ies_file(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        self.download_asset(model_number, None, AssetType::Ies, dest_path, cancel)
+            .await
+    }
+
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
+        self.find_download_url(&Self::normalize_hinban(model_number), AssetType::Ies)
+            .await
+            .map(ResolvedIesUrl::direct)
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![
+            AssetType::Ies,
+            AssetType::PhotometricReport,
+            AssetType::SpecSheet,
+            AssetType::Cad,
+            AssetType::Bim,
+            AssetType::Manual,
+            AssetType::Image,
+        ]
+    }
+
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type == AssetType::Image {
+            return download_product_image(self, &self.client, model_number, dest_path, cancel)
+                .await;
+        }
+        if Self::file_type(asset_type).is_none() {
+            return Err(not_provided(self.display_name(), asset_type));
+        }
+        let started = Instant::now();
+        let hinban = Self::normalize_hinban(model_number);
+        let url = cancel
+            .run(self.find_download_url(&hinban, asset_type))
+            .await?;
+        let lookup_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let result = cancel.run(self.download_file(&url, dest_path)).await?;
+        Ok(result.with_timing(lookup_ms, started.elapsed().as_millis() as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filename::{self, FilenameContext};
+
+    #[test]
+    fn test_can_handle() {
+        let provider = PanasonicProvider::new();
+        assert!(provider.can_handle("パナソニック"));
+        assert!(provider.can_handle("Panasonic"));
+        assert!(provider.can_handle("パナソニック エレクトリックワークス"));
+        assert!(!provider.can_handle("コイズミ照明"));
+        assert!(!provider.can_handle("大光電機"));
+    }
+
+    #[test]
+    fn test_normalize_hinban() {
+        assert_eq!(
+            PanasonicProvider::normalize_hinban("XND1010WWK LE9"),
+            "XND1010WWKLE9"
+        );
+        assert_eq!(
+            PanasonicProvider::normalize_hinban("ｘｎｄ１０１０ＷＷＫ　ＬＥ９"),
+            "XND1010WWKLE9"
+        );
+        assert_eq!(
+            PanasonicProvider::normalize_hinban("NNN21615LE1"),
+            "NNN21615LE1"
+        );
+    }
+
+    #[test]
+    fn test_extract_download_links() {
+        let provider = PanasonicProvider::new();
+        let html = r#"
+            <a class="dl" href="/jp/catalog/lighting/download/?type=spec&amp;hinban=XND1010WWKLE9">仕様図</a>
+            <a href='/jp/catalog/lighting/download/?type=ies&hinban=XND1010WWKLE9'
+               target="_blank">配光データ（IES） XND1010WWKLE9</a>
+            <a href="/jp/catalog/lighting/download/?type=iesx&hinban=XND1010WWKLE9">other</a>
+        "#;
+
+        let links = provider.extract_download_links(html, AssetType::Ies);
+        assert_eq!(
+            links,
+            vec![DownloadLink {
+                url: "https://www2.panasonic.biz/jp/catalog/lighting/download/?type=ies&hinban=XND1010WWKLE9".to_string(),
+                label: "配光データ（IES） XND1010WWKLE9".to_string(),
+            }]
+        );
+        assert_eq!(
+            provider
+                .extract_download_links(html, AssetType::SpecSheet)
+                .first()
+                .map(|link| link.url.as_str()),
+            Some("https://www2.panasonic.biz/jp/catalog/lighting/download/?type=spec&hinban=XND1010WWKLE9")
+        );
+        assert!(provider
+            .extract_download_links(html, AssetType::Cad)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_select_link() {
+        let link = |id: &str, label: &str| DownloadLink {
+            url: format!("https://example.com/download/?type=ies&id={}", id),
+            label: label.to_string(),
+        };
+        let links = vec![
+            link("1", "配光データ XND1010WWKLE9"),
+            link("2", "配光データ XND1010WWKRZ9"),
+        ];
+
+        // リンクテキストの品番で絞り込む
+        assert_eq!(
+            PanasonicProvider::select_link(&links, "XND1010WWKRZ9").unwrap(),
+            Some("https://example.com/download/?type=ies&id=2".to_string())
+        );
+        // 絞り込めない場合は候補を含むエラー
+        let error = PanasonicProvider::select_link(&links, "XND1010").unwrap_err();
+        assert_eq!(error.code, ErrorCode::IesAmbiguous);
+        assert!(error
+            .message
+            .starts_with("Multiple IES files found for XND1010"));
+
+        // リンクが1つだけなら品番に関わらず使う
+        assert_eq!(
+            PanasonicProvider::select_link(&links[..1], "NNN21615LE1").unwrap(),
+            Some("https://example.com/download/?type=ies&id=1".to_string())
+        );
+        assert_eq!(
+            PanasonicProvider::select_link(&[], "NNN21615LE1").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_product_details() {
+        let provider = PanasonicProvider::new();
+        let html = r#"
+            <html><head>
+              <meta property="og:image" content="/jp/catalog/lighting/images/XND1010WWKLE9.jpg">
+            </head><body>
+              <table>
+                <tr><th>品　名</th><td> LEDダウンライト </td></tr>
+                <tr><th>希望小売価格（税抜）</th><td>24,500円</td></tr>
+              </table>
+              <a href="/jp/catalog/lighting/products/detail/?hinban=XND1010WWKLE9">XND1010WWKLE9</a>
+              <a href="/jp/catalog/lighting/products/detail/?hinban=NQ28749">ライコン NQ28749</a>
+            </body></html>
+        "#;
+
+        assert_eq!(
+            PanasonicProvider::extract_product_name(html).as_deref(),
+            Some("LEDダウンライト")
+        );
+        assert_eq!(
+            PanasonicProvider::extract_price(html),
+            Some(Price::jpy(24500).assume_tax_included(false))
+        );
+        assert_eq!(
+            provider.extract_image_url(html).as_deref(),
+            Some("https://www2.panasonic.biz/jp/catalog/lighting/images/XND1010WWKLE9.jpg")
+        );
+        assert_eq!(
+            PanasonicProvider::extract_item_links(html),
+            vec![
+                ("XND1010WWKLE9".to_string(), None),
+                ("NQ28749".to_string(), Some("ライコン NQ28749".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_discontinuation() {
+        let provider = PanasonicProvider::new();
+        let html = r#"
+            <p class="notice">この商品は生産終了品です。</p>
+            <p>後継機種：<a href="/jp/catalog/lighting/products/detail/?hinban=XND1011WWKLE9">XND1011WWKLE9</a></p>
+        "#;
+        let discontinued = provider.extract_discontinuation(html).unwrap();
+        assert_eq!(discontinued.successor.as_deref(), Some("XND1011WWKLE9"));
+        assert_eq!(
+            discontinued.successor_page_url.as_deref(),
+            Some("https://www2.panasonic.biz/jp/catalog/lighting/products/detail/?hinban=XND1011WWKLE9")
+        );
+
+        // 生産終了予定は生産終了とみなさない
+        let html = "<p>2027年3月 生産終了予定</p>";
+        assert_eq!(provider.extract_discontinuation(html), None);
+    }
+
+    #[test]
+    fn test_is_unrecognized_detail_page() {
+        let html = r#"<div class="spec-grid"><span>品名</span><span>LEDダウンライト</span>
+            <span>品番</span><span>XND1010WWKLE9</span></div>"#;
+        assert!(PanasonicProvider::is_unrecognized_detail_page(
+            html,
+            "XND1010WWKLE9"
+        ));
+
+        let html = r#"<table><tr><th>品名</th><td>LEDダウンライト</td></tr></table>"#;
+        assert!(!PanasonicProvider::is_unrecognized_detail_page(
+            html,
+            "XND1010WWKLE9"
+        ));
+    }
+
+    #[test]
+    fn test_default_filename_template() {
+        let provider = PanasonicProvider::new();
+        let render = |asset_type, model_number, psu, original_filename| {
+            let context = FilenameContext {
+                spec_no: "1001",
+                manufacturer: provider.display_name(),
+                model_number,
+                psu,
+                original_filename,
+                photometry: None,
+            };
+            filename::render(
+                provider.default_filename_template(asset_type),
+                asset_type,
+                &context,
+            )
+        };
+
+        // IES: 元ファイル名あり
+        assert_eq!(
+            render(
+                AssetType::Ies,
+                "XND1010WWKLE9",
+                None,
+                Some("XND1010WWKLE9.IES")
+            ),
+            "1001_XND1010WWKLE9.ies"
+        );
+
+        // IES: 元ファイル名なし（型番）
+        assert_eq!(
+            render(AssetType::Ies, "XND1010WWKLE9", None, None),
+            "1001_XND1010WWKLE9.ies"
+        );
+
+        // PSUは無視される
+        assert_eq!(
+            render(AssetType::Ies, "XND1010WWKLE9", Some("NQ28749"), None),
+            "1001_XND1010WWKLE9.ies"
+        );
+
+        // アセット: 元ファイル名あり（拡張子を保持）
+        assert_eq!(
+            render(
+                AssetType::Cad,
+                "XND1010WWKLE9",
+                None,
+                Some("XND1010WWKLE9.dxf")
+            ),
+            "1001_XND1010WWKLE9.dxf"
+        );
+    }
+}
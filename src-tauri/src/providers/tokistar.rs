@@ -2,27 +2,124 @@
 //!
 //! TOKISTAR (toki.co.jp) からの
 //! 製品情報・IESファイル取得を担当する。
+//! ZIPにIESファイルがなくEULUMDAT（LDT）のみの場合は、IESに変換して保存する（LDTも残す）。
 
-use super::{DownloadResult, ManufacturerProvider, ProductInfo};
+use super::html::{
+    self, TOKISTAR_IES_ZIP_LINK, TOKISTAR_NEXT_PAGE, TOKISTAR_PRODUCT_IMAGE, TOKISTAR_PRODUCT_LINK,
+    TOKISTAR_PRODUCT_TITLE, TOKISTAR_SPEC_LABEL, TOKISTAR_ZIP_LINK,
+};
+use super::{
+    can_request_decision, check_url, download_product_image, fetch_content_length, matching,
+    not_provided, parse_price, price_from_candidates, provider_outdated, report_phase,
+    request_decision, retry_incomplete, run_blocking, send_request, status_error, verify_length,
+    zip_member, AssetType, BatchCache, CancelToken, Diagnosis, DownloadPhase, DownloadResult,
+    DownloadSource, DownloadWarning, DownloadWarningKind, ManufacturerProvider, Price,
+    ProductCandidate, ProductInfo, ProviderConfig, ResolvedIesUrl, ZipCandidate, CANCELLED,
+};
+use crate::cache::CacheScope;
+use crate::error::{ErrorCode, ProviderError, ProviderResult};
+use crate::longpath;
+use crate::photometry;
 use async_trait::async_trait;
-use regex::Regex;
-use std::io::Read;
-use std::path::Path;
+use reqwest::header::IF_MODIFIED_SINCE;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+
+/// TOKISTARのWebサイトの既定のベースURL（設定の `providers.tokistar.baseUrl` で変更できる）
+const BASE_URL: &str = "https://toki.co.jp/tokistar";
+
+/// 検索結果をたどるページ数の上限
+const MAX_SEARCH_PAGES: usize = 10;
+
+/// IES ZIPのディスクキャッシュの保存先（起動時に設定）
+static ZIP_CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// IES ZIPのディスクキャッシュの保存先を設定（起動時）
+///
+/// 設定するとダウンロードしたZIPをURLごとに保存し、次回以降は `Last-Modified` で更新が
+/// ないことを確認して再利用する（めったに更新されない数MBのZIPを実行のたびにダウンロードしない）。
+pub fn set_zip_cache_dir(cache_dir: &Path) {
+    let _ = ZIP_CACHE_DIR.set(CacheScope::TokistarZip.dir(cache_dir));
+}
+
+/// ディスクキャッシュのメタデータ
+///
+/// 形式: {キャッシュディレクトリ}/tokistar_zip/{URLのSHA-256の先頭16桁}.json（ZIPは同名の .zip）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ZipCacheMeta {
+    url: String,
+    /// ダウンロード時の `Last-Modified` ヘッダー
+    last_modified: String,
+}
+
+/// ダウンロードしたIES ZIPのファイル
+enum ZipFile {
+    /// 一時ファイル（最後の参照がなくなると削除される）
+    Temp(NamedTempFile),
+    /// ディスクキャッシュのファイル
+    Cached(PathBuf),
+}
+
+impl ZipFile {
+    fn path(&self) -> &Path {
+        match self {
+            ZipFile::Temp(file) => file.path(),
+            ZipFile::Cached(path) => path,
+        }
+    }
+}
 
 /// TOKISTAR プロバイダー
 pub struct TokistarProvider {
     base_url: String,
     client: reqwest::Client,
+    /// 一括ダウンロード中に取得したIES ZIP（partial_fixture_id ごと）
+    /// 同じシリーズの型番が多数並ぶスケジュールで、同じZIPを何度もダウンロードしないためのもの。
+    /// 値は検索結果に掲載されたZIPごとの (ZIPのURL, ZIPのファイル)。
+    /// 一括ダウンロード中以外は保持しない
+    zip_cache: BatchCache<Vec<(DownloadSource, Arc<ZipFile>)>>,
+}
+
+/// 取得したIES ZIP
+struct FetchedZip {
+    /// ZIPの取得元
+    source: DownloadSource,
+    /// ダウンロードしたZIPのファイル
+    file: Arc<ZipFile>,
+    lookup_ms: u64,
+    download_ms: u64,
+    /// 今回ダウンロードしたサイズ（候補のZIPすべての合計。再利用した場合は0）
+    bytes_transferred: u64,
+}
+
+/// 製品ページ（シリーズ単位）
+struct ProductPage {
+    /// サイト内検索で見つけた候補
+    candidate: ProductCandidate,
+    url: String,
+    html: String,
 }
 
 impl TokistarProvider {
     pub fn new() -> Self {
+        Self::with_config(&ProviderConfig::default())
+    }
+
+    /// 接続設定（ベースURL・User-Agent・タイムアウト）を反映して作成
+    pub fn with_config(config: &ProviderConfig) -> Self {
         Self {
-            base_url: "https://toki.co.jp/tokistar".to_string(),
-            client: reqwest::Client::builder()
-                .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
-                .build()
-                .expect("Failed to create HTTP client"),
+            base_url: config.base_url(BASE_URL),
+            client: config.client(),
+            zip_cache: BatchCache::default(),
         }
     }
 
@@ -38,143 +135,586 @@ impl TokistarProvider {
             .to_string()
     }
 
-    /// 検索ページからIES ZIPファイルのURLを取得
-    async fn get_ies_zip_url(&self, partial_id: &str) -> Result<Option<String>, String> {
-        let search_url = format!("{}/download01/?freeword={}", self.base_url, partial_id);
+    /// 検索ページからIES ZIPファイルのURLをすべて取得
+    /// 検索結果が複数ページにわたる場合は次のページもたどる（最大 MAX_SEARCH_PAGES ページ）。
+    /// シリーズが複数のZIPに分かれて掲載されている場合があるため、最初の1件に限らない
+    async fn get_ies_zip_urls(&self, partial_id: &str) -> ProviderResult<Vec<String>> {
+        let mut next = Some(format!(
+            "{}/download01/?freeword={}",
+            self.base_url, partial_id
+        ));
+        let mut visited: Vec<String> = Vec::new();
+        let mut urls: Vec<String> = Vec::new();
 
-        let response = self
-            .client
-            .get(&search_url)
-            .send()
-            .await
-            .map_err(|e| format!("Search request failed: {}", e))?;
+        while let Some(page_url) = next.take() {
+            if visited.len() >= MAX_SEARCH_PAGES || visited.contains(&page_url) {
+                break;
+            }
 
-        let html = response
-            .text()
-            .await
-            .map_err(|e| format!("Failed to read response: {}", e))?;
+            let response = send_request(self.client.get(&page_url))
+                .await
+                .map_err(|e| e.context("Search request failed"))?;
+
+            let html = response.text().await.map_err(ProviderError::wrap(
+                ErrorCode::NetworkError,
+                "Failed to read response",
+            ))?;
+
+            // IES ZIPのURLを抽出
+            for link in html::links(&html, &TOKISTAR_IES_ZIP_LINK) {
+                if !urls.contains(&link.href) {
+                    urls.push(link.href);
+                }
+            }
+            if urls.is_empty() && Self::has_unrecognized_ies_link(&html, partial_id) {
+                return Err(provider_outdated(
+                    self.id(),
+                    "IES ZIP link",
+                    partial_id,
+                    &html,
+                ));
+            }
 
-        // IES ZIPのURLを抽出
-        // パターン: href="https://toki.co.jp/tokistar/wp-content/uploads/YYYY/MM/IES_XXX.zip"
-        let re = Regex::new(r#"href="([^"]*\/IES_[^"]*\.zip)""#).unwrap();
-        if let Some(caps) = re.captures(&html) {
-            return Ok(Some(caps[1].to_string()));
+            next = Self::next_page_url(&html, &page_url);
+            visited.push(page_url);
         }
 
-        Ok(None)
+        Ok(urls)
     }
 
-    /// 2つの文字列の前方一致長を計算
-    fn common_prefix_length(a: &str, b: &str) -> usize {
-        a.chars()
-            .zip(b.chars())
-            .take_while(|(ca, cb)| ca == cb)
-            .count()
+    /// IES ZIPのリンクとして認識できないが、型番の配光データらしいZIPへのリンクがあるか
+    /// （ファイル名の付け方が変わり、セレクターが一致しなくなった可能性が高い）
+    fn has_unrecognized_ies_link(html: &str, partial_id: &str) -> bool {
+        let partial_id = partial_id.to_uppercase();
+        html::links(html, &TOKISTAR_ZIP_LINK).iter().any(|link| {
+            let link = format!("{} {}", link.href, link.text).to_uppercase();
+            (link.contains("IES") || link.contains("配光")) && link.contains(&partial_id)
+        })
     }
 
-    /// ZIPファイルの中から最適な.iesファイルを選択
-    /// fixture_id の '-' を '_' に置換し、前方一致が最も長いファイルを選択
-    fn select_best_ies_file(fixture_id: &str, ies_files: &[String]) -> Option<String> {
-        // fixture_id の - を _ に置換して正規化
-        let normalized = fixture_id.replace('-', "_");
+    /// 検索結果のHTMLから次のページのURLを取得（相対URLは現在のページを基準に解決）
+    fn next_page_url(html: &str, current_url: &str) -> Option<String> {
+        let href = html::links(html, &TOKISTAR_NEXT_PAGE)
+            .into_iter()
+            .next()?
+            .href;
+        Self::resolve_url(current_url, &href)
+    }
 
-        // 前方一致の長さでソートし、最長を選択
-        ies_files
-            .iter()
-            .map(|f| {
-                // パスからファイル名のみを取り出す（IES_OSP/OSP01_30K.ies → OSP01_30K）
-                let filename = Path::new(f)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or(f);
-                let name = filename
-                    .trim_end_matches(".ies")
-                    .trim_end_matches(".IES");
-                let match_len = Self::common_prefix_length(&normalized, name);
-                (f, match_len)
-            })
-            .max_by_key(|(_, len)| *len)
-            .filter(|(_, len)| *len > 0)
-            .map(|(f, _)| f.clone())
+    /// 相対URLを `base_url` を基準に絶対URLにする
+    fn resolve_url(base_url: &str, href: &str) -> Option<String> {
+        reqwest::Url::parse(base_url)
+            .ok()?
+            .join(href)
+            .ok()
+            .map(String::from)
     }
 
-    /// ZIPファイルをダウンロードして展開し、最適な.iesファイルを取得
-    async fn download_and_extract_ies(
-        &self,
-        zip_url: &str,
-        fixture_id: &str,
-        dest_path: &str,
-    ) -> Result<DownloadResult, String> {
-        // ZIPファイルをダウンロード
-        let response = self
-            .client
-            .get(zip_url)
-            .send()
-            .await
-            .map_err(|e| format!("ZIP download failed: {}", e))?;
+    /// サイト内検索で partial_fixture_id の製品ページを探して取得（見つからない場合は None）
+    async fn fetch_product_page(&self, partial_id: &str) -> ProviderResult<Option<ProductPage>> {
+        let partial_id = partial_id.to_uppercase();
+        let Some(candidate) = self
+            .search_products(&partial_id)
+            .await?
+            .into_iter()
+            .find(|c| c.model_number == partial_id)
+        else {
+            return Ok(None);
+        };
+        let Some(url) = candidate.product_page_url.clone() else {
+            return Ok(None);
+        };
 
+        let response = send_request(self.client.get(&url))
+            .await
+            .map_err(|e| e.context("Product page request failed"))?;
         if !response.status().is_success() {
-            return Ok(DownloadResult::failure(format!(
-                "ZIP download failed with status: {}",
-                response.status()
-            )));
+            return Err(status_error(
+                response.status(),
+                format!(
+                    "Product page request failed with status: {}",
+                    response.status()
+                ),
+            ));
         }
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
 
-        let zip_bytes = response
-            .bytes()
-            .await
-            .map_err(|e| format!("Failed to read ZIP content: {}", e))?;
+        Ok(Some(ProductPage {
+            candidate,
+            url,
+            html,
+        }))
+    }
 
-        // ZIPを展開して.iesファイル一覧を取得
-        let cursor = std::io::Cursor::new(zip_bytes.as_ref());
-        let mut archive =
-            zip::ZipArchive::new(cursor).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+    /// 製品ページのHTMLから品名を抽出（仕様表になければページの見出し）
+    fn extract_product_name(html: &str) -> Option<String> {
+        html::labeled_value(html, &TOKISTAR_SPEC_LABEL, &["品名", "商品名", "製品名"])
+            .or_else(|| html::first_text(html, &TOKISTAR_PRODUCT_TITLE))
+    }
 
-        // .iesファイル一覧を収集
-        let ies_files: Vec<String> = (0..archive.len())
+    /// トキスターの価格は税抜で掲載されている（表記がなくても税抜とみなす）
+    fn parse_list_price(text: &str) -> Option<Price> {
+        parse_price(text).map(|price| price.assume_tax_included(false))
+    }
+
+    /// 製品ページのHTMLから価格を抽出
+    fn extract_price(html: &str) -> Option<Price> {
+        html::labeled_value(
+            html,
+            &TOKISTAR_SPEC_LABEL,
+            &["価格", "定価", "希望小売価格"],
+        )
+        .as_deref()
+        .and_then(Self::parse_list_price)
+    }
+
+    /// 製品ページのHTMLから製品画像のURLを抽出（相対URLは製品ページを基準に解決）
+    fn extract_image_url(html: &str, page_url: &str) -> Option<String> {
+        let url = html::image_url(html, &TOKISTAR_PRODUCT_IMAGE)?;
+        Self::resolve_url(page_url, &url)
+    }
+
+    /// サイト内検索結果のHTMLから製品ページへのリンクを抽出
+    /// パターン: href="https://toki.co.jp/tokistar/products/osp01/"
+    fn extract_product_candidates(html: &str) -> Vec<ProductCandidate> {
+        let mut candidates: Vec<ProductCandidate> = Vec::new();
+        for link in html::links(html, &TOKISTAR_PRODUCT_LINK) {
+            // 製品ページ直下（/products/{型番}/）のリンクが対象
+            let Some((_, slug)) = link.href.split_once("/tokistar/products/") else {
+                continue;
+            };
+            let slug = slug.strip_suffix('/').unwrap_or(slug);
+            let valid = !slug.is_empty()
+                && slug
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+            if !valid {
+                continue;
+            }
+            let model_number = slug.to_uppercase();
+            if candidates.iter().any(|c| c.model_number == model_number) {
+                continue;
+            }
+
+            let text = link.text;
+            candidates.push(ProductCandidate {
+                model_number,
+                price: Self::parse_list_price(&text),
+                product_name: if text.is_empty() { None } else { Some(text) },
+                product_page_url: Some(link.href),
+            });
+        }
+
+        candidates
+    }
+
+    /// 複数のZIPの中から、内容が fixture_id に最も一致するZIPを選択
+    /// `archives` はZIPごとのファイル一覧。一致度が同じ場合は検索結果で先に掲載されたものを選ぶ
+    fn select_best_archive(fixture_id: &str, archives: &[Vec<String>]) -> usize {
+        archives
+            .iter()
+            .enumerate()
+            .map(|(i, files)| {
+                let best = files
+                    .iter()
+                    .filter_map(|f| matching::match_score(fixture_id, f))
+                    .max();
+                (i, best)
+            })
+            .rev()
+            .max_by_key(|(_, score)| *score)
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// ZIP内の指定拡張子（例: ".ies"）のファイル一覧を取得
+    fn list_files<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, suffix: &str) -> Vec<String> {
+        (0..archive.len())
             .filter_map(|i| {
                 archive.by_index(i).ok().and_then(|file| {
                     let name = file.name().to_string();
-                    if name.to_lowercase().ends_with(".ies") {
+                    if name.to_lowercase().ends_with(suffix) {
                         Some(name)
                     } else {
                         None
                     }
                 })
             })
-            .collect();
+            .collect()
+    }
+
+    /// IES ZIPを検索してダウンロードし、内容が fixture_id に最も一致するものを返す
+    /// （見つからない場合は None）
+    ///
+    /// シリーズが複数のZIPに分かれている場合は候補のZIPをすべて取得し、.iesファイルの
+    /// 前方一致が最も長いZIPを選ぶ。
+    /// 一括ダウンロード中は partial_fixture_id ごとに保持し、同じシリーズの2件目以降は
+    /// 検索・ダウンロードを行わずに再利用する。
+    async fn fetch_zip(&self, fixture_id: &str) -> ProviderResult<Option<FetchedZip>> {
+        let partial_id = Self::extract_partial_fixture_id(fixture_id);
+        let cached = self.zip_cache.get(&partial_id);
 
-        if ies_files.is_empty() {
-            return Ok(DownloadResult::failure(
-                "No .ies files found in ZIP".to_string(),
+        let (mut archives, lookup_ms, download_ms, bytes_transferred) = match cached {
+            Some(archives) => (archives, 0, 0, 0),
+            None => {
+                // IES ZIPのURLを取得
+                let started = Instant::now();
+                let urls = self.get_ies_zip_urls(&partial_id).await?;
+                if urls.is_empty() {
+                    return Ok(None);
+                }
+                let lookup_ms = started.elapsed().as_millis() as u64;
+
+                // ZIPファイルをダウンロード
+                let started = Instant::now();
+                let mut archives = Vec::with_capacity(urls.len());
+                let mut bytes_transferred = 0;
+                for url in urls {
+                    let (file, size, source) = retry_incomplete(|| self.download_zip(&url)).await?;
+                    bytes_transferred += size;
+                    archives.push((source, file));
+                }
+                self.zip_cache.insert(partial_id, archives.clone());
+                let download_ms = started.elapsed().as_millis() as u64;
+                (archives, lookup_ms, download_ms, bytes_transferred)
+            }
+        };
+
+        // 内容が fixture_id に最も一致するZIPを選択（展開はブロッキングするため専用スレッドで行う）
+        let index = if archives.len() > 1 {
+            let (files, fixture_id) = (
+                archives
+                    .iter()
+                    .map(|(_, file)| file.clone())
+                    .collect::<Vec<_>>(),
+                fixture_id.to_string(),
+            );
+            run_blocking(move || -> ProviderResult<_> {
+                let lists: Vec<Vec<String>> = files
+                    .iter()
+                    .map(|file| {
+                        Self::open_archive(file.path())
+                            .map(|mut archive| Self::list_files(&mut archive, ".ies"))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                Ok(Self::select_best_archive(&fixture_id, &lists))
+            })
+            .await?
+        } else {
+            0
+        };
+        let (source, file) = archives.swap_remove(index);
+
+        Ok(Some(FetchedZip {
+            source,
+            file,
+            lookup_ms,
+            download_ms,
+            bytes_transferred,
+        }))
+    }
+
+    /// ZIPファイルをダウンロード（戻り値: ZIPのファイル・今回ダウンロードしたサイズ・取得元）
+    ///
+    /// ディスクキャッシュにあるものは `If-Modified-Since` で確認し、更新がなければ再利用する。
+    async fn download_zip(&self, url: &str) -> ProviderResult<(Arc<ZipFile>, u64, DownloadSource)> {
+        let cache_dir = ZIP_CACHE_DIR.get();
+        let cached = cache_dir.and_then(|dir| Self::load_cached_zip(dir, url));
+
+        let mut request = self.client.get(url);
+        if let Some((_, last_modified)) = &cached {
+            request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+        }
+        let mut response = send_request(request).await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "ZIP download failed",
+        ))?;
+        let mut source = DownloadSource::from_response(&response);
+        let last_modified = source.last_modified.clone();
+
+        // 更新がなければキャッシュを使用（304に対応していないサーバーでも Last-Modified が同じなら本文を読まない）
+        if let Some((path, cached_last_modified)) = cached {
+            if response.status() == StatusCode::NOT_MODIFIED
+                || last_modified.as_deref() == Some(cached_last_modified.as_str())
+            {
+                tracing::debug!(url, "reusing cached TOKISTAR ZIP");
+                // 304 の応答には Last-Modified がない場合があるため、保存時の値を記録する
+                source.last_modified = Some(cached_last_modified);
+                return Ok((Arc::new(ZipFile::Cached(path)), 0, source));
+            }
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(
+                response.status(),
+                format!("ZIP download failed with status: {}", response.status()),
             ));
         }
+        let total_bytes = response.content_length();
+        report_phase(DownloadPhase::Downloading, Some(0), total_bytes);
 
-        // 最適なファイルを選択
-        let best_file = Self::select_best_ies_file(fixture_id, &ies_files)
-            .ok_or_else(|| format!("No matching .ies file found for: {}", fixture_id))?;
+        // 受信しながら一時ファイルに書き出す（大きなZIPでも全体をメモリに保持しない）
+        // キャッシュする場合は保存先への移動が名前の変更で済むよう、キャッシュディレクトリに作成する
+        let file = match cache_dir.filter(|dir| std::fs::create_dir_all(dir).is_ok()) {
+            Some(dir) => NamedTempFile::new_in(dir),
+            None => NamedTempFile::new(),
+        }
+        .map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to create temporary file",
+        ))?;
+        let mut writer =
+            file.reopen()
+                .map(tokio::fs::File::from_std)
+                .map_err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to create temporary file",
+                ))?;
+        let mut size = 0u64;
+        while let Some(chunk) = response.chunk().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read ZIP content",
+        ))? {
+            writer.write_all(&chunk).await.map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to write temporary file",
+            ))?;
+            size += chunk.len() as u64;
+            report_phase(DownloadPhase::Downloading, Some(size), total_bytes);
+        }
+        writer.flush().await.map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write temporary file",
+        ))?;
+        drop(writer);
+        verify_length(total_bytes, size)?;
 
-        // 選択したファイルを取り出して保存
-        let mut file = archive
-            .by_name(&best_file)
-            .map_err(|e| format!("Failed to read {} from ZIP: {}", best_file, e))?;
+        // Last-Modified がない場合は更新を確認できないためキャッシュしない
+        let file = match (cache_dir, last_modified) {
+            (Some(dir), Some(last_modified)) => {
+                Self::store_cached_zip(dir, url, &last_modified, file)
+            }
+            _ => ZipFile::Temp(file),
+        };
+        Ok((Arc::new(file), size, source))
+    }
+
+    /// ディスクキャッシュのファイル名（拡張子なし）
+    fn zip_cache_key(url: &str) -> String {
+        format!("{:x}", Sha256::digest(url.as_bytes()))[..16].to_string()
+    }
+
+    /// ディスクキャッシュからZIPを探す（戻り値: ZIPのパス・保存時の Last-Modified）
+    fn load_cached_zip(dir: &Path, url: &str) -> Option<(PathBuf, String)> {
+        let key = Self::zip_cache_key(url);
+        let contents = std::fs::read(dir.join(format!("{}.json", key))).ok()?;
+        let meta: ZipCacheMeta = serde_json::from_slice(&contents).ok()?;
+        let path = dir.join(format!("{}.zip", key));
+        (meta.url == url && path.is_file()).then_some((path, meta.last_modified))
+    }
+
+    /// ダウンロードしたZIPをディスクキャッシュに保存
+    /// 保存できなかった場合は一時ファイルのまま返す（ダウンロード結果には影響しない）
+    fn store_cached_zip(
+        dir: &Path,
+        url: &str,
+        last_modified: &str,
+        file: NamedTempFile,
+    ) -> ZipFile {
+        let key = Self::zip_cache_key(url);
+        let path = dir.join(format!("{}.zip", key));
+        let file = match file.persist(&path) {
+            Ok(_) => ZipFile::Cached(path),
+            Err(e) => {
+                tracing::warn!(url, error = %e.error, "failed to cache TOKISTAR ZIP");
+                return ZipFile::Temp(e.file);
+            }
+        };
+        let meta = ZipCacheMeta {
+            url: url.to_string(),
+            last_modified: last_modified.to_string(),
+        };
+        if let Ok(json) = serde_json::to_vec(&meta) {
+            let _ = std::fs::write(dir.join(format!("{}.json", key)), json);
+        }
+        file
+    }
 
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents)
-            .map_err(|e| format!("Failed to read file content: {}", e))?;
+    /// IES ZIPを取得して展開し、最適な.iesファイルを保存
+    async fn download_from_zip(
+        &self,
+        fixture_id: &str,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let partial_id = Self::extract_partial_fixture_id(fixture_id);
+        let zip = cancel
+            .run(self.fetch_zip(fixture_id))
+            .await?
+            .ok_or_else(|| {
+                ProviderError::new(
+                    ErrorCode::IesNotAvailable,
+                    format!("IES archive not found for: {}", partial_id),
+                )
+            })?;
 
-        let file_size = contents.len() as u64;
+        // 利用者が指定したファイルを使う。指定がなく、同程度に一致するIESファイルが複数あれば、
+        // 展開前に利用者に選んでもらう
+        let member = zip_member();
+        let chosen = if member.is_some() {
+            member
+        } else if can_request_decision() {
+            let (zip_file, fixture_id) = (zip.file.clone(), fixture_id.to_string());
+            let request = run_blocking(move || -> ProviderResult<_> {
+                let mut archive = Self::open_archive(zip_file.path())?;
+                let files = Self::list_files(&mut archive, ".ies");
+                Ok(matching::decision_request(&fixture_id, &files))
+            })
+            .await?;
+            match request {
+                Some(request) => {
+                    cancel
+                        .run(async { Ok::<_, ProviderError>(request_decision(request).await) })
+                        .await?
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        // 展開・保存はブロッキングするため専用スレッドで行う
+        // （専用スレッドの処理は破棄できないため、中断指示を渡して途中で止める）
+        report_phase(DownloadPhase::Extracting, None, None);
+        let started = Instant::now();
+        let (zip_file, fixture_id, dest_path, cancel) = (
+            zip.file.clone(),
+            fixture_id.to_string(),
+            dest_path.to_string(),
+            cancel.clone(),
+        );
+        let mut result = run_blocking(move || {
+            Self::extract_best_file(
+                zip_file.path(),
+                &fixture_id,
+                &dest_path,
+                chosen.as_deref(),
+                &cancel,
+            )
+        })
+        .await?;
+        result.timing.bytes_transferred = zip.bytes_transferred;
+        let download_ms = zip.download_ms + started.elapsed().as_millis() as u64;
+        Ok(result
+            .with_timing(zip.lookup_ms, download_ms)
+            .with_source(zip.source))
+    }
+
+    /// ZIPファイルを開く
+    fn open_archive(zip_path: &Path) -> ProviderResult<zip::ZipArchive<File>> {
+        let file = File::open(zip_path).map_err(ProviderError::wrap(
+            ErrorCode::ZipInvalid,
+            "Failed to open ZIP",
+        ))?;
+        zip::ZipArchive::new(file).map_err(ProviderError::wrap(
+            ErrorCode::ZipInvalid,
+            "Failed to open ZIP",
+        ))
+    }
+
+    /// ZIPを展開し、最適な.iesファイルを保存（.iesファイルがなくLDTがあれば変換して保存）
+    /// 選択したエントリのみをファイルへ直接書き出す（展開中のメモリ使用量を抑える）
+    /// `chosen` にZIP内のファイル名を指定した場合は、一致度によらずそのファイルを保存する。
+    /// 中断が指示された場合は書き出し途中のファイルを削除し、`CANCELLED` のエラーを返す。
+    fn extract_best_file(
+        zip_path: &Path,
+        fixture_id: &str,
+        dest_path: &str,
+        chosen: Option<&str>,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        cancel.check()?;
+        // ZIPを開いて対象ファイル一覧を取得
+        let mut archive = Self::open_archive(zip_path)?;
+
+        // .iesファイル一覧を収集
+        let files = Self::list_files(&mut archive, ".ies");
+
+        if files.is_empty() {
+            return Self::extract_converted_ldt(&mut archive, fixture_id, dest_path, cancel);
+        }
+
+        // 最適なファイルを選択（利用者が選んだファイルがあればそれを使う）
+        if let Some(name) = chosen.filter(|name| !files.iter().any(|f| f == name)) {
+            return Err(ProviderError::new(
+                ErrorCode::ZipNoMatch,
+                format!("No matching file {} in ZIP", name),
+            ));
+        }
+        let best_file = match chosen {
+            Some(name) => name.to_string(),
+            None => matching::select_best_file(fixture_id, &files).ok_or_else(|| {
+                ProviderError::new(
+                    ErrorCode::ZipNoMatch,
+                    format!("No matching .ies file found for: {}", fixture_id),
+                )
+            })?,
+        };
+
+        // 選択したファイルを取り出す
+        let mut entry = archive.by_name(&best_file).map_err(|e| {
+            ProviderError::new(
+                ErrorCode::ZipInvalid,
+                format!("Failed to read {} from ZIP: {}", best_file, e),
+            )
+        })?;
 
         // 保存先ディレクトリを作成
-        let dest = Path::new(dest_path);
+        let dest = longpath::extended(dest_path);
         if let Some(parent) = dest.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            std::fs::create_dir_all(parent).map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
         }
 
         // ファイルを保存
-        std::fs::write(dest_path, &contents)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+        let mut dest_file = File::create(&dest).map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+        let copied = std::io::copy(
+            &mut CancellableReader {
+                inner: &mut entry,
+                cancel,
+            },
+            &mut dest_file,
+        );
+        let file_size = match copied {
+            Ok(size) => size,
+            Err(e) => {
+                drop(dest_file);
+                let _ = std::fs::remove_file(&dest);
+                cancel.check()?;
+                return Err(ProviderError::wrap(
+                    ErrorCode::FileSystem,
+                    "Failed to write file",
+                )(e));
+            }
+        };
+        // 空のファイルを成功として残さない
+        if file_size == 0 {
+            drop(dest_file);
+            let _ = std::fs::remove_file(&dest);
+            return Err(ProviderError::new(
+                ErrorCode::InvalidContent,
+                format!("{} in ZIP is empty", best_file),
+            ));
+        }
 
         // 元ファイル名（拡張子なし）を取得
         let original_filename = Path::new(&best_file)
@@ -182,11 +722,127 @@ impl TokistarProvider {
             .and_then(|n| n.to_str())
             .map(|s| s.to_string());
 
-        Ok(DownloadResult::success(
-            dest_path.to_string(),
-            file_size,
-            original_filename,
-        ))
+        let mut result =
+            DownloadResult::success(dest_path.to_string(), file_size, original_filename);
+        let others = matching::ambiguous_matches(fixture_id, &files, &best_file);
+        if others.is_empty() || chosen.is_some() {
+            return Ok(result);
+        }
+        // 手動で選び直せるよう、同程度に一致した候補を返す
+        result.candidates = std::iter::once(best_file.clone())
+            .chain(others.iter().cloned())
+            .collect();
+        Ok(result.with_warning(DownloadWarning::new(
+            DownloadWarningKind::AmbiguousZipMatch,
+            format!(
+                "Selected {} for {}, but {} also matched",
+                best_file,
+                fixture_id,
+                others.join(", ")
+            ),
+        )))
+    }
+}
+
+impl TokistarProvider {
+    /// ZIP内のEULUMDAT（LDT）から最適なファイルを選び、IESに変換して保存
+    ///
+    /// 変換元のLDTは `dest_path` の拡張子を `.ldt` にしたパスに保存する。
+    /// CIBSE TM-14のデータのみの場合は変換できないため、その旨のエラーにする。
+    fn extract_converted_ldt<R: Read + Seek>(
+        archive: &mut zip::ZipArchive<R>,
+        fixture_id: &str,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        let files = Self::list_files(archive, ".ldt");
+        if files.is_empty() {
+            let tm14 = [".tm14", ".cib"]
+                .iter()
+                .find_map(|suffix| Self::list_files(archive, suffix).into_iter().next());
+            return Ok(DownloadResult::failure(match tm14 {
+                Some(name) => format!(
+                    "No .ies files found in ZIP (CIBSE TM-14 conversion is not supported: {})",
+                    name
+                ),
+                None => "No .ies files found in ZIP".to_string(),
+            }));
+        }
+
+        let best_file = matching::select_best_file(fixture_id, &files).ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::ZipNoMatch,
+                format!("No matching .ldt file found for: {}", fixture_id),
+            )
+        })?;
+        let mut entry = archive.by_name(&best_file).map_err(|e| {
+            ProviderError::new(
+                ErrorCode::ZipInvalid,
+                format!("Failed to read {} from ZIP: {}", best_file, e),
+            )
+        })?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| {
+            ProviderError::new(
+                ErrorCode::ZipInvalid,
+                format!("Failed to read {} from ZIP: {}", best_file, e),
+            )
+        })?;
+        cancel.check()?;
+        let ies = photometry::parse_ldt(&String::from_utf8_lossy(&bytes))
+            .map_err(ProviderError::wrap(
+                ErrorCode::InvalidContent,
+                "LDT conversion failed",
+            ))?
+            .to_ies();
+
+        let dest = longpath::extended(dest_path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(ProviderError::wrap(
+                ErrorCode::FileSystem,
+                "Failed to create directory",
+            ))?;
+        }
+        let ldt_path = Path::new(dest_path).with_extension("ldt");
+        std::fs::write(&dest, &ies).map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+        std::fs::write(longpath::extended(&ldt_path), &bytes).map_err(ProviderError::wrap(
+            ErrorCode::FileSystem,
+            "Failed to write file",
+        ))?;
+
+        // ファイル名テンプレートの {original} はIESファイルとしての名前にする
+        let original_filename = Path::new(&best_file)
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .map(|stem| format!("{}.ies", stem));
+        let mut result =
+            DownloadResult::success(dest_path.to_string(), ies.len() as u64, original_filename);
+        result.converted_from = Some(ldt_path.to_string_lossy().into_owned());
+        Ok(result.with_warning(DownloadWarning::new(
+            DownloadWarningKind::ConvertedFromLdt,
+            format!(
+                "No IES file for {}; converted {} to IES",
+                fixture_id, best_file
+            ),
+        )))
+    }
+}
+
+/// 中断が指示されたら読み込みを失敗させるリーダー（ZIPのエントリの書き出しを途中で止める）
+struct CancellableReader<'a, R> {
+    inner: R,
+    cancel: &'a CancelToken,
+}
+
+impl<R: Read> Read for CancellableReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.cancel.is_cancelled() {
+            return Err(std::io::Error::other(CANCELLED));
+        }
+        self.inner.read(buf)
     }
 }
 
@@ -198,55 +854,112 @@ impl Default for TokistarProvider {
 
 #[async_trait]
 impl ManufacturerProvider for TokistarProvider {
+    fn id(&self) -> &str {
+        "tokistar"
+    }
+
     fn display_name(&self) -> &str {
         "TOKISTAR"
     }
 
-    fn can_handle(&self, manufacturer: &str) -> bool {
-        let lower = manufacturer.to_lowercase();
-        lower.contains("tokistar") || lower.contains("トキスター")
+    fn base_url(&self) -> &str {
+        &self.base_url
     }
 
-    async fn fetch_product_info(&self, model_number: &str) -> Result<ProductInfo, String> {
+    fn aliases(&self) -> Vec<&str> {
+        vec!["tokistar", "トキスター"]
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_pricing(&self) -> bool {
+        true
+    }
+
+    fn sample_model_number(&self) -> Option<&str> {
+        Some("OSP01")
+    }
+
+    async fn check_connection(&self) -> Result<(), Diagnosis> {
+        check_url(&self.client, &self.base_url).await
+    }
+
+    /// IES ZIPはダウンロードページの検索から、品名・価格・製品画像は製品ページ（シリーズ単位）から取得する
+    /// 製品ページが見つからない・取得できない場合は、品名・価格・製品画像を空のまま返す
+    async fn fetch_product_info(&self, model_number: &str) -> ProviderResult<ProductInfo> {
         let partial_id = Self::extract_partial_fixture_id(model_number);
-        let ies_file_url = self.get_ies_zip_url(&partial_id).await?;
+        let ies_file_url = self.get_ies_zip_urls(&partial_id).await?.into_iter().next();
+
+        let page = match self.fetch_product_page(&partial_id).await {
+            Ok(page) => page,
+            Err(e) => {
+                tracing::warn!(model_number, error = %e, "failed to fetch TOKISTAR product page");
+                None
+            }
+        };
+        let Some(page) = page else {
+            return Ok(ProductInfo {
+                model_number: model_number.to_string(),
+                product_name: None,
+                price: None,
+                ies_file_url,
+                image_url: None,
+                product_page_url: Some(format!(
+                    "{}/download01/?freeword={}",
+                    self.base_url, partial_id
+                )),
+                accessories: vec![],
+                discontinued: None,
+                replaces: None,
+                match_quality: None,
+            });
+        };
 
         Ok(ProductInfo {
             model_number: model_number.to_string(),
-            product_name: None,
-            price: None,
+            product_name: Self::extract_product_name(&page.html).or(page.candidate.product_name),
+            price: Self::extract_price(&page.html).or(page.candidate.price),
             ies_file_url,
-            image_url: None,
-            product_page_url: Some(format!(
-                "{}/download01/?freeword={}",
-                self.base_url, partial_id
-            )),
+            image_url: Self::extract_image_url(&page.html, &page.url),
+            product_page_url: Some(page.url),
+            accessories: vec![],
+            discontinued: None,
+            replaces: None,
+            match_quality: None,
         })
     }
 
-    fn generate_filename(
-        &self,
-        spec_no: &str,
-        model_number: &str,
-        _psu: Option<&str>,
-        original_filename: Option<&str>,
-    ) -> String {
-        // {Spec No.}_{元ファイル名}
-        // 例: "1001_OSP01_30K_30D.ies"
-        match original_filename {
-            Some(orig) => {
-                // 既に .ies 拡張子がある場合はそのまま使用
-                if orig.to_lowercase().ends_with(".ies") {
-                    format!("{}_{}", spec_no, orig)
-                } else {
-                    format!("{}_{}.ies", spec_no, orig)
-                }
-            }
-            None => {
-                let safe_model = model_number.replace('/', "_").replace('\\', "_");
-                format!("{}_{}.ies", spec_no, safe_model)
+    async fn search_products(&self, keyword: &str) -> ProviderResult<Vec<ProductCandidate>> {
+        // WordPressのサイト内検索
+        let response = send_request(
+            self.client
+                .get(format!("{}/", self.base_url))
+                .query(&[("s", keyword)]),
+        )
+        .await
+        .map_err(|e| e.context("Search request failed"))?;
+
+        let html = response.text().await.map_err(ProviderError::wrap(
+            ErrorCode::NetworkError,
+            "Failed to read response",
+        ))?;
+
+        Ok(Self::extract_product_candidates(&html))
+    }
+
+    /// 定価は製品ページ（シリーズ単位）に掲載されているため、製品ページから取得する
+    /// 製品ページに見つからない場合はサイト内検索の結果から取得する
+    async fn fetch_price(&self, model_number: &str) -> ProviderResult<Option<Price>> {
+        let partial_id = Self::extract_partial_fixture_id(model_number);
+        if let Some(page) = self.fetch_product_page(&partial_id).await? {
+            if let Some(price) = Self::extract_price(&page.html) {
+                return Ok(Some(price));
             }
         }
+        let candidates = self.search_products(&partial_id).await?;
+        Ok(price_from_candidates(&candidates, model_number))
     }
 
     async fn download_ies_file(
@@ -254,25 +967,98 @@ impl ManufacturerProvider for TokistarProvider {
         model_number: &str,
         _psu: Option<&str>, // PSUは無視
         dest_path: &str,
-    ) -> Result<DownloadResult, String> {
-        // partial_fixture_id を抽出
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        // ZIPをダウンロードして展開、最適な.iesファイルを保存
+        self.download_from_zip(model_number, dest_path, cancel)
+            .await
+    }
+
+    /// IESファイルはZIPで配布されるため、ZIPのURLとZIP内の.iesファイル候補を返す
+    async fn resolve_ies_url(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+    ) -> ProviderResult<ResolvedIesUrl> {
         let partial_id = Self::extract_partial_fixture_id(model_number);
 
-        // IES ZIPのURLを取得
-        let zip_url = self
-            .get_ies_zip_url(&partial_id)
-            .await?
-            .ok_or_else(|| format!("IES file not found for: {}", partial_id))?;
+        // 候補一覧を得るためにZIPを取得（展開・保存はしない）
+        let zip = self.fetch_zip(model_number).await?.ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::IesNotAvailable,
+                format!("IES file not found for: {}", partial_id),
+            )
+        })?;
 
-        // ZIPをダウンロードして展開、最適な.iesファイルを保存
-        self.download_and_extract_ies(&zip_url, model_number, dest_path)
-            .await
+        let zip_file = zip.file.clone();
+        let candidates = run_blocking(move || -> ProviderResult<_> {
+            let mut archive = Self::open_archive(zip_file.path())?;
+            Ok(Self::list_files(&mut archive, ".ies"))
+        })
+        .await?;
+        let selected = matching::select_best_file(model_number, &candidates);
+
+        Ok(ResolvedIesUrl {
+            url: zip.source.url,
+            candidates,
+            selected,
+        })
+    }
+
+    async fn list_zip_candidates(&self, model_number: &str) -> ProviderResult<Vec<ZipCandidate>> {
+        let partial_id = Self::extract_partial_fixture_id(model_number);
+        let zip = self.fetch_zip(model_number).await?.ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::IesNotAvailable,
+                format!("IES file not found for: {}", partial_id),
+            )
+        })?;
+
+        let zip_file = zip.file.clone();
+        let files = run_blocking(move || -> ProviderResult<_> {
+            let mut archive = Self::open_archive(zip_file.path())?;
+            Ok(Self::list_files(&mut archive, ".ies"))
+        })
+        .await?;
+        Ok(matching::rank_candidates(model_number, &files))
+    }
+
+    /// IESファイルはZIPで配布されるため、ZIP全体のサイズを返す
+    async fn fetch_file_size(&self, url: &str) -> Option<u64> {
+        fetch_content_length(&self.client, url).await
+    }
+
+    fn supported_assets(&self) -> Vec<AssetType> {
+        vec![AssetType::Ies, AssetType::Image]
+    }
+
+    async fn download_asset(
+        &self,
+        model_number: &str,
+        _psu: Option<&str>,
+        asset_type: AssetType,
+        dest_path: &str,
+        cancel: &CancelToken,
+    ) -> ProviderResult<DownloadResult> {
+        if asset_type != AssetType::Image {
+            return Err(not_provided(self.display_name(), asset_type));
+        }
+        download_product_image(self, &self.client, model_number, dest_path, cancel).await
+    }
+
+    fn begin_batch(&self) {
+        self.zip_cache.begin();
+    }
+
+    fn end_batch(&self) {
+        self.zip_cache.end();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filename::{self, FilenameContext};
 
     #[test]
     fn test_can_handle() {
@@ -306,89 +1092,324 @@ mod tests {
     }
 
     #[test]
-    fn test_common_prefix_length() {
-        assert_eq!(TokistarProvider::common_prefix_length("OSP01_30K", "OSP01_30K_30D"), 9);
-        assert_eq!(TokistarProvider::common_prefix_length("OSP01_30K_30D", "OSP01_27K"), 6);
-        assert_eq!(TokistarProvider::common_prefix_length("ABC", "XYZ"), 0);
-        assert_eq!(TokistarProvider::common_prefix_length("", "OSP01"), 0);
+    fn test_extract_product_candidates() {
+        let html = r#"
+            <article>
+              <a href="https://toki.co.jp/tokistar/products/osp01/">
+                <h2>OSP01</h2><p>屋外用スポットライト ¥28,000</p>
+              </a>
+              <a href="https://toki.co.jp/tokistar/products/osp01/">詳細</a>
+              <a href="https://toki.co.jp/tokistar/products/mrd01">MRD01</a>
+            </article>
+        "#;
+
+        let candidates = TokistarProvider::extract_product_candidates(html);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].model_number, "OSP01");
+        assert_eq!(
+            candidates[0].product_name.as_deref(),
+            Some("OSP01 屋外用スポットライト ¥28,000")
+        );
+        assert_eq!(
+            candidates[0].price,
+            Some(Price::jpy(28000).assume_tax_included(false))
+        );
+        assert_eq!(candidates[1].model_number, "MRD01");
+        assert_eq!(candidates[1].price, None);
     }
 
     #[test]
-    fn test_select_best_ies_file() {
-        // フラットなファイル名
-        let ies_files = vec![
-            "OSP01_27K.ies".to_string(),
-            "OSP01_30K_30D.ies".to_string(),
-            "OSP01.ies".to_string(),
+    fn test_extract_product_details() {
+        let html = r#"
+            <html><head>
+              <meta property="og:image" content="/tokistar/wp-content/uploads/2023/04/osp01.jpg">
+            </head><body>
+              <h1 class="entry-title">OSP01</h1>
+              <table>
+                <tr><th>品　名</th><td>屋外用スポットライト</td></tr>
+                <tr><th>価格</th><td>¥28,000（税抜）</td></tr>
+              </table>
+            </body></html>
+        "#;
+        let page_url = "https://toki.co.jp/tokistar/products/osp01/";
+
+        assert_eq!(
+            TokistarProvider::extract_product_name(html).as_deref(),
+            Some("屋外用スポットライト")
+        );
+        assert_eq!(
+            TokistarProvider::extract_price(html),
+            Some(Price::jpy(28000).assume_tax_included(false))
+        );
+        assert_eq!(
+            TokistarProvider::extract_image_url(html, page_url).as_deref(),
+            Some("https://toki.co.jp/tokistar/wp-content/uploads/2023/04/osp01.jpg")
+        );
+
+        // 仕様表に品名がない場合はページの見出し
+        let html = r#"<h1 class="entry-title">MRD01 ミニダウンライト</h1><p>価格はお問い合わせください</p>"#;
+        assert_eq!(
+            TokistarProvider::extract_product_name(html).as_deref(),
+            Some("MRD01 ミニダウンライト")
+        );
+        assert_eq!(TokistarProvider::extract_price(html), None);
+    }
+
+    #[test]
+    fn test_select_best_archive() {
+        // 同じシリーズが配光角ごとに別のZIPに分かれている場合
+        let archives = vec![
+            vec![
+                "IES_OSP/OSP01_30K_15D.ies".to_string(),
+                "IES_OSP/OSP01_27K_15D.ies".to_string(),
+            ],
+            vec![
+                "IES_OSP_30D/OSP01_30K_30D.ies".to_string(),
+                "IES_OSP_30D/OSP01_27K_30D.ies".to_string(),
+            ],
         ];
+        assert_eq!(
+            TokistarProvider::select_best_archive("OSP01-30K-30D-B-TB", &archives),
+            1
+        );
+        assert_eq!(
+            TokistarProvider::select_best_archive("OSP01-27K-15D", &archives),
+            0
+        );
+        // 一致の長さが同じ場合は先に掲載されたもの
+        assert_eq!(TokistarProvider::select_best_archive("OSP01", &archives), 0);
+        assert_eq!(TokistarProvider::select_best_archive("OSP01", &[]), 0);
+    }
 
-        // OSP01-30K-30D-B-TB → OSP01_30K_30D_B_TB
-        // 最も長く一致する OSP01_30K_30D.ies が選ばれるべき
-        let result = TokistarProvider::select_best_ies_file("OSP01-30K-30D-B-TB", &ies_files);
-        assert_eq!(result, Some("OSP01_30K_30D.ies".to_string()));
+    #[test]
+    fn test_has_unrecognized_ies_link() {
+        // ファイル名の付け方が変わった（IES_ で始まらない）
+        let html = r#"<a href="https://toki.co.jp/tokistar/wp-content/uploads/2025/01/osp01-ies.zip">OSP01 配光データ</a>"#;
+        assert!(TokistarProvider::has_unrecognized_ies_link(html, "OSP01"));
+        // CADデータのみ・別の型番の配光データ
+        let html =
+            r#"<a href="/uploads/CAD_OSP01.zip">CAD</a><a href="/uploads/mrd01-ies.zip">IES</a>"#;
+        assert!(!TokistarProvider::has_unrecognized_ies_link(html, "OSP01"));
+    }
 
-        // OSP01-27K → OSP01_27K
-        let result = TokistarProvider::select_best_ies_file("OSP01-27K", &ies_files);
-        assert_eq!(result, Some("OSP01_27K.ies".to_string()));
+    #[test]
+    fn test_next_page_url() {
+        let current = "https://toki.co.jp/tokistar/download01/?freeword=OSP";
+        let html = r#"
+            <div class="nav-links">
+              <span class="page-numbers current">1</span>
+              <a class="page-numbers" href="/tokistar/download01/page/2/?freeword=OSP">2</a>
+              <a class="next page-numbers" href="/tokistar/download01/page/2/?freeword=OSP">次へ</a>
+            </div>
+        "#;
+        assert_eq!(
+            TokistarProvider::next_page_url(html, current).as_deref(),
+            Some("https://toki.co.jp/tokistar/download01/page/2/?freeword=OSP")
+        );
+        assert_eq!(
+            TokistarProvider::next_page_url("<p>no pages</p>", current),
+            None
+        );
+    }
+
+    #[test]
+    fn test_list_files() {
+        use std::io::Write;
 
-        // OSP01 → OSP01
-        let result = TokistarProvider::select_best_ies_file("OSP01", &ies_files);
-        // OSP01.ies, OSP01_27K.ies, OSP01_30K_30D.ies 全て5文字一致
-        // max_by_key は最後に見つかった最大値を返すため、実装依存
-        assert!(result.is_some());
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for name in [
+            "IES_OSP/OSP01_27K.IES",
+            "IES_OSP/OSP01_27K.pdf",
+            "readme.txt",
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"data").unwrap();
+        }
+        let mut archive = zip::ZipArchive::new(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            TokistarProvider::list_files(&mut archive, ".ies"),
+            vec!["IES_OSP/OSP01_27K.IES".to_string()]
+        );
     }
 
     #[test]
-    fn test_select_best_ies_file_with_path() {
-        // 実際のZIPのようにパス付きファイル名
-        let ies_files = vec![
-            "IES_OSP/OSP01_27K_15D.ies".to_string(),
-            "IES_OSP/OSP01_30K_30D.ies".to_string(),
-            "IES_OSP/HL/OSP01_30K-HL_30D_HL.ies".to_string(),
-        ];
+    fn test_zip_cache_within_batch() {
+        use std::io::Write;
+
+        // 展開は専用スレッドで行うためTokioランタイム上で実行する
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let mut writer = zip::ZipWriter::new(NamedTempFile::new().unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["IES_OSP/OSP01_27K.ies", "IES_OSP/OSP01_30K.ies"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"data").unwrap();
+        }
+        let zip_file = Arc::new(ZipFile::Temp(writer.finish().unwrap()));
+        let zip_url = "https://toki.co.jp/tokistar/IES_OSP.zip".to_string();
+        let source = DownloadSource {
+            url: zip_url.clone(),
+            status: Some(200),
+            content_type: Some("application/zip".to_string()),
+            last_modified: Some("Wed, 01 Oct 2025 00:00:00 GMT".to_string()),
+        };
 
-        // OSP01-30K-30D → OSP01_30K_30D
-        let result = TokistarProvider::select_best_ies_file("OSP01-30K-30D", &ies_files);
-        assert_eq!(result, Some("IES_OSP/OSP01_30K_30D.ies".to_string()));
+        // 一括ダウンロード中以外は保持しない
+        let provider = TokistarProvider::new();
+        provider.zip_cache.insert("OSP01".to_string(), Vec::new());
+        assert!(provider.zip_cache.get("OSP01").is_none());
+
+        // 一括ダウンロード中は取得済みのZIPを再利用する（通信しない）
+        provider.begin_batch();
+        provider
+            .zip_cache
+            .insert("OSP01".to_string(), vec![(source.clone(), zip_file)]);
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("A-1.ies");
+        let result = runtime
+            .block_on(provider.download_from_zip(
+                "OSP01-30K-30D",
+                dest.to_str().unwrap(),
+                &CancelToken::new(),
+            ))
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.original_filename.as_deref(), Some("OSP01_30K.ies"));
+        assert_eq!(std::fs::read(&dest).unwrap(), b"data");
+        assert_eq!(result.timing.bytes_transferred, 0);
+        assert_eq!(result.source, Some(source));
+        assert_eq!(
+            runtime
+                .block_on(provider.resolve_ies_url("OSP01-27K", None))
+                .unwrap()
+                .url,
+            zip_url
+        );
+
+        provider.end_batch();
+        assert!(provider.zip_cache.get("OSP01").is_none());
+    }
+
+    #[test]
+    fn test_zip_disk_cache() {
+        let temp = tempfile::tempdir().unwrap();
+        let url = "https://toki.co.jp/tokistar/wp-content/uploads/2024/01/IES_OSP.zip";
+        let last_modified = "Wed, 10 Jan 2024 01:00:00 GMT";
+        assert!(TokistarProvider::load_cached_zip(temp.path(), url).is_none());
 
-        // OSP01-27K-15D → OSP01_27K_15D
-        let result = TokistarProvider::select_best_ies_file("OSP01-27K-15D", &ies_files);
-        assert_eq!(result, Some("IES_OSP/OSP01_27K_15D.ies".to_string()));
+        let mut file = NamedTempFile::new_in(temp.path()).unwrap();
+        std::io::Write::write_all(&mut file, b"zip").unwrap();
+        let stored = TokistarProvider::store_cached_zip(temp.path(), url, last_modified, file);
+        assert!(matches!(stored, ZipFile::Cached(_)));
+
+        let (path, cached_last_modified) =
+            TokistarProvider::load_cached_zip(temp.path(), url).unwrap();
+        assert_eq!(path, stored.path());
+        assert_eq!(cached_last_modified, last_modified);
+        assert_eq!(std::fs::read(&path).unwrap(), b"zip");
+
+        // URLごとに保存する
+        let other = "https://toki.co.jp/tokistar/wp-content/uploads/2024/01/IES_MRD.zip";
+        assert!(TokistarProvider::load_cached_zip(temp.path(), other).is_none());
     }
 
     #[test]
-    fn test_select_best_ies_file_no_match() {
-        let ies_files = vec!["ABC123.ies".to_string()];
+    fn test_extract_converted_ldt() {
+        use std::io::Write;
+
+        let ies = photometry::parse_ies(
+            "IESNA:LM-63-2002\r\n[MANUFAC] TOKISTAR\r\nTILT=NONE\r\n\
+             1 1000 1 3 1 1 2 -0.1 0 0\r\n1 1 12.5\r\n0 45 90\r\n0\r\n300 200 0\r\n",
+        )
+        .unwrap();
+        let ldt = ies.to_eulumdat("OSP01", "OSP01_30K.ldt");
+
+        let temp = tempfile::tempdir().unwrap();
+        let zip_path = temp.path().join("IES_OSP.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("LDT_OSP/OSP01_30K.ldt", options).unwrap();
+        writer.write_all(ldt.as_bytes()).unwrap();
+        writer.finish().unwrap();
 
-        let result = TokistarProvider::select_best_ies_file("XYZ999", &ies_files);
-        assert_eq!(result, None);
+        let dest = temp.path().join("A-1.ies");
+        let result = TokistarProvider::extract_best_file(
+            &zip_path,
+            "OSP01-30K",
+            &dest.to_string_lossy(),
+            None,
+            &CancelToken::new(),
+        )
+        .unwrap();
+        assert!(result.success);
+        assert_eq!(result.original_filename.as_deref(), Some("OSP01_30K.ies"));
+        assert_eq!(
+            result.warnings[0].kind,
+            DownloadWarningKind::ConvertedFromLdt
+        );
+        assert!(temp.path().join("A-1.ldt").exists());
+        let converted = photometry::parse_ies(&std::fs::read_to_string(&dest).unwrap()).unwrap();
+        assert_eq!(converted.candela, ies.candela);
+
+        // 中断が指示されていれば展開しない
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let dest = temp.path().join("A-2.ies");
+        let result = TokistarProvider::extract_best_file(
+            &zip_path,
+            "OSP01-30K",
+            &dest.to_string_lossy(),
+            None,
+            &cancel,
+        );
+        assert_eq!(result.unwrap_err(), crate::providers::cancelled());
+        assert!(!dest.exists());
     }
 
     #[test]
-    fn test_generate_filename() {
+    fn test_default_filename_template() {
         let provider = TokistarProvider::new();
+        let generate_filename = |model_number, psu, original_filename| {
+            let context = FilenameContext {
+                spec_no: "1001",
+                manufacturer: provider.display_name(),
+                model_number,
+                psu,
+                original_filename,
+                photometry: None,
+            };
+            filename::render(
+                provider.default_filename_template(AssetType::Ies),
+                AssetType::Ies,
+                &context,
+            )
+        };
 
         // 元ファイル名あり（.ies付き）
         assert_eq!(
-            provider.generate_filename("1001", "OSP01-30K", None, Some("OSP01_30K_30D.ies")),
+            generate_filename("OSP01-30K", None, Some("OSP01_30K_30D.ies")),
             "1001_OSP01_30K_30D.ies"
         );
 
         // 元ファイル名あり（.iesなし）
         assert_eq!(
-            provider.generate_filename("1001", "OSP01-30K", None, Some("OSP01_30K_30D")),
+            generate_filename("OSP01-30K", None, Some("OSP01_30K_30D")),
             "1001_OSP01_30K_30D.ies"
         );
 
         // 元ファイル名なし
         assert_eq!(
-            provider.generate_filename("1001", "OSP01-30K", None, None),
+            generate_filename("OSP01-30K", None, None),
             "1001_OSP01-30K.ies"
         );
 
         // PSUは無視される
         assert_eq!(
-            provider.generate_filename("1001", "OSP01", Some("PSU123"), Some("OSP01.ies")),
+            generate_filename("OSP01", Some("PSU123"), Some("OSP01.ies")),
             "1001_OSP01.ies"
         );
     }
@@ -0,0 +1,234 @@
+//! ダウンロード結果レポート
+//!
+//! ダウンロード履歴をCSV・JSON・HTML形式のレポートに変換する。
+
+use crate::history::HistoryEntry;
+use crate::i18n::{Locale, Message};
+use serde::{Deserialize, Serialize};
+
+/// レポートの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    Csv,
+    Json,
+    Html,
+}
+
+/// レポートの列見出し
+fn columns(locale: Locale) -> [String; 8] {
+    [
+        "Spec No.".to_string(),
+        Message::ColumnManufacturer.text(locale),
+        Message::ColumnModel.text(locale),
+        Message::ColumnAsset.text(locale),
+        Message::ColumnResult.text(locale),
+        Message::ColumnFile.text(locale),
+        Message::ColumnError.text(locale),
+        Message::ColumnDate.text(locale),
+    ]
+}
+
+/// 1行分の値（列見出しと同じ順序）
+fn row(entry: &HistoryEntry) -> [String; 8] {
+    [
+        entry.spec_no.clone(),
+        entry.manufacturer.clone(),
+        entry.model_number.clone(),
+        serde_json::to_value(entry.asset_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        if entry.success { "OK" } else { "NG" }.to_string(),
+        entry.file_path.clone().unwrap_or_default(),
+        entry.error.clone().unwrap_or_default(),
+        entry.downloaded_at.to_rfc3339(),
+    ]
+}
+
+/// 履歴をレポートに変換
+///
+/// `title` はHTMLレポートの見出しに使用する。列見出し等は `locale` の言語で出力する。
+pub fn render(
+    entries: &[HistoryEntry],
+    format: ReportFormat,
+    title: &str,
+    locale: Locale,
+) -> Result<String, String> {
+    match format {
+        ReportFormat::Csv => Ok(render_csv(entries, locale)),
+        ReportFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize report: {}", e)),
+        ReportFormat::Html => Ok(render_html(entries, title, locale)),
+    }
+}
+
+/// CSVの値をエスケープ（カンマ・改行・ダブルクォートを含む場合はクォート）
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV形式（Excelで開けるようBOM付きUTF-8、CRLF改行）
+fn render_csv(entries: &[HistoryEntry], locale: Locale) -> String {
+    let mut out = String::from("\u{feff}");
+    out.push_str(&columns(locale).join(","));
+    out.push_str("\r\n");
+    for entry in entries {
+        let fields: Vec<_> = row(entry).iter().map(|v| csv_field(v)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// 監査用のCSV（レポートの列に取得元URL・SHA-256を加える。BOM付きUTF-8、CRLF改行）
+pub fn render_audit_csv(entries: &[HistoryEntry], locale: Locale) -> String {
+    let mut out = String::from("\u{feff}");
+    let mut header = columns(locale).to_vec();
+    header.extend(["URL".to_string(), "SHA-256".to_string()]);
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for entry in entries {
+        let mut values = row(entry).to_vec();
+        values.push(
+            entry
+                .source
+                .as_ref()
+                .map(|s| s.url.clone())
+                .unwrap_or_default(),
+        );
+        values.push(entry.sha256.clone().unwrap_or_default());
+        let fields: Vec<_> = values.iter().map(|v| csv_field(v)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// HTMLの特殊文字をエスケープ
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// HTML形式（単体で閲覧・印刷できる表）
+fn render_html(entries: &[HistoryEntry], title: &str, locale: Locale) -> String {
+    let success_count = entries.iter().filter(|e| e.success).count();
+    let title = escape_html(title);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n",
+        locale.code()
+    ));
+    out.push_str(&format!("<title>{}</title>\n", title));
+    out.push_str(
+        "<style>body{font-family:sans-serif}table{border-collapse:collapse}\
+         th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         tr.ng td{background:#fdecea}</style>\n",
+    );
+    out.push_str("</head>\n<body>\n");
+    out.push_str(&format!("<h1>{}</h1>\n", title));
+    let summary = Message::ReportSummary {
+        success: success_count,
+        failure: entries.len() - success_count,
+        total: entries.len(),
+    };
+    out.push_str(&format!("<p>{}</p>\n", summary.text(locale)));
+    out.push_str("<table>\n<thead><tr>");
+    for column in columns(locale) {
+        out.push_str(&format!("<th>{}</th>", column));
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for entry in entries {
+        let class = if entry.success { "ok" } else { "ng" };
+        out.push_str(&format!("<tr class=\"{}\">", class));
+        for value in row(entry) {
+            out.push_str(&format!("<td>{}</td>", escape_html(&value)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetType;
+
+    fn entry(spec_no: &str, success: bool, error: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            project_id: None,
+            spec_no: spec_no.to_string(),
+            manufacturer: "Koizumi".to_string(),
+            model_number: "AD12345".to_string(),
+            psu: None,
+            asset_type: AssetType::Ies,
+            success,
+            file_path: None,
+            original_filename: None,
+            sha256: None,
+            source: None,
+            provider: None,
+            error: error.map(str::to_string),
+            downloaded_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let entries = [
+            entry("1001", true, None),
+            entry("1002", false, Some("Not found, \"AD12345\"")),
+        ];
+        let csv = render(&entries, ReportFormat::Csv, "", Locale::Ja).unwrap();
+        let lines: Vec<_> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+
+        assert_eq!(
+            lines[0],
+            "Spec No.,メーカー,型番,アセット,結果,ファイル,エラー,日時"
+        );
+        assert!(lines[1].starts_with("1001,Koizumi,AD12345,ies,OK,,,"));
+        assert!(lines[2].contains(",NG,,\"Not found, \"\"AD12345\"\"\","));
+    }
+
+    #[test]
+    fn test_render_audit_csv() {
+        let mut ok = entry("1001", true, None);
+        ok.source = Some(crate::providers::DownloadSource {
+            url: "https://example.com/a.ies".to_string(),
+            ..Default::default()
+        });
+        ok.sha256 = Some("abc123".to_string());
+        let csv = render_audit_csv(&[ok], Locale::En);
+        let lines: Vec<_> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+
+        assert!(lines[0].ends_with(",URL,SHA-256"));
+        assert!(lines[1].ends_with(",https://example.com/a.ies,abc123"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let entries = [entry("<1001>", false, Some("a & b"))];
+        let html = render(&entries, ReportFormat::Html, "Project <A>", Locale::Ja).unwrap();
+
+        assert!(html.contains("<h1>Project &lt;A&gt;</h1>"));
+        assert!(html.contains("<td>&lt;1001&gt;</td>"));
+        assert!(html.contains("<td>a &amp; b</td>"));
+        assert!(html.contains("成功: 0 / 失敗: 1 / 合計: 1"));
+
+        let html = render(&entries, ReportFormat::Html, "Project", Locale::En).unwrap();
+        assert!(html.contains("<html lang=\"en\">"));
+        assert!(html.contains("<th>Manufacturer</th>"));
+        assert!(html.contains("Succeeded: 0 / Failed: 1 / Total: 1"));
+    }
+}
@@ -0,0 +1,457 @@
+//! プロバイダールールの更新（アプリ本体とは別の更新チャンネル）
+//!
+//! メーカーサイトの変更への対応（スクレイピングのルールの修正）をアプリのリリースを待たずに
+//! 届けるため、設定したフィードからプロバイダールールのバンドル（JSON）を定期的に取得する。
+//!
+//! - バンドルはSHA-256を検証してから `{アプリのデータディレクトリ}/provider-rules/{version}.json` に保存する
+//! - バージョンを固定した場合は、フィードに新しいバージョンがあっても固定したバージョンを使う
+//! - 直前に使っていたバージョンを保持し、更新後に問題があればロールバックできる
+//! - 使用中のバンドルの定義は、定義ファイルのプロバイダー（[`crate::providers::generic`]）として
+//!   登録する。切り替え・ロールバックの後は呼び出し元でプロバイダーを登録し直す
+//!
+//! フィードの形式:
+//!
+//! ```json
+//! {
+//!   "versions": [
+//!     {
+//!       "version": "2024.06.01-1",
+//!       "url": "https://example.com/rules/2024.06.01-1.json",
+//!       "sha256": "…",
+//!       "minAppVersion": "0.2.0"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `versions` は新しい順に並べる。先頭から、アプリのバージョンが `minAppVersion` 以上のものを使う。
+//!
+//! バンドルの形式（`providers` は定義ファイルと同じ形式の定義の配列）:
+//!
+//! ```json
+//! { "providers": [{ "id": "example", "displayName": "Example照明", … }] }
+//! ```
+
+use crate::portable::{self, STORE_NAME};
+use crate::providers::generic::RulesBundle;
+use crate::providers::send_request;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 更新状態を保存するキー
+const STATE_KEY: &str = "providerRules";
+/// ロールバック用に保持する過去のバージョン数
+const MAX_HISTORY: usize = 5;
+/// 確認間隔の範囲（時間）
+const MIN_CHECK_INTERVAL_HOURS: u32 = 1;
+const MAX_CHECK_INTERVAL_HOURS: u32 = 168;
+
+/// プロバイダールールの更新設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RulesUpdateSettings {
+    /// 定期的に更新を確認する（既定は無効）
+    pub enabled: bool,
+    /// フィードのURL
+    pub feed_url: Option<String>,
+    /// 固定するバージョン（未指定時は最新を使う）
+    pub pinned_version: Option<String>,
+    /// 確認間隔（時間）
+    pub check_interval_hours: u32,
+}
+
+impl Default for RulesUpdateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feed_url: None,
+            pinned_version: None,
+            check_interval_hours: 6,
+        }
+    }
+}
+
+impl RulesUpdateSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_CHECK_INTERVAL_HOURS..=MAX_CHECK_INTERVAL_HOURS)
+            .contains(&self.check_interval_hours)
+        {
+            return Err(format!(
+                "rulesUpdate.checkIntervalHours must be between {} and {}",
+                MIN_CHECK_INTERVAL_HOURS, MAX_CHECK_INTERVAL_HOURS
+            ));
+        }
+        if let Some(version) = &self.pinned_version {
+            validate_version(version)?;
+        }
+        match &self.feed_url {
+            Some(url) => {
+                let url = reqwest::Url::parse(url)
+                    .map_err(|e| format!("Invalid rules feed URL: {}", e))?;
+                if url.scheme() != "https" {
+                    return Err("rulesUpdate.feedUrl must use https".to_string());
+                }
+            }
+            None if self.enabled => {
+                return Err("rulesUpdate.feedUrl is required when enabled".to_string())
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// フィード
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RulesFeed {
+    versions: Vec<RulesRelease>,
+}
+
+/// フィードに掲載されたバージョン
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RulesRelease {
+    version: String,
+    url: String,
+    sha256: String,
+    #[serde(default)]
+    min_app_version: Option<String>,
+}
+
+/// 更新状態（`get_rules_status` の戻り値）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesStatus {
+    /// 使用中のバージョン（未取得の場合は None = アプリに組み込みのルール）
+    pub active: Option<String>,
+    /// ロールバックできる過去のバージョン（新しい順）
+    pub history: Vec<String>,
+    /// 最後に確認した日時
+    pub last_checked: Option<DateTime<Utc>>,
+    /// 最後の確認で発生したエラー
+    pub last_error: Option<String>,
+}
+
+/// バージョン文字列を検証（ファイル名に使うため）
+fn validate_version(version: &str) -> Result<(), String> {
+    let valid = !version.is_empty()
+        && version.len() <= 64
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid rules version: {}", version))
+    }
+}
+
+/// `x.y.z` 形式のバージョンを比較用の数値に変換（数値でない部分は0とみなす）
+fn parse_app_version(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '+'])
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// 使用するバージョンを選ぶ
+fn select_release<'a>(
+    feed: &'a RulesFeed,
+    app_version: &str,
+    pinned: Option<&str>,
+) -> Result<&'a RulesRelease, String> {
+    if let Some(pinned) = pinned {
+        return feed
+            .versions
+            .iter()
+            .find(|release| release.version == pinned)
+            .ok_or_else(|| format!("Pinned rules version not found in feed: {}", pinned));
+    }
+    let app_version = parse_app_version(app_version);
+    feed.versions
+        .iter()
+        .find(|release| {
+            release
+                .min_app_version
+                .as_deref()
+                .is_none_or(|min| parse_app_version(min) <= app_version)
+        })
+        .ok_or_else(|| "No rules version compatible with this app version".to_string())
+}
+
+fn load_status<R: Runtime>(app: &AppHandle<R>) -> RulesStatus {
+    app.store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(STATE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_status<R: Runtime>(app: &AppHandle<R>, status: &RulesStatus) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        STATE_KEY,
+        serde_json::to_value(status).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save rules status: {}", e))
+}
+
+/// バンドルの保存先ディレクトリ
+fn rules_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    portable::data_dir(app).map(|dir| dir.join("provider-rules"))
+}
+
+/// 現在の更新状態
+pub fn status<R: Runtime>(app: &AppHandle<R>) -> RulesStatus {
+    load_status(app)
+}
+
+/// 使用中のバンドルのパス（未取得の場合は `None` = アプリに組み込みのルールのみ）
+pub fn active_bundle<R: Runtime>(app: &AppHandle<R>) -> Result<Option<PathBuf>, String> {
+    let Some(version) = load_status(app).active else {
+        return Ok(None);
+    };
+    Ok(Some(rules_dir(app)?.join(format!("{}.json", version))))
+}
+
+/// 確認間隔が経過しているか
+fn is_due(status: &RulesStatus, settings: &RulesUpdateSettings, now: DateTime<Utc>) -> bool {
+    status.last_checked.is_none_or(|checked| {
+        now - checked >= Duration::hours(settings.check_interval_hours as i64)
+    })
+}
+
+/// 確認間隔が経過していれば更新を確認
+pub async fn check_if_due<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    settings: &RulesUpdateSettings,
+) -> Result<RulesStatus, String> {
+    let status = load_status(app);
+    if !settings.enabled || !is_due(&status, settings, Utc::now()) {
+        return Ok(status);
+    }
+    check(app, client, settings).await
+}
+
+/// 更新を確認し、使用するバージョンが変わった場合は取得して切り替える
+///
+/// 確認の結果（エラーを含む）は更新状態に記録する。
+pub async fn check<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    settings: &RulesUpdateSettings,
+) -> Result<RulesStatus, String> {
+    let mut status = load_status(app);
+    let result = update(app, client, settings, &mut status).await;
+    status.last_checked = Some(Utc::now());
+    status.last_error = result.as_ref().err().cloned();
+    save_status(app, &status)?;
+    result.map(|()| status)
+}
+
+async fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    settings: &RulesUpdateSettings,
+    status: &mut RulesStatus,
+) -> Result<(), String> {
+    let feed_url = settings
+        .feed_url
+        .as_deref()
+        .ok_or("No rules feed URL configured")?;
+    let response = send_request(client.get(feed_url))
+        .await
+        .map_err(|e| format!("Rules feed request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Rules feed returned status: {}", response.status()));
+    }
+    let feed: RulesFeed = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid rules feed: {}", e))?;
+
+    let app_version = app.package_info().version.to_string();
+    let release = select_release(&feed, &app_version, settings.pinned_version.as_deref())?;
+    validate_version(&release.version)?;
+    if status.active.as_deref() == Some(release.version.as_str()) {
+        return Ok(());
+    }
+
+    let dir = rules_dir(app)?;
+    let path = dir.join(format!("{}.json", release.version));
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        let bytes = download_bundle(client, release).await?;
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+        tokio::fs::write(&path, &bytes)
+            .await
+            .map_err(|e| format!("Failed to save rules: {}", e))?;
+    }
+    tracing::info!(
+        from = status.active.as_deref(),
+        to = %release.version,
+        "provider rules updated"
+    );
+    activate(status, release.version.clone());
+    prune(&dir, status).await;
+    Ok(())
+}
+
+/// バンドルを取得し、ハッシュとJSONの形式を検証
+async fn download_bundle(
+    client: &reqwest::Client,
+    release: &RulesRelease,
+) -> Result<Vec<u8>, String> {
+    let response = send_request(client.get(&release.url))
+        .await
+        .map_err(|e| format!("Rules download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Rules download returned status: {}",
+            response.status()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read rules: {}", e))?;
+    let digest = format!("{:x}", Sha256::digest(&bytes));
+    if !digest.eq_ignore_ascii_case(release.sha256.trim()) {
+        return Err(format!("Rules checksum mismatch for {}", release.version));
+    }
+    RulesBundle::parse(&bytes)?;
+    Ok(bytes.to_vec())
+}
+
+/// バージョンを切り替え、それまでのバージョンを履歴の先頭に追加
+fn activate(status: &mut RulesStatus, version: String) {
+    status.history.retain(|v| *v != version);
+    if let Some(previous) = status.active.replace(version) {
+        status.history.retain(|v| *v != previous);
+        status.history.insert(0, previous);
+    }
+    status.history.truncate(MAX_HISTORY);
+}
+
+/// 使用中・履歴にないバンドルを削除
+async fn prune(dir: &Path, status: &RulesStatus) {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let Some(version) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        if status.active.as_deref() != Some(version) && !status.history.iter().any(|v| v == version)
+        {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+    }
+}
+
+/// 直前のバージョンに戻す
+///
+/// 履歴がない場合はアプリに組み込みのルールに戻す。戻したバージョンは次の更新確認で
+/// 再び最新に切り替わらないよう、固定する場合は設定の `pinnedVersion` を指定する。
+pub fn rollback<R: Runtime>(app: &AppHandle<R>) -> Result<RulesStatus, String> {
+    let mut status = load_status(app);
+    if status.active.is_none() {
+        return Err("No provider rules update to roll back".to_string());
+    }
+    let dir = rules_dir(app)?;
+    let previous = status
+        .history
+        .iter()
+        .position(|version| dir.join(format!("{}.json", version)).exists());
+    let rolled_back = status.active.take();
+    status.active = previous.map(|index| status.history.remove(index));
+    tracing::info!(
+        from = rolled_back.as_deref(),
+        to = status.active.as_deref(),
+        "provider rules rolled back"
+    );
+    save_status(app, &status)?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed() -> RulesFeed {
+        serde_json::from_str(
+            r#"{ "versions": [
+                { "version": "2024.06.02", "url": "https://e/3", "sha256": "c", "minAppVersion": "0.3.0" },
+                { "version": "2024.06.01", "url": "https://e/2", "sha256": "b", "minAppVersion": "0.2.0" },
+                { "version": "2024.05.20", "url": "https://e/1", "sha256": "a" }
+            ] }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_select_release() {
+        let feed = feed();
+        assert_eq!(
+            select_release(&feed, "0.2.0", None).unwrap().version,
+            "2024.06.01"
+        );
+        assert_eq!(
+            select_release(&feed, "0.10.1", None).unwrap().version,
+            "2024.06.02"
+        );
+        assert_eq!(
+            select_release(&feed, "0.3.0", Some("2024.05.20"))
+                .unwrap()
+                .version,
+            "2024.05.20"
+        );
+        assert!(select_release(&feed, "0.2.0", Some("2023.01.01")).is_err());
+    }
+
+    #[test]
+    fn test_activate() {
+        let mut status = RulesStatus::default();
+        activate(&mut status, "1".to_string());
+        activate(&mut status, "2".to_string());
+        activate(&mut status, "3".to_string());
+        assert_eq!(status.active.as_deref(), Some("3"));
+        assert_eq!(status.history, vec!["2", "1"]);
+
+        // 履歴にあるバージョンに戻した場合は重複させない
+        activate(&mut status, "1".to_string());
+        assert_eq!(status.history, vec!["3", "2"]);
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        let mut settings = RulesUpdateSettings::default();
+        assert!(settings.validate().is_ok());
+        settings.enabled = true;
+        assert!(settings.validate().is_err());
+        settings.feed_url = Some("http://example.com/feed.json".to_string());
+        assert!(settings.validate().is_err());
+        settings.feed_url = Some("https://example.com/feed.json".to_string());
+        assert!(settings.validate().is_ok());
+        settings.pinned_version = Some("../etc".to_string());
+        assert!(settings.validate().is_err());
+    }
+}
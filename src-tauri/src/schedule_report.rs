@@ -0,0 +1,666 @@
+//! 器具リスト（見積もり用の一覧表）
+//!
+//! 読み込んだ器具リストの各行に、ダウンロード済みのIESファイルの配光データ（器具光束・
+//! 消費電力・ビーム角・屋外器具向けのゾーナル光束とBUG評価）と取得済みの製品情報（品名・定価）を結合し、CSV・JSON・HTMLで出力する。
+//! 見積もり担当者が手作業でまとめている器具リストを作るためのもの。
+//! CSVはExcelでそのまま開け、HTMLはブラウザーの印刷機能でPDFにできる。
+//!
+//! あわせて、一括ダウンロードの結果から接続負荷（IESファイルの入力電力 × 台数）を
+//! メーカー・設置エリアごとに集計する（省エネ基準の届出資料用）。
+//! 器具リストに消費電力の列がある場合は、IESファイルの入力電力と照合し、許容差を超える
+//! 行を警告する（別の出力・色温度のバリエーションのIESファイルを取得した場合の検出用）。
+//! 定価（取得済みの製品情報）× 台数もアイテム・メーカーごとに集計する（概算見積もり用）。
+
+use crate::excel::ImportedRow;
+use crate::history::HistoryEntry;
+use crate::i18n::{Locale, Message};
+use crate::photometry::{self, BugRating, ZonalLumens};
+use crate::providers::{AssetType, Currency, Price, ProductInfo};
+use crate::report::{csv_field, escape_html, ReportFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 器具リストの1行
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleSummaryRow {
+    pub spec_no: String,
+    pub luminaire_type: String,
+    pub manufacturer: String,
+    pub model_number: String,
+    pub psu: Option<String>,
+    /// 品名（製品情報から）
+    pub product_name: Option<String>,
+    /// 器具光束（lm。IESファイルの光度分布を積分した値）
+    pub lumens: Option<f64>,
+    /// 消費電力（W。IESファイルの入力電力）
+    pub watts: Option<f64>,
+    /// ビーム角（度）
+    pub beam_angle: Option<f64>,
+    /// 区分ごとの光束（lm。屋外器具の光害対策の確認用）
+    pub zonal_lumens: Option<ZonalLumens>,
+    /// BUG評価（IES TM-15-11）
+    pub bug_rating: Option<BugRating>,
+    /// 台数（器具リストに台数の列がない場合は None）
+    #[serde(default)]
+    pub quantity: Option<u32>,
+    /// 定価（製品情報から）
+    pub price: Option<Price>,
+    /// 定価 × 台数（台数の指定がない場合は1台）
+    #[serde(default)]
+    pub extended_price: Option<Price>,
+    /// 配光データを読み込んだIESファイルのパス
+    pub ies_file: Option<String>,
+}
+
+/// 器具リストの行を作成
+///
+/// IESファイルは Spec No.・型番が一致する最新の成功したダウンロード履歴のものを使用する。
+/// `product_info` は行に対応する取得済みの製品情報を返す（取得していない場合は None）。
+pub fn build_rows(
+    rows: &[ImportedRow],
+    entries: &[HistoryEntry],
+    product_info: impl Fn(&ImportedRow) -> Option<ProductInfo>,
+) -> Vec<ScheduleSummaryRow> {
+    rows.iter()
+        .map(|row| {
+            let ies_file = entries
+                .iter()
+                .rev()
+                .find(|e| {
+                    e.success
+                        && e.asset_type == AssetType::Ies
+                        && e.spec_no == row.spec_no
+                        && e.model_number == row.fixture
+                })
+                .and_then(|e| e.file_path.clone());
+            let ies = ies_file.as_deref().and_then(|path| {
+                let bytes = std::fs::read(path).ok()?;
+                photometry::parse_ies(&String::from_utf8_lossy(&bytes)).ok()
+            });
+            let info = product_info(row);
+            let price = info.as_ref().and_then(|i| i.price);
+
+            ScheduleSummaryRow {
+                spec_no: row.spec_no.clone(),
+                luminaire_type: row.luminaire_type.clone(),
+                manufacturer: row.manufacturer.clone(),
+                model_number: row.fixture.clone(),
+                psu: row.psu.clone(),
+                product_name: info.as_ref().and_then(|i| i.product_name.clone()),
+                lumens: ies.as_ref().map(|ies| ies.luminaire_lumens()),
+                watts: ies.as_ref().map(|ies| ies.input_watts).filter(|w| *w > 0.0),
+                beam_angle: ies.as_ref().and_then(|ies| ies.beam_angle()),
+                zonal_lumens: ies.as_ref().map(|ies| ies.zonal_lumens()),
+                bug_rating: ies.as_ref().map(|ies| ies.zonal_lumens().bug_rating()),
+                quantity: row.quantity,
+                price,
+                extended_price: price.map(|p| p.times(row.quantity.unwrap_or(1))),
+                ies_file,
+            }
+        })
+        .collect()
+}
+
+/// 消費電力の照合で一致とみなす許容差（器具リストの消費電力に対する割合）
+pub const WATTAGE_TOLERANCE: f64 = 0.1;
+
+/// 器具リストの消費電力とIESファイルの入力電力の照合結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WattageCheck {
+    /// 器具リストの消費電力（W）
+    pub schedule_watts: f64,
+    /// IESファイルの入力電力（W）
+    pub ies_watts: f64,
+    /// 差の割合（(IES − 器具リスト) / 器具リスト）
+    pub difference_ratio: f64,
+    /// 許容差を超えるか
+    pub mismatch: bool,
+}
+
+/// 器具リストの消費電力とIESファイルの入力電力を照合
+///
+/// 器具リストの消費電力が0以下の場合は照合しない（None）。
+pub fn check_wattage(schedule_watts: f64, ies_watts: f64) -> Option<WattageCheck> {
+    if schedule_watts <= 0.0 {
+        return None;
+    }
+    let difference_ratio = (ies_watts - schedule_watts) / schedule_watts;
+    Some(WattageCheck {
+        schedule_watts,
+        ies_watts,
+        difference_ratio,
+        mismatch: difference_ratio.abs() > WATTAGE_TOLERANCE,
+    })
+}
+
+/// IESファイルの入力電力（W。読み込めない・記載がない場合は None）
+pub fn ies_watts(path: &Path) -> Option<f64> {
+    let bytes = std::fs::read(path).ok()?;
+    let ies = photometry::parse_ies(&String::from_utf8_lossy(&bytes)).ok()?;
+    Some(ies.input_watts).filter(|w| *w > 0.0)
+}
+
+/// 接続負荷の集計対象の1行
+pub struct LoadItem<'a> {
+    pub manufacturer: &'a str,
+    pub area: Option<&'a str>,
+    /// 台数
+    pub quantity: u32,
+    /// 1台あたりの消費電力（W。不明な場合は None）
+    pub watts: Option<f64>,
+}
+
+/// メーカー・設置エリアごとの接続負荷
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadGroup {
+    pub manufacturer: String,
+    /// 設置エリア（指定のない行は None）
+    pub area: Option<String>,
+    /// 台数の合計
+    pub fixture_count: u32,
+    /// 消費電力の合計（W。消費電力が不明な器具を除く）
+    pub total_watts: f64,
+    /// 消費電力が不明な器具の台数
+    pub unknown_count: u32,
+}
+
+/// 接続負荷の集計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedLoad {
+    /// 台数の合計
+    pub fixture_count: u32,
+    /// 消費電力の合計（W。消費電力が不明な器具を除く）
+    pub total_watts: f64,
+    /// 消費電力が不明な器具の台数
+    pub unknown_count: u32,
+    /// メーカー・設置エリアごとの集計（メーカー・エリアの順）
+    pub groups: Vec<LoadGroup>,
+}
+
+/// 接続負荷を集計
+pub fn connected_load<'a>(items: impl IntoIterator<Item = LoadItem<'a>>) -> ConnectedLoad {
+    let mut groups: BTreeMap<(&str, Option<&str>), LoadGroup> = BTreeMap::new();
+    for item in items {
+        let group = groups
+            .entry((item.manufacturer, item.area))
+            .or_insert_with(|| LoadGroup {
+                manufacturer: item.manufacturer.to_string(),
+                area: item.area.map(str::to_string),
+                fixture_count: 0,
+                total_watts: 0.0,
+                unknown_count: 0,
+            });
+        group.fixture_count += item.quantity;
+        match item.watts {
+            Some(watts) => group.total_watts += watts * item.quantity as f64,
+            None => group.unknown_count += item.quantity,
+        }
+    }
+
+    let groups: Vec<LoadGroup> = groups.into_values().collect();
+    ConnectedLoad {
+        fixture_count: groups.iter().map(|g| g.fixture_count).sum(),
+        total_watts: groups.iter().map(|g| g.total_watts).sum(),
+        unknown_count: groups.iter().map(|g| g.unknown_count).sum(),
+        groups,
+    }
+}
+
+/// 定価の集計対象の1行
+pub struct CostItem<'a> {
+    pub spec_no: &'a str,
+    pub manufacturer: &'a str,
+    /// 台数
+    pub quantity: u32,
+    /// 1台あたりの定価（不明な場合は None）
+    pub unit_price: Option<Price>,
+}
+
+/// アイテムごとの定価
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemCost {
+    pub spec_no: String,
+    pub manufacturer: String,
+    /// 台数
+    pub quantity: u32,
+    /// 1台あたりの定価（不明な場合は None）
+    pub unit_price: Option<Price>,
+    /// 定価 × 台数
+    pub extended_price: Option<Price>,
+}
+
+/// メーカーごとの定価の合計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostGroup {
+    pub manufacturer: String,
+    /// 台数の合計
+    pub fixture_count: u32,
+    /// 定価 × 台数の合計（通貨・税込／税抜ごと。定価が不明な器具を除く）
+    pub totals: Vec<Price>,
+    /// 定価が不明な器具の台数
+    pub unpriced_count: u32,
+}
+
+/// 定価の集計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRollup {
+    /// アイテムごとの定価（アイテムと同じ順）
+    pub items: Vec<ItemCost>,
+    /// メーカーごとの合計（メーカーの順）
+    pub groups: Vec<CostGroup>,
+    /// 定価 × 台数の合計（通貨・税込／税抜ごと。定価が不明な器具を除く）
+    pub totals: Vec<Price>,
+    /// 定価が不明な器具の台数
+    pub unpriced_count: u32,
+}
+
+/// 定価 × 台数をアイテム・メーカーごとに集計
+pub fn cost_rollup<'a>(items: impl IntoIterator<Item = CostItem<'a>>) -> CostRollup {
+    let items: Vec<ItemCost> = items
+        .into_iter()
+        .map(|item| ItemCost {
+            spec_no: item.spec_no.to_string(),
+            manufacturer: item.manufacturer.to_string(),
+            quantity: item.quantity,
+            unit_price: item.unit_price,
+            extended_price: item.unit_price.map(|p| p.times(item.quantity)),
+        })
+        .collect();
+
+    let mut by_manufacturer: BTreeMap<&str, Vec<&ItemCost>> = BTreeMap::new();
+    for item in &items {
+        by_manufacturer
+            .entry(&item.manufacturer)
+            .or_default()
+            .push(item);
+    }
+    let groups = by_manufacturer
+        .into_iter()
+        .map(|(manufacturer, items)| CostGroup {
+            manufacturer: manufacturer.to_string(),
+            fixture_count: items.iter().map(|i| i.quantity).sum(),
+            totals: price_totals(items.iter().filter_map(|i| i.extended_price)),
+            unpriced_count: unpriced_count(items.iter().copied()),
+        })
+        .collect();
+
+    CostRollup {
+        totals: price_totals(items.iter().filter_map(|i| i.extended_price)),
+        unpriced_count: unpriced_count(&items),
+        groups,
+        items,
+    }
+}
+
+/// 定価が不明な器具の台数
+fn unpriced_count<'a>(items: impl IntoIterator<Item = &'a ItemCost>) -> u32 {
+    items
+        .into_iter()
+        .filter(|i| i.unit_price.is_none())
+        .map(|i| i.quantity)
+        .sum()
+}
+
+/// 器具リストの列見出し
+fn columns(locale: Locale) -> [String; 16] {
+    [
+        "Spec No.".to_string(),
+        Message::ColumnLuminaireType.text(locale),
+        Message::ColumnManufacturer.text(locale),
+        Message::ColumnModel.text(locale),
+        Message::ColumnPsu.text(locale),
+        Message::ColumnProductName.text(locale),
+        Message::ColumnLumens.text(locale),
+        Message::ColumnWatts.text(locale),
+        Message::ColumnBeamAngle.text(locale),
+        Message::ColumnBugRating.text(locale),
+        Message::ColumnQuantity.text(locale),
+        Message::ColumnPrice.text(locale),
+        Message::ColumnExtendedPrice.text(locale),
+        Message::ColumnCurrency.text(locale),
+        Message::ColumnTax.text(locale),
+        Message::ColumnFile.text(locale),
+    ]
+}
+
+/// 1行分の値（列見出しと同じ順序。数値は器具光束・ビーム角を整数、消費電力を小数点以下1桁、
+/// 定価を通貨の補助単位の桁数で出力）
+fn row(row: &ScheduleSummaryRow, locale: Locale) -> [String; 16] {
+    let number = |value: Option<f64>, digits: usize| {
+        value
+            .map(|v| format!("{:.*}", digits, v))
+            .unwrap_or_default()
+    };
+    [
+        row.spec_no.clone(),
+        row.luminaire_type.clone(),
+        row.manufacturer.clone(),
+        row.model_number.clone(),
+        row.psu.clone().unwrap_or_default(),
+        row.product_name.clone().unwrap_or_default(),
+        number(row.lumens, 0),
+        number(row.watts, 1),
+        number(row.beam_angle, 0),
+        row.bug_rating.map(|r| r.to_string()).unwrap_or_default(),
+        row.quantity.map(|q| q.to_string()).unwrap_or_default(),
+        row.price.map(|p| p.amount()).unwrap_or_default(),
+        row.extended_price.map(|p| p.amount()).unwrap_or_default(),
+        row.price
+            .map(|p| p.currency.code().to_string())
+            .unwrap_or_default(),
+        match row.price.and_then(|p| p.tax_included) {
+            Some(true) => Message::TaxIncluded.text(locale),
+            Some(false) => Message::TaxExcluded.text(locale),
+            None => String::new(),
+        },
+        row.ies_file.clone().unwrap_or_default(),
+    ]
+}
+
+/// 器具リストを出力
+///
+/// `title` はHTMLの見出しに使用する。列見出し等は `locale` の言語で出力する。
+pub fn render(
+    rows: &[ScheduleSummaryRow],
+    format: ReportFormat,
+    title: &str,
+    locale: Locale,
+) -> Result<String, String> {
+    match format {
+        ReportFormat::Csv => Ok(render_csv(rows, locale)),
+        ReportFormat::Json => serde_json::to_string_pretty(rows)
+            .map_err(|e| format!("Failed to serialize lighting schedule: {}", e)),
+        ReportFormat::Html => Ok(render_html(rows, title, locale)),
+    }
+}
+
+/// CSV形式（Excelで開けるようBOM付きUTF-8、CRLF改行）
+fn render_csv(rows: &[ScheduleSummaryRow], locale: Locale) -> String {
+    let mut out = String::from("\u{feff}");
+    out.push_str(&columns(locale).join(","));
+    out.push_str("\r\n");
+    for r in rows {
+        let fields: Vec<_> = row(r, locale).iter().map(|v| csv_field(v)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// 金額の合計（通貨・税込／税抜が異なる金額は合算せず、組み合わせごとに合計する）
+fn price_totals(prices: impl IntoIterator<Item = Price>) -> Vec<Price> {
+    let mut totals: BTreeMap<(Currency, Option<bool>), u64> = BTreeMap::new();
+    for price in prices {
+        *totals
+            .entry((price.currency, price.tax_included))
+            .or_default() += price.minor_units;
+    }
+    totals
+        .into_iter()
+        .map(|((currency, tax_included), minor_units)| Price {
+            minor_units,
+            currency,
+            tax_included,
+        })
+        .collect()
+}
+
+/// HTML形式（単体で閲覧・印刷できる表。定価 × 台数の合計を付ける）
+fn render_html(rows: &[ScheduleSummaryRow], title: &str, locale: Locale) -> String {
+    let totals = price_totals(rows.iter().filter_map(|r| r.extended_price));
+    let unpriced = rows.iter().filter(|r| r.price.is_none()).count();
+    let title = escape_html(title);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n",
+        locale.code()
+    ));
+    out.push_str(&format!("<title>{}</title>\n", title));
+    out.push_str(
+        "<style>body{font-family:sans-serif}table{border-collapse:collapse}\
+         th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         td.num{text-align:right}@media print{body{font-size:9pt}}</style>\n",
+    );
+    out.push_str("</head>\n<body>\n");
+    out.push_str(&format!("<h1>{}</h1>\n", title));
+    let summary = Message::ScheduleSummary {
+        rows: rows.len(),
+        totals: &totals,
+        unpriced,
+    };
+    out.push_str(&format!("<p>{}</p>\n", summary.text(locale)));
+    out.push_str("<table>\n<thead><tr>");
+    for column in columns(locale) {
+        out.push_str(&format!("<th>{}</th>", column));
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for r in rows {
+        out.push_str("<tr>");
+        for (i, value) in row(r, locale).iter().enumerate() {
+            // 器具光束・消費電力・ビーム角・台数・定価は右寄せ
+            let class = if (6..=8).contains(&i) || (10..=12).contains(&i) {
+                " class=\"num\""
+            } else {
+                ""
+            };
+            out.push_str(&format!("<td{}>{}</td>", class, escape_html(value)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IES: &str = "IESNA:LM-63-2002\r\n[TEST] T-001\r\n[MANUFAC] KOIZUMI\r\n\
+        [LUMCAT] AD12345\r\nTILT=NONE\r\n\
+        1 1000 1 3 1 1 2 -0.1 0 0\r\n1 1 12.5\r\n\
+        0 45 90\r\n0\r\n300 200 0\r\n";
+
+    fn imported(spec_no: &str, model_number: &str) -> ImportedRow {
+        ImportedRow {
+            spec_no: spec_no.to_string(),
+            luminaire_type: "ダウンライト".to_string(),
+            manufacturer: "Koizumi".to_string(),
+            fixture: model_number.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_rows() {
+        let temp = tempfile::tempdir().unwrap();
+        let ies_path = temp.path().join("A-1.ies");
+        std::fs::write(&ies_path, IES).unwrap();
+        let entry = HistoryEntry {
+            project_id: None,
+            spec_no: "A-1".to_string(),
+            manufacturer: "Koizumi".to_string(),
+            model_number: "AD12345".to_string(),
+            psu: None,
+            asset_type: AssetType::Ies,
+            success: true,
+            file_path: Some(ies_path.to_string_lossy().into_owned()),
+            original_filename: None,
+            sha256: None,
+            source: None,
+            provider: None,
+            error: None,
+            downloaded_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+        };
+        let rows = [
+            ImportedRow {
+                quantity: Some(3),
+                ..imported("A-1", "AD12345")
+            },
+            imported("A-2", "AD99999"),
+        ];
+
+        let summary = build_rows(&rows, &[entry], |row| {
+            (row.fixture == "AD12345").then(|| ProductInfo {
+                model_number: row.fixture.clone(),
+                product_name: Some("ダウンライト φ100".to_string()),
+                price: Some(Price::jpy(12800).assume_tax_included(false)),
+                ies_file_url: None,
+                image_url: None,
+                product_page_url: None,
+                accessories: vec![],
+                discontinued: None,
+                replaces: None,
+                match_quality: None,
+            })
+        });
+
+        assert_eq!(
+            summary[0].product_name.as_deref(),
+            Some("ダウンライト φ100")
+        );
+        assert_eq!(summary[0].price.map(|p| p.minor_units), Some(12800));
+        assert_eq!(
+            summary[0].extended_price.map(|p| p.minor_units),
+            Some(38400)
+        );
+        assert_eq!(summary[0].watts, Some(12.5));
+        assert_eq!(summary[0].beam_angle, Some(112.5));
+        assert!(summary[0].lumens.is_some_and(|lm| lm > 0.0));
+        assert_eq!(summary[0].bug_rating.map(|r| r.uplight), Some(0));
+        // IESファイル・製品情報がない行も出力する
+        assert_eq!(summary[1].ies_file, None);
+        assert_eq!(summary[1].price, None);
+
+        let csv = render(&summary, ReportFormat::Csv, "", Locale::Ja).unwrap();
+        let lines: Vec<_> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+        assert!(lines[0].starts_with("Spec No.,器具タイプ,メーカー,型番,PSU,品名,"));
+        assert!(lines[1].starts_with("A-1,ダウンライト,Koizumi,AD12345,,ダウンライト φ100,"));
+        assert!(lines[1].contains(",12.5,"));
+        assert!(lines[1].contains(",3,12800,38400,JPY,税抜,"));
+
+        let html = render(&summary, ReportFormat::Html, "A棟 器具リスト", Locale::Ja).unwrap();
+        assert!(html.contains("<h1>A棟 器具リスト</h1>"));
+        assert!(html.contains("器具 2件・定価合計 38400円（税抜）（定価不明 1件を除く）"));
+    }
+
+    #[test]
+    fn test_check_wattage() {
+        let check = check_wattage(12.0, 12.5).unwrap();
+        assert!(!check.mismatch);
+        // 別の出力のバリエーション（20W）を取得した場合
+        let check = check_wattage(12.0, 20.0).unwrap();
+        assert!(check.mismatch);
+        assert!((check.difference_ratio - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(check_wattage(0.0, 12.5), None);
+    }
+
+    #[test]
+    fn test_connected_load() {
+        let load = connected_load([
+            LoadItem {
+                manufacturer: "Koizumi",
+                area: Some("1F"),
+                quantity: 10,
+                watts: Some(12.5),
+            },
+            LoadItem {
+                manufacturer: "Koizumi",
+                area: Some("1F"),
+                quantity: 2,
+                watts: None,
+            },
+            LoadItem {
+                manufacturer: "Koizumi",
+                area: Some("2F"),
+                quantity: 4,
+                watts: Some(20.0),
+            },
+            LoadItem {
+                manufacturer: "TOKISTAR",
+                area: None,
+                quantity: 1,
+                watts: Some(8.0),
+            },
+        ]);
+
+        assert_eq!(load.fixture_count, 17);
+        assert_eq!(load.total_watts, 213.0);
+        assert_eq!(load.unknown_count, 2);
+        assert_eq!(load.groups.len(), 3);
+        assert_eq!(load.groups[0].area.as_deref(), Some("1F"));
+        assert_eq!(load.groups[0].fixture_count, 12);
+        assert_eq!(load.groups[0].total_watts, 125.0);
+        assert_eq!(load.groups[0].unknown_count, 2);
+        assert_eq!(load.groups[2].manufacturer, "TOKISTAR");
+    }
+
+    #[test]
+    fn test_cost_rollup() {
+        let usd = Price {
+            minor_units: 1250,
+            currency: Currency::Usd,
+            tax_included: None,
+        };
+        let rollup = cost_rollup([
+            CostItem {
+                spec_no: "A-1",
+                manufacturer: "Koizumi",
+                quantity: 10,
+                unit_price: Some(Price::jpy(12800).assume_tax_included(false)),
+            },
+            CostItem {
+                spec_no: "A-2",
+                manufacturer: "Koizumi",
+                quantity: 2,
+                unit_price: None,
+            },
+            CostItem {
+                spec_no: "B-1",
+                manufacturer: "TOKISTAR",
+                quantity: 4,
+                unit_price: Some(Price::jpy(28000).assume_tax_included(false)),
+            },
+            CostItem {
+                spec_no: "C-1",
+                manufacturer: "Acme",
+                quantity: 3,
+                unit_price: Some(usd),
+            },
+        ]);
+
+        assert_eq!(
+            rollup.items[0].extended_price.map(|p| p.minor_units),
+            Some(128000)
+        );
+        assert_eq!(rollup.items[1].extended_price, None);
+        assert_eq!(rollup.unpriced_count, 2);
+        // 通貨が異なる定価は合算しない
+        assert_eq!(
+            rollup.totals,
+            vec![
+                Price::jpy(240000).assume_tax_included(false),
+                Price {
+                    minor_units: 3750,
+                    ..usd
+                },
+            ]
+        );
+        assert_eq!(rollup.groups.len(), 3);
+        assert_eq!(rollup.groups[1].manufacturer, "Koizumi");
+        assert_eq!(rollup.groups[1].fixture_count, 12);
+        assert_eq!(rollup.groups[1].unpriced_count, 2);
+        assert_eq!(
+            rollup.groups[1].totals,
+            vec![Price::jpy(128000).assume_tax_included(false)]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! IESファイルのヘッダーの匿名化
+//!
+//! 施主・協力事務所にIESファイルを納品する前に、ヘッダー（`TILT=` 行より前）から
+//! 社内のファイルパス・測定者名等の内部情報を取り除く。配光データ（`TILT=` 行以降）は変更しない。
+//!
+//! - 測定者・作成者等のキーワード（`[TESTER]` `[PREPAREDBY]` 等）と、ユーザー定義の
+//!   キーワード（`[_xxx]`）の行を削除する（続く `[MORE]` 行も削除する）
+//! - 「Tested by ...」のように担当者名を含む行を削除する
+//! - 絶対パス（`C:\Users\...` `\\server\share\...` `/Users/...` 等）はファイル名だけにする
+//!
+//! Shift_JIS等のUTF-8以外のヘッダーも壊さないよう、バイト列のまま処理する。
+
+use regex::bytes::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// 内部情報として削除するキーワード（大文字）
+const PRIVATE_KEYWORDS: [&str; 9] = [
+    "TESTER",
+    "OPERATOR",
+    "PREPARER",
+    "PREPAREDBY",
+    "AUTHOR",
+    "USER",
+    "COMPUTER",
+    "SOURCEFILE",
+    "FILEGENINFO",
+];
+
+/// 絶対パス（Windowsのドライブ・UNC、macOS・Linuxのユーザー・マウント先等）
+///
+/// URL中の `/var/` 等に一致しないよう、行頭・空白・括弧等の直後から始まるものに限る。
+static ABSOLUTE_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(^|[\s"'=(\[,;])((?:[A-Za-z]:[\\/]|\\\\[^\\\s]+\\|/(?:Users|home|Volumes|mnt|media|srv|tmp|var|opt|private)/)[^\s"'<>|\[\]]*)"#,
+    )
+    .unwrap()
+});
+
+/// 担当者名を含む行（"Tested by" "Prepared by" 等）
+static PERSON_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:tested|prepared|measured|checked|approved|created)\s+by\b").unwrap()
+});
+
+/// `[KEYWORD]` の行のキーワード（大文字）
+fn keyword(line: &str) -> Option<String> {
+    let (key, _) = line.trim().strip_prefix('[')?.split_once(']')?;
+    Some(key.trim().to_uppercase())
+}
+
+fn is_private(key: &str) -> bool {
+    key.starts_with('_') || PRIVATE_KEYWORDS.contains(&key)
+}
+
+/// 絶対パスをファイル名（最後の要素）に置き換える
+fn redact_paths(line: &[u8]) -> Vec<u8> {
+    ABSOLUTE_PATH_RE
+        .replace_all(line, |caps: &Captures| {
+            let path = &caps[2];
+            let start = path
+                .iter()
+                .rposition(|&b| b == b'\\' || b == b'/')
+                .map_or(0, |i| i + 1);
+            [&caps[1], &path[start..]].concat()
+        })
+        .into_owned()
+}
+
+/// IESファイルのヘッダーから内部情報を取り除く（配光データは変更しない）
+pub fn scrub_ies_header(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut in_header = true;
+    let mut dropping = false;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        if !in_header {
+            out.extend_from_slice(line);
+            continue;
+        }
+        let text = String::from_utf8_lossy(line);
+        if text.trim_start().starts_with("TILT=") {
+            // `TILT=` にチルトファイルのパスが書かれている場合もファイル名だけにする
+            in_header = false;
+            out.extend(redact_paths(line));
+            continue;
+        }
+        match keyword(&text) {
+            Some(key) if key == "MORE" => {}
+            Some(key) => dropping = is_private(&key),
+            None => dropping = false,
+        }
+        if dropping || PERSON_RE.is_match(line) {
+            continue;
+        }
+        out.extend(redact_paths(line));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_ies_header() {
+        let ies = "IESNA:LM-63-2002\r\n[TEST] T-001\r\n[MANUFAC] KOIZUMI\r\n\
+            [TESTER] Taro Yamada\r\n[MORE] 2nd shift\r\n[_PROJECT] Internal project\r\n\
+            [OTHER] Tested by Hanako\r\n[LUMCAT] AD12345\r\n\
+            [SEARCH] C:\\Users\\taro\\Projects\\AD12345.ies\r\n\
+            [MORE] \\\\fileserver\\photometry\\raw\\AD12345.ldt\r\n\
+            [OTHER] https://example.com/var/AD12345\r\n\
+            TILT=NONE\r\n1 1000 1 3 1 1 2 -0.1 0 0\r\n1 1 12.5\r\n0 45 90\r\n0\r\n300 200 0\r\n";
+        let scrubbed = String::from_utf8(scrub_ies_header(ies.as_bytes())).unwrap();
+        assert_eq!(
+            scrubbed,
+            "IESNA:LM-63-2002\r\n[TEST] T-001\r\n[MANUFAC] KOIZUMI\r\n[LUMCAT] AD12345\r\n\
+             [SEARCH] AD12345.ies\r\n[MORE] AD12345.ldt\r\n\
+             [OTHER] https://example.com/var/AD12345\r\n\
+             TILT=NONE\r\n1 1000 1 3 1 1 2 -0.1 0 0\r\n1 1 12.5\r\n0 45 90\r\n0\r\n300 200 0\r\n"
+        );
+
+        // UTF-8以外（Shift_JIS）のヘッダーもそのまま残す
+        let sjis =
+            b"IESNA:LM-63-2002\n[LUMINAIRE] \x83_\x83E\x83\x93\x83\x89\x83C\x83g\nTILT=NONE\n";
+        assert_eq!(scrub_ies_header(sjis), sjis.to_vec());
+    }
+}
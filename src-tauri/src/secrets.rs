@@ -0,0 +1,146 @@
+//! 資格情報の保存（OSのキーチェーン）
+//!
+//! 共有のパスワードやクラウドストレージのトークン等は、ストアファイルではなく
+//! OSの資格情報ストア（macOS: キーチェーン、Windows: 資格情報マネージャー、
+//! Linux: Secret Service）に保存する。ストアファイルは平文のため、ポータブルモードで
+//! USBメモリに置いた場合や、バックアップ・同期フォルダに含まれた場合に漏れる。
+//!
+//! 以前のバージョンでストアファイルに保存した値は、初回の読み込み時にキーチェーンへ移して
+//! ストアファイルから削除する（[`get_or_migrate`]）。
+
+use crate::portable::{self, STORE_NAME};
+use keyring::Entry;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// キーチェーンのサービス名（アプリの識別子）
+const SERVICE: &str = "com.ayanel.autosight";
+
+/// 1件に保存する文字数の上限
+///
+/// Windowsの資格情報マネージャーは1件あたり2560バイト（UTF-16で1280文字）までのため、
+/// OAuthのトークン等の長い値は分割し、2件目以降を `{キー}#2` `{キー}#3` ... に保存する。
+const CHUNK_CHARS: usize = 1000;
+
+fn entry(key: &str, index: usize) -> Result<Entry, String> {
+    let user = match index {
+        1 => key.to_string(),
+        index => format!("{}#{}", key, index),
+    };
+    Entry::new(SERVICE, &user).map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+fn split_chunks(value: &str) -> Vec<String> {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(CHUNK_CHARS).map(String::from_iter).collect()
+}
+
+fn read(key: &str, index: usize) -> Result<Option<String>, String> {
+    match entry(key, index)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read keychain: {}", e)),
+    }
+}
+
+/// `index` 件目以降の分割した値を削除
+fn delete_from(key: &str, index: usize) -> Result<(), String> {
+    for index in index.. {
+        match entry(key, index)?.delete_credential() {
+            Ok(()) => {}
+            Err(keyring::Error::NoEntry) => break,
+            Err(e) => return Err(format!("Failed to delete from keychain: {}", e)),
+        }
+    }
+    Ok(())
+}
+
+/// 保存した値を取得（保存していない場合は `None`）
+pub fn get(key: &str) -> Result<Option<String>, String> {
+    let Some(mut value) = read(key, 1)? else {
+        return Ok(None);
+    };
+    let mut last = value.chars().count();
+    let mut index = 2;
+    while last == CHUNK_CHARS {
+        let Some(chunk) = read(key, index)? else {
+            break;
+        };
+        last = chunk.chars().count();
+        value.push_str(&chunk);
+        index += 1;
+    }
+    Ok(Some(value))
+}
+
+/// 値を保存（既に保存している場合は上書き）
+pub fn set(key: &str, value: &str) -> Result<(), String> {
+    let chunks = split_chunks(value);
+    for (i, chunk) in chunks.iter().enumerate() {
+        entry(key, i + 1)?
+            .set_password(chunk)
+            .map_err(|e| format!("Failed to save to keychain: {}", e))?;
+    }
+    // 以前に保存した値の方が長い場合の残り
+    delete_from(key, chunks.len() + 1)
+}
+
+/// 保存した値を削除（保存していない場合は何もしない）
+pub fn delete(key: &str) -> Result<(), String> {
+    delete_from(key, 1)
+}
+
+/// 保存した値を取得し、無い場合は以前のバージョンがストアファイルに保存した値をキーチェーンへ移す
+///
+/// ストアファイルの値（JSON）は文字列ならそのまま、それ以外はJSONの文字列にして保存する。
+/// キーチェーンへの保存に失敗した場合はストアファイルの値を残す。
+pub fn get_or_migrate<R: Runtime>(app: &AppHandle<R>, key: &str) -> Result<Option<String>, String> {
+    if let Some(value) = get(key)? {
+        return Ok(Some(value));
+    }
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    let value = match store.get(key) {
+        None => return Ok(None),
+        Some(serde_json::Value::String(value)) => value,
+        Some(value) => value.to_string(),
+    };
+    set(key, &value)?;
+    remove_from_store(app, key)?;
+    Ok(Some(value))
+}
+
+/// 以前のバージョンがストアファイルに保存した値を削除（保存・削除時に平文の値を残さないため）
+pub fn remove_from_store<R: Runtime>(app: &AppHandle<R>, key: &str) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    if store.delete(key) {
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        assert_eq!(split_chunks(""), [""]);
+        assert_eq!(split_chunks("token"), ["token"]);
+
+        let value = "あ".repeat(CHUNK_CHARS * 2 + 1);
+        let chunks = split_chunks(&value);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].chars().count(), CHUNK_CHARS);
+        assert_eq!(chunks[2], "あ");
+        assert_eq!(chunks.concat(), value);
+    }
+}
@@ -0,0 +1,164 @@
+//! 直前の作業状態（セッション）の復元
+//!
+//! 最後に読み込んだ器具リスト・取得した製品情報・各行のダウンロード状態をストアに記録しておき、
+//! クラッシュや再起動の後にアプリを開き直したとき、器具リストの読み込みや製品情報の取得を
+//! やり直さずに続きから作業できるようにする。新しい器具リストを読み込むと置き換わる。
+
+use crate::batch::DownloadProgressEvent;
+use crate::commands::ProductInfoResult;
+use crate::excel::ImportResult;
+use crate::portable::{self, STORE_NAME};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// セッションを保存するキー
+const SESSION_KEY: &str = "lastSession";
+
+/// 読み込み・更新・保存の間に他のタスクの更新を挟まないためのロック
+/// （製品情報の先読み等、複数のタスクから同時に記録される）
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// 直前の作業状態（`restore_last_session` の戻り値）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// 読み込んだ器具リストのパス
+    pub source_path: Option<String>,
+    /// 器具リストの読み込み結果
+    pub import: Option<ImportResult>,
+    /// 取得した製品情報（Spec No.をキーとする）
+    pub product_info: BTreeMap<String, ProductInfoResult>,
+    /// 各行の直近のダウンロード結果（Spec No.をキーとする。処理中・待機中の状態は記録しない）
+    pub statuses: BTreeMap<String, DownloadProgressEvent>,
+    /// 最後に更新した日時
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// セッションを読み込む（ない場合・読み込めない場合は None）
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Option<Session> {
+    let store = app.store(portable::store_path(STORE_NAME)).ok()?;
+    serde_json::from_value(store.get(SESSION_KEY)?).ok()
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, session: &Session) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        SESSION_KEY,
+        serde_json::to_value(session).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save session: {}", e))
+}
+
+/// セッションを更新して保存（ない場合は空のセッションから始める）
+fn update<R: Runtime>(app: &AppHandle<R>, f: impl FnOnce(&mut Session)) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut session = load(app).unwrap_or_default();
+    f(&mut session);
+    session.updated_at = Some(Utc::now());
+    save(app, &session)
+}
+
+/// 器具リストの読み込みを記録（以前の製品情報・ダウンロード状態は破棄する）
+pub fn record_import<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &str,
+    import: &ImportResult,
+) -> Result<(), String> {
+    update(app, |session| {
+        *session = Session {
+            source_path: Some(path.to_string()),
+            import: Some(import.clone()),
+            ..Default::default()
+        };
+    })
+}
+
+/// 取得した製品情報を記録
+pub fn record_product_info<R: Runtime>(
+    app: &AppHandle<R>,
+    results: &[ProductInfoResult],
+) -> Result<(), String> {
+    if results.is_empty() {
+        return Ok(());
+    }
+    update(app, |session| {
+        for result in results {
+            session
+                .product_info
+                .insert(result.spec_no.clone(), result.clone());
+        }
+    })
+}
+
+/// アイテムのダウンロード結果を記録（処理中・待機中の状態は無視する）
+pub fn record_status<R: Runtime>(
+    app: &AppHandle<R>,
+    event: &DownloadProgressEvent,
+) -> Result<(), String> {
+    if !is_final(&event.status) {
+        return Ok(());
+    }
+    update(app, |session| {
+        session
+            .statuses
+            .insert(event.spec_no.clone(), event.clone());
+    })
+}
+
+/// 結果が確定した状態か
+fn is_final(status: &str) -> bool {
+    matches!(status, "success" | "error" | "cancelled")
+}
+
+/// セッションを削除
+pub fn clear<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    if store.delete(SESSION_KEY) {
+        store
+            .save()
+            .map_err(|e| format!("Failed to save session: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_final() {
+        assert!(is_final("success"));
+        assert!(is_final("cancelled"));
+        assert!(!is_final("processing"));
+        assert!(!is_final("waiting"));
+    }
+
+    #[test]
+    fn test_session_json() {
+        let mut session = Session {
+            source_path: Some("/tmp/schedule.xlsx".to_string()),
+            ..Default::default()
+        };
+        session.statuses.insert(
+            "A01".to_string(),
+            DownloadProgressEvent::new("A01", "error", Some("Not found".to_string())),
+        );
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["sourcePath"], "/tmp/schedule.xlsx");
+        assert_eq!(json["statuses"]["A01"]["status"], "error");
+
+        let restored: Session = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.statuses["A01"].error.as_deref(), Some("Not found"));
+    }
+}
@@ -0,0 +1,626 @@
+//! アプリ設定
+//!
+//! バックエンドの動作に関わる設定（同時実行数・タイムアウト・ファイル名テンプレート・
+//! プロキシ・保存先ルール・ログレベル・利用状況の送信・エラー報告・ローカルHTTP API・
+//! クラウドへのアップロード・ネットワーク共有・プロバイダールールの更新・バッテリー駆動時の負荷の軽減・
+//! コイズミ照明の固有設定）を型付きで管理する。
+//! tauri-plugin-store のストアファイル（`autosight.store.json`）に、バージョン付きで永続化する。
+
+use crate::api_server::ApiServerSettings;
+use crate::cassette::CassetteSettings;
+use crate::cloud::CloudUploadSettings;
+use crate::error_reporting::ErrorReportingSettings;
+use crate::filename;
+use crate::i18n::Locale;
+use crate::logging::LogLevel;
+use crate::network_share::{self, NetworkShareSettings};
+use crate::portable::{self, STORE_NAME};
+use crate::power::BatterySettings;
+use crate::providers::{ProviderConfig, RetrySettings, CONFIGURABLE_PROVIDERS};
+use crate::rules_update::RulesUpdateSettings;
+use crate::telemetry::TelemetrySettings;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 設定を保存するキー
+const SETTINGS_KEY: &str = "settings";
+/// 現在の設定バージョン
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// 同時実行数の上限
+pub const MAX_CONCURRENCY: usize = 16;
+/// タイムアウトの上限（秒）
+const MAX_TIMEOUT_SECS: u64 = 600;
+/// ドメインごとのリクエストの最小間隔の上限（ミリ秒）
+const MAX_REQUEST_INTERVAL_MS: u64 = 60_000;
+
+/// アプリ設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Settings {
+    /// 設定のバージョン（マイグレーション用）
+    pub version: u32,
+    /// 製品情報取得等の同時実行数
+    pub concurrency: usize,
+    /// ドメインごとの同時リクエスト数の上限（例: `{"toki.co.jp": 1}`。サブドメインにも適用する）
+    ///
+    /// 同時に多くのリクエストを送ると制限・エラーになるメーカーサイト向け。指定のないドメインは
+    /// ホストごとに既定の上限（[`crate::providers::DEFAULT_DOMAIN_CONCURRENCY`]）で制限する。
+    pub domain_concurrency: BTreeMap<String, usize>,
+    /// ドメインごとのリクエストの最小間隔（ミリ秒。例: `{"toki.co.jp": 1000}`。サブドメインにも適用する）
+    pub domain_request_interval_ms: BTreeMap<String, u64>,
+    /// レート制限・一時的なエラー時の再試行
+    pub retry: RetrySettings,
+    /// HTTPリクエストのタイムアウト（秒）
+    pub request_timeout_secs: u64,
+    /// ファイル名テンプレート（未指定時はプロバイダーの命名規則）
+    pub filename_template: Option<String>,
+    /// 生成するファイル名の全角英数字を半角に変換
+    pub halfwidth_alphanumerics: bool,
+    /// プロキシ設定（未指定時は直接接続）
+    pub proxy: Option<ProxySettings>,
+    /// TLSの設定（社内のルート証明書等）
+    pub tls: TlsSettings,
+    /// 保存先ルール
+    pub destination: DestinationSettings,
+    /// 一括ダウンロードの完了時にデスクトップ通知を表示
+    pub notify_when_finished: bool,
+    /// ウィンドウを閉じてもシステムトレイに常駐し、一括ダウンロードを続ける（デスクトップのみ）
+    pub close_to_tray: bool,
+    /// オフラインモード（通信せず、キャッシュ・IESライブラリだけから結果を返す）
+    pub offline: bool,
+    /// 製品情報の一括取得で、廃番の製品は後継品の製品情報に切り替える
+    pub follow_successor: bool,
+    /// 照明計算ソフト向けの書き出しで、IESファイルのヘッダーから社内のパス・測定者名等を取り除く
+    pub scrub_ies_headers: bool,
+    /// ログレベル
+    pub log_level: LogLevel,
+    /// 表示言語（バックエンドが生成する警告・レポート・通知の文言）
+    pub locale: Locale,
+    /// 利用状況の集計・送信（オプトイン）
+    pub telemetry: TelemetrySettings,
+    /// エラー報告（Sentry互換、オプトイン）
+    pub error_reporting: ErrorReportingSettings,
+    /// ローカルHTTP APIサーバー（オプトイン）
+    pub api_server: ApiServerSettings,
+    /// 一括ダウンロード後のクラウドへのアップロード（オプトイン）
+    pub cloud_upload: CloudUploadSettings,
+    /// ネットワーク共有（SMB）への保存
+    pub network_share: NetworkShareSettings,
+    /// プロバイダールールの更新（アプリ本体とは別の更新チャンネル）
+    pub rules_update: RulesUpdateSettings,
+    /// バッテリー駆動時の負荷の軽減
+    pub battery: BatterySettings,
+    /// プロバイダーごとの接続設定（プロバイダーIDをキーとする。例: `{"tokistar": {"baseUrl": "..."}}`）
+    pub providers: BTreeMap<String, ProviderConfig>,
+    /// 通信の記録・再生とモックプロバイダー（デモ・研修・結合テスト用）
+    pub cassette: CassetteSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            concurrency: 4,
+            domain_concurrency: BTreeMap::new(),
+            domain_request_interval_ms: BTreeMap::new(),
+            retry: RetrySettings::default(),
+            request_timeout_secs: 30,
+            filename_template: None,
+            halfwidth_alphanumerics: false,
+            proxy: None,
+            tls: TlsSettings::default(),
+            destination: DestinationSettings::default(),
+            notify_when_finished: true,
+            close_to_tray: false,
+            offline: false,
+            follow_successor: false,
+            scrub_ies_headers: false,
+            log_level: LogLevel::default(),
+            locale: Locale::default(),
+            telemetry: TelemetrySettings::default(),
+            error_reporting: ErrorReportingSettings::default(),
+            api_server: ApiServerSettings::default(),
+            cloud_upload: CloudUploadSettings::default(),
+            network_share: NetworkShareSettings::default(),
+            rules_update: RulesUpdateSettings::default(),
+            battery: BatterySettings::default(),
+            providers: BTreeMap::new(),
+            cassette: CassetteSettings::default(),
+        }
+    }
+}
+
+/// プロキシ設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProxySettings {
+    /// プロキシのURL（例: `http://proxy.example.com:8080`）
+    pub url: String,
+    /// 認証ユーザー名
+    #[serde(default)]
+    pub username: Option<String>,
+    /// 認証パスワード
+    #[serde(default)]
+    pub password: Option<String>,
+    /// プロキシを経由しないホスト（カンマ区切り。例: `localhost,.intra.example.co.jp`）
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+}
+
+/// TLSの設定
+///
+/// 通信を検査するプロキシ等で、社内の認証局が発行した証明書を使う環境向け。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct TlsSettings {
+    /// 追加で信頼するルート証明書のファイル（PEMまたはDER）
+    pub root_certificate_path: Option<String>,
+    /// サーバー証明書を検証しない（検証に失敗する環境での一時的な回避用）
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsSettings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(path) = &self.root_certificate_path {
+            if !Path::new(path).is_absolute() {
+                return Err("tls.rootCertificatePath must be an absolute path".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 保存先ルール
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct DestinationSettings {
+    /// IES以外のアセットを種別ごとのサブフォルダに保存する
+    pub asset_subdirs: bool,
+    /// 同名のファイルがある場合に上書きする
+    pub overwrite_existing: bool,
+    /// 既定の保存先ディレクトリ（ダウンロード時に保存先が指定されなかった場合に使用）
+    /// （`{project}` `{date}` `{manufacturer}` 等のプレースホルダーを使用可能。[`filename::render_dir`]）
+    pub default_dir: Option<String>,
+    /// 既定の保存先ディレクトリに作成するプロジェクトごとのサブフォルダ名
+    /// （`{project}` `{project_id}` `{date}` を使用可能。未指定時はサブフォルダを作成しない）
+    pub project_subdir: Option<String>,
+    /// 一括ダウンロードの完了後に保存先ディレクトリをFinder/エクスプローラーで開く
+    pub open_when_finished: bool,
+}
+
+impl Default for DestinationSettings {
+    fn default() -> Self {
+        Self {
+            asset_subdirs: true,
+            overwrite_existing: true,
+            default_dir: None,
+            project_subdir: None,
+            open_when_finished: false,
+        }
+    }
+}
+
+/// プロジェクトごとのサブフォルダ名に使用できるプレースホルダー
+const PROJECT_SUBDIR_PLACEHOLDERS: [&str; 3] = ["{project}", "{project_id}", "{date}"];
+
+impl DestinationSettings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(dir) = &self.default_dir {
+            if !Path::new(dir).is_absolute() && !network_share::is_share_spec(dir) {
+                return Err("destination.defaultDir must be an absolute path".to_string());
+            }
+            filename::validate_dir(dir)?;
+        }
+        if let Some(subdir) = &self.project_subdir {
+            if subdir.trim().is_empty() {
+                return Err("destination.projectSubdir must not be empty".to_string());
+            }
+            if subdir.contains(['/', '\\']) {
+                return Err(
+                    "destination.projectSubdir must not contain path separators".to_string()
+                );
+            }
+            let literal = PROJECT_SUBDIR_PLACEHOLDERS
+                .iter()
+                .fold(subdir.clone(), |s, placeholder| s.replace(placeholder, ""));
+            if literal.contains(['{', '}']) {
+                return Err(format!(
+                    "destination.projectSubdir supports only {}",
+                    PROJECT_SUBDIR_PLACEHOLDERS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 保存先ディレクトリを決定
+    ///
+    /// 呼び出し側の指定を優先する。指定がない場合は既定の保存先ディレクトリを使用し、
+    /// プロジェクトのダウンロードであればプロジェクトごとのサブフォルダを付ける。
+    pub fn resolve_dir(
+        &self,
+        explicit: Option<&str>,
+        project_id: Option<&str>,
+        project_name: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<String, String> {
+        if let Some(dir) = explicit.filter(|dir| !dir.trim().is_empty()) {
+            return Ok(dir.to_string());
+        }
+        let default_dir = self
+            .default_dir
+            .as_deref()
+            .ok_or("No destination directory specified and no default directory configured")?;
+        let (Some(template), Some(project_id)) = (&self.project_subdir, project_id) else {
+            return Ok(default_dir.to_string());
+        };
+
+        let subdir = template
+            .replace("{project_id}", project_id)
+            .replace("{project}", project_name.unwrap_or(project_id))
+            .replace("{date}", &today.format("%Y-%m-%d").to_string());
+        Ok(Path::new(default_dir)
+            .join(filename::sanitize_filename(&subdir))
+            .to_string_lossy()
+            .into_owned())
+    }
+}
+
+/// リクエストで指定された同時実行数を検証し、上限に丸める
+///
+/// 0 は設定の `concurrency` と同じくエラーにし、上限を超える値は [`MAX_CONCURRENCY`] にする。
+pub fn clamp_concurrency(n: usize) -> Result<usize, String> {
+    if n == 0 {
+        return Err(format!(
+            "maxConcurrency must be between 1 and {}",
+            MAX_CONCURRENCY
+        ));
+    }
+    Ok(n.min(MAX_CONCURRENCY))
+}
+
+impl Settings {
+    /// 値の範囲・形式を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CONCURRENCY).contains(&self.concurrency) {
+            return Err(format!(
+                "concurrency must be between 1 and {}",
+                MAX_CONCURRENCY
+            ));
+        }
+        for (domain, &limit) in &self.domain_concurrency {
+            if domain.trim().is_empty() || domain.contains(['/', ':', ' ']) {
+                return Err(format!(
+                    "domainConcurrency key must be a domain name: {:?}",
+                    domain
+                ));
+            }
+            if !(1..=MAX_CONCURRENCY).contains(&limit) {
+                return Err(format!(
+                    "domainConcurrency for {} must be between 1 and {}",
+                    domain, MAX_CONCURRENCY
+                ));
+            }
+        }
+        for (domain, &interval) in &self.domain_request_interval_ms {
+            if domain.trim().is_empty() || domain.contains(['/', ':', ' ']) {
+                return Err(format!(
+                    "domainRequestIntervalMs key must be a domain name: {:?}",
+                    domain
+                ));
+            }
+            if interval > MAX_REQUEST_INTERVAL_MS {
+                return Err(format!(
+                    "domainRequestIntervalMs for {} must be at most {}",
+                    domain, MAX_REQUEST_INTERVAL_MS
+                ));
+            }
+        }
+        self.retry.validate()?;
+        for (id, config) in &self.providers {
+            if !CONFIGURABLE_PROVIDERS.contains(&id.as_str()) {
+                return Err(format!("Unknown provider in providers: {}", id));
+            }
+            config.validate(id)?;
+        }
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.request_timeout_secs) {
+            return Err(format!(
+                "requestTimeoutSecs must be between 1 and {}",
+                MAX_TIMEOUT_SECS
+            ));
+        }
+        if let Some(template) = &self.filename_template {
+            if template.trim().is_empty() {
+                return Err("filenameTemplate must not be empty".to_string());
+            }
+            if template.contains(['/', '\\']) {
+                return Err("filenameTemplate must not contain path separators".to_string());
+            }
+            filename::validate(template)?;
+        }
+        self.destination.validate()?;
+        self.telemetry.validate()?;
+        self.error_reporting.validate()?;
+        self.api_server.validate()?;
+        self.cloud_upload.validate()?;
+        self.network_share.validate()?;
+        self.rules_update.validate()?;
+        self.battery.validate()?;
+        if let Some(proxy) = &self.proxy {
+            let url =
+                reqwest::Url::parse(&proxy.url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("Unsupported proxy scheme: {}", url.scheme()));
+            }
+        }
+        self.tls.validate()?;
+        Ok(())
+    }
+
+    /// ファイル名の生成設定
+    pub fn filename_options(&self) -> filename::FilenameOptions {
+        filename::FilenameOptions {
+            template: self.filename_template.clone(),
+            halfwidth_alphanumerics: self.halfwidth_alphanumerics,
+            reserved: None,
+        }
+    }
+}
+
+/// 保存された設定を現在のバージョンに変換
+///
+/// バージョン番号のない設定はバージョン0（バージョン管理導入前）として扱う。
+pub fn migrate(mut value: Value) -> Result<Settings, String> {
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "Settings version {} is newer than supported version {}",
+            version, SETTINGS_VERSION
+        ));
+    }
+
+    if version < 1 {
+        migrate_v0(&mut value);
+    }
+    if version < 2 {
+        migrate_v1(&mut value);
+    }
+
+    let settings: Settings =
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// v0 → v1: `maxConcurrency` を `concurrency` に、`timeout`（ミリ秒）を
+/// `requestTimeoutSecs`（秒）に変更
+fn migrate_v0(value: &mut Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    if let Some(concurrency) = obj.remove("maxConcurrency") {
+        obj.entry("concurrency").or_insert(concurrency);
+    }
+    if let Some(timeout_ms) = obj.remove("timeout").and_then(|v| v.as_u64()) {
+        obj.entry("requestTimeoutSecs")
+            .or_insert(Value::from(timeout_ms.div_ceil(1000)));
+    }
+    obj.insert("version".to_string(), Value::from(1));
+}
+
+/// v1 → v2: コイズミ照明の仕様書の同時取得（`koizumi.specSheetWithIes`）を削除
+///
+/// 仕様書のダウンロード種別がメーカーサイトで確認できなかったため、設定ごと廃止した。
+fn migrate_v1(value: &mut Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    obj.remove("koizumi");
+    obj.insert("version".to_string(), Value::from(2));
+}
+
+/// 設定を読み込む（未保存の場合はデフォルト値）
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Settings, String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    match store.get(SETTINGS_KEY) {
+        Some(value) => migrate(value),
+        None => Ok(Settings::default()),
+    }
+}
+
+/// 設定を検証して保存
+pub fn save<R: Runtime>(app: &AppHandle<R>, settings: &Settings) -> Result<(), String> {
+    settings.validate()?;
+
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        SETTINGS_KEY,
+        serde_json::to_value(settings).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_clamp_concurrency() {
+        assert!(clamp_concurrency(0).is_err());
+        assert_eq!(clamp_concurrency(1), Ok(1));
+        assert_eq!(clamp_concurrency(MAX_CONCURRENCY), Ok(MAX_CONCURRENCY));
+        assert_eq!(clamp_concurrency(1000), Ok(MAX_CONCURRENCY));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Settings::default().validate().is_ok());
+
+        let settings = Settings {
+            concurrency: 0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            domain_concurrency: BTreeMap::from([("toki.co.jp".to_string(), 0)]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            domain_concurrency: BTreeMap::from([("https://toki.co.jp/".to_string(), 1)]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            domain_request_interval_ms: BTreeMap::from([("toki.co.jp".to_string(), 120_000)]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            retry: RetrySettings {
+                max_attempts: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            providers: BTreeMap::from([("mock".to_string(), ProviderConfig::default())]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            providers: BTreeMap::from([(
+                "tokistar".to_string(),
+                ProviderConfig {
+                    base_url: Some("toki.co.jp".to_string()),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            filename_template: Some("{spec_no}/{model}".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            filename_template: Some("{specNo}_{model}".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            proxy: Some(ProxySettings {
+                url: "ftp://proxy.example.com".to_string(),
+                username: None,
+                password: None,
+                no_proxy: None,
+            }),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            tls: TlsSettings {
+                root_certificate_path: Some("certs/root.pem".to_string()),
+                accept_invalid_certs: false,
+            },
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_resolve_dir() {
+        let today = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+        let mut destination = DestinationSettings::default();
+
+        // 指定がなく既定の保存先もない
+        assert!(destination.resolve_dir(None, None, None, today).is_err());
+        // 呼び出し側の指定を優先
+        assert_eq!(
+            destination
+                .resolve_dir(Some("/tmp/out"), Some("p1"), Some("A棟"), today)
+                .unwrap(),
+            "/tmp/out"
+        );
+
+        destination.default_dir = Some("/data/ies".to_string());
+        assert_eq!(
+            destination
+                .resolve_dir(None, Some("p1"), Some("A棟"), today)
+                .unwrap(),
+            "/data/ies"
+        );
+
+        destination.project_subdir = Some("{project}_{date}".to_string());
+        assert!(destination.validate().is_ok());
+        assert_eq!(
+            destination
+                .resolve_dir(None, Some("p1"), Some("A棟: 照明"), today)
+                .unwrap(),
+            Path::new("/data/ies")
+                .join("A棟_ 照明_2026-04-01")
+                .to_string_lossy()
+        );
+        // プロジェクト以外のダウンロードはサブフォルダを作成しない
+        assert_eq!(
+            destination.resolve_dir(None, None, None, today).unwrap(),
+            "/data/ies"
+        );
+
+        destination.project_subdir = Some("{projectName}".to_string());
+        assert!(destination.validate().is_err());
+    }
+
+    #[test]
+    fn test_migrate_v0() {
+        let settings = migrate(json!({ "maxConcurrency": 8, "timeout": 15000 })).unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.concurrency, 8);
+        assert_eq!(settings.request_timeout_secs, 15);
+        assert_eq!(settings.destination, DestinationSettings::default());
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let settings = migrate(json!({
+            "version": 1,
+            "concurrency": 2,
+            "koizumi": { "specSheetWithIes": true }
+        }))
+        .unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.concurrency, 2);
+    }
+
+    #[test]
+    fn test_migrate_rejects_unknown_fields() {
+        assert!(migrate(json!({ "version": 1, "unknownKey": true })).is_err());
+        assert!(migrate(json!({ "version": 99 })).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! モバイル（Android・iOS）の保存先
+//!
+//! 一括ダウンロードは `std::fs` でパスに書き込むが、Androidのスコープストレージ・iOSのサンドボックスでは
+//! アプリ外の任意のパスに書き込めない。モバイルでは保存先をアプリのドキュメントディレクトリ配下
+//! （iOSは「ファイル」アプリから参照できる）に置き換えて書き込み、ユーザーが選んだ場所
+//! （AndroidのSAF・iOSの「ファイル」アプリで作成した `content://` / `file://` のURI）には
+//! ダウンロード後に tauri-plugin-fs を介して書き出す。
+//!
+//! デスクトップでは保存先のパスをそのまま使用する。
+
+use crate::filename;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_fs::{FilePath, FsExt, OpenOptions};
+
+/// 保存先を指定しなかった場合のモバイルでのフォルダ名
+const DEFAULT_FOLDER: &str = "Downloads";
+
+/// 書き出し結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageExportResult {
+    /// 書き出し先（URIまたはパス）
+    pub target: String,
+    /// 書き出したバイト数
+    pub bytes: u64,
+}
+
+/// 保存先ディレクトリを書き込みに使うパスに変換
+///
+/// デスクトップではそのまま返す。モバイルではアプリのドキュメントディレクトリ配下の、
+/// 指定した保存先の最後の要素（URIの場合はデコードしたもの）と同じ名前のフォルダを返す。
+pub fn writable_dir<R: Runtime>(app: &AppHandle<R>, dir: &str) -> Result<String, String> {
+    if !cfg!(mobile) {
+        return Ok(dir.to_string());
+    }
+    let base = app
+        .path()
+        .document_dir()
+        .map_err(|e| format!("Failed to resolve document directory: {}", e))?;
+    if dir.starts_with(&*base.to_string_lossy()) {
+        return Ok(dir.to_string());
+    }
+    Ok(base.join(folder_name(dir)).to_string_lossy().into_owned())
+}
+
+/// 保存先（パスまたはURI）からアプリ内のフォルダ名を決める
+fn folder_name(dir: &str) -> String {
+    let last = dir
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default();
+    // SAFのツリーURIは最後の要素に `primary:Documents/IES` のようにエンコードされている
+    let decoded = percent_decode(last);
+    let name = decoded.rsplit([':', '/']).next().unwrap_or_default();
+    if name.trim().is_empty() || name == "." || name == ".." {
+        DEFAULT_FOLDER.to_string()
+    } else {
+        filename::sanitize_filename(name)
+    }
+}
+
+/// パーセントエンコードをデコード（不正なシーケンスはそのまま残す）
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// ダウンロードしたファイルをユーザーが選んだ場所に書き出す
+///
+/// `target` はAndroidのSAF・iOSの「ファイル」アプリで作成したファイルのURI
+/// （保存ダイアログの戻り値）、またはデスクトップのパス。既存の内容は上書きする。
+pub fn export_file<R: Runtime>(
+    app: &AppHandle<R>,
+    source: &Path,
+    target: &str,
+) -> Result<StorageExportResult, String> {
+    let target_path: FilePath = target
+        .parse()
+        .map_err(|e| format!("Invalid export target: {}", e))?;
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    let mut reader = std::fs::File::open(crate::longpath::extended(source))
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut writer = app
+        .fs()
+        .open(target_path, options)
+        .map_err(|e| format!("Failed to open export target: {}", e))?;
+    let bytes = std::io::copy(&mut reader, &mut writer)
+        .map_err(|e| format!("Failed to write export target: {}", e))?;
+    writer
+        .sync_all()
+        .map_err(|e| format!("Failed to write export target: {}", e))?;
+
+    Ok(StorageExportResult {
+        target: target.to_string(),
+        bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folder_name() {
+        assert_eq!(folder_name("/Users/me/Projects/IES/"), "IES");
+        assert_eq!(
+            folder_name(
+                "content://com.android.externalstorage.documents/tree/primary%3ADocuments%2FIES"
+            ),
+            "IES"
+        );
+        assert_eq!(
+            folder_name("content://com.android.externalstorage.documents/tree/primary%3A"),
+            DEFAULT_FOLDER
+        );
+        assert_eq!(folder_name(""), DEFAULT_FOLDER);
+        assert_eq!(percent_decode("%E6%A1%88%E4%BB%B6%2"), "\u{6848}\u{4ef6}%2");
+    }
+}
@@ -0,0 +1,153 @@
+//! 後継品の対応表
+//!
+//! 製品ページに廃番と後継品が掲載されていた型番をプロバイダーごとに記録しておき、
+//! 後にメーカーサイトから旧型番のページが削除されて取得できなくなった場合に、後継品の型番で
+//! 取得し直せるようにする。対応表はストアに保存し、起動時に読み込む。
+
+use crate::error::ErrorCode;
+use crate::portable::{self, STORE_NAME};
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, RwLock};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 対応表を保存するキー
+const SUCCESSION_KEY: &str = "successors";
+/// 後継品の後継品をたどる上限（循環した対応表で止まらなくなるのを防ぐ）
+const MAX_HOPS: usize = 5;
+
+/// プロバイダーID → (正規化した旧型番 → 後継品の型番)
+type Successions = BTreeMap<String, BTreeMap<String, String>>;
+
+/// 起動時に読み込んだ対応表（記録のたびに更新する）
+static SUCCESSIONS: LazyLock<RwLock<Successions>> = LazyLock::new(Default::default);
+
+/// 比較用に正規化（大文字・小文字と空白を無視する）
+fn normalize(model_number: &str) -> String {
+    model_number
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// 保存済みの対応表を読み込む（起動時）
+pub fn load<R: Runtime>(app: &AppHandle<R>) {
+    let successions: Successions = app
+        .store(portable::store_path(STORE_NAME))
+        .ok()
+        .and_then(|store| store.get(SUCCESSION_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *SUCCESSIONS.write().unwrap_or_else(|e| e.into_inner()) = successions;
+}
+
+/// 旧型番と後継品の型番を記録（すでに同じ内容がある場合は保存しない）
+pub fn record<R: Runtime>(
+    app: &AppHandle<R>,
+    provider_id: &str,
+    model_number: &str,
+    successor: &str,
+) -> Result<(), String> {
+    let key = normalize(model_number);
+    if key.is_empty() || normalize(successor).is_empty() || key == normalize(successor) {
+        return Ok(());
+    }
+    let snapshot = {
+        let mut successions = SUCCESSIONS.write().unwrap_or_else(|e| e.into_inner());
+        let models = successions.entry(provider_id.to_string()).or_default();
+        if models.get(&key).map(String::as_str) == Some(successor.trim()) {
+            return Ok(());
+        }
+        models.insert(key, successor.trim().to_string());
+        successions.clone()
+    };
+
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        SUCCESSION_KEY,
+        serde_json::to_value(&snapshot).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save successors: {}", e))
+}
+
+/// 記録されている後継品の型番（後継品も廃番になっている場合は最新のものまでたどる）
+pub fn successor(provider_id: &str, model_number: &str) -> Option<String> {
+    let successions = SUCCESSIONS.read().unwrap_or_else(|e| e.into_inner());
+    find_successor(successions.get(provider_id)?, model_number)
+}
+
+fn find_successor(models: &BTreeMap<String, String>, model_number: &str) -> Option<String> {
+    let mut current: Option<&String> = None;
+    let mut key = normalize(model_number);
+    for _ in 0..MAX_HOPS {
+        match models.get(&key) {
+            Some(next) if normalize(next) != normalize(model_number) => {
+                current = Some(next);
+                key = normalize(next);
+            }
+            _ => break,
+        }
+    }
+    current.cloned()
+}
+
+/// 型番が見つからなかった（後継品で取得し直す対象の）エラーか
+pub fn is_not_found(code: ErrorCode, message: &str) -> bool {
+    match code {
+        ErrorCode::IesNotAvailable | ErrorCode::Discontinued | ErrorCode::ProviderOutdated => true,
+        ErrorCode::HttpStatus => message.contains("404"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_successor() {
+        let models = BTreeMap::from([
+            ("AD12345".to_string(), "AD22345".to_string()),
+            ("AD22345".to_string(), "AD32345".to_string()),
+            ("XD1".to_string(), "XD2".to_string()),
+            ("XD2".to_string(), "xd 1".to_string()),
+        ]);
+        // 後継品の後継品までたどる
+        assert_eq!(
+            find_successor(&models, "ad 12345").as_deref(),
+            Some("AD32345")
+        );
+        assert_eq!(
+            find_successor(&models, "AD22345").as_deref(),
+            Some("AD32345")
+        );
+        assert_eq!(find_successor(&models, "AD32345"), None);
+        // 循環している場合は元の型番に戻る手前で止める
+        assert_eq!(find_successor(&models, "XD1").as_deref(), Some("XD2"));
+    }
+
+    #[test]
+    fn test_is_not_found() {
+        assert!(is_not_found(
+            ErrorCode::IesNotAvailable,
+            "IES file not found for AD12345"
+        ));
+        assert!(is_not_found(
+            ErrorCode::HttpStatus,
+            "Download failed with status: 404 Not Found"
+        ));
+        assert!(!is_not_found(
+            ErrorCode::HttpStatus,
+            "Download failed with status: 503"
+        ));
+        assert!(!is_not_found(
+            ErrorCode::NetworkTimeout,
+            "Download request failed: timed out"
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+//! 入力の候補
+//!
+//! 単体ダウンロードのメーカー名・型番の入力欄で、事務所で過去に使ったものを補完できるよう、
+//! ダウンロード履歴（成功したもの）とIESライブラリの登録内容から前方一致する候補を返す。
+//! 使った回数の多い順・最後に使った日時の新しい順に並べる。
+
+use crate::history::HistoryEntry;
+use crate::library::LibraryEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 返す候補のデフォルト件数
+const DEFAULT_LIMIT: usize = 10;
+
+/// 補完する入力欄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestField {
+    Manufacturer,
+    Model,
+}
+
+/// 入力の候補
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// 候補（最後に使ったときの表記）
+    pub value: String,
+    /// メーカー名（型番の候補のみ。最後に使ったときのもの）
+    pub manufacturer: Option<String>,
+    /// 使った回数
+    pub count: usize,
+    /// 最後に使った日時
+    pub last_used: DateTime<Utc>,
+}
+
+/// 比較用に正規化（大文字・小文字と空白を無視する）
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// 候補を返す
+///
+/// # Arguments
+/// * `history` - ダウンロード履歴
+/// * `library` - IESライブラリの登録内容
+/// * `field` - 補完する入力欄
+/// * `prefix` - 入力中の文字列（空の場合はすべてが対象）
+/// * `manufacturer` - 型番の候補を絞り込むメーカー名
+/// * `limit` - 件数（省略時は10件）
+pub fn suggest(
+    history: &[HistoryEntry],
+    library: &[LibraryEntry],
+    field: SuggestField,
+    prefix: &str,
+    manufacturer: Option<&str>,
+    limit: Option<usize>,
+) -> Vec<Suggestion> {
+    let prefix = normalize(prefix);
+    let manufacturer = manufacturer.map(normalize).filter(|m| !m.is_empty());
+
+    // (メーカー名, 型番, 日時) の組
+    let used = history
+        .iter()
+        .filter(|entry| entry.success)
+        .map(|entry| {
+            (
+                entry.manufacturer.as_str(),
+                entry.model_number.as_str(),
+                entry.downloaded_at,
+            )
+        })
+        .chain(library.iter().map(|entry| {
+            let keyword = |key: &str| entry.keywords.get(key).map_or("", String::as_str);
+            (keyword("MANUFAC"), keyword("LUMCAT"), entry.added_at)
+        }));
+
+    let mut suggestions: HashMap<String, Suggestion> = HashMap::new();
+    for (entry_manufacturer, model, used_at) in used {
+        if manufacturer
+            .as_ref()
+            .is_some_and(|m| *m != normalize(entry_manufacturer))
+        {
+            continue;
+        }
+        let value = match field {
+            SuggestField::Manufacturer => entry_manufacturer,
+            SuggestField::Model => model,
+        }
+        .trim();
+        let key = normalize(value);
+        if key.is_empty() || !key.starts_with(&prefix) {
+            continue;
+        }
+        let suggestion = suggestions.entry(key).or_insert_with(|| Suggestion {
+            value: value.to_string(),
+            manufacturer: None,
+            count: 0,
+            last_used: used_at,
+        });
+        suggestion.count += 1;
+        if used_at >= suggestion.last_used {
+            suggestion.value = value.to_string();
+            suggestion.last_used = used_at;
+            if field == SuggestField::Model && !entry_manufacturer.trim().is_empty() {
+                suggestion.manufacturer = Some(entry_manufacturer.trim().to_string());
+            }
+        }
+    }
+
+    let mut suggestions: Vec<_> = suggestions.into_values().collect();
+    suggestions.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.last_used.cmp(&a.last_used))
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    suggestions.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::AssetType;
+    use chrono::TimeZone;
+    use std::collections::BTreeMap;
+
+    fn history_entry(manufacturer: &str, model_number: &str, day: u32) -> HistoryEntry {
+        HistoryEntry {
+            project_id: None,
+            spec_no: "A01".to_string(),
+            manufacturer: manufacturer.to_string(),
+            model_number: model_number.to_string(),
+            psu: None,
+            asset_type: AssetType::Ies,
+            success: true,
+            file_path: None,
+            original_filename: None,
+            sha256: None,
+            source: None,
+            provider: None,
+            error: None,
+            downloaded_at: Utc.with_ymd_and_hms(2026, 10, day, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let mut failed = history_entry("コイズミ", "AD99999", 9);
+        failed.success = false;
+        let history = vec![
+            history_entry("コイズミ", "AD12345", 1),
+            history_entry("コイズミ", "ad 12345", 2),
+            history_entry("コイズミ", "AD54321", 3),
+            history_entry("TOKISTAR", "AD10000", 4),
+            failed,
+        ];
+        let library = vec![LibraryEntry {
+            sha256: "abc".to_string(),
+            file_name: "AD77777.ies".to_string(),
+            path: "/library/AD77777.ies".to_string(),
+            size: 100,
+            keywords: BTreeMap::from([
+                ("MANUFAC".to_string(), "KOIZUMI".to_string()),
+                ("LUMCAT".to_string(), "AD77777".to_string()),
+            ]),
+            search_terms: Vec::new(),
+            added_at: Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap(),
+        }];
+
+        let models = suggest(&history, &library, SuggestField::Model, "ad", None, None);
+        let values: Vec<_> = models.iter().map(|s| s.value.as_str()).collect();
+        // 回数の多い順、同じ回数なら新しい順（失敗したものは含まない）
+        assert_eq!(values, vec!["ad 12345", "AD77777", "AD10000", "AD54321"]);
+        assert_eq!(models[0].count, 2);
+        assert_eq!(models[1].manufacturer.as_deref(), Some("KOIZUMI"));
+
+        // メーカーで絞り込む
+        let models = suggest(
+            &history,
+            &library,
+            SuggestField::Model,
+            "AD1",
+            Some("コイズミ"),
+            None,
+        );
+        assert_eq!(models.len(), 1);
+
+        let manufacturers = suggest(
+            &history,
+            &library,
+            SuggestField::Manufacturer,
+            "",
+            None,
+            Some(2),
+        );
+        let values: Vec<_> = manufacturers.iter().map(|s| s.value.as_str()).collect();
+        assert_eq!(values, vec!["コイズミ", "KOIZUMI"]);
+    }
+}
@@ -0,0 +1,223 @@
+//! 利用状況の集計（オプトイン）
+//!
+//! どのプロバイダーが実際の環境で失敗しているかを把握するため、設定で有効にした場合のみ
+//! 一括ダウンロードの実行回数とプロバイダーごとの成功・失敗件数を集計し、
+//! 設定した送信先に定期的に送信する。型番・ファイルパス等の個別の情報は記録しない。
+//! 集計値は tauri-plugin-store のストアファイル（`autosight.store.json`）に保存する。
+
+use crate::portable::{self, STORE_NAME};
+use crate::providers::send_request;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// 集計値を保存するキー
+const TELEMETRY_KEY: &str = "telemetry";
+/// 送信間隔（時間）
+const SEND_INTERVAL_HOURS: i64 = 24;
+
+/// 利用状況の設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    /// 集計・送信を行う（既定は無効）
+    pub enabled: bool,
+    /// 送信先URL（未指定時は集計のみ行い送信しない）
+    pub endpoint: Option<String>,
+}
+
+impl TelemetrySettings {
+    /// 送信先URLを検証
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(endpoint) = &self.endpoint {
+            let url = reqwest::Url::parse(endpoint)
+                .map_err(|e| format!("Invalid telemetry endpoint: {}", e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!(
+                    "Unsupported telemetry endpoint scheme: {}",
+                    url.scheme()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// プロバイダーごとの件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCounts {
+    pub success: u64,
+    pub failure: u64,
+}
+
+/// 前回の送信以降の集計値
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCounts {
+    /// 集計開始日時
+    pub since: DateTime<Utc>,
+    /// 一括ダウンロードの実行回数
+    pub batches_run: u64,
+    /// プロバイダーIDごとの成功・失敗件数（プロバイダーがないメーカーは "unsupported"）
+    pub providers: BTreeMap<String, ProviderCounts>,
+}
+
+impl Default for UsageCounts {
+    fn default() -> Self {
+        Self {
+            since: Utc::now(),
+            batches_run: 0,
+            providers: BTreeMap::new(),
+        }
+    }
+}
+
+impl UsageCounts {
+    /// 1回分の一括ダウンロードの結果を加算
+    pub fn add_batch(&mut self, batch: &BTreeMap<String, ProviderCounts>) {
+        self.batches_run += 1;
+        for (provider_id, counts) in batch {
+            let total = self.providers.entry(provider_id.clone()).or_default();
+            total.success += counts.success;
+            total.failure += counts.failure;
+        }
+    }
+
+    /// 送信間隔が経過しているか
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.batches_run > 0 && now - self.since >= Duration::hours(SEND_INTERVAL_HOURS)
+    }
+}
+
+/// 送信する内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub app_version: String,
+    pub os: String,
+    #[serde(flatten)]
+    pub counts: UsageCounts,
+}
+
+impl UsageReport {
+    pub fn new(app_version: String, counts: UsageCounts) -> Self {
+        Self {
+            app_version,
+            os: std::env::consts::OS.to_string(),
+            counts,
+        }
+    }
+}
+
+/// 集計値を読み込む（未保存の場合は空）
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Result<UsageCounts, String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    match store.get(TELEMETRY_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse usage counts: {}", e)),
+        None => Ok(UsageCounts::default()),
+    }
+}
+
+/// 集計値を保存
+fn save<R: Runtime>(app: &AppHandle<R>, counts: &UsageCounts) -> Result<(), String> {
+    let store = app
+        .store(portable::store_path(STORE_NAME))
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        TELEMETRY_KEY,
+        serde_json::to_value(counts).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save usage counts: {}", e))
+}
+
+/// 1回分の一括ダウンロードの結果を記録
+pub fn record_batch<R: Runtime>(
+    app: &AppHandle<R>,
+    batch: &BTreeMap<String, ProviderCounts>,
+) -> Result<(), String> {
+    let mut counts = load(app).unwrap_or_default();
+    counts.add_batch(batch);
+    save(app, &counts)
+}
+
+/// 送信間隔が経過していれば集計値を送信し、集計をやり直す
+///
+/// 送信に失敗した場合は集計値を残し、次回の一括ダウンロード後に再送する。
+pub async fn send_if_due<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    endpoint: &str,
+) -> Result<(), String> {
+    let counts = load(app)?;
+    if !counts.is_due(Utc::now()) {
+        return Ok(());
+    }
+
+    let report = UsageReport::new(app.package_info().version.to_string(), counts);
+    let response = send_request(client.post(endpoint).json(&report))
+        .await
+        .map_err(|e| format!("Telemetry request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Telemetry endpoint returned status: {}",
+            response.status()
+        ));
+    }
+
+    save(app, &UsageCounts::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_batch() {
+        let mut counts = UsageCounts::default();
+        let batch = BTreeMap::from([
+            (
+                "koizumi".to_string(),
+                ProviderCounts {
+                    success: 3,
+                    failure: 1,
+                },
+            ),
+            (
+                "unsupported".to_string(),
+                ProviderCounts {
+                    success: 0,
+                    failure: 2,
+                },
+            ),
+        ]);
+        counts.add_batch(&batch);
+        counts.add_batch(&batch);
+
+        assert_eq!(counts.batches_run, 2);
+        assert_eq!(counts.providers["koizumi"].success, 6);
+        assert_eq!(counts.providers["unsupported"].failure, 4);
+
+        assert!(!counts.is_due(counts.since + Duration::hours(1)));
+        assert!(counts.is_due(counts.since + Duration::hours(SEND_INTERVAL_HOURS)));
+        assert!(!UsageCounts::default().is_due(Utc::now() + Duration::days(30)));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(TelemetrySettings::default().validate().is_ok());
+        let settings = TelemetrySettings {
+            enabled: true,
+            endpoint: Some("ftp://metrics.example.com".to_string()),
+        };
+        assert!(settings.validate().is_err());
+    }
+}
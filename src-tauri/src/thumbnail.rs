@@ -0,0 +1,96 @@
+//! 製品画像のサムネイル
+//!
+//! メーカーサイトの製品画像を取得・縮小し、data URL としてフロントエンドに渡す。
+//! WebViewから直接メーカーサイトにアクセスしない（CORS回避）ためのもの。
+//! 縮小した画像はアプリのキャッシュディレクトリに保存し、次回以降は再取得しない。
+
+use crate::cache::{self, CacheScope};
+use base64::Engine;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// サムネイルの既定サイズ（長辺のピクセル数）
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 96;
+/// サムネイルの最大サイズ（長辺のピクセル数）
+const MAX_THUMBNAIL_SIZE: u32 = 512;
+
+/// サイズを許容範囲に収める
+pub fn clamp_size(size: Option<u32>) -> u32 {
+    size.unwrap_or(DEFAULT_THUMBNAIL_SIZE)
+        .clamp(1, MAX_THUMBNAIL_SIZE)
+}
+
+/// キャッシュファイルのパス
+///
+/// 形式: {キャッシュディレクトリ}/thumbnails/{プロバイダーID}/{型番}_{サイズ}.png
+pub fn cache_path(cache_dir: &Path, provider_id: &str, model_number: &str, size: u32) -> PathBuf {
+    CacheScope::Thumbnails
+        .dir(cache_dir)
+        .join(provider_id)
+        .join(format!("{}_{}.png", cache::file_stem(model_number), size))
+}
+
+/// 画像を縦横比を保ったまま縮小し、PNGにエンコード
+pub fn downscale(bytes: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let image =
+        image::load_from_memory(bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let mut png = Vec::new();
+    image
+        .thumbnail(size, size)
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(png)
+}
+
+/// PNGを data URL に変換
+pub fn data_url(png: &[u8]) -> String {
+    format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    )
+}
+
+/// キャッシュ済みのサムネイルを読み込む
+pub fn load_cached(path: &Path) -> Option<Vec<u8>> {
+    std::fs::read(path).ok()
+}
+
+/// サムネイルをキャッシュに保存（失敗しても取得結果には影響しない）
+pub fn store_cached(path: &Path, png: &[u8]) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, png);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path() {
+        let path = cache_path(Path::new("/cache"), "koizumi", "AD12345+XE/92701", 96);
+        assert_eq!(
+            path,
+            Path::new("/cache/thumbnails/koizumi/AD12345_XE_92701_96.png")
+        );
+        assert_eq!(clamp_size(None), DEFAULT_THUMBNAIL_SIZE);
+        assert_eq!(clamp_size(Some(4096)), MAX_THUMBNAIL_SIZE);
+    }
+
+    #[test]
+    fn test_downscale() {
+        let source = image::RgbImage::new(400, 200);
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgb8(source)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+
+        let png = downscale(&bytes, 100).unwrap();
+        let thumbnail = image::load_from_memory(&png).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (100, 50));
+        assert!(data_url(&png).starts_with("data:image/png;base64,"));
+        assert!(downscale(b"not an image", 100).is_err());
+    }
+}
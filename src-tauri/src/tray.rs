@@ -0,0 +1,152 @@
+//! システムトレイ（デスクトップのみ）
+//!
+//! 設定の `close_to_tray` が有効な場合、ウィンドウを閉じてもアプリを終了せずにトレイに常駐し、
+//! 一括ダウンロードを続ける。トレイメニューには実行中のバッチの進捗を表示し、
+//! 一時停止・再開・キャンセルを実行中のすべてのバッチに対して行える。
+
+use crate::batch::BatchState;
+use crate::commands;
+use crate::i18n::Message;
+use crate::settings;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Window, WindowEvent, Wry};
+
+/// トレイアイコンのID
+const TRAY_ID: &str = "main";
+/// メニュー項目のID
+const MENU_PROGRESS: &str = "progress";
+const MENU_SHOW: &str = "show";
+const MENU_PAUSE: &str = "pause";
+const MENU_CANCEL: &str = "cancel";
+const MENU_QUIT: &str = "quit";
+
+/// 進捗に応じて更新するメニュー項目
+pub struct TrayState {
+    progress: MenuItem<Wry>,
+    pause: MenuItem<Wry>,
+    cancel: MenuItem<Wry>,
+}
+
+/// トレイアイコンを作成
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let locale = settings::load(app).unwrap_or_default().locale;
+    let item = |id: &str, message: Message, enabled: bool| {
+        MenuItem::with_id(app, id, message.text(locale), enabled, None::<&str>)
+    };
+    let progress = item(MENU_PROGRESS, Message::TrayIdle, false)?;
+    let show = item(MENU_SHOW, Message::TrayShow, true)?;
+    let pause = item(MENU_PAUSE, Message::TrayPause, false)?;
+    let cancel = item(MENU_CANCEL, Message::TrayCancel, false)?;
+    let quit = item(MENU_QUIT, Message::TrayQuit, true)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &progress,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &pause,
+            &cancel,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(&app.package_info().name)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayState {
+        progress,
+        pause,
+        cancel,
+    });
+    Ok(())
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let batches = app.state::<BatchState>();
+    match event.id().as_ref() {
+        MENU_SHOW => show_window(app),
+        MENU_PAUSE => {
+            // 一時停止していないバッチがあればすべて一時停止し、すべて一時停止中なら再開する
+            let running = batches.running();
+            let pause = running.iter().any(|batch| !batch.is_paused());
+            for batch in running {
+                commands::set_batch_paused(app, &batch, pause);
+            }
+        }
+        MENU_CANCEL => batches.interrupt_all(),
+        MENU_QUIT => app.exit(0),
+        _ => {}
+    }
+    update(app);
+}
+
+/// メインウィンドウを表示して前面に出す
+pub fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// 実行中のバッチの進捗をトレイメニュー・ツールチップに反映
+pub fn update(app: &AppHandle) {
+    let Some(tray) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let locale = settings::load(app).unwrap_or_default().locale;
+    let running = app.state::<BatchState>().running();
+    let paused = !running.is_empty() && running.iter().all(|batch| batch.is_paused());
+
+    let progress = if running.is_empty() {
+        Message::TrayIdle
+    } else {
+        let statuses: Vec<_> = running.iter().map(|batch| batch.status()).collect();
+        Message::TrayProgress {
+            done: statuses
+                .iter()
+                .map(|s| s.success_count + s.failure_count + s.cancelled_count)
+                .sum(),
+            total: statuses.iter().map(|s| s.total).sum(),
+            paused,
+        }
+    };
+    let pause = if paused {
+        Message::TrayResume
+    } else {
+        Message::TrayPause
+    };
+
+    let text = progress.text(locale);
+    let _ = tray.progress.set_text(&text);
+    let _ = tray.pause.set_text(pause.text(locale));
+    let _ = tray.pause.set_enabled(!running.is_empty());
+    let _ = tray.cancel.set_enabled(!running.is_empty());
+    if let Some(icon) = app.tray_by_id(TRAY_ID) {
+        let tooltip = format!("{} - {}", app.package_info().name, text);
+        let _ = icon.set_tooltip(Some(tooltip));
+    }
+}
+
+/// ウィンドウを閉じる操作を処理
+///
+/// トレイに常駐する設定の場合は、アプリを終了せずにウィンドウを隠す。
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    let app = window.app_handle();
+    if app.try_state::<TrayState>().is_some() && settings::load(app).is_ok_and(|s| s.close_to_tray)
+    {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
@@ -0,0 +1,300 @@
+//! 一括ダウンロードの結果の器具リストExcelへの書き戻し
+//!
+//! 読み込んだ器具リストのシートを新しいブックに値のまま写し、右端に結果の列
+//! （結果・ファイル・ファイルサイズ・エラー）を追加して保存する。元のファイルは変更しない。
+//! 行は Spec No. と型番で結果と対応させる（型番が一致しない場合は Spec No. のみで対応させる）。
+//! 書式・数式・結合セルは写さない（数式は計算済みの値になる）。
+
+use crate::commands::SingleDownloadResult;
+use crate::excel::{self, ImportProfile, Sheet};
+use crate::i18n::{Locale, Message};
+use crate::providers::CANCELLED;
+use calamine::Data;
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet, XlsxError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 書き戻しの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritebackResult {
+    /// 保存したブックのパス
+    pub path: String,
+    /// 結果を書き込んだ行数
+    pub updated_rows: usize,
+    /// シートに対応する行がなかった Spec No.
+    pub unmatched_spec_nos: Vec<String>,
+}
+
+/// 追加する列の見出し
+fn result_columns(locale: Locale) -> [String; 4] {
+    [
+        Message::ColumnResult.text(locale),
+        Message::ColumnFile.text(locale),
+        Message::ColumnFileSize.text(locale),
+        Message::ColumnError.text(locale),
+    ]
+}
+
+/// 書き戻し先の既定のパス（元のファイルと同じフォルダの `{元のファイル名}_results.xlsx`）
+pub fn default_dest_path(source: &Path) -> std::path::PathBuf {
+    let stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    source.with_file_name(format!("{}_results.xlsx", stem))
+}
+
+/// 器具リスト（`source`）に結果の列を追加したブックを `dest` に保存
+pub fn write(
+    source: &str,
+    dest: &Path,
+    profile: &ImportProfile,
+    results: &[SingleDownloadResult],
+    locale: Locale,
+) -> Result<WritebackResult, String> {
+    if std::fs::canonicalize(source).ok() == std::fs::canonicalize(dest).ok() && dest.exists() {
+        return Err("Cannot overwrite the source spec sheet".to_string());
+    }
+    let sheet = excel::read_sheet(source, profile, locale)?;
+    let (mut workbook, updated_rows, unmatched_spec_nos) =
+        build(&sheet, profile, results, locale).map_err(|e| e.to_string())?;
+    workbook
+        .save(dest)
+        .map_err(|e| format!("Failed to save workbook: {}", e))?;
+
+    Ok(WritebackResult {
+        path: dest.to_string_lossy().into_owned(),
+        updated_rows,
+        unmatched_spec_nos,
+    })
+}
+
+/// シートを写したブックを作成し、結果を書き込んだ行数と対応する行がなかった Spec No. を返す
+fn build(
+    sheet: &Sheet,
+    profile: &ImportProfile,
+    results: &[SingleDownloadResult],
+    locale: Locale,
+) -> Result<(Workbook, usize, Vec<String>), XlsxError> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    // シート名の制約（31文字・使用できない記号）に合わない場合は既定の名前のままにする
+    if worksheet.set_name(&sheet.name).is_err() {
+        tracing::debug!(sheet = %sheet.name, "sheet name not usable, keeping default");
+    }
+    copy_cells(worksheet, sheet)?;
+
+    let range = &sheet.range;
+    let header_row = excel::detect_header_row(range, profile);
+    let sheet_columns = excel::header_columns(range, header_row);
+    let column = |name: &str| sheet_columns.get(&excel::header_key(name)).copied();
+    let (spec_no_col, fixture_col) = (
+        column(&profile.spec_no_column),
+        column(&profile.fixture_column),
+    );
+    let (last_row, last_col) = range.end().unwrap_or((0, 0));
+    let first_col = (last_col + 1) as u16;
+
+    let header = Format::new().set_bold();
+    for (i, title) in result_columns(locale).into_iter().enumerate() {
+        worksheet.write_string_with_format(header_row - 1, first_col + i as u16, title, &header)?;
+    }
+    worksheet.set_column_width(first_col + 1, 40)?;
+    worksheet.set_column_width(first_col + 3, 50)?;
+
+    let failed = Format::new()
+        .set_font_color(Color::RGB(0x9C0006))
+        .set_background_color(Color::RGB(0xFFC7CE));
+    let mut matched = HashSet::new();
+    let mut updated_rows = 0;
+    for row_number in header_row + 1..=last_row + 1 {
+        let cell = |col: Option<u32>| {
+            col.and_then(|col| excel::cell_string(excel::cell_at(range, row_number, col)))
+        };
+        let Some(spec_no) = cell(spec_no_col) else {
+            continue;
+        };
+        let fixture = cell(fixture_col);
+        let Some((index, result)) = results
+            .iter()
+            .enumerate()
+            .find(|(_, r)| r.spec_no == spec_no && Some(&r.model_number) == fixture.as_ref())
+            .or_else(|| {
+                results
+                    .iter()
+                    .enumerate()
+                    .find(|(_, r)| r.spec_no == spec_no)
+            })
+        else {
+            continue;
+        };
+        matched.insert(index);
+        updated_rows += 1;
+
+        let row = row_number - 1;
+        let outcome = &result.result;
+        let cancelled = outcome.error.as_deref() == Some(CANCELLED);
+        match (outcome.success, cancelled) {
+            (true, _) => worksheet.write_string(row, first_col, "OK")?,
+            (false, true) => worksheet.write_string(row, first_col, "-")?,
+            (false, false) => worksheet.write_string_with_format(row, first_col, "NG", &failed)?,
+        };
+        if let Some(file_name) = outcome
+            .file_path
+            .as_deref()
+            .and_then(|path| Path::new(path).file_name())
+        {
+            worksheet.write_string(row, first_col + 1, file_name.to_string_lossy())?;
+        }
+        if let Some(size) = outcome.file_size.filter(|_| outcome.success) {
+            worksheet.write_number(row, first_col + 2, size as f64)?;
+        }
+        if let Some(error) = outcome.error.as_deref().filter(|_| !outcome.success) {
+            worksheet.write_string(row, first_col + 3, error)?;
+        }
+    }
+
+    let unmatched_spec_nos = results
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !matched.contains(i))
+        .map(|(_, r)| r.spec_no.clone())
+        .collect();
+    Ok((workbook, updated_rows, unmatched_spec_nos))
+}
+
+/// シートのセルの値を写す（日付は日付の書式で書き込む）
+fn copy_cells(worksheet: &mut Worksheet, sheet: &Sheet) -> Result<(), XlsxError> {
+    let (first_row, first_col) = sheet.range.start().unwrap_or((0, 0));
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let date_time = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+    for (r, c, value) in sheet.range.cells() {
+        let (row, col) = (first_row + r as u32, (first_col as usize + c) as u16);
+        match value {
+            Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => {
+                worksheet.write_string(row, col, s)?;
+            }
+            Data::Float(f) => {
+                worksheet.write_number(row, col, *f)?;
+            }
+            Data::Int(i) => {
+                worksheet.write_number(row, col, *i as f64)?;
+            }
+            Data::Bool(b) => {
+                worksheet.write_boolean(row, col, *b)?;
+            }
+            Data::DateTime(dt) => {
+                let serial = dt.as_f64();
+                let format = if serial.fract() == 0.0 {
+                    &date
+                } else {
+                    &date_time
+                };
+                worksheet.write_number_with_format(row, col, serial, format)?;
+            }
+            Data::Empty | Data::Error(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{DownloadResult, DownloadTiming};
+    use calamine::{open_workbook_auto, Range, Reader};
+
+    fn s(value: &str) -> Data {
+        Data::String(value.to_string())
+    }
+
+    fn item(spec_no: &str, model_number: &str, result: DownloadResult) -> SingleDownloadResult {
+        SingleDownloadResult {
+            spec_no: spec_no.to_string(),
+            model_number: model_number.to_string(),
+            result,
+            assets: Vec::new(),
+            timing: DownloadTiming::default(),
+            wattage_check: None,
+            skipped: false,
+            deduplicated: false,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let rows = [
+            vec![s("Spec No."), s("メーカー"), s("FIXTURE"), s("消費電力")],
+            vec![s("*Insert new Rows above this line")],
+            vec![s("A01"), s("コイズミ照明"), s("AD12345"), Data::Float(8.5)],
+            vec![s("A02"), s("TOKISTAR"), s("SDL-1"), Data::Int(12)],
+            vec![s("A03"), s("TOKISTAR"), s("SDL-2")],
+        ];
+        let mut range = Range::new((0, 0), (4, 3));
+        for (r, row) in rows.into_iter().enumerate() {
+            for (c, value) in row.into_iter().enumerate() {
+                range.set_value((r as u32, c as u32), value);
+            }
+        }
+        let sheet = Sheet {
+            name: "Fixture Base".to_string(),
+            names: vec!["Fixture Base".to_string()],
+            range,
+        };
+        let results = vec![
+            item(
+                "A01",
+                "AD12345",
+                DownloadResult::success("/out/A01_AD12345.ies".to_string(), 2048, None),
+            ),
+            item(
+                "A02",
+                "SDL-1",
+                DownloadResult::failure("IES file not available for: SDL-1".to_string()),
+            ),
+            item(
+                "A03",
+                "SDL-2",
+                DownloadResult::failure(CANCELLED.to_string()),
+            ),
+            item(
+                "B01",
+                "X-1",
+                DownloadResult::failure("request failed".to_string()),
+            ),
+        ];
+
+        let (mut workbook, updated_rows, unmatched) =
+            build(&sheet, &ImportProfile::default(), &results, Locale::Ja).unwrap();
+        assert_eq!(updated_rows, 3);
+        assert_eq!(unmatched, vec!["B01"]);
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("results.xlsx");
+        workbook.save(&path).unwrap();
+        let mut reopened = open_workbook_auto(&path).unwrap();
+        let range = reopened.worksheet_range("Fixture Base").unwrap();
+        assert_eq!(range.get_value((0, 4)), Some(&s("結果")));
+        assert_eq!(range.get_value((2, 3)), Some(&Data::Float(8.5)));
+        assert_eq!(range.get_value((2, 4)), Some(&s("OK")));
+        assert_eq!(range.get_value((2, 5)), Some(&s("A01_AD12345.ies")));
+        assert_eq!(range.get_value((2, 6)), Some(&Data::Float(2048.0)));
+        assert_eq!(range.get_value((3, 4)), Some(&s("NG")));
+        assert_eq!(
+            range.get_value((3, 7)),
+            Some(&s("IES file not available for: SDL-1"))
+        );
+        assert_eq!(range.get_value((4, 4)), Some(&s("-")));
+    }
+
+    #[test]
+    fn test_default_dest_path() {
+        assert_eq!(
+            default_dest_path(Path::new("/projects/照明器具リスト.xlsx")),
+            Path::new("/projects/照明器具リスト_results.xlsx")
+        );
+    }
+}
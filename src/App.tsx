@@ -12,9 +12,9 @@ import { useWizardState } from './components/wizard/useWizardState';
 import { ProjectListPage } from './components/project/ProjectListPage';
 import { ProjectDetailPage } from './components/project/ProjectDetailPage';
 import { useProjectStore } from './hooks/useProjectStore';
-import { getSupportedManufacturers, batchDownloadIesFiles, listenDownloadProgress } from './services/tauri/commands';
+import { getSupportedManufacturers, getSettings, batchDownloadIesFiles, listenDownloadProgress, errorMessage } from './services/tauri/commands';
 import { parseExcelFromBinary, updateIesFileCheck } from './services/excel/parser';
-import type { Fixture, FixtureSelection, BatchDownloadResult } from './types/fixture';
+import type { Fixture, FixtureSelection, BatchDownloadResult, DownloadProgressEvent } from './types/fixture';
 import type { Project } from './types/project';
 import type { ParseResult } from './services/excel/parser';
 import type { StepConfig } from './components/wizard/WizardStepper';
@@ -49,6 +49,8 @@ function App() {
 
   // ダウンロード関連
   const [destDir, setDestDir] = useState<string>('');
+  // 設定の既定の保存先（保存先を選択しなかった場合にバックエンドで使用される）
+  const [defaultDestDir, setDefaultDestDir] = useState<string | undefined>(undefined);
   const [isDownloading, setIsDownloading] = useState(false);
   const [lastResult, setLastResult] = useState<BatchDownloadResult | null>(null);
 
@@ -74,34 +76,47 @@ function App() {
       .catch(console.error);
   }, []);
 
-  // ダウンロード進捗イベントをリッスン
+  // 既定の保存先を取得
+  useEffect(() => {
+    getSettings()
+      .then((settings) => setDefaultDestDir(settings.destination.defaultDir))
+      .catch(console.error);
+  }, []);
+
+  // ダウンロード進捗を反映
+  const applyProgress = useCallback((event: DownloadProgressEvent) => {
+    setSelections((prev) =>
+      prev.map((s) => {
+        if (s.fixture.specNo !== event.specNo) return s;
+
+        // ステータスに応じて更新
+        if (event.status === 'processing') {
+          return { ...s, downloadStatus: 'downloading', downloadProgress: event };
+        } else if (event.status === 'success' || event.status === 'skipped') {
+          // 飛ばしたアイテムは保存先に取得済みのファイルがあるため成功として扱う
+          return { ...s, downloadStatus: 'success', downloadError: undefined, downloadProgress: undefined };
+        } else if (event.status === 'error') {
+          return { ...s, downloadStatus: 'error', downloadError: event.error, downloadProgress: undefined };
+        } else if (event.status === 'cancelled') {
+          return { ...s, downloadStatus: 'cancelled', downloadError: undefined, downloadProgress: undefined };
+        }
+        return s;
+      })
+    );
+  }, []);
+
+  // 他のウィンドウ・トレイから開始したバッチの進捗イベントをリッスン
   useEffect(() => {
     let unlisten: (() => void) | null = null;
 
-    listenDownloadProgress((event) => {
-      setSelections((prev) =>
-        prev.map((s) => {
-          if (s.fixture.specNo !== event.specNo) return s;
-
-          // ステータスに応じて更新
-          if (event.status === 'processing') {
-            return { ...s, downloadStatus: 'downloading' };
-          } else if (event.status === 'success') {
-            return { ...s, downloadStatus: 'success', downloadError: undefined };
-          } else if (event.status === 'error') {
-            return { ...s, downloadStatus: 'error', downloadError: event.error };
-          }
-          return s;
-        })
-      );
-    }).then((fn) => {
+    listenDownloadProgress(applyProgress).then((fn) => {
       unlisten = fn;
     });
 
     return () => {
       if (unlisten) unlisten();
     };
-  }, []);
+  }, [applyProgress]);
 
   // ウィザードリセット
   const resetWizard = useCallback(() => {
@@ -174,7 +189,7 @@ function App() {
   // ダウンロード実行
   const handleDownload = useCallback(async () => {
     const selectedItems = selections.filter((s) => s.selected);
-    if (!destDir || selectedItems.length === 0) return;
+    if (!(destDir || defaultDestDir) || selectedItems.length === 0) return;
 
     setIsDownloading(true);
     setLastResult(null);
@@ -195,8 +210,14 @@ function App() {
           manufacturer: item.fixture.manufacturer,
           modelNumber: item.fixture.fixture,
           psu: item.fixture.psu,
+          wattage: item.fixture.wattage,
+          quantity: item.fixture.quantity,
         })),
-        destDir,
+        destDir: destDir || undefined,
+        projectId: selectedProjectId ?? undefined,
+      }, (event) => {
+        // リスナーの登録を待たずに、このバッチの進捗を漏れなく受け取る
+        if (event.event === 'progress') applyProgress(event.data);
       });
 
       // 最終結果を保存（サマリー表示用）
@@ -209,14 +230,14 @@ function App() {
           ...s,
           downloadStatus: s.downloadStatus === 'waiting' ? 'error' : s.downloadStatus,
           downloadError: s.downloadStatus === 'waiting'
-            ? (err instanceof Error ? err.message : 'ダウンロードに失敗')
+            ? errorMessage(err, 'ダウンロードに失敗')
             : s.downloadError,
         }))
       );
     } finally {
       setIsDownloading(false);
     }
-  }, [selections, destDir]);
+  }, [selections, destDir, defaultDestDir, selectedProjectId, applyProgress]);
 
   // Excel保存処理
   const handleSaveToExcel = useCallback(async () => {
@@ -247,7 +268,7 @@ function App() {
 
   // 選択されている器具の数
   const selectedCount = selections.filter((s) => s.selected).length;
-  const canDownload = selectedCount > 0 && destDir && !isDownloading;
+  const canDownload = selectedCount > 0 && (destDir || defaultDestDir) && !isDownloading;
   const canSave = lastResult && lastResult.successCount > 0 && !isSaving && !saveComplete;
 
   // プロジェクト選択時のハンドラー
@@ -392,7 +413,11 @@ function App() {
                     <TextInput
                       value={destDir}
                       onChange={(e) => setDestDir(e.target.value)}
-                      placeholder="保存先フォルダを選択..."
+                      placeholder={
+                        defaultDestDir
+                          ? `既定の保存先: ${defaultDestDir}`
+                          : '保存先フォルダを選択...'
+                      }
                       className="flex-1"
                       readOnly
                     />
@@ -1,7 +1,7 @@
 import { useState, useMemo } from 'react';
 import { Button, Spinner, Tooltip } from 'flowbite-react';
 import { HiSearch, HiCheck, HiX, HiExclamation, HiClock } from 'react-icons/hi';
-import type { Fixture, FixtureSelection } from '../../types/fixture';
+import type { DownloadPhase, DownloadProgressEvent, Fixture, FixtureSelection } from '../../types/fixture';
 
 interface FixtureTableProps {
   fixtures: Fixture[];
@@ -10,7 +10,35 @@ interface FixtureTableProps {
   supportedManufacturers: string[];
 }
 
-function StatusBadge({ status, error }: { status?: string; error?: string }) {
+/** 処理中の段階の表示名 */
+const PHASE_LABELS: Record<DownloadPhase, string> = {
+  lookup: '検索中',
+  downloading: '受信中',
+  extracting: '展開中',
+  renaming: '保存中',
+  awaitingDecision: '選択待ち',
+};
+
+/** 処理中の表示（段階と、受信中は進捗率または受信したサイズ） */
+function progressLabel(progress?: DownloadProgressEvent): string {
+  if (!progress?.phase) return '処理中';
+  const label = PHASE_LABELS[progress.phase];
+  if (progress.phase !== 'downloading' || progress.bytes === undefined) return label;
+  if (progress.totalBytes) {
+    return `${label} ${Math.min(100, Math.floor((progress.bytes / progress.totalBytes) * 100))}%`;
+  }
+  return `${label} ${(progress.bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+function StatusBadge({
+  status,
+  error,
+  progress,
+}: {
+  status?: string;
+  error?: string;
+  progress?: DownloadProgressEvent;
+}) {
   if (status === 'waiting') {
     return (
       <span className="inline-flex items-center gap-1 px-2 py-1 text-xs font-medium rounded bg-gray-200 text-gray-600 whitespace-nowrap">
@@ -24,7 +52,7 @@ function StatusBadge({ status, error }: { status?: string; error?: string }) {
     return (
       <span className="inline-flex items-center gap-1 px-2 py-1 text-xs font-medium rounded bg-blue-100 text-blue-800 whitespace-nowrap">
         <Spinner size="xs" className="animate-spin" />
-        処理中
+        {progressLabel(progress)}
       </span>
     );
   }
@@ -49,6 +77,15 @@ function StatusBadge({ status, error }: { status?: string; error?: string }) {
     );
   }
 
+  if (status === 'cancelled') {
+    return (
+      <span className="inline-flex items-center gap-1 px-2 py-1 text-xs font-medium rounded bg-gray-200 text-gray-600 whitespace-nowrap">
+        <HiX className="w-3.5 h-3.5" />
+        キャンセル
+      </span>
+    );
+  }
+
   return (
     <span className="px-2 py-1 text-xs font-medium rounded bg-gray-100 text-gray-500 whitespace-nowrap">
       -
@@ -112,6 +149,7 @@ export function FixtureTable({
         selected: isFiltered ? !allSelected : current?.selected || false,
         downloadStatus: current?.downloadStatus,
         downloadError: current?.downloadError,
+        downloadProgress: current?.downloadProgress,
       };
     });
 
@@ -229,7 +267,11 @@ export function FixtureTable({
                     )}
                   </td>
                   <td className="px-4 py-3">
-                    <StatusBadge status={selection?.downloadStatus} error={selection?.downloadError} />
+                    <StatusBadge
+                      status={selection?.downloadStatus}
+                      error={selection?.downloadError}
+                      progress={selection?.downloadProgress}
+                    />
                   </td>
                 </tr>
               );
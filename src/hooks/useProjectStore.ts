@@ -5,6 +5,7 @@
 
 import { useState, useEffect, useCallback } from 'react';
 import { Store } from '@tauri-apps/plugin-store';
+import { getStorePath } from '../services/tauri/commands';
 import {
   Project,
   CreateProjectInput,
@@ -12,7 +13,6 @@ import {
   createProjectId,
 } from '../types/project';
 
-const STORE_NAME = 'autosight.store.json';
 const PROJECTS_KEY = 'projects';
 const CURRENT_PROJECT_KEY = 'current_project_id';
 
@@ -42,14 +42,19 @@ function getBasename(path: string): string {
   return parts[parts.length - 1] || path;
 }
 
-/** ストアインスタンスを取得（キャッシュ） */
+/**
+ * ストアインスタンスを取得（キャッシュ）
+ * ポータブルモードではバックエンドが指定する保存先フォルダのストアを開く
+ */
 let storePromise: Promise<Store> | null = null;
 async function getStore(): Promise<Store> {
   if (!storePromise) {
-    storePromise = Store.load(STORE_NAME, {
-      defaults: {},
-      autoSave: false,
-    });
+    storePromise = getStorePath().then((path) =>
+      Store.load(path, {
+        defaults: {},
+        autoSave: false,
+      })
+    );
   }
   return storePromise;
 }
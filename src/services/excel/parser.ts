@@ -33,6 +33,7 @@ const COLUMN_MAPPING: Record<string, keyof Fixture> = {
   'Cost of Fixture': 'costFixture',
   'Cost of Driver': 'costDriver',
   'Cost of Others': 'costOthers',
+  台数: 'quantity',
 };
 
 /** 必須カラム */
@@ -169,6 +170,12 @@ export async function parseExcelFromBinary(data: Uint8Array): Promise<ParseResul
         case 'costOthers':
           fixture[fixtureKey] = typeof value === 'number' ? value : parseFloat(String(value));
           break;
+        case 'quantity': {
+          // 1以上の整数のみ（小数は四捨五入）
+          const quantity = Math.round(typeof value === 'number' ? value : parseFloat(String(value)));
+          if (quantity >= 1) fixture[fixtureKey] = quantity;
+          break;
+        }
         default:
           fixture[fixtureKey] = String(value).trim();
       }
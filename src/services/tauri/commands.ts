@@ -3,15 +3,110 @@
  * Rust側で定義したコマンドをTypeScriptから呼び出すためのラッパー
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { Channel, invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
+  AvailabilityReport,
+  AvailabilityResult,
+  BackoffEvent,
+  BatchAssetDownloadRequest,
+  BatchBundleResult,
+  BatchDownloadItem,
   BatchDownloadRequest,
   BatchDownloadResult,
+  BatchEstimate,
+  BatchEvent,
+  BatchFinishedEvent,
+  BatchStatus,
+  CacheScope,
+  CacheStats,
+  ClipboardImportResult,
+  CloudUploadResult,
+  CommandError,
+  ConeDiagram,
+  CrashReport,
+  CustomProvidersResult,
+  DecisionRequiredEvent,
   DownloadProgressEvent,
   DownloadResult,
+  DroppedFileResult,
+  ExportTarget,
+  Fixture,
+  HistoryPage,
+  HistoryQuery,
+  IesAnalysis,
+  IesSummary,
+  IgnoreEntry,
+  IgnoreKind,
+  ImportProfile,
+  ImportResult,
+  InterruptedBatch,
+  ItemResolution,
+  JobResult,
+  LibraryEntry,
+  ManualSource,
+  ManufacturerAlias,
+  OfflinePrepareProgress,
+  OfflinePrepareSummary,
+  PrefetchSummary,
+  PriceResult,
+  ProductCandidate,
   ProductInfo,
+  ProductInfoResult,
+  ProjectExportResult,
+  ProviderDefinition,
+  ProviderDiagnosis,
+  ProviderInfo,
+  RenameRequest,
+  RenameResult,
+  ReportFormat,
+  ResolveItemOptions,
+  ResolvedIesUrl,
+  RulesStatus,
+  Session,
+  StorageExportResult,
+  SuggestField,
+  Suggestion,
+  UrlDownloadRequest,
+  UrlDownloadResult,
+  UsageReport,
+  WritebackResult,
+  ZipCandidate,
 } from '../../types/fixture';
+import type {
+  CloudProvider,
+  CloudToken,
+  ConfigImportResult,
+  LogLevel,
+  NetworkSettings,
+  NetworkShareSettings,
+  PortableInfo,
+  PowerStatus,
+  ProviderConfig,
+  Settings,
+} from '../../types/settings';
+
+/**
+ * invoke の reject 値がコマンドのエラーかどうか
+ */
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    'code' in err &&
+    'message' in err
+  );
+}
+
+/**
+ * エラーから表示用のメッセージを取り出す
+ */
+export function errorMessage(err: unknown, fallback: string): string {
+  if (isCommandError(err) || err instanceof Error) {
+    return err.message;
+  }
+  return fallback;
+}
 
 /**
  * 対応メーカー一覧を取得
@@ -20,6 +115,140 @@ export async function getSupportedManufacturers(): Promise<string[]> {
   return invoke<string[]>('get_supported_manufacturers');
 }
 
+/**
+ * 登録済みプロバイダーのメタデータ一覧を取得（無効なプロバイダーを含む）
+ */
+export async function listProviders(): Promise<ProviderInfo[]> {
+  return invoke<ProviderInfo[]>('list_providers');
+}
+
+/**
+ * プロバイダーの有効・無効を切り替え
+ */
+export async function setProviderEnabled(id: string, enabled: boolean): Promise<void> {
+  return invoke<void>('set_provider_enabled', { id, enabled });
+}
+
+/**
+ * 利用者が登録したメーカー名の別名一覧を取得
+ */
+export async function listManufacturerAliases(): Promise<ManufacturerAlias[]> {
+  return invoke<ManufacturerAlias[]>('list_manufacturer_aliases');
+}
+
+/**
+ * メーカー名の別名を登録（同じ表記の別名がある場合は置き換える）
+ * @param alias メーカー欄の表記
+ * @param providerId 対応付けるプロバイダーID
+ * @returns 登録後の別名一覧
+ */
+export async function addManufacturerAlias(
+  alias: string,
+  providerId: string
+): Promise<ManufacturerAlias[]> {
+  return invoke<ManufacturerAlias[]>('add_manufacturer_alias', { alias, providerId });
+}
+
+/**
+ * プロバイダーごとの接続設定（ベースURL・User-Agent・タイムアウト・試行回数）を取得
+ */
+export async function getProviderSettings(): Promise<Record<string, ProviderConfig>> {
+  return invoke<Record<string, ProviderConfig>>('get_provider_settings');
+}
+
+/**
+ * プロバイダーごとの接続設定を保存し、プロバイダーを作り直す
+ * 実行中の一括ダウンロードには反映しない
+ */
+export async function updateProviderSettings(
+  providers: Record<string, ProviderConfig>
+): Promise<Record<string, ProviderConfig>> {
+  return invoke<Record<string, ProviderConfig>>('update_provider_settings', { providers });
+}
+
+/**
+ * 定義ファイルのプロバイダー（カスタムプロバイダー）を読み込み直す
+ * アプリのデータディレクトリの providers/ にある定義ファイル（JSON・TOML）から登録し直す
+ */
+export async function reloadCustomProviders(): Promise<CustomProvidersResult> {
+  return invoke<CustomProvidersResult>('reload_custom_providers');
+}
+
+/**
+ * プロバイダーの定義（JSON・TOML）を検証し、解析した定義を返す
+ * 組み込みのプロバイダー・他の定義ファイルのプロバイダーと同じIDはエラー
+ */
+export async function validateProviderDefinition(contents: string): Promise<ProviderDefinition> {
+  return invoke<ProviderDefinition>('validate_provider_definition', { contents });
+}
+
+/** 通信の設定（プロキシ・TLS）を取得 */
+export async function getNetworkSettings(): Promise<NetworkSettings> {
+  return invoke<NetworkSettings>('get_network_settings');
+}
+
+/**
+ * 通信の設定（プロキシ・TLS）を保存し、プロバイダーを作り直す
+ * ルート証明書を読み込めない場合はエラー（保存しない）。実行中の一括ダウンロードには反映しない
+ */
+export async function updateNetworkSettings(network: NetworkSettings): Promise<NetworkSettings> {
+  return invoke<NetworkSettings>('update_network_settings', { network });
+}
+
+/**
+ * プロバイダーの疎通確認と製品検索の動作確認を行う
+ * 失敗時は原因（DNS・プロキシ認証・ページ構成の変更等）が status に分類される
+ */
+export async function testProviderConnection(id: string): Promise<ProviderDiagnosis> {
+  return invoke<ProviderDiagnosis>('test_provider_connection', { id });
+}
+
+/**
+ * 不具合報告用の診断情報バンドル（ZIP）を作成
+ * ログ・設定（認証情報は伏せ字）・プロバイダーの疎通確認結果・最近の失敗履歴・
+ * 直前の一括ダウンロードの結果・クラッシュレポートを含む
+ * @param destPath 保存先（省略時はデータディレクトリの diagnostics フォルダ）
+ * @param skipProviderChecks メーカーサイトへの疎通確認を行わない
+ * @returns 保存先パス
+ */
+export async function createDiagnosticsBundle(
+  destPath?: string,
+  skipProviderChecks?: boolean
+): Promise<string> {
+  return invoke<string>('create_diagnostics_bundle', { destPath, skipProviderChecks });
+}
+
+/**
+ * 最近のログを取得（ログファイルの末尾、古い順）
+ * @param limit 行数（省略時は500行）
+ */
+export async function getRecentLogs(limit?: number): Promise<string[]> {
+  return invoke<string[]>('get_recent_logs', { limit });
+}
+
+/**
+ * 未確認のクラッシュレポート一覧を取得（新しい順）
+ * 起動時に呼び出し、前回の実行中に発生したパニックを知らせるために使用する
+ */
+export async function getCrashReports(): Promise<CrashReport[]> {
+  return invoke<CrashReport[]>('get_crash_reports');
+}
+
+/**
+ * クラッシュレポートを指定パスに書き出す
+ * @returns 保存先パス
+ */
+export async function exportCrashReport(id: string, destPath: string): Promise<string> {
+  return invoke<string>('export_crash_report', { id, destPath });
+}
+
+/**
+ * クラッシュレポートを確認済みにする（一覧から削除）
+ */
+export async function dismissCrashReport(id: string): Promise<void> {
+  return invoke<void>('dismiss_crash_report', { id });
+}
+
 /**
  * メーカーが対応しているか確認
  */
@@ -27,6 +256,70 @@ export async function isManufacturerSupported(manufacturer: string): Promise<boo
   return invoke<boolean>('is_manufacturer_supported', { manufacturer });
 }
 
+/**
+ * Excel器具リストを読み込む
+ * 行データと検証警告（未対応メーカー・型番未入力・Spec No.重複等）を返す
+ */
+export async function importExcel(
+  path: string,
+  profile?: ImportProfile,
+  projectId?: string
+): Promise<ImportResult> {
+  return invoke<ImportResult>('import_excel', { path, profile, projectId });
+}
+
+/**
+ * Excelからコピーした行（タブ区切り）をダウンロードアイテムに変換
+ * ヘッダー行がない場合は「メーカー・型番・Spec No.・PSU」の順の列とみなす
+ */
+export async function parseClipboardItems(
+  text: string,
+  profile?: ImportProfile
+): Promise<ClipboardImportResult> {
+  return invoke<ClipboardImportResult>('parse_clipboard_items', { text, profile });
+}
+
+/**
+ * ドロップされたファイルを取り込む
+ * 器具リスト（.xlsx / .xls / .csv）は読み込み結果を返し、IESファイルはIESライブラリに登録する
+ */
+export async function ingestDroppedFiles(
+  paths: string[],
+  profile?: ImportProfile
+): Promise<DroppedFileResult[]> {
+  return invoke<DroppedFileResult[]>('ingest_dropped_files', { paths, profile });
+}
+
+/**
+ * IESライブラリの登録内容を取得
+ */
+export async function getLibraryEntries(): Promise<LibraryEntry[]> {
+  return invoke<LibraryEntry[]>('get_library_entries');
+}
+
+/**
+ * IESライブラリを検索
+ * @param query 空白区切りの検索語（例: "3000K 15度 Koizumi"）
+ * @returns すべての語に一致する登録内容（新しい順）
+ */
+export async function searchLibrary(query: string): Promise<LibraryEntry[]> {
+  return invoke<LibraryEntry[]>('search_library', { query });
+}
+
+/**
+ * メーカー名・型番の入力の候補を取得（過去に使ったものを回数の多い順に返す）
+ * @param prefix 入力中の文字列（大文字・小文字と空白は無視）
+ * @param manufacturer 型番の候補を絞り込むメーカー名
+ */
+export async function suggest(
+  field: SuggestField,
+  prefix: string,
+  manufacturer?: string,
+  limit?: number
+): Promise<Suggestion[]> {
+  return invoke<Suggestion[]>('suggest', { field, prefix, manufacturer, limit });
+}
+
 /**
  * 製品情報を取得
  */
@@ -40,49 +333,1067 @@ export async function fetchProductInfo(
   });
 }
 
+/**
+ * 製品情報を一括取得
+ * 同時実行数を制限して並列に取得し、1行ごとに product-info-progress イベントで通知される
+ */
+export async function fetchProductInfoBatch(
+  items: BatchDownloadItem[],
+  maxConcurrency?: number
+): Promise<ProductInfoResult[]> {
+  return invoke<ProductInfoResult[]>('fetch_product_info_batch', {
+    items: items.map((item) => ({
+      specNo: item.specNo,
+      manufacturer: item.manufacturer,
+      modelNumber: item.modelNumber,
+      psu: item.psu,
+    })),
+    maxConcurrency,
+  });
+}
+
+/**
+ * 器具リスト取り込み後の製品情報の先読みを開始
+ * バックグラウンドで並列に取得し、1行ごとに product-info-progress イベント、
+ * 完了時に product-info-prefetch-finished イベントで通知される。取得結果はキャッシュされる
+ * @returns 取得する製品数（メーカー・型番の重複を除いた件数）
+ */
+export async function prefetchProductInfo(
+  items: BatchDownloadItem[],
+  maxConcurrency?: number
+): Promise<number> {
+  return invoke<number>('prefetch_product_info', {
+    items: items.map((item) => ({
+      specNo: item.specNo,
+      manufacturer: item.manufacturer,
+      modelNumber: item.modelNumber,
+      psu: item.psu,
+    })),
+    maxConcurrency,
+  });
+}
+
+/**
+ * 実行中の製品情報の先読みを中止
+ */
+export async function cancelPrefetch(): Promise<void> {
+  return invoke<void>('cancel_prefetch');
+}
+
+/**
+ * オフラインの準備（シリーズの製品情報をキャッシュし、IESファイルをIESライブラリに登録）
+ * 1製品ごとに offline-prepare-progress イベントで通知される
+ * @param seriesPrefix 型番の接頭辞（例: "AD12"）
+ * @param maxItems 取得する製品数の上限（省略時は100件）
+ */
+export async function prepareOffline(
+  manufacturer: string,
+  seriesPrefix: string,
+  maxItems?: number
+): Promise<OfflinePrepareSummary> {
+  return invoke<OfflinePrepareSummary>('prepare_offline', {
+    manufacturer,
+    seriesPrefix,
+    maxItems,
+  });
+}
+
+/**
+ * 製品画像のサムネイルを取得（PNGの data URL）
+ * 画像はバックエンドで取得・縮小・キャッシュされる（WebViewからメーカーサイトにアクセスしない）
+ * @param size 長辺のピクセル数（省略時は96）
+ */
+export async function fetchThumbnail(
+  manufacturer: string,
+  modelNumber: string,
+  size?: number
+): Promise<string> {
+  return invoke<string>('fetch_thumbnail', {
+    manufacturer,
+    modelNumber,
+    size,
+  });
+}
+
+/**
+ * 定価を一括取得（Spec No.をキーとした結果）
+ * 定価の取得に対応していないメーカーの行はエラーになる
+ */
+export async function fetchPrices(
+  items: BatchDownloadItem[]
+): Promise<Record<string, PriceResult>> {
+  return invoke<Record<string, PriceResult>>('fetch_prices', {
+    items: items.map((item) => ({
+      specNo: item.specNo,
+      manufacturer: item.manufacturer,
+      modelNumber: item.modelNumber,
+      psu: item.psu,
+    })),
+  });
+}
+
+/**
+ * キーワードで製品を検索（型番が不確かな場合の候補一覧）
+ */
+export async function searchProducts(
+  manufacturer: string,
+  keyword: string
+): Promise<ProductCandidate[]> {
+  return invoke<ProductCandidate[]>('search_products', {
+    manufacturer,
+    keyword,
+  });
+}
+
+/**
+ * IESファイルのダウンロードURLを解決（ダウンロードはしない）
+ * ZIPで配布される場合はZIP内のIESファイル候補も返す
+ */
+export async function resolveIesUrl(
+  manufacturer: string,
+  modelNumber: string,
+  psu?: string
+): Promise<ResolvedIesUrl> {
+  return invoke<ResolvedIesUrl>('resolve_ies_url', {
+    manufacturer,
+    modelNumber,
+    psu,
+  });
+}
+
+/**
+ * ZIPで配布されるIESファイルの候補を一致度の高い順に取得（ダウンロードはしない）
+ * ZIPで配布しないメーカーでは空
+ */
+export async function listZipCandidates(
+  manufacturer: string,
+  modelNumber: string
+): Promise<ZipCandidate[]> {
+  return invoke<ZipCandidate[]>('list_zip_candidates', {
+    manufacturer,
+    modelNumber,
+  });
+}
+
 /**
  * IESファイルを単体ダウンロード
+ * @param destPath 保存先ファイルパス（省略時は設定の既定の保存先ディレクトリにファイル名テンプレートで保存）
+ * @param specNo ファイル名に使用するSpec No.（destPath 省略時のみ）
+ * @param member ZIP内のファイル名（listZipCandidates の候補。指定すると一致度によらずこのファイルを保存する）
  */
 export async function downloadIesFile(
   manufacturer: string,
   modelNumber: string,
-  destPath: string
+  destPath?: string,
+  specNo?: string,
+  member?: string
 ): Promise<DownloadResult> {
   return invoke<DownloadResult>('download_ies_file', {
     manufacturer,
     modelNumber,
     destPath,
+    specNo,
+    member,
+  });
+}
+
+/**
+ * 1アイテムを解決（製品情報・IESファイルのURLまたはアセットのダウンロード・IESファイルの検証を1回で行う）
+ * 製品情報・URLの解決・検証の失敗は結果の各項目に記録される。ダウンロードした結果は履歴に記録される
+ */
+export async function resolveItem(
+  item: BatchDownloadItem,
+  options?: ResolveItemOptions
+): Promise<ItemResolution> {
+  return invoke<ItemResolution>('resolve_item', {
+    item: {
+      specNo: item.specNo,
+      manufacturer: item.manufacturer,
+      modelNumber: item.modelNumber,
+      psu: item.psu,
+      assetTypes: item.assetTypes,
+      quantity: item.quantity,
+      area: item.area,
+      wattage: item.wattage,
+      provider: item.provider,
+    },
+    options,
+  });
+}
+
+/**
+ * 任意のURLからファイルをダウンロード
+ * プロバイダーのないメーカー向け。ファイル名の形式・保存先ルールは一括ダウンロードと同じで、履歴に記録される
+ */
+export async function downloadFromUrl(request: UrlDownloadRequest): Promise<UrlDownloadResult> {
+  return invoke<UrlDownloadResult>('download_from_url', { request });
+}
+
+/**
+ * IESファイルを手動指定のURLからダウンロード
+ * ZIPの場合は型番に最も一致するIESファイルを取り出す。ファイル名はプロバイダーの命名規則で生成し、
+ * 取得できたURLは手動指定の取得元として登録する（以降の一括ダウンロードでも使用する）
+ */
+export async function downloadIesFromUrl(
+  item: BatchDownloadItem,
+  url: string,
+  destDir?: string,
+  projectId?: string
+): Promise<DownloadResult> {
+  return invoke<DownloadResult>('download_ies_from_url', { item, url, destDir, projectId });
+}
+
+/**
+ * プロジェクトの手動指定の取得元を取得（projectId 省略時はプロジェクト未指定の取得元）
+ */
+export async function getManualSources(projectId?: string): Promise<ManualSource[]> {
+  return invoke<ManualSource[]>('get_manual_sources', { projectId });
+}
+
+/**
+ * 手動指定の取得元の登録を削除
+ * @returns 更新後の取得元
+ */
+export async function removeManualSource(
+  specNo: string,
+  projectId?: string
+): Promise<ManualSource[]> {
+  return invoke<ManualSource[]>('remove_manual_source', { projectId, specNo });
+}
+
+/**
+ * 一括ダウンロードの事前見積もり
+ * 対応メーカーの有無・IESファイルのURLの解決可否・ファイル名の衝突・合計サイズの目安を返す（ファイルは保存しない）
+ */
+export async function estimateBatch(request: BatchDownloadRequest): Promise<BatchEstimate> {
+  return invoke<BatchEstimate>('estimate_batch', {
+    request: {
+      items: request.items.map((item) => ({
+        specNo: item.specNo,
+        manufacturer: item.manufacturer,
+        modelNumber: item.modelNumber,
+        psu: item.psu,
+        assetTypes: item.assetTypes,
+        provider: item.provider,
+      })),
+      destDir: request.destDir,
+      assetTypes: request.assetTypes,
+      projectId: request.projectId,
+      sheetName: request.sheetName,
+    },
   });
 }
 
+/**
+ * 器具リスト全体の事前確認
+ * 各行の製品情報のみを取得し、IESファイルが取得できるかを返す（ファイルは保存しない）
+ */
+export async function checkAvailability(request: BatchDownloadRequest): Promise<AvailabilityReport> {
+  return invoke<AvailabilityReport>('check_availability', {
+    request: {
+      items: request.items.map((item) => ({
+        specNo: item.specNo,
+        manufacturer: item.manufacturer,
+        modelNumber: item.modelNumber,
+        psu: item.psu,
+        provider: item.provider,
+      })),
+      destDir: request.destDir,
+      maxConcurrency: request.maxConcurrency,
+    },
+  });
+}
+
+/**
+ * バッチのイベントを受け取るチャネルを作成（コールバック省略時はチャネルを使わずイベントで通知される）
+ */
+function batchChannel(onEvent?: (event: BatchEvent) => void): Channel<BatchEvent> | undefined {
+  if (!onEvent) return undefined;
+  const channel = new Channel<BatchEvent>();
+  channel.onmessage = onEvent;
+  return channel;
+}
+
 /**
  * IESファイルを一括ダウンロード
+ * 実行中は cancelBatch(request.batchId) で中止できる（処理中のアイテムの通信も中断される）
+ * @param onEvent 指定した場合、このバッチの進捗・完了はイベントではなくこのコールバックにのみ届く
  */
 export async function batchDownloadIesFiles(
-  request: BatchDownloadRequest
+  request: BatchDownloadRequest,
+  onEvent?: (event: BatchEvent) => void
 ): Promise<BatchDownloadResult> {
   return invoke<BatchDownloadResult>('batch_download_ies_files', {
+    onEvent: batchChannel(onEvent),
     request: {
       items: request.items.map((item) => ({
         specNo: item.specNo,
         manufacturer: item.manufacturer,
         modelNumber: item.modelNumber,
         psu: item.psu,
+        assetTypes: item.assetTypes,
+        provider: item.provider,
       })),
       destDir: request.destDir,
+      assetTypes: request.assetTypes,
+      projectId: request.projectId,
+      sheetName: request.sheetName,
+      interactive: request.interactive,
+      maxConcurrency: request.maxConcurrency,
+      useCache: request.useCache,
+      skipExisting: request.skipExisting,
+      dedupeExisting: request.dedupeExisting,
     },
   });
 }
 
 /**
- * ダウンロード進捗イベントをリッスン
- * @param callback 進捗イベント受信時のコールバック
- * @returns リスナー解除関数
+ * 直前のバッチで失敗・キャンセルしたアイテムを再ダウンロード
+ * request には直前のバッチと同じリクエストを渡す
+ * プロジェクトの無視リストに含まれるアイテムは再実行しない
+ * @param includePermanent true の場合は掲載なし等の恒久的なエラーも再実行する（既定は通信エラー等のみ）
+ * @param onEvent 指定した場合、進捗・完了はイベントではなくこのコールバックにのみ届く
  */
-export async function listenDownloadProgress(
-  callback: (event: DownloadProgressEvent) => void
+export async function retryFailedItems(
+  request: BatchDownloadRequest,
+  includePermanent?: boolean,
+  onEvent?: (event: BatchEvent) => void
+): Promise<BatchDownloadResult> {
+  return invoke<BatchDownloadResult>('retry_failed_items', {
+    request,
+    includePermanent,
+    onEvent: batchChannel(onEvent),
+  });
+}
+
+/**
+ * 中断した一括ダウンロードの一覧を取得
+ * アプリが終了された・バックグラウンドの実行時間が上限に達した等で最後まで実行されなかったバッチ
+ */
+export async function getInterruptedBatches(): Promise<InterruptedBatch[]> {
+  return invoke<InterruptedBatch[]>('get_interrupted_batches');
+}
+
+/**
+ * 中断した一括ダウンロードの未完了のアイテムを再実行
+ * @param onEvent 指定した場合、進捗・完了はイベントではなくこのコールバックにのみ届く
+ */
+export async function resumeInterruptedBatch(
+  batchId?: string,
+  onEvent?: (event: BatchEvent) => void
+): Promise<BatchDownloadResult> {
+  return invoke<BatchDownloadResult>('resume_interrupted_batch', {
+    batchId,
+    onEvent: batchChannel(onEvent),
+  });
+}
+
+/**
+ * 中断した一括ダウンロードを再開せずに破棄
+ */
+export async function discardInterruptedBatch(batchId?: string): Promise<void> {
+  return invoke<void>('discard_interrupted_batch', { batchId });
+}
+
+/**
+ * プロジェクトの無視リストを取得（projectId 省略時はプロジェクト未指定のリスト）
+ */
+export async function getIgnoreList(projectId?: string): Promise<IgnoreEntry[]> {
+  return invoke<IgnoreEntry[]>('get_ignore_list', { projectId });
+}
+
+/**
+ * プロジェクトの無視リストにSpec No. または型番を追加
+ * 追加した器具は失敗したアイテムの再実行の対象から外れ、読み込み時に ignored の印が付く
+ * @returns 更新後の無視リスト
+ */
+export async function addToIgnoreList(
+  kind: IgnoreKind,
+  value: string,
+  projectId?: string,
+  reason?: string
+): Promise<IgnoreEntry[]> {
+  return invoke<IgnoreEntry[]>('add_to_ignore_list', { projectId, kind, value, reason });
+}
+
+/**
+ * プロジェクトの無視リストから削除
+ * @returns 更新後の無視リスト
+ */
+export async function removeFromIgnoreList(
+  kind: IgnoreKind,
+  value: string,
+  projectId?: string
+): Promise<IgnoreEntry[]> {
+  return invoke<IgnoreEntry[]>('remove_from_ignore_list', { projectId, kind, value });
+}
+
+/**
+ * IES以外のアセット（BIM・取扱説明書等）を一括ダウンロード
+ * 保存先ディレクトリ直下のアセット種別ごとのサブフォルダに保存される
+ * @param onEvent 指定した場合、進捗・完了はイベントではなくこのコールバックにのみ届く
+ */
+export async function batchDownloadAssets(
+  request: BatchAssetDownloadRequest,
+  onEvent?: (event: BatchEvent) => void
+): Promise<BatchDownloadResult> {
+  return invoke<BatchDownloadResult>('batch_download_assets', {
+    onEvent: batchChannel(onEvent),
+    request: {
+      items: request.items.map((item) => ({
+        specNo: item.specNo,
+        manufacturer: item.manufacturer,
+        modelNumber: item.modelNumber,
+        psu: item.psu,
+        provider: item.provider,
+      })),
+      destDir: request.destDir,
+      assetType: request.assetType,
+      projectId: request.projectId,
+      sheetName: request.sheetName,
+    },
+  });
+}
+
+/**
+ * 各アイテムの全アセット（IES・仕様書・画像・CAD・3D・取説等）を一括ダウンロード
+ * Spec No.ごとのフォルダにまとめて保存される
+ * @param onEvent 指定した場合、進捗・完了はイベントではなくこのコールバックにのみ届く
+ */
+export async function batchDownloadAssetBundle(
+  request: BatchDownloadRequest,
+  onEvent?: (event: BatchEvent) => void
+): Promise<BatchBundleResult> {
+  return invoke<BatchBundleResult>('batch_download_asset_bundle', {
+    onEvent: batchChannel(onEvent),
+    request: {
+      items: request.items.map((item) => ({
+        specNo: item.specNo,
+        manufacturer: item.manufacturer,
+        modelNumber: item.modelNumber,
+        psu: item.psu,
+        provider: item.provider,
+      })),
+      destDir: request.destDir,
+      projectId: request.projectId,
+      sheetName: request.sheetName,
+      interactive: request.interactive,
+      maxConcurrency: request.maxConcurrency,
+      useCache: request.useCache,
+      skipExisting: request.skipExisting,
+      dedupeExisting: request.dedupeExisting,
+    },
+  });
+}
+
+/**
+ * ジョブファイル（JSON）に定義された一括ダウンロードを実行
+ * 保存先・アセット種別・ファイル名テンプレート・同時実行数はジョブファイルの指定に従う
+ * @param path ジョブファイルのパス
+ * @param onEvent 指定した場合、進捗・完了はイベントではなくこのコールバックにのみ届く
+ */
+export async function runJobFile(
+  path: string,
+  batchId?: string,
+  onEvent?: (event: BatchEvent) => void
+): Promise<JobResult> {
+  return invoke<JobResult>('run_job_file', { path, batchId, onEvent: batchChannel(onEvent) });
+}
+
+/**
+ * 一括ダウンロード中のアイテムを個別にキャンセル
+ * @returns 処理中のアイテムを中断した場合は true（未着手の場合は順番が来た時点でスキップ）
+ */
+export async function cancelItem(specNo: string, batchId?: string): Promise<boolean> {
+  return invoke<boolean>('cancel_item', { specNo, batchId });
+}
+
+/**
+ * 判断待ちのアイテムの候補を選ぶ
+ * @param choice 候補の値（省略時は判断せずに続行し、自動で選ぶ候補があればそれを使う）
+ * @returns 判断待ちのアイテムだった場合は true
+ */
+export async function resolveItemChoice(
+  specNo: string,
+  choice?: string,
+  batchId?: string
+): Promise<boolean> {
+  return invoke<boolean>('resolve_item_choice', { specNo, choice, batchId });
+}
+
+/**
+ * 実行中の一括ダウンロードを一時停止（処理中のアイテムは完了まで続ける）
+ * @returns 一時停止した場合は true
+ */
+export async function pauseBatch(batchId?: string): Promise<boolean> {
+  return invoke<boolean>('pause_batch', { batchId });
+}
+
+/**
+ * 一時停止した一括ダウンロードを再開
+ * @returns 再開した場合は true
+ */
+export async function resumeBatch(batchId?: string): Promise<boolean> {
+  return invoke<boolean>('resume_batch', { batchId });
+}
+
+/**
+ * 実行中の一括ダウンロードをバッチ単位でキャンセル（他のバッチは継続）
+ * @returns バッチが実行中だった場合は true
+ */
+export async function cancelBatch(batchId?: string): Promise<boolean> {
+  return invoke<boolean>('cancel_batch', { batchId });
+}
+
+/**
+ * アプリ設定を取得（古いバージョンの設定は変換される）
+ */
+export async function getSettings(): Promise<Settings> {
+  return invoke<Settings>('get_settings');
+}
+
+/**
+ * アプリ設定を検証して保存
+ * 不明なキーや範囲外の値を含む場合はエラー
+ */
+export async function updateSettings(settings: Settings): Promise<Settings> {
+  return invoke<Settings>('update_settings', { settings });
+}
+
+/**
+ * アプリ設定と無視リストを1つのファイルに書き出す（プロキシのパスワード等は含まない）
+ * @returns 保存先パス
+ */
+export async function exportConfig(destPath: string): Promise<string> {
+  return invoke<string>('export_config', { destPath });
+}
+
+/**
+ * 書き出した設定ファイルを読み込んで反映（無視リストは既存の項目に追加する）
+ */
+export async function importConfig(path: string): Promise<ConfigImportResult> {
+  return invoke<ConfigImportResult>('import_config', { path });
+}
+
+/**
+ * プロバイダールールの更新状態を取得
+ */
+export async function getRulesStatus(): Promise<RulesStatus> {
+  return invoke<RulesStatus>('get_rules_status');
+}
+
+/**
+ * プロバイダールールの更新を今すぐ確認
+ */
+export async function checkRulesUpdate(): Promise<RulesStatus> {
+  return invoke<RulesStatus>('check_rules_update');
+}
+
+/**
+ * プロバイダールールを直前のバージョンに戻す
+ * 次回の確認で最新に戻らないようにするには設定の pinnedVersion を指定する
+ */
+export async function rollbackRules(): Promise<RulesStatus> {
+  return invoke<RulesStatus>('rollback_rules');
+}
+
+/**
+ * ログレベルを変更して設定に保存
+ * ログはアプリのログディレクトリに日付ごとのファイルとして出力される
+ */
+export async function setLogLevel(level: LogLevel): Promise<void> {
+  return invoke<void>('set_log_level', { level });
+}
+
+/**
+ * 送信される利用状況（前回の送信以降の集計値）を取得
+ * 利用状況の送信を有効にする前に内容を確認するために使用する
+ */
+export async function getUsageReport(): Promise<UsageReport> {
+  return invoke<UsageReport>('get_usage_report');
+}
+
+/**
+ * 実行中（または直前）の一括ダウンロードの状態を取得
+ * フロントエンドの再読み込み後に実行中のバッチへ再接続するために使用する
+ */
+export async function getBatchStatus(batchId?: string): Promise<BatchStatus> {
+  return invoke<BatchStatus>('get_batch_status', { batchId });
+}
+
+/**
+ * すべてのバッチ（実行中・直前に実行したもの）の状態を取得
+ */
+export async function listBatches(): Promise<BatchStatus[]> {
+  return invoke<BatchStatus[]>('list_batches');
+}
+
+/**
+ * 直前の作業状態（器具リスト・製品情報・各行のダウンロード結果）を取得
+ * クラッシュや再起動の後、読み込み・製品情報の取得をやり直さずに作業を再開するために使用する
+ */
+export async function restoreLastSession(): Promise<Session | null> {
+  return invoke<Session | null>('restore_last_session');
+}
+
+/**
+ * 直前の作業状態を破棄
+ */
+export async function clearLastSession(): Promise<void> {
+  return invoke<void>('clear_last_session');
+}
+
+/**
+ * ディープリンク（autosight://download?...）で追加されたアイテムを取り出す
+ * 起動時に呼び出し、起動前に開かれたリンクのアイテムを受け取る
+ */
+export async function takeDeepLinkItems(): Promise<BatchDownloadItem[]> {
+  return invoke<BatchDownloadItem[]>('take_deep_link_items');
+}
+
+/**
+ * ディープリンクでアイテムが追加されたイベントをリッスン
+ * @param callback 追加時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenDeepLinkDownload(
+  callback: (item: BatchDownloadItem) => void
+): Promise<UnlistenFn> {
+  return listen<BatchDownloadItem>('deep-link-download', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * ダウンロード済みファイルを既定のアプリで開く
+ * 一括ダウンロードの保存先ディレクトリ配下のファイルのみ開ける
+ */
+export async function openDownloadedFile(path: string): Promise<void> {
+  return invoke<void>('open_downloaded_file', { path });
+}
+
+/**
+ * ダウンロード済みファイルをFinder/エクスプローラーで表示
+ * 一括ダウンロードの保存先ディレクトリ配下のファイルのみ表示できる
+ */
+export async function revealInFolder(path: string): Promise<void> {
+  return invoke<void>('reveal_in_folder', { path });
+}
+
+/**
+ * ダウンロード済みファイルをユーザーが選んだ場所に書き出す
+ * モバイルでは保存ダイアログで作成したファイルのURI（AndroidのSAF・iOSの「ファイル」アプリ）を渡す
+ * @param path ダウンロード済みファイルのパス
+ * @param target 書き出し先のURIまたはパス
+ */
+export async function exportDownloadedFile(
+  path: string,
+  target: string
+): Promise<StorageExportResult> {
+  return invoke<StorageExportResult>('export_downloaded_file', { path, target });
+}
+
+/**
+ * ダウンロード済みファイルをOSの共有シートに渡す（モバイルのみ）
+ * フォルダを指定した場合はZIPにまとめて共有する
+ * @param path ダウンロード済みファイル・フォルダのパス
+ */
+export async function shareDownloadedFile(path: string): Promise<void> {
+  return invoke<void>('share_downloaded_file', { path });
+}
+
+/**
+ * ダウンロード履歴を検索
+ * プロジェクト・メーカー・期間・成否で絞り込み、新しい順にページ単位で返す
+ */
+export async function getDownloadHistory(query: HistoryQuery = {}): Promise<HistoryPage> {
+  return invoke<HistoryPage>('get_download_history', { query });
+}
+
+/**
+ * ダウンロード履歴を文字列で検索
+ * 型番・Spec No.・メーカー名・保存先パス・取得元URLのいずれかに含む履歴を新しい順にページ単位で返す
+ * @param query 追加の絞り込み条件
+ */
+export async function searchHistory(text: string, query?: HistoryQuery): Promise<HistoryPage> {
+  return invoke<HistoryPage>('search_history', { text, query });
+}
+
+/**
+ * ダウンロード履歴を監査用のCSV（取得元URL・SHA-256を含む）に書き出す
+ * @param destPath 保存先
+ * @param query 絞り込み条件（省略時は全履歴。ページ分割しない）
+ * @returns 書き出した件数
+ */
+export async function exportHistoryCsv(destPath: string, query?: HistoryQuery): Promise<number> {
+  return invoke<number>('export_history_csv', { destPath, query });
+}
+
+/**
+ * ダウンロード結果のレポートを出力
+ * @param projectId 対象のプロジェクトID（省略時は全履歴）
+ * @returns レポートの内容（保存は呼び出し側で行う）
+ */
+export async function exportReport(
+  format: ReportFormat,
+  projectId?: string
+): Promise<string> {
+  return invoke<string>('export_report', { projectId, format });
+}
+
+/**
+ * 一括ダウンロードの結果を器具リストExcelに書き戻す（元のファイルは変更しない）
+ * @param sourcePath 元の器具リストのパス
+ * @param result 一括ダウンロードの結果
+ * @param destPath 保存先（省略時は `{元のファイル名}_results.xlsx`）
+ * @param profile 読み込み設定（省略時は標準の列構成）
+ */
+export async function exportBatchReport(
+  sourcePath: string,
+  result: BatchDownloadResult,
+  destPath?: string,
+  profile?: ImportProfile
+): Promise<WritebackResult> {
+  return invoke<WritebackResult>('export_batch_report', {
+    sourcePath,
+    result,
+    destPath,
+    profile,
+  });
+}
+
+/**
+ * 器具リスト（見積もり用の一覧表）を出力
+ * 各行にダウンロード済みのIESファイルの配光データ（器具光束・消費電力・ビーム角・BUG評価）と
+ * 取得済みの製品情報（品名・定価）を結合する
+ * @param projectId 対象のプロジェクトID（省略時は全履歴）
+ * @returns 器具リストの内容（保存は呼び出し側で行う）
+ */
+export async function exportScheduleSummary(
+  rows: Fixture[],
+  format: ReportFormat,
+  projectId?: string
+): Promise<string> {
+  return invoke<string>('export_schedule_summary', { rows, projectId, format });
+}
+
+/**
+ * IESファイルの配光データを解析（器具光束・ビーム角・ゾーナル光束・BUG評価）
+ * @param path IESファイルのパス
+ */
+export async function analyzeIes(path: string): Promise<IesAnalysis> {
+  return invoke<IesAnalysis>('analyze_ies', { path });
+}
+
+/**
+ * ダウンロードしたIESファイルの配光データの概要を取得（最大光度・1/10ビーム角・対称性・配光曲線）
+ * @param path IESファイルのパス
+ * @param step 配光曲線の鉛直角の間隔（度。省略時は5度）
+ */
+export async function analyzeIesFile(path: string, step?: number): Promise<IesSummary> {
+  return invoke<IesSummary>('analyze_ies_file', { path, step });
+}
+
+/**
+ * 配光データをIES・EULUMDAT（LDT）の間で変換（IESはLDTに、LDTはIESに）
+ * @param path 変換元のファイルのパス
+ * @param destPath 保存先（省略時は同じフォルダの拡張子のみ異なる名前）
+ * @returns 保存先のパス
+ */
+export async function convertPhotometry(path: string, destPath?: string): Promise<string> {
+  return invoke<string>('convert_photometry', { path, destPath });
+}
+
+/**
+ * 照射円錐図（コーンダイアグラム）のデータを計算
+ * @param path IESファイルのパス
+ * @param heights 取付高さ（m）
+ * @returns 高さごとの光軸上の照度（lx）とビーム径（m）
+ */
+export async function coneDiagram(path: string, heights: number[]): Promise<ConeDiagram> {
+  return invoke<ConeDiagram>('cone_diagram', { path, heights });
+}
+
+/**
+ * ダウンロード済みの配光データを照明計算ソフト（DIALux evo・Relux・AGi32）向けに書き出す
+ * メーカーごとのフォルダにまとめ、一覧（index.csv）を作成する（AGi32向けは平置きし、器具表も作成する）
+ * @param destDir 書き出し先ディレクトリ
+ * @param projectId 対象のプロジェクトID（省略時は全履歴）
+ */
+export async function exportLightingProject(
+  destDir: string,
+  target: ExportTarget,
+  projectId?: string
+): Promise<ProjectExportResult> {
+  return invoke<ProjectExportResult>('export_lighting_project', { projectId, destDir, target });
+}
+
+/**
+ * 保存先ディレクトリをクラウド（Dropbox・Google Drive）にアップロード
+ * アップロード先・ZIPにまとめるかは設定に従う
+ * @param dir 一括ダウンロードの保存先ディレクトリ
+ */
+export async function uploadToCloud(dir: string): Promise<CloudUploadResult> {
+  return invoke<CloudUploadResult>('upload_to_cloud', { dir });
+}
+
+/**
+ * クラウドのOAuthトークンを保存
+ * @param token 省略時は削除して連携を解除
+ */
+export async function setCloudToken(provider: CloudProvider, token?: CloudToken): Promise<void> {
+  return invoke<void>('set_cloud_token', { provider, token });
+}
+
+/**
+ * トークンを保存しているクラウドのサービスを取得
+ */
+export async function getCloudConnections(): Promise<CloudProvider[]> {
+  return invoke<CloudProvider[]>('get_cloud_connections');
+}
+
+/**
+ * ネットワーク共有のパスワードを保存
+ * @param password 省略時は削除
+ */
+export async function setNetworkSharePassword(password?: string): Promise<void> {
+  return invoke<void>('set_network_share_password', { password });
+}
+
+/**
+ * ネットワーク共有に接続できるか確認
+ * @param settings 確認する設定（保存前の値を渡せる）
+ * @returns 書き込みに使うパス
+ */
+export async function testNetworkShare(settings: NetworkShareSettings): Promise<string> {
+  return invoke<string>('test_network_share', { settings });
+}
+
+/**
+ * ダウンロード済みファイルに現在の命名規則を再適用
+ * 履歴に記録したメタデータからファイル名を再生成する（同名ファイルがある場合は上書きしない）
+ */
+export async function renameDownloadedFiles(request: RenameRequest = {}): Promise<RenameResult[]> {
+  return invoke<RenameResult[]>('rename_downloaded_files', { request });
+}
+
+/**
+ * キャッシュの使用量を種別ごとに取得
+ */
+export async function getCacheStats(): Promise<CacheStats[]> {
+  return invoke<CacheStats[]>('get_cache_stats');
+}
+
+/**
+ * キャッシュを削除し、削除した合計サイズ（バイト）を返す
+ * @param scope 削除する種別（省略時はすべて）
+ */
+export async function clearCache(scope?: CacheScope): Promise<number> {
+  return invoke<number>('clear_cache', { scope });
+}
+
+/**
+ * IESファイルのダウンロードキャッシュを削除し、削除した合計サイズ（バイト）を返す
+ */
+export async function clearDownloadCache(): Promise<number> {
+  return invoke<number>('clear_download_cache');
+}
+
+/**
+ * 電源の状態と、それに応じた同時実行数を取得（バッテリー駆動時は設定に従って下げる）
+ */
+export async function getPowerStatus(): Promise<PowerStatus> {
+  return invoke<PowerStatus>('get_power_status');
+}
+
+/**
+ * ポータブルモードの状態を取得
+ */
+export async function getPortableInfo(): Promise<PortableInfo> {
+  return invoke<PortableInfo>('get_portable_info');
+}
+
+/**
+ * 次回の起動からポータブルモードを有効・無効にする（反映には再起動が必要）
+ * @param dir 保存先フォルダ（実行ファイルのフォルダからの相対パスも可。省略時は AutoSight Data）
+ */
+export async function setPortableMode(enabled: boolean, dir?: string): Promise<PortableInfo> {
+  return invoke<PortableInfo>('set_portable_mode', { enabled, dir });
+}
+
+/**
+ * tauri-plugin-store で開くストアファイルのパス（ポータブルモードでは保存先フォルダ内）
+ */
+export async function getStorePath(): Promise<string> {
+  return invoke<string>('get_store_path');
+}
+
+/**
+ * バッチ固有のイベント名（batchId 省略時は既定のバッチの従来のイベント名）
+ */
+function batchEvent(event: string, batchId?: string): string {
+  return batchId ? `${event}:${batchId}` : event;
+}
+
+/**
+ * ダウンロード進捗イベントをリッスン
+ * @param callback 進捗イベント受信時のコールバック
+ * @param batchId バッチID（省略時は既定のバッチ）
+ * @returns リスナー解除関数
+ */
+export async function listenDownloadProgress(
+  callback: (event: DownloadProgressEvent) => void,
+  batchId?: string
+): Promise<UnlistenFn> {
+  return listen<DownloadProgressEvent>(batchEvent('download-progress', batchId), (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 一括ダウンロードの一時停止・再開イベントをリッスン（トレイメニューからの操作を含む）
+ * @param callback 一時停止中かを受け取るコールバック
+ * @param batchId バッチID（省略時は既定のバッチ）
+ * @returns リスナー解除関数
+ */
+export async function listenDownloadPaused(
+  callback: (paused: boolean) => void,
+  batchId?: string
+): Promise<UnlistenFn> {
+  return listen<boolean>(batchEvent('download-paused', batchId), (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 一括ダウンロードの完了イベントをリッスン
+ * @param callback 完了時のコールバック
+ * @param batchId バッチID（省略時は既定のバッチ）
+ * @returns リスナー解除関数
+ */
+export async function listenDownloadFinished(
+  callback: (event: BatchFinishedEvent) => void,
+  batchId?: string
+): Promise<UnlistenFn> {
+  return listen<BatchFinishedEvent>(batchEvent('download-finished', batchId), (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 一括ダウンロードのアイテムの判断待ちイベントをリッスン（interactive を指定したバッチのみ）
+ * @param callback 候補を受け取るコールバック（resolveItemChoice で選ぶまでアイテムは完了しない）
+ * @param batchId バッチID（省略時は既定のバッチ）
+ * @returns リスナー解除関数
+ */
+export async function listenDownloadDecisionRequired(
+  callback: (event: DecisionRequiredEvent) => void,
+  batchId?: string
+): Promise<UnlistenFn> {
+  return listen<DecisionRequiredEvent>(
+    batchEvent('download-decision-required', batchId),
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+/**
+ * プロバイダールールの更新（使用するバージョンの変更）イベントをリッスン
+ * @param callback 更新時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenProviderRulesUpdated(
+  callback: (status: RulesStatus) => void
+): Promise<UnlistenFn> {
+  return listen<RulesStatus>('provider-rules-updated', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 一括ダウンロード後のクラウドへのアップロードの完了イベントをリッスン
+ * @param callback 完了時のコールバック（ファイル単位の失敗は errors に含まれる）
+ * @param onError アップロードを開始できなかった場合のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenCloudUpload(
+  callback: (result: CloudUploadResult) => void,
+  onError?: (error: string) => void
+): Promise<UnlistenFn> {
+  const unlistenFinished = await listen<CloudUploadResult>('cloud-upload-finished', (event) => {
+    callback(event.payload);
+  });
+  const unlistenFailed = await listen<string>('cloud-upload-failed', (event) => {
+    onError?.(event.payload);
+  });
+  return () => {
+    unlistenFinished();
+    unlistenFailed();
+  };
+}
+
+/**
+ * レート制限・一時的なエラーによる再試行待ちのイベントをリッスン
+ * @param callback 待機開始時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenBackoff(
+  callback: (event: BackoffEvent) => void
+): Promise<UnlistenFn> {
+  return listen<BackoffEvent>('download-backoff', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 事前確認の進捗イベントをリッスン
+ * @param callback 1行分の結果受信時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenAvailabilityProgress(
+  callback: (event: AvailabilityResult) => void
+): Promise<UnlistenFn> {
+  return listen<AvailabilityResult>('availability-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 製品情報一括取得の進捗イベントをリッスン
+ * @param callback 1行分の結果受信時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenProductInfoProgress(
+  callback: (event: ProductInfoResult) => void
+): Promise<UnlistenFn> {
+  return listen<ProductInfoResult>('product-info-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * 製品情報の先読みの完了イベントをリッスン
+ * @param callback 完了時のコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenPrefetchFinished(
+  callback: (event: PrefetchSummary) => void
+): Promise<UnlistenFn> {
+  return listen<PrefetchSummary>('product-info-prefetch-finished', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * オフラインの準備の進捗イベントをリッスン
+ * @param callback 1製品ごとのコールバック
+ * @returns リスナー解除関数
+ */
+export async function listenOfflinePrepareProgress(
+  callback: (event: OfflinePrepareProgress) => void
 ): Promise<UnlistenFn> {
-  return listen<DownloadProgressEvent>('download-progress', (event) => {
+  return listen<OfflinePrepareProgress>('offline-prepare-progress', (event) => {
     callback(event.payload);
   });
 }
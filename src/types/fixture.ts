@@ -3,6 +3,8 @@
  * schema/ies-fixture-list.schema.json の Fixture Base シートに対応
  */
 
+import type { CloudProvider } from './settings';
+
 /** Fixture Base の1行に対応する器具データ */
 export interface Fixture {
   /** 器具記号 (例: A01, D11w) */
@@ -53,6 +55,41 @@ export interface Fixture {
   costDriver?: number;
   /** その他付属品定価 */
   costOthers?: number;
+  /** 台数 */
+  quantity?: number;
+}
+
+/** エラーの種別（Rust側の ErrorCode と対応） */
+export type ErrorCode =
+  | 'PROVIDER_NOT_FOUND'
+  | 'IES_NOT_AVAILABLE'
+  | 'IES_AMBIGUOUS'
+  | 'ASSET_NOT_AVAILABLE'
+  | 'DISCONTINUED'
+  | 'ZIP_NO_MATCH'
+  | 'ZIP_INVALID'
+  | 'NETWORK_TIMEOUT'
+  | 'NETWORK_ERROR'
+  | 'INCOMPLETE_DOWNLOAD'
+  | 'RATE_LIMITED'
+  | 'HTTP_STATUS'
+  | 'INVALID_CONTENT'
+  | 'FILE_EXISTS'
+  | 'FILE_NOT_FOUND'
+  | 'FILE_SYSTEM'
+  | 'INVALID_INPUT'
+  | 'CANCELLED'
+  | 'OFFLINE_NOT_CACHED'
+  | 'PROVIDER_OUTDATED'
+  | 'UNKNOWN';
+
+/** コマンドのエラー（invoke の reject 値） */
+export interface CommandError {
+  code: ErrorCode;
+  /** 詳細メッセージ（ログ・デバッグ用） */
+  message: string;
+  /** 再試行で解決する可能性があるか */
+  retryable: boolean;
 }
 
 /** ダウンロード結果（Rust側と対応） */
@@ -61,6 +98,125 @@ export interface DownloadResult {
   filePath?: string;
   fileSize?: number;
   error?: string;
+  /** エラーコード（エラー時のみ） */
+  code?: ErrorCode;
+  /** 再試行で解決する可能性がある失敗か */
+  retryable: boolean;
+  /** 成功したが確認が必要な点 */
+  warnings: DownloadWarning[];
+  /** ZIP内で同程度に一致したファイルの候補（選択したファイルを含む。曖昧でない場合は空） */
+  candidates: string[];
+  /** 変換元のファイルのパス（LDTから変換した場合のみ。変換後のファイルと同じ場所に保存） */
+  convertedFrom?: string;
+  /** 取得元（オフラインモード等、通信せずに取得した場合はなし） */
+  source?: DownloadSource;
+  /** 所要時間・転送量 */
+  timing: DownloadTiming;
+  /** IESファイルの概要（IESファイルの取得に成功した場合のみ） */
+  iesMetadata?: IesMetadata;
+  /** ダウンロードキャッシュから取り出したか（メーカーサイトに問い合わせていない） */
+  fromCache?: boolean;
+}
+
+/** ダウンロードしたIESファイルの概要（Rust側と対応） */
+export interface IesMetadata {
+  /** 規格（1行目の "IESNA:LM-63-2002" 等） */
+  format?: string;
+  /** 配光のタイプ */
+  photometricType: 'C' | 'B' | 'A';
+  /** 器具光束（lm） */
+  lumens?: number;
+  /** 入力電力（W） */
+  inputWatts?: number;
+  /** 鉛直角の数 */
+  verticalAngleCount: number;
+  /** 水平角の数 */
+  horizontalAngleCount: number;
+}
+
+/** ダウンロードしたファイルの取得元 */
+export interface DownloadSource {
+  /** 取得元のURL（リダイレクト後。ZIPで配布される場合はZIPのURL） */
+  url: string;
+  /** HTTPステータス */
+  status?: number;
+  /** Content-Type ヘッダー */
+  contentType?: string;
+  /** サーバー上の最終更新日時（Last-Modified ヘッダーの値） */
+  lastModified?: string;
+}
+
+/** ダウンロードの所要時間・転送量 */
+export interface DownloadTiming {
+  /** ダウンロードURLの解決にかかった時間（ミリ秒） */
+  lookupMs: number;
+  /** ファイルの取得・保存にかかった時間（ミリ秒） */
+  downloadMs: number;
+  /** 全体の所要時間（ミリ秒） */
+  totalMs: number;
+  /** 受信したバイト数（ZIPで配布される場合はZIP全体のサイズ） */
+  bytesTransferred: number;
+}
+
+/**
+ * ダウンロード時の警告の種類
+ * - ambiguousZipMatch: ZIP内に同程度に一致するファイルが複数あり、そのうち1つを選択した
+ * - psuFallback: PSU指定ありで見つからず、型番のみで取得した
+ * - convertedFromLdt: IESファイルがなく、EULUMDAT（LDT）から変換した
+ * - seriesApproximation: 型番の製品のIESファイルがなく、シリーズの代表のIESファイルを取得した
+ * - ldtConversionFailed: 出力形式にLDTを指定したが、IESファイルをLDTに変換できなかった（IESファイルは残す）
+ * - unmatchedLink: 製品ページのIESファイルのリンクが1つだけで、ファイル名を型番と照合できなかった（そのリンクから取得した）
+ */
+export type DownloadWarningKind =
+  | 'ambiguousZipMatch'
+  | 'psuFallback'
+  | 'convertedFromLdt'
+  | 'productSubstituted'
+  | 'successorSubstituted'
+  | 'seriesApproximation'
+  | 'ldtConversionFailed'
+  | 'unmatchedLink';
+
+/** ダウンロード時の警告（失敗ではないが、結果の確認が必要なもの） */
+export interface DownloadWarning {
+  kind: DownloadWarningKind;
+  message: string;
+}
+
+/** IESファイルのダウンロードURLの解決結果（Rust側と対応） */
+export interface ResolvedIesUrl {
+  /** ダウンロードURL（ZIPで配布される場合はZIPのURL） */
+  url: string;
+  /** ZIP内のIESファイル候補（ZIPで配布されない場合は空） */
+  candidates: string[];
+  /** 候補のうちダウンロード時に選択されるファイル */
+  selected?: string;
+}
+
+/** ZIP内のファイル候補（listZipCandidates の戻り値） */
+export interface ZipCandidate {
+  /** ZIP内のパス */
+  name: string;
+  /** ダウンロード時に選択されるファイルか */
+  selected: boolean;
+  /** 選択されるファイルと同程度に一致するか（型番だけでは区別できない候補） */
+  closeMatch: boolean;
+  /** 型番と一致するか（シリーズ・色温度・配光角が異なるものは false） */
+  matches: boolean;
+  /** 型番との一致度（型番と一致しない場合は省略） */
+  score?: MatchScore;
+}
+
+/** ZIP内のファイルと型番の一致度（フィールドの順に比較し、大きいほど一致する） */
+export interface MatchScore {
+  /** 一致した色温度・配光角の数 */
+  semantic: number;
+  /** 一致したその他の要素の数（シリーズを除く） */
+  others: number;
+  /** 型番にない要素の数（少ないほど一致する） */
+  extra: number;
+  /** 前方一致長 */
+  prefix: number;
 }
 
 /** 一括ダウンロード用のアイテム */
@@ -69,49 +225,1162 @@ export interface BatchDownloadItem {
   manufacturer: string;
   modelNumber: string;
   psu?: string;
+  /** この行で取得するアセット種別（省略時はリクエストの指定に従う） */
+  assetTypes?: AssetType[];
+  /** 台数（接続負荷・定価の集計に使用。省略時は1台） */
+  quantity?: number;
+  /** 設置エリア（接続負荷の集計単位） */
+  area?: string;
+  /** 器具リストの消費電力（W。IESファイルの入力電力との照合に使用） */
+  wattage?: number;
+  /** プロバイダーID（指定するとメーカー名にかかわらずこのプロバイダーで処理する） */
+  provider?: string;
+  /** IESファイルの取得元のURL（手動指定。指定するとプロバイダーの照合の代わりにこのURLから取得する） */
+  sourceUrl?: string;
 }
 
 /** 一括ダウンロードリクエスト */
 export interface BatchDownloadRequest {
   items: BatchDownloadItem[];
+  /**
+   * 保存先ディレクトリ（省略時は設定の既定の保存先ディレクトリ）
+   * `{project}` `{project_id}` `{date}` `{sheet}` `{manufacturer}` を使用可能
+   */
+  destDir?: string;
+  /** 取得するアセット種別（省略時はIESのみ） */
+  assetTypes?: AssetType[];
+  /** ダウンロード履歴に記録するプロジェクトID */
+  projectId?: string;
+  /** バッチID（省略時は既定のバッチ）。IDの異なるバッチは同時に実行できる */
+  batchId?: string;
+  /** 読み込んだシート名（保存先ディレクトリの `{sheet}` に使用） */
+  sheetName?: string;
+  /**
+   * 曖昧な一致（同程度に一致するIESファイル・近い型番の製品）を利用者に判断してもらうか
+   * true の場合、該当するアイテムは resolveItemChoice で選ぶまで完了しない
+   */
+  interactive?: boolean;
+  /** 同時にダウンロードするアイテム数の上限（省略時は設定の同時実行数） */
+  maxConcurrency?: number;
+  /**
+   * IESファイルのダウンロードキャッシュを使うか（メーカー・型番・PSUが同じファイルを再ダウンロードしない）
+   * キャッシュにないファイルはダウンロード後にキャッシュに保存する
+   */
+  useCache?: boolean;
+  /**
+   * 保存先に取得済みのファイルがあるアイテムを飛ばすか（中断したバッチの再開用）
+   * 保存先の監査ログに成功として記録され、ファイルが残っているアセットを取得済みとみなす
+   */
+  skipExisting?: boolean;
+  /**
+   * 同じ内容のIESファイルの照合に保存先の既存のファイルも含めるか
+   * バッチ内で同じ内容のファイルを取得したアイテムは常に重複として報告する（`duplicateOf`）
+   */
+  dedupeExisting?: boolean;
+  /**
+   * 配光データの出力形式（省略時はIESのみ）
+   * LDTを含む場合、取得したIESファイルをEULUMDAT形式に変換し、同じ名前（拡張子のみ異なる）で保存する
+   */
+  outputFormat?: PhotometryFormat;
+  /**
+   * ファイル名テンプレート（省略時は設定のテンプレート。設定もない場合はプロバイダーの命名規則）
+   * 例: `{spec_no}-{manufacturer}-{model}`。使用できるプレースホルダーは設定のテンプレートと同じ。
+   * バッチ内で別のアイテムと同じファイル名になった場合は連番（`_2` `_3` …）を付ける
+   */
+  filenameTemplate?: string;
+}
+
+/**
+ * 一括ダウンロードで保存する配光データの形式
+ * - ies: IESファイルのみ
+ * - ldt: EULUMDAT（LDT）のみ（変換後にIESファイルを削除する）
+ * - both: IESファイルとLDTの両方
+ */
+export type PhotometryFormat = 'ies' | 'ldt' | 'both';
+
+/** URL指定ダウンロードリクエスト */
+export interface UrlDownloadRequest {
+  /** ダウンロードするURL（http / https） */
+  url: string;
+  specNo: string;
+  /** メーカー名（履歴に記録） */
+  manufacturer?: string;
+  /** 型番（元ファイル名が取得できない場合のファイル名に使用） */
+  modelNumber?: string;
+  /** アセット種別（省略時はIES） */
+  assetType?: AssetType;
+  /** 保存先ディレクトリ（空の場合は設定の既定の保存先ディレクトリ） */
   destDir: string;
+  /** ダウンロード履歴に記録するプロジェクトID */
+  projectId?: string;
+}
+
+/** URL指定ダウンロード結果 */
+export interface UrlDownloadResult extends DownloadResult {
+  /** 保存したファイルのSHA-256（成功時のみ） */
+  sha256?: string;
+}
+
+/** ダウンロード対象のアセット種別（Rust側と対応） */
+export type AssetType =
+  | 'ies'
+  | 'photometricReport'
+  | 'specSheet'
+  | 'image'
+  | 'cad'
+  | 'bim'
+  | 'model3d'
+  | 'manual';
+
+/** アセット一括ダウンロードリクエスト */
+export interface BatchAssetDownloadRequest {
+  items: BatchDownloadItem[];
+  /** プロジェクトフォルダ（アセット種別ごとのサブフォルダに保存。省略時は設定の既定の保存先ディレクトリ） */
+  destDir?: string;
+  assetType: AssetType;
+  /** ダウンロード履歴に記録するプロジェクトID */
+  projectId?: string;
+  /** バッチID（省略時は既定のバッチ）。IDの異なるバッチは同時に実行できる */
+  batchId?: string;
+  /** 読み込んだシート名（保存先ディレクトリの `{sheet}` に使用） */
+  sheetName?: string;
 }
 
 /** 単体ダウンロード結果 */
 export interface SingleDownloadResult {
   specNo: string;
   modelNumber: string;
+  /** 主アセットの結果（IESを要求した場合はIES、それ以外は先頭のアセット） */
   result: DownloadResult;
+  /** 要求したアセット種別ごとの結果 */
+  assets: AssetDownloadResult[];
+  /** 所要時間・転送量（全アセットの合計。totalMs はアイテム全体の時間） */
+  timing: DownloadTiming;
+  /** 器具リストの消費電力とIESファイルの入力電力の照合結果（消費電力の指定がある場合のみ） */
+  wattageCheck?: WattageCheck;
+  /** 取得済みのファイルがあるため取得し直さなかったか（`skipExisting`） */
+  skipped?: boolean;
+  /** 取得したIESファイルが先に保存したファイルと同じ内容か（SHA-256で照合） */
+  deduplicated?: boolean;
+  /** 同じ内容の先に保存したファイルのパス（deduplicated の場合のみ） */
+  duplicateOf?: string;
+}
+
+/** 器具リストの消費電力とIESファイルの入力電力の照合結果 */
+export interface WattageCheck {
+  /** 器具リストの消費電力（W） */
+  scheduleWatts: number;
+  /** IESファイルの入力電力（W） */
+  iesWatts: number;
+  /** 差の割合（(IES − 器具リスト) / 器具リスト） */
+  differenceRatio: number;
+  /** 許容差（±10%）を超えるか */
+  mismatch: boolean;
 }
 
 /** 一括ダウンロード結果 */
 export interface BatchDownloadResult {
   successCount: number;
   failureCount: number;
+  cancelledCount: number;
+  /** 取得済みのため飛ばした件数 */
+  skippedCount: number;
   results: SingleDownloadResult[];
+  /** 監査ログ（JSONL）のパス（作成できなかった場合は未設定） */
+  auditLogPath?: string;
+  /** 接続負荷（ダウンロードしたIESファイルの入力電力 × 台数）の集計 */
+  connectedLoad: ConnectedLoad;
+  /** 定価（取得済みの製品情報）× 台数の集計 */
+  costRollup: CostRollup;
+}
+
+/** 一括ダウンロードの結果の器具リストExcelへの書き戻しの結果 */
+export interface WritebackResult {
+  /** 保存したブックのパス */
+  path: string;
+  /** 結果を書き込んだ行数 */
+  updatedRows: number;
+  /** シートに対応する行がなかった Spec No. */
+  unmatchedSpecNos: string[];
+}
+
+/** メーカー・設置エリアごとの接続負荷 */
+export interface LoadGroup {
+  manufacturer: string;
+  /** 設置エリア（指定のない行は未設定） */
+  area?: string;
+  /** 台数の合計 */
+  fixtureCount: number;
+  /** 消費電力の合計（W。消費電力が不明な器具を除く） */
+  totalWatts: number;
+  /** 消費電力が不明な器具の台数 */
+  unknownCount: number;
+}
+
+/** 接続負荷の集計 */
+export interface ConnectedLoad {
+  /** 台数の合計 */
+  fixtureCount: number;
+  /** 消費電力の合計（W。消費電力が不明な器具を除く） */
+  totalWatts: number;
+  /** 消費電力が不明な器具の台数 */
+  unknownCount: number;
+  /** メーカー・設置エリアごとの集計 */
+  groups: LoadGroup[];
+}
+
+/** アイテムごとの定価 */
+export interface ItemCost {
+  specNo: string;
+  manufacturer: string;
+  /** 台数 */
+  quantity: number;
+  /** 1台あたりの定価（不明な場合は未設定） */
+  unitPrice?: Price;
+  /** 定価 × 台数 */
+  extendedPrice?: Price;
+}
+
+/** メーカーごとの定価の合計 */
+export interface CostGroup {
+  manufacturer: string;
+  /** 台数の合計 */
+  fixtureCount: number;
+  /** 定価 × 台数の合計（通貨・税込／税抜ごと。定価が不明な器具を除く） */
+  totals: Price[];
+  /** 定価が不明な器具の台数 */
+  unpricedCount: number;
+}
+
+/** 定価 × 台数の集計 */
+export interface CostRollup {
+  /** アイテムごとの定価（アイテムと同じ順） */
+  items: ItemCost[];
+  /** メーカーごとの合計 */
+  groups: CostGroup[];
+  /** 定価 × 台数の合計（通貨・税込／税抜ごと。定価が不明な器具を除く） */
+  totals: Price[];
+  /** 定価が不明な器具の台数 */
+  unpricedCount: number;
+}
+
+/** 区分ごとの光束（lm。IES TM-15-11 の区分。前方はC0〜180度） */
+export interface ZonalLumens {
+  /** 前方 0〜30度 */
+  frontLow: number;
+  /** 前方 30〜60度 */
+  frontMid: number;
+  /** 前方 60〜80度 */
+  frontHigh: number;
+  /** 前方 80〜90度 */
+  frontVeryHigh: number;
+  /** 後方 0〜30度 */
+  backLow: number;
+  /** 後方 30〜60度 */
+  backMid: number;
+  /** 後方 60〜80度 */
+  backHigh: number;
+  /** 後方 80〜90度 */
+  backVeryHigh: number;
+  /** 上方 90〜100度 */
+  uplightLow: number;
+  /** 上方 100〜180度 */
+  uplightHigh: number;
+}
+
+/** BUG評価（Backlight・Uplight・Glare。それぞれ0〜5） */
+export interface BugRating {
+  backlight: number;
+  uplight: number;
+  glare: number;
+}
+
+/** IESファイルの配光データの解析結果 */
+export interface IesAnalysis {
+  /** 器具光束（lm） */
+  lumens: number;
+  /** 入力電力（W。記載がない場合は未設定） */
+  inputWatts?: number;
+  /** 絶対測光（LED器具等、ランプ光束のない測光）か */
+  absolutePhotometry: boolean;
+  /** ランプ数 */
+  lampCount: number;
+  /** ランプ光束の合計（lm。絶対測光の場合は未設定） */
+  lampLumens?: number;
+  /** 器具効率（%。絶対測光の場合は未設定） */
+  lightOutputRatio?: number;
+  /** 固有エネルギー消費効率（lm/W） */
+  efficacy?: number;
+  /** ビーム角（度） */
+  beamAngle?: number;
+  /** 区分ごとの光束 */
+  zonalLumens: ZonalLumens;
+  /** BUG評価（屋外器具向け） */
+  bugRating: BugRating;
+}
+
+/** 水平角の対称性 */
+export type Symmetry = 'axial' | 'quadrant' | 'c0c180' | 'c90c270' | 'none';
+
+/** 配光曲線（極座標グラフ）の1面 */
+export interface PolarPlane {
+  /** 水平角（C、度） */
+  horizontalAngle: number;
+  /** 光度（cd。verticalAngles の順） */
+  candela: number[];
+}
+
+/** 配光曲線（極座標グラフ）の表示用データ（C0・C90・C180・C270面） */
+export interface PolarCurve {
+  /** 鉛直角（γ、度） */
+  verticalAngles: number[];
+  planes: PolarPlane[];
+}
+
+/** IESファイルの配光データの概要 */
+export interface IesSummary extends IesAnalysis {
+  /** 最大光度（cd） */
+  maxCandela: number;
+  /** 最大光度の鉛直角（度） */
+  maxCandelaVerticalAngle: number;
+  /** 最大光度の水平角（度） */
+  maxCandelaHorizontalAngle: number;
+  /** 1/10ビーム角（フィールド角。度） */
+  fieldAngle?: number;
+  /** 水平角の対称性 */
+  symmetry: Symmetry;
+  /** 鉛直角の数 */
+  verticalAngleCount: number;
+  /** 水平角の数 */
+  horizontalAngleCount: number;
+  /** 配光曲線の表示用データ */
+  polarCurve: PolarCurve;
 }
 
-/** ダウンロード進捗イベント（Rust側からの通知） */
+/** 照射円錐図（コーンダイアグラム）の1行 */
+export interface ConePoint {
+  /** 器具からの距離（m。取付高さ） */
+  distance: number;
+  /** 光軸上の照度（lx） */
+  centerIlluminance: number;
+  /** ビーム径（m。ビーム角が求められない場合は未設定） */
+  beamDiameter?: number;
+}
+
+/** 照射円錐図（コーンダイアグラム） */
+export interface ConeDiagram {
+  /** 光軸（真下）方向の光度（cd） */
+  centerIntensity: number;
+  /** ビーム角（度） */
+  beamAngle?: number;
+  /** 距離ごとの照度・ビーム径 */
+  points: ConePoint[];
+}
+
+/** アセット種別ごとのダウンロード結果 */
+export interface AssetDownloadResult {
+  assetType: AssetType;
+  result: DownloadResult;
+  /** 取得元のプロバイダーID（メーカー欄に複数のメーカーがある場合に、どれから取得したか。失敗時はなし） */
+  provider?: string;
+}
+
+/** 1アイテムの解決（resolve_item）のオプション */
+export interface ResolveItemOptions {
+  /** アセットをダウンロードするか（false の場合はIESファイルのURLの解決まで） */
+  download?: boolean;
+  /** 取得するアセット種別（省略時はアイテムの指定、それもなければIESのみ） */
+  assetTypes?: AssetType[];
+  /** 保存先ディレクトリ（省略時は設定の既定の保存先） */
+  destDir?: string;
+}
+
+/** 1アイテムの解決の結果 */
+export interface ItemResolution {
+  specNo: string;
+  /** 処理したプロバイダーID */
+  provider: string;
+  productInfo: ProductInfoResult;
+  /** IESファイルのURL（ダウンロードしない場合のみ） */
+  resolvedIesUrl?: ResolvedIesUrl;
+  resolveError?: string;
+  /** アセット種別ごとのダウンロード結果（ダウンロードした場合のみ） */
+  assets: AssetDownloadResult[];
+  /** ダウンロードしたIESファイルの配光データ */
+  analysis?: IesAnalysis;
+  /** ダウンロードしたIESファイルを配光データとして読み込めなかった場合のエラー */
+  validationError?: string;
+  /** 器具リストの消費電力とIESファイルの入力電力の照合結果 */
+  wattageCheck?: WattageCheck;
+}
+
+/** 1アイテム分の全アセットのダウンロード結果 */
+export interface BundleItemResult {
+  specNo: string;
+  modelNumber: string;
+  /** 保存先フォルダ（{保存先ディレクトリ}/{Spec No.}） */
+  destDir: string;
+  assets: AssetDownloadResult[];
+}
+
+/** 全アセット一括ダウンロード結果 */
+export interface BatchBundleResult {
+  successCount: number;
+  failureCount: number;
+  cancelledCount: number;
+  results: BundleItemResult[];
+}
+
+/** ジョブファイルの1アイテム分の結果 */
+export interface JobItemResult {
+  specNo: string;
+  modelNumber: string;
+  /** 要求したアセットがすべて取得できたか */
+  success: boolean;
+  assets: AssetDownloadResult[];
+}
+
+/** ジョブファイルの実行結果 */
+export interface JobResult {
+  /** 保存先ディレクトリ（ジョブファイルを基準に解決したパス） */
+  destDir: string;
+  successCount: number;
+  failureCount: number;
+  /** アイテムごとの結果（ジョブファイルの順序） */
+  results: JobItemResult[];
+}
+
+/**
+ * ダウンロード進捗イベント（Rust側からの通知。'waiting' はバッチ状態でのみ使用）
+ * 'skipped' は保存先に取得済みのファイルがあり、取得し直さなかったアイテム
+ */
 export interface DownloadProgressEvent {
   specNo: string;
-  status: 'processing' | 'success' | 'error';
+  status: 'waiting' | 'processing' | 'success' | 'error' | 'cancelled' | 'skipped';
   error?: string;
+  /** エラーコード（エラー時のみ） */
+  code?: ErrorCode;
+  /** 再試行で解決する可能性がある失敗か */
+  retryable: boolean;
+  /** バッチ内の順番（0始まり） */
+  index: number;
+  /** バッチ全体の件数 */
+  total: number;
+  /** 処理中の段階（処理中で、段階が分かる場合のみ） */
+  phase?: DownloadPhase;
+  /** 受信したバイト数（受信中のみ） */
+  bytes?: number;
+  /** 受信するファイル全体のバイト数（受信中で、サーバーがサイズを返した場合のみ） */
+  totalBytes?: number;
+  /** 利用者の判断を待っている内容（判断待ちの場合のみ） */
+  decision?: DecisionRequest;
+  batchId: string;
+  /** このイベント時点のバッチ全体の成功件数 */
+  successCount: number;
+  /** このイベント時点のバッチ全体の失敗件数 */
+  failureCount: number;
+  /** このイベント時点のバッチ全体のキャンセル件数 */
+  cancelledCount: number;
+  /** このイベント時点のバッチ全体の取得済みのため飛ばした件数 */
+  skippedCount: number;
+}
+
+/**
+ * ダウンロードの段階
+ * - lookup: ダウンロードURLの解決（製品ページ・検索ページの取得）
+ * - downloading: ファイル（ZIP）の受信
+ * - extracting: ZIPの展開・形式の変換
+ * - renaming: ファイル名の変更・保存先への移動
+ * - awaitingDecision: 曖昧な一致について利用者の判断を待っている
+ */
+export type DownloadPhase = 'lookup' | 'downloading' | 'extracting' | 'renaming' | 'awaitingDecision';
+
+/**
+ * 利用者の判断が必要な曖昧な一致の種類
+ * - iesFile: 同程度に一致するIESファイルが複数ある（ZIP内のファイル・製品ページのリンク）
+ * - product: 型番が見つからず、近い型番の製品が複数ある
+ */
+export type DecisionKind = 'iesFile' | 'product';
+
+/** 判断を求める候補 */
+export interface DecisionCandidate {
+  /** 選択時に resolveItemChoice に渡す値（ZIP内のファイル名・ダウンロードURL・型番） */
+  value: string;
+  /** 表示名 */
+  label: string;
+}
+
+/** 利用者に判断を求める内容 */
+export interface DecisionRequest {
+  kind: DecisionKind;
+  candidates: DecisionCandidate[];
+  /** 判断されなかった場合に自動で選ぶ候補の値（ない場合は失敗になる） */
+  selected?: string;
+}
+
+/** 判断待ちのアイテムのイベント（download-decision-required） */
+export interface DecisionRequiredEvent extends DecisionRequest {
+  batchId: string;
+  specNo: string;
+}
+
+/** 再試行のための待機の理由 */
+export type BackoffReason = 'rateLimited' | 'serverBusy' | 'networkError';
+
+/** 再試行のための待機の通知（進捗が止まって見える理由の表示用） */
+export interface BackoffEvent {
+  /** 待機の対象ホスト */
+  host: string;
+  reason: BackoffReason;
+  /** サーバーが返したステータス（接続エラーの場合は undefined） */
+  status?: number;
+  /** 待機時間（秒） */
+  waitSecs: number;
+  /** 次が何回目の試行か */
+  attempt: number;
+  /** 表示用メッセージ（例: "waiting 30s due to 429 from webcatalog.koizumi-lt.co.jp"） */
+  message: string;
+}
+
+/** 保存先ファイル名の衝突 */
+export interface FilenameCollision {
+  filePath: string;
+  /** このパスに保存されるアイテムのSpec No. */
+  specNos: string[];
+  /** 同名のファイルが既に存在するか */
+  exists: boolean;
+}
+
+/** 一括ダウンロードの事前見積もり */
+export interface BatchEstimate {
+  total: number;
+  /** 対応メーカーのアイテム数 */
+  supportedCount: number;
+  /** IESファイルのURLを解決できたアイテム数 */
+  resolvedCount: number;
+  /** 対応メーカーがないアイテムのSpec No. */
+  unsupported: string[];
+  /** IESファイルのURLを解決できなかったアイテムのSpec No. */
+  unresolved: string[];
+  collisions: FilenameCollision[];
+  /** IESファイルの合計サイズの目安（バイト。サイズを取得できたファイルのみ） */
+  totalSize: number;
+  /** サイズを取得できたファイル数 */
+  sizedCount: number;
+}
+
+/** 事前確認の1行分の状態 */
+export type AvailabilityStatus = 'found' | 'notFound' | 'unsupported' | 'needsPsu' | 'failed';
+
+/** 事前確認の1行分の結果（`availability-progress` イベントのペイロード） */
+export interface AvailabilityResult {
+  specNo: string;
+  status: AvailabilityStatus;
+  /** 確認に使用したプロバイダーID */
+  provider?: string;
+  info?: ProductInfo;
+  error?: string;
+  code?: ErrorCode;
+}
+
+/** 事前確認の結果 */
+export interface AvailabilityReport {
+  total: number;
+  /** IESファイルが掲載されているアイテム数（PSU型番の指定が必要なものを除く） */
+  foundCount: number;
+  /** 各アイテムの結果（リクエストの順） */
+  results: AvailabilityResult[];
+}
+
+/** 一括ダウンロードの完了通知（`download-finished` イベントのペイロード） */
+export interface BatchFinishedEvent {
+  batchId: string;
+  successCount: number;
+  failureCount: number;
+  cancelledCount: number;
+  /** 保存先ディレクトリ */
+  destDir: string;
+  /** 保存先ディレクトリをFinder/エクスプローラーで開いたか */
+  openedDestDir: boolean;
+}
+
+/**
+ * 一括ダウンロードの開始時に指定したチャネルに届くイベント
+ * チャネルを指定したバッチでは download-progress 等のイベントは発火しない
+ */
+export type BatchEvent =
+  | { event: 'progress'; data: DownloadProgressEvent }
+  | { event: 'paused'; data: boolean }
+  | { event: 'finished'; data: BatchFinishedEvent }
+  | { event: 'decisionRequired'; data: DecisionRequiredEvent };
+
+/** 実行中（または直前）の一括ダウンロードの状態 */
+export interface BatchStatus {
+  batchId: string;
+  running: boolean;
+  /** 一時停止中か（処理中のアイテムは完了まで続け、次のアイテムから停止する） */
+  paused: boolean;
+  total: number;
+  successCount: number;
+  failureCount: number;
+  cancelledCount: number;
+  /** 取得済みのため飛ばした件数 */
+  skippedCount: number;
+  /** 各アイテムの状態（リクエストの順序） */
+  items: DownloadProgressEvent[];
+}
+
+/** 通貨（ISO 4217） */
+export type Currency = 'JPY' | 'USD' | 'EUR';
+
+/** 価格（Rust側と対応） */
+export interface Price {
+  /** 金額（通貨の補助単位。円は1円、ドル・ユーロは1セント） */
+  minorUnits: number;
+  currency: Currency;
+  /** 税込価格か（表記がなく判断できない場合は undefined） */
+  taxIncluded?: boolean;
 }
 
 /** 製品情報（Rust側と対応） */
 export interface ProductInfo {
   modelNumber: string;
   productName?: string;
-  price?: number;
+  price?: Price;
   iesFileUrl?: string;
   imageUrl?: string;
   productPageUrl?: string;
+  /** 製品ページに掲載されている適合部材（電源・フレーム・レンズ等） */
+  accessories: Accessory[];
+  /** 廃番の場合の情報 */
+  discontinued?: Discontinuation;
+  /** 廃番の型番から後継品の製品情報に切り替えた場合の元の型番 */
+  replaces?: string;
+  /** IESファイルが型番の製品のものか、シリーズの代表による近似か（区別できるプロバイダーのみ） */
+  matchQuality?: MatchQuality;
+}
+
+/**
+ * IESファイルと型番の対応の確かさ
+ * - exact: 型番の製品のIESファイル
+ * - series: 型番の製品のIESファイルが掲載されておらず、シリーズの代表のIESファイルで代用した
+ */
+export type MatchQuality = 'exact' | 'series';
+
+/** 廃番の情報 */
+export interface Discontinuation {
+  /** 後継品の型番（製品ページに掲載されている場合） */
+  successor?: string;
+  /** 後継品の製品ページのURL */
+  successorPageUrl?: string;
+}
+
+/** 製品情報一括取得の1行分の結果（product-info-progress イベントのペイロードを兼ねる） */
+export interface ProductInfoResult {
+  specNo: string;
+  info?: ProductInfo;
+  error?: string;
+  /** エラーコード（エラー時のみ） */
+  code?: ErrorCode;
+  /** 再試行で解決する可能性がある失敗か */
+  retryable: boolean;
+  /** キャッシュから返した結果か */
+  cached: boolean;
+}
+
+/** 製品情報の先読みの完了通知 */
+export interface PrefetchSummary {
+  /** 通知した行数 */
+  total: number;
+  /** キャッシュから返した行数 */
+  cached: number;
+  /** 取得に失敗した行数 */
+  failed: number;
+}
+
+/** オフラインの準備の1製品分の結果（offline-prepare-progress イベント） */
+export interface OfflinePrepareProgress {
+  modelNumber: string;
+  /** 製品情報をキャッシュしたか（すでにキャッシュにあった場合を含む） */
+  productInfo: boolean;
+  /** IESファイルをIESライブラリに登録したか（すでに登録されていた場合を含む） */
+  ies: boolean;
+  error?: string;
+}
+
+/** オフラインの準備の結果 */
+export interface OfflinePrepareSummary {
+  /** 対象の製品数 */
+  total: number;
+  /** 製品情報をキャッシュした製品数 */
+  productInfo: number;
+  /** IESファイルをIESライブラリに登録した製品数 */
+  ies: number;
+  /** どちらかを取得できなかった製品数 */
+  failed: number;
+}
+
+/** 定価取得の1行分の結果 */
+export interface PriceResult {
+  /** 定価（掲載されていない場合は undefined） */
+  price?: Price;
+  /** エラーメッセージ（エラー時・定価取得に対応していないメーカーの場合） */
+  error?: string;
+  /** エラーコード（エラー時のみ） */
+  code?: ErrorCode;
+  /** 再試行で解決する可能性がある失敗か */
+  retryable: boolean;
+}
+
+/** 製品検索の候補（Rust側と対応） */
+export interface ProductCandidate {
+  modelNumber: string;
+  productName?: string;
+  price?: Price;
+  productPageUrl?: string;
+}
+
+/** 適合部材の種別 */
+export type AccessoryKind = 'powerSupply' | 'frame' | 'lens' | 'other';
+
+/** 適合部材（Rust側と対応） */
+export interface Accessory {
+  modelNumber: string;
+  name?: string;
+  kind: AccessoryKind;
+  productPageUrl?: string;
 }
 
 /** 器具の選択状態 */
 export interface FixtureSelection {
   fixture: Fixture;
   selected: boolean;
-  downloadStatus?: 'pending' | 'waiting' | 'downloading' | 'success' | 'error';
+  downloadStatus?: 'pending' | 'waiting' | 'downloading' | 'success' | 'error' | 'cancelled';
   downloadError?: string;
+  /** 処理中の進捗（処理中のみ） */
+  downloadProgress?: DownloadProgressEvent;
+}
+
+/** ダウンロード履歴の1件（1アイテム・1アセット分） */
+export interface HistoryEntry {
+  projectId?: string;
+  specNo: string;
+  manufacturer: string;
+  modelNumber: string;
+  psu?: string;
+  assetType: AssetType;
+  success: boolean;
+  filePath?: string;
+  /** サーバーから取得した元ファイル名 */
+  originalFilename?: string;
+  /** 保存したファイルのSHA-256（成功時のみ） */
+  sha256?: string;
+  /** 取得元のURL・HTTP応答の情報 */
+  source?: DownloadSource;
+  /** 取得元のプロバイダーID（メーカー欄に複数のメーカーがある場合に、どれから取得したか） */
+  provider?: string;
+  error?: string;
+  /** ダウンロード日時（ISO 8601） */
+  downloadedAt: string;
+}
+
+/** ダウンロード履歴の検索条件（指定した条件はすべて満たす必要がある） */
+export interface HistoryQuery {
+  projectId?: string;
+  /** メーカー名（大文字・小文字を区別しない） */
+  manufacturer?: string;
+  /** この日時以降（ISO 8601） */
+  from?: string;
+  /** この日時以前（ISO 8601） */
+  to?: string;
+  /** 成功（true）/失敗（false）のみ */
+  success?: boolean;
+  /** 型番・Spec No.・メーカー名・保存先パス・取得元URLのいずれかに含まれる文字列（大文字・小文字を区別しない） */
+  text?: string;
+  offset?: number;
+  /** 取得件数（省略時は50件） */
+  limit?: number;
+}
+
+/** ダウンロード履歴の検索結果（1ページ分） */
+export interface HistoryPage {
+  /** 条件に一致した全件数 */
+  total: number;
+  /** このページの履歴（新しい順） */
+  entries: HistoryEntry[];
+}
+
+/** レポートの出力形式 */
+export type ReportFormat = 'csv' | 'json' | 'html';
+
+/** クラウドへのアップロード結果（cloud-upload-finished イベントのペイロードを兼ねる） */
+export interface CloudUploadResult {
+  provider: CloudProvider;
+  /** アップロードしたディレクトリ */
+  source: string;
+  uploadedCount: number;
+  failureCount: number;
+  /** 失敗したファイルとエラー */
+  errors: string[];
+}
+
+/** 書き出し先の照明計算ソフト（Relux向けはLDTに変換、AGi32向けは器具表も作成） */
+export type ExportTarget = 'dialuxEvo' | 'relux' | 'agi32';
+
+/** 照明計算ソフト向けに書き出したファイル */
+export interface ExportedFile {
+  specNo: string;
+  manufacturer: string;
+  modelNumber: string;
+  /** AGi32のラベル（AGi32向けのみ） */
+  label?: string;
+  /** 書き出したファイルのパス（失敗時は undefined） */
+  path?: string;
+  /** LDTへの変換に失敗した・ファイルがない等の理由 */
+  note?: string;
+}
+
+/** 照明計算ソフト向けの書き出し結果 */
+export interface ProjectExportResult {
+  /** 一覧（index.csv）のパス */
+  indexPath: string;
+  /** AGi32の器具表のパス（AGi32向けのみ） */
+  schedulePath?: string;
+  files: ExportedFile[];
+}
+
+/** ダウンロード済みファイルの再リネームのリクエスト（対象は履歴に記録された成功ファイル） */
+export interface RenameRequest {
+  projectId?: string;
+  /** 対象のフォルダ（配下のファイルのみ） */
+  dir?: string;
+  /** true の場合はリネームせず、変更内容のみ返す */
+  dryRun?: boolean;
+  /**
+   * true の場合は設定のテンプレートではなく、IESファイルの内容（色温度・ビーム角）から
+   * 命名するテンプレート（例: `1001_XD93319_3000K_30deg.ies`）を使用する
+   */
+  contentAware?: boolean;
+}
+
+/** 1ファイル分の再リネーム結果 */
+export interface RenameResult {
+  specNo: string;
+  oldPath: string;
+  newPath: string;
+  /** リネームしたかどうか（dry run では false） */
+  renamed: boolean;
+  error?: string;
+  /** エラーコード（エラー時のみ） */
+  code?: ErrorCode;
+}
+
+/** Excel読み込み設定（省略した項目は標準の列構成） */
+export interface ImportProfile {
+  /** シート名（省略時は "Fixture Base" または名前に "fixture" を含むシート） */
+  sheetName?: string;
+  /** ヘッダー行（1始まり） */
+  headerRow?: number;
+  /** データ開始行（1始まり） */
+  dataStartRow?: number;
+  specNoColumn?: string;
+  manufacturerColumn?: string;
+  fixtureColumn?: string;
+  psuColumn?: string;
+}
+
+/** Excelから読み込んだ1行分の器具データ（日付は YYYY-MM-DD の文字列） */
+export interface ImportedRow extends Omit<Fixture, 'date'> {
+  /** Excel上の行番号（1始まり） */
+  rowNumber: number;
+  date?: string;
+  /** プロジェクトの無視リストに含まれる行（取得を試みない） */
+  ignored?: boolean;
+}
+
+/** Excel読み込みの検証警告の種別 */
+export type ImportWarningKind =
+  | 'missingColumn'
+  | 'emptySpecNo'
+  | 'emptyManufacturer'
+  | 'emptyModel'
+  | 'unknownManufacturer'
+  | 'duplicateSpecNo';
+
+/** Excel読み込みの検証警告 */
+export interface ImportWarning {
+  kind: ImportWarningKind;
+  /** Excel上の行番号（列に関する警告では undefined） */
+  rowNumber?: number;
+  specNo?: string;
+  message: string;
+}
+
+/** Excel読み込み結果 */
+export interface ImportResult {
+  sheetName: string;
+  sheetNames: string[];
+  rows: ImportedRow[];
+  warnings: ImportWarning[];
+}
+
+/** 無視する対象の種別（Spec No. は完全一致、型番は大文字・小文字と空白を無視して比較） */
+export type IgnoreKind = 'specNo' | 'model';
+
+/** 無視リストの項目 */
+export interface IgnoreEntry {
+  kind: IgnoreKind;
+  /** Spec No. または型番 */
+  value: string;
+  /** 無視する理由 */
+  reason?: string;
+  /** 追加日時（ISO 8601） */
+  addedAt: string;
+}
+
+/** 手動指定の取得元（一括ダウンロードでプロバイダーの照合の代わりに使用する） */
+export interface ManualSource {
+  specNo: string;
+  /** 登録時の型番（器具リストの型番が変わった場合は適用しない） */
+  modelNumber: string;
+  /** IESファイルまたはZIPのURL */
+  url: string;
+  /** 登録日時（ISO 8601） */
+  addedAt: string;
+}
+
+/** 中断した一括ダウンロード */
+export interface InterruptedBatch {
+  batchId: string;
+  items: BatchDownloadItem[];
+  /** アイテムごとの指定がない場合に取得するアセット種別 */
+  assetTypes: AssetType[];
+  /** 保存先ディレクトリ（解決済みのパス） */
+  destDir: string;
+  projectId?: string;
+  /** 完了（成功・失敗）したアイテムのSpec No. */
+  completed: string[];
+  /** 開始日時（ISO 8601） */
+  startedAt: string;
+}
+
+/** プロバイダールールの更新状態 */
+export interface RulesStatus {
+  /** 使用中のバージョン（未取得の場合はアプリに組み込みのルール） */
+  active?: string;
+  /** ロールバックできる過去のバージョン（新しい順） */
+  history: string[];
+  /** 最後に確認した日時（ISO 8601） */
+  lastChecked?: string;
+  /** 最後の確認で発生したエラー */
+  lastError?: string;
+}
+
+/** ダウンロード済みファイルの書き出し結果 */
+export interface StorageExportResult {
+  /** 書き出し先（URIまたはパス） */
+  target: string;
+  /** 書き出したバイト数 */
+  bytes: number;
+}
+
+/** クリップボードの貼り付け結果 */
+export interface ClipboardImportResult {
+  items: BatchDownloadItem[];
+  /** 検証警告（行番号は貼り付けたテキストの行番号） */
+  warnings: ImportWarning[];
+}
+
+/** ドロップされたファイルの種別 */
+export type DroppedFileKind = 'schedule' | 'ies' | 'unsupported';
+
+/** IESライブラリに登録したIESファイル */
+export interface LibraryEntry {
+  /** ファイルのSHA-256（16進数。登録の識別子を兼ねる） */
+  sha256: string;
+  /** 取り込んだときの元ファイル名 */
+  fileName: string;
+  /** ライブラリ内の保存先パス */
+  path: string;
+  size: number;
+  /** ヘッダーのキーワード（例: { MANUFAC: 'KOIZUMI', LUMCAT: 'AD12345' }） */
+  keywords: Record<string, string>;
+  /** 検索語（キーワードの値・元ファイル名から作成。表記を揃えた小文字） */
+  searchTerms: string[];
+  addedAt: string;
+}
+
+/** 補完する入力欄 */
+export type SuggestField = 'manufacturer' | 'model';
+
+/** 入力の候補（ダウンロード履歴・IESライブラリから） */
+export interface Suggestion {
+  /** 候補（最後に使ったときの表記） */
+  value: string;
+  /** メーカー名（型番の候補のみ） */
+  manufacturer?: string;
+  /** 使った回数 */
+  count: number;
+  /** 最後に使った日時 */
+  lastUsed: string;
+}
+
+/** ドロップされたファイル1件の処理結果 */
+export interface DroppedFileResult {
+  path: string;
+  kind: DroppedFileKind;
+  /** 器具リストの読み込み結果（器具リストの場合のみ） */
+  import?: ImportResult;
+  /** IESライブラリの登録内容（IESファイルの場合のみ） */
+  libraryEntry?: LibraryEntry;
+  /** IESライブラリに新しく登録したか */
+  added: boolean;
+  /** エラーメッセージ（失敗時・未対応の形式の場合） */
+  error?: string;
+}
+
+/** プロバイダーのメタデータ */
+export interface ProviderInfo {
+  /** プロバイダーID（例: koizumi） */
+  id: string;
+  displayName: string;
+  /** メーカー名の判定に使用する別名 */
+  aliases: string[];
+  baseUrl: string;
+  supportedAssets: AssetType[];
+  /** キーワード検索に対応しているか */
+  supportsSearch: boolean;
+  /** 定価の取得に対応しているか */
+  supportsPricing: boolean;
+  /** 有効かどうか（無効なプロバイダーはメーカー名の判定に使用しない） */
+  enabled: boolean;
+  /** 定義ファイルから作成したプロバイダーか */
+  custom?: boolean;
+}
+
+/** 定義ファイルから作成するプロバイダー（カスタムプロバイダー）の定義 */
+export interface ProviderDefinition {
+  /** プロバイダーID（英小文字・数字・`-` `_`。組み込みのプロバイダーと重複しないもの） */
+  id: string;
+  displayName: string;
+  baseUrl: string;
+  /** メーカー名の判定に使用する別名（省略時は表示名） */
+  aliases?: string[];
+  /** 製品ページ・検索結果のページのURLのテンプレート（`{model}` を型番に置き換える） */
+  searchUrl: string;
+  /** IESファイルへのリンクのCSSセレクター（iesLinkPattern といずれか一方を指定） */
+  iesLinkSelector?: string;
+  /** IESファイルのURLを抽出する正規表現（1つ目のグループ、なければ一致した全体） */
+  iesLinkPattern?: string;
+  /** ZIPへのリンクもIESファイルの取得元とするか */
+  zip?: boolean;
+  /** 保存先ファイル名の既定テンプレート */
+  filenameTemplate?: string;
+  /** 疎通確認に使用する型番 */
+  sampleModelNumber?: string;
+}
+
+/** 読み込めなかった定義ファイル */
+export interface DefinitionError {
+  file: string;
+  error: string;
+}
+
+/** 定義ファイルのプロバイダーの読み込み結果 */
+export interface CustomProvidersResult {
+  /** 定義ファイルのディレクトリ */
+  dir: string;
+  /** 登録したプロバイダーのID */
+  loaded: string[];
+  errors: DefinitionError[];
+}
+
+/** 利用者が登録したメーカー名の別名 */
+export interface ManufacturerAlias {
+  /** メーカー欄の表記（照合時に全角・半角、大文字・小文字、法人格の違いを無視する） */
+  alias: string;
+  /** 対応付けるプロバイダーID */
+  providerId: string;
+}
+
+/** 疎通確認の診断結果の種別 */
+export type DiagnosisStatus =
+  | 'ok'
+  | 'dnsFailure'
+  | 'connectionFailed'
+  | 'timeout'
+  | 'proxyAuthRequired'
+  | 'httpError'
+  | 'htmlChanged'
+  | 'lookupFailed';
+
+/** プロバイダーの疎通確認結果 */
+export interface ProviderDiagnosis {
+  providerId: string;
+  status: DiagnosisStatus;
+  /** 詳細メッセージ */
+  message: string;
+  /** 所要時間（ミリ秒） */
+  elapsedMs: number;
+}
+
+/** クラッシュレポート（前回以前の実行中に発生したパニック） */
+export interface CrashReport {
+  id: string;
+  createdAt: string;
+  appVersion: string;
+  os: string;
+  /** パニックのメッセージ */
+  message: string;
+  /** パニックが発生したソースの位置 */
+  location?: string;
+  thread?: string;
+  backtrace: string;
+  /** 直前の操作（古い順） */
+  recentOperations: string[];
+  /** エラー報告の送信先に送信済み */
+  reported: boolean;
+}
+
+/** プロバイダーごとの成功・失敗件数 */
+export interface ProviderCounts {
+  success: number;
+  failure: number;
+}
+
+/** 送信される利用状況（前回の送信以降の集計値） */
+export interface UsageReport {
+  appVersion: string;
+  os: string;
+  /** 集計開始日時 */
+  since: string;
+  /** 一括ダウンロードの実行回数 */
+  batchesRun: number;
+  /** プロバイダーIDごとの件数（プロバイダーがないメーカーは "unsupported"） */
+  providers: Record<string, ProviderCounts>;
+}
+
+/** キャッシュの種別（Rust側と対応） */
+export type CacheScope = 'http' | 'productInfo' | 'tokistarZip' | 'thumbnails' | 'iesDownload';
+
+/** 種別ごとのキャッシュ使用量 */
+export interface CacheStats {
+  scope: CacheScope;
+  fileCount: number;
+  /** 合計サイズ（バイト） */
+  totalBytes: number;
+}
+
+/** 直前の作業状態（restore_last_session の戻り値） */
+export interface Session {
+  /** 読み込んだ器具リストのパス */
+  sourcePath?: string;
+  /** 器具リストの読み込み結果 */
+  import?: ImportResult;
+  /** 取得した製品情報（Spec No.をキーとする） */
+  productInfo: Record<string, ProductInfoResult>;
+  /** 各行の直近のダウンロード結果（Spec No.をキーとする） */
+  statuses: Record<string, DownloadProgressEvent>;
+  /** 最後に更新した日時（ISO 8601） */
+  updatedAt?: string;
 }
@@ -0,0 +1,284 @@
+/**
+ * アプリ設定型定義
+ * Rust側の settings.rs に対応
+ */
+
+/** プロキシ設定 */
+export interface ProxySettings {
+  /** プロキシのURL（例: http://proxy.example.com:8080） */
+  url: string;
+  username?: string;
+  password?: string;
+  /** プロキシを経由しないホスト（カンマ区切り。例: localhost,.intra.example.co.jp） */
+  noProxy?: string;
+}
+
+/** TLSの設定（社内の認証局が発行した証明書を使う環境向け） */
+export interface TlsSettings {
+  /** 追加で信頼するルート証明書のファイル（PEMまたはDER、絶対パス） */
+  rootCertificatePath?: string;
+  /** サーバー証明書を検証しない（検証に失敗する環境での一時的な回避用） */
+  acceptInvalidCerts: boolean;
+}
+
+/** 通信の設定（プロキシ・TLS） */
+export interface NetworkSettings {
+  /** プロキシ設定（未指定時は直接接続） */
+  proxy?: ProxySettings;
+  tls: TlsSettings;
+}
+
+/** 保存先ルール */
+export interface DestinationSettings {
+  /** IES以外のアセットを種別ごとのサブフォルダに保存する */
+  assetSubdirs: boolean;
+  /** 同名のファイルがある場合に上書きする */
+  overwriteExisting: boolean;
+  /**
+   * 既定の保存先ディレクトリ（ダウンロード時に保存先を指定しなかった場合に使用）
+   * `{project}` `{date}` `{manufacturer}` 等のプレースホルダーを使用可能
+   */
+  defaultDir?: string;
+  /**
+   * 既定の保存先ディレクトリに作成するプロジェクトごとのサブフォルダ名
+   * `{project}` `{project_id}` `{date}` を使用可能（未指定時はサブフォルダを作成しない）
+   */
+  projectSubdir?: string;
+  /** 一括ダウンロードの完了後に保存先ディレクトリをFinder/エクスプローラーで開く */
+  openWhenFinished: boolean;
+}
+
+/** 利用状況の集計・送信（オプトイン） */
+export interface TelemetrySettings {
+  /** 集計・送信を行う（既定は無効） */
+  enabled: boolean;
+  /** 送信先URL（未指定時は集計のみ行い送信しない） */
+  endpoint?: string;
+}
+
+/** アップロード先のクラウドサービス */
+export type CloudProvider = 'dropbox' | 'googleDrive';
+
+/** 一括ダウンロード後のクラウドへのアップロード（オプトイン） */
+export interface CloudUploadSettings {
+  /** 一括ダウンロードの完了後にアップロードする（既定は無効） */
+  enabled: boolean;
+  provider: CloudProvider;
+  /** アップロード先のフォルダ（Dropboxは / から始まるパス、Google DriveはフォルダID） */
+  folder: string;
+  /** 保存先ディレクトリをZIPにまとめてアップロードする */
+  uploadZip: boolean;
+}
+
+/** クラウドのOAuthトークン（設定とは別に保存される） */
+export interface CloudToken {
+  accessToken: string;
+  /** リフレッシュトークン（ある場合は期限切れ時に更新する） */
+  refreshToken?: string;
+  clientId?: string;
+  clientSecret?: string;
+  /** アクセストークンの有効期限（ISO 8601） */
+  expiresAt?: string;
+}
+
+/** ネットワーク共有（SMB）への保存（パスワードは設定とは別に保存される） */
+export interface NetworkShareSettings {
+  /** 共有への保存時に資格情報で接続する（既定は無効） */
+  enabled: boolean;
+  /** 共有（\\server\share または smb://server/share） */
+  share: string;
+  username: string;
+  /** ドメイン（ワークグループ） */
+  domain?: string;
+  /** 共有が切断された場合の再試行回数（0〜10） */
+  maxRetries: number;
+}
+
+/** プロバイダールールの更新（アプリ本体とは別の更新チャンネル） */
+export interface RulesUpdateSettings {
+  /** 定期的に更新を確認する（既定は無効） */
+  enabled: boolean;
+  /** フィードのURL（https） */
+  feedUrl?: string;
+  /** 固定するバージョン（未指定時は最新を使う） */
+  pinnedVersion?: string;
+  /** 確認間隔（時間、1〜168） */
+  checkIntervalHours: number;
+}
+
+/** レート制限・一時的なエラー時の再試行 */
+export interface RetrySettings {
+  /** 送信の最大試行回数（1〜10。1 で再試行しない） */
+  maxAttempts: number;
+  /** 接続エラー・タイムアウト時の最初の待機時間（秒。試行ごとに2倍にする） */
+  baseWaitSecs: number;
+  /** 待機時間の上限（秒、1〜600。Retry-After ヘッダーの値にも適用する） */
+  maxWaitSecs: number;
+}
+
+/** プロバイダーの接続設定（未指定の項目は既定値を使う） */
+export interface ProviderConfig {
+  /** メーカーサイトのベースURL（例: "https://webcatalog.koizumi-lt.co.jp"） */
+  baseUrl?: string;
+  /** User-Agent */
+  userAgent?: string;
+  /** リクエストのタイムアウト（秒、1〜600。未指定の場合は `requestTimeoutSecs`） */
+  timeoutSecs?: number;
+  /** 送信の最大試行回数（1〜10。未指定の場合は `retry.maxAttempts`） */
+  maxAttempts?: number;
+}
+
+/** バッテリー駆動時の負荷の軽減 */
+export interface BatterySettings {
+  /** バッテリー駆動時に負荷を下げる（既定は有効） */
+  enabled: boolean;
+  /** バッテリー駆動時の同時実行数の上限（1〜16） */
+  concurrency: number;
+  /** バッテリー駆動時に一括ダウンロードのアイテムの間に入れる待機（ミリ秒、0〜10000） */
+  itemDelayMs: number;
+}
+
+/**
+ * 通信の記録・再生のモード
+ * - off: 通常どおり通信する
+ * - mock: すべてのメーカーの行をモックプロバイダーで処理する
+ * - record: 通信した内容を記録する
+ * - replay: 記録した内容を再生する（通信しない。記録にないリクエストはエラー）
+ */
+export type CassetteMode = 'off' | 'mock' | 'record' | 'replay';
+
+/** 通信の記録・再生とモックプロバイダー（デモ・研修・結合テスト用） */
+export interface CassetteSettings {
+  mode: CassetteMode;
+  /** カセットのディレクトリ（省略時はデータディレクトリの cassettes） */
+  dir?: string;
+}
+
+/** ローカルHTTP APIサーバー（オプトイン） */
+export interface ApiServerSettings {
+  /** サーバーを起動する（既定は無効） */
+  enabled: boolean;
+  /** 待ち受けポート（127.0.0.1 のみ。1024以上） */
+  port: number;
+  /** 認証トークン（有効時は16文字以上が必須。Authorization: Bearer で送る） */
+  token?: string;
+}
+
+/** エラー報告（Sentry互換、オプトイン） */
+export interface ErrorReportingSettings {
+  /** 送信を行う（既定は無効） */
+  enabled: boolean;
+  /** Sentry互換のDSN（例: https://<key>@sentry.example.com/<project>） */
+  dsn?: string;
+  /** 環境名（送信先での絞り込み用） */
+  environment?: string;
+}
+
+/** ログレベル */
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/** 表示言語 */
+export type Locale = 'ja' | 'en';
+
+/** アプリ設定 */
+export interface Settings {
+  /** 設定のバージョン（保存時にRust側で設定される） */
+  version: number;
+  /** 製品情報取得等の同時実行数（1〜16） */
+  concurrency: number;
+  /**
+   * ドメインごとの同時リクエスト数の上限（1〜16。例: `{ "toki.co.jp": 1 }`）
+   * サブドメインにも適用する。指定のないドメインはホストごとに既定の上限（4）で制限する
+   */
+  domainConcurrency: Record<string, number>;
+  /**
+   * ドメインごとのリクエストの最小間隔（ミリ秒、0〜60000。例: `{ "toki.co.jp": 1000 }`）
+   * サブドメインにも適用する
+   */
+  domainRequestIntervalMs: Record<string, number>;
+  /** レート制限・一時的なエラー時の再試行 */
+  retry: RetrySettings;
+  /** HTTPリクエストのタイムアウト（秒、1〜600） */
+  requestTimeoutSecs: number;
+  /**
+   * ファイル名テンプレート（未指定時はプロバイダーの命名規則）
+   * 例: `{spec_no}_{original|model}`。使用できるプレースホルダーは
+   * `{spec_no}` `{model}` `{psu}` `{item}` `{original}` `{manufacturer}` `{cct}`、
+   * IESファイルの内容から `{ies_cct}` `{beam}` `{lumens}`。
+   * 拡張子は自動で付与される
+   */
+  filenameTemplate?: string;
+  /** 生成するファイル名の全角英数字を半角に変換 */
+  halfwidthAlphanumerics: boolean;
+  /** プロキシ設定（未指定時は直接接続） */
+  proxy?: ProxySettings;
+  /** TLSの設定（社内のルート証明書等） */
+  tls: TlsSettings;
+  destination: DestinationSettings;
+  /** 一括ダウンロードの完了時にデスクトップ通知を表示 */
+  notifyWhenFinished: boolean;
+  /** ウィンドウを閉じてもシステムトレイに常駐し、一括ダウンロードを続ける（デスクトップのみ） */
+  closeToTray: boolean;
+  /** オフラインモード（通信せず、キャッシュ・IESライブラリだけから結果を返す） */
+  offline: boolean;
+  /** 製品情報の一括取得で、廃番の製品は後継品の製品情報に切り替える */
+  followSuccessor: boolean;
+  /** 照明計算ソフト向けの書き出しで、IESファイルのヘッダーから社内のパス・測定者名等を取り除く */
+  scrubIesHeaders: boolean;
+  /** ログレベル */
+  logLevel: LogLevel;
+  /** 表示言語（バックエンドが生成する警告・レポート・通知の文言） */
+  locale: Locale;
+  /** 利用状況の集計・送信（オプトイン） */
+  telemetry: TelemetrySettings;
+  /** エラー報告（Sentry互換、オプトイン） */
+  errorReporting: ErrorReportingSettings;
+  /** ローカルHTTP APIサーバー（オプトイン） */
+  apiServer: ApiServerSettings;
+  /** 一括ダウンロード後のクラウドへのアップロード（オプトイン） */
+  cloudUpload: CloudUploadSettings;
+  /** ネットワーク共有（SMB）への保存 */
+  networkShare: NetworkShareSettings;
+  /** プロバイダールールの更新 */
+  rulesUpdate: RulesUpdateSettings;
+  /** バッテリー駆動時の負荷の軽減 */
+  battery: BatterySettings;
+  /** プロバイダーごとの接続設定（プロバイダーIDをキーとする。例: `{ "tokistar": { "baseUrl": "..." } }`） */
+  providers: Record<string, ProviderConfig>;
+  /** 通信の記録・再生とモックプロバイダー（デモ・研修・結合テスト用） */
+  cassette: CassetteSettings;
+}
+
+/** 設定ファイルの読み込みの結果 */
+export interface ConfigImportResult {
+  /** 反映した設定 */
+  settings: Settings;
+  /** 無視リストに追加した項目の件数 */
+  ignoreEntriesAdded: number;
+}
+
+/** ポータブルモードの指定元 */
+export type PortableSource = 'environment' | 'marker';
+
+/** ポータブルモードの状態 */
+export interface PortableInfo {
+  /** 実行中のプロセスがポータブルモードか */
+  enabled: boolean;
+  /** 保存先フォルダ（ポータブルモードの場合） */
+  root?: string;
+  source?: PortableSource;
+  /** 実行ファイルと同じフォルダの目印のファイル（autosight.portable）のパス */
+  markerPath?: string;
+  /** 目印のファイルを変更したため、反映に再起動が必要か */
+  restartRequired: boolean;
+}
+
+/** 電源の状態 */
+export interface PowerStatus {
+  /** バッテリーで動作しているか（取得できない場合は未定義） */
+  onBattery?: boolean;
+  /** 負荷を下げているか */
+  reduced: boolean;
+  /** 現在の同時実行数 */
+  concurrency: number;
+}